@@ -0,0 +1,47 @@
+//! Scaffold a project from code, skipping the uv steps:
+//!
+//!     cargo run --example scaffold -- /tmp/demo_proj
+//!
+//! Pass `--with-uv` as a second argument to run uv too (via `pyproj::create`).
+
+use anyhow::Result;
+use pyproj::{Event, PlannedAction, ScaffoldPlan, WriteOutcome, WritePolicy};
+use std::env;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let root = PathBuf::from(args.next().unwrap_or_else(|| "demo_proj".to_string()));
+    let with_uv = args.next().as_deref() == Some("--with-uv");
+    let project = root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "demo_proj".to_string());
+
+    let plan = ScaffoldPlan::builder(project)
+        .root(&root)
+        .var("author", "Example Author")
+        .on_event(|event| match event {
+            Event::FileWritten {
+                path,
+                outcome: WriteOutcome::Created,
+                ..
+            } => println!("created {}", path.display()),
+            Event::CommandStarted { label, .. } => println!("{label}"),
+            _ => {}
+        })
+        .build()?;
+
+    let summary = if with_uv {
+        pyproj::create(&plan, &Default::default())?
+    } else {
+        let actions: Vec<PlannedAction> = plan
+            .plan(true)?
+            .into_iter()
+            .filter(|a| !matches!(a, PlannedAction::RunCommand { .. }))
+            .collect();
+        plan.execute(&actions, WritePolicy::Skip)?
+    };
+    println!("{}: {}", root.display(), summary.line());
+    Ok(())
+}
@@ -0,0 +1,561 @@
+//! Removing caches from a project, or the whole project.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::lock::{ProjectLock, LOCK_FILE};
+use crate::manifest::MANIFEST_FILE;
+use crate::util::{
+    canonicalize_lenient, check_interrupt, home_dir, interrupted, measure, remove_tree, venv_dir_name, wildcard_match, Size,
+};
+use crate::workspace;
+
+/// Build, test, and tool caches removed by [`clean`], relative to the project root,
+/// along with the project's venv (see [`venv_dir_name`]).
+pub const CACHE_PATHS: &[&str] = &[
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    "build",
+    "dist",
+    "htmlcov",
+    ".coverage", // sometimes a file
+    ".cache",
+    ".tox",
+    ".nox",
+    ".eggs",
+    ".hypothesis",
+    ".benchmarks",
+    "pip-wheel-metadata",
+];
+
+/// Directories (relative to the root) searched one level deep for `*.egg-info`.
+const EGG_INFO_PARENTS: &[&str] = &["", "src"];
+
+/// Directories removed by [`clean`] wherever they appear below the project root.
+pub const SWEPT_DIRS: &[&str] = &["__pycache__", ".ipynb_checkpoints"];
+
+/// File extensions removed by [`clean`] wherever they appear below the project root.
+pub const SWEPT_EXTENSIONS: &[&str] = &["pyc", "pyo"];
+
+/// Never descended into while sweeping, nor is the project's venv.
+const SWEEP_SKIP: &[&str] = &[".git", ".venv"];
+
+/// Directories (relative to the root) that [`clean`] never removes or looks inside, not
+/// even for an extra pattern: the data layout's datasets and trained models.
+pub const PROTECTED_DIRS: &[&str] = &["data", "models"];
+
+/// What [`clean`] should leave alone.
+#[derive(Debug, Clone, Default)]
+pub struct CleanOptions {
+    /// Keep the venv, which is the slowest thing to rebuild.
+    pub keep_venv: bool,
+    /// Extra globs relative to the root (see [`check_pattern`]); matches are removed too.
+    pub extra_patterns: Vec<String>,
+    /// Remove only what `extra_patterns` match: no built-in caches, no sweep.
+    pub only_extra: bool,
+}
+
+/// Which of [`clean`]'s rules matched a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanKind {
+    /// A [`CACHE_PATHS`] directory (or `*.egg-info`), removed with everything below it.
+    CacheDir,
+    /// A [`CACHE_PATHS`] entry that is a file or a symlink, or `.coverage.*` data.
+    CacheFile,
+    /// A [`SWEPT_DIRS`] directory found anywhere in the tree.
+    SweptDir,
+    /// A file with a [`SWEPT_EXTENSIONS`] extension.
+    SweptFile,
+    /// A directory matched by [`CleanOptions::extra_patterns`].
+    ExtraDir,
+    /// A file (or symlink) matched by [`CleanOptions::extra_patterns`].
+    ExtraFile,
+}
+
+/// One path [`clean`] removed (or, from [`plan_clean`], would remove).
+#[derive(Debug, Clone)]
+pub struct CleanEntry {
+    pub path: PathBuf,
+    pub kind: CleanKind,
+    /// Measured before removal.
+    pub size: Size,
+    /// Why the path could not be removed; it is still there.
+    pub error: Option<String>,
+}
+
+/// What [`clean`] removed, or tried to.
+#[derive(Debug, Default)]
+pub struct CleanReport {
+    /// Cache entries first, then swept ones, in the order they were found.
+    pub entries: Vec<CleanEntry>,
+    /// What [`clean`] ran into without stopping, such as a stale lock it broke.
+    pub warnings: Vec<String>,
+}
+
+impl CleanReport {
+    /// Entries that are gone (or, for a plan, would be).
+    pub fn removed(&self) -> impl Iterator<Item = &CleanEntry> {
+        self.entries.iter().filter(|e| e.error.is_none())
+    }
+
+    /// Entries that could not be removed.
+    pub fn failed(&self) -> impl Iterator<Item = &CleanEntry> {
+        self.entries.iter().filter(|e| e.error.is_some())
+    }
+
+    /// Bytes freed by the removed entries.
+    pub fn freed(&self) -> u64 {
+        self.removed().map(|e| e.size.bytes).sum()
+    }
+}
+
+/// How [`delete`] removes the project.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteOptions {
+    /// Skip every check in [`check_deletable`], including the root/home refusal.
+    pub allow_unsafe: bool,
+    /// Delete a directory that [`check_project`] does not recognise as a py-proj project.
+    pub force_unmanaged: bool,
+    /// Move the project to the trash instead of removing it. When that is not
+    /// possible, [`Error::TrashUnavailable`] is returned and nothing is removed.
+    pub trash: bool,
+}
+
+/// Files that only a py-proj scaffold has, relative to the project root.
+pub const SIGNATURE_FILES: &[&str] = &["pyrefly.toml", ".vscode/settings.json", "Makefile"];
+
+/// The first line of content in the generated Makefile.
+const MAKEFILE_HEADER: &str = "# === CONFIG ===";
+
+/// What [`check_project`] found that marks a directory as a py-proj project.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectCheck {
+    pub manifest: bool,
+    pub pyproject: bool,
+    /// The [`SIGNATURE_FILES`] present (a Makefile only with py-proj's header).
+    pub signatures: Vec<&'static str>,
+}
+
+impl ProjectCheck {
+    /// A manifest, or a `pyproject.toml` plus at least one signature file.
+    pub fn is_managed(&self) -> bool {
+        self.manifest || (self.pyproject && !self.signatures.is_empty())
+    }
+}
+
+/// Look for the py-proj manifest, `pyproject.toml`, and [`SIGNATURE_FILES`] in `root`.
+pub fn check_project(root: &Path) -> ProjectCheck {
+    let signatures = SIGNATURE_FILES
+        .iter()
+        .copied()
+        .filter(|rel| match *rel {
+            "Makefile" => fs::read_to_string(root.join(rel))
+                .is_ok_and(|text| text.lines().any(|l| l.trim() == MAKEFILE_HEADER)),
+            _ => root.join(rel).is_file(),
+        })
+        .collect();
+    ProjectCheck {
+        manifest: root.join(MANIFEST_FILE).is_file(),
+        pyproject: root.join("pyproject.toml").is_file(),
+        signatures,
+    }
+}
+
+/// What [`delete`] did.
+#[derive(Debug, Default)]
+pub struct DeleteReport {
+    /// False when the project root did not exist.
+    pub deleted: bool,
+    /// Where the project went with [`DeleteOptions::trash`].
+    pub trashed_to: Option<PathBuf>,
+    /// Paths below the root that could not be removed, with why; the rest is gone.
+    /// The project stays in its workspace while any are left.
+    pub failed: Vec<(PathBuf, String)>,
+    /// The uv workspace the project was dropped from, if it was a member.
+    pub unregistered_from: Option<PathBuf>,
+    /// What [`delete`] ran into without stopping, such as a stale lock it broke.
+    pub warnings: Vec<String>,
+}
+
+/// Remove the [`CACHE_PATHS`] under `root` (plus `*.egg-info` in the root and `src/`,
+/// and `.coverage.*` data files), then every [`SWEPT_DIRS`] directory and
+/// [`SWEPT_EXTENSIONS`] file below it, and whatever [`CleanOptions::extra_patterns`]
+/// match, all outside [`PROTECTED_DIRS`]. Symlinks are never followed. Removal carries on past failures; each entry
+/// records what it left behind. An interrupt stops it between entries: the report
+/// then holds only the entries it got to.
+pub fn clean(root: &Path, opts: &CleanOptions) -> Result<CleanReport> {
+    let warnings = RefCell::new(Vec::new());
+    let _lock = ProjectLock::acquire(root, "clean", false, &|message| warnings.borrow_mut().push(message))?;
+    let mut report = plan_clean(root, opts)?;
+    report.warnings = warnings.take();
+    let mut done = 0;
+    for entry in &mut report.entries {
+        if interrupted() {
+            break;
+        }
+        let failed = remove_tree(&entry.path);
+        entry.error = match failed.as_slice() {
+            [] => None,
+            [(path, e)] if *path == entry.path => Some(e.to_string()),
+            [(path, e), rest @ ..] => Some(match rest.len() {
+                0 => format!("{}: {e}", path.display()),
+                n => format!("{}: {e} (and {n} more)", path.display()),
+            }),
+        };
+        done += 1;
+    }
+    report.entries.truncate(done);
+    Ok(report)
+}
+
+/// What [`clean`] would remove, with sizes, without removing anything. Fails if an
+/// extra pattern is rejected by [`check_pattern`].
+pub fn plan_clean(root: &Path, opts: &CleanOptions) -> Result<CleanReport> {
+    for pattern in &opts.extra_patterns {
+        if let Err(e) = check_pattern(pattern) {
+            bail!("{e}");
+        }
+    }
+    let venv = root.join(venv_dir_name(root));
+    let mut found = Vec::new();
+    if !opts.only_extra {
+        for p in cache_targets(root, &venv, opts) {
+            let Ok(meta) = fs::symlink_metadata(&p) else {
+                continue;
+            };
+            // A symlinked cache goes as the link; what it points at is left alone.
+            if meta.is_dir() {
+                found.push((p, CleanKind::CacheDir));
+            } else if meta.is_file() || meta.is_symlink() {
+                found.push((p, CleanKind::CacheFile));
+            }
+        }
+        let mut skip: Vec<PathBuf> = found.iter().map(|(p, _)| p.clone()).collect();
+        skip.push(venv.clone());
+        skip.extend(PROTECTED_DIRS.iter().map(|d| root.join(d)));
+        sweep(root, &skip, &mut found);
+    }
+    let mut extra = Vec::new();
+    for pattern in &opts.extra_patterns {
+        let dirs_only = pattern.ends_with('/');
+        let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty() && *s != ".").collect();
+        glob(root, &segments, dirs_only, &venv, &mut extra);
+    }
+    extra.retain(|p| !PROTECTED_DIRS.iter().any(|d| p.starts_with(root.join(d))) && *p != root.join(LOCK_FILE));
+    // Shortest first, so a matched directory is seen before anything inside it.
+    extra.sort_by_key(|p: &PathBuf| p.components().count());
+    for path in extra {
+        let covered = found
+            .iter()
+            .any(|(p, kind)| path.starts_with(p) && (p == &path || is_dir_kind(*kind)));
+        if covered {
+            continue;
+        }
+        let kind = match fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => CleanKind::ExtraDir,
+            Ok(_) => CleanKind::ExtraFile,
+            Err(_) => continue,
+        };
+        found.push((path, kind));
+    }
+    let entries = found
+        .into_iter()
+        .map(|(path, kind)| CleanEntry {
+            size: measure(&path),
+            path,
+            kind,
+            error: None,
+        })
+        .collect();
+    Ok(CleanReport {
+        entries,
+        warnings: Vec::new(),
+    })
+}
+
+fn is_dir_kind(kind: CleanKind) -> bool {
+    matches!(kind, CleanKind::CacheDir | CleanKind::SweptDir | CleanKind::ExtraDir)
+}
+
+/// Whether `pattern` can be used as an extra clean pattern: a non-empty glob relative
+/// to the project root (`*` and `?` within a name, `**` for any number of
+/// directories, a trailing `/` for directories only) that cannot reach outside it.
+pub fn check_pattern(pattern: &str) -> Result<(), String> {
+    let escapes = Path::new(pattern).is_absolute()
+        || pattern.starts_with(['/', '\\'])
+        || pattern.get(1..2) == Some(":")
+        || pattern.split(['/', '\\']).any(|s| s == "..");
+    if escapes {
+        return Err(format!(
+            "clean pattern `{pattern}` reaches outside the project; use a path relative to its root"
+        ));
+    }
+    if pattern.split('/').all(|s| s.is_empty() || s == "." || s == "**") {
+        return Err(format!("clean pattern `{pattern}` would match the whole project"));
+    }
+    Ok(())
+}
+
+/// Collect the paths below `dir` matching the remaining pattern `segments`.
+/// Symlinks are matched but never descended into, and `**` skips [`SWEEP_SKIP`] and `venv`.
+fn glob(dir: &Path, segments: &[&str], dirs_only: bool, venv: &Path, found: &mut Vec<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        return;
+    };
+    if *segment == "**" {
+        glob(dir, rest, dirs_only, venv, found);
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(ty) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if *segment == "**" {
+            if ty.is_dir() && !SWEEP_SKIP.contains(&name.as_ref()) && path != venv {
+                glob(&path, segments, dirs_only, venv, found);
+            }
+        } else if wildcard_match(segment, &name) {
+            if !rest.is_empty() {
+                if ty.is_dir() {
+                    glob(&path, rest, dirs_only, venv, found);
+                }
+            } else if (ty.is_dir() || !dirs_only) && !found.contains(&path) {
+                found.push(path);
+            }
+        }
+    }
+}
+
+/// The venv and the top-level cache directories and files that exist under `root`.
+fn cache_targets(root: &Path, venv: &Path, opts: &CleanOptions) -> Vec<PathBuf> {
+    let mut targets: Vec<PathBuf> = Some(venv.to_path_buf())
+        .filter(|_| !opts.keep_venv)
+        .into_iter()
+        .chain(CACHE_PATHS.iter().map(|rel| root.join(rel)))
+        .filter(|p| p.is_dir() || (p.is_file() && p.ends_with(".coverage")))
+        .collect();
+    let shallow = |dir: PathBuf| fs::read_dir(dir).into_iter().flatten().flatten();
+    for parent in EGG_INFO_PARENTS {
+        targets.extend(
+            shallow(root.join(parent))
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".egg-info"))
+                .map(|e| e.path()),
+        );
+    }
+    // Parallel-mode coverage data: `.coverage.<host>.<pid>.<random>`.
+    targets.extend(
+        shallow(root.to_path_buf())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".coverage."))
+            .map(|e| e.path()),
+    );
+    targets
+}
+
+/// Collect [`SWEPT_DIRS`] and [`SWEPT_EXTENSIONS`] files below `dir`, not descending
+/// into `skip` (cache dirs removed wholesale) or [`SWEEP_SKIP`].
+fn sweep(dir: &Path, skip: &[PathBuf], found: &mut Vec<(PathBuf, CleanKind)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // `file_type` does not follow symlinks, so a link to elsewhere is neither.
+        let Ok(ty) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if ty.is_dir() {
+            if skip.contains(&path) || SWEEP_SKIP.contains(&name.as_ref()) {
+                continue;
+            }
+            if SWEPT_DIRS.contains(&name.as_ref()) {
+                found.push((path, CleanKind::SweptDir));
+            } else {
+                sweep(&path, skip, found);
+            }
+        } else if ty.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| SWEPT_EXTENSIONS.iter().any(|e| ext == *e))
+        {
+            found.push((path, CleanKind::SweptFile));
+        }
+    }
+}
+
+/// Delete the entire project directory (dangerous), or move it to the trash, and drop
+/// it from its uv workspace. A workspace root that still has members is refused, and
+/// so (unless `allow_unsafe`) is a filesystem root, the home directory, or anything
+/// that does not look like a Python project.
+pub fn delete(root: &Path, opts: &DeleteOptions) -> Result<DeleteReport> {
+    if !root.exists() {
+        return Ok(DeleteReport::default());
+    }
+    // A refused delete must not leave a lock file behind in a directory it refused.
+    check_deletable(root, opts)?;
+    let warnings = RefCell::new(Vec::new());
+    let _lock = ProjectLock::acquire(root, "delete", false, &|message| warnings.borrow_mut().push(message))?;
+    let members = workspace::members(root);
+    if !members.is_empty() {
+        bail!(
+            "{} is a uv workspace root with {} member(s) ({}); delete members one at a time",
+            root.display(),
+            members.len(),
+            members.join(", ")
+        );
+    }
+    let workspace = workspace::find_root(root)?;
+    // Past this point an interrupt lets the removal (and unregistering) finish.
+    check_interrupt()?;
+    let trashed_to = match opts.trash {
+        true => Some(move_to_trash(root).map_err(|reason| Error::TrashUnavailable {
+            path: root.to_path_buf(),
+            reason,
+        })?),
+        false => {
+            let failed: Vec<(PathBuf, String)> = remove_tree(root)
+                .into_iter()
+                .map(|(path, e)| (path, e.to_string()))
+                .collect();
+            if !failed.is_empty() {
+                return Ok(DeleteReport {
+                    failed,
+                    warnings: warnings.take(),
+                    ..DeleteReport::default()
+                });
+            }
+            None
+        }
+    };
+    if let Some(ws) = &workspace {
+        let member = workspace::member_path(ws, root)?;
+        workspace::remove_member(ws, &member)?;
+    }
+    Ok(DeleteReport {
+        deleted: true,
+        trashed_to,
+        failed: Vec::new(),
+        unregistered_from: workspace,
+        warnings: warnings.take(),
+    })
+}
+
+/// Move `path` into the freedesktop.org trash (`$XDG_DATA_HOME/Trash`) with the
+/// `.trashinfo` file desktop environments use to restore it. Only a rename is tried,
+/// so a path on another filesystem than the trash (e.g. NFS) fails. Returns where it
+/// went, which [`DeleteReport::trashed_to`] reports and `trash::delete` would not.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn move_to_trash(path: &Path) -> std::result::Result<PathBuf, String> {
+    let trash = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home_dir().map(|h| h.join(".local/share")))
+        .ok_or("no home directory to find the trash in")?
+        .join("Trash");
+    let (files, info) = (trash.join("files"), trash.join("info"));
+    for dir in [&files, &info] {
+        fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+    }
+    let source = std::path::absolute(path).map_err(|e| e.to_string())?;
+    let name = source.file_name().ok_or("the path has no file name")?.to_string_lossy().into_owned();
+    // `name`, `name.2`, `name.3`, ...: the .trashinfo is created first to claim the slot.
+    let (dest, info_file) = (1..)
+        .map(|n| match n {
+            1 => name.clone(),
+            n => format!("{name}.{n}"),
+        })
+        .map(|n| (files.join(&n), info.join(format!("{n}.trashinfo"))))
+        .find(|(dest, info_file)| !dest.exists() && !info_file.exists())
+        .expect("unbounded");
+    let date = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S");
+    let contents = format!("[Trash Info]\nPath={}\nDeletionDate={date}\n", percent_encode(&source));
+    fs::write(&info_file, contents).map_err(|e| format!("cannot write {}: {e}", info_file.display()))?;
+    if let Err(e) = fs::rename(&source, &dest) {
+        let _ = fs::remove_file(&info_file);
+        return Err(match e.raw_os_error() {
+            Some(libc::EXDEV) => format!("{} is on a different filesystem than the trash", source.display()),
+            _ => e.to_string(),
+        });
+    }
+    Ok(dest)
+}
+
+/// Move `path` into `~/.Trash`. Finder's "Put Back" does not know about it, but the
+/// files are there.
+#[cfg(target_os = "macos")]
+fn move_to_trash(path: &Path) -> std::result::Result<PathBuf, String> {
+    let trash = home_dir().ok_or("no home directory to find the trash in")?.join(".Trash");
+    let name = path.file_name().ok_or("the path has no file name")?.to_string_lossy().into_owned();
+    let dest = (1..)
+        .map(|n| match n {
+            1 => trash.join(&name),
+            n => trash.join(format!("{name} {n}")),
+        })
+        .find(|d| !d.exists())
+        .expect("unbounded");
+    fs::rename(path, &dest).map_err(|e| e.to_string())?;
+    Ok(dest)
+}
+
+#[cfg(not(unix))]
+fn move_to_trash(_path: &Path) -> std::result::Result<PathBuf, String> {
+    Err("moving to the Recycle Bin is not supported on this platform".to_string())
+}
+
+/// The `Path=` value of a `.trashinfo` file: RFC 3986 escaping, with `/` kept.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn percent_encode(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .map(|&b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                char::from(b).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Refuse paths that are never a project: `/` (or a drive root) and the home directory
+/// unless `allow_unsafe`, and directories [`check_project`] does not recognise unless
+/// `force_unmanaged` (or `allow_unsafe`).
+pub fn check_deletable(root: &Path, opts: &DeleteOptions) -> Result<()> {
+    if opts.allow_unsafe {
+        return Ok(());
+    }
+    let canonical = canonicalize_lenient(root);
+    let (reason, flag) = if canonical.parent().is_none() {
+        ("it is a filesystem root", "--i-know-what-im-doing")
+    } else if home_dir().is_some_and(|home| canonicalize_lenient(&home) == canonical) {
+        ("it is your home directory", "--i-know-what-im-doing")
+    } else if !opts.force_unmanaged && !check_project(&canonical).is_managed() {
+        (
+            "it has no .pyproj.toml, nor a pyproject.toml with py-proj's pyrefly.toml, \
+             .vscode/settings.json, or Makefile, so it does not look like a py-proj project",
+            "--force-delete-unmanaged",
+        )
+    } else {
+        return Ok(());
+    };
+    Err(Error::NeedsConfirmation {
+        action: format!("delete {} ({reason})", canonical.display()),
+        flag,
+    }
+    .into())
+}
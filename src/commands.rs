@@ -0,0 +1,149 @@
+//! The CLI's subcommands, and the terminal output for library [`Event`]s.
+
+use owo_colors::OwoColorize;
+use std::cell::RefCell;
+use std::path::Path;
+
+use crate::progress::{self, Spinner};
+use pyproj::{Event, WriteOutcome};
+
+pub mod activate;
+pub mod add_module;
+pub mod adopt;
+pub mod archive;
+pub mod batch;
+pub mod bump;
+pub mod check_vscode;
+pub mod clean_all;
+pub mod config_show;
+pub mod diff;
+pub mod doctor;
+pub mod export;
+pub mod history;
+pub mod info;
+pub mod list;
+pub mod open;
+pub mod preflight;
+pub mod rename;
+pub mod repair;
+pub mod report;
+pub mod run;
+pub mod sbom;
+pub mod stats;
+pub mod summary;
+pub mod templates;
+pub mod undo;
+pub mod upgrade;
+pub mod verify;
+
+/// Print scaffold progress; with `verbose`, also where each file came from and the
+/// output of each uv step as it runs. Under `--quiet` only warnings and rollback show.
+pub fn printer(verbose: bool) -> impl Fn(&Event<'_>) + 'static {
+    // The running step's spinner and its message, while one is on screen.
+    let spinner: RefCell<Option<(Spinner, String)>> = RefCell::new(None);
+    move |event| match event {
+        Event::RollbackStarted => println!("↩️  Rolling back …"),
+        Event::RolledBack {
+            path,
+            recursive: true,
+        } => println!("  {} {}", "rm -rf".yellow(), path.display()),
+        Event::RolledBack { path, .. } => {
+            println!("  {} {}", "rm".yellow(), path.display().to_string().dimmed())
+        }
+        Event::Warning { message } => println!("  {} {}", "WARN".yellow().bold(), message.yellow()),
+        _ if progress::quiet() => {}
+        Event::FileWritten {
+            path,
+            origin,
+            source,
+            outcome,
+        } => {
+            let rel = path.display();
+            if verbose {
+                match source {
+                    Some(src) => println!(
+                        "  {} {rel} {}",
+                        origin.label().cyan(),
+                        format!("({})", src.display()).dimmed()
+                    ),
+                    None => println!("  {} {rel}", origin.label().dimmed()),
+                }
+            }
+            match outcome {
+                WriteOutcome::Updated => println!("  {} {rel}", "update".green()),
+                WriteOutcome::Skipped => {
+                    println!("  {} {rel} {}", "skip".yellow(), "(differs)".dimmed())
+                }
+                WriteOutcome::BackedUp(bak) => println!(
+                    "  {} {rel} {}",
+                    "update".green(),
+                    format!("(backup: {})", bak.display()).dimmed()
+                ),
+                WriteOutcome::Created | WriteOutcome::Unchanged => {}
+            }
+        }
+        Event::PyprojectMerged { added, replaced } => {
+            if !added.is_empty() {
+                println!("    {} {}", "+".green(), added.join(", ").dimmed());
+            }
+            for r in replaced.iter() {
+                println!("    {} {} {} → {}", "~".yellow(), r.key, r.old.dimmed(), r.new);
+            }
+        }
+        Event::CommandStarted {
+            label,
+            command,
+            step,
+            steps,
+        } => {
+            let message = format!("{} {label}", format!("[{step}/{steps}]").dimmed());
+            if verbose {
+                println!("  {} {}", "$".dimmed(), command.dimmed());
+            }
+            // Streamed output (--verbose) would fight with the spinner for the line.
+            match (!verbose).then(|| Spinner::start(message.clone())).flatten() {
+                Some(s) => *spinner.borrow_mut() = Some((s, message)),
+                None => println!("{message}"),
+            }
+        }
+        Event::CommandOutput { program, line } if verbose => {
+            let name = Path::new(program).file_name().unwrap_or_default().to_string_lossy();
+            println!("  {} {line}", format!("[{name}]").dimmed());
+        }
+        Event::CommandFinished { success, elapsed, .. } => {
+            if let Some((s, message)) = spinner.borrow_mut().take() {
+                s.finish();
+                if *success {
+                    let secs = format!("({:.1}s)", elapsed.as_secs_f64());
+                    println!("{message} {}", secs.dimmed());
+                }
+            }
+        }
+        Event::UvInstalled { path } => println!(
+            "  {} {} {}",
+            "uv".green(),
+            path.display(),
+            "(add its directory to PATH to use it directly)".dimmed()
+        ),
+        Event::VenvKept { version: Some(version) } => {
+            println!("🧪 Keeping existing .venv {}", format!("(Python {version})").dimmed())
+        }
+        Event::VenvKept { version: None } => println!("🧪 Keeping existing .venv"),
+        Event::PythonInstallSkipped { version } => {
+            println!("🐍 Python {version} already installed {}", "(skipped)".dimmed())
+        }
+        Event::HookStarted {
+            index,
+            total,
+            command,
+        } => println!("🪝 Running hook {index}/{total}: {}", command.bold()),
+        Event::WorkspaceUpdated { what } => println!("  {} {}", "update".green(), what.dimmed()),
+        Event::TemplatesCached { url, path } => println!(
+            "  {} {url} {}",
+            "cache".dimmed(),
+            format!("({})", path.display()).dimmed()
+        ),
+        Event::TemplatesCloning { url } => println!("  {} {url}", "git clone".cyan()),
+        _ => {}
+    }
+}
@@ -0,0 +1,158 @@
+//! `py-proj env`: print shell code that activates a project's venv, for
+//! `eval "$(py-proj env)"`. Everything else it prints is a shell comment, so a missing
+//! venv never hands eval something it would choke on.
+
+use anyhow::{bail, Result};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::Manifest;
+use pyproj::util::{find_project_root, notebooks_dir_name, project_venv};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    /// The shell that will eval the output: the parent process when it is a known
+    /// shell, then `$SHELL`, then PowerShell on Windows and bash elsewhere.
+    pub fn detect() -> Shell {
+        parent_process_name()
+            .and_then(|name| Shell::from_program(&name))
+            .or_else(|| env::var("SHELL").ok().and_then(|s| Shell::from_program(&s)))
+            .unwrap_or(if cfg!(windows) { Shell::Powershell } else { Shell::Bash })
+    }
+
+    /// `/usr/bin/zsh`, `-fish`, `pwsh.exe` -> the matching shell.
+    fn from_program(program: &str) -> Option<Shell> {
+        let name = Path::new(program.trim()).file_stem()?.to_string_lossy().to_lowercase();
+        match name.trim_start_matches('-') {
+            "bash" | "sh" | "dash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "pwsh" | "powershell" => Some(Shell::Powershell),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+        })
+    }
+}
+
+/// Print the activation snippet for the project at or above `start`. Fails (after a
+/// comment saying why) when there is no project or no venv.
+pub fn print_env(start: &Path, shell: Shell) -> Result<()> {
+    let Some(root) = find_project_root(start) else {
+        println!("# py-proj: no project found in {} or its parents", start.display());
+        return Err(pyproj::Error::project_not_found(start, "py-proj env path/to/proj").into());
+    };
+    let Some(venv) = project_venv(&root)? else {
+        println!(
+            "# py-proj: {} has no .venv; create it with `uv venv` (or `uv sync`) first",
+            root.display()
+        );
+        bail!("{} has no .venv", root.display());
+    };
+    println!("# py-proj: {} ({shell})", root.display());
+    let paths = python_path(&root)?;
+    for line in snippet(shell, &venv, &paths) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// What the flat layout's `.env` puts on `PYTHONPATH` (root, `src`, the notebooks), as
+/// absolute paths, so imports resolve in an ad-hoc REPL. Packaged members need none.
+fn python_path(root: &Path) -> Result<Vec<PathBuf>> {
+    let flat = match Manifest::load(root)? {
+        Some(manifest) => manifest.options.layout == "flat",
+        None => root.join("src").join("main.py").is_file(),
+    };
+    if !flat {
+        return Ok(Vec::new());
+    }
+    let mut paths = vec![root.to_path_buf(), root.join("src")];
+    let notebooks = root.join(notebooks_dir_name(root));
+    if notebooks.is_dir() {
+        paths.push(notebooks);
+    }
+    Ok(paths)
+}
+
+fn snippet(shell: Shell, venv: &Path, paths: &[PathBuf]) -> Vec<String> {
+    let joined = |sep: &str| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(sep);
+    let mut lines = Vec::new();
+    match shell {
+        Shell::Bash | Shell::Zsh => {
+            lines.push(format!("source {}", posix_quote(&venv.join("bin").join("activate"))));
+            if !paths.is_empty() {
+                lines.push(format!(
+                    "export PYTHONPATH={}\"${{PYTHONPATH:+:$PYTHONPATH}}\"",
+                    posix_quote(Path::new(&joined(":")))
+                ));
+            }
+        }
+        Shell::Fish => {
+            lines.push(format!("source {}", fish_quote(&venv.join("bin").join("activate.fish"))));
+            if !paths.is_empty() {
+                let quoted: Vec<String> = paths.iter().map(|p| fish_quote(p)).collect();
+                lines.push(format!("set -gx PYTHONPATH {} $PYTHONPATH", quoted.join(" ")));
+            }
+        }
+        Shell::Powershell => {
+            // Windows venvs keep their scripts in `Scripts`, others (pwsh on Unix) in `bin`.
+            let scripts = match venv.join("Scripts").is_dir() {
+                true => venv.join("Scripts"),
+                false => venv.join("bin"),
+            };
+            lines.push(format!("& {}", ps_quote(&scripts.join("Activate.ps1"))));
+            if !paths.is_empty() {
+                let sep = if cfg!(windows) { ";" } else { ":" };
+                lines.push(format!(
+                    "$env:PYTHONPATH = {} + $(if ($env:PYTHONPATH) {{ '{sep}' + $env:PYTHONPATH }} else {{ '' }})",
+                    ps_quote(Path::new(&joined(sep)))
+                ));
+            }
+        }
+    }
+    lines
+}
+
+fn posix_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
+
+fn fish_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn ps_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', "''"))
+}
+
+/// The executable name of the process that started py-proj, where the platform tells.
+fn parent_process_name() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let ppid = std::os::unix::process::parent_id();
+        let comm = std::fs::read_to_string(format!("/proc/{ppid}/comm")).ok()?;
+        Some(comm.trim().to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
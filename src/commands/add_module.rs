@@ -0,0 +1,133 @@
+//! `py-proj add-module`: add a module, its parent packages, and a placeholder test.
+
+use anyhow::{bail, Result};
+use minijinja::Value;
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::{FileEntry, Manifest};
+use pyproj::scaffold::license_holder;
+use pyproj::templates::{add_license_header, add_provenance_stamp, module_py, module_test_py, render, Vars};
+use pyproj::util::{import_name, sha256_hex, validate_module, write};
+
+/// Where modules go: the package directory and its import name.
+struct Layout {
+    dir: PathBuf,
+    package: String,
+}
+
+pub fn add_modules(root: &Path, modules: &[String]) -> Result<()> {
+    let mut manifest = Manifest::load(root)?;
+    let layout = match manifest.as_ref().and_then(|m| Some((m.options.namespace.as_ref()?, &m.options.project))) {
+        Some((ns, project)) => {
+            let package = import_name(project);
+            Layout { dir: Path::new("src").join(ns).join(&package), package: format!("{ns}.{package}") }
+        }
+        None => detect_layout(root)?,
+    };
+    let logger_import = root.join(&layout.dir).join("app_logging/glogger.py").is_file();
+
+    // Validate everything first so a bad name in the list writes nothing.
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    for module in modules {
+        let parts = validate_module(module)?;
+        let (leaf, parents) = parts.split_last().expect("validated non-empty");
+
+        let mut pkg_dir = layout.dir.clone();
+        for parent in parents {
+            pkg_dir.push(parent);
+            let init = pkg_dir.join("__init__.py");
+            if !root.join(&init).exists() && !files.iter().any(|(p, _)| *p == init) {
+                files.push((init, String::new()));
+            }
+        }
+        let module_path = pkg_dir.join(format!("{leaf}.py"));
+        if root.join(&module_path).exists() || root.join(pkg_dir.join(leaf)).is_dir() {
+            bail!("module `{module}` already exists ({})", module_path.display());
+        }
+        let test_name = parts.join("_");
+        let test_path = PathBuf::from("tests").join(format!("test_{test_name}.py"));
+        if root.join(&test_path).exists() {
+            bail!("test file already exists: {}", test_path.display());
+        }
+
+        let mut parent_import = vec![layout.package.as_str()];
+        parent_import.extend(parents.iter().copied());
+        let mut ctx = Vars::new();
+        ctx.insert("module".into(), Value::from(module.as_str()));
+        ctx.insert("package".into(), Value::from(layout.package.as_str()));
+        ctx.insert("parent_import".into(), Value::from(parent_import.join(".")));
+        ctx.insert("leaf".into(), Value::from(*leaf));
+        ctx.insert("test_name".into(), Value::from(test_name));
+        ctx.insert("logger_import".into(), Value::from(logger_import));
+
+        let rel = module_path.to_string_lossy().into_owned();
+        files.push((module_path, render(&rel, module_py(), &ctx)?));
+        let rel = test_path.to_string_lossy().into_owned();
+        files.push((test_path, render(&rel, module_test_py(), &ctx)?));
+    }
+
+    for (_, content) in files.iter_mut().filter(|(_, c)| !c.is_empty()) {
+        *content = add_provenance_stamp(content);
+    }
+    if let Some(m) = manifest.as_ref() {
+        if let Some(spdx) = &m.options.license_header {
+            let holder = license_holder(m.options.author.as_deref(), &m.options.project);
+            let year: String = m.tool.created.chars().take(4).collect();
+            for (_, content) in files.iter_mut() {
+                *content = add_license_header(content, spdx, &year, &holder)?;
+            }
+        }
+    }
+    for (rel, content) in &files {
+        println!("  {} {}", "create".green(), rel.display());
+        write(root.join(rel), content)?;
+        if let Some(m) = manifest.as_mut() {
+            m.files.push(FileEntry {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                sha256: sha256_hex(content),
+                mode: None,
+            });
+        }
+    }
+    if let Some(m) = manifest {
+        m.write(root)?;
+    }
+    Ok(())
+}
+
+/// `src/` itself when it is a package (the flat layout), else the single package under it
+/// (or under a namespace directory in it, one without an `__init__.py`).
+fn detect_layout(root: &Path) -> Result<Layout> {
+    let src = root.join("src");
+    if src.join("__init__.py").is_file() {
+        return Ok(Layout { dir: PathBuf::from("src"), package: "src".to_string() });
+    }
+    let mut packages = Vec::new();
+    if let Ok(entries) = fs::read_dir(&src) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().join("__init__.py").is_file() {
+                packages.push(name);
+            } else if let Ok(below) = fs::read_dir(entry.path()) {
+                // A PEP 420 namespace: its packages import as `namespace.package`.
+                packages.extend(
+                    below
+                        .flatten()
+                        .filter(|e| e.path().join("__init__.py").is_file())
+                        .map(|e| format!("{name}.{}", e.file_name().to_string_lossy())),
+                );
+            }
+        }
+    }
+    match packages.as_slice() {
+        [pkg] => Ok(Layout { dir: pkg.split('.').fold(PathBuf::from("src"), |dir, p| dir.join(p)), package: pkg.clone() }),
+        [] => bail!("no package found under {}; is this a py-proj project?", src.display()),
+        _ => bail!(
+            "several packages under {} ({}); cannot tell where modules go",
+            src.display(),
+            packages.join(", ")
+        ),
+    }
+}
@@ -0,0 +1,217 @@
+//! `py-proj adopt`: add py-proj's tooling to an existing Python project without
+//! touching its code, and record a manifest so later commands treat it as managed.
+
+use anyhow::{bail, Context, Result};
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::pyproject;
+use pyproj::scaffold::{Origin, PlannedAction, ScaffoldPlan};
+use pyproj::templates::{dotenv_example, find, Group};
+use pyproj::util::{blank_env_values, write, write_with, WriteOutcome, WritePolicy};
+
+pub struct AdoptOptions {
+    /// Replace existing config files that differ from the templates.
+    pub force: bool,
+    /// Write the config files only; leave the venv and the Python install alone.
+    pub skip_uv: bool,
+    pub verbose: bool,
+}
+
+pub fn adopt_project(root: &Path, opts: &AdoptOptions) -> Result<()> {
+    if !root.is_dir() {
+        bail!("not a directory: {}", root.display());
+    }
+    if Manifest::load(root)?.is_some() {
+        return Err(pyproj::Error::TargetExists {
+            path: root.join(MANIFEST_FILE),
+            hint: "the project is already managed; use `py-proj upgrade` instead".to_string(),
+        }
+        .into());
+    }
+    let mut plan = ScaffoldPlan::inferred(root, super::printer(opts.verbose))?;
+    plan.skip_uv = opts.skip_uv;
+    println!(
+        "  {} {} {}",
+        "Project:".dimmed(),
+        plan.project.blue().bold(),
+        format!("(Python {})", plan.python).dimmed()
+    );
+
+    // Before the first write, so a missing uv or an unusable venv leaves the tree alone.
+    plan.check_uv_toolchain()?;
+    // Taken before anything is written, so `undo` can tell the project's files from ours.
+    let preexisting = plan.preexisting(false)?;
+    let mut replaced = Replaced::default();
+    let adopted = write_files(&plan, opts, &mut replaced)
+        .and_then(|()| plan.install_uv_toolchain())
+        .and_then(|()| plan.write_manifest());
+    if let Err(e) = adopted {
+        plan.rollback(false);
+        replaced.restore();
+        return Err(e);
+    }
+    plan.record_preexisting(preexisting)
+}
+
+/// Write the config templates into the adopted project: new files through `plan`, so
+/// a rollback removes them, and merges and `--force` overwrites through `replaced`.
+fn write_files(plan: &ScaffoldPlan, opts: &AdoptOptions, replaced: &mut Replaced) -> Result<()> {
+    let root = &plan.root;
+    let mut created = Vec::new();
+    for (rel, new) in plan.render_files()? {
+        let Some(group) = find(&rel).map(|t| t.group) else {
+            continue;
+        };
+        // Code, tests, and docs belong to the project; .env files may hold secrets.
+        if matches!(group, Group::Src | Group::AppLogging | Group::Readme | Group::Changelog) {
+            continue;
+        }
+        let path = root.join(&rel);
+        // An existing .env is the project's own: describe its keys, not the template's.
+        let new = match fs::read_to_string(root.join(".env")) {
+            Ok(env) if rel == ".env.example" => {
+                (dotenv_example().to_string() + &blank_env_values(&env)).into_bytes()
+            }
+            _ => new,
+        };
+        let Ok(current) = fs::read(&path) else {
+            println!("  {} {}", "create".green(), rel);
+            created.push(PlannedAction::WriteFile {
+                path: rel.into(),
+                contents: new,
+                origin: Origin::BuiltIn,
+            });
+            continue;
+        };
+        if current == new {
+            if opts.verbose {
+                println!("  {} {}", "ok".dimmed(), rel.dimmed());
+            }
+        } else if group == Group::Pyproject {
+            merge_pyproject(&path, &String::from_utf8_lossy(&new), replaced)?;
+        } else if group == Group::Gitignore {
+            merge_gitignore(&path, &String::from_utf8_lossy(&new), replaced)?;
+        } else if opts.force && group != Group::Envs {
+            println!("  {} {}", "overwrite".yellow(), rel);
+            replaced.write(&path, current, new)?;
+        } else {
+            let why = match group {
+                Group::Envs => "(exists)",
+                _ => "(exists and differs; --force to replace)",
+            };
+            println!("  {} {} {}", "skip".yellow(), rel, why.dimmed());
+        }
+    }
+    plan.execute(&created, WritePolicy::Skip).map(|_| ())
+}
+
+/// The existing files adopt merged into or overwrote, with their contents before, and
+/// the backups it made of them; [`Replaced::restore`] puts them back after a failure.
+#[derive(Default)]
+struct Replaced {
+    originals: Vec<(PathBuf, Vec<u8>)>,
+    backups: Vec<PathBuf>,
+}
+
+impl Replaced {
+    /// Replace `path` (currently `old`) with `new`, backing it up first.
+    fn write(&mut self, path: &Path, old: Vec<u8>, new: impl AsRef<[u8]>) -> Result<()> {
+        let outcome = write_with(path, new, WritePolicy::Backup)?;
+        self.originals.push((path.to_path_buf(), old));
+        if let WriteOutcome::BackedUp(bak) = &outcome {
+            self.backups.push(bak.clone());
+        }
+        report_backup(outcome);
+        Ok(())
+    }
+
+    /// Put back what [`Replaced::write`] replaced and remove the backups it made. Best
+    /// effort: the error being reported is the one that caused the rollback.
+    fn restore(self) {
+        for (path, old) in self.originals.iter().rev() {
+            if let Err(e) = write(path, old) {
+                eprintln!("  {} {}: {e:#}", "warning:".yellow(), path.display());
+            }
+        }
+        for bak in &self.backups {
+            let _ = fs::remove_file(bak);
+        }
+    }
+}
+
+/// Merge the generated tool tables and dev extras into an existing pyproject.toml,
+/// keeping the user's metadata, comments, and formatting (see [`pyproject::merge`]).
+fn merge_pyproject(path: &Path, generated: &str, replaced: &mut Replaced) -> Result<()> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let merge = pyproject::merge(&text, generated).with_context(|| format!("failed to merge {}", path.display()))?;
+    if merge.is_noop() {
+        println!("  {} {}", "ok".dimmed(), "pyproject.toml (nothing to add)".dimmed());
+        return Ok(());
+    }
+    report_pyproject_merge(&merge);
+    replaced.write(path, text.into_bytes(), merge.text)?;
+    Ok(())
+}
+
+/// One `merge` line for pyproject.toml, then each replaced key with its old value.
+fn report_pyproject_merge(merge: &pyproject::Merge) {
+    let mut what = Vec::new();
+    if !merge.added.is_empty() {
+        what.push(format!("+ {}", merge.added.join(", ")));
+    }
+    if !merge.replaced.is_empty() {
+        what.push(format!("{} key(s) replaced", merge.replaced.len()));
+    }
+    println!("  {} pyproject.toml {}", "merge".green(), format!("({})", what.join("; ")).dimmed());
+    for r in &merge.replaced {
+        println!("    {} {} {} → {}", "~".yellow(), r.key, r.old.dimmed(), r.new);
+    }
+}
+
+/// Append the generated .gitignore patterns the existing file lacks, each under the
+/// comment heading its section in the template. The user's lines are left as they are.
+fn merge_gitignore(path: &Path, generated: &str, replaced: &mut Replaced) -> Result<()> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let have: Vec<&str> = text.lines().map(str::trim).collect();
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+    let mut heading = "# py-proj";
+    for line in generated.lines().map(str::trim) {
+        if line.starts_with('#') {
+            heading = line;
+        } else if !line.is_empty() && !have.contains(&line) {
+            match sections.last_mut() {
+                Some((h, lines)) if *h == heading => lines.push(line),
+                _ => sections.push((heading, vec![line])),
+            }
+        }
+    }
+    let added: usize = sections.iter().map(|(_, lines)| lines.len()).sum();
+    if added == 0 {
+        println!("  {} {}", "ok".dimmed(), ".gitignore (nothing to add)".dimmed());
+        return Ok(());
+    }
+    let mut merged = text.trim_end().to_string();
+    for (heading, lines) in &sections {
+        merged.push_str(&format!("\n\n{heading}\n{}", lines.join("\n")));
+    }
+    merged.push('\n');
+    println!(
+        "  {} .gitignore {}",
+        "merge".green(),
+        format!("(+ {added} pattern(s))").dimmed()
+    );
+    replaced.write(path, text.into_bytes(), merged)?;
+    Ok(())
+}
+
+/// Mention the backup file, if `outcome` made one.
+pub fn report_backup(outcome: WriteOutcome) {
+    if let WriteOutcome::BackedUp(bak) = outcome {
+        println!("    {} {}", "backup".dimmed(), bak.display().to_string().dimmed());
+    }
+}
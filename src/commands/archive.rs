@@ -0,0 +1,260 @@
+//! `py-proj archive`: pack a project into a `.tar.gz` or `.zip` without what
+//! `--clean_project` would remove (the venv, caches, build output) or `.git`, e.g.
+//! before deleting an experiment. Entries are sorted; with `--deterministic` their
+//! times are pinned too, so the same tree gives the same archive.
+
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+use pyproj::lock::{ProjectLock, LOCK_FILE};
+use pyproj::util::{canonicalize_lenient, check_interrupt};
+use pyproj::{CleanOptions, Event};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A gzip-compressed tarball.
+    #[default]
+    #[value(name = "tar.gz")]
+    TarGz,
+    Zip,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::TarGz => "tar.gz",
+            Format::Zip => "zip",
+        }
+    }
+
+    /// The format an output path's extension names, if any.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        match () {
+            _ if name.ends_with(".tar.gz") || name.ends_with(".tgz") => Some(Format::TarGz),
+            _ if name.ends_with(".zip") => Some(Format::Zip),
+            _ => None,
+        }
+    }
+}
+
+pub struct ArchiveOptions {
+    /// Where to write the archive (default: `<project>.<ext>` next to the project).
+    pub output: Option<PathBuf>,
+    /// Default: from `output`'s extension, else tar.gz.
+    pub format: Option<Format>,
+    /// Keep `.git`.
+    pub with_git: bool,
+    /// Stamp every entry with [`pyproj::manifest::build_time`] instead of its mtime.
+    pub deterministic: bool,
+    pub verbose: bool,
+}
+
+/// What [`archive`] wrote.
+#[derive(Debug)]
+pub struct Archived {
+    pub path: PathBuf,
+    pub format: Format,
+    /// Size of the archive itself.
+    pub bytes: u64,
+    /// Files and symlinks stored (directories not counted).
+    pub files: usize,
+    /// Top-most paths left out, relative to the root.
+    pub excluded: Vec<PathBuf>,
+}
+
+pub fn archive(root: &Path, opts: &ArchiveOptions) -> Result<Archived> {
+    let printer = super::printer(opts.verbose);
+    let _lock = ProjectLock::acquire(root, "archive", false, &|message| printer(&Event::Warning { message }))?;
+    let root = canonicalize_lenient(root);
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .with_context(|| format!("{} has no directory name to archive it under", root.display()))?;
+    let format = opts
+        .format
+        .or_else(|| opts.output.as_deref().and_then(Format::from_path))
+        .unwrap_or_default();
+    let output = match &opts.output {
+        Some(path) => std::path::absolute(path)?,
+        None => root.with_file_name(format!("{name}.{}", format.extension())),
+    };
+    if output.exists() {
+        return Err(pyproj::Error::TargetExists {
+            path: output,
+            hint: "remove it or pass -o with another path".to_string(),
+        }
+        .into());
+    }
+
+    let mut skip: Vec<PathBuf> = pyproj::plan_clean(&root, &CleanOptions::default())?
+        .entries
+        .into_iter()
+        .map(|e| e.path)
+        .collect();
+    if !opts.with_git {
+        skip.push(root.join(".git"));
+    }
+    let excluded = skip
+        .iter()
+        .filter(|p| p.symlink_metadata().is_ok())
+        .filter_map(|p| p.strip_prefix(&root).ok().map(Path::to_path_buf))
+        .collect();
+    // Held by this run, so never part of the project.
+    skip.push(root.join(LOCK_FILE));
+    let mut entries = Vec::new();
+    walk(&root, &skip, &mut entries)?;
+    // The archive may be written inside the project; it is not part of itself.
+    let output_canonical = canonicalize_lenient(&output);
+    entries.retain(|p| *p != output_canonical);
+
+    let time = opts.deterministic.then(pyproj::manifest::build_time);
+    let partial = output.with_file_name(format!(
+        "{}.partial",
+        output.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let written = match format {
+        Format::TarGz => write_tar(&partial, &root, &name, &entries, time),
+        Format::Zip => write_zip(&partial, &root, &name, &entries, time),
+    };
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e.context(format!("failed to write {}", output.display())));
+    }
+    fs::rename(&partial, &output).with_context(|| format!("failed to write {}", output.display()))?;
+    Ok(Archived {
+        bytes: fs::metadata(&output)?.len(),
+        path: output,
+        format,
+        files: entries
+            .iter()
+            .filter(|p| p.symlink_metadata().is_ok_and(|m| !m.is_dir()))
+            .count(),
+        excluded,
+    })
+}
+
+/// Everything below `dir` except `skip` (and what is below it), sorted by name at each
+/// level so the archive does not depend on directory order. Symlinks are not followed.
+fn walk(dir: &Path, skip: &[PathBuf], out: &mut Vec<PathBuf>) -> Result<()> {
+    let mut children = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .collect::<io::Result<Vec<_>>>()?;
+    children.sort_by_key(|e| e.file_name());
+    for child in children {
+        check_interrupt()?;
+        let path = child.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        out.push(path.clone());
+        if child.file_type()?.is_dir() {
+            walk(&path, skip, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// `path`'s name in the archive: under `name/`, with `/` separators.
+fn entry_name(root: &Path, name: &str, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+    format!("{name}/{rel}")
+}
+
+fn write_tar(
+    out: &Path,
+    root: &Path,
+    name: &str,
+    entries: &[PathBuf],
+    time: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    let gz = GzEncoder::new(File::create(out)?, Compression::default());
+    let mut tar = tar::Builder::new(gz);
+    let mode = match time {
+        Some(_) => tar::HeaderMode::Deterministic,
+        None => tar::HeaderMode::Complete,
+    };
+    for path in entries {
+        check_interrupt()?;
+        let meta = fs::symlink_metadata(path)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata_in_mode(&meta, mode);
+        if let Some(time) = time {
+            header.set_mtime(time.timestamp().max(0) as u64);
+        }
+        let entry = entry_name(root, name, path);
+        if meta.is_symlink() {
+            tar.append_link(&mut header, &entry, fs::read_link(path)?)?;
+        } else if meta.is_dir() {
+            tar.append_data(&mut header, format!("{entry}/"), io::empty())?;
+        } else {
+            tar.append_data(&mut header, &entry, File::open(path)?)?;
+        }
+    }
+    tar.into_inner()?.finish()?.sync_all()?;
+    Ok(())
+}
+
+fn write_zip(
+    out: &Path,
+    root: &Path,
+    name: &str,
+    entries: &[PathBuf],
+    time: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    let mut zip = zip::ZipWriter::new(File::create(out)?);
+    for path in entries {
+        check_interrupt()?;
+        let meta = fs::symlink_metadata(path)?;
+        let mtime = time.or_else(|| meta.modified().ok().map(Into::into));
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(mtime.map(zip_time).unwrap_or_default())
+            .unix_permissions(unix_mode(&meta));
+        let entry = entry_name(root, name, path);
+        if meta.is_symlink() {
+            zip.add_symlink(&entry, fs::read_link(path)?.to_string_lossy(), options)?;
+        } else if meta.is_dir() {
+            zip.add_directory(format!("{entry}/"), options)?;
+        } else {
+            zip.start_file(&entry, options)?;
+            io::copy(&mut File::open(path)?, &mut zip)?;
+        }
+    }
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// `time` as a zip timestamp; zip cannot go before 1980, which is where earlier times end up.
+fn zip_time(time: chrono::DateTime<chrono::Utc>) -> zip::DateTime {
+    use chrono::{Datelike, Timelike};
+    let field = |n: u32| n.try_into().unwrap_or(u8::MAX);
+    zip::DateTime::from_date_and_time(
+        time.year().try_into().unwrap_or(0),
+        field(time.month()),
+        field(time.day()),
+        field(time.hour()),
+        field(time.minute()),
+        field(time.second()),
+    )
+    .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn unix_mode(meta: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn unix_mode(meta: &Metadata) -> u32 {
+    match meta.is_dir() {
+        true => 0o755,
+        false => 0o644,
+    }
+}
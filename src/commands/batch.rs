@@ -0,0 +1,457 @@
+//! `py-proj batch SPEC`: scaffold many projects from one TOML or YAML spec, e.g. a room
+//! full of identical workshop projects. Each `[[project]]` entry layers its own options
+//! over `[defaults]`; the run ends with a per-project result table.
+
+use anyhow::{bail, Context, Result};
+use minijinja::value::{Serde, Value};
+use owo_colors::OwoColorize;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pyproj::config::Config;
+use pyproj::profile::Profile;
+use pyproj::python::{detect_python, PythonVersion};
+use pyproj::scaffold::{DepUpdates, MatrixRunner};
+use pyproj::util::{canonicalize_lenient, interrupted, run_streaming, uv_bin};
+use pyproj::{events, CreateOptions, Event, PlannedAction, ScaffoldPlan, ScaffoldPlanBuilder};
+
+pub struct BatchOptions {
+    /// How many projects are scaffolded at once (1: one after another, with full output).
+    pub parallel: usize,
+    /// Stop starting projects after the first failure.
+    pub fail_fast: bool,
+    pub skip_uv: bool,
+    pub offline: bool,
+    pub verbose: bool,
+}
+
+/// What `[defaults]` and each `[[project]]` entry may set; every key mirrors a create
+/// flag. An entry's value replaces the default, except `vars`, which are merged.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProjectOptions {
+    python: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    email: Option<String>,
+    repo_url: Option<String>,
+    citation: Option<bool>,
+    template: Option<Profile>,
+    entry_point: Option<String>,
+    modules: Option<Vec<String>>,
+    python_matrix: Option<Vec<String>>,
+    matrix_runner: Option<MatrixRunner>,
+    dep_updates: Option<DepUpdates>,
+    tools_global: Option<bool>,
+    license_header: Option<String>,
+    jupytext: Option<bool>,
+    exclude: Option<Vec<String>>,
+    gitignore_extra: Option<Vec<String>>,
+    post_hooks: Option<Vec<String>>,
+    /// Relative to the spec file.
+    templates_dir: Option<PathBuf>,
+    vars: BTreeMap<String, toml::Value>,
+}
+
+impl ProjectOptions {
+    /// `self` with `defaults` filling whatever it leaves unset.
+    fn over(mut self, defaults: &ProjectOptions) -> ProjectOptions {
+        macro_rules! inherit {
+            ($($field:ident),*) => {
+                $(if self.$field.is_none() {
+                    self.$field = defaults.$field.clone();
+                })*
+            };
+        }
+        inherit!(
+            python, description, author, email, repo_url, citation, template, entry_point, modules,
+            python_matrix, matrix_runner, dep_updates, tools_global, license_header, jupytext,
+            exclude, gitignore_extra, post_hooks, templates_dir
+        );
+        for (key, value) in &defaults.vars {
+            self.vars.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        self
+    }
+}
+
+/// A loaded spec: its entries, and whether it asks to stop at the first failure
+/// (`fail_fast = true`, which `--keep-going` overrides).
+#[derive(Debug)]
+pub struct Spec {
+    pub entries: Vec<Entry>,
+    pub fail_fast: bool,
+}
+
+/// One `[[project]]` entry, with the defaults applied.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub root: PathBuf,
+    python: PythonVersion,
+    options: ProjectOptions,
+}
+
+/// How one entry of the batch went.
+#[derive(Debug)]
+pub enum Status {
+    Created { summary: String },
+    Failed { error: String },
+    /// Left out after an earlier failure under `--fail-fast`.
+    NotRun,
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub name: String,
+    pub root: PathBuf,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+/// Read and validate `spec`: a `[defaults]` table and one `[[project]]` per project,
+/// each with a `name` and an optional `outdir` (relative to the spec file; default
+/// `<spec dir>/<name>`), and optionally `fail_fast`. A `.yaml`/`.yml` spec has the same
+/// keys, with `project` a list of mappings. Errors name the entry and the field they
+/// are about.
+pub fn load(spec: &Path, skip_uv: bool, offline: bool) -> Result<Spec> {
+    let text = fs::read_to_string(spec).with_context(|| format!("failed to read {}", spec.display()))?;
+    let mut doc: toml::Table = match spec.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
+        true => serde_yaml::from_str(&text).with_context(|| format!("invalid YAML in {}", spec.display()))?,
+        false => toml::from_str(&text).with_context(|| format!("invalid TOML in {}", spec.display()))?,
+    };
+    let base = spec.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let base = std::path::absolute(base)?;
+
+    let defaults = match doc.remove("defaults") {
+        Some(toml::Value::Table(table)) => options(table, "[defaults]")?,
+        Some(_) => bail!("`defaults` in {} must be a table", spec.display()),
+        None => ProjectOptions::default(),
+    };
+    let projects = match doc.remove("project") {
+        Some(toml::Value::Array(projects)) => projects,
+        Some(_) => bail!("`project` in {} must be an array of tables ([[project]])", spec.display()),
+        None => bail!("{} lists no projects; add a [[project]] entry with a `name`", spec.display()),
+    };
+    let fail_fast = match doc.remove("fail_fast") {
+        Some(toml::Value::Boolean(fail_fast)) => fail_fast,
+        Some(_) => bail!("`fail_fast` in {} must be true or false", spec.display()),
+        None => false,
+    };
+    if let Some(key) = doc.keys().next() {
+        bail!("unknown key `{key}` in {}; expected `defaults`, `project`, and `fail_fast`", spec.display());
+    }
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for (i, project) in projects.into_iter().enumerate() {
+        let toml::Value::Table(mut table) = project else {
+            bail!("project #{} must be a table", i + 1);
+        };
+        let name = match table.remove("name") {
+            Some(toml::Value::String(name)) => name,
+            Some(_) => bail!("project #{}: field `name` must be a string", i + 1),
+            None => bail!("project #{}: missing field `name`", i + 1),
+        };
+        let label = format!("project #{} (`{name}`)", i + 1);
+        let root = match table.remove("outdir") {
+            Some(toml::Value::String(dir)) => base.join(dir),
+            Some(_) => bail!("{label}: field `outdir` must be a string"),
+            None => base.join(&name),
+        };
+        let mut options = options(table, &label)?.over(&defaults);
+        options.templates_dir = options.templates_dir.map(|dir| base.join(dir));
+        let python = match &options.python {
+            Some(python) => PythonVersion::from_str(python)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("{label}: field `python`"))?,
+            None => detect_python(&base).0,
+        };
+        let python = match skip_uv {
+            true => python,
+            false => python.resolve(offline),
+        };
+        let root = canonicalize_lenient(&root);
+        if let Some(other) = entries.iter().find(|e| e.root == root) {
+            bail!("{label}: outdir {} is already used by `{}`", root.display(), other.name);
+        }
+        entries.push(Entry { name, root, python, options });
+    }
+    Ok(Spec { entries, fail_fast })
+}
+
+/// Deserialize one entry's options; on failure, name the first field that does not
+/// parse on its own.
+fn options(table: toml::Table, label: &str) -> Result<ProjectOptions> {
+    ProjectOptions::deserialize(toml::Value::Table(table.clone())).map_err(|e| {
+        let field = table.iter().find(|(key, value)| {
+            let one = toml::Table::from_iter([(key.to_string(), (*value).clone())]);
+            ProjectOptions::deserialize(toml::Value::Table(one)).is_err()
+        });
+        let message = e.message().to_string();
+        match field {
+            Some((key, _)) => anyhow::anyhow!("{label}: field `{key}`: {message}"),
+            None => anyhow::anyhow!("{label}: {message}"),
+        }
+    })
+}
+
+/// The plan builder for `entry`, without an event handler.
+fn builder(entry: &Entry, config: &Config, opts: &BatchOptions) -> ScaffoldPlanBuilder {
+    let o = &entry.options;
+    let mut vars: pyproj::templates::Vars = config
+        .vars
+        .iter()
+        .map(|(k, v)| (k.clone(), Value::from(Serde(v.clone()))))
+        .collect();
+    vars.extend(o.vars.iter().map(|(k, v)| (k.clone(), Value::from(Serde(v.clone())))));
+    let mut b = ScaffoldPlan::builder(&entry.name)
+        .root(&entry.root)
+        .python(entry.python.to_string())
+        .vars(vars)
+        .skip_uv(opts.skip_uv)
+        .offline(opts.offline)
+        .citation(o.citation.unwrap_or(false))
+        .tools_global(o.tools_global.unwrap_or(false))
+        .jupytext(o.jupytext.unwrap_or(false))
+        .org_defaults(&config.org);
+    if let Some(description) = &o.description {
+        b = b.description(description);
+    }
+    if let Some(author) = &o.author {
+        b = b.author(author);
+    }
+    if let Some(email) = &o.email {
+        b = b.email(email);
+    }
+    if let Some(url) = &o.repo_url {
+        b = b.repo_url(url);
+    }
+    if let Some(name) = &o.entry_point {
+        b = b.entry_point(name);
+    }
+    if let Some(spdx) = &o.license_header {
+        b = b.license_header(spdx);
+    }
+    if let Some(profile) = o.template {
+        b = b.profile(profile);
+    }
+    for module in o.modules.iter().flatten() {
+        b = b.module(module.trim());
+    }
+    for version in o.python_matrix.iter().flatten() {
+        b = b.matrix_python(version);
+    }
+    if let Some(runner) = o.matrix_runner {
+        b = b.matrix_runner(runner);
+    }
+    if let Some(bot) = o.dep_updates {
+        b = b.dep_updates(bot);
+    }
+    for pattern in o.exclude.iter().flatten() {
+        b = b.exclude(pattern);
+    }
+    for pattern in config.gitignore_extra.iter().chain(o.gitignore_extra.iter().flatten()) {
+        b = b.gitignore_extra(pattern);
+    }
+    for hook in config.post_hooks.iter().chain(o.post_hooks.iter().flatten()) {
+        b = b.post_hook(hook);
+    }
+    if let Some(dir) = o.templates_dir.as_ref().or(config.templates_dir.as_ref()) {
+        b = b.templates_dir(dir);
+    }
+    b
+}
+
+/// Build every plan (rendering its templates) so a bad entry fails before anything
+/// is written, and return what each would write and run.
+pub fn validate(entries: &[Entry], config: &Config, opts: &BatchOptions) -> Result<Vec<(usize, usize)>> {
+    let mut counts = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let label = format!("project #{} (`{}`)", i + 1, entry.name);
+        let plan = builder(entry, config, opts)
+            .on_event(events::silent())
+            .build()
+            .with_context(|| label.clone())?;
+        let actions = plan.plan(true).with_context(|| label.clone())?;
+        let files = actions
+            .iter()
+            .filter(|a| matches!(a, PlannedAction::WriteFile { .. } | PlannedAction::WriteCodeWorkspace { .. }))
+            .count();
+        let commands = actions.iter().filter(|a| matches!(a, PlannedAction::RunCommand { .. })).count();
+        counts.push((files, commands));
+    }
+    Ok(counts)
+}
+
+/// `--dry-run`: one line per project.
+pub fn print_plan(entries: &[Entry], counts: &[(usize, usize)]) {
+    let name_w = entries.iter().map(|e| e.name.len()).max().unwrap_or(0).max(4);
+    println!(
+        "  {}",
+        format!("{:name_w$}  {:8}  {:8}  {:>5}  {:>4}  ROOT", "NAME", "PYTHON", "TEMPLATE", "FILES", "RUNS").bold()
+    );
+    for (entry, (files, commands)) in entries.iter().zip(counts) {
+        let template = entry.options.template.unwrap_or_default();
+        let name = format!("{:name_w$}", entry.name);
+        println!(
+            "  {}  {:8}  {:8}  {files:>5}  {commands:>4}  {}",
+            name.blue().bold(),
+            entry.python.to_string(),
+            template.name(),
+            entry.root.display().to_string().dimmed()
+        );
+    }
+}
+
+/// Install each distinct Python the batch needs once, one at a time, before any
+/// project runs: the installs are shared, so concurrent projects must not race on them.
+pub fn install_pythons(entries: &[Entry], opts: &BatchOptions) -> Result<()> {
+    if opts.skip_uv || opts.offline {
+        return Ok(());
+    }
+    let versions: BTreeSet<String> = entries.iter().map(|e| e.python.to_string()).collect();
+    for version in versions {
+        println!("  {} {}", "python".cyan(), format!("uv python install {version}").dimmed());
+        let verbose = opts.verbose;
+        run_streaming(&uv_bin(), &["python", "install", &version], &std::env::temp_dir(), |line| {
+            if verbose {
+                println!("    {}", line.dimmed());
+            }
+        })
+        .with_context(|| format!("failed to install Python {version}"))?;
+    }
+    Ok(())
+}
+
+/// Scaffold every entry, `opts.parallel` at a time. Failures are rolled back and
+/// reported in the outcome rather than returned.
+pub fn run(entries: &[Entry], config: &Config, opts: &BatchOptions) -> Vec<Outcome> {
+    if opts.parallel <= 1 {
+        let mut outcomes = Vec::new();
+        let mut failed = false;
+        for (i, entry) in entries.iter().enumerate() {
+            if (failed && opts.fail_fast) || interrupted() {
+                outcomes.push(outcome(entry, Status::NotRun, Duration::ZERO));
+                continue;
+            }
+            println!(
+                "{} {} {}",
+                ">>".cyan().bold(),
+                format!("Create project {}", entry.name).bold(),
+                format!("({}/{})", i + 1, entries.len()).dimmed()
+            );
+            println!("  {} {}", "Root:".dimmed(), entry.root.display().to_string().blue());
+            let handler = super::printer(opts.verbose);
+            let started = Instant::now();
+            let status = scaffold(builder(entry, config, opts).on_event(handler));
+            failed |= matches!(status, Status::Failed { .. });
+            outcomes.push(outcome(entry, status, started.elapsed()));
+        }
+        return outcomes;
+    }
+
+    // Workers build their own plans: a plan and its event handler stay on one thread.
+    let next = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..opts.parallel.min(entries.len()) {
+            let (next, stop, tx) = (Arc::clone(&next), Arc::clone(&stop), tx.clone());
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(entry) = entries.get(i) else {
+                    break;
+                };
+                if stop.load(Ordering::SeqCst) || interrupted() {
+                    let _ = tx.send((i, Status::NotRun, Duration::ZERO));
+                    continue;
+                }
+                let name = entry.name.clone();
+                let handler = move |event: &Event<'_>| {
+                    if let Event::Warning { message } = event {
+                        println!("  {} {} {}", "WARN".yellow().bold(), format!("[{name}]").dimmed(), message.yellow());
+                    }
+                };
+                let started = Instant::now();
+                let status = scaffold(builder(entry, config, opts).on_event(handler));
+                if matches!(status, Status::Failed { .. }) && opts.fail_fast {
+                    stop.store(true, Ordering::SeqCst);
+                }
+                let _ = tx.send((i, status, started.elapsed()));
+            });
+        }
+        drop(tx);
+        let mut results: Vec<Option<(Status, Duration)>> = entries.iter().map(|_| None).collect();
+        for (i, status, elapsed) in rx {
+            let mark = match status {
+                Status::Created { .. } => "ok".green().to_string(),
+                Status::Failed { .. } => "FAILED".red().to_string(),
+                Status::NotRun => "not run".dimmed().to_string(),
+            };
+            println!("  {mark} {}", entries[i].name);
+            results[i] = Some((status, elapsed));
+        }
+        entries
+            .iter()
+            .zip(results)
+            .map(|(entry, result)| {
+                let (status, elapsed) = result.unwrap_or((Status::NotRun, Duration::ZERO));
+                outcome(entry, status, elapsed)
+            })
+            .collect()
+    })
+}
+
+fn scaffold(builder: ScaffoldPlanBuilder) -> Status {
+    let result = builder
+        .build()
+        .and_then(|plan| pyproj::create(&plan, &CreateOptions::default()));
+    match result {
+        Ok(summary) => Status::Created { summary: summary.line() },
+        Err(e) => Status::Failed { error: format!("{e:#}") },
+    }
+}
+
+fn outcome(entry: &Entry, status: Status, elapsed: Duration) -> Outcome {
+    Outcome {
+        name: entry.name.clone(),
+        root: entry.root.clone(),
+        status,
+        elapsed,
+    }
+}
+
+/// The closing table: one row per project, failures with the first line of their error.
+pub fn print_table(outcomes: &[Outcome]) {
+    let name_w = outcomes.iter().map(|o| o.name.len()).max().unwrap_or(0).max(4);
+    println!("  {}", format!("{:name_w$}  {:7}  {:>7}  DETAIL", "NAME", "STATUS", "TIME").bold());
+    for o in outcomes {
+        let (status, detail) = match &o.status {
+            Status::Created { summary } => ("ok".green().to_string(), summary.dimmed().to_string()),
+            Status::Failed { error } => (
+                "FAILED".red().bold().to_string(),
+                error.lines().next().unwrap_or_default().red().to_string(),
+            ),
+            Status::NotRun => ("skipped".dimmed().to_string(), "not run after an earlier failure".dimmed().to_string()),
+        };
+        // Pad by the plain width; the colour codes would throw `{:7}` off.
+        let plain = match o.status {
+            Status::Created { .. } => 2,
+            Status::Failed { .. } => 6,
+            Status::NotRun => 7,
+        };
+        println!(
+            "  {:name_w$}  {status}{}  {:>7}  {detail}",
+            o.name,
+            " ".repeat(7 - plain),
+            format!("{:.1}s", o.elapsed.as_secs_f64())
+        );
+    }
+}
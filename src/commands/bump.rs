@@ -0,0 +1,189 @@
+//! `py-proj bump`: raise `[project] version` in pyproject.toml and turn the Unreleased
+//! changelog entries into a dated release, optionally committing and tagging it.
+
+use anyhow::{bail, Context, Result};
+use owo_colors::OwoColorize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use toml_edit::DocumentMut;
+
+use super::diff::print_unified;
+use super::rename::has_uncommitted_changes;
+use pyproj::util::{run_output, write};
+
+/// Which part of `MAJOR.MINOR.PATCH` to raise; the parts after it reset to 0.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Part {
+    Patch,
+    Minor,
+    Major,
+}
+
+pub struct BumpOptions {
+    pub part: Part,
+    /// Commit pyproject.toml and CHANGELOG.md and tag the commit `v<version>`.
+    pub git_tag: bool,
+    /// Print the new version and the edits without writing anything.
+    pub dry_run: bool,
+}
+
+/// A `MAJOR.MINOR.PATCH` version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Version(u64, u64, u64);
+
+impl Version {
+    pub fn parse(s: &str) -> Result<Version> {
+        let parts: Vec<&str> = s.split('.').collect();
+        let numbers: Option<Vec<u64>> = parts
+            .iter()
+            .map(|p| p.chars().all(|c| c.is_ascii_digit()).then(|| p.parse().ok()).flatten())
+            .collect();
+        match numbers.as_deref() {
+            Some(&[major, minor, patch]) => Ok(Version(major, minor, patch)),
+            _ => bail!("`{s}` is not a MAJOR.MINOR.PATCH version; bump it by hand"),
+        }
+    }
+
+    pub fn bump(self, part: Part) -> Version {
+        let Version(major, minor, patch) = self;
+        match part {
+            Part::Patch => Version(major, minor, patch + 1),
+            Part::Minor => Version(major, minor + 1, 0),
+            Part::Major => Version(major + 1, 0, 0),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+pub fn bump_project(root: &Path, opts: &BumpOptions) -> Result<()> {
+    let pyproject = root.join("pyproject.toml");
+    let changelog = root.join("CHANGELOG.md");
+    let old_toml = fs::read_to_string(&pyproject)
+        .with_context(|| format!("failed to read {}", pyproject.display()))?;
+    let old_log = fs::read_to_string(&changelog).with_context(|| {
+        format!(
+            "failed to read {}; create one with an `## [Unreleased]` section \
+             (`py-proj templates show CHANGELOG.md > CHANGELOG.md`)",
+            changelog.display()
+        )
+    })?;
+    if opts.git_tag {
+        check_git_clean(root)?;
+    }
+
+    let mut doc: DocumentMut = old_toml.parse().context("invalid TOML in pyproject.toml")?;
+    let version = doc
+        .get_mut("project")
+        .and_then(|p| p.get_mut("version"))
+        .context("pyproject.toml has no [project] version to bump (is it dynamic?)")?;
+    let current = Version::parse(version.as_str().context("[project] version is not a string")?)?;
+    let next = current.bump(opts.part);
+    // Keep any trailing comment on the line.
+    let decor = version.as_value().map(|v| v.decor().clone());
+    *version = toml_edit::value(next.to_string());
+    if let (Some(decor), Some(value)) = (decor, version.as_value_mut()) {
+        *value.decor_mut() = decor;
+    }
+    let new_toml = doc.to_string();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let (new_log, entries) = release_changelog(&old_log, &next.to_string(), &today)?;
+
+    println!("  {} {} → {}", "Version:".dimmed(), current.dimmed(), next.blue().bold());
+    if entries == 0 {
+        println!(
+            "  {} {}",
+            "WARN".yellow().bold(),
+            "the Unreleased section is empty; the release heading has no entries".yellow()
+        );
+    }
+    let edits = [("pyproject.toml", &old_toml, &new_toml), ("CHANGELOG.md", &old_log, &new_log)];
+    if opts.dry_run {
+        for (rel, old, new) in edits {
+            print_unified(rel, old, new);
+        }
+        if opts.git_tag {
+            println!("  {} {}", "git".cyan(), format!("commit and tag v{next}").dimmed());
+        }
+        return Ok(());
+    }
+
+    // The manifest hashes stay as generated: the version is not a scaffold option, so
+    // `upgrade` should treat the bumped pyproject.toml as edited rather than reset it.
+    for (rel, _, new) in edits {
+        println!("  {} {}", "modify".green(), rel);
+        write(root.join(rel), new)?;
+    }
+    if opts.git_tag {
+        let tag = format!("v{next}");
+        run_output("git", &["add", "--", "pyproject.toml", "CHANGELOG.md"], root)?;
+        run_output("git", &["commit", "-m", &format!("Release {next}")], root)?;
+        run_output("git", &["tag", "-a", &tag, "-m", &format!("Release {next}")], root)?;
+        println!("  {} {}", "git".cyan(), format!("committed and tagged {tag}").dimmed());
+    }
+    Ok(())
+}
+
+/// `--git-tag` commits on top of the current state, so that state must be committed.
+fn check_git_clean(root: &Path) -> Result<()> {
+    if which::which("git").is_err() {
+        return Err(pyproj::Error::MissingTool {
+            tool: "git".to_string(),
+        }
+        .into());
+    }
+    if run_output("git", &["rev-parse", "--git-dir"], root).is_err() {
+        bail!("{} is not in a git repository; drop --git-tag", root.display());
+    }
+    if has_uncommitted_changes(root)? {
+        bail!(
+            "{} has uncommitted changes; commit or stash them before --git-tag",
+            root.display()
+        );
+    }
+    Ok(())
+}
+
+/// Move the entries below `## [Unreleased]` under a new `## [version] - date` heading,
+/// leaving an empty Unreleased section. Returns the new text and how many entry lines moved.
+fn release_changelog(text: &str, version: &str, date: &str) -> Result<(String, usize)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let is_unreleased = |line: &str| {
+        let heading = line.trim().to_ascii_lowercase();
+        heading == "## [unreleased]" || heading == "## unreleased"
+    };
+    let start = lines
+        .iter()
+        .position(|l| is_unreleased(l))
+        .context("CHANGELOG.md has no `## [Unreleased]` section")?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with("## ") || l.starts_with("[unreleased]:"))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let body: Vec<&str> = lines[start + 1..end].to_vec();
+    let first = body.iter().position(|l| !l.trim().is_empty());
+    let last = body.iter().rposition(|l| !l.trim().is_empty());
+    let entries = match (first, last) {
+        (Some(first), Some(last)) => &body[first..=last],
+        _ => &[][..],
+    };
+
+    let mut out: Vec<String> = lines[..=start].iter().map(|l| l.to_string()).collect();
+    out.push(String::new());
+    out.push(format!("## [{version}] - {date}"));
+    if !entries.is_empty() {
+        out.push(String::new());
+        out.extend(entries.iter().map(|l| l.to_string()));
+    }
+    if end < lines.len() {
+        out.push(String::new());
+        out.extend(lines[end..].iter().map(|l| l.to_string()));
+    }
+    let moved = entries.iter().filter(|l| !l.trim().is_empty()).count();
+    Ok((out.join("\n") + "\n", moved))
+}
@@ -0,0 +1,544 @@
+//! `py-proj check-vscode`: check that the `.vscode` files point at things that exist, so
+//! "it doesn't work in VS Code" can be answered before opening the editor.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::doctor::Check;
+use pyproj::manifest::Manifest;
+use pyproj::scaffold::{DepStyle, GLOBAL_TOOLS};
+use pyproj::util::{home_dir, run_output, uv_bin, uv_install_hint, venv_dir_name};
+
+/// VS Code extensions whose `<prefix>.path` and `<prefix>.importStrategy` settings name
+/// a tool, and the module that tool is imported as.
+const TOOL_EXTENSIONS: &[(&str, &str, &str)] = &[
+    ("black-formatter", "black", "ms-python.black-formatter"),
+    ("ruff", "ruff", "charliermarsh.ruff"),
+    ("mypy-type-checker", "mypy", "ms-python.mypy-type-checker"),
+    ("pylint", "pylint", "ms-python.pylint"),
+    ("flake8", "flake8", "ms-python.flake8"),
+    ("isort", "isort", "ms-python.isort"),
+];
+
+/// Run every check against the `.vscode` directory under `root`.
+pub fn diagnose(root: &Path) -> Result<Vec<Check>> {
+    // `${workspaceFolder}` is absolute, and relative paths are taken from it.
+    let root = &fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let manifest = Manifest::load(root)?;
+    let vscode = root.join(".vscode");
+    let mut checks = Vec::new();
+    if !vscode.is_dir() {
+        checks.push(Check::fail(
+            "settings.json",
+            "no .vscode directory",
+            "regenerate it with `py-proj upgrade`, or open the project root rather than a folder inside it",
+        ));
+        return Ok(checks);
+    }
+
+    let settings = load(&vscode, "settings.json", &mut checks);
+    let launch = load(&vscode, "launch.json", &mut checks);
+    let tasks = load(&vscode, "tasks.json", &mut checks);
+    let extensions = load(&vscode, "extensions.json", &mut checks);
+
+    let interpreter = settings.as_ref().and_then(|s| check_interpreter(root, s, &mut checks));
+    if let Some(settings) = &settings {
+        check_tools(root, settings, interpreter.as_deref(), manifest.as_ref(), &mut checks);
+    }
+    check_pyright(root, &mut checks);
+    if let Some(launch) = &launch {
+        check_launch(root, launch, interpreter.as_deref(), &mut checks);
+    }
+    if let Some(tasks) = &tasks {
+        check_tasks(root, tasks, interpreter.as_deref(), &mut checks);
+    }
+    let wanted = wanted_extensions(settings.as_ref(), launch.as_ref(), extensions.as_ref());
+    checks.push(check_extensions(&wanted));
+    Ok(checks)
+}
+
+/// Parse `.vscode/<name>`, recording a failure when it does not parse. A missing
+/// `extensions.json` or `tasks.json` is fine; a missing settings or launch file is not.
+fn load(vscode: &Path, name: &'static str, checks: &mut Vec<Check>) -> Option<Value> {
+    let Ok(text) = fs::read_to_string(vscode.join(name)) else {
+        if matches!(name, "settings.json" | "launch.json") {
+            checks.push(Check::warn(name, "not found", "regenerate it with `py-proj upgrade`"));
+        }
+        return None;
+    };
+    match serde_json::from_str(&strip_jsonc(&text)) {
+        Ok(value) => {
+            checks.push(Check::pass(name, "parses"));
+            Some(value)
+        }
+        Err(e) => {
+            checks.push(Check::fail(name, e.to_string(), "fix the JSON syntax error"));
+            None
+        }
+    }
+}
+
+/// `text` with the comments and trailing commas VS Code allows in its JSON files removed.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    // Trailing commas, now that no comment can stand between one and its bracket.
+    let mut cleaned = String::with_capacity(out.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in out.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' && matches!(out[i + 1..].trim_start().chars().next(), Some('}' | ']')) {
+            continue;
+        }
+        cleaned.push(c);
+    }
+    cleaned
+}
+
+/// `path` with the variables VS Code substitutes in settings filled in; `None` when it
+/// uses one that depends on the editor's state (`${file}`, `${input:...}`).
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let mut out = String::new();
+    let mut rest = path;
+    if let Some(tail) = rest.strip_prefix('~').filter(|t| t.is_empty() || t.starts_with(['/', '\\'])) {
+        out.push_str(&home_dir()?.to_string_lossy());
+        rest = tail;
+    }
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let end = start + rest[start..].find('}')?;
+        let name = &rest[start + 2..end];
+        match name {
+            "workspaceFolder" | "workspaceRoot" => out.push_str(&root.to_string_lossy()),
+            "userHome" => out.push_str(&home_dir()?.to_string_lossy()),
+            "pathSeparator" | "/" => out.push(std::path::MAIN_SEPARATOR),
+            _ => out.push_str(&env::var(name.strip_prefix("env:")?).unwrap_or_default()),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    let path = PathBuf::from(out);
+    Some(if path.is_relative() { root.join(path) } else { path })
+}
+
+/// The command that builds the venv, for hints.
+fn venv_hint(root: &Path) -> String {
+    let python = fs::read_to_string(root.join(".python-version")).unwrap_or_default();
+    match python.trim() {
+        "" => format!("uv venv {}", venv_dir_name(root)),
+        python => format!("uv venv --python {python} {}", venv_dir_name(root)),
+    }
+}
+
+/// The interpreter path VS Code uses for this platform and venv.
+fn interpreter_setting(root: &Path) -> String {
+    let dir = venv_dir_name(root);
+    match cfg!(windows) {
+        true => format!("${{workspaceFolder}}\\{dir}\\Scripts\\python.exe"),
+        false => format!("${{workspaceFolder}}/{dir}/bin/python"),
+    }
+}
+
+/// `python.defaultInterpreterPath` names an interpreter that exists and runs. Returns
+/// its resolved path when it does.
+fn check_interpreter(root: &Path, settings: &Value, checks: &mut Vec<Check>) -> Option<PathBuf> {
+    let name = "interpreter";
+    let want = interpreter_setting(root);
+    let Some(setting) = settings["python.defaultInterpreterPath"].as_str() else {
+        checks.push(Check::warn(
+            name,
+            "python.defaultInterpreterPath is not set; VS Code will pick one itself",
+            format!("set it to {want}"),
+        ));
+        return None;
+    };
+    let unix_style = setting.contains("/bin/python");
+    let windows_style = setting.contains("Scripts") && setting.ends_with(".exe");
+    if (cfg!(windows) && unix_style) || (!cfg!(windows) && windows_style) {
+        let (kind, here) = if unix_style { ("Unix", "Windows") } else { ("Windows", "Unix") };
+        checks.push(Check::fail(
+            name,
+            format!("{setting} is a {kind} venv path, but this is {here}"),
+            format!("set python.defaultInterpreterPath to {want}"),
+        ));
+        return None;
+    }
+    let Some(path) = resolve(root, setting) else {
+        checks.push(Check::warn(
+            name,
+            format!("{setting} uses a variable only VS Code can resolve"),
+            format!("set python.defaultInterpreterPath to {want}"),
+        ));
+        return None;
+    };
+    let dir = venv_dir_name(root);
+    if !path.exists() {
+        let venv_missing = !root.join(&dir).join("pyvenv.cfg").is_file();
+        let (detail, hint) = match (venv_missing, path.starts_with(root.join(&dir))) {
+            (true, _) => (format!("{setting} does not exist: {dir} has not been created"), venv_hint(root)),
+            (false, true) => (
+                format!("{setting} does not exist (the venv's Python was deleted?)"),
+                format!("py-proj --create_project --recreate-venv, or remove {dir} and run {}", venv_hint(root)),
+            ),
+            (false, false) => (
+                format!("{setting} does not exist, and it is not in {dir}"),
+                format!("set python.defaultInterpreterPath to {want}"),
+            ),
+        };
+        checks.push(Check::fail(name, detail, hint));
+        return None;
+    }
+    if !is_executable(&path) {
+        checks.push(Check::fail(
+            name,
+            format!("{} is not executable", path.display()),
+            format!("chmod +x {}", path.display()),
+        ));
+        return None;
+    }
+    if !path.starts_with(root.join(&dir)) {
+        checks.push(Check::warn(
+            name,
+            format!("{setting} is outside the project's {dir}"),
+            format!("set python.defaultInterpreterPath to {want}"),
+        ));
+        return Some(path);
+    }
+    checks.push(Check::pass(name, setting));
+    Some(path)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The formatter and linter extensions find the tools their settings point at: each
+/// `<extension>.path` exists, and with `importStrategy: fromEnvironment` the tool
+/// imports in the venv (or, for the `--tools-global` ones, is on PATH).
+fn check_tools(
+    root: &Path,
+    settings: &Value,
+    interpreter: Option<&Path>,
+    manifest: Option<&Manifest>,
+    checks: &mut Vec<Check>,
+) {
+    let name = "tools";
+    let install = match manifest.and_then(|m| m.options.dep_style).unwrap_or_default() {
+        DepStyle::Extras => "uv pip install -e \".[dev]\"",
+        DepStyle::Groups => "uv pip install -e . --group dev",
+    };
+    let tools_global = manifest.is_some_and(|m| m.options.tools_global);
+    let mut imports = Vec::new();
+    for &(prefix, module, _) in TOOL_EXTENSIONS {
+        let paths = match &settings[format!("{prefix}.path")] {
+            Value::String(p) => vec![p.as_str()],
+            Value::Array(ps) => ps.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        // The first entry is the executable; the rest are arguments for it.
+        if let Some(setting) = paths.first() {
+            match resolve(root, setting) {
+                Some(path) if !path.exists() => checks.push(Check::fail(
+                    name,
+                    format!("{prefix}.path points at {setting}, which does not exist"),
+                    install.to_string(),
+                )),
+                Some(_) => checks.push(Check::pass(name, format!("{prefix}.path {setting}"))),
+                None => {}
+            }
+        }
+        if settings[format!("{prefix}.importStrategy")].as_str() != Some("fromEnvironment") {
+            continue;
+        }
+        if tools_global && GLOBAL_TOOLS.contains(&module) {
+            checks.push(match which::which(module) {
+                Ok(path) => Check::pass(name, format!("{module} is a uv tool at {}", path.display())),
+                Err(_) => Check::fail(
+                    name,
+                    format!("{prefix}.importStrategy is fromEnvironment but {module} is not on PATH"),
+                    format!("uv tool install {module}"),
+                ),
+            });
+            continue;
+        }
+        imports.push((prefix, module));
+    }
+    if imports.is_empty() {
+        return;
+    }
+    let Some(python) = interpreter else {
+        checks.push(Check::warn(
+            name,
+            format!("cannot check that the venv can import {} without a working interpreter", module_list(&imports)),
+            "fix the interpreter first",
+        ));
+        return;
+    };
+    let modules: Vec<&str> = imports.iter().map(|(_, m)| *m).collect();
+    let found = match importable(python, root, &modules) {
+        Ok(out) => out,
+        Err(e) => {
+            checks.push(Check::fail(
+                name,
+                format!("{} does not run: {e:#}", python.display()),
+                format!("py-proj --create_project --recreate-venv, or remove {} and run {}", venv_dir_name(root), venv_hint(root)),
+            ));
+            return;
+        }
+    };
+    for (prefix, module) in imports {
+        checks.push(match found.contains(module) {
+            true => Check::pass(name, format!("{module} imports in the venv")),
+            false => Check::fail(
+                name,
+                format!("{prefix}.importStrategy is fromEnvironment but {module} does not import in the venv"),
+                install.to_string(),
+            ),
+        });
+    }
+}
+
+/// Which of `modules` the interpreter `python` can import, asked in one run.
+fn importable(python: &Path, root: &Path, modules: &[&str]) -> Result<BTreeSet<String>> {
+    let script = format!(
+        "import importlib.util as u\nfor m in {modules:?}:\n    if u.find_spec(m) is not None: print(m)"
+    );
+    let out = run_output(&python.to_string_lossy(), &["-c", &script], root)?;
+    Ok(out.lines().map(str::to_string).collect())
+}
+
+fn module_list(imports: &[(&str, &str)]) -> String {
+    imports.iter().map(|(_, m)| *m).collect::<Vec<_>>().join(", ")
+}
+
+/// Pylance and pyright read the venv from `pyrightconfig.json`; it must be the real one.
+fn check_pyright(root: &Path, checks: &mut Vec<Check>) {
+    let name = "pyright";
+    let Ok(text) = fs::read_to_string(root.join("pyrightconfig.json")) else {
+        return;
+    };
+    let config: Value = match serde_json::from_str(&strip_jsonc(&text)) {
+        Ok(v) => v,
+        Err(e) => {
+            checks.push(Check::fail(name, format!("pyrightconfig.json: {e}"), "fix the JSON syntax error"));
+            return;
+        }
+    };
+    let Some(venv) = config["venv"].as_str() else {
+        return;
+    };
+    let venv_path = root.join(config["venvPath"].as_str().unwrap_or(".")).join(venv);
+    let dir = venv_dir_name(root);
+    if !venv_path.join("pyvenv.cfg").is_file() {
+        let hint = match venv == dir {
+            true => venv_hint(root),
+            false => format!("set \"venv\": \"{dir}\" in pyrightconfig.json"),
+        };
+        checks.push(Check::fail(
+            name,
+            format!("pyrightconfig.json names the venv {venv}, which does not exist"),
+            hint,
+        ));
+        return;
+    }
+    checks.push(Check::pass(name, format!("venv {venv}")));
+}
+
+/// Each launch configuration runs a `program` or `module` that exists.
+fn check_launch(root: &Path, launch: &Value, interpreter: Option<&Path>, checks: &mut Vec<Check>) {
+    let name = "launch.json";
+    let configs = launch["configurations"].as_array().map(Vec::as_slice).unwrap_or_default();
+    for config in configs {
+        let label = config["name"].as_str().unwrap_or("(unnamed)");
+        if let Some(program) = config["program"].as_str() {
+            match resolve(root, program) {
+                Some(path) if !path.is_file() => checks.push(Check::fail(
+                    name,
+                    format!("\"{label}\" runs {program}, which does not exist"),
+                    "point \"program\" at an existing file, or remove the configuration",
+                )),
+                Some(_) => checks.push(Check::pass(name, format!("\"{label}\" runs {program}"))),
+                None => checks.push(Check::pass(name, format!("\"{label}\" runs {program} (set by VS Code)"))),
+            }
+        }
+        if let Some(module) = config["module"].as_str() {
+            checks.push(check_module(root, interpreter, name, label, module));
+        }
+        if let Some(python) = config["python"].as_str() {
+            if resolve(root, python).is_some_and(|p| !p.exists()) {
+                checks.push(Check::fail(
+                    name,
+                    format!("\"{label}\" uses the interpreter {python}, which does not exist"),
+                    "remove \"python\" to use python.defaultInterpreterPath",
+                ));
+            }
+        }
+    }
+}
+
+/// Each task that runs a module with `python -m` names one that exists.
+fn check_tasks(root: &Path, tasks: &Value, interpreter: Option<&Path>, checks: &mut Vec<Check>) {
+    let name = "tasks.json";
+    let tasks = tasks["tasks"].as_array().map(Vec::as_slice).unwrap_or_default();
+    for task in tasks {
+        let label = task["label"].as_str().unwrap_or("(unnamed)");
+        let Some(command) = task["command"].as_str() else {
+            continue;
+        };
+        let words: Vec<&str> = command.split_whitespace().collect();
+        if words.first() == Some(&"uv") && which::which(uv_bin()).is_err() {
+            checks.push(Check::fail(
+                name,
+                format!("\"{label}\" runs uv, which is not on PATH"),
+                format!("install uv: {}", uv_install_hint()),
+            ));
+        }
+        if let Some(module) = words.windows(2).find(|w| w[0] == "-m").map(|w| w[1]) {
+            checks.push(check_module(root, interpreter, name, label, module));
+        }
+    }
+}
+
+/// `module` resolves from the root or `src/`, the way the generated PYTHONPATH finds it,
+/// or else is installed in the venv (`uvicorn`).
+fn check_module(root: &Path, interpreter: Option<&Path>, name: &'static str, label: &str, module: &str) -> Check {
+    let rel = module.replace('.', "/");
+    let in_project = [root.to_path_buf(), root.join("src")].iter().any(|base| {
+        let path = base.join(&rel);
+        path.with_extension("py").is_file() || path.join("__init__.py").is_file() || path.join("__main__.py").is_file()
+    });
+    if in_project {
+        return Check::pass(name, format!("\"{label}\" runs module {module}"));
+    }
+    let Some(python) = interpreter else {
+        return Check::warn(
+            name,
+            format!("\"{label}\" runs module {module}, which is not in the project, and the venv cannot be asked"),
+            "fix the interpreter first",
+        );
+    };
+    match importable(python, root, &[module]) {
+        Ok(found) if found.contains(module) => Check::pass(name, format!("\"{label}\" runs module {module} from the venv")),
+        _ => Check::fail(
+            name,
+            format!("\"{label}\" runs module {module}, which is neither in the project nor installed in the venv"),
+            format!("create {rel}.py, install the package that provides it, or point the configuration elsewhere"),
+        ),
+    }
+}
+
+/// The extensions the project asks for in `extensions.json`, plus the ones its settings
+/// and launch configurations cannot work without.
+fn wanted_extensions(settings: Option<&Value>, launch: Option<&Value>, extensions: Option<&Value>) -> BTreeSet<String> {
+    let mut wanted = BTreeSet::new();
+    if let Some(ids) = extensions.and_then(|e| e["recommendations"].as_array()) {
+        wanted.extend(ids.iter().filter_map(Value::as_str).map(str::to_lowercase));
+    }
+    if let Some(settings) = settings {
+        if settings.get("python.defaultInterpreterPath").is_some() {
+            wanted.insert("ms-python.python".to_string());
+        }
+        for formatter in [&settings["[python]"]["editor.defaultFormatter"], &settings["notebook.defaultFormatter"]] {
+            if let Some(id) = formatter.as_str() {
+                wanted.insert(id.to_lowercase());
+            }
+        }
+        for &(prefix, _, id) in TOOL_EXTENSIONS {
+            let configured = settings.as_object().is_some_and(|s| s.keys().any(|k| k.starts_with(&format!("{prefix}."))));
+            if configured {
+                wanted.insert(id.to_string());
+            }
+        }
+    }
+    let configs = launch.and_then(|l| l["configurations"].as_array()).map(Vec::as_slice).unwrap_or_default();
+    for kind in configs.iter().filter_map(|c| c["type"].as_str()) {
+        match kind {
+            "debugpy" => wanted.insert("ms-python.debugpy".to_string()),
+            "python" => wanted.insert("ms-python.python".to_string()),
+            _ => false,
+        };
+    }
+    wanted
+}
+
+/// The `wanted` extensions are installed, when the `code` CLI is there to ask.
+fn check_extensions(wanted: &BTreeSet<String>) -> Check {
+    let name = "extensions";
+    if wanted.is_empty() {
+        return Check::pass(name, "none needed");
+    }
+    let listed = which::which("code")
+        .ok()
+        .and_then(|code| run_output(&code.to_string_lossy(), &["--list-extensions"], Path::new(".")).ok());
+    let Some(listed) = listed else {
+        return Check::warn(
+            name,
+            format!("cannot check for {}: the `code` CLI is not on PATH", join(wanted.iter())),
+            "in VS Code, run \"Shell Command: Install 'code' command in PATH\"",
+        );
+    };
+    let installed: BTreeSet<String> = listed.lines().map(|l| l.trim().to_lowercase()).collect();
+    let missing: Vec<&String> = wanted.iter().filter(|id| !installed.contains(*id)).collect();
+    if missing.is_empty() {
+        return Check::pass(name, format!("{} installed", join(wanted.iter())));
+    }
+    let install = missing
+        .iter()
+        .map(|id| format!("code --install-extension {id}"))
+        .collect::<Vec<_>>()
+        .join(" && ");
+    Check::warn(name, format!("not installed: {}", join(missing.into_iter())), install)
+}
+
+fn join<'a>(ids: impl Iterator<Item = &'a String>) -> String {
+    ids.map(String::as_str).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,187 @@
+//! `py-proj --clean_project --all DIR`: clean every project under a directory, with
+//! one confirmation for all of them. A project that fails is reported and skipped.
+
+use anyhow::Result;
+use dialoguer::Confirm;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use super::list;
+use pyproj::util::{human_bytes, interrupted, interruptible};
+use pyproj::CleanOptions;
+
+pub struct CleanAllOptions {
+    pub clean: CleanOptions,
+    /// How many directory levels below the searched directory to look for projects.
+    pub max_depth: usize,
+    /// Globs (relative to the searched directory) of directories not to search.
+    pub exclude: Vec<String>,
+    pub dry_run: bool,
+    /// Go ahead without asking.
+    pub yes: bool,
+    /// Print nothing; the caller prints the returned report as JSON.
+    pub json: bool,
+}
+
+/// One project of a [`clean_all`] run.
+#[derive(Debug, Serialize)]
+pub struct ProjectClean {
+    pub name: String,
+    pub path: PathBuf,
+    /// What a clean would free, measured before anything was removed.
+    pub reclaimable_bytes: u64,
+    pub freed_bytes: u64,
+    pub removed: usize,
+    /// Paths that could not be removed.
+    pub failed: usize,
+    /// Why the project could not be cleaned (or measured) at all, e.g. another run holds its lock.
+    pub error: Option<String>,
+}
+
+/// What a [`clean_all`] run found and freed.
+#[derive(Debug, Serialize)]
+pub struct CleanedAll {
+    pub dir: PathBuf,
+    pub dry_run: bool,
+    /// False when the user declined at the prompt; nothing was removed.
+    pub confirmed: bool,
+    pub projects: Vec<ProjectClean>,
+    pub reclaimable_bytes: u64,
+    pub freed_bytes: u64,
+    /// One line per project that failed or paths it left behind, e.g. `path: why`.
+    pub errors: Vec<String>,
+}
+
+/// Find the projects under `dir` (as `py-proj list` does), show what each would free,
+/// and after one confirmation clean them one by one. A failure in one project is
+/// recorded and the rest are still cleaned; an interrupt stops between projects.
+pub fn clean_all(dir: &Path, opts: &CleanAllOptions) -> Result<CleanedAll> {
+    let found = list::find_projects(dir, opts.max_depth, &opts.exclude)?;
+    let mut projects: Vec<ProjectClean> = found
+        .into_iter()
+        .map(|p| {
+            let (reclaimable_bytes, error) = match pyproj::plan_clean(&p.path, &opts.clean) {
+                Ok(plan) => (plan.freed(), None),
+                Err(e) => (0, Some(format!("{e:#}"))),
+            };
+            ProjectClean {
+                name: p.name,
+                path: p.path,
+                reclaimable_bytes,
+                freed_bytes: 0,
+                removed: 0,
+                failed: 0,
+                error,
+            }
+        })
+        .collect();
+    let reclaimable_bytes = projects.iter().map(|p| p.reclaimable_bytes).sum();
+    let mut cleaned = CleanedAll {
+        dir: dir.to_path_buf(),
+        dry_run: opts.dry_run,
+        confirmed: false,
+        projects: Vec::new(),
+        reclaimable_bytes,
+        freed_bytes: 0,
+        errors: Vec::new(),
+    };
+    if !opts.json {
+        print_table(&projects, reclaimable_bytes);
+    }
+    if opts.dry_run || reclaimable_bytes == 0 {
+        cleaned.errors = project_errors(&projects);
+        cleaned.projects = projects;
+        return Ok(cleaned);
+    }
+
+    if !opts.yes {
+        let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+        if !interactive {
+            return Err(pyproj::Error::NeedsConfirmation {
+                action: format!("clean {} project(s) under {}", projects.len(), dir.display()),
+                flag: "--yes",
+            }
+            .into());
+        }
+        let go = Confirm::new()
+            .with_prompt(format!("Clean these projects, freeing {}?", human_bytes(reclaimable_bytes)))
+            .default(false)
+            .interact()?;
+        if !go {
+            cleaned.projects = projects;
+            return Ok(cleaned);
+        }
+    }
+    cleaned.confirmed = true;
+
+    for project in projects.iter_mut().filter(|p| p.error.is_none() && p.reclaimable_bytes > 0) {
+        if interrupted() {
+            break;
+        }
+        match interruptible(|| pyproj::clean(&project.path, &opts.clean)) {
+            Ok(report) => {
+                project.freed_bytes = report.freed();
+                project.removed = report.removed().count();
+                project.failed = report.failed().count();
+                for entry in report.failed() {
+                    let why = entry.error.as_deref().unwrap_or_default();
+                    cleaned.errors.push(format!("{}: {why}", entry.path.display()));
+                }
+            }
+            Err(e) => project.error = Some(format!("{e:#}")),
+        }
+        if !opts.json {
+            print_outcome(project);
+        }
+    }
+    cleaned.errors.splice(0..0, project_errors(&projects));
+    cleaned.freed_bytes = projects.iter().map(|p| p.freed_bytes).sum();
+    cleaned.projects = projects;
+    Ok(cleaned)
+}
+
+fn project_errors(projects: &[ProjectClean]) -> Vec<String> {
+    let failed = projects.iter().filter_map(|p| Some((p, p.error.as_ref()?)));
+    failed.map(|(p, e)| format!("{}: {e}", p.path.display())).collect()
+}
+
+/// The projects found, biggest first, with what cleaning each would free.
+fn print_table(projects: &[ProjectClean], total: u64) {
+    if projects.is_empty() {
+        println!("  {}", "No py-proj projects found.".dimmed());
+        return;
+    }
+    let mut rows: Vec<&ProjectClean> = projects.iter().collect();
+    rows.sort_by(|a, b| b.reclaimable_bytes.cmp(&a.reclaimable_bytes).then_with(|| a.path.cmp(&b.path)));
+    let name_w = rows.iter().map(|p| p.name.len()).max().unwrap_or(0).max(4);
+    println!("  {}", format!("{:name_w$}  {:>11}  PATH", "NAME", "RECLAIMABLE").bold());
+    for p in rows {
+        let size = match (&p.error, p.reclaimable_bytes) {
+            (Some(_), _) => format!("{:>11}", "error").red().to_string(),
+            (None, 0) => format!("{:>11}", "-"),
+            (None, bytes) => format!("{:>11}", human_bytes(bytes)),
+        };
+        println!("  {:name_w$}  {size:>11}  {}", p.name, p.path.display().to_string().dimmed());
+    }
+    println!(
+        "  {:name_w$}  {:>11}  {}",
+        "",
+        human_bytes(total).bold(),
+        format!("total ({} project(s))", projects.len()).bold()
+    );
+}
+
+fn print_outcome(p: &ProjectClean) {
+    match (&p.error, p.failed) {
+        (Some(e), _) => println!("  {} {} {}", "FAIL".red().bold(), p.name, e.dimmed()),
+        (None, 0) => println!("  {} {} {}", "clean".green(), p.name, format!("(freed {})", human_bytes(p.freed_bytes)).dimmed()),
+        (None, n) => println!(
+            "  {} {} {}",
+            "WARN".yellow().bold(),
+            p.name,
+            format!("(freed {}; {n} path(s) could not be removed)", human_bytes(p.freed_bytes)).yellow()
+        ),
+    }
+}
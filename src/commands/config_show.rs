@@ -0,0 +1,108 @@
+//! `py-proj config show`: the settings the next run would use, merged from the flags,
+//! the environment, the repository's `.pyprojrc.toml`, and the user config; with
+//! `--origin`, which of those each one came from.
+
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use pyproj::config::{Config, APPENDED};
+
+/// One effective setting.
+#[derive(Debug, Serialize)]
+pub struct Setting {
+    /// Dotted for the tables: `org.license`, `vars.team`.
+    pub key: String,
+    /// `None` while nothing sets it and the built-in default applies.
+    pub value: Option<toml::Value>,
+    /// Where the value came from, lowest precedence first: `default`, `user config (…)`,
+    /// `repo rc (…)`, `env ($…)`, or `flag (--…)`. A list that adds up names each.
+    pub origin: Vec<String>,
+}
+
+/// The settings listed (besides `vars.*`), with the flag that overrides each.
+const SETTINGS: &[(&str, Option<&str>)] = &[
+    ("python", Some("--python")),
+    ("venv_dir", Some("--venv-dir")),
+    ("notebooks_dir", Some("--notebooks-dir")),
+    ("minimal", Some("--minimal")),
+    ("with", Some("--with")),
+    ("exclude", Some("--exclude")),
+    ("templates_dir", Some("--templates-dir")),
+    ("gitignore_extra", Some("--gitignore-extra")),
+    ("post_hooks", Some("--post-hook")),
+    ("command_timeout", Some("--command-timeout")),
+    ("lock_timeout", Some("--lock-timeout")),
+    ("clean.extra_patterns", Some("--clean-extra")),
+    ("history", Some("--no-history")),
+    ("check_updates", None),
+    ("editor", Some("--editor")),
+    ("org.name", Some("--org")),
+    ("org.license", Some("--license")),
+    ("org.repo_url", Some("--repo-url")),
+    ("org.homepage", Some("--homepage")),
+    ("org.maintainers", Some("--maintainer")),
+];
+
+/// The effective settings. `flags` holds what the command line set, by key
+/// (`vars.<name>` for each `--var`); `env` what the environment did, with the variable.
+pub fn settings(
+    config: &Config,
+    flags: &BTreeMap<String, toml::Value>,
+    env: &BTreeMap<&str, (toml::Value, &str)>,
+) -> Vec<Setting> {
+    let vars: BTreeSet<&str> = config
+        .keys()
+        .chain(flags.keys().map(String::as_str))
+        .filter(|key| key.starts_with("vars."))
+        .collect();
+    let keys = SETTINGS
+        .iter()
+        .copied()
+        .chain(vars.into_iter().map(|key| (key, Some("--var"))));
+    keys.map(|(key, flag)| {
+        let mut value = config.value(key).cloned();
+        let mut origin: Vec<String> = config.origins(key).iter().map(ToString::to_string).collect();
+        if let Some((set, var)) = env.get(key) {
+            value = Some(set.clone());
+            origin = vec![format!("env (${var})")];
+        }
+        if let (Some(set), Some(flag)) = (flags.get(key), flag) {
+            let label = format!("flag ({flag})");
+            match (&mut value, set) {
+                (Some(toml::Value::Array(list)), toml::Value::Array(more)) if APPENDED.contains(&key) => {
+                    list.extend(more.iter().cloned());
+                    origin.push(label);
+                }
+                _ => {
+                    value = Some(set.clone());
+                    origin = vec![label];
+                }
+            }
+        }
+        if origin.is_empty() {
+            origin.push("default".to_string());
+        }
+        Setting {
+            key: key.to_string(),
+            value,
+            origin,
+        }
+    })
+    .collect()
+}
+
+/// Print `settings` as `key = value` lines; with `origin`, each followed by its layers.
+pub fn print_settings(settings: &[Setting], origin: bool) {
+    let width = settings.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    for s in settings {
+        let value = match &s.value {
+            Some(value) => value.to_string(),
+            None => "(not set)".dimmed().to_string(),
+        };
+        match origin {
+            true => println!("  {:<width$} = {value}  {}", s.key, format!("# {}", s.origin.join(" + ")).dimmed()),
+            false => println!("  {:<width$} = {value}", s.key),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `py-proj diff`: compare a project on disk with what the current templates would generate.
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+use std::path::Path;
+
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::scaffold::ScaffoldPlan;
+use pyproj::util::{current_mode, file_mode, format_mode, human_bytes};
+
+/// Print the drift report for `root`; returns true when anything differs or is missing.
+pub fn diff_project(root: &Path, verbose: bool) -> Result<bool> {
+    let manifest = Manifest::load(root)?;
+    let plan = match &manifest {
+        Some(m) => ScaffoldPlan::from_manifest(root, m, super::printer(verbose))?,
+        None => {
+            println!(
+                "  {} {}",
+                "WARN".yellow().bold(),
+                format!("no {MANIFEST_FILE}; inferring options from the project").yellow()
+            );
+            ScaffoldPlan::inferred(root, super::printer(verbose))?
+        }
+    };
+    println!(
+        "  {} {} {}",
+        "Against:".dimmed(),
+        plan.project.blue().bold(),
+        format!("(Python {}, templates: {})", plan.python, plan.template_profile).dimmed()
+    );
+
+    let mut missing = Vec::new();
+    let mut same = Vec::new();
+    let mut changed = 0;
+    for (rel, new) in plan.render_files()? {
+        let Ok(old) = fs::read(root.join(&rel)) else {
+            missing.push(rel);
+            continue;
+        };
+        let mode = match &manifest {
+            Some(m) => m.mode_of(&rel),
+            None => file_mode(&rel),
+        };
+        // An executable file that lost its bit (or gained others) differs too.
+        let mode_drift = mode.zip(current_mode(&root.join(&rel))).filter(|(want, have)| want != have);
+        if old == new && mode_drift.is_none() {
+            same.push(rel);
+            continue;
+        }
+        changed += 1;
+        if let Some((want, have)) = mode_drift {
+            let change = format!("({} -> {})", format_mode(have), format_mode(want));
+            println!("  {} {} {}", "mode".yellow().bold(), rel, change.dimmed());
+        }
+        if old != new {
+            print_change(&rel, &old, &new);
+        }
+    }
+
+    if !missing.is_empty() {
+        println!("  {}", "Would create:".bold());
+        for rel in &missing {
+            println!("    {} {}", "+".green(), rel);
+        }
+    }
+    if !same.is_empty() {
+        println!("  {}", "Identical:".bold());
+        for rel in &same {
+            println!("    {} {}", "=".dimmed(), rel.dimmed());
+        }
+    }
+    println!(
+        "  {} {changed} differ, {} missing, {} identical",
+        "Summary:".dimmed(),
+        missing.len(),
+        same.len()
+    );
+    Ok(changed > 0 || !missing.is_empty())
+}
+
+/// Files larger than this get a summary instead of a line diff.
+const MAX_DIFF_BYTES: usize = 256 * 1024;
+
+/// A unified diff from `old` to `new`, or for binary-looking, notebook, and very
+/// large files, a one-line summary with the sizes and where they first differ.
+pub fn print_change(rel: &str, old: &[u8], new: &[u8]) {
+    fn text(bytes: &[u8]) -> Option<&str> {
+        std::str::from_utf8(bytes).ok().filter(|s| !s.contains('\0') && bytes.len() <= MAX_DIFF_BYTES)
+    }
+    match (text(old), text(new)) {
+        (Some(old), Some(new)) if !rel.ends_with(".ipynb") => print_unified(rel, old, new),
+        _ => {
+            let at = old.iter().zip(new).position(|(a, b)| a != b).unwrap_or(old.len().min(new.len()));
+            let line = old[..at].iter().filter(|b| **b == b'\n').count() + 1;
+            println!(
+                "  {} {} {}",
+                "differs".yellow().bold(),
+                rel,
+                format!(
+                    "({} → {}, first difference at byte {at}, line {line})",
+                    human_bytes(old.len() as u64),
+                    human_bytes(new.len() as u64)
+                )
+                .dimmed()
+            );
+        }
+    }
+}
+
+/// Print a colored unified diff (3 lines of context) from `old` to `new`.
+pub fn print_unified(path: &str, old: &str, new: &str) {
+    let diff = TextDiff::from_lines(old, new);
+    println!("{}", format!("--- a/{path}").red().bold());
+    println!("{}", format!("+++ b/{path}").green().bold());
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        println!("{}", hunk.header().to_string().cyan());
+        for change in hunk.iter_changes() {
+            let line = change.to_string_lossy();
+            let line = line.trim_end_matches(['\n', '\r']);
+            match change.tag() {
+                ChangeTag::Delete => println!("{}", format!("-{line}").red()),
+                ChangeTag::Insert => println!("{}", format!("+{line}").green()),
+                ChangeTag::Equal => println!(" {}", line.dimmed()),
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! `py-proj doctor`: check the toolchain and an existing project for the usual breakages.
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::Path;
+
+use pyproj::manifest::Manifest;
+use pyproj::preflight::{check_tool, check_uv};
+pub use pyproj::preflight::{Check, Status};
+use pyproj::python::PythonVersion;
+use pyproj::scaffold::{DepStyle, Ide, Security, GLOBAL_TOOLS, NVIM_EXRC};
+use pyproj::util::{
+    detect_system_python, read_pyproject, run_output, uv_bin, venv_dir_name, venv_python_matches,
+    venv_python_version,
+};
+
+/// Run every check against `root`; the caller decides how to print them.
+pub fn diagnose(root: &Path) -> Result<Vec<Check>> {
+    let python = pinned_python(root)?;
+    let mut checks = vec![check_uv(false)];
+    checks.push(check_python_installed(root, &python));
+    checks.push(check_venv(root, &python));
+    checks.push(check_pyproject(root));
+    let manifest = Manifest::load(root)?;
+    match manifest.as_ref().and_then(|m| m.options.ide).unwrap_or_default() {
+        Ide::Vscode => checks.push(check_vscode(root)),
+        Ide::Neovim => checks.push(check_neovim(root)),
+        Ide::None => {}
+    }
+    if manifest.as_ref().is_some_and(|m| m.options.tools_global) {
+        for tool in GLOBAL_TOOLS {
+            checks.push(check_tool(tool, &format!("uv tool install {tool} (--tools-global project)")));
+        }
+    }
+    if let Some(m) = &manifest {
+        if let Some(scanner) = m.options.security {
+            checks.push(check_security(root, scanner, m.options.dep_style.unwrap_or_default()));
+        }
+    }
+    checks.push(check_tool("direnv", "install direnv to load .envrc automatically"));
+    checks.push(check_tool("git", "install git to version the project"));
+    Ok(checks)
+}
+
+/// Print checks as a colored list with hints under anything that is not a pass.
+pub fn print_report(checks: &[Check]) {
+    for c in checks {
+        let label = match c.status {
+            Status::Pass => "PASS".green().bold().to_string(),
+            Status::Warn => "WARN".yellow().bold().to_string(),
+            Status::Fail => "FAIL".red().bold().to_string(),
+        };
+        println!("  {label} {} {}", c.name.bold(), c.detail.dimmed());
+        if let Some(hint) = &c.hint {
+            println!("       {} {}", "hint:".cyan(), hint);
+        }
+    }
+}
+
+/// The version the project wants: manifest, then `.python-version`, then the system Python.
+fn pinned_python(root: &Path) -> Result<String> {
+    if let Some(m) = Manifest::load(root)? {
+        return Ok(m.options.python);
+    }
+    if let Ok(pin) = fs::read_to_string(root.join(".python-version")) {
+        let pin = pin.trim();
+        if !pin.is_empty() {
+            return Ok(pin.to_string());
+        }
+    }
+    Ok(detect_system_python())
+}
+
+/// The `--security` scanner is still wired up: bandit installed in the venv, or the
+/// `S` rules still selected in pyproject.toml.
+fn check_security(root: &Path, scanner: Security, dep_style: DepStyle) -> Check {
+    let name = "security";
+    match scanner {
+        Security::Bandit => {
+            let bin = if cfg!(windows) { "Scripts/bandit.exe" } else { "bin/bandit" };
+            match root.join(venv_dir_name(root)).join(bin).is_file() {
+                true => Check::pass(name, "bandit is installed in the venv"),
+                false => {
+                    let install = match dep_style {
+                        DepStyle::Extras => "uv pip install -e \".[dev]\"",
+                        DepStyle::Groups => "uv pip install -e . --group dev",
+                    };
+                    Check::warn(name, "bandit is not installed in the venv", install)
+                }
+            }
+        }
+        Security::RuffS => {
+            let selected = read_pyproject(root).is_some_and(|t| {
+                let select = t
+                    .get("tool")
+                    .and_then(|t| t.get("ruff")?.get("lint")?.get("extend-select")?.as_array().cloned());
+                select.is_some_and(|s| s.iter().any(|r| r.as_str() == Some("S")))
+            });
+            match selected {
+                true => Check::pass(name, "ruff's S rules are enabled"),
+                false => Check::warn(
+                    name,
+                    "ruff's S rules are not enabled in pyproject.toml",
+                    "add extend-select = [\"S\"] under [tool.ruff.lint]",
+                ),
+            }
+        }
+    }
+}
+
+fn check_python_installed(root: &Path, python: &str) -> Check {
+    let name = "python";
+    let Ok(list) = run_output(&uv_bin(), &["python", "list", "--only-installed"], root) else {
+        return Check::warn(name, format!("could not list Pythons for {python}"), "fix uv first");
+    };
+    let installed = list.lines().any(|l| {
+        let id = l.split_whitespace().next().unwrap_or_default();
+        id.contains(&format!("-{python}-")) || id.contains(&format!("-{python}+"))
+    });
+    if installed {
+        Check::pass(name, format!("{python} is installed"))
+    } else {
+        Check::fail(name, format!("{python} is not installed"), format!("uv python install {python}"))
+    }
+}
+
+fn check_venv(root: &Path, python: &str) -> Check {
+    let name = ".venv";
+    let dir = venv_dir_name(root);
+    let venv = root.join(&dir);
+    let recreate = format!("uv venv --python {python} {dir}");
+    if !venv.join("pyvenv.cfg").is_file() {
+        return Check::fail(name, "missing or has no pyvenv.cfg", recreate);
+    }
+    let version = venv_python_version(root);
+    let bin = if cfg!(windows) { "Scripts/python.exe" } else { "bin/python" };
+    if !venv.join(bin).exists() {
+        return Check::fail(name, "interpreter is missing (deleted Python?)", recreate);
+    }
+    let wanted = python.parse::<PythonVersion>().ok();
+    match (version, wanted) {
+        (Some(v), Some(wanted)) if venv_python_matches(&v, &wanted) => Check::pass(name, format!("Python {v}")),
+        (Some(v), Some(_)) => Check::fail(
+            name,
+            format!("built with Python {v}, expected {python}"),
+            format!("py-proj --create_project --recreate-venv (or remove {dir} and run uv venv)"),
+        ),
+        (Some(v), None) => Check::pass(name, format!("Python {v}")),
+        (None, _) => Check::warn(name, "pyvenv.cfg has no version", recreate),
+    }
+}
+
+fn check_pyproject(root: &Path) -> Check {
+    let name = "pyproject.toml";
+    match fs::read_to_string(root.join(name)) {
+        Err(_) => Check::fail(name, "not found", "run doctor from the project root or pass its path"),
+        Ok(text) => match toml::from_str::<toml::Table>(&text) {
+            Ok(_) => Check::pass(name, "parses"),
+            Err(e) => Check::fail(name, e.message().to_string(), "fix the TOML syntax error"),
+        },
+    }
+}
+
+fn check_vscode(root: &Path) -> Check {
+    let name = "vscode";
+    let Ok(text) = fs::read_to_string(root.join(".vscode/settings.json")) else {
+        return Check::warn(name, "no .vscode/settings.json", "regenerate it with `py-proj upgrade`");
+    };
+    let settings: serde_json::Value = match serde_json::from_str(&text) {
+        Ok(v) => v,
+        Err(e) => return Check::fail(name, format!("settings.json: {e}"), "fix the JSON syntax error"),
+    };
+    let dir = venv_dir_name(root);
+    match settings["python.defaultInterpreterPath"].as_str() {
+        Some(p) if p.contains(dir.as_str()) => Check::pass(name, format!("interpreter {p}")),
+        Some(p) => Check::warn(
+            name,
+            format!("interpreter {p} is outside {dir}"),
+            format!("set python.defaultInterpreterPath to ${{workspaceFolder}}/{dir}/bin/python"),
+        ),
+        None => Check::warn(
+            name,
+            "python.defaultInterpreterPath is not set",
+            format!("set it to ${{workspaceFolder}}/{dir}/bin/python"),
+        ),
+    }
+}
+
+/// `--ide neovim`: `.nvim.lua` is there and points at the project's venv.
+fn check_neovim(root: &Path) -> Check {
+    let name = "neovim";
+    let Ok(text) = fs::read_to_string(root.join(NVIM_EXRC)) else {
+        return Check::warn(name, format!("no {NVIM_EXRC}"), "regenerate it with `py-proj upgrade`");
+    };
+    let dir = venv_dir_name(root);
+    match text.contains(&format!("\"/{dir}\"")) {
+        true => Check::pass(name, format!("{NVIM_EXRC} uses {dir} (Neovim loads it with 'exrc' on)")),
+        false => Check::warn(
+            name,
+            format!("{NVIM_EXRC} does not point at {dir}"),
+            format!("set `local venv = root .. \"/{dir}\"` in {NVIM_EXRC}, or regenerate it with `py-proj upgrade`"),
+        ),
+    }
+}
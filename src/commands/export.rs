@@ -0,0 +1,316 @@
+//! `py-proj export-requirements`: write requirements.txt (and one file per `--group`)
+//! for deploy targets that still want them, from uv.lock when the project has one and
+//! straight from pyproject.toml otherwise. `py-proj export-conda` writes a conda
+//! `environment.yml` from the same sources, for clusters that only take those.
+
+use anyhow::{bail, Context, Result};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::Manifest;
+use pyproj::util::{dist_name, run_output, uv_bin, write};
+use pyproj::workspace;
+
+/// PyPI packages conda-forge has under a known name (PEP 503 name, conda name); the
+/// rest of the dependencies go in the `pip:` section.
+const CONDA_PACKAGES: &[(&str, &str)] = &[
+    ("numpy", "numpy"),
+    ("pandas", "pandas"),
+    ("scipy", "scipy"),
+    ("scikit-learn", "scikit-learn"),
+    ("matplotlib", "matplotlib"),
+    ("seaborn", "seaborn"),
+    ("polars", "polars"),
+    ("pyarrow", "pyarrow"),
+    ("xarray", "xarray"),
+    ("dask", "dask"),
+    ("numba", "numba"),
+    ("h5py", "h5py"),
+    ("netcdf4", "netcdf4"),
+    ("statsmodels", "statsmodels"),
+    ("sympy", "sympy"),
+    ("networkx", "networkx"),
+    ("torch", "pytorch"),
+    ("tensorflow", "tensorflow"),
+    ("jax", "jax"),
+    ("jupyterlab", "jupyterlab"),
+    ("ipykernel", "ipykernel"),
+    ("pillow", "pillow"),
+    ("opencv-python", "opencv"),
+    ("lxml", "lxml"),
+    ("psycopg2", "psycopg2"),
+    ("pyyaml", "pyyaml"),
+    ("requests", "requests"),
+    ("sqlalchemy", "sqlalchemy"),
+];
+
+pub struct CondaOptions {
+    /// Where the file goes, relative to the root (default: environment.yml).
+    pub output: Option<PathBuf>,
+}
+
+/// A conda environment file, serialized to YAML by serde_yaml.
+#[derive(Serialize)]
+struct CondaEnvironment {
+    name: String,
+    channels: Vec<String>,
+    dependencies: Vec<CondaDependency>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum CondaDependency {
+    /// A conda match spec: `python=3.12`, `numpy==2.1.0`.
+    Conda(String),
+    /// The requirements pip installs into the environment.
+    Pip { pip: Vec<String> },
+}
+
+pub struct ExportOptions {
+    /// Also export this dependency group or extra, to `<output stem>-<group>.txt`.
+    pub group: Option<String>,
+    /// Where the main file goes, relative to the root (default: requirements.txt).
+    pub output: Option<PathBuf>,
+}
+
+/// How the project's dependencies are pinned.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Workflow {
+    /// `uv.lock` (the project's own, or its workspace root's): `uv export`.
+    Lock,
+    /// Only pyproject.toml: `uv pip compile`.
+    Compile,
+}
+
+pub fn export_requirements(root: &Path, opts: &ExportOptions) -> Result<()> {
+    let pyproject = root.join("pyproject.toml");
+    let text = fs::read_to_string(&pyproject)
+        .with_context(|| format!("failed to read {}", pyproject.display()))?;
+    let doc: toml::Table = toml::from_str(&text).context("invalid TOML in pyproject.toml")?;
+    let workflow = workflow(root)?;
+    println!(
+        "  {} {}",
+        "Source:".dimmed(),
+        match workflow {
+            Workflow::Lock => "uv.lock (uv export)",
+            Workflow::Compile => "pyproject.toml (uv pip compile)",
+        }
+    );
+
+    let main = opts.output.clone().unwrap_or_else(|| PathBuf::from("requirements.txt"));
+    let mut files = vec![(main.clone(), None)];
+    if let Some(group) = &opts.group {
+        let selector = selector(&doc, group)?;
+        files.push((grouped_path(&main, group), Some(selector)));
+    }
+    for (rel, selector) in files {
+        let (flag, name) = selector.unzip();
+        let args = uv_args(workflow, flag, name);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let out = run_output(&uv_bin(), &args, root)?;
+        let header = format!(
+            "# Generated by py-proj export-requirements from {} (`uv {}`).\n\
+             # Regenerate it rather than editing it by hand.\n",
+            match workflow {
+                Workflow::Lock => "uv.lock",
+                Workflow::Compile => "pyproject.toml",
+            },
+            args.join(" ")
+        );
+        let count = out
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with(['#', ' ', '-']))
+            .count();
+        let path = root.join(&rel);
+        write(&path, header + &out)?;
+        println!(
+            "  {} {} {}",
+            "write".green(),
+            rel.display(),
+            format!("({count} package(s))").dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Lock-based when there is a uv.lock next to pyproject.toml or, for a workspace
+/// member (per the manifest or a workspace root above it), at the workspace root.
+fn workflow(root: &Path) -> Result<Workflow> {
+    if root.join("uv.lock").is_file() {
+        return Ok(Workflow::Lock);
+    }
+    let member = Manifest::load(root)?.is_some_and(|m| m.options.layout == "member");
+    match workspace::find_root(root)? {
+        Some(ws) if ws.join("uv.lock").is_file() => Ok(Workflow::Lock),
+        Some(_) if member => bail!(
+            "this workspace member has no uv.lock at its workspace root; run `uv lock` there first"
+        ),
+        _ => Ok(Workflow::Compile),
+    }
+}
+
+/// `--group` or `--extra` for `group`, whichever pyproject.toml declares it as.
+fn selector(doc: &toml::Table, group: &str) -> Result<(&'static str, String)> {
+    let has = |table: &str, sub: Option<&str>| {
+        let t = doc.get(table);
+        let t = match sub {
+            Some(sub) => t.and_then(|t| t.get(sub)),
+            None => t,
+        };
+        t.and_then(|t| t.get(group)).is_some()
+    };
+    if has("dependency-groups", None) {
+        Ok(("--group", group.to_string()))
+    } else if has("project", Some("optional-dependencies")) {
+        Ok(("--extra", group.to_string()))
+    } else {
+        bail!("pyproject.toml has no dependency group or extra named `{group}`")
+    }
+}
+
+/// `requirements.txt` -> `requirements-dev.txt`.
+fn grouped_path(main: &Path, group: &str) -> PathBuf {
+    let stem = main.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match main.extension() {
+        Some(ext) => format!("{stem}-{group}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{group}"),
+    };
+    main.with_file_name(name)
+}
+
+fn uv_args(workflow: Workflow, flag: Option<&str>, group: Option<String>) -> Vec<String> {
+    let mut args: Vec<String> = match workflow {
+        Workflow::Lock => ["export", "--format", "requirements-txt", "--locked", "--no-emit-project"]
+            .map(String::from)
+            .to_vec(),
+        Workflow::Compile => ["pip", "compile", "pyproject.toml", "--quiet"].map(String::from).to_vec(),
+    };
+    args.push("--no-header".to_string());
+    if let (Some(flag), Some(group)) = (flag, group) {
+        args.push(flag.to_string());
+        args.push(group);
+    } else if workflow == Workflow::Lock {
+        // Dependency groups (uv's `dev` included) are in by default; extras are not.
+        args.push("--no-default-groups".to_string());
+    }
+    args
+}
+
+pub fn export_conda(root: &Path, opts: &CondaOptions) -> Result<()> {
+    let pyproject = root.join("pyproject.toml");
+    let text = fs::read_to_string(&pyproject)
+        .with_context(|| format!("failed to read {}", pyproject.display()))?;
+    let doc: toml::Table = toml::from_str(&text).context("invalid TOML in pyproject.toml")?;
+    let project = doc.get("project").and_then(|p| p.as_table());
+    let Some(name) = project.and_then(|p| p.get("name")).and_then(|n| n.as_str()) else {
+        bail!("pyproject.toml has no [project] name");
+    };
+    let python = python_pin(root, project)?;
+
+    // The lock's pinned set when there is one; else the ranges pyproject.toml declares.
+    let (requirements, source): (Vec<String>, String) = match workflow(root)? {
+        Workflow::Lock => {
+            let args = [
+                "export", "--format", "requirements-txt", "--locked", "--no-emit-project", "--no-header",
+                "--no-hashes", "--no-default-groups",
+            ];
+            let out = run_output(&uv_bin(), &args, root)?;
+            let requirements = out
+                .lines()
+                .map(str::trim_end)
+                .filter(|l| !l.is_empty() && !l.starts_with(['#', ' ', '-']))
+                .map(|l| l.trim_end_matches('\\').trim().to_string())
+                .collect();
+            (requirements, format!("uv.lock (`uv {}`)", args.join(" ")))
+        }
+        Workflow::Compile => {
+            let declared = project.and_then(|p| p.get("dependencies")).and_then(|d| d.as_array());
+            let requirements = declared
+                .into_iter()
+                .flatten()
+                .filter_map(|d| d.as_str())
+                .map(str::to_string)
+                .collect();
+            (requirements, "the ranges in pyproject.toml (no uv.lock)".to_string())
+        }
+    };
+    println!("  {} {}", "Source:".dimmed(), source);
+
+    let mut dependencies = vec![CondaDependency::Conda(format!("python={python}"))];
+    let mut pip = Vec::new();
+    for requirement in requirements {
+        match conda_spec(&requirement) {
+            Some(spec) => dependencies.push(CondaDependency::Conda(spec)),
+            None => pip.push(requirement),
+        }
+    }
+    let native = dependencies.len() - 1;
+    if !pip.is_empty() {
+        dependencies.push(CondaDependency::Conda("pip".to_string()));
+        dependencies.push(CondaDependency::Pip { pip: pip.clone() });
+    }
+    let env = CondaEnvironment {
+        name: name.to_string(),
+        channels: vec!["conda-forge".to_string()],
+        dependencies,
+    };
+    let header = format!(
+        "# Generated by py-proj export-conda from {source}.\n\
+         # Regenerate it with `py-proj export-conda` rather than editing it by hand.\n"
+    );
+    let rel = opts.output.clone().unwrap_or_else(|| PathBuf::from("environment.yml"));
+    write(root.join(&rel), header + &serde_yaml::to_string(&env)?)?;
+    println!(
+        "  {} {} {}",
+        "write".green(),
+        rel.display(),
+        format!("(Python {python}, {native} conda package(s), {} pip)", pip.len()).dimmed()
+    );
+    Ok(())
+}
+
+/// `major.minor` of the project's Python: `.python-version`, else the manifest's, else
+/// the lower bound of `requires-python`.
+fn python_pin(root: &Path, project: Option<&toml::Table>) -> Result<String> {
+    let pinned = fs::read_to_string(root.join(".python-version"))
+        .ok()
+        .and_then(|text| text.lines().next().map(str::to_string));
+    let recorded = || Manifest::load(root).ok().flatten().map(|m| m.options.python);
+    let required = || {
+        let spec = project?.get("requires-python")?.as_str()?;
+        Some(spec.trim().trim_start_matches(['>', '=', '~']).to_string())
+    };
+    let version = pinned
+        .or_else(recorded)
+        .or_else(required)
+        .context("no Python version found: add .python-version or requires-python to pyproject.toml")?;
+    let parts: Vec<&str> = version.trim().split('.').take(2).collect();
+    if parts.len() < 2 || !parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit())) {
+        bail!("cannot pin Python `{}` in a conda environment; expected a version like 3.12", version.trim());
+    }
+    Ok(parts.join("."))
+}
+
+/// `requirement` as a conda match spec when conda-forge has the package and the version
+/// constraint is one conda understands; `None` for extras, markers, URLs, and `~=`.
+fn conda_spec(requirement: &str) -> Option<String> {
+    let requirement = requirement.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "._-".contains(c)))
+        .unwrap_or(requirement.len());
+    let (name, constraint) = requirement.split_at(end);
+    let (_, conda) = CONDA_PACKAGES.iter().find(|(pypi, _)| *pypi == dist_name(name))?;
+    let constraint: String = constraint.chars().filter(|c| !c.is_whitespace()).collect();
+    if constraint.contains([';', '@', '[']) {
+        return None;
+    }
+    let simple = constraint.split(',').all(|part| {
+        part.is_empty()
+            || ["==", ">=", "<=", "!=", ">", "<"]
+                .iter()
+                .any(|op| part.strip_prefix(op).is_some_and(|v| !v.is_empty() && !v.starts_with(['=', '~'])))
+    });
+    (simple && !constraint.contains("~=")).then(|| format!("{conda}{constraint}"))
+}
@@ -0,0 +1,47 @@
+//! `py-proj history`: show what past runs did to which projects.
+
+use chrono::{DateTime, Local};
+use owo_colors::OwoColorize;
+
+use pyproj::history::Entry;
+
+/// Print `entries` one per line, each failure followed by its error; with `verbose`,
+/// also the version and command line of every run.
+pub fn print_table(entries: &[Entry], verbose: bool) {
+    let outcome_w = entries.iter().map(|e| outcome(e).len()).max().unwrap_or(0).max(7);
+    println!(
+        "  {}",
+        format!("{:16}  {:7}  {:outcome_w$}  PATH", "WHEN", "COMMAND", "OUTCOME").bold()
+    );
+    for e in entries {
+        let when = DateTime::parse_from_rfc3339(&e.time)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| e.time.clone());
+        let shown = format!("{:outcome_w$}", outcome(e));
+        let shown = match e.outcome.as_str() {
+            "ok" => shown.green().to_string(),
+            o if o.starts_with("failed") => shown.red().to_string(),
+            _ => shown.dimmed().to_string(),
+        };
+        println!(
+            "  {when:16}  {:7}  {shown}  {}",
+            e.command,
+            e.path.display().to_string().dimmed()
+        );
+        if let Some(reason) = e.outcome.strip_prefix("failed: ") {
+            println!("  {:16}  {}", "", reason.red());
+        }
+        if verbose {
+            let line = format!("py-proj {} {}", e.version, e.args.join(" "));
+            println!("  {:16}  {}", "", line.dimmed());
+        }
+    }
+}
+
+/// The outcome without the error message, which would not fit in a column.
+fn outcome(e: &Entry) -> &str {
+    match e.outcome.starts_with("failed") {
+        true => "failed",
+        false => &e.outcome,
+    }
+}
@@ -0,0 +1,164 @@
+//! `py-proj info`: an overview of one project: name, version, pinned Python, venv,
+//! generated components, and disk usage. Without a manifest, what the files on disk do
+//! not tell is reported as unknown (`null` in JSON).
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::scaffold::Security;
+use pyproj::templates::REGISTRY;
+use pyproj::util::{find_project_root, human_bytes, measure, project_venv, pyvenv_python, read_pyproject};
+
+#[derive(Debug, Serialize)]
+pub struct ProjectInfo {
+    pub root: PathBuf,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// The pinned Python and where the pin came from (`.python-version` or the manifest).
+    pub python: Option<String>,
+    pub python_source: Option<&'static str>,
+    pub venv: Option<VenvInfo>,
+    /// Template groups generated: the manifest's, or inferred from the files present.
+    pub components: Vec<String>,
+    pub components_inferred: bool,
+    /// The `--security` scanner from the manifest (`bandit` or `ruff-s`).
+    pub security: Option<&'static str>,
+    /// The py-proj version that created the project, and when.
+    pub created_by: Option<String>,
+    pub created: Option<String>,
+    /// The project's files, the venv excluded.
+    pub project_bytes: u64,
+    pub venv_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VenvInfo {
+    pub path: PathBuf,
+    pub python: Option<String>,
+}
+
+/// Gather the overview for the project at or above `start`.
+pub fn inspect(start: &Path) -> Result<ProjectInfo> {
+    let Some(root) = find_project_root(start) else {
+        return Err(pyproj::Error::project_not_found(start, "py-proj info path/to/proj").into());
+    };
+    // A broken manifest is reported as missing rather than failing the overview.
+    let manifest = Manifest::load(&root).ok().flatten();
+    let pyproject = read_pyproject(&root);
+    let project = pyproject.as_ref().and_then(|t| t.get("project"));
+    let name = project
+        .and_then(|p| p.get("name")?.as_str().map(str::to_string))
+        .or_else(|| manifest.as_ref().map(|m| m.options.project.clone()));
+    let version = project.and_then(|p| match p.get("version") {
+        Some(v) => v.as_str().map(str::to_string),
+        None => p
+            .get("dynamic")?
+            .as_array()?
+            .iter()
+            .any(|d| d.as_str() == Some("version"))
+            .then(|| "dynamic".to_string()),
+    });
+    let pinned = fs::read_to_string(root.join(".python-version"))
+        .ok()
+        .and_then(|t| t.lines().map(str::trim).find(|l| !l.is_empty() && !l.starts_with('#')).map(str::to_string));
+    let (python, python_source) = match (pinned, &manifest) {
+        (Some(pin), _) => (Some(pin), Some(".python-version")),
+        (None, Some(m)) => (Some(m.options.python.clone()), Some("manifest")),
+        (None, None) => (None, None),
+    };
+    let venv = project_venv(&root)?.map(|path| VenvInfo {
+        python: pyvenv_python(&path),
+        path,
+    });
+    let (components, components_inferred) = match &manifest {
+        Some(m) => (m.options.components.clone(), false),
+        None => (inferred_components(&root), true),
+    };
+    let venv_bytes = venv.as_ref().map_or(0, |v| measure(&v.path).bytes);
+    let total = measure(&root).bytes;
+    let project_bytes = match &venv {
+        Some(v) if v.path.starts_with(&root) => total.saturating_sub(venv_bytes),
+        _ => total,
+    };
+    Ok(ProjectInfo {
+        root,
+        name,
+        version,
+        python,
+        python_source,
+        venv,
+        components,
+        components_inferred,
+        security: manifest.as_ref().and_then(|m| m.options.security).map(Security::name),
+        created_by: manifest.as_ref().map(|m| m.tool.version.clone()),
+        created: manifest.as_ref().map(|m| m.tool.created.clone()),
+        project_bytes,
+        venv_bytes,
+    })
+}
+
+/// The template groups with at least one of their files in `root`.
+fn inferred_components(root: &Path) -> Vec<String> {
+    let mut components: Vec<String> = Vec::new();
+    for t in REGISTRY.iter().filter(|t| root.join(t.path).is_file()) {
+        if !components.iter().any(|c| c == t.group.name()) {
+            components.push(t.group.name().to_string());
+        }
+    }
+    components
+}
+
+pub fn print_info(info: &ProjectInfo) {
+    let unknown = || "unknown".dimmed().to_string();
+    let field = |label: &str, value: String| println!("  {} {value}", format!("{label:<11}").dimmed());
+    field("Root:", info.root.display().to_string().blue().to_string());
+    field("Name:", info.name.as_deref().map_or_else(unknown, |n| n.blue().bold().to_string()));
+    field("Version:", info.version.clone().unwrap_or_else(unknown));
+    field(
+        "Python:",
+        match (&info.python, info.python_source) {
+            (Some(python), Some(source)) => format!("{} {}", python.magenta(), format!("(from {source})").dimmed()),
+            _ => unknown(),
+        },
+    );
+    field(
+        ".venv:",
+        match &info.venv {
+            Some(venv) => format!(
+                "Python {} {}",
+                venv.python.as_deref().unwrap_or("?"),
+                venv.path.display().to_string().dimmed()
+            ),
+            None => "none".yellow().to_string(),
+        },
+    );
+    field(
+        "Components:",
+        match (info.components.is_empty(), info.components_inferred) {
+            (true, _) => unknown(),
+            (false, false) => info.components.join(", "),
+            (false, true) => format!("{} {}", info.components.join(", "), "(inferred from files)".dimmed()),
+        },
+    );
+    if let Some(scanner) = info.security {
+        field("Security:", scanner.to_string());
+    }
+    field(
+        "Created by:",
+        match (&info.created_by, &info.created) {
+            (Some(version), Some(created)) => {
+                format!("py-proj {version} {}", format!("on {}", created.chars().take(10).collect::<String>()).dimmed())
+            }
+            _ => format!("{} {}", unknown(), format!("(no {MANIFEST_FILE})").dimmed()),
+        },
+    );
+    let venv = match &info.venv {
+        Some(_) => format!(", {} .venv", human_bytes(info.venv_bytes)),
+        None => String::new(),
+    };
+    field("Size:", format!("{} project{venv}", human_bytes(info.project_bytes)));
+}
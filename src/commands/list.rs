@@ -0,0 +1,168 @@
+//! `py-proj list`: find py-proj projects under a directory.
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::util::{dir_size, glob_matches, human_bytes, read_pyproject, venv_dir_name, venv_python_version};
+
+/// Directories never worth descending into.
+const SKIP_DIRS: &[&str] = &[".venv", "node_modules", ".git", "__pycache__"];
+
+#[derive(Debug, Serialize)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub python: Option<String>,
+    /// True when found via the manifest, false for a pyproject.toml fingerprint match.
+    pub managed: bool,
+    pub venv_bytes: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// Walk `dir` up to `max_depth` levels, returning every project found. Directories
+/// whose path relative to `dir` matches one of the `exclude` globs are not entered.
+pub fn find_projects(dir: &Path, max_depth: usize, exclude: &[String]) -> Result<Vec<ProjectInfo>> {
+    let mut found = Vec::new();
+    walk(dir, "", 0, &Walk { max_depth, exclude }, &mut found);
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(found)
+}
+
+/// Keep projects untouched for at least `days`.
+pub fn filter_stale(projects: Vec<ProjectInfo>, days: u64) -> Vec<ProjectInfo> {
+    let cutoff = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+    let cutoff = DateTime::<Utc>::from(cutoff);
+    projects.into_iter().filter(|p| p.modified < cutoff).collect()
+}
+
+pub fn print_table(projects: &[ProjectInfo]) {
+    let name_w = projects.iter().map(|p| p.name.len()).max().unwrap_or(0).max(4);
+    println!(
+        "  {}",
+        format!("{:name_w$}  {:8}  {:>9}  {:16}  PATH", "NAME", "PYTHON", ".VENV", "MODIFIED")
+            .bold()
+    );
+    for p in projects {
+        let python = p.python.as_deref().unwrap_or("?");
+        let venv = if p.venv_bytes == 0 { "-".to_string() } else { human_bytes(p.venv_bytes) };
+        let modified = p.modified.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+        let name = format!("{:name_w$}", p.name);
+        let name = if p.managed { name.blue().bold().to_string() } else { name.dimmed().to_string() };
+        println!(
+            "  {name}  {python:8}  {venv:>9}  {modified:16}  {}",
+            p.path.display().to_string().dimmed()
+        );
+    }
+}
+
+/// The limits of a [`find_projects`] walk.
+struct Walk<'a> {
+    max_depth: usize,
+    exclude: &'a [String],
+}
+
+fn walk(dir: &Path, rel: &str, depth: usize, limits: &Walk<'_>, found: &mut Vec<ProjectInfo>) {
+    if let Some(info) = inspect(dir) {
+        found.push(info);
+    }
+    if depth >= limits.max_depth {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // `file_type` does not follow symlinks, so linked directories (and cycles) are skipped.
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        let name = entry.file_name();
+        if !is_dir || SKIP_DIRS.iter().any(|s| name == *s) {
+            continue;
+        }
+        let rel = match rel {
+            "" => name.to_string_lossy().into_owned(),
+            _ => format!("{rel}/{}", name.to_string_lossy()),
+        };
+        if !limits.exclude.iter().any(|pattern| glob_matches(pattern, &rel)) {
+            walk(&entry.path(), &rel, depth + 1, limits, found);
+        }
+    }
+}
+
+/// Describe `dir` if it is a py-proj project (or looks like one).
+fn inspect(dir: &Path) -> Option<ProjectInfo> {
+    let (name, python, managed) = match Manifest::load(dir) {
+        Ok(Some(m)) => (m.options.project, Some(m.options.python), true),
+        _ if looks_generated(dir) => {
+            let name = read_pyproject(dir)
+                .and_then(|t| t.get("project")?.get("name")?.as_str().map(str::to_string))
+                .unwrap_or_else(|| dir.file_name().unwrap_or_default().to_string_lossy().into_owned());
+            (name, venv_python_version(dir), false)
+        }
+        _ => return None,
+    };
+    Some(ProjectInfo {
+        name,
+        path: dir.to_path_buf(),
+        python,
+        managed,
+        venv_bytes: dir_size(&dir.join(venv_dir_name(dir))),
+        modified: DateTime::<Utc>::from(last_modified(dir)),
+    })
+}
+
+/// Our fingerprint for projects scaffolded before the manifest existed: pyrefly.toml
+/// next to a pyproject.toml whose `[tool.ruff]` excludes `.venv` and has a rendered
+/// `target-version` (an exported template pack still has placeholders there).
+fn looks_generated(dir: &Path) -> bool {
+    if dir.join(MANIFEST_FILE).exists() || !dir.join("pyrefly.toml").is_file() {
+        return false;
+    }
+    let Some(doc) = read_pyproject(dir) else {
+        return false;
+    };
+    let Some(ruff) = doc.get("tool").and_then(|t| t.get("ruff")) else {
+        return false;
+    };
+    let excludes_venv = ruff
+        .get("extend-exclude")
+        .and_then(|e| e.as_array())
+        .is_some_and(|e| e.iter().any(|v| v.as_str() == Some(".venv")));
+    let rendered = ruff
+        .get("target-version")
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.strip_prefix("py"))
+        .is_some_and(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit()));
+    excludes_venv && rendered
+}
+
+/// Newest modification time of the project's own files (caches and the venv excluded).
+fn last_modified(dir: &Path) -> SystemTime {
+    fn newest(dir: &Path, depth: usize, best: &mut SystemTime) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if let Ok(m) = meta.modified() {
+                *best = (*best).max(m);
+            }
+            let name = entry.file_name();
+            let skip = SKIP_DIRS.iter().any(|s| name == *s);
+            if entry.file_type().is_ok_and(|t| t.is_dir()) && !skip && depth < 8 {
+                newest(&entry.path(), depth + 1, best);
+            }
+        }
+    }
+
+    let mut best = SystemTime::UNIX_EPOCH;
+    newest(dir, 0, &mut best);
+    best
+}
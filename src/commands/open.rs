@@ -0,0 +1,150 @@
+//! `py-proj open [path]`, and `--open` after a create: launch an editor on a project.
+//! GUI editors start detached, so py-proj exits without waiting for them; terminal
+//! editors (`$VISUAL`, `$EDITOR`, or a known one like vim) get the terminal and
+//! py-proj waits until they quit.
+
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use pyproj::util::{find_project_root, home_dir, interruptible};
+
+/// Editors that draw in the terminal they are started from.
+const TERMINAL_EDITORS: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "pico", "micro", "hx", "helix", "kak", "emacs", "mg", "ne", "joe", "ed",
+];
+
+/// One editor command to try, and what named it (for the error when none is found).
+struct Candidate {
+    program: String,
+    args: Vec<String>,
+    source: &'static str,
+    /// Run attached to the terminal instead of detached.
+    terminal: bool,
+}
+
+/// Open the project at or above `start` in the editor that `editor` (`--editor`),
+/// `$PY_PROJ_EDITOR`, or `configured` (the config file's `editor`) names, else VS Code,
+/// else `$VISUAL` or `$EDITOR`.
+pub fn open_project(start: &Path, editor: Option<&str>, configured: Option<&str>) -> Result<()> {
+    let Some(root) = find_project_root(start) else {
+        return Err(pyproj::Error::project_not_found(start, "py-proj open path/to/proj").into());
+    };
+    open_root(&root, editor, configured)
+}
+
+/// [`open_project`] on a known project root, as `--open` does after a create.
+pub fn open_root(root: &Path, editor: Option<&str>, configured: Option<&str>) -> Result<()> {
+    let candidates = candidates(editor, configured);
+    let mut tried = Vec::new();
+    for candidate in &candidates {
+        let Some(path) = resolve(&candidate.program) else {
+            tried.push(format!("{} ({})", candidate.program, candidate.source));
+            continue;
+        };
+        let mut cmd = Command::new(&path);
+        cmd.args(&candidate.args).arg(root);
+        return match candidate.terminal {
+            true => {
+                let status = interruptible(|| cmd.status())
+                    .with_context(|| format!("failed to run {}", path.display()))?;
+                if !status.success() {
+                    bail!("`{}` exited with {status}", candidate.program);
+                }
+                Ok(())
+            }
+            false => spawn_detached(&mut cmd).with_context(|| format!("failed to start {}", path.display())),
+        };
+    }
+    bail!(
+        "no editor found to open {}; tried: {}. Pass --editor <COMMAND>, set $PY_PROJ_EDITOR, \
+         or set `editor` in the config file",
+        root.display(),
+        tried.join(", ")
+    )
+}
+
+/// The editors to try, in order. An editor named on purpose is the only candidate, so a
+/// typo fails instead of opening something else.
+fn candidates(editor: Option<&str>, configured: Option<&str>) -> Vec<Candidate> {
+    let named = [
+        (editor.map(str::to_string), "--editor"),
+        (env::var("PY_PROJ_EDITOR").ok(), "$PY_PROJ_EDITOR"),
+        (configured.map(str::to_string), "config editor"),
+    ];
+    for (command, source) in named {
+        if let Some(candidate) = command.and_then(|c| Candidate::parse(&c, source, None)) {
+            return vec![candidate];
+        }
+    }
+    let mut candidates: Vec<Candidate> = Candidate::parse("code", "default", Some(false)).into_iter().collect();
+    for app in vscode_app_paths() {
+        candidates.push(Candidate {
+            program: app.to_string_lossy().into_owned(),
+            args: Vec::new(),
+            source: "VS Code app",
+            terminal: false,
+        });
+    }
+    for (var, source) in [("VISUAL", "$VISUAL"), ("EDITOR", "$EDITOR")] {
+        let editor = env::var(var).ok();
+        candidates.extend(editor.and_then(|e| Candidate::parse(&e, source, Some(true))));
+    }
+    candidates
+}
+
+impl Candidate {
+    /// `subl -n` -> program `subl` with argument `-n`; `None` for a blank command.
+    /// Unless `terminal` says, known terminal editors run attached and others detached.
+    fn parse(command: &str, source: &'static str, terminal: Option<bool>) -> Option<Candidate> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words.next()?;
+        Some(Candidate {
+            terminal: terminal.unwrap_or_else(|| is_terminal_editor(&program)),
+            program,
+            args: words.collect(),
+            source,
+        })
+    }
+}
+
+/// Where the `code` launcher sits inside an installed VS Code on macOS, for when
+/// "Install 'code' command in PATH" was never run.
+fn vscode_app_paths() -> Vec<PathBuf> {
+    if !cfg!(target_os = "macos") {
+        return Vec::new();
+    }
+    let app = Path::new("Visual Studio Code.app/Contents/Resources/app/bin/code");
+    let mut paths = vec![Path::new("/Applications").join(app)];
+    if let Some(home) = home_dir() {
+        paths.push(home.join("Applications").join(app));
+    }
+    paths
+}
+
+fn is_terminal_editor(program: &str) -> bool {
+    let name = Path::new(program).file_stem().unwrap_or_default().to_string_lossy();
+    TERMINAL_EDITORS.contains(&name.as_ref())
+}
+
+/// `program` as a path to run: as given when it has a directory part, else from `PATH`.
+fn resolve(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    match path.components().count() > 1 {
+        true => path.is_file().then(|| path.to_path_buf()),
+        false => which::which(program).ok(),
+    }
+}
+
+/// Start `cmd` without waiting for it, its output discarded, and (on Unix) in its own
+/// process group, so a Ctrl-C in this terminal does not reach it.
+fn spawn_detached(cmd: &mut Command) -> std::io::Result<()> {
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd.spawn().map(drop)
+}
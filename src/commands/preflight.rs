@@ -0,0 +1,38 @@
+//! The preflight table a create prints before it writes anything: one row per check
+//! from [`pyproj::preflight::run`], then GO, GO with warnings, or NO-GO.
+
+use owo_colors::OwoColorize;
+
+use pyproj::preflight::{Check, Status};
+
+/// Print `checks` as a table; with `quiet`, only the ones that are not a pass.
+pub fn print(checks: &[Check], quiet: bool) {
+    let shown: Vec<&Check> = checks.iter().filter(|c| !quiet || c.status != Status::Pass).collect();
+    if shown.is_empty() {
+        return;
+    }
+    println!("{} {}", ">>".cyan().bold(), "Preflight".bold());
+    let width = shown.iter().map(|c| c.name.len()).max().unwrap_or_default();
+    for c in shown {
+        let mark = match c.status {
+            Status::Pass => "✓".green().bold().to_string(),
+            Status::Warn => "!".yellow().bold().to_string(),
+            Status::Fail => "✗".red().bold().to_string(),
+        };
+        let name = format!("{:width$}", c.name);
+        match c.ignored {
+            true => println!("  {mark} {} {} {}", name.dimmed(), c.detail.dimmed(), "(ignored)".dimmed()),
+            false => println!("  {mark} {} {}", name.bold(), c.detail.dimmed()),
+        }
+        if let (Some(hint), false) = (&c.hint, c.ignored) {
+            println!("    {:width$} {} {hint}", "", "hint:".cyan());
+        }
+    }
+    if checks.iter().any(Check::blocks) {
+        println!("  {}", "NO-GO".red().bold());
+    } else if checks.iter().any(Check::warns) {
+        println!("  {}", "GO with warnings".yellow().bold());
+    } else if !quiet {
+        println!("  {}", "GO".green().bold());
+    }
+}
@@ -0,0 +1,181 @@
+//! `py-proj rename-project`: change the project name everywhere py-proj put it.
+
+use anyhow::{bail, Context, Result};
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::Path;
+use toml_edit::DocumentMut;
+
+use super::diff::print_unified;
+use pyproj::manifest::Manifest;
+use pyproj::util::{dist_name, import_name, run_output, sha256_hex, validate_project_name, write};
+
+pub struct RenameOptions<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    /// Rename even with uncommitted git changes.
+    pub force: bool,
+    /// Show the edits without writing anything.
+    pub dry_run: bool,
+}
+
+pub fn rename_project(root: &Path, opts: &RenameOptions) -> Result<()> {
+    validate_project_name(opts.to)?;
+    if opts.from == opts.to {
+        bail!("the new name is the same as the old one");
+    }
+    if !opts.force && has_uncommitted_changes(root)? {
+        bail!(
+            "{} has uncommitted changes; commit or stash them first, or pass --force",
+            root.display()
+        );
+    }
+
+    let mut manifest = Manifest::load(root)?;
+    if let Some(m) = &manifest {
+        if m.options.project != opts.from {
+            bail!(
+                "the manifest says this project is `{}`, not `{}`",
+                m.options.project,
+                opts.from
+            );
+        }
+    }
+
+    let mut edits = Vec::new();
+    for rel in ["pyproject.toml", "pyrefly.toml"] {
+        if let Some(edit) = rename_toml(root, rel, opts)? {
+            edits.push(edit);
+        }
+    }
+    if let Some(edit) = rename_readme(root, opts)? {
+        edits.push(edit);
+    }
+    // The package keeps its import name; only the docstring names the project. Where
+    // it lives depends on the layout, which the manifest's file list records.
+    let mains: Vec<String> = match &manifest {
+        Some(m) => m.files.iter().map(|f| f.path.clone()).filter(|p| p.ends_with("__main__.py")).collect(),
+        None => vec!["src/__main__.py".to_string(), format!("src/{}/__main__.py", import_name(opts.from))],
+    };
+    for rel in mains {
+        if let Some(edit) = rename_dunder_main(root, &rel, opts)? {
+            edits.push(edit);
+        }
+    }
+    if edits.is_empty() && manifest.is_none() {
+        bail!("`{}` was not found in pyproject.toml, pyrefly.toml, or README.md", opts.from);
+    }
+
+    for (rel, old, new) in &edits {
+        println!("  {} {}", "modify".green(), rel);
+        if opts.dry_run {
+            print_unified(rel, old, new);
+            continue;
+        }
+        write(root.join(rel), new)?;
+        // Files still identical to what py-proj generated stay "unedited" for upgrade.
+        if let Some(m) = manifest.as_mut() {
+            if let Some(entry) = m.files.iter_mut().find(|f| f.path == *rel) {
+                if entry.sha256 == sha256_hex(old) {
+                    entry.sha256 = sha256_hex(new);
+                }
+            }
+        }
+    }
+
+    if let Some(m) = manifest.as_mut() {
+        println!("  {} {}", "modify".green(), pyproj::manifest::MANIFEST_FILE);
+        if !opts.dry_run {
+            m.options.project = opts.to.to_string();
+            m.write(root)?;
+        }
+    }
+    Ok(())
+}
+
+/// Set `[project] name` in a TOML file, if it currently holds the old name.
+fn rename_toml(root: &Path, rel: &str, opts: &RenameOptions) -> Result<Option<(String, String, String)>> {
+    let Ok(old) = fs::read_to_string(root.join(rel)) else {
+        return Ok(None);
+    };
+    let mut doc: DocumentMut = old.parse().with_context(|| format!("invalid TOML in {rel}"))?;
+    let Some(name) = doc.get_mut("project").and_then(|p| p.get_mut("name")) else {
+        return Ok(None);
+    };
+    // pyproject.toml holds the normalized distribution name, e.g. `my-proj` for `My_Proj`.
+    let dist = rel == "pyproject.toml";
+    let (from, to) = match dist {
+        true => (dist_name(opts.from), dist_name(opts.to)),
+        false => (opts.from.to_string(), opts.to.to_string()),
+    };
+    match name.as_str() {
+        Some(current) if current == from || (dist && dist_name(current) == from) => {}
+        Some(current) => bail!("{rel} names the project `{current}`, not `{}`", opts.from),
+        None => return Ok(None),
+    }
+    // Keep any trailing comment on the line.
+    let decor = name.as_value().map(|v| v.decor().clone());
+    *name = toml_edit::value(to);
+    if let (Some(decor), Some(value)) = (decor, name.as_value_mut()) {
+        *value.decor_mut() = decor;
+    }
+    Ok(Some((rel.to_string(), old, doc.to_string())))
+}
+
+/// Rewrite the generated `# name` heading, `cd name` line, and `name/` top of the
+/// structure tree in README.md.
+fn rename_readme(root: &Path, opts: &RenameOptions) -> Result<Option<(String, String, String)>> {
+    let rel = "README.md";
+    let Ok(old) = fs::read_to_string(root.join(rel)) else {
+        return Ok(None);
+    };
+    let heading = format!("# {}", opts.from);
+    let cd = format!("cd {}", opts.from);
+    let tree = format!("{}/", opts.from);
+    let new: String = old
+        .split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let ending = &line[body.len()..];
+            if body == heading {
+                format!("# {}{ending}", opts.to)
+            } else if body == cd {
+                format!("cd {}{ending}", opts.to)
+            } else if body == tree {
+                format!("{}/{ending}", opts.to)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    Ok((new != old).then(|| (rel.to_string(), old, new)))
+}
+
+/// Rewrite the name in the generated "Run name with `python -m ...`" docstring of `rel`.
+fn rename_dunder_main(root: &Path, rel: &str, opts: &RenameOptions) -> Result<Option<(String, String, String)>> {
+    let Ok(old) = fs::read_to_string(root.join(rel)) else {
+        return Ok(None);
+    };
+    let from = format!("\"\"\"Run {} with `python -m ", opts.from);
+    let to = format!("\"\"\"Run {} with `python -m ", opts.to);
+    let new: String = old
+        .split_inclusive('\n')
+        .map(|line| match line.strip_prefix(&from) {
+            Some(rest) => format!("{to}{rest}"),
+            None => line.to_string(),
+        })
+        .collect();
+    Ok((new != old).then(|| (rel.to_string(), old, new)))
+}
+
+/// True when `root` is inside a git work tree with uncommitted changes.
+pub fn has_uncommitted_changes(root: &Path) -> Result<bool> {
+    if which::which("git").is_err() {
+        return Ok(false);
+    }
+    match run_output("git", &["status", "--porcelain"], root) {
+        Ok(status) => Ok(!status.trim().is_empty()),
+        // Not a git repository.
+        Err(_) => Ok(false),
+    }
+}
@@ -0,0 +1,134 @@
+//! `py-proj repair`: put back the pieces of a scaffold that went missing (a deleted
+//! pyproject.toml, `tests/`, `.venv`), re-rendered from the options in the manifest.
+//! Files that are there are never touched, edited or not; edited ones are only listed.
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::Path;
+
+use pyproj::lock::ProjectLock;
+use pyproj::manifest::{FileEntry, Manifest, MANIFEST_FILE};
+use pyproj::scaffold::ScaffoldPlan;
+use pyproj::util::{format_mode, is_safe_relative, sha256_hex, write_with_mode, WritePolicy};
+use pyproj::Event;
+
+pub struct RepairOptions {
+    /// Print what would be restored without writing anything.
+    pub dry_run: bool,
+    /// Restore files and directories only, even when the venv is gone.
+    pub skip_uv: bool,
+    pub verbose: bool,
+}
+
+/// What a repair put back and found.
+#[derive(Debug, Default)]
+pub struct Repaired {
+    pub files: usize,
+    pub dirs: usize,
+    pub venv: bool,
+    /// Generated files edited since; left as they are.
+    pub modified: usize,
+    /// Missing files the recorded options no longer render (a template gone from the
+    /// `--templates-dir`, say); left missing.
+    pub unrestorable: usize,
+}
+
+pub fn repair(root: &Path, opts: &RepairOptions) -> Result<Repaired> {
+    let printer = super::printer(opts.verbose);
+    let _lock = match opts.dry_run {
+        true => None,
+        false => Some(ProjectLock::acquire(root, "repair", false, &|message| {
+            printer(&Event::Warning { message })
+        })?),
+    };
+    let mut manifest = Manifest::load(root)?.with_context(|| {
+        format!("no {MANIFEST_FILE} in {}; repair rebuilds a scaffold from it", root.display())
+    })?;
+    let mut plan = ScaffoldPlan::from_manifest(root, &manifest, super::printer(opts.verbose))?;
+    let rendered = plan.render_files()?;
+    let mut repaired = Repaired::default();
+
+    for dir in &manifest.options.dirs {
+        let path = root.join(dir);
+        if !is_safe_relative(Path::new(dir)) || path.exists() {
+            continue;
+        }
+        println!("  {} {}/", "mkdir".green(), dir);
+        repaired.dirs += 1;
+        if !opts.dry_run {
+            plan.create_dir(&path)?;
+        }
+    }
+
+    let mut restored = Vec::new();
+    for entry in &manifest.files {
+        let rel = entry.path.as_str();
+        if !is_safe_relative(Path::new(rel)) {
+            continue;
+        }
+        let path = root.join(rel);
+        match fs::read(&path) {
+            Ok(bytes) if sha256_hex(&bytes) == entry.sha256 => {
+                if opts.verbose {
+                    println!("  {} {}", "ok".dimmed(), rel.dimmed());
+                }
+                continue;
+            }
+            Ok(_) => {
+                println!("  {} {} {}", "modified".yellow(), rel, "(left as is)".dimmed());
+                repaired.modified += 1;
+                continue;
+            }
+            Err(_) => {}
+        }
+        if manifest.preexisting.files.iter().any(|f| f == rel) {
+            // It was there before py-proj; what it held is not py-proj's to bring back.
+            println!("  {} {} {}", "skip".yellow(), rel, "(not created by py-proj)".dimmed());
+            continue;
+        }
+        let Some((_, bytes)) = rendered.iter().find(|(p, _)| p == rel) else {
+            println!("  {} {} {}", "missing".red(), rel, "(no template renders it any more)".dimmed());
+            repaired.unrestorable += 1;
+            continue;
+        };
+        println!("  {} {}", "restore".green(), rel);
+        repaired.files += 1;
+        if !opts.dry_run {
+            if let Some(parent) = path.parent() {
+                plan.create_dir(parent)?;
+            }
+            write_with_mode(&path, bytes, WritePolicy::Overwrite, manifest.mode_of(rel))?;
+        }
+        restored.push((rel.to_string(), sha256_hex(bytes)));
+    }
+
+    let venv = root.join(plan.venv_dir());
+    if !venv.exists() && plan.workspace_root.is_none() {
+        if manifest.preexisting.venv {
+            // --skip-uv, or one the project already had: not py-proj's to make.
+            println!("  {} {} {}", "skip".yellow(), plan.venv_dir(), "(not created by py-proj)".dimmed());
+        } else if opts.skip_uv {
+            println!("  {} {} {}", "skip".yellow(), plan.venv_dir(), "(--skip-uv)".dimmed());
+        } else {
+            println!("  {} {}", "venv".green(), plan.venv_dir());
+            repaired.venv = true;
+            if !opts.dry_run {
+                // Only the project's own venv; the uv tools are not part of the project.
+                plan.tools_global = false;
+                plan.install_uv_toolchain()?;
+            }
+        }
+    }
+
+    if !opts.dry_run && !restored.is_empty() {
+        for (rel, sha256) in restored {
+            let mode = manifest.mode_of(&rel).map(format_mode);
+            if let Some(entry) = manifest.files.iter_mut().find(|f| f.path == rel) {
+                *entry = FileEntry { path: rel, sha256, mode };
+            }
+        }
+        manifest.write(root)?;
+    }
+    Ok(repaired)
+}
@@ -0,0 +1,274 @@
+//! The report of a `--create_project` run: what `--json` prints and `--summary-file`
+//! writes, one schema for both. It is filled in from the scaffold's events as it runs,
+//! so a run that fails partway still has one.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::summary::{self, NextStep, TreeEntry};
+use pyproj::manifest::Options;
+use pyproj::preflight::Check;
+use pyproj::scaffold::{PythonPreference, WriteSummary};
+use pyproj::util::{self, sha256_hex};
+use pyproj::{Event, ScaffoldPlan, WriteOutcome};
+
+/// Where `--summary-file` without a value writes, relative to the project root.
+pub const DEFAULT_REPORT_FILE: &str = ".pyproj-report.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    DryRun,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateReport {
+    pub status: Status,
+    pub root: PathBuf,
+    pub project: String,
+    pub python: String,
+    pub dry_run: bool,
+    /// The resolved options, as the manifest records them.
+    pub options: Options,
+    /// How many files were created, updated, ...; `None` in a dry run or a failed one.
+    pub files: Option<FileCounts>,
+    /// Every file the run wrote or left alone, in the order it got to them.
+    pub written: Vec<FileReport>,
+    /// The external commands run, in order.
+    pub commands: Vec<CommandReport>,
+    pub offline_skipped: Vec<&'static str>,
+    pub uv: UvReport,
+    pub tree: Vec<TreeEntry>,
+    pub next_steps: Vec<NextStep>,
+    /// The preflight checks run before the create, with what they found.
+    pub preflight: Vec<Check>,
+    pub warnings: Vec<String>,
+    /// The error, then what caused it, when `status` is `failed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Vec<String>>,
+    /// `--timings`.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+    /// `--sbom`: what [`super::sbom::write_sbom`] wrote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sbom: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileCounts {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: Vec<PathBuf>,
+    pub backed_up: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    /// Relative to the project root.
+    pub path: PathBuf,
+    /// `created`, `updated`, `unchanged`, `skipped`, or `backed_up`.
+    pub outcome: &'static str,
+    /// Of the file on disk once the run was over; absent when it is gone (rolled back).
+    pub sha256: Option<String>,
+    pub bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandReport {
+    pub label: String,
+    /// The command line, shell-quoted for display.
+    pub command: String,
+    /// `None` when the run stopped before the command finished.
+    pub success: Option<bool>,
+    pub exit_code: Option<i32>,
+    pub ms: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UvReport {
+    pub bin: String,
+    pub python_preference: Option<&'static str>,
+    pub python_already_installed: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Timings {
+    pub timings: Vec<PhaseTiming>,
+    pub total_ms: f64,
+    pub overlapped_ms: f64,
+    pub parallel_saving_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub ms: f64,
+}
+
+/// Collects a [`CreateReport`]'s files, commands, and warnings from the scaffold's
+/// events (see [`Reporter::record`]), for [`Reporter::report`] and [`Reporter::failed`].
+pub struct Reporter {
+    cwd: PathBuf,
+    manifest: bool,
+    preflight: RefCell<Vec<Check>>,
+    files: RefCell<Vec<FileReport>>,
+    commands: RefCell<Vec<CommandReport>>,
+    warnings: RefCell<Vec<String>>,
+}
+
+impl Reporter {
+    /// `manifest`: whether the run writes `.pyproj.toml` (it shows in the tree).
+    pub fn new(cwd: &Path, manifest: bool) -> Reporter {
+        Reporter {
+            cwd: cwd.to_path_buf(),
+            manifest,
+            preflight: RefCell::default(),
+            files: RefCell::default(),
+            commands: RefCell::default(),
+            warnings: RefCell::default(),
+        }
+    }
+
+    /// Keep the preflight checks for the report.
+    pub fn set_preflight(&self, checks: Vec<Check>) {
+        *self.preflight.borrow_mut() = checks;
+    }
+
+    pub fn record(&self, event: &Event<'_>) {
+        match event {
+            Event::FileWritten { path, outcome, .. } => self.files.borrow_mut().push(FileReport {
+                path: path.to_path_buf(),
+                outcome: outcome_name(outcome),
+                sha256: None,
+                bytes: None,
+            }),
+            Event::CommandStarted { label, command, .. } => self.commands.borrow_mut().push(CommandReport {
+                label: label.trim().to_string(),
+                command: command.to_string(),
+                success: None,
+                exit_code: None,
+                ms: None,
+            }),
+            Event::CommandFinished { success, elapsed, exit_code } => {
+                if let Some(last) = self.commands.borrow_mut().last_mut() {
+                    last.success = Some(*success);
+                    last.exit_code = *exit_code;
+                    last.ms = Some(ms(*elapsed));
+                }
+            }
+            Event::Warning { message } => self.warnings.borrow_mut().push(message.clone()),
+            _ => {}
+        }
+    }
+
+    /// The report of a run that got through: `written` is `None` for a dry run.
+    pub fn report(&self, plan: &ScaffoldPlan, written: Option<&WriteSummary>) -> Result<CreateReport> {
+        let status = match written {
+            Some(_) => Status::Ok,
+            None => Status::DryRun,
+        };
+        self.build(plan, status, written)
+    }
+
+    /// The report of a run that stopped with `err`, with what it did up to then.
+    pub fn failed(&self, plan: &ScaffoldPlan, err: &anyhow::Error) -> Result<CreateReport> {
+        let mut report = self.build(plan, Status::Failed, None)?;
+        report.error = Some(err.chain().map(ToString::to_string).collect());
+        Ok(report)
+    }
+
+    fn build(&self, plan: &ScaffoldPlan, status: Status, written: Option<&WriteSummary>) -> Result<CreateReport> {
+        let files = self
+            .files
+            .borrow()
+            .iter()
+            .map(|f| {
+                let bytes = fs::read(plan.root.join(&f.path)).ok();
+                FileReport {
+                    path: f.path.clone(),
+                    outcome: f.outcome,
+                    sha256: bytes.as_ref().map(sha256_hex),
+                    bytes: bytes.map(|b| b.len() as u64),
+                }
+            })
+            .collect();
+        Ok(CreateReport {
+            status,
+            root: std::path::absolute(&plan.root)?,
+            project: plan.project.clone(),
+            python: plan.python.to_string(),
+            dry_run: status == Status::DryRun,
+            options: plan.manifest()?.options,
+            files: written.map(|s| FileCounts {
+                created: s.created,
+                updated: s.updated,
+                unchanged: s.unchanged,
+                skipped: s.skipped.clone(),
+                backed_up: s.backed_up.clone(),
+            }),
+            written: files,
+            commands: self.commands.borrow().clone(),
+            offline_skipped: plan.offline_skips(),
+            uv: UvReport {
+                bin: util::resolved_uv_bin(),
+                python_preference: plan.python_preference.map(PythonPreference::name),
+                python_already_installed: written
+                    .and_then(|s| s.python_already_installed.as_ref().map(ToString::to_string)),
+            },
+            tree: summary::tree(plan, self.manifest)?,
+            next_steps: summary::next_steps(plan, &self.cwd),
+            preflight: self.preflight.borrow().clone(),
+            warnings: self.warnings.borrow().clone(),
+            error: None,
+            timings: None,
+            sbom: None,
+        })
+    }
+}
+
+impl CreateReport {
+    /// Add `--timings`: each phase of `summary`, and the run's `total` wall-clock time.
+    pub fn with_timings(mut self, summary: &WriteSummary, total: Duration) -> CreateReport {
+        self.timings = Some(Timings {
+            timings: summary
+                .timings
+                .iter()
+                .map(|(phase, d)| PhaseTiming { phase: phase.clone(), ms: ms(*d) })
+                .collect(),
+            total_ms: ms(total),
+            overlapped_ms: ms(summary.overlapped),
+            parallel_saving_ms: ms(summary.parallel_saving),
+        });
+        self
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Write the report to `path` (`--summary-file`).
+    pub fn write(&self, path: &Path) -> Result<()> {
+        util::write(path, self.to_json()? + "\n")
+    }
+}
+
+fn outcome_name(outcome: &WriteOutcome) -> &'static str {
+    match outcome {
+        WriteOutcome::Created => "created",
+        WriteOutcome::Updated => "updated",
+        WriteOutcome::Unchanged => "unchanged",
+        WriteOutcome::Skipped => "skipped",
+        WriteOutcome::BackedUp(_) => "backed_up",
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
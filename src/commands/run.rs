@@ -0,0 +1,77 @@
+//! `py-proj run -- <command>`: run a command in a project's environment from anywhere,
+//! through `uv run` in the project root, with the terminal handed straight to it.
+
+use anyhow::{bail, Result};
+use dialoguer::Confirm;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+use pyproj::util::{self, find_project_root, project_venv, run_captured, uv_bin, venv_dir_name, DEFAULT_VENV_DIR};
+use pyproj::Error;
+
+/// Run `argv` with `uv run` in the project at or above `start`, creating its venv
+/// first if there is none (after asking, unless `yes`). Returns the command's exit code.
+pub fn run_in_project(start: &Path, argv: &[String], yes: bool) -> Result<i32> {
+    let Some(root) = find_project_root(start) else {
+        return Err(pyproj::Error::project_not_found(start, "py-proj run -C path/to/proj -- ...").into());
+    };
+    let dir = venv_dir_name(&root);
+    if project_venv(&root)?.is_none() {
+        ensure_venv(&root, &dir, yes)?;
+    }
+    let uv = uv_bin();
+    let mut cmd = Command::new(&uv);
+    cmd.arg("run").arg("--").args(argv).current_dir(&root);
+    if dir != DEFAULT_VENV_DIR {
+        // uv only knows a `--venv-dir` venv through this; it would make a `.venv` otherwise.
+        cmd.env("UV_PROJECT_ENVIRONMENT", &dir);
+    }
+    // The command owns the terminal: a Ctrl-C reaches it directly, and py-proj only
+    // waits for it to exit instead of exiting first.
+    let status = util::interruptible(|| cmd.status()).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::MissingTool { tool: uv.clone() }.into(),
+        _ => anyhow::Error::from(e).context(format!("failed to run `{uv} run`")),
+    })?;
+    Ok(exit_code(status))
+}
+
+/// Create the missing venv `dir` with `uv venv`, which picks the Python from
+/// `.python-version` and puts a workspace member's venv at the workspace root.
+fn ensure_venv(root: &Path, dir: &str, yes: bool) -> Result<()> {
+    if !yes {
+        if !(io::stdin().is_terminal() && io::stderr().is_terminal()) {
+            return Err(Error::NeedsConfirmation {
+                action: format!("create a {dir} in {}", root.display()),
+                flag: "--yes",
+            }
+            .into());
+        }
+        let go = Confirm::new()
+            .with_prompt(format!("{} has no {dir}; create it with `uv venv`?", root.display()))
+            .default(true)
+            .interact()?;
+        if !go {
+            bail!("{} has no {dir}; create it with `uv venv` (or `uv sync`) first", root.display());
+        }
+    }
+    eprintln!("py-proj: creating {dir} in {} …", root.display());
+    let mut args = vec!["venv"];
+    if dir != DEFAULT_VENV_DIR {
+        args.push(dir);
+    }
+    run_captured(&uv_bin(), &args, root)?;
+    Ok(())
+}
+
+/// The code to exit with: the command's own, or 128 + the signal that killed it.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
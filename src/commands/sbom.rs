@@ -0,0 +1,447 @@
+//! `py-proj sbom`: a software bill of materials for a project's environment. The
+//! installed packages come from `uv pip list` in the project's venv, the project's own
+//! entry from pyproject.toml; the document is CycloneDX 1.5 or SPDX 2.3 JSON.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyproj::manifest::build_time_rfc3339;
+use pyproj::util::{dist_name, project_venv, run_output, sha256_hex, uv_bin, venv_dir_name, write};
+
+/// Where the document goes unless `-o` says otherwise, relative to the project root.
+pub const DEFAULT_OUTPUT: &str = "sbom.json";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// CycloneDX 1.5 JSON.
+    #[default]
+    Cyclonedx,
+    /// SPDX 2.3 JSON.
+    Spdx,
+}
+
+impl Format {
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Cyclonedx => "CycloneDX 1.5",
+            Format::Spdx => "SPDX 2.3",
+        }
+    }
+}
+
+pub struct SbomOptions {
+    pub format: Format,
+    /// Where the document goes, relative to the root (default: [`DEFAULT_OUTPUT`]).
+    pub output: Option<PathBuf>,
+}
+
+/// What [`write_sbom`] wrote.
+#[derive(Debug, Serialize)]
+pub struct Written {
+    pub path: PathBuf,
+    pub format: &'static str,
+    /// Installed packages listed, the project itself not counted.
+    pub packages: usize,
+}
+
+/// One entry of `uv pip list --format json`.
+#[derive(Debug, Deserialize)]
+struct Installed {
+    name: String,
+    version: String,
+}
+
+/// The project's own entry, from pyproject.toml.
+struct Project {
+    name: String,
+    version: String,
+    description: Option<String>,
+    license: Option<String>,
+}
+
+/// A package as both formats list it.
+struct Package {
+    name: String,
+    version: String,
+    purl: String,
+}
+
+impl Package {
+    fn new(name: &str, version: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            version: version.to_string(),
+            purl: purl(name, version),
+        }
+    }
+}
+
+/// Write the SBOM of the project at `root`. Fails when the project has no venv yet.
+pub fn write_sbom(root: &Path, opts: &SbomOptions) -> Result<Written> {
+    let Some(venv) = project_venv(root)? else {
+        bail!(
+            "{} has no {}; install the environment first (`uv sync`, or create the project without --skip-uv)",
+            root.display(),
+            venv_dir_name(root)
+        );
+    };
+    let installed = installed(root, &venv)?;
+    let project = project(root, &installed)?;
+    let packages: Vec<Package> = installed
+        .iter()
+        .filter(|p| dist_name(&p.name) != dist_name(&project.name))
+        .map(|p| Package::new(&p.name, &p.version))
+        .collect();
+
+    let json = match opts.format {
+        Format::Cyclonedx => {
+            let bom = CycloneDx::new(&project, &packages);
+            bom.validate()?;
+            serde_json::to_string_pretty(&bom)?
+        }
+        Format::Spdx => {
+            let doc = Spdx::new(&project, &packages);
+            doc.validate()?;
+            serde_json::to_string_pretty(&doc)?
+        }
+    };
+    let rel = opts.output.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT));
+    write(root.join(&rel), json + "\n")?;
+    Ok(Written {
+        path: rel,
+        format: opts.format.label(),
+        packages: packages.len(),
+    })
+}
+
+/// The packages installed in `venv`, sorted by name.
+fn installed(root: &Path, venv: &Path) -> Result<Vec<Installed>> {
+    let venv = venv.to_string_lossy();
+    let out = run_output(&uv_bin(), &["pip", "list", "--format", "json", "--python", &venv], root)?;
+    let mut packages: Vec<Installed> =
+        serde_json::from_str(&out).context("`uv pip list --format json` printed something other than a package list")?;
+    packages.sort_by_key(|p| dist_name(&p.name));
+    Ok(packages)
+}
+
+/// The `[project]` table's name, version, description, and license. A dynamic version
+/// is taken from the project's own install in the venv.
+fn project(root: &Path, installed: &[Installed]) -> Result<Project> {
+    let path = root.join("pyproject.toml");
+    let text = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let doc: toml::Table = toml::from_str(&text).context("invalid TOML in pyproject.toml")?;
+    let table = doc.get("project").and_then(|p| p.as_table());
+    let field = |key: &str| table.and_then(|t| t.get(key)).and_then(|v| v.as_str()).map(str::to_string);
+    let Some(name) = field("name") else {
+        bail!("pyproject.toml has no [project] name");
+    };
+    let version = field("version")
+        .or_else(|| {
+            let own = installed.iter().find(|p| dist_name(&p.name) == dist_name(&name))?;
+            Some(own.version.clone())
+        })
+        .context("pyproject.toml has no [project] version, and the project is not installed in the venv")?;
+    Ok(Project {
+        description: field("description"),
+        license: field("license"),
+        name,
+        version,
+    })
+}
+
+/// `pkg:pypi/<name>@<version>`, with the name normalized as PyPI does.
+fn purl(name: &str, version: &str) -> String {
+    let version: String = version
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "._-~".contains(c) {
+            true => c.to_string(),
+            false => format!("%{:02X}", c as u32),
+        })
+        .collect();
+    format!("pkg:pypi/{}@{version}", dist_name(name))
+}
+
+/// A UUID (version 4 layout) for the document's serial number or namespace, from the
+/// project, the time, and the process: unique per run, which is all either format asks.
+fn document_uuid(project: &Project) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let hex = sha256_hex(format!("{}@{} {nanos} {}", project.name, project.version, std::process::id()));
+    let variant = ['8', '9', 'a', 'b'][usize::from_str_radix(&hex[16..17], 16).unwrap_or(0) % 4];
+    format!("{}-{}-4{}-{variant}{}-{}", &hex[0..8], &hex[8..12], &hex[13..16], &hex[17..20], &hex[20..32])
+}
+
+/// py-proj, as the tool that made the document.
+fn tool_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+// --- CycloneDX 1.5 -------------------------------------------------------------------
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CycloneDx {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    serial_number: String,
+    version: u32,
+    metadata: CdxMetadata,
+    components: Vec<CdxComponent>,
+}
+
+#[derive(Serialize)]
+struct CdxMetadata {
+    timestamp: String,
+    tools: CdxTools,
+    component: CdxComponent,
+}
+
+#[derive(Serialize)]
+struct CdxTools {
+    components: Vec<CdxComponent>,
+}
+
+#[derive(Serialize)]
+struct CdxComponent {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    bom_ref: Option<String>,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<CdxLicense>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CdxLicense {
+    /// An SPDX license expression, as `[project] license` is.
+    expression: String,
+}
+
+impl CycloneDx {
+    fn new(project: &Project, packages: &[Package]) -> CycloneDx {
+        let library = |p: &Package| CdxComponent {
+            kind: "library",
+            bom_ref: Some(p.purl.clone()),
+            name: p.name.clone(),
+            version: p.version.clone(),
+            description: None,
+            licenses: Vec::new(),
+            purl: Some(p.purl.clone()),
+        };
+        let own = Package::new(&project.name, &project.version);
+        CycloneDx {
+            bom_format: "CycloneDX",
+            spec_version: "1.5",
+            serial_number: format!("urn:uuid:{}", document_uuid(project)),
+            version: 1,
+            metadata: CdxMetadata {
+                timestamp: build_time_rfc3339(),
+                tools: CdxTools {
+                    components: vec![CdxComponent {
+                        kind: "application",
+                        bom_ref: None,
+                        name: "py-proj".to_string(),
+                        version: tool_version().to_string(),
+                        description: None,
+                        licenses: Vec::new(),
+                        purl: None,
+                    }],
+                },
+                component: CdxComponent {
+                    kind: "application",
+                    description: project.description.clone(),
+                    licenses: project.license.iter().map(|l| CdxLicense { expression: l.clone() }).collect(),
+                    ..library(&own)
+                },
+            },
+            components: packages.iter().map(library).collect(),
+        }
+    }
+
+    /// The fields CycloneDX 1.5 requires, and the ones this document promises: every
+    /// component named and versioned, with a unique `bom-ref` and a PyPI purl.
+    fn validate(&self) -> Result<()> {
+        if self.bom_format != "CycloneDX" || self.spec_version != "1.5" {
+            bail!("invalid CycloneDX document: bomFormat/specVersion");
+        }
+        let mut refs = BTreeSet::new();
+        let components = std::iter::once(&self.metadata.component).chain(&self.components);
+        for c in components {
+            if c.name.is_empty() || c.version.is_empty() {
+                bail!("invalid CycloneDX document: a component has no name or version (`{}`)", c.name);
+            }
+            if !c.purl.as_deref().is_some_and(|p| p.starts_with("pkg:pypi/")) {
+                bail!("invalid CycloneDX document: component `{}` has no PyPI purl", c.name);
+            }
+            match &c.bom_ref {
+                Some(r) if refs.insert(r.as_str()) => {}
+                _ => bail!("invalid CycloneDX document: component `{}` has a missing or duplicate bom-ref", c.name),
+            }
+        }
+        Ok(())
+    }
+}
+
+// --- SPDX 2.3 ------------------------------------------------------------------------
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Spdx {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: SpdxCreationInfo,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<SpdxRelationship>,
+}
+
+#[derive(Serialize)]
+struct SpdxCreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    version_info: String,
+    download_location: &'static str,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license_declared: Option<String>,
+    primary_package_purpose: &'static str,
+    external_refs: Vec<SpdxExternalRef>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxRelationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+const SPDX_DOCUMENT: &str = "SPDXRef-DOCUMENT";
+
+impl Spdx {
+    fn new(project: &Project, packages: &[Package]) -> Spdx {
+        let package = |p: &Package, purpose: &'static str| SpdxPackage {
+            name: p.name.clone(),
+            spdx_id: spdx_id(&p.name),
+            version_info: p.version.clone(),
+            download_location: "NOASSERTION",
+            files_analyzed: false,
+            description: None,
+            license_declared: None,
+            primary_package_purpose: purpose,
+            external_refs: vec![SpdxExternalRef {
+                reference_category: "PACKAGE-MANAGER",
+                reference_type: "purl",
+                reference_locator: p.purl.clone(),
+            }],
+        };
+        let own = SpdxPackage {
+            description: project.description.clone(),
+            license_declared: project.license.clone(),
+            ..package(&Package::new(&project.name, &project.version), "APPLICATION")
+        };
+        let mut relationships = vec![SpdxRelationship {
+            spdx_element_id: SPDX_DOCUMENT.to_string(),
+            relationship_type: "DESCRIBES",
+            related_spdx_element: own.spdx_id.clone(),
+        }];
+        relationships.extend(packages.iter().map(|p| SpdxRelationship {
+            spdx_element_id: own.spdx_id.clone(),
+            relationship_type: "DEPENDS_ON",
+            related_spdx_element: spdx_id(&p.name),
+        }));
+        Spdx {
+            spdx_version: "SPDX-2.3",
+            data_license: "CC0-1.0",
+            spdx_id: SPDX_DOCUMENT,
+            name: format!("{}-{}", project.name, project.version),
+            document_namespace: format!(
+                "https://spdx.org/spdxdocs/{}-{}",
+                dist_name(&project.name),
+                document_uuid(project)
+            ),
+            creation_info: SpdxCreationInfo {
+                created: build_time_rfc3339(),
+                creators: vec![format!("Tool: py-proj-{}", tool_version())],
+            },
+            packages: std::iter::once(own)
+                .chain(packages.iter().map(|p| package(p, "LIBRARY")))
+                .collect(),
+            relationships,
+        }
+    }
+
+    /// The fields SPDX 2.3 requires of a document and its packages, unique and
+    /// well-formed SPDX identifiers, and relationships between packages that exist.
+    fn validate(&self) -> Result<()> {
+        let fields = [
+            ("spdxVersion", self.spdx_version),
+            ("dataLicense", self.data_license),
+            ("name", &self.name),
+            ("documentNamespace", &self.document_namespace),
+            ("creationInfo.created", &self.creation_info.created),
+        ];
+        if let Some((field, _)) = fields.iter().find(|(_, v)| v.is_empty()) {
+            bail!("invalid SPDX document: {field} is empty");
+        }
+        if self.creation_info.creators.is_empty() {
+            bail!("invalid SPDX document: creationInfo.creators is empty");
+        }
+        let mut ids = BTreeSet::from([self.spdx_id]);
+        for p in &self.packages {
+            if p.name.is_empty() || p.version_info.is_empty() || p.download_location.is_empty() {
+                bail!("invalid SPDX document: package `{}` has no name, version, or downloadLocation", p.name);
+            }
+            let well_formed = p
+                .spdx_id
+                .strip_prefix("SPDXRef-")
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || ".-".contains(c)));
+            if !well_formed || !ids.insert(p.spdx_id.as_str()) {
+                bail!("invalid SPDX document: package `{}` has a malformed or duplicate SPDXID", p.name);
+            }
+        }
+        for r in &self.relationships {
+            if !ids.contains(r.spdx_element_id.as_str()) || !ids.contains(r.related_spdx_element.as_str()) {
+                bail!("invalid SPDX document: a relationship names an unknown element");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `SPDXRef-Package-<name>`, the name normalized and kept to the letters SPDX allows.
+fn spdx_id(name: &str) -> String {
+    format!("SPDXRef-Package-{}", dist_name(name).replace(|c: char| !(c.is_ascii_alphanumeric() || c == '.'), "-"))
+}
@@ -0,0 +1,316 @@
+//! `py-proj stats`: what the history log says about how py-proj is used on this machine:
+//! runs per subcommand, the most used flags, how long the uv steps take, what fails,
+//! and what `--clean_project` freed. Everything is computed from the local log.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use pyproj::history::Entry;
+use pyproj::util::human_bytes;
+
+/// How many of the most used flags and error kinds are listed.
+const TOP: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub runs: usize,
+    /// Time of the first and last run counted, RFC 3339.
+    pub first: Option<String>,
+    pub last: Option<String>,
+    /// Runs from before durations were recorded; they count everywhere but in the times.
+    pub without_durations: usize,
+    pub commands: Vec<CommandStats>,
+    pub flags: Vec<Count>,
+    /// The uv steps of creates, e.g. `uv venv`, with their flags and arguments dropped.
+    pub uv_phases: Vec<Timing>,
+    pub failures: Failures,
+    pub cleaned: Cleaned,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: usize,
+    pub failed: usize,
+    /// Of the runs with a duration; `None` when none has one.
+    pub median_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Timing {
+    pub phase: String,
+    pub runs: usize,
+    pub median_ms: f64,
+    pub p90_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Failures {
+    pub failed: usize,
+    /// Failed runs over all runs, 0 to 1.
+    pub rate: f64,
+    /// By error kind (see `--json` errors); `unknown` for runs from before kinds were recorded.
+    pub kinds: Vec<Count>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Cleaned {
+    pub runs: usize,
+    pub freed_bytes: u64,
+    /// Bytes freed per month (`YYYY-MM`), oldest first.
+    pub by_month: Vec<Month>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Month {
+    pub month: String,
+    pub freed_bytes: u64,
+}
+
+/// Parse `--since`: a date (`2026-01-31`, from midnight UTC) or an RFC 3339 time.
+pub fn parse_since(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc());
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| format!("invalid date `{s}`; expected YYYY-MM-DD or an RFC 3339 time"))
+}
+
+/// The entries from `since` on (all without it); ones whose time does not parse are dropped.
+pub fn since(entries: Vec<Entry>, since: Option<DateTime<Utc>>) -> Vec<Entry> {
+    entries
+        .into_iter()
+        .filter(|e| {
+            let time = DateTime::parse_from_rfc3339(&e.time).map(|t| t.with_timezone(&Utc));
+            time.is_ok_and(|t| since.is_none_or(|s| t >= s))
+        })
+        .collect()
+}
+
+pub fn aggregate(entries: &[Entry]) -> Stats {
+    let failed = |e: &Entry| e.outcome.starts_with("failed");
+
+    let mut commands: BTreeMap<&str, (usize, usize, Vec<f64>)> = BTreeMap::new();
+    let mut flags: BTreeMap<String, usize> = BTreeMap::new();
+    let mut phases: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut months: BTreeMap<String, u64> = BTreeMap::new();
+    let mut cleans = 0;
+    for e in entries {
+        let command = commands.entry(&e.command).or_default();
+        command.0 += 1;
+        command.2.extend(e.ms);
+        if failed(e) {
+            command.1 += 1;
+            let kind = match e.format {
+                1 => "unknown",
+                _ => e.error_kind.as_deref().unwrap_or("Other"),
+            };
+            *kinds.entry(kind).or_default() += 1;
+        }
+        let mut seen: Vec<&str> = Vec::new();
+        for flag in e.args.iter().filter_map(|a| flag_name(a)) {
+            if !seen.contains(&flag) {
+                seen.push(flag);
+                *flags.entry(flag.to_string()).or_default() += 1;
+            }
+        }
+        for p in &e.phases {
+            if let Some(step) = uv_step(&p.phase) {
+                phases.entry(step).or_default().push(p.ms);
+            }
+        }
+        if let Some(freed) = e.freed {
+            cleans += 1;
+            *months.entry(e.time.chars().take(7).collect()).or_default() += freed;
+        }
+    }
+
+    let failures = entries.iter().filter(|e| failed(e)).count();
+    Stats {
+        runs: entries.len(),
+        first: entries.first().map(|e| e.time.clone()),
+        last: entries.last().map(|e| e.time.clone()),
+        without_durations: entries.iter().filter(|e| e.format < 2).count(),
+        commands: sorted_by_count(commands.into_iter().map(|(command, (runs, failed, mut ms))| {
+            ms.sort_by(f64::total_cmp);
+            let stats = CommandStats {
+                command: command.to_string(),
+                runs,
+                failed,
+                median_ms: percentile(&ms, 50.0),
+                p90_ms: percentile(&ms, 90.0),
+            };
+            (runs, stats)
+        })),
+        flags: top(flags.into_iter().map(|(name, count)| Count { name, count })),
+        uv_phases: sorted_by_count(phases.into_iter().map(|(phase, mut ms)| {
+            ms.sort_by(f64::total_cmp);
+            let timing = Timing {
+                phase,
+                runs: ms.len(),
+                median_ms: percentile(&ms, 50.0).unwrap_or_default(),
+                p90_ms: percentile(&ms, 90.0).unwrap_or_default(),
+                max_ms: ms.last().copied().unwrap_or_default(),
+            };
+            (timing.runs, timing)
+        })),
+        failures: Failures {
+            failed: failures,
+            rate: match entries.len() {
+                0 => 0.0,
+                n => failures as f64 / n as f64,
+            },
+            kinds: top(kinds.into_iter().map(|(name, count)| Count { name: name.to_string(), count })),
+        },
+        cleaned: Cleaned {
+            runs: cleans,
+            freed_bytes: months.values().sum(),
+            by_month: months.into_iter().map(|(month, freed_bytes)| Month { month, freed_bytes }).collect(),
+        },
+    }
+}
+
+/// `--name` of a `--name=value` or `--name` argument, `-n` of a short one; `None` for
+/// values and positional arguments.
+fn flag_name(arg: &str) -> Option<&str> {
+    let name = arg.split('=').next().unwrap_or(arg);
+    let short = name.len() == 2 && name.starts_with('-') && name.as_bytes()[1].is_ascii_alphabetic();
+    (name.starts_with("--") && name.len() > 2 || short).then_some(name)
+}
+
+/// `uv venv` for the phase `uv --offline venv --python 3.12 .venv`: the subcommand
+/// words, without the flags, their values, and the arguments. `None` for a phase
+/// that is not a uv command.
+fn uv_step(phase: &str) -> Option<String> {
+    let mut words = phase.split_whitespace();
+    if words.next() != Some("uv") {
+        return None;
+    }
+    let mut step = vec!["uv"];
+    let mut skip_value = false;
+    for word in words {
+        if std::mem::take(&mut skip_value) {
+            continue;
+        }
+        if word.starts_with('-') {
+            // `--offline` takes no value; the other global flags uv steps get do.
+            skip_value = word != "--offline";
+            if step.len() > 1 {
+                break;
+            }
+            continue;
+        }
+        if !word.chars().all(|c| c.is_ascii_lowercase()) {
+            break;
+        }
+        step.push(word);
+    }
+    (step.len() > 1).then(|| step.join(" "))
+}
+
+/// The nearest-rank `p`th percentile of the sorted `values`.
+fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let rank = ((p / 100.0) * values.len() as f64).ceil() as usize;
+    values.get(rank.saturating_sub(1)).copied()
+}
+
+/// The items, most counted first (ties in the order given).
+fn sorted_by_count<T>(items: impl Iterator<Item = (usize, T)>) -> Vec<T> {
+    let mut items: Vec<(usize, T)> = items.collect();
+    items.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// The [`TOP`] biggest counts, biggest first.
+fn top(counts: impl Iterator<Item = Count>) -> Vec<Count> {
+    let mut counts = sorted_by_count(counts.map(|c| (c.count, c)));
+    counts.truncate(TOP);
+    counts
+}
+
+pub fn print(stats: &Stats) {
+    let range = match (&stats.first, &stats.last) {
+        (Some(first), Some(last)) => format!("{} to {}", &first[..10.min(first.len())], &last[..10.min(last.len())]),
+        _ => String::new(),
+    };
+    println!("  {} {} {}", "Runs:".dimmed(), stats.runs, range.dimmed());
+    if stats.without_durations > 0 {
+        println!(
+            "  {} {} run(s) from before durations were recorded are left out of the times.",
+            "Note:".dimmed(),
+            stats.without_durations
+        );
+    }
+
+    println!("  {}", format!("{:14}  {:>5}  {:>6}  {:>9}  {:>9}", "COMMAND", "RUNS", "FAILED", "MEDIAN", "P90").bold());
+    for c in &stats.commands {
+        println!(
+            "  {:14}  {:>5}  {:>6}  {:>9}  {:>9}",
+            c.command,
+            c.runs,
+            c.failed,
+            c.median_ms.map_or("-".to_string(), format_ms),
+            c.p90_ms.map_or("-".to_string(), format_ms)
+        );
+    }
+
+    if !stats.flags.is_empty() {
+        println!("  {}", format!("{:30}  {:>5}", "FLAG", "RUNS").bold());
+        for f in &stats.flags {
+            println!("  {:30}  {:>5}", f.name, f.count);
+        }
+    }
+
+    if !stats.uv_phases.is_empty() {
+        println!(
+            "  {}",
+            format!("{:22}  {:>5}  {:>9}  {:>9}  {:>9}", "UV STEP", "RUNS", "MEDIAN", "P90", "MAX").bold()
+        );
+        for t in &stats.uv_phases {
+            println!(
+                "  {:22}  {:>5}  {:>9}  {:>9}  {:>9}",
+                t.phase,
+                t.runs,
+                format_ms(t.median_ms),
+                format_ms(t.p90_ms),
+                format_ms(t.max_ms)
+            );
+        }
+    }
+
+    let rate = format!("({:.1}%)", stats.failures.rate * 100.0);
+    println!("  {} {} {}", "Failed:".dimmed(), stats.failures.failed, rate.dimmed());
+    for k in &stats.failures.kinds {
+        println!("    {:>5}  {}", k.count, k.name.red());
+    }
+
+    println!(
+        "  {} {} in {} clean(s)",
+        "Freed:".dimmed(),
+        human_bytes(stats.cleaned.freed_bytes),
+        stats.cleaned.runs
+    );
+    for m in &stats.cleaned.by_month {
+        println!("    {}  {:>10}", m.month, human_bytes(m.freed_bytes));
+    }
+}
+
+/// `1.23s`, or `45.6ms` below a second.
+fn format_ms(ms: f64) -> String {
+    match ms < 1000.0 {
+        true => format!("{ms:.1}ms"),
+        false => format!("{:.2}s", ms / 1000.0),
+    }
+}
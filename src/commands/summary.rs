@@ -0,0 +1,229 @@
+//! What `--create_project` prints once the project exists: a short tree of what was
+//! generated, and next steps that match the options it was created with.
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use pyproj::lang::Strings;
+use pyproj::scaffold::DepStyle;
+use pyproj::util::{import_name, quote_argv};
+use pyproj::{PlannedAction, ScaffoldPlan};
+
+/// One line of the post-create tree: a top-level entry, or one level below it.
+#[derive(Debug, Serialize)]
+pub struct TreeEntry {
+    /// Relative to the project root, `/`-separated; directories end in `/`.
+    pub path: String,
+    /// Files below a directory, at any depth (0 for files).
+    pub files: usize,
+}
+
+/// A command to run next, and why.
+#[derive(Debug, Serialize)]
+pub struct NextStep {
+    pub command: String,
+    pub why: String,
+}
+
+/// The top two levels of what `plan` writes, directories before files at each level.
+pub fn tree(plan: &ScaffoldPlan, manifest: bool) -> Result<Vec<TreeEntry>> {
+    let mut dirs: Vec<String> = Vec::new();
+    let mut files: Vec<String> = Vec::new();
+    for action in plan.plan(manifest)? {
+        match action {
+            PlannedAction::CreateDir { path } => dirs.push(slashed(&path)),
+            PlannedAction::WriteFile { path, .. } => files.push(slashed(&path)),
+            _ => {}
+        }
+    }
+    // Every directory that holds something, including the parents of written files.
+    for file in &files {
+        let parts: Vec<&str> = file.split('/').collect();
+        for n in 1..parts.len() {
+            dirs.push(parts[..n].join("/"));
+        }
+    }
+    dirs.sort();
+    dirs.dedup();
+
+    let depth = |p: &str| p.split('/').count();
+    let parent = |p: &str| p.rsplit_once('/').map(|(dir, _)| dir.to_string());
+    let count = |dir: &str| files.iter().filter(|f| f.starts_with(&format!("{dir}/"))).count();
+    // Keyed by (parent, is file, name): sorted, that is the order a tree is drawn in.
+    let mut shown: BTreeMap<(String, bool, String), TreeEntry> = BTreeMap::new();
+    for dir in dirs.iter().filter(|d| depth(d) <= 2) {
+        let key = (parent(dir).unwrap_or_default(), false, dir.clone());
+        shown.insert(key, TreeEntry { path: format!("{dir}/"), files: count(dir) });
+    }
+    for file in files.iter().filter(|f| depth(f) <= 2) {
+        let key = (parent(file).unwrap_or_default(), true, file.clone());
+        shown.insert(key, TreeEntry { path: file.clone(), files: 0 });
+    }
+    // Top-level entries, each followed by its children.
+    let (top, nested): (Vec<_>, Vec<_>) =
+        shown.into_iter().partition(|((p, _, _), _)| p.is_empty());
+    let mut nested: BTreeMap<String, Vec<TreeEntry>> =
+        nested.into_iter().fold(BTreeMap::new(), |mut acc, ((p, _, _), entry)| {
+            acc.entry(p).or_default().push(entry);
+            acc
+        });
+    let mut out = Vec::new();
+    for (_, entry) in top {
+        let children = nested.remove(entry.path.trim_end_matches('/')).unwrap_or_default();
+        out.push(entry);
+        out.extend(children);
+    }
+    Ok(out)
+}
+
+/// Print `tree` below the project root, with file counts for directories one level down.
+pub fn print_tree(root: &Path, tree: &[TreeEntry]) {
+    let name = root.file_name().unwrap_or(root.as_os_str()).to_string_lossy();
+    println!("  {}", format!("{name}/").blue().bold());
+    let is_top = |e: &TreeEntry| !e.path.trim_end_matches('/').contains('/');
+    for (i, entry) in tree.iter().enumerate() {
+        let rest = &tree[i + 1..];
+        let name = entry.path.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        let name = match entry.path.ends_with('/') {
+            true => format!("{name}/"),
+            false => name.to_string(),
+        };
+        if is_top(entry) {
+            let last = !rest.iter().any(is_top);
+            println!("  {} {name}", if last { "└─" } else { "├─" });
+        } else {
+            let rail = if rest.iter().any(is_top) { "│ " } else { "  " };
+            let last_child = rest.first().is_none_or(is_top);
+            let branch = if last_child { "└─" } else { "├─" };
+            match entry.files {
+                0 => println!("  {rail} {branch} {name}"),
+                n => println!("  {rail} {branch} {name} {}", format!("({n} files)").dimmed()),
+            }
+        }
+    }
+}
+
+/// What to do now, for this plan as it was created from `cwd`.
+pub fn next_steps(plan: &ScaffoldPlan, cwd: &Path) -> Vec<NextStep> {
+    let step = |command: String, why: &str| NextStep { command, why: why.to_string() };
+    let tr = &plan.strings;
+    let root = std::path::absolute(&plan.root).unwrap_or_else(|_| plan.root.clone());
+    let shown = match root.strip_prefix(cwd) {
+        Ok(rel) if !rel.as_os_str().is_empty() => rel.to_path_buf(),
+        _ => root.clone(),
+    };
+    let py = plan.python.to_string();
+    let dist = plan.dist_name();
+    let mut steps = Vec::new();
+    if root != cwd {
+        steps.push(step(format!("cd {}", shown.display()), tr.get("next_enter")));
+    }
+
+    if plan.offline_skips().contains(&"uv python install") {
+        steps.push(step(
+            format!("uv python install {py}"),
+            tr.get("next_fetch_python"),
+        ));
+    }
+    match &plan.workspace_root {
+        Some(ws) => {
+            if plan.skip_uv {
+                steps.push(step(
+                    format!("uv sync --directory {}", ws.display()),
+                    tr.get("next_workspace_venv"),
+                ));
+            }
+            let dev = match plan.dep_style {
+                DepStyle::Extras => "--extra dev",
+                DepStyle::Groups => "--group dev",
+            };
+            steps.push(step(
+                format!("uv sync --package {dist} {dev}"),
+                tr.get("next_member_dev"),
+            ));
+            let pkg = import_name(&plan.project);
+            steps.push(step(
+                format!("uv run --package {dist} python -c \"import {pkg}\""),
+                tr.get("next_check_import"),
+            ));
+            if let Some(name) = &plan.entry_point {
+                steps.push(step(
+                    format!("uv run --package {dist} {name} --version"),
+                    tr.get("next_console_script"),
+                ));
+            }
+        }
+        None => {
+            if plan.skip_uv {
+                let args = plan.venv_args();
+                steps.push(step(
+                    quote_argv("uv", &args),
+                    tr.get("next_create_venv"),
+                ));
+            }
+            steps.push(step(
+                format!("source {}/bin/activate", plan.venv_dir()),
+                &tr.fill("next_activate", &[("prompt", plan.prompt())]),
+            ));
+            if plan.venv_dir.is_some() {
+                steps.push(step(
+                    format!("export UV_PROJECT_ENVIRONMENT={}", plan.venv_dir()),
+                    tr.get("next_uv_env"),
+                ));
+            }
+            let install = match plan.dep_style {
+                DepStyle::Extras => "uv pip install -e \".[dev]\"",
+                DepStyle::Groups => "uv sync --group dev",
+            };
+            steps.push(step(
+                install.to_string(),
+                tr.get("next_install"),
+            ));
+            match &plan.entry_point {
+                Some(name) => steps.push(step(format!("uv run {name} --version"), tr.get("next_console_script"))),
+                None => steps.push(step(format!("uv run python -m {}", plan.run_module()), tr.get("next_entry"))),
+            }
+        }
+    }
+    steps.push(step("uv run pytest".to_string(), tr.get("next_tests")));
+
+    let writes_envrc = plan.plan(false).is_ok_and(|actions| {
+        actions.iter().any(
+            |a| matches!(a, PlannedAction::WriteFile { path, .. } if path == Path::new(".envrc")),
+        )
+    });
+    if writes_envrc && which::which("direnv").is_ok() {
+        steps.push(step("direnv allow".to_string(), tr.get("next_direnv")));
+    }
+    // A post-create hook may have made it a git repository; with no remote, it is unpushed.
+    let git_config = fs::read_to_string(root.join(".git/config")).unwrap_or_default();
+    if root.join(".git").is_dir() && !git_config.contains("[remote ") {
+        steps.push(step(
+            "git remote add origin <url> && git push -u origin HEAD".to_string(),
+            tr.get("next_publish"),
+        ));
+    }
+    steps
+}
+
+/// Print `steps` as a numbered list, headed in the language of `strings`.
+pub fn print_next_steps(steps: &[NextStep], strings: &Strings) {
+    println!("  {}", strings.get("next_heading").bold());
+    let width = steps.iter().map(|s| s.command.chars().count()).max().unwrap_or(0);
+    for (i, s) in steps.iter().enumerate() {
+        println!(
+            "    {}. {:<width$}  {}",
+            i + 1,
+            s.command.cyan(),
+            format!("# {}", s.why).dimmed()
+        );
+    }
+}
+
+fn slashed(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
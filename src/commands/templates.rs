@@ -0,0 +1,154 @@
+//! `py-proj templates`: list, preview, and export the files a scaffold writes; and
+//! `py-proj debug render-all`, which renders them for a fixed set of option combinations.
+
+use anyhow::{bail, Result};
+use owo_colors::OwoColorize;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use pyproj::profile::Profile;
+use pyproj::scaffold::{LineEndings, Publish, ScaffoldPlanBuilder};
+use pyproj::{templates, util, PlannedAction, ScaffoldPlan};
+
+/// Sets the options of one `render-all` combination.
+type Options = fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder;
+
+/// The option combinations `render-all` renders, each into a subdirectory of its name.
+const COMBINATIONS: &[(&str, Options)] = &[
+    ("default", |b| b),
+    ("minimal", |b| b.minimal(true)),
+    ("package", |b| {
+        b.entry_point("demo")
+            .publish(Publish::Pypi)
+            .license("MIT")
+            .license_header("MIT")
+            .citation(true)
+            .repo_url("https://github.com/example/demo")
+    }),
+    ("windows", |b| b.line_endings(LineEndings::Crlf)),
+    ("datasci", |b| b.profile(Profile::Datasci)),
+];
+
+/// Print the relative path of every file `plan` would write, with where it comes from.
+pub fn list(plan: &ScaffoldPlan) -> Result<()> {
+    for (path, origin) in planned_files(plan)? {
+        println!("  {} {}", path, format!("({})", origin.label()).dimmed());
+    }
+    Ok(())
+}
+
+/// Print the file `plan` would write at `rel` to stdout, rendered with the plan's
+/// variables, or with its placeholders intact when `raw`.
+pub fn show(plan: &ScaffoldPlan, rel: &str, raw: bool) -> Result<()> {
+    let rel = rel.trim_start_matches("./");
+    let actions = plan.plan(false)?;
+    let Some(contents) = actions.iter().find_map(|a| match a {
+        PlannedAction::WriteFile { path, contents, .. } if path == Path::new(rel) => Some(contents),
+        _ => None,
+    }) else {
+        let paths: Vec<String> = planned_files(plan)?.into_iter().map(|(p, _)| p).collect();
+        let close = close_matches(rel, &paths);
+        match close.is_empty() {
+            true => bail!("no template generates `{rel}`; see `py-proj templates list`"),
+            false => bail!("no template generates `{rel}`; did you mean {}?", close.join(", ")),
+        }
+    };
+    let bytes = match raw {
+        true => raw_source(plan, rel)?,
+        false => contents.clone(),
+    };
+    std::io::stdout().write_all(&bytes)?;
+    Ok(())
+}
+
+/// Dump the template registry to `dir` so it can be edited and fed back via --templates-dir.
+pub fn export(dir: &Path, force: bool) -> Result<()> {
+    ensure_empty(dir, force)?;
+    for t in templates::REGISTRY {
+        println!("  {} {}", "write".green(), t.path.dimmed());
+        util::write(dir.join(t.path), (t.source)())?;
+    }
+    Ok(())
+}
+
+/// Render every file of a project `demo` for each of the [`COMBINATIONS`] into
+/// `dir/<combination>/`, without running uv or git. The plans are deterministic, so
+/// two runs (on any machine, any day) write the same bytes and can be diffed.
+pub fn render_all(dir: &Path, force: bool) -> Result<()> {
+    ensure_empty(dir, force)?;
+    for (name, options) in COMBINATIONS {
+        let root = dir.join(name);
+        let builder = ScaffoldPlan::builder("demo").root(&root).python("3.12").skip_uv(true).deterministic(true);
+        let plan = options(builder).build()?;
+        let mut written = 0;
+        for action in plan.plan(false)? {
+            if let PlannedAction::WriteFile { path, contents, .. } = action {
+                util::write(root.join(path), &contents)?;
+                written += 1;
+            }
+        }
+        println!("  {} {}/ {}", "write".green(), name, format!("({written} files)").dimmed());
+    }
+    Ok(())
+}
+
+fn ensure_empty(dir: &Path, force: bool) -> Result<()> {
+    let non_empty = dir.is_dir() && fs::read_dir(dir)?.next().is_some();
+    if non_empty && !force {
+        return Err(pyproj::Error::TargetExists {
+            path: dir.to_path_buf(),
+            hint: "the directory is not empty; use --force to write into it anyway".to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn planned_files(plan: &ScaffoldPlan) -> Result<Vec<(String, pyproj::Origin)>> {
+    Ok(plan
+        .plan(false)?
+        .into_iter()
+        .filter_map(|a| match a {
+            PlannedAction::WriteFile { path, origin, .. } => {
+                Some((path.to_string_lossy().replace('\\', "/"), origin))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// The un-rendered source of `rel`: the `--templates-dir` file if there is one, else
+/// the built-in template.
+fn raw_source(plan: &ScaffoldPlan, rel: &str) -> Result<Vec<u8>> {
+    if let Some(file) = plan.templates_dir.as_ref().map(|d| d.join(rel)).filter(|f| f.is_file()) {
+        return Ok(fs::read(file)?);
+    }
+    match templates::find(rel) {
+        Some(t) => Ok((t.source)().as_bytes().to_vec()),
+        None => bail!("`{rel}` is generated without a template, so there is no raw form"),
+    }
+}
+
+/// Up to three of `paths` that look like a mistyped `wanted`: same file name, one
+/// containing the other, or a small edit distance.
+fn close_matches(wanted: &str, paths: &[String]) -> Vec<String> {
+    let name = |p: &str| p.rsplit('/').next().unwrap_or(p).to_ascii_lowercase();
+    let wanted_name = name(wanted);
+    let mut scored: Vec<(usize, &String)> = paths
+        .iter()
+        .filter_map(|p| {
+            let candidate = name(p);
+            let score = if candidate == wanted_name {
+                0
+            } else if candidate.contains(&wanted_name) || wanted_name.contains(&candidate) {
+                1
+            } else {
+                util::edit_distance(&candidate, &wanted_name) + 1
+            };
+            (score <= 3).then_some((score, p))
+        })
+        .collect();
+    scored.sort();
+    scored.into_iter().take(3).map(|(_, p)| format!("`{p}`")).collect()
+}
@@ -0,0 +1,192 @@
+//! `py-proj undo`: reverse the last create or adopt in a directory, file by file from
+//! the manifest. Unlike `--delete_project` it leaves everything py-proj did not make,
+//! so it is safe in a directory that had a project (or anything else) in it before.
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use pyproj::lock::ProjectLock;
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::util::{is_safe_relative, remove_tree, sha256_hex, DEFAULT_VENV_DIR};
+use pyproj::Event;
+
+pub struct UndoOptions {
+    /// Print what would be removed without removing anything.
+    pub dry_run: bool,
+    /// Remove generated files even when they were edited since.
+    pub force_modified: bool,
+    pub verbose: bool,
+}
+
+/// What an undo removed and left.
+#[derive(Debug, Default)]
+pub struct Undone {
+    pub removed: usize,
+    /// Files put back from the `.bak` py-proj saved before replacing them.
+    pub restored: usize,
+    /// Generated files left because they were edited since.
+    pub modified: usize,
+    /// The manifest is gone, and with it py-proj's record of the project.
+    pub manifest_removed: bool,
+}
+
+pub fn undo(root: &Path, opts: &UndoOptions) -> Result<Undone> {
+    let printer = super::printer(opts.verbose);
+    let lock = match opts.dry_run {
+        true => None,
+        false => Some(ProjectLock::acquire(root, "undo", false, &|message| {
+            printer(&Event::Warning { message })
+        })?),
+    };
+    let manifest = Manifest::load(root)?.with_context(|| {
+        format!("no {MANIFEST_FILE} in {}; undo only reverses a scaffold py-proj recorded", root.display())
+    })?;
+    let kept = &manifest.preexisting;
+    let mut undone = Undone::default();
+    let remove = |path: &Path, what: std::result::Result<(), std::io::Error>| -> Result<()> {
+        match what {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    };
+
+    let mut dirs: BTreeSet<PathBuf> = manifest
+        .options
+        .dirs
+        .iter()
+        .map(PathBuf::from)
+        .filter(|d| is_safe_relative(d))
+        .flat_map(|d| d.ancestors().filter(|a| !a.as_os_str().is_empty()).map(Path::to_path_buf).collect::<Vec<_>>())
+        .collect();
+    for entry in &manifest.files {
+        let rel = Path::new(&entry.path);
+        if !is_safe_relative(rel) {
+            println!("  {} {} {}", "skip".yellow(), entry.path, "(not a path inside the project)".dimmed());
+            continue;
+        }
+        dirs.extend(rel.ancestors().skip(1).filter(|d| !d.as_os_str().is_empty()).map(Path::to_path_buf));
+        let path = root.join(rel);
+        if let Some(hash) = kept.backups.get(&entry.path) {
+            restore(root, &entry.path, hash, opts, &mut undone)?;
+            continue;
+        }
+        if kept.files.contains(&entry.path) {
+            if opts.verbose {
+                println!("  {} {} {}", "keep".dimmed(), entry.path, "(there before py-proj)".dimmed());
+            }
+            continue;
+        }
+        let Ok(current) = fs::read(&path) else {
+            continue;
+        };
+        if sha256_hex(&current) != entry.sha256 {
+            if !opts.force_modified {
+                println!(
+                    "  {} {} {}",
+                    "keep".yellow(),
+                    entry.path,
+                    "(edited since it was generated; --force-modified removes it)".dimmed()
+                );
+                undone.modified += 1;
+                continue;
+            }
+            println!("  {} {} {}", "rm".yellow(), entry.path, "(edited)".dimmed());
+        } else {
+            println!("  {} {}", "rm".yellow(), entry.path.dimmed());
+        }
+        undone.removed += 1;
+        if !opts.dry_run {
+            remove(&path, fs::remove_file(&path))?;
+        }
+    }
+
+    // Deepest first, so a directory's subdirectories are gone before it is tried.
+    for rel in dirs.iter().rev() {
+        let shown = rel.to_string_lossy().replace('\\', "/");
+        if kept.dirs.contains(&shown) {
+            continue;
+        }
+        let path = root.join(rel);
+        match opts.dry_run {
+            true if path.is_dir() => println!("  {} {}/ {}", "rmdir".yellow(), shown.dimmed(), "(if empty)".dimmed()),
+            true => {}
+            false => match fs::remove_dir(&path) {
+                Ok(()) => println!("  {} {}/", "rmdir".yellow(), shown.dimmed()),
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(_) => println!("  {} {}/ {}", "keep".yellow(), shown, "(not empty)".dimmed()),
+            },
+        }
+    }
+
+    let venv_dir = manifest.options.venv_dir.as_deref().unwrap_or(DEFAULT_VENV_DIR);
+    let venv = root.join(venv_dir);
+    if !kept.venv && is_safe_relative(Path::new(venv_dir)) && venv.is_dir() {
+        println!("  {} {}", "rm -rf".yellow(), venv_dir);
+        if !opts.dry_run {
+            if let Some((path, e)) = remove_tree(&venv).into_iter().next() {
+                return Err(e).with_context(|| format!("failed to remove {}", path.display()));
+            }
+        }
+    }
+
+    // Kept while generated files are, so `undo --force-modified` can finish the job.
+    if undone.modified == 0 {
+        println!("  {} {}", "rm".yellow(), MANIFEST_FILE.dimmed());
+        if !opts.dry_run {
+            let path = root.join(MANIFEST_FILE);
+            remove(&path, fs::remove_file(&path))?;
+        }
+        undone.manifest_removed = true;
+    }
+    drop(lock);
+    if undone.manifest_removed && !kept.root {
+        let empty = fs::read_dir(root).is_ok_and(|mut entries| entries.next().is_none());
+        match (opts.dry_run, empty) {
+            // The files above are still there in a dry run; the root would go with them.
+            (true, _) => println!("  {} {} {}", "rmdir".yellow(), root.display(), "(if empty)".dimmed()),
+            (false, true) => {
+                remove(root, fs::remove_dir(root))?;
+                println!("  {} {}", "rmdir".yellow(), root.display());
+            }
+            (false, false) => println!("  {} {} {}", "keep".yellow(), root.display(), "(not empty)".dimmed()),
+        }
+    }
+    Ok(undone)
+}
+
+/// Put back the original of `rel` that the run saved as `<rel>.bak`, unless the file
+/// changed since py-proj left it (its hash is not `hash`) and `force_modified` is off.
+fn restore(root: &Path, rel: &str, hash: &str, opts: &UndoOptions, undone: &mut Undone) -> Result<()> {
+    let path = root.join(rel);
+    let bak = root.join(format!("{rel}.bak"));
+    let edited = fs::read(&path).is_ok_and(|current| sha256_hex(current) != hash);
+    if !bak.is_file() {
+        // Without the backup an edited file is most likely the original, put back already.
+        if !edited {
+            println!("  {} {} {}", "keep".yellow(), rel, format!("(its original, {rel}.bak, is gone)").dimmed());
+        }
+        return Ok(());
+    }
+    if edited && !opts.force_modified {
+        println!(
+            "  {} {} {}",
+            "keep".yellow(),
+            rel,
+            format!("(edited since py-proj changed it; the original is {rel}.bak, --force-modified restores it)").dimmed()
+        );
+        undone.modified += 1;
+        return Ok(());
+    }
+    println!("  {} {} {}", "restore".green(), rel, format!("(from {rel}.bak)").dimmed());
+    undone.restored += 1;
+    if !opts.dry_run {
+        fs::rename(&bak, &path).with_context(|| format!("failed to restore {} from {}", path.display(), bak.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! `py-proj upgrade`: re-render the config templates of an existing project and
+//! bring them up to date, without clobbering edits the user made since creation.
+
+use anyhow::{Context, Result};
+use dialoguer::Confirm;
+use owo_colors::OwoColorize;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use super::diff::print_unified;
+use pyproj::lock::ProjectLock;
+use pyproj::manifest::{build_time_rfc3339, FileEntry, Manifest, MANIFEST_FILE};
+use pyproj::scaffold::ScaffoldPlan;
+use pyproj::templates::find;
+use pyproj::Event;
+use super::adopt::report_backup;
+use pyproj::util::{current_mode, format_mode, set_mode, sha256_hex, write_with_mode, WritePolicy};
+
+pub struct UpgradeOptions {
+    /// Replace user-edited files without asking.
+    pub force: bool,
+    /// Print the changes without writing anything.
+    pub dry_run: bool,
+    pub verbose: bool,
+}
+
+/// What happens to one generated file.
+enum Action {
+    UpToDate,
+    /// Missing from the manifest: a template added since the project was created.
+    Create,
+    /// Unchanged since generation; safe to replace.
+    Update,
+    /// Edited by the user; only replaced with --force or confirmation.
+    Edited,
+    /// Recorded in the manifest but deleted from disk; left deleted.
+    Deleted,
+}
+
+pub fn upgrade_project(root: &Path, opts: &UpgradeOptions) -> Result<()> {
+    // Taken before the manifest is read, so a create or clean running meanwhile finishes first.
+    let printer = super::printer(opts.verbose);
+    let _lock = match opts.dry_run {
+        true => None,
+        false => Some(ProjectLock::acquire(root, "upgrade", false, &|message| {
+            printer(&Event::Warning { message })
+        })?),
+    };
+    let mut manifest = Manifest::load(root)?.with_context(|| {
+        format!("no {MANIFEST_FILE} in {}; was it created by py-proj?", root.display())
+    })?;
+    let plan = ScaffoldPlan::from_manifest(root, &manifest, super::printer(opts.verbose))?;
+
+    let mut changed = 0;
+    for (rel, new) in plan.render_files()? {
+        let path = root.join(&rel);
+        let mode = manifest.mode_of(&rel);
+        if !is_upgradable(&rel, plan.notebooks_dir()) {
+            // The contents are the user's now, but a lost executable bit is still restored.
+            changed += usize::from(restore_mode(&path, &rel, mode, opts)?);
+            continue;
+        }
+        let recorded = manifest.hash_of(&rel).map(str::to_string);
+        let current = fs::read(&path).ok();
+        let action = match (&current, &recorded) {
+            (Some(cur), _) if *cur == new => Action::UpToDate,
+            (Some(cur), Some(hash)) if sha256_hex(cur) == *hash => Action::Update,
+            (Some(_), _) => Action::Edited,
+            (None, Some(_)) => Action::Deleted,
+            (None, None) => Action::Create,
+        };
+
+        let replace = match action {
+            Action::UpToDate => {
+                if restore_mode(&path, &rel, mode, opts)? {
+                    changed += 1;
+                } else if opts.verbose {
+                    println!("  {} {}", "ok".dimmed(), rel.dimmed());
+                }
+                record(&mut manifest, &rel, &new);
+                continue;
+            }
+            Action::Deleted => {
+                println!("  {} {} {}", "skip".yellow(), rel, "(deleted locally)".dimmed());
+                continue;
+            }
+            Action::Create => {
+                println!("  {} {}", "create".green(), rel);
+                true
+            }
+            Action::Update => {
+                println!("  {} {}", "update".green(), rel);
+                true
+            }
+            Action::Edited => {
+                println!(
+                    "  {} {} {}",
+                    "edited".yellow().bold(),
+                    rel,
+                    "(changed since generation)".dimmed()
+                );
+                let old = String::from_utf8_lossy(current.as_deref().unwrap_or_default());
+                print_unified(&rel, &old, &String::from_utf8_lossy(&new));
+                confirm_replace(&rel, opts)?
+            }
+        };
+        if !replace {
+            println!("  {} {}", "keep".yellow(), rel);
+            continue;
+        }
+        changed += 1;
+        if !opts.dry_run {
+            // Keep the user's version of an edited file next to the new one.
+            let policy = match action {
+                Action::Edited => WritePolicy::Backup,
+                _ => WritePolicy::Overwrite,
+            };
+            report_backup(write_with_mode(&path, &new, policy, mode)?);
+            record(&mut manifest, &rel, &new);
+        }
+    }
+
+    if opts.dry_run {
+        println!("  {} {}", "Would change:".bold(), changed);
+        return Ok(());
+    }
+    manifest.tool.version = env!("CARGO_PKG_VERSION").to_string();
+    manifest.tool.upgraded = Some(build_time_rfc3339());
+    manifest.write(root)
+}
+
+/// Give `path` back the permission bits `mode` when it lost them; true when it had.
+fn restore_mode(path: &Path, rel: &str, mode: Option<u32>, opts: &UpgradeOptions) -> Result<bool> {
+    let Some(mode) = mode.filter(|&m| current_mode(path).is_some_and(|c| c != m)) else {
+        return Ok(false);
+    };
+    println!("  {} {} {}", "chmod".green(), rel, format!("({})", format_mode(mode)).dimmed());
+    if !opts.dry_run {
+        set_mode(path, mode)?;
+    }
+    Ok(true)
+}
+
+/// Only config-type templates and override extras outside the user's source tree.
+fn is_upgradable(rel: &str, notebooks_dir: &str) -> bool {
+    if rel.starts_with("src/") || rel.starts_with("tests/") || rel.starts_with(&format!("{notebooks_dir}/")) {
+        return false;
+    }
+    find(rel).is_none_or(|t| t.group.is_config())
+}
+
+/// Decide whether an edited file gets replaced: --force, an interactive prompt, or no.
+fn confirm_replace(rel: &str, opts: &UpgradeOptions) -> Result<bool> {
+    if opts.force {
+        return Ok(true);
+    }
+    if opts.dry_run || !std::io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    Ok(Confirm::new()
+        .with_prompt(format!("Replace {rel} with the new template?"))
+        .default(false)
+        .interact()?)
+}
+
+/// Point the manifest entry for `rel` at `bytes`, and record its mode.
+fn record(manifest: &mut Manifest, rel: &str, bytes: &[u8]) {
+    let sha256 = sha256_hex(bytes);
+    let mode = manifest.mode_of(rel).map(format_mode);
+    match manifest.files.iter_mut().find(|f| f.path == rel) {
+        Some(entry) => {
+            entry.sha256 = sha256;
+            entry.mode = mode;
+        }
+        None => manifest.files.push(FileEntry {
+            path: rel.to_string(),
+            sha256,
+            mode,
+        }),
+    }
+}
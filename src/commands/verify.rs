@@ -0,0 +1,70 @@
+//! `py-proj verify`: check the generated files against the hashes recorded in the manifest.
+
+use anyhow::{bail, Result};
+use owo_colors::OwoColorize;
+use std::fs;
+use std::path::Path;
+
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::util::sha256_hex;
+
+pub struct VerifyOptions {
+    /// Record the current contents as the new baseline instead of reporting drift.
+    pub update_manifest: bool,
+}
+
+/// Print which generated files are unmodified, modified, or missing; returns true when
+/// any are modified or missing (and `--update-manifest` was not given).
+pub fn verify_project(root: &Path, opts: &VerifyOptions, verbose: bool) -> Result<bool> {
+    let Some(mut manifest) = Manifest::load(root)? else {
+        bail!(
+            "no {MANIFEST_FILE} in {}; nothing to verify against (run `adopt` to write one)",
+            root.display()
+        );
+    };
+
+    let mut modified = Vec::new();
+    let mut missing = Vec::new();
+    let mut same = 0;
+    for entry in manifest.files.iter_mut() {
+        let Ok(bytes) = fs::read(root.join(&entry.path)) else {
+            missing.push(entry.path.clone());
+            continue;
+        };
+        let hash = sha256_hex(&bytes);
+        if hash == entry.sha256 {
+            same += 1;
+            if verbose {
+                println!("  {} {}", "=".dimmed(), entry.path.dimmed());
+            }
+            continue;
+        }
+        println!("  {} {}", "modified".yellow().bold(), entry.path);
+        modified.push(entry.path.clone());
+        entry.sha256 = hash;
+    }
+    for rel in &missing {
+        println!("  {} {}", "missing".red().bold(), rel);
+    }
+    println!(
+        "  {} {} modified, {} missing, {same} unmodified",
+        "Summary:".dimmed(),
+        modified.len(),
+        missing.len()
+    );
+
+    if modified.is_empty() && missing.is_empty() {
+        return Ok(false);
+    }
+    if !opts.update_manifest {
+        return Ok(true);
+    }
+    manifest.files.retain(|f| !missing.contains(&f.path));
+    manifest.write(root)?;
+    println!(
+        "  {} {}",
+        "update".green(),
+        format!("{MANIFEST_FILE} (current contents are the new baseline)").dimmed()
+    );
+    Ok(false)
+}
@@ -0,0 +1,396 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::scaffold::Maintainer;
+
+/// A repository's own defaults, found by walking up from the current directory (the
+/// nearest one wins) and checked into git alongside the code.
+pub const RC_FILE: &str = ".pyprojrc.toml";
+
+/// User configuration loaded from `config.toml`, with a repository's `.pyprojrc.toml`
+/// merged over it. CLI flags always win over both.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Python version for new projects when `--python` is not given.
+    pub python: Option<String>,
+    /// The venv's directory name when `--venv-dir` is not given.
+    pub venv_dir: Option<String>,
+    /// The notebooks directory's name when `--notebooks-dir` is not given.
+    pub notebooks_dir: Option<String>,
+    /// Only the essential files, as with `--minimal`.
+    pub minimal: bool,
+    /// Components `--minimal` keeps anyway (before any `--with` flags).
+    pub with: Vec<String>,
+    /// Globs of generated files to leave out (before any `--exclude` flags).
+    pub exclude: Vec<String>,
+    /// Directory whose files override the built-in templates.
+    pub templates_dir: Option<PathBuf>,
+    /// Extra template variables (`[vars]` table); `--var` takes precedence.
+    pub vars: BTreeMap<String, toml::Value>,
+    /// Commands run after a successful create (before any `--post-hook` flags).
+    pub post_hooks: Vec<String>,
+    /// Patterns appended to every new project's .gitignore (before `--gitignore-extra`).
+    pub gitignore_extra: Vec<String>,
+    /// Seconds before a uv/git command is killed (0: never); `--command-timeout` wins.
+    pub command_timeout: Option<u64>,
+    /// Seconds to wait for another run's `.pyproj.lock`; `--lock-timeout` wins.
+    pub lock_timeout: Option<u64>,
+    /// `[clean]` table: what `--clean_project` removes besides the built-in caches.
+    pub clean: CleanConfig,
+    /// `false` stops recording runs in the history log; `--no-history` does so per run.
+    pub history: Option<bool>,
+    /// Look for a newer py-proj release at most once a week (off unless set).
+    pub check_updates: bool,
+    /// `[org]` table: metadata every new project of the organization shares.
+    pub org: OrgConfig,
+    /// Editor command for `open` and `--open`; `--editor` and `$PY_PROJ_EDITOR` win.
+    pub editor: Option<String>,
+    /// The merged files, for [`Config::value`].
+    #[serde(skip)]
+    table: toml::Table,
+    /// The files each key was set in, user config first.
+    #[serde(skip)]
+    origins: BTreeMap<String, Vec<ConfigLayer>>,
+    /// Problems with the repo's `.pyprojrc.toml` that did not stop it from loading.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// A config file a setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLayer {
+    /// The repository's `.pyprojrc.toml`.
+    Repo(PathBuf),
+    /// The user's `config.toml` (or `--config`, or `$PY_PROJ_CONFIG`).
+    User(PathBuf),
+}
+
+impl ConfigLayer {
+    pub fn path(&self) -> &Path {
+        match self {
+            ConfigLayer::Repo(path) | ConfigLayer::User(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLayer::Repo(path) => write!(f, "repo rc ({})", path.display()),
+            ConfigLayer::User(path) => write!(f, "user config ({})", path.display()),
+        }
+    }
+}
+
+/// The keys of [`Config`] and of its tables, for the repo file's unknown-key warnings.
+const KEYS: &[&str] = &[
+    "python",
+    "venv_dir",
+    "notebooks_dir",
+    "minimal",
+    "with",
+    "exclude",
+    "templates_dir",
+    "vars",
+    "post_hooks",
+    "gitignore_extra",
+    "command_timeout",
+    "lock_timeout",
+    "clean",
+    "history",
+    "check_updates",
+    "org",
+    "editor",
+];
+const ORG_KEYS: &[&str] = &["name", "license", "repo_url", "homepage", "maintainers"];
+const CLEAN_KEYS: &[&str] = &["extra_patterns"];
+
+/// Keys only the user config may set: they run commands, which a cloned repository
+/// does not get to choose for whoever runs py-proj inside it.
+const USER_ONLY: &[&str] = &["post_hooks", "editor"];
+
+/// Tables whose keys merge one by one across the files.
+const TABLES: &[&str] = &["vars", "org", "clean"];
+
+/// Lists that add up across the files (user config first) instead of the nearer file's
+/// replacing the other's; the matching flags are appended after them.
+pub const APPENDED: &[&str] = &["with", "exclude", "gitignore_extra", "post_hooks", "clean.extra_patterns"];
+
+/// The `[org]` table of the config file. Each value is a default that the matching
+/// flag (`--org`, `--license`, `--repo-url`, `--homepage`, `--maintainer`) overrides.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrgConfig {
+    /// Organization name, for the README's repository badge.
+    pub name: Option<String>,
+    /// SPDX license expression for `[project] license` and CITATION.cff.
+    pub license: Option<String>,
+    /// Repository URL pattern, e.g. `https://github.com/acme/{project}`.
+    pub repo_url: Option<String>,
+    /// Homepage URL pattern, with `{project}` substituted like `repo_url`.
+    pub homepage: Option<String>,
+    /// `[[org.maintainers]]` entries: `name` and an optional `email`.
+    pub maintainers: Vec<Maintainer>,
+}
+
+/// The `[clean]` table of the config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CleanConfig {
+    /// Globs relative to the project root, removed along with the built-in caches
+    /// (before any `--clean-extra` flags).
+    pub extra_patterns: Vec<String>,
+}
+
+impl Config {
+    /// Load the config from `explicit`, `$PY_PROJ_CONFIG`, or the default location, and
+    /// merge the `.pyprojrc.toml` nearest the current directory over it. A missing
+    /// default file is not an error; a missing explicit one is.
+    pub fn load(explicit: Option<&Path>) -> Result<Config> {
+        let cwd = env::current_dir().unwrap_or_default();
+        Config::load_from(explicit, &cwd)
+    }
+
+    /// [`Config::load`], looking for `.pyprojrc.toml` in `cwd` and its parents.
+    pub fn load_from(explicit: Option<&Path>, cwd: &Path) -> Result<Config> {
+        let mut table = toml::Table::new();
+        let mut origins = BTreeMap::new();
+        let mut warnings = Vec::new();
+        if let Some(path) = user_config_path(explicit)? {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read config {}", path.display()))?;
+            // Parsed into the struct first, so a mistake is reported with its line.
+            toml::from_str::<Config>(&text).with_context(|| format!("invalid config {}", path.display()))?;
+            let user = read_table(&text, &path)?;
+            merge(&mut table, user, "", &ConfigLayer::User(path), &mut origins);
+        }
+        if let Some(path) = find_rc(cwd) {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read config {}", path.display()))?;
+            let mut repo = read_table(&text, &path)?;
+            warnings.extend(prune(&mut repo, &path));
+            toml::Value::Table(repo.clone())
+                .try_into::<Config>()
+                .with_context(|| format!("invalid config {}", path.display()))?;
+            merge(&mut table, repo, "", &ConfigLayer::Repo(path), &mut origins);
+        }
+        let mut cfg: Config = toml::Value::Table(table.clone()).try_into().context("invalid config")?;
+        cfg.table = table;
+        cfg.origins = origins;
+        cfg.warnings = warnings;
+        Ok(cfg)
+    }
+
+    /// The effective value of `key` (dotted for the tables: `org.license`, `vars.team`).
+    pub fn value(&self, key: &str) -> Option<&toml::Value> {
+        let mut parts = key.split('.');
+        let first = self.table.get(parts.next()?)?;
+        parts.try_fold(first, |value, part| value.get(part))
+    }
+
+    /// The files that set `key`, user config first; empty when it is the default.
+    pub fn origins(&self, key: &str) -> &[ConfigLayer] {
+        self.origins.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every key set in either file, dotted.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.origins.keys().map(String::as_str)
+    }
+}
+
+/// The user config to read: `explicit`, `$PY_PROJ_CONFIG`, or the default location
+/// when it exists.
+fn user_config_path(explicit: Option<&Path>) -> Result<Option<PathBuf>> {
+    let (path, required) = match explicit {
+        Some(p) => (p.to_path_buf(), true),
+        None => match env::var_os("PY_PROJ_CONFIG") {
+            Some(p) => (PathBuf::from(p), true),
+            None => match default_config_path() {
+                Some(p) => (p, false),
+                None => return Ok(None),
+            },
+        },
+    };
+    match (path.is_file(), required) {
+        (true, _) => Ok(Some(path)),
+        (false, true) => bail!("config file not found: {}", path.display()),
+        (false, false) => Ok(None),
+    }
+}
+
+/// The nearest `.pyprojrc.toml` in `dir` or its parents.
+pub fn find_rc(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().map(|d| d.join(RC_FILE)).find(|p| p.is_file())
+}
+
+/// Parse a config file, resolving its relative `templates_dir` against the file.
+fn read_table(text: &str, path: &Path) -> Result<toml::Table> {
+    let mut table: toml::Table =
+        toml::from_str(text).with_context(|| format!("invalid config {}", path.display()))?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    if let Some(toml::Value::String(dir)) = table.get_mut("templates_dir") {
+        *dir = base.join(&*dir).to_string_lossy().into_owned();
+    }
+    Ok(table)
+}
+
+/// Drop the keys a repo file may not set (unknown ones, and [`USER_ONLY`]), returning a
+/// warning for each.
+fn prune(table: &mut toml::Table, path: &Path) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut drop = |table: &mut toml::Table, prefix: &str, known: &[&str]| {
+        let unknown: Vec<String> = table.keys().filter(|k| !known.contains(&k.as_str())).cloned().collect();
+        for key in unknown {
+            table.remove(&key);
+            warnings.push(format!("{}: unknown key `{prefix}{key}` ignored", path.display()));
+        }
+    };
+    drop(table, "", KEYS);
+    for (name, known) in [("org", ORG_KEYS), ("clean", CLEAN_KEYS)] {
+        if let Some(toml::Value::Table(sub)) = table.get_mut(name) {
+            drop(sub, &format!("{name}."), known);
+        }
+    }
+    for key in USER_ONLY {
+        if table.remove(*key).is_some() {
+            warnings.push(format!(
+                "{}: `{key}` ignored; it runs commands, so only the user config may set it",
+                path.display()
+            ));
+        }
+    }
+    warnings
+}
+
+/// Merge `over` into `base`, recording `layer` as the origin of every key it sets. The
+/// `vars`, `org`, and `clean` tables merge key by key; [`APPENDED`] lists add up.
+fn merge(
+    base: &mut toml::Table,
+    over: toml::Table,
+    prefix: &str,
+    layer: &ConfigLayer,
+    origins: &mut BTreeMap<String, Vec<ConfigLayer>>,
+) {
+    for (key, value) in over {
+        let dotted = format!("{prefix}{key}");
+        let table = prefix.is_empty() && TABLES.contains(&key.as_str());
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(inner)), toml::Value::Table(value)) if table => {
+                merge(inner, value, &format!("{key}."), layer, origins);
+            }
+            (None, toml::Value::Table(value)) if table => {
+                let mut inner = toml::Table::new();
+                merge(&mut inner, value, &format!("{key}."), layer, origins);
+                base.insert(key, toml::Value::Table(inner));
+            }
+            (Some(toml::Value::Array(list)), toml::Value::Array(more)) if APPENDED.contains(&dotted.as_str()) => {
+                list.extend(more);
+                origins.entry(dotted).or_default().push(layer.clone());
+            }
+            (_, value) => {
+                origins.insert(dotted, vec![layer.clone()]);
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/py-proj/config.toml`, falling back to `~/.config/py-proj/config.toml`.
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| crate::util::home_dir().map(|h| h.join(".config")))?;
+    Some(base.join("py-proj").join("config.toml"))
+}
+
+/// Where py-proj keeps state between runs: `$XDG_STATE_HOME/py-proj`, falling back to
+/// `~/.local/state/py-proj`.
+pub fn state_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| crate::util::home_dir().map(|h| h.join(".local").join("state")))?;
+    Some(base.join("py-proj"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A user config and two nested repo rc files, `outer/.pyprojrc.toml` and
+    /// `outer/inner/.pyprojrc.toml`, with `outer/inner/deep` to run from.
+    fn layers() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let deep = dir.path().join("outer/inner/deep");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(
+            dir.path().join("user.toml"),
+            "python = \"3.10\"\nvenv_dir = \".v\"\nwith = [\"user\"]\n\n[org]\nname = \"user\"\nlicense = \"MIT\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("outer").join(RC_FILE),
+            "python = \"3.11\"\nwith = [\"outer\"]\n\n[org]\nname = \"outer\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("outer/inner").join(RC_FILE),
+            "python = \"3.12\"\nwith = [\"inner\"]\ntemplates_dir = \"tpl\"\n\n[org]\nname = \"inner\"\n\n[clean]\nextra_patterns = [\"*.log\"]\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn the_nearest_rc_wins_over_the_user_config() {
+        let dir = layers();
+        let user = dir.path().join("user.toml");
+        let inner = ConfigLayer::Repo(dir.path().join("outer/inner").join(RC_FILE));
+        let cfg = Config::load_from(Some(&user), &dir.path().join("outer/inner/deep")).unwrap();
+        assert_eq!(cfg.python.as_deref(), Some("3.12"));
+        assert_eq!(cfg.origins("python"), std::slice::from_ref(&inner));
+        assert_eq!(cfg.venv_dir.as_deref(), Some(".v"));
+        assert_eq!(cfg.origins("venv_dir"), [ConfigLayer::User(user.clone())]);
+        // Only the nearest rc is read; lists add up with the user config's.
+        assert_eq!(cfg.with, ["user", "inner"]);
+        assert_eq!(cfg.origins("with"), [ConfigLayer::User(user), inner]);
+        assert_eq!((cfg.org.name.as_deref(), cfg.org.license.as_deref()), (Some("inner"), Some("MIT")));
+        assert_eq!(cfg.clean.extra_patterns, ["*.log"]);
+        assert_eq!(cfg.templates_dir, Some(dir.path().join("outer/inner/tpl")));
+        assert!(cfg.warnings.is_empty(), "{:?}", cfg.warnings);
+
+        let cfg = Config::load_from(Some(&dir.path().join("user.toml")), &dir.path().join("outer")).unwrap();
+        assert_eq!(cfg.python.as_deref(), Some("3.11"));
+        assert_eq!(cfg.with, ["user", "outer"]);
+        let cfg = Config::load_from(Some(&dir.path().join("user.toml")), dir.path()).unwrap();
+        assert_eq!(cfg.python.as_deref(), Some("3.10"));
+        assert_eq!(cfg.value("org.name").and_then(toml::Value::as_str), Some("user"));
+    }
+
+    #[test]
+    fn an_rc_cannot_set_unknown_or_command_running_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = dir.path().join(RC_FILE);
+        fs::write(&rc, "pyhton = \"3.12\"\neditor = \"vim\"\npost_hooks = [\"rm -rf /\"]\n\n[org]\nlicence = \"MIT\"\n").unwrap();
+        let user = dir.path().join("user.toml");
+        fs::write(&user, "").unwrap();
+        let cfg = Config::load_from(Some(&user), dir.path()).unwrap();
+        assert_eq!((cfg.editor, cfg.post_hooks), (None, Vec::new()));
+        let warnings = cfg.warnings.join("\n");
+        for expected in ["unknown key `pyhton` ignored", "unknown key `org.licence` ignored", "`editor` ignored", "`post_hooks` ignored"] {
+            assert!(warnings.contains(expected), "no {expected:?} in:\n{warnings}");
+        }
+        // The user config is the user's own: a mistake there is an error.
+        fs::write(&user, "pyhton = \"3.12\"\n").unwrap();
+        let err = Config::load_from(Some(&user), dir.path()).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown field `pyhton`"), "{err:#}");
+    }
+}
@@ -0,0 +1,195 @@
+//! Failures scripts may want to tell apart. Everything else stays a plain `anyhow`
+//! error; these travel inside it and are recovered with [`Error::find`].
+
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+
+/// A failure with a stable exit code (see [`Error::exit_code`]).
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A required program (uv, git) is not installed or not on `PATH`.
+    #[error("`{tool}` was not found on PATH; {}", install_hint(.tool))]
+    MissingTool { tool: String },
+    /// A required program is too old for what py-proj asks of it.
+    #[error("`{tool}` {found} is too old; py-proj needs {required} or newer (upgrade: `uv self update`)")]
+    OutdatedTool {
+        tool: String,
+        found: String,
+        required: String,
+    },
+    /// Writing would clobber something that is already there.
+    #[error("refusing to overwrite {}: {hint}", path.display())]
+    TargetExists { path: PathBuf, hint: String },
+    #[error("invalid project name `{name}` ({reason})")]
+    InvalidProjectName { name: String, reason: &'static str },
+    /// An external command ran but exited unsuccessfully; `stderr` holds the end of
+    /// what it printed there (empty when it was not captured).
+    #[error("command `{program}` failed with status {status}{}", indented(.stderr))]
+    CommandFailed {
+        program: String,
+        status: ExitStatus,
+        stderr: String,
+    },
+    /// An external command ran longer than the command timeout and was killed;
+    /// `output` holds the last lines it printed.
+    #[error("command `{program}` timed out after {secs}s and was killed (raise --command-timeout){}", indented(.output))]
+    Timeout {
+        program: String,
+        secs: u64,
+        output: String,
+    },
+    /// The user pressed Ctrl-C (or sent SIGTERM): while `program` was running, if one
+    /// was, and with `rolled_back` files removed again if a scaffold was undone.
+    #[error("{}", interrupted(.program, .rolled_back))]
+    Interrupted {
+        program: Option<String>,
+        rolled_back: Option<usize>,
+    },
+    /// uv does not offer the requested Python version.
+    #[error("Python {version} not found{}", did_you_mean(.suggestions))]
+    UnknownPython {
+        version: String,
+        suggestions: Vec<String>,
+    },
+    /// `--trash` could not move `path` to the trash; nothing was removed.
+    #[error("could not move {} to the trash: {reason}", path.display())]
+    TrashUnavailable { path: PathBuf, reason: String },
+    /// Another py-proj run held the project's lock for longer than the lock timeout.
+    #[error("{} is held by {holder}; gave up after {secs}s (raise --lock-timeout, or remove the lock if that run is gone)", path.display())]
+    ProjectLocked { path: PathBuf, holder: String, secs: u64 },
+    /// A dangerous action was requested without its confirmation flag.
+    #[error("refusing to {action} without confirmation; pass {flag} to confirm")]
+    NeedsConfirmation { action: String, flag: &'static str },
+    /// The preflight checks before a create found something that would make it fail;
+    /// `checks` holds them all, the failed ones included.
+    #[error("preflight failed: {}; fix it, or pass --preflight-ignore <check> to go ahead anyway", failed(.checks))]
+    PreflightFailed { checks: Vec<crate::preflight::Check> },
+    /// No project at or above the directory a command was pointed at; `searched` are
+    /// the directories looked in, and `usage` how to name the project instead.
+    #[error(
+        "no project found in {} or its parents; looked for {} or pyproject.toml in:{}",
+        start.display(),
+        crate::manifest::MANIFEST_FILE,
+        searched_dirs(.searched)
+    )]
+    ProjectNotFound {
+        start: PathBuf,
+        searched: Vec<PathBuf>,
+        usage: String,
+    },
+}
+
+impl Error {
+    /// Process exit code: 2 usage, 3 missing tool, 4 conflict (or a locked project),
+    /// 5 external command failure, 6 timeout, 7 failed preflight checks, 130 interrupted.
+    /// Other errors (including a missing trash) exit with 1.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::InvalidProjectName { .. }
+            | Error::UnknownPython { .. }
+            | Error::NeedsConfirmation { .. }
+            | Error::ProjectNotFound { .. } => 2,
+            Error::MissingTool { .. } | Error::OutdatedTool { .. } => 3,
+            Error::TargetExists { .. } | Error::ProjectLocked { .. } => 4,
+            Error::CommandFailed { .. } => 5,
+            Error::Timeout { .. } => 6,
+            Error::PreflightFailed { .. } => 7,
+            Error::Interrupted { .. } => 130,
+            Error::TrashUnavailable { .. } => 1,
+        }
+    }
+
+    /// The variant name, as reported in `--json` error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::MissingTool { .. } => "MissingTool",
+            Error::OutdatedTool { .. } => "OutdatedTool",
+            Error::TargetExists { .. } => "TargetExists",
+            Error::ProjectLocked { .. } => "ProjectLocked",
+            Error::InvalidProjectName { .. } => "InvalidProjectName",
+            Error::CommandFailed { .. } => "CommandFailed",
+            Error::Timeout { .. } => "Timeout",
+            Error::Interrupted { .. } => "Interrupted",
+            Error::UnknownPython { .. } => "UnknownPython",
+            Error::NeedsConfirmation { .. } => "NeedsConfirmation",
+            Error::TrashUnavailable { .. } => "TrashUnavailable",
+            Error::PreflightFailed { .. } => "PreflightFailed",
+            Error::ProjectNotFound { .. } => "ProjectNotFound",
+        }
+    }
+
+    /// What to do about it, for the `hint:` line under the error (and `--json`).
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            Error::ProjectNotFound { usage, .. } => Some(format!(
+                "run this inside a project, or pass the project's path: `{usage}`"
+            )),
+            _ => None,
+        }
+    }
+
+    /// [`Error::ProjectNotFound`] for a search that started at `start`; `usage` is the
+    /// command line that names the project, e.g. `py-proj info path/to/proj`.
+    pub fn project_not_found(start: &Path, usage: impl Into<String>) -> Error {
+        let start = std::path::absolute(start).unwrap_or_else(|_| start.to_path_buf());
+        Error::ProjectNotFound {
+            searched: start.ancestors().map(Path::to_path_buf).collect(),
+            start,
+            usage: usage.into(),
+        }
+    }
+
+    /// The typed error somewhere in `err`'s chain, if any.
+    pub fn find(err: &anyhow::Error) -> Option<&Error> {
+        err.chain().find_map(|e| e.downcast_ref::<Error>())
+    }
+}
+
+/// How to get `tool`: the uv installer for uv, a generic hint otherwise.
+fn install_hint(tool: &str) -> String {
+    let name = std::path::Path::new(tool).file_stem().unwrap_or_default();
+    match name.to_str() {
+        Some("uv") => format!("install it with `{}`, or pass --skip-uv", crate::util::uv_install_hint()),
+        _ => "install it or put it on PATH".to_string(),
+    }
+}
+
+/// `text` as an indented block below the message, or nothing when it is empty.
+fn indented(text: &str) -> String {
+    text.lines().map(|line| format!("\n    {line}")).collect()
+}
+
+/// The directories a project search looked in, one per indented line.
+fn searched_dirs(dirs: &[PathBuf]) -> String {
+    dirs.iter().map(|d| format!("\n    {}", d.display())).collect()
+}
+
+/// `Interrupted — rolled back 3 files`, or which command was running when there was
+/// nothing to roll back.
+fn interrupted(program: &Option<String>, rolled_back: &Option<usize>) -> String {
+    match (program, rolled_back) {
+        (_, Some(1)) => "Interrupted — rolled back 1 file".to_string(),
+        (_, Some(n)) => format!("Interrupted — rolled back {n} files"),
+        (Some(program), None) => format!("interrupted while `{program}` was running"),
+        (None, None) => "interrupted".to_string(),
+    }
+}
+
+/// `uv (not found on PATH), disk (...)`: the failed checks that were not ignored.
+fn failed(checks: &[crate::preflight::Check]) -> String {
+    checks
+        .iter()
+        .filter(|c| c.blocks())
+        .map(|c| format!("{} ({})", c.name, c.detail))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `; did you mean 3.13.0 or 3.12.8?`, or nothing without suggestions.
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!("; did you mean {only}?"),
+        [rest @ .., last] => format!("; did you mean {} or {last}?", rest.join(", ")),
+    }
+}
@@ -0,0 +1,82 @@
+//! Progress reporting. The library never prints; it reports what it does as
+//! [`Event`]s to a callback, and the CLI turns them into terminal output.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::pyproject::Replaced;
+use crate::python::PythonVersion;
+use crate::scaffold::Origin;
+use crate::util::WriteOutcome;
+
+/// Something the scaffolder did or is about to do.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A planned file was written, or left alone (see `outcome`).
+    FileWritten {
+        /// Relative to the project root.
+        path: &'a Path,
+        origin: Origin,
+        /// The `--templates-dir` file it came from, for overrides and extras.
+        source: Option<&'a Path>,
+        outcome: &'a WriteOutcome,
+    },
+    /// External command `step` (1-based) of `steps` is about to run, usually uv.
+    CommandStarted {
+        label: &'a str,
+        /// The exact command line, each argument shell-quoted as needed (display only;
+        /// commands run without a shell).
+        command: &'a str,
+        step: usize,
+        steps: usize,
+    },
+    /// A line the running command printed (stdout or stderr), as it arrives.
+    CommandOutput { program: &'a str, line: &'a str },
+    /// The command from the last [`Event::CommandStarted`] exited (or was killed);
+    /// `exit_code` is `None` when it did not start or was killed by a signal.
+    CommandFinished {
+        success: bool,
+        elapsed: Duration,
+        exit_code: Option<i32>,
+    },
+    /// The project already had a `.venv` (built with Python `version`, when its
+    /// pyvenv.cfg says), so it was not created again.
+    VenvKept { version: Option<&'a str> },
+    /// uv already has Python `version`, so `uv python install` is not run.
+    PythonInstallSkipped { version: &'a PythonVersion },
+    /// Post-create hook `index` (1-based) of `total` is about to run.
+    HookStarted {
+        index: usize,
+        total: usize,
+        command: &'a str,
+    },
+    /// `--bootstrap-uv` installed uv at `path`, which may not be on `PATH` yet.
+    UvInstalled { path: &'a Path },
+    /// A file of the uv workspace root changed, e.g. "workspace members".
+    WorkspaceUpdated { what: &'a str },
+    /// A template pack was found in the cache and will not be cloned.
+    TemplatesCached { url: &'a str, path: &'a Path },
+    /// A template pack is about to be cloned.
+    TemplatesCloning { url: &'a str },
+    /// A failed scaffold is being undone.
+    RollbackStarted,
+    /// Rollback removed `path` (with everything below it when `recursive`).
+    RolledBack { path: &'a Path, recursive: bool },
+    /// An existing pyproject.toml was merged into rather than replaced; `replaced`
+    /// lists the keys of py-proj's tables that changed value.
+    PyprojectMerged {
+        added: &'a [String],
+        replaced: &'a [Replaced],
+    },
+    /// Something went wrong, but the operation carries on.
+    Warning { message: String },
+}
+
+/// Callback that receives [`Event`]s.
+pub type EventHandler = Box<dyn Fn(&Event<'_>)>;
+
+/// A handler that ignores every event.
+pub fn silent() -> EventHandler {
+    Box::new(|_| {})
+}
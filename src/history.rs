@@ -0,0 +1,151 @@
+//! The history log: one JSON line per create, clean, delete, or upgrade, appended to
+//! `history.log` in the [state directory](crate::config::state_dir), so what touched a
+//! project and when can be looked up later. Nothing in it leaves the machine.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::state_dir;
+use crate::manifest::now_rfc3339;
+
+pub const HISTORY_FILE: &str = "history.log";
+
+/// The format of the entries written now. Format 1 (entries without a `format`) has no
+/// `ms`, `phases`, `error_kind`, or `freed`.
+pub const FORMAT: u32 = 2;
+
+/// One recorded run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// RFC 3339, UTC.
+    pub time: String,
+    /// The py-proj version that ran.
+    pub version: String,
+    /// `create`, `clean`, `delete`, or `upgrade`.
+    pub command: String,
+    /// The project root, made absolute.
+    pub path: PathBuf,
+    /// The command line after the program name.
+    pub args: Vec<String>,
+    /// `ok`, `dry run`, `skipped`, or `failed: <error>`.
+    pub outcome: String,
+    /// See [`FORMAT`].
+    #[serde(default = "first_format")]
+    pub format: u32,
+    /// How long the run took.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ms: Option<f64>,
+    /// How long each phase of a create took, in the order they ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<Phase>,
+    /// For a failed run, the [`crate::Error::kind`] of its error, or `Other`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+    /// What a clean removed, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freed: Option<u64>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+/// One phase of a run (see [`crate::scaffold::PlannedAction::phase`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Phase {
+    pub phase: String,
+    pub ms: f64,
+}
+
+fn first_format() -> u32 {
+    1
+}
+
+impl Entry {
+    /// An entry for `command` on `path`, stamped now; `outcome` is filled in later.
+    pub fn new(command: &str, path: &Path, args: Vec<String>) -> Entry {
+        Entry {
+            time: now_rfc3339(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            command: command.to_string(),
+            path: std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+            args,
+            outcome: String::new(),
+            format: FORMAT,
+            ms: None,
+            phases: Vec::new(),
+            error_kind: None,
+            freed: None,
+            started: Some(Instant::now()),
+        }
+    }
+
+    /// Record the run as over: how long it took since [`Entry::new`].
+    pub fn finish(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.ms = Some(ms(started.elapsed()));
+        }
+    }
+
+    /// Record how long each of `timings`'s phases took.
+    pub fn set_phases(&mut self, timings: &[(String, Duration)]) {
+        self.phases = timings
+            .iter()
+            .map(|(phase, elapsed)| Phase { phase: phase.clone(), ms: ms(*elapsed) })
+            .collect();
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// `<state dir>/history.log`, or `None` when there is no home directory to put it in.
+pub fn log_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Append `entry` to the log at `path`, creating it (and its directory) if needed.
+pub fn append(path: &Path, entry: &Entry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(line.as_bytes()))
+        .with_context(|| format!("failed to append to {}", path.display()))
+}
+
+/// The last `limit` entries of the log at `path`, oldest first, keeping only those for
+/// `project` or a directory below it when given. A missing log reads as empty, and
+/// lines that do not parse (say, from a newer py-proj) are skipped.
+pub fn read(path: &Path, project: Option<&Path>, limit: usize) -> Result<Vec<Entry>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let project = project.map(std::path::absolute).transpose()?;
+    let entries: Vec<Entry> = text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+        .filter(|e| project.as_ref().is_none_or(|p| e.path.starts_with(p)))
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+/// Remove the log at `path`; false when there was none.
+pub fn clear(path: &Path) -> Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
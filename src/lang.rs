@@ -0,0 +1,384 @@
+//! Languages for the prose in generated files (`--lang`): docstrings and comments, the
+//! README, the Makefile's comments and help text, and the next steps printed after a
+//! create. Templates see the active catalog as `{{tr}}` (`{{tr.readme_quickstart}}`)
+//! and its code as `{{lang}}`. Identifiers, config keys, and log formats stay English.
+//!
+//! English and Japanese are built in. A templates dir adds a language, or rewords a
+//! built-in one, with `lang/<code>.toml`: a flat table of keys from [`keys`] to text,
+//! where any key it leaves out keeps the built-in (or English) wording. `{name}`-style
+//! placeholders in a text are filled in where it is used.
+
+use anyhow::{bail, Context, Result};
+use minijinja::value::{Serde, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The language used when none is given; its output is the untranslated templates.
+pub const DEFAULT_LANG: &str = "en";
+
+/// Where a templates dir keeps its catalogs (`lang/<code>.toml`). Files below it are
+/// not written into the project.
+pub const CATALOG_DIR: &str = "lang";
+
+/// The catalogs built into py-proj.
+pub const BUILTIN: &[&str] = &["en", "ja"];
+
+/// The text for every key, in one language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strings {
+    lang: String,
+    text: BTreeMap<&'static str, String>,
+}
+
+impl Strings {
+    /// The English catalog.
+    pub fn english() -> Strings {
+        Strings {
+            lang: DEFAULT_LANG.to_string(),
+            text: EN.iter().map(|&(key, text)| (key, text.to_string())).collect(),
+        }
+    }
+
+    /// The catalog for `lang`: English, overlaid with the built-in catalog for `lang`,
+    /// overlaid with `lang/<lang>.toml` from `templates_dir` when it has one.
+    pub fn load(lang: &str, templates_dir: Option<&Path>) -> Result<Strings> {
+        if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            bail!("invalid language `{lang}`; expected a code such as en or ja");
+        }
+        let mut strings = Strings::english();
+        strings.lang = lang.to_string();
+        let builtin = match lang {
+            "en" => Some(EN),
+            "ja" => Some(JA),
+            _ => None,
+        };
+        for &(key, text) in builtin.unwrap_or_default() {
+            strings.text.insert(key, text.to_string());
+        }
+        let file = templates_dir
+            .map(|dir| dir.join(CATALOG_DIR).join(format!("{lang}.toml")))
+            .filter(|path| path.is_file());
+        match file {
+            Some(path) => strings.overlay(&path)?,
+            None if builtin.is_none() => bail!(
+                "unknown language `{lang}`; built in: {}, or add {CATALOG_DIR}/{lang}.toml to a templates dir",
+                BUILTIN.join(", ")
+            ),
+            None => {}
+        }
+        Ok(strings)
+    }
+
+    fn overlay(&mut self, path: &Path) -> Result<()> {
+        let text = fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let table: BTreeMap<String, String> =
+            toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+        for (key, text) in table {
+            let Some((&key, _)) = self.text.get_key_value(key.as_str()) else {
+                bail!("unknown key `{key}` in {}", path.display());
+            };
+            self.text.insert(key, text);
+        }
+        Ok(())
+    }
+
+    /// The language code.
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// The text for `key` (the key itself for one that is not in the catalog).
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.text.get(key).map_or(key, String::as_str)
+    }
+
+    /// The text for `key` with each `{name}` placeholder replaced by its value.
+    pub fn fill(&self, key: &str, values: &[(&str, &str)]) -> String {
+        values
+            .iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| text.replace(&format!("{{{name}}}"), value))
+    }
+
+    /// The catalog as the `tr` template variable.
+    pub fn to_value(&self) -> Value {
+        Value::from(Serde(&self.text))
+    }
+}
+
+/// Every catalog key, in the order of the English catalog.
+pub fn keys() -> impl Iterator<Item = &'static str> {
+    EN.iter().map(|&(key, _)| key)
+}
+
+const EN: &[(&str, &str)] = &[
+    ("main_run_doc", "Set up logging before the event loop starts, then run `main` on it."),
+    ("clamp_doc", "`value` limited to `low..high`"),
+    ("clamp_doc_see", "(see tests/test_properties.py)"),
+    ("log_formatter_doc", "Custom log formatter to add colors to log messages based on the log level\n    and other attributes such as timestamp, filename, function name, and line number."),
+    ("log_timestamp", "Generate timestamp with formatTime method including timezone"),
+    ("log_add_color", "Add color to different fields"),
+    ("log_construct", "Construct formatted log line"),
+    ("log_ansi", "Define ANSI escape codes for colors"),
+    ("log_debug_color", "Cyan for DEBUG level"),
+    ("log_info_color", "light magenta"),
+    ("log_warning_color", "Yellow for WARNING level"),
+    ("log_error_color", "Red for ERROR level"),
+    ("log_critical_color", "Bold Red for CRITICAL level"),
+    ("log_field_colors", "Additional colors for log fields"),
+    ("log_filename_color", "Magenta for filename"),
+    ("log_funcname_color", "Blue for function name"),
+    ("log_lineno_color", "Cyan for line number"),
+    ("log_time_color", "Light gray for timestamp"),
+    ("log_project_logger", "your top-level package"),
+    ("log_stdout_filter", "Allow only DEBUG and INFO messages for stdout."),
+    ("log_stderr_filter", "Allow only WARNING, ERROR, and CRITICAL messages for stderr."),
+    ("mk_config", "CONFIG"),
+    ("mk_run_app", "RUN APP"),
+    ("mk_linting", "LINTING"),
+    ("mk_formatting", "FORMATTING"),
+    ("mk_type_checking", "TYPE CHECKING"),
+    ("mk_security", "SECURITY"),
+    ("mk_testing", "TESTING"),
+    ("mk_cleaning", "CLEANING"),
+    ("mk_help", "HELP"),
+    ("mk_uv_run", "Every Python tool runs through `uv run`, so targets behave the same whether or not\n# the venv is activated."),
+    ("mk_tools_global", "ruff and pyright are uv tools; `uv run` finds them on PATH."),
+    ("mk_no_color", "No Color"),
+    ("mk_run_command", "Run the {name} command"),
+    ("mk_install", "Install the project and its dev tools"),
+    ("mk_run_main", "Run the main application"),
+    ("mk_serve", "Serve the web app with uvicorn, reloading on changes (HOST, PORT)"),
+    ("mk_lint", "Run Ruff linter"),
+    ("mk_lint_fix", "Run Ruff with auto-fix"),
+    ("mk_fmt", "Format code with Black"),
+    ("mk_fmt_check", "Check formatting with Black"),
+    ("mk_typecheck", "Static type check with Pyright"),
+    ("mk_security_scan", "Scan for security issues with bandit"),
+    ("mk_test", "Run Pytest"),
+    ("mk_bench", "Run the benchmarks (pytest-benchmark; results saved to .benchmarks/)"),
+    ("mk_coverage", "Run tests with coverage report"),
+    ("mk_clean", "Remove build/test/cache artifacts"),
+    ("mk_show_help", "Show this help"),
+    ("mk_available", "Available make targets:"),
+    ("mk_help_comment", "Lists every target annotated with `## description` on its rule line."),
+    ("nvim_header", "Project-local Neovim settings, loaded with 'exrc' on (see README.md). Start nvim in the project root."),
+    ("nvim_venv", "The venv's tools (ruff, pytest, python) come first."),
+    ("nvim_pyright", "pyright uses the venv's interpreter (pyrightconfig.json says the same)."),
+    ("readme_generated", "Generated by PY-PROJ scaffolder."),
+    ("readme_quickstart", "Quickstart"),
+    ("readme_structure", "Project structure"),
+    ("readme_development", "Development"),
+    ("readme_data_heading", "Data"),
+    ("readme_webapp_heading", "Web app"),
+    ("readme_notebooks_heading", "Notebooks"),
+    ("readme_releasing_heading", "Releasing"),
+    ("readme_maintainers_heading", "Maintainers"),
+    ("readme_same_via_main", "the same, via __main__.py"),
+    ("readme_local_settings", "local settings; .env is git-ignored"),
+    ("readme_or_source", "or: source {venv}/bin/activate   (prompt: ({prompt}))"),
+    ("readme_prompt", "prompt: ({prompt})"),
+    ("readme_uv_env", "so uv run uses it, not .venv"),
+    ("readme_tree_package", "the package"),
+    ("readme_tree_package_cli", "(cli.py: the {name} command, also `python -m {module}`)"),
+    ("readme_tree_benchmarks", "pytest-benchmark, outside tests/"),
+    ("readme_tree_entry", "entry point"),
+    ("readme_tree_async", "(async def main, run by asyncio.run)"),
+    ("readme_tree_command", "the {name} command"),
+    ("readme_tree_app", "create_app(): the FastAPI app (make serve)"),
+    ("readme_tree_settings", "Settings, from the environment and .env"),
+    ("readme_tree_logging", "queue-based logging setup"),
+    ("readme_tree_jupytext", "starter.py, paired with starter.ipynb by jupytext"),
+    ("readme_tree_data", "raw/, interim/, processed/ (contents git-ignored)"),
+    ("readme_tree_models", "trained models (contents git-ignored)"),
+    ("readme_tree_dvc", "DVC pipeline stages"),
+    ("readme_tree_vscode", "launch, settings, and tasks"),
+    ("readme_tree_nvim", "Neovim: the venv and pyright (needs 'exrc')"),
+    ("readme_tree_githooks", "pre-commit (ruff) and pre-push (pytest)"),
+    ("readme_tree_env_example", "copy to .env (git-ignored) for local settings"),
+    ("readme_tree_nox", "nox sessions for Python {versions}"),
+    ("readme_tree_tox", "tox environments for Python {versions}"),
+    ("readme_dev_workspace", "Run from the workspace root, which holds the shared `.venv`:"),
+    ("readme_lint", "lint"),
+    ("readme_format", "format"),
+    ("readme_type_check", "type check"),
+    ("readme_test", "test"),
+    ("readme_benchmark", "benchmark"),
+    ("readme_run", "run"),
+    ("readme_make_run_command", "run the {name} command"),
+    ("readme_make_install", "install the project and its dev tools"),
+    ("readme_make_run", "run src.main"),
+    ("readme_make_lint", "ruff check (make lint-fix to fix)"),
+    ("readme_make_fmt", "black (make fmt-check to only check)"),
+    ("readme_make_security", "bandit (configured in pyproject.toml)"),
+    ("readme_make_test", "pytest (make coverage for a coverage report)"),
+    ("readme_make_bench", "pytest-benchmark on benchmarks/ (results saved to .benchmarks/)"),
+    ("readme_make_clean", "remove build/test/cache artifacts"),
+    ("readme_tools_global", "ruff and pyright are uv tools (`uv tool install ruff pyright`), not dev dependencies."),
+    ("readme_ruff_s", "`make lint` includes ruff's `S` security rules (flake8-bandit); tests may still `assert`."),
+    ("readme_hypothesis", "`tests/test_properties.py` holds hypothesis property tests; `tests/conftest.py` runs more\nexamples under CI (`$CI` set, or `HYPOTHESIS_PROFILE=ci`) than locally."),
+    ("readme_matrix_nox", "Test every supported Python ({versions}) with\n`uv run nox` (`uv run nox -l` lists the sessions)."),
+    ("readme_matrix_tox", "Test every supported Python ({versions}) with\n`uv run tox` (`uv run tox -l` lists the environments)."),
+    ("readme_git_hooks", "`.githooks/pre-commit` runs ruff before each commit and `.githooks/pre-push` runs pytest\nbefore each push. After cloning, enable them with `git config core.hooksPath .githooks`;\n`--no-verify` skips them once."),
+    ("readme_neovim", "`.nvim.lua` puts `{venv}/bin` first on Neovim's `PATH`, so ruff, pytest, and `:!python` use the\nvenv, and points pyright at `{venv}/bin/python`. Neovim reads it only with `'exrc'` on, and asks\nonce whether to trust it. Turn that on, and with Neovim 0.11 or later enable the language servers\n(pyright and ruff on `PATH` or in the venv), in `init.lua`:"),
+    ("readme_neovim_lspconfig", "On an older Neovim with nvim-lspconfig, the first line is enough: `.nvim.lua` sets up pyright."),
+    ("readme_data", "`data/raw/` holds the inputs as received, `data/interim/` intermediate results, and\n`data/processed/` the final datasets; trained models go in `models/`. Their contents are\ngit-ignored. `src/paths.py` names them as `pathlib` paths below `DATA_ROOT` (see `.env`)."),
+    ("readme_dvc", "`dvc.yaml` is a DVC pipeline with no stages yet: `uv add --dev dvc`, `uv run dvc init`,\nthen add stages and run `uv run dvc repro`."),
+    ("readme_webapp", "`src/app.py` builds the FastAPI application in `create_app()`, with a `/health` route, and\n`src/settings.py` reads its settings from the environment and `.env` with pydantic-settings.\n`make serve` runs it with uvicorn at http://127.0.0.1:8000, reloading on changes (the API\ndocs are at `/docs`); the \"uvicorn src.app\" launch configuration does the same in the VS\nCode debugger. `tests/test_app.py` calls the app in-process through httpx."),
+    ("readme_notebooks", "`{notebooks}/` is on `PYTHONPATH` (see `.env`), so notebooks can `import src`.\nPick the `{venv}` interpreter as the kernel."),
+    ("readme_jupytext", "Each notebook is paired with a percent-format `.py` file by jupytext (`[tool.jupytext]` in\npyproject.toml); review and diff the `.py` side. After editing either one, run\n`uv run jupytext --sync {notebooks}/*.py`."),
+    ("readme_jupytext_ignore", "The `.ipynb` files are git-ignored: a fresh clone gets them\nback with the same command."),
+    ("readme_releasing", "1. Add the changes under `## [Unreleased]` in CHANGELOG.md.\n2. `py-proj bump patch --git-tag` (or `minor`/`major`) raises the version, dates the\n   changelog, and tags the commit `v<version>`.\n3. `git push --follow-tags`: `.github/workflows/release.yml` builds the sdist and wheel\n   with `uv build`, publishes them to PyPI with trusted publishing, and attaches them to\n   the GitHub release.\n\nBefore the first release, add this repository as a trusted publisher on PyPI (workflow\n`release.yml`, environment `pypi`)."),
+    ("next_heading", "Next steps:"),
+    ("next_enter", "enter the project"),
+    ("next_fetch_python", "fetch the Python skipped by --offline"),
+    ("next_workspace_venv", "create the workspace venv skipped by --skip-uv"),
+    ("next_member_dev", "install the member's dev tools"),
+    ("next_check_import", "check the package imports"),
+    ("next_console_script", "run the console script"),
+    ("next_create_venv", "create the venv skipped by --skip-uv"),
+    ("next_activate", "activate the venv; the prompt shows ({prompt})"),
+    ("next_uv_env", "point uv run at the venv instead of .venv"),
+    ("next_install", "install the project and its dev tools"),
+    ("next_entry", "run the entry point"),
+    ("next_tests", "run the tests"),
+    ("next_direnv", "load .envrc when you cd in"),
+    ("next_publish", "publish the repository"),
+    ("cli_project_created", "Project created"),
+];
+
+const JA: &[(&str, &str)] = &[
+    ("main_run_doc", "イベントループの開始前にロギングを設定し、その上で `main` を実行する。"),
+    ("clamp_doc", "`low..high` の範囲に収めた `value`"),
+    ("clamp_doc_see", "(tests/test_properties.py を参照)"),
+    ("log_formatter_doc", "ログレベルに応じてログメッセージに色を付けるカスタムフォーマッタ。\n    タイムスタンプ、ファイル名、関数名、行番号などの属性にも色を付ける。"),
+    ("log_timestamp", "formatTime でタイムゾーン付きのタイムスタンプを生成する"),
+    ("log_add_color", "各フィールドに色を付ける"),
+    ("log_construct", "整形済みのログ行を組み立てる"),
+    ("log_ansi", "色の ANSI エスケープコード"),
+    ("log_debug_color", "DEBUG レベルはシアン"),
+    ("log_info_color", "ライトマゼンタ"),
+    ("log_warning_color", "WARNING レベルは黄色"),
+    ("log_error_color", "ERROR レベルは赤"),
+    ("log_critical_color", "CRITICAL レベルは太字の赤"),
+    ("log_field_colors", "ログの各フィールドの色"),
+    ("log_filename_color", "ファイル名はマゼンタ"),
+    ("log_funcname_color", "関数名は青"),
+    ("log_lineno_color", "行番号はシアン"),
+    ("log_time_color", "タイムスタンプはライトグレー"),
+    ("log_project_logger", "最上位のパッケージ"),
+    ("log_stdout_filter", "stdout には DEBUG と INFO のメッセージだけを通す。"),
+    ("log_stderr_filter", "stderr には WARNING、ERROR、CRITICAL のメッセージだけを通す。"),
+    ("mk_config", "設定"),
+    ("mk_run_app", "アプリの実行"),
+    ("mk_linting", "リント"),
+    ("mk_formatting", "フォーマット"),
+    ("mk_type_checking", "型チェック"),
+    ("mk_security", "セキュリティ"),
+    ("mk_testing", "テスト"),
+    ("mk_cleaning", "クリーンアップ"),
+    ("mk_help", "ヘルプ"),
+    ("mk_uv_run", "Python のツールはすべて `uv run` 経由で動くので、venv を有効にしていてもいなくても\n# ターゲットの動作は同じ。"),
+    ("mk_tools_global", "ruff と pyright は uv tool なので、`uv run` は PATH から見つける。"),
+    ("mk_no_color", "色なし"),
+    ("mk_run_command", "{name} コマンドを実行する"),
+    ("mk_install", "プロジェクトと開発ツールをインストールする"),
+    ("mk_run_main", "メインアプリケーションを実行する"),
+    ("mk_serve", "uvicorn で Web アプリを起動し、変更時に再読み込みする (HOST, PORT)"),
+    ("mk_lint", "Ruff でリントする"),
+    ("mk_lint_fix", "Ruff で自動修正する"),
+    ("mk_fmt", "Black でコードを整形する"),
+    ("mk_fmt_check", "Black で整形済みか確認する"),
+    ("mk_typecheck", "Pyright で静的型チェックする"),
+    ("mk_security_scan", "bandit でセキュリティ上の問題を探す"),
+    ("mk_test", "Pytest を実行する"),
+    ("mk_bench", "ベンチマークを実行する (pytest-benchmark、結果は .benchmarks/ に保存)"),
+    ("mk_coverage", "カバレッジレポート付きでテストを実行する"),
+    ("mk_clean", "ビルド・テスト・キャッシュの生成物を削除する"),
+    ("mk_show_help", "このヘルプを表示する"),
+    ("mk_available", "利用できる make ターゲット:"),
+    ("mk_help_comment", "ルール行に `## 説明` が付いたターゲットをすべて一覧表示する。"),
+    ("nvim_header", "プロジェクト用の Neovim 設定。'exrc' が有効なときに読み込まれる (README.md を参照)。nvim はプロジェクトのルートで起動する。"),
+    ("nvim_venv", "venv のツール (ruff、pytest、python) を優先する。"),
+    ("nvim_pyright", "pyright は venv のインタープリターを使う (pyrightconfig.json と同じ)。"),
+    ("readme_generated", "PY-PROJ で生成。"),
+    ("readme_quickstart", "クイックスタート"),
+    ("readme_structure", "プロジェクト構成"),
+    ("readme_development", "開発"),
+    ("readme_data_heading", "データ"),
+    ("readme_webapp_heading", "Web アプリ"),
+    ("readme_notebooks_heading", "ノートブック"),
+    ("readme_releasing_heading", "リリース"),
+    ("readme_maintainers_heading", "メンテナー"),
+    ("readme_same_via_main", "__main__.py 経由で同じもの"),
+    ("readme_local_settings", "ローカル設定。.env は git 管理外"),
+    ("readme_or_source", "または: source {venv}/bin/activate   (プロンプト: ({prompt}))"),
+    ("readme_prompt", "プロンプト: ({prompt})"),
+    ("readme_uv_env", "uv run が .venv ではなくこちらを使うように"),
+    ("readme_tree_package", "パッケージ"),
+    ("readme_tree_package_cli", "(cli.py: {name} コマンド、`python -m {module}` でも実行可)"),
+    ("readme_tree_benchmarks", "pytest-benchmark、tests/ の外"),
+    ("readme_tree_entry", "エントリーポイント"),
+    ("readme_tree_async", "(async def main を asyncio.run で実行)"),
+    ("readme_tree_command", "{name} コマンド"),
+    ("readme_tree_app", "create_app(): FastAPI アプリ (make serve)"),
+    ("readme_tree_settings", "環境変数と .env から読む Settings"),
+    ("readme_tree_logging", "キューを使うロギング設定"),
+    ("readme_tree_jupytext", "starter.py、jupytext で starter.ipynb と対になる"),
+    ("readme_tree_data", "raw/、interim/、processed/ (中身は git 管理外)"),
+    ("readme_tree_models", "学習済みモデル (中身は git 管理外)"),
+    ("readme_tree_dvc", "DVC パイプラインのステージ"),
+    ("readme_tree_vscode", "launch、settings、tasks"),
+    ("readme_tree_nvim", "Neovim 用: venv と pyright の設定 ('exrc' が必要)"),
+    ("readme_tree_githooks", "pre-commit (ruff) と pre-push (pytest)"),
+    ("readme_tree_env_example", ".env にコピーしてローカル設定に使う (git 管理外)"),
+    ("readme_tree_nox", "Python {versions} 用の nox セッション"),
+    ("readme_tree_tox", "Python {versions} 用の tox 環境"),
+    ("readme_dev_workspace", "共有の `.venv` があるワークスペースのルートで実行する:"),
+    ("readme_lint", "リント"),
+    ("readme_format", "整形"),
+    ("readme_type_check", "型チェック"),
+    ("readme_test", "テスト"),
+    ("readme_benchmark", "ベンチマーク"),
+    ("readme_run", "実行"),
+    ("readme_make_run_command", "{name} コマンドを実行"),
+    ("readme_make_install", "プロジェクトと開発ツールをインストール"),
+    ("readme_make_run", "src.main を実行"),
+    ("readme_make_lint", "ruff check (修正は make lint-fix)"),
+    ("readme_make_fmt", "black (確認だけなら make fmt-check)"),
+    ("readme_make_security", "bandit (設定は pyproject.toml)"),
+    ("readme_make_test", "pytest (カバレッジレポートは make coverage)"),
+    ("readme_make_bench", "benchmarks/ で pytest-benchmark (結果は .benchmarks/ に保存)"),
+    ("readme_make_clean", "ビルド・テスト・キャッシュの生成物を削除"),
+    ("readme_tools_global", "ruff と pyright は開発依存ではなく uv tool (`uv tool install ruff pyright`)。"),
+    ("readme_ruff_s", "`make lint` には ruff の `S` セキュリティルール (flake8-bandit) が含まれる。テストでは `assert` を使ってよい。"),
+    ("readme_hypothesis", "`tests/test_properties.py` は hypothesis のプロパティテスト。`tests/conftest.py` により、CI\n(`$CI` が設定されているか `HYPOTHESIS_PROFILE=ci`) ではローカルより多くの例を試す。"),
+    ("readme_matrix_nox", "サポートするすべての Python ({versions}) でのテストは\n`uv run nox` (`uv run nox -l` でセッション一覧)。"),
+    ("readme_matrix_tox", "サポートするすべての Python ({versions}) でのテストは\n`uv run tox` (`uv run tox -l` で環境一覧)。"),
+    ("readme_git_hooks", "`.githooks/pre-commit` はコミットの前に ruff を、`.githooks/pre-push` はプッシュの前に pytest を\n実行する。クローンしたら `git config core.hooksPath .githooks` で有効にする。\n`--no-verify` で一度だけ飛ばせる。"),
+    ("readme_neovim", "`.nvim.lua` は Neovim の `PATH` の先頭に `{venv}/bin` を加え (ruff、pytest、`:!python` が venv を\n使う)、pyright のインタープリターを `{venv}/bin/python` にする。Neovim は `'exrc'` が有効なときだけこれを\n読み込み、最初に信頼してよいか確認する。`init.lua` で `'exrc'` を有効にし、Neovim 0.11 以降では言語サーバー\n(`PATH` か venv にある pyright と ruff) も有効にする:"),
+    ("readme_neovim_lspconfig", "古い Neovim で nvim-lspconfig を使う場合は最初の行だけでよい (`.nvim.lua` が pyright を設定する)。"),
+    ("readme_data", "`data/raw/` には受け取ったままの入力、`data/interim/` には中間結果、`data/processed/` には\n最終的なデータセットを置き、学習済みモデルは `models/` に置く。これらの中身は git 管理外。\n`src/paths.py` はこれらを `DATA_ROOT` (`.env` を参照) 以下の `pathlib` のパスとして定義する。"),
+    ("readme_dvc", "`dvc.yaml` はまだステージのない DVC パイプライン。`uv add --dev dvc`、`uv run dvc init` の後、\nステージを追加して `uv run dvc repro` を実行する。"),
+    ("readme_webapp", "`src/app.py` は `create_app()` で `/health` ルート付きの FastAPI アプリケーションを作り、\n`src/settings.py` は pydantic-settings で環境変数と `.env` から設定を読む。\n`make serve` は uvicorn で http://127.0.0.1:8000 に起動し、変更時に再読み込みする (API\nドキュメントは `/docs`)。launch 構成の \"uvicorn src.app\" は同じことを VS Code のデバッガーで行う。\n`tests/test_app.py` は httpx でアプリをプロセス内から呼び出す。"),
+    ("readme_notebooks", "`{notebooks}/` は `PYTHONPATH` に含まれる (`.env` を参照) ので、ノートブックから `import src` できる。\nカーネルには `{venv}` のインタープリターを選ぶ。"),
+    ("readme_jupytext", "各ノートブックは jupytext (pyproject.toml の `[tool.jupytext]`) で percent 形式の `.py`\nファイルと対になる。レビューと差分は `.py` 側で行う。どちらかを編集したら\n`uv run jupytext --sync {notebooks}/*.py` を実行する。"),
+    ("readme_jupytext_ignore", "`.ipynb` ファイルは git 管理外で、新しいクローンでは\n同じコマンドで復元する。"),
+    ("readme_releasing", "1. CHANGELOG.md の `## [Unreleased]` に変更点を書く。\n2. `py-proj bump patch --git-tag` (または `minor`/`major`) でバージョンを上げ、changelog に\n   日付を入れ、コミットに `v<version>` のタグを付ける。\n3. `git push --follow-tags`: `.github/workflows/release.yml` が `uv build` で sdist と wheel を\n   ビルドし、trusted publishing で PyPI に公開し、GitHub のリリースに添付する。\n\n最初のリリースの前に、このリポジトリを PyPI の trusted publisher として登録する (ワークフロー\n`release.yml`、環境 `pypi`)。"),
+    ("next_heading", "次のステップ:"),
+    ("next_enter", "プロジェクトに移動する"),
+    ("next_fetch_python", "--offline で飛ばした Python を取得する"),
+    ("next_workspace_venv", "--skip-uv で飛ばしたワークスペースの venv を作る"),
+    ("next_member_dev", "メンバーの開発ツールをインストールする"),
+    ("next_check_import", "パッケージを import できるか確認する"),
+    ("next_console_script", "コンソールスクリプトを実行する"),
+    ("next_create_venv", "--skip-uv で飛ばした venv を作る"),
+    ("next_activate", "venv を有効にする。プロンプトに ({prompt}) と表示される"),
+    ("next_uv_env", "uv run が .venv ではなくこの venv を使うようにする"),
+    ("next_install", "プロジェクトと開発ツールをインストールする"),
+    ("next_entry", "エントリーポイントを実行する"),
+    ("next_tests", "テストを実行する"),
+    ("next_direnv", "cd したときに .envrc を読み込む"),
+    ("next_publish", "リポジトリを公開する"),
+    ("cli_project_created", "プロジェクトを作成しました"),
+];
@@ -0,0 +1,60 @@
+//! The py-proj scaffolder as a library: plan a uv + VS Code Python project, write it,
+//! and clean or delete it again, without going through the CLI.
+//!
+//! Nothing here prints. Long-running steps report [`Event`]s to the callback passed
+//! to [`ScaffoldPlanBuilder::on_event`], and results come back as typed reports.
+//!
+//! ```no_run
+//! use pyproj::{create, CreateOptions, Event, ScaffoldPlan, WritePolicy};
+//!
+//! let plan = ScaffoldPlan::builder("demo")
+//!     .root("/tmp/demo")
+//!     .python("3.12.4")
+//!     .on_event(|event| {
+//!         if let Event::CommandStarted { label, .. } = event {
+//!             println!("{label}");
+//!         }
+//!     })
+//!     .build()?;
+//! let opts = CreateOptions {
+//!     policy: WritePolicy::Overwrite,
+//!     ..Default::default()
+//! };
+//! let summary = create(&plan, &opts)?;
+//! println!("{}", summary.line());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! The built-in templates are listed in [`templates::REGISTRY`].
+
+pub mod bootstrap;
+pub mod cleanup;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod history;
+pub mod lang;
+pub mod lock;
+pub mod manifest;
+pub mod plan_file;
+pub mod preflight;
+pub mod profile;
+pub mod pyproject;
+pub mod python;
+pub mod scaffold;
+pub mod template_repo;
+pub mod templates;
+pub mod update;
+pub mod util;
+pub mod workspace;
+
+pub use cleanup::{
+    clean, delete, plan_clean, CleanEntry, CleanKind, CleanOptions, CleanReport, DeleteOptions,
+    DeleteReport, ProjectCheck,
+};
+pub use error::Error;
+pub use events::{Event, EventHandler};
+pub use scaffold::{
+    create, CreateOptions, Maintainer, Origin, PlannedAction, ScaffoldPlan, ScaffoldPlanBuilder, WriteSummary,
+};
+pub use util::{WriteOutcome, WritePolicy};
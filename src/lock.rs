@@ -0,0 +1,301 @@
+//! `.pyproj.lock`: an advisory lock on a project root, so two py-proj runs against the
+//! same directory (a retried CI job, two terminals) take turns instead of interleaving
+//! their writes. [`create`](crate::create), [`clean`](crate::clean),
+//! [`delete`](crate::delete), and `upgrade` hold it while they work.
+//!
+//! The lock is a file created with `O_EXCL` that names its holder. A second run waits
+//! for it to go away (up to [`set_lock_timeout`]), and removes it with a warning when
+//! the process that took it is no longer running.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::error::Error;
+use crate::manifest::now_rfc3339;
+use crate::util::check_interrupt;
+
+pub const LOCK_FILE: &str = ".pyproj.lock";
+
+/// How long to wait for another run's lock by default.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a waiting run looks at the lock again.
+const POLL: Duration = Duration::from_millis(100);
+
+/// A lock file younger than this may still be being written by its holder, so one
+/// that cannot be read yet is not taken for stale.
+const WRITE_GRACE: Duration = Duration::from_secs(5);
+
+static LOCK_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_LOCK_TIMEOUT.as_secs());
+/// The lock files this process holds, for [`release_all`].
+static HELD: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Wait up to `timeout` for another run's lock before failing (zero: fail right away).
+pub fn set_lock_timeout(timeout: Duration) {
+    LOCK_TIMEOUT_SECS.store(timeout.as_secs(), Ordering::Relaxed);
+}
+
+fn lock_timeout() -> Duration {
+    Duration::from_secs(LOCK_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// Who holds a lock, as written in the lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// What the holder is doing: `create`, `clean`, `delete`, or `upgrade`.
+    pub operation: String,
+    /// When it took the lock (RFC 3339, UTC).
+    pub started: String,
+}
+
+impl LockHolder {
+    /// `py-proj create (pid 4242, since 2025-01-01T12:00:00Z)`.
+    pub fn describe(&self) -> String {
+        format!("py-proj {} (pid {}, since {})", self.operation, self.pid, self.started)
+    }
+}
+
+/// A held `.pyproj.lock`, removed again when this is dropped (on success, on error,
+/// and while unwinding from a panic). See [`release_all`] for a Ctrl-C that exits.
+#[derive(Debug)]
+pub struct ProjectLock {
+    root: PathBuf,
+    path: PathBuf,
+    created_root: bool,
+}
+
+impl ProjectLock {
+    /// Lock `root` for `operation`, waiting while another run holds it. With
+    /// `create_root` a missing root is created first (see [`ProjectLock::created_root`]);
+    /// otherwise a missing root is an error. `warn` hears about the wait and about a
+    /// stale lock being broken.
+    pub fn acquire(root: &Path, operation: &str, create_root: bool, warn: &dyn Fn(String)) -> Result<ProjectLock> {
+        let path = root.join(LOCK_FILE);
+        let holder = LockHolder {
+            pid: std::process::id(),
+            operation: operation.to_string(),
+            started: now_rfc3339(),
+        };
+        let contents = toml::to_string(&holder)?;
+        let timeout = lock_timeout();
+        let started = Instant::now();
+        let mut created_root = false;
+        let mut waiting = false;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(contents.as_bytes())
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                    HELD.lock().unwrap_or_else(|e| e.into_inner()).push(path.clone());
+                    return Ok(ProjectLock {
+                        root: root.to_path_buf(),
+                        path,
+                        created_root,
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound && create_root => {
+                    if let Some(parent) = root.parent().filter(|p| !p.as_os_str().is_empty()) {
+                        fs::create_dir_all(parent)
+                            .with_context(|| format!("failed to create {}", parent.display()))?;
+                    }
+                    // Only the run whose `create_dir` succeeds owns the root, so only it
+                    // removes the whole directory when it rolls back.
+                    match fs::create_dir(root) {
+                        Ok(()) => created_root = true,
+                        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                        Err(e) => return Err(e).with_context(|| format!("failed to create {}", root.display())),
+                    }
+                    continue;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e).with_context(|| format!("failed to lock {}", root.display())),
+            }
+
+            let current = read_holder(&path);
+            if let Some(stale) = current.as_ref().filter(|h| !is_running(h.pid)) {
+                // Another waiter may have broken it first and taken its own lock since.
+                if read_holder(&path).as_ref() == Some(stale) && fs::remove_file(&path).is_ok() {
+                    warn(format!(
+                        "removed a stale {LOCK_FILE} left by {}, which is no longer running",
+                        stale.describe()
+                    ));
+                }
+                continue;
+            }
+            if current.is_none() {
+                match age(&path) {
+                    None => continue, // released in the meantime
+                    Some(age) if age > WRITE_GRACE => {
+                        warn(format!("removed an unreadable {LOCK_FILE} in {}", root.display()));
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    Some(_) => {}
+                }
+            }
+            let holder = current.map_or_else(|| "another py-proj run".to_string(), |h| h.describe());
+            if started.elapsed() >= timeout {
+                return Err(Error::ProjectLocked {
+                    path,
+                    holder,
+                    secs: timeout.as_secs(),
+                }
+                .into());
+            }
+            if !waiting {
+                warn(format!(
+                    "{} is locked by {holder}; waiting up to {}s",
+                    root.display(),
+                    timeout.as_secs()
+                ));
+                waiting = true;
+            }
+            check_interrupt()?;
+            thread::sleep(POLL);
+        }
+    }
+
+    /// Whether [`acquire`](ProjectLock::acquire) created the project root: only then
+    /// may a rollback remove the directory itself.
+    pub fn created_root(&self) -> bool {
+        self.created_root
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // Gone already when a rollback or `delete` removed the whole project.
+        let _ = fs::remove_file(&self.path);
+        if self.created_root {
+            // A root made only to hold the lock (the run failed before writing) goes too.
+            let _ = fs::remove_dir(&self.root);
+        }
+        HELD.lock().unwrap_or_else(|e| e.into_inner()).retain(|p| *p != self.path);
+    }
+}
+
+/// Remove every lock this process holds, for a Ctrl-C that exits without unwinding.
+pub fn release_all() {
+    let held = std::mem::take(&mut *HELD.lock().unwrap_or_else(|e| e.into_inner()));
+    for path in held {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// The holder named in the lock at `path`; `None` when it is gone or not written yet.
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn age(path: &Path) -> Option<Duration> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+/// Whether process `pid` still exists. Where that cannot be asked (Windows), every
+/// holder counts as running and a stale lock waits out the timeout like a live one.
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // SAFETY: signal 0 sends nothing; it only checks that `pid` exists.
+    let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    fn quiet(_: String) {}
+
+    #[test]
+    fn two_runs_take_turns_and_the_second_times_out_naming_the_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Arc::new(dir.path().to_path_buf());
+        let inside = Arc::new(AtomicBool::new(false));
+        let overlaps = Arc::new(AtomicU64::new(0));
+        let runs: Vec<_> = (0..2)
+            .map(|_| {
+                let (root, inside, overlaps) = (root.clone(), inside.clone(), overlaps.clone());
+                thread::spawn(move || {
+                    for _ in 0..3 {
+                        let _lock = ProjectLock::acquire(&root, "create", false, &quiet).unwrap();
+                        if inside.swap(true, Ordering::SeqCst) {
+                            overlaps.fetch_add(1, Ordering::SeqCst);
+                        }
+                        thread::sleep(Duration::from_millis(20));
+                        inside.store(false, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+        for run in runs {
+            run.join().unwrap();
+        }
+        assert_eq!(overlaps.load(Ordering::SeqCst), 0);
+        assert!(!root.join(LOCK_FILE).exists());
+
+        // The timeout is process-wide, so it is only changed here.
+        let held = ProjectLock::acquire(&root, "clean", false, &quiet).unwrap();
+        set_lock_timeout(Duration::ZERO);
+        let err = ProjectLock::acquire(&root, "delete", false, &quiet).unwrap_err();
+        set_lock_timeout(DEFAULT_LOCK_TIMEOUT);
+        let message = err.to_string();
+        assert!(matches!(err.downcast_ref(), Some(Error::ProjectLocked { secs: 0, .. })), "{message}");
+        assert!(message.contains(&format!("py-proj clean (pid {}, since ", std::process::id())), "{message}");
+        drop(held);
+        assert!(!root.join(LOCK_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_lock_left_by_a_dead_process_is_broken_with_a_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        let stale = LockHolder {
+            pid: dead,
+            operation: "upgrade".to_string(),
+            started: "2025-01-01T12:00:00Z".to_string(),
+        };
+        fs::write(dir.path().join(LOCK_FILE), toml::to_string(&stale).unwrap()).unwrap();
+
+        let warnings = Mutex::new(Vec::new());
+        let lock = ProjectLock::acquire(dir.path(), "create", false, &|w| warnings.lock().unwrap().push(w)).unwrap();
+        let warnings = warnings.into_inner().unwrap();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains(&format!("stale {LOCK_FILE} left by py-proj upgrade (pid {dead}")), "{}", warnings[0]);
+        let holder = read_holder(&dir.path().join(LOCK_FILE)).unwrap();
+        assert_eq!((holder.pid, holder.operation.as_str()), (std::process::id(), "create"));
+        drop(lock);
+        assert!(!dir.path().join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn a_root_created_for_the_lock_is_removed_when_nothing_was_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("a/demo");
+        assert!(ProjectLock::acquire(&root, "delete", false, &quiet).is_err());
+        let lock = ProjectLock::acquire(&root, "create", true, &quiet).unwrap();
+        assert!(lock.created_root());
+        assert!(root.join(LOCK_FILE).is_file());
+        drop(lock);
+        assert!(!root.exists());
+        assert!(dir.path().join("a").is_dir());
+    }
+}
@@ -1,16 +1,35 @@
 use anyhow::{bail, Context, Result};
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, ArgGroup, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use owo_colors::OwoColorize;
 use std::env;
-use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-mod scaffold;
-mod templates;
-mod util;
+mod commands;
+mod progress;
 
-use scaffold::ScaffoldPlan;
-use util::detect_system_python;
+use commands::{
+    activate, add_module, adopt, archive, batch, bump, check_vscode, clean_all, config_show, diff, doctor, export, info, list, open, preflight, rename, repair, report, sbom, stats, summary, templates,
+    undo, upgrade, verify,
+};
+use pyproj::config::Config;
+use pyproj::history;
+use pyproj::update;
+use pyproj::manifest::{Manifest, MANIFEST_FILE};
+use pyproj::plan_file::PlanFile;
+use pyproj::profile::Profile;
+use pyproj::scaffold::{DepStyle, DepUpdates, GitHooks, LineEndings, MatrixRunner, Ide, Publish, PythonPreference, Security, Status, TestingExtra, Toolchain};
+use pyproj::python::{detect_python, PythonSource, PythonVersion};
+use pyproj::util::{human_bytes, parse_mode, quote_argv, Size};
+use pyproj::{
+    util, workspace, CleanEntry, CleanKind, CleanOptions, CleanReport, CreateOptions,
+    DeleteOptions, PlannedAction, ProjectCheck, ScaffoldPlan, WritePolicy,
+};
 
 /// Fancy banner shown in --help
 const BANNER: &str = r#"
@@ -34,10 +53,14 @@ const BANNER: &str = r#"
     name = "py-proj",
     disable_help_flag = true,
     disable_version_flag = true,
+    group(ArgGroup::new("json_action").args(["create_project", "clean_project"]).multiple(true)),
     about = "Scaffold a minimal Python project (uv + VS Code) with logging package.",
     long_about = None
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Create a new project (non-interactive)
     #[arg(long = "create_project", action = ArgAction::SetTrue)]
     create_project: bool,
@@ -46,26 +69,407 @@ struct Cli {
     #[arg(long = "clean_project", action = ArgAction::SetTrue)]
     clean_project: bool,
 
-    /// Delete (nuke) the entire project directory (requires --yes)
+    /// With --create_project or --clean_project, print a JSON report instead of the usual output
+    #[arg(long = "json", action = ArgAction::SetTrue, requires = "json_action")]
+    json: bool,
+
+    /// Keep .venv when cleaning
+    #[arg(long = "keep-venv", action = ArgAction::SetTrue, requires = "clean_project")]
+    keep_venv: bool,
+
+    /// Also remove what this glob matches, relative to the project root (repeatable)
+    #[arg(long = "clean-extra", value_name = "GLOB", value_parser = parse_clean_pattern, requires = "clean_project")]
+    clean_extra: Vec<String>,
+
+    /// With --clean_project, remove only the --clean-extra / config patterns
+    #[arg(long = "clean-default-off", action = ArgAction::SetTrue, requires = "clean_project")]
+    clean_default_off: bool,
+
+    /// With --clean_project, clean every py-proj project under DIR (asks once, or pass --yes)
+    #[arg(
+        long = "all",
+        value_name = "DIR",
+        requires = "clean_project",
+        conflicts_with_all = ["path", "outdir", "project", "create_project", "delete_project"]
+    )]
+    all: Option<PathBuf>,
+
+    /// With --all, how many directory levels below DIR to search
+    #[arg(long = "max-depth", value_name = "N", default_value_t = 4, requires = "all")]
+    max_depth: usize,
+
+    /// With --all, do not search directories matching this glob, relative to DIR (repeatable)
+    #[arg(long = "exclude-dir", value_name = "GLOB", requires = "all")]
+    exclude_dir: Vec<String>,
+
+    /// Delete (nuke) the entire project directory (asks first, or pass --yes)
     #[arg(long = "delete_project", action = ArgAction::SetTrue)]
     delete_project: bool,
 
     /// Project name (default: <cwd_basename>_proj)
-    #[arg(long, short = 'p')]
+    #[arg(long, short = 'p', global = true)]
     project: Option<String>,
 
-    /// Python version to install via uv (default: auto-detected)
-    #[arg(long = "python", short = 'P')]
-    py_full: Option<String>,
+    /// Allow a project name that shadows a standard-library module
+    #[arg(long = "force-name", action = ArgAction::SetTrue, requires = "create_project")]
+    force_name: bool,
+
+    /// Python version to install via uv (default: .python-version, pyenv, then PATH)
+    #[arg(long = "python", short = 'P', value_name = "VER", value_parser = PythonVersion::from_str, global = true)]
+    py_full: Option<PythonVersion>,
+
+    #[command(flatten)]
+    metadata: MetadataArgs,
+
+    /// Kind of project: default, cli, datasci, or webapp
+    #[arg(long = "template", value_name = "PROFILE", value_parser = Profile::from_str, global = true)]
+    profile: Option<Profile>,
+
+    /// Extra .gitignore pattern (repeatable)
+    #[arg(long = "gitignore-extra", value_name = "PATTERN", global = true)]
+    gitignore_extra: Vec<String>,
+
+    /// Generate a random secret into .env under KEY (repeatable; webapp: SECRET_KEY)
+    #[arg(long = "gen-secret", value_name = "KEY", global = true)]
+    gen_secrets: Vec<String>,
+
+    /// Modules to generate with a placeholder test each (comma-separated, dotted for subpackages)
+    #[arg(long = "modules", value_name = "A,B,C", value_delimiter = ',', global = true)]
+    modules: Vec<String>,
+
+    /// addopts for [tool.pytest.ini_options], e.g. "-ra --strict-markers"
+    #[arg(long = "pytest-addopts", value_name = "OPTS", allow_hyphen_values = true, global = true)]
+    pytest_addopts: Option<String>,
+
+    /// Register a pytest marker, NAME or NAME:DESCRIPTION (repeatable)
+    #[arg(long = "pytest-marker", value_name = "NAME:DESC", global = true)]
+    pytest_markers: Vec<String>,
+
+    /// Testing libraries to set up on top of pytest: hypothesis (comma-separated)
+    #[arg(long = "testing-extras", value_name = "EXTRAS", value_delimiter = ',', value_parser = TestingExtra::from_str, global = true)]
+    testing_extras: Vec<TestingExtra>,
+
+    /// Make src/main.py's main() async (asyncio.run), with pytest-asyncio for the tests
+    #[arg(long = "async", action = ArgAction::SetTrue, global = true)]
+    async_main: bool,
+
+    /// Add benchmarks/ with pytest-benchmark, run by `make bench` (not by `make test`)
+    #[arg(long = "benchmarks", action = ArgAction::SetTrue, global = true)]
+    benchmarks: bool,
+
+    /// Add data/{raw,interim,processed}/, models/, and src/paths.py (implied by --template datasci)
+    #[arg(long = "data-layout", action = ArgAction::SetTrue, global = true)]
+    data_layout: bool,
+
+    /// Add a stub DVC pipeline: dvc.yaml and .dvcignore (needs the data layout)
+    #[arg(long = "dvc", action = ArgAction::SetTrue, global = true)]
+    dvc: bool,
+
+    /// Supported Pythons, e.g. 3.11,3.12,3.13; --python must be one of them
+    #[arg(long = "python-matrix", value_name = "VERS", value_parser = PythonVersion::from_str, value_delimiter = ',', global = true)]
+    python_matrix: Vec<PythonVersion>,
+
+    /// What runs the matrix sessions: nox (noxfile.py) or tox (tox.ini)
+    #[arg(long = "matrix-runner", value_name = "RUNNER", value_parser = MatrixRunner::from_str, requires = "python_matrix", global = true)]
+    matrix_runner: Option<MatrixRunner>,
+
+    /// Where pyproject.toml lists the dev tools: extras ([project.optional-dependencies], default) or groups (PEP 735 [dependency-groups])
+    #[arg(long = "dep-style", value_name = "STYLE", value_parser = DepStyle::from_str, global = true)]
+    dep_style: Option<DepStyle>,
+
+    /// Write a dependency-update bot config: dependabot or renovate
+    #[arg(long = "dep-updates", value_name = "BOT", value_parser = DepUpdates::from_str, global = true)]
+    dep_updates: Option<DepUpdates>,
+
+    /// Write a release workflow: pypi (GitHub Actions, uv build, PyPI trusted publishing)
+    #[arg(long = "publish", value_name = "TARGET", value_parser = Publish::from_str, global = true)]
+    publish: Option<Publish>,
+
+    /// Write git hooks and enable them: native (.githooks/ scripts, core.hooksPath)
+    #[arg(long = "git-hooks", value_name = "STYLE", value_parser = GitHooks::from_str, global = true)]
+    git_hooks: Option<GitHooks>,
+
+    /// Static security scanning: bandit (dev dependency, [tool.bandit], make security) or ruff-s (ruff's S rules)
+    #[arg(long = "security", value_name = "SCANNER", value_parser = Security::from_str, global = true)]
+    security: Option<Security>,
+
+    /// Development status classifier in pyproject.toml: alpha, beta, or stable
+    #[arg(long = "status", value_name = "STATUS", value_parser = Status::from_str, global = true)]
+    status: Option<Status>,
+
+    /// Editor files to write: vscode (default, .vscode/), neovim (.nvim.lua for 'exrc'), or none
+    #[arg(long = "ide", value_name = "EDITOR", value_parser = Ide::from_str, global = true)]
+    ide: Option<Ide>,
+
+    /// Write no .vscode/ (the same as --ide none)
+    #[arg(long = "no-vscode", action = ArgAction::SetTrue, conflicts_with = "ide", global = true)]
+    no_vscode: bool,
+
+    /// Pin the project's Python in .mise.toml (mise), .tool-versions (asdf), or .python-version
+    #[arg(long = "toolchain-file", value_name = "TOOL", value_parser = Toolchain::from_str, global = true)]
+    toolchain_file: Option<Toolchain>,
 
-    /// Output directory; default: $PWD/<project>
+    /// Line endings of the generated files: lf (default), crlf, or native
+    #[arg(long = "line-endings", value_name = "EOL", value_parser = LineEndings::from_str, global = true)]
+    line_endings: Option<LineEndings>,
+
+    /// Start every generated .py file with an SPDX header for this license, e.g. Apache-2.0
+    #[arg(long = "license-header", value_name = "SPDX", global = true)]
+    license_header: Option<String>,
+
+    /// Pair notebooks with percent-format .py files through jupytext
+    #[arg(long, action = ArgAction::SetTrue, global = true)]
+    jupytext: bool,
+
+    /// Generate only pyproject.toml, the src package, a smoke test, .gitignore, README, and CHANGELOG
+    #[arg(long = "minimal", action = ArgAction::SetTrue, global = true)]
+    minimal: bool,
+
+    /// With --minimal, keep a component after all: vscode, envs, pyrefly, makefile, app_logging, notebook
+    #[arg(long = "with", value_name = "COMPONENT", value_delimiter = ',', requires = "minimal", global = true)]
+    with: Vec<String>,
+
+    /// With --jupytext, git-ignore the notebooks' *.ipynb and commit only the .py side
+    #[arg(long = "jupytext-ignore-ipynb", action = ArgAction::SetTrue, requires = "jupytext", global = true)]
+    jupytext_ignore_ipynb: bool,
+
+    /// Also write a multi-root <project>.code-workspace next to the project (or at PATH)
+    #[arg(long = "code-workspace", value_name = "PATH", num_args = 0..=1, global = true)]
+    code_workspace: Option<Option<PathBuf>>,
+
+    /// Replace an existing .venv built with another Python instead of failing
+    #[arg(long = "recreate-venv", action = ArgAction::SetTrue, global = true)]
+    recreate_venv: bool,
+
+    /// Seed the venv with pip, setuptools, and wheel (uv venv --seed)
+    #[arg(long = "venv-seed", action = ArgAction::SetTrue, global = true)]
+    venv_seed: bool,
+
+    /// The venv's shell prompt name (uv venv --prompt; default: the project name)
+    #[arg(long = "venv-prompt", value_name = "NAME", global = true)]
+    venv_prompt: Option<String>,
+
+    /// The venv's directory name under the project root (default: .venv)
+    #[arg(long = "venv-dir", value_name = "NAME", global = true)]
+    venv_dir: Option<String>,
+
+    /// The notebooks directory's name under the project root (default: Notebooks)
+    #[arg(long = "notebooks-dir", value_name = "NAME", global = true)]
+    notebooks_dir: Option<String>,
+
+    /// Extra argument for `uv venv`, appended as given (repeatable)
+    #[arg(long = "uv-arg", value_name = "ARG", action = ArgAction::Append, allow_hyphen_values = true, global = true)]
+    uv_arg: Vec<String>,
+
+    /// Install ruff and pyright as uv tools instead of project dev dependencies
+    #[arg(long = "tools-global", action = ArgAction::SetTrue, global = true)]
+    tools_global: bool,
+
+    /// Write scripts/bootstrap.sh (.ps1 on Windows) replaying the scaffold's uv commands and hooks
+    #[arg(long = "emit-bootstrap", action = ArgAction::SetTrue, global = true)]
+    emit_bootstrap: bool,
+
+    /// Leave out generated files matching this path or glob (repeatable)
+    #[arg(long = "exclude", value_name = "GLOB", global = true)]
+    exclude: Vec<String>,
+
+    /// Octal permissions for the project root and the directories it creates, e.g. 2775
+    #[arg(long = "dir-mode", value_name = "OCTAL", value_parser = parse_mode, global = true)]
+    dir_mode: Option<u32>,
+
+    /// Octal permissions for the generated files, e.g. 664 (scripts also get execute)
+    #[arg(long = "file-mode", value_name = "OCTAL", value_parser = parse_mode, global = true)]
+    file_mode: Option<u32>,
+
+    /// Language of generated docstrings, comments, README prose, and next steps (en, ja)
+    #[arg(long = "lang", value_name = "CODE", global = true)]
+    lang: Option<String>,
+
+    /// Output directory (`~` and $VARS expanded); default: $PWD/<project>
     #[arg(long = "outdir")]
     outdir: Option<PathBuf>,
 
+    /// Create the --outdir's missing parent directories instead of refusing
+    #[arg(long = "create-parents", action = ArgAction::SetTrue, requires = "create_project")]
+    create_parents: bool,
+
+    /// Scaffold into the current directory, named after it
+    #[arg(long = "here", action = ArgAction::SetTrue, conflicts_with = "outdir", requires = "create_project")]
+    here: bool,
+
+    /// Project to clean or delete (default: the project containing the current directory)
+    #[arg(value_name = "PATH", conflicts_with_all = ["outdir", "create_project"])]
+    path: Option<PathBuf>,
+
+    /// Directory of template overrides (same relative paths as the scaffold)
+    #[arg(long = "templates-dir", global = true)]
+    templates_dir: Option<PathBuf>,
+
+    /// Git URL of a template pack, optionally with #branch or #tag
+    #[arg(long = "template-repo", value_name = "URL[#REF]", conflicts_with = "templates_dir", global = true)]
+    template_repo: Option<String>,
+
+    /// Re-fetch --template-repo even if it is already cached
+    #[arg(long = "refresh-templates", action = ArgAction::SetTrue)]
+    refresh_templates: bool,
+
+    /// Template variable KEY=VALUE (repeatable)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_var, global = true)]
+    vars: Vec<(String, String)>,
+
+    /// Shell command to run in the project root after creation (repeatable)
+    #[arg(long = "post-hook", value_name = "COMMAND")]
+    post_hooks: Vec<String>,
+
+    /// Scaffold a uv workspace member instead of a standalone project
+    #[arg(long = "workspace-member", action = ArgAction::SetTrue)]
+    workspace_member: bool,
+
+    /// Create the workspace root in the current directory if none is found
+    #[arg(long = "init-workspace", action = ArgAction::SetTrue, requires = "workspace_member")]
+    init_workspace: bool,
+
+    /// Overwrite existing files whose content differs (default: skip them)
+    #[arg(long, action = ArgAction::SetTrue)]
+    force: bool,
+
+    /// Like --force, but save each replaced file as <name>.bak first
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "force")]
+    backup: bool,
+
+    /// Like --force, but ask per changed file whether to overwrite, skip, or back it up
+    #[arg(long = "force-interactive", action = ArgAction::SetTrue, conflicts_with_all = ["force", "backup", "json"])]
+    force_interactive: bool,
+
+    /// With --force/--backup, overwrite an existing pyproject.toml instead of merging into it
+    #[arg(long = "pyproject-replace", action = ArgAction::SetTrue)]
+    pyproject_replace: bool,
+
+    /// Keep a partially created project when scaffolding fails (for debugging)
+    #[arg(long = "keep-on-failure", action = ArgAction::SetTrue)]
+    keep_on_failure: bool,
+
+    /// Write the project files only; skip uv (no Python install, no .venv)
+    #[arg(long = "skip-uv", action = ArgAction::SetTrue)]
+    skip_uv: bool,
+
+    /// Install uv with the official installer if it is not on PATH
+    #[arg(long = "bootstrap-uv", action = ArgAction::SetTrue, conflicts_with = "skip_uv")]
+    bootstrap_uv: bool,
+
+    /// Never touch the network (the Python must already be installed)
+    #[arg(long = "offline", action = ArgAction::SetTrue)]
+    offline: bool,
+
+    /// Which Pythons uv may use: only-managed, managed, system, or only-system
+    #[arg(long = "uv-python-preference", value_name = "PREF", value_parser = PythonPreference::from_str)]
+    uv_python_preference: Option<PythonPreference>,
+
+    /// The uv executable to run (default: $PY_PROJ_UV, $PY_PROJ_UV_BIN, or $UV, else uv on PATH)
+    #[arg(long = "uv-bin", value_name = "PATH", global = true)]
+    uv_bin: Option<String>,
+
+    /// Kill any uv/git command running longer than SECS (0: never; default 600)
+    #[arg(long = "command-timeout", value_name = "SECS")]
+    command_timeout: Option<u64>,
+
+    /// Wait up to SECS for another py-proj run to release the project (0: fail at once; default 30)
+    #[arg(long = "lock-timeout", value_name = "SECS", global = true)]
+    lock_timeout: Option<u64>,
+
+    /// Stamp generated files with $SOURCE_DATE_EPOCH (or 1970-01-01) instead of now, so two runs give identical trees
+    #[arg(long = "deterministic", action = ArgAction::SetTrue, global = true)]
+    deterministic: bool,
+
+    /// Do not write the .pyproj.toml manifest
+    #[arg(long = "no-manifest", action = ArgAction::SetTrue)]
+    no_manifest: bool,
+
+    /// After --create_project, print how long each phase took
+    #[arg(long = "timings", action = ArgAction::SetTrue, requires = "create_project")]
+    timings: bool,
+
+    /// After --create_project, open the new project in an editor (see `py-proj open`)
+    #[arg(long = "open", action = ArgAction::SetTrue, requires = "create_project")]
+    open: bool,
+
+    /// After --create_project's install step, write sbom.json (CycloneDX 1.5, or FORMAT)
+    #[arg(long = "sbom", value_name = "FORMAT", value_enum, num_args = 0..=1, default_missing_value = "cyclonedx",
+          requires = "create_project", conflicts_with = "skip_uv")]
+    sbom: Option<sbom::Format>,
+
+    /// Editor command for `open` and --open, e.g. `code` or "subl -n" (default: VS Code)
+    #[arg(long = "editor", value_name = "COMMAND", global = true)]
+    editor: Option<String>,
+
+    /// Show what would be done without writing or running anything
+    #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+    dry_run: bool,
+
+    /// Write the planned actions to FILE (JSON) instead of carrying them out; see `apply-plan`
+    #[arg(
+        long = "emit-plan",
+        value_name = "FILE",
+        requires = "create_project",
+        conflicts_with_all = ["dry_run", "force_interactive", "open", "json", "init_workspace"]
+    )]
+    emit_plan: Option<PathBuf>,
+
+    /// With --create_project, also write a JSON report of the run (the --json schema) to PATH in the project, even when it fails
+    #[arg(
+        long = "summary-file",
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = report::DEFAULT_REPORT_FILE,
+        requires = "create_project",
+        conflicts_with_all = ["dry_run", "emit_plan"]
+    )]
+    summary_file: Option<PathBuf>,
+
+    /// Add the --summary-file report to the project's .gitignore
+    #[arg(long = "summary-file-gitignore", action = ArgAction::SetTrue, requires = "summary_file")]
+    summary_file_gitignore: bool,
+
+    /// Config file (default: $XDG_CONFIG_HOME/py-proj/config.toml)
+    #[arg(long = "config")]
+    config: Option<PathBuf>,
+
+    /// Check GitHub for a newer py-proj release (after any other action)
+    #[arg(long = "check-update", action = ArgAction::SetTrue, conflicts_with = "json")]
+    check_update: bool,
+
+    /// Do not record this run in the history log (see `py-proj history`)
+    #[arg(long = "no-history", action = ArgAction::SetTrue)]
+    no_history: bool,
+
+    /// Hide progress output (uv steps, per-file lines); warnings and errors still show
+    #[arg(long = "quiet", short = 'q', action = ArgAction::SetTrue, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print details such as where each generated file came from
+    #[arg(long = "verbose", short = 'v', action = ArgAction::SetTrue)]
+    verbose: bool,
+
     /// Auto-confirm dangerous actions like --delete_project
     #[arg(long = "yes", short = 'y', action = ArgAction::SetTrue)]
     yes: bool,
 
+    /// Create even though this preflight check failed or warned (repeatable, comma-separated)
+    #[arg(long = "preflight-ignore", value_name = "CHECK", value_delimiter = ',', value_parser = clap::builder::PossibleValuesParser::new(pyproj::preflight::CHECKS))]
+    preflight_ignore: Vec<String>,
+
+    /// Move the project to the trash instead of deleting it with --delete_project
+    #[arg(long = "trash", action = ArgAction::SetTrue, requires = "delete_project")]
+    trash: bool,
+
+    /// Let --delete_project remove a path that does not look like a project
+    #[arg(long = "i-know-what-im-doing", action = ArgAction::SetTrue, requires = "delete_project")]
+    i_know_what_im_doing: bool,
+
+    /// Let --delete_project remove a project py-proj did not scaffold
+    #[arg(long = "force-delete-unmanaged", action = ArgAction::SetTrue, requires = "delete_project")]
+    force_delete_unmanaged: bool,
+
     /// Show help with banner and color
     #[arg(long = "help", short = 'h', action = ArgAction::SetTrue)]
     help: bool,
@@ -75,234 +479,3236 @@ struct Cli {
     version: bool,
 }
 
-#[allow(clippy::print_literal)]
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// Project metadata written into the generated files.
+#[derive(Args, Debug)]
+struct MetadataArgs {
+    /// One-line project summary for pyproject.toml and README.md
+    #[arg(long = "description", value_name = "TEXT", global = true)]
+    description: Option<String>,
 
-    if cli.version {
-        println!("{} {}", "py-proj".bold(), env!("CARGO_PKG_VERSION").green());
-        return Ok(());
-    }
+    /// Author name for pyproject.toml and CITATION.cff
+    #[arg(long = "author", value_name = "NAME", global = true)]
+    author: Option<String>,
 
-    // If help is requested or no primary action was provided, show help and exit.
-    let no_action = !(cli.create_project || cli.clean_project || cli.delete_project);
-    if cli.help || no_action {
-        print_help();
-        return Ok(());
-    }
+    /// Author email for pyproject.toml and CITATION.cff
+    #[arg(long = "email", value_name = "ADDR", global = true)]
+    email: Option<String>,
 
-    // Resolve defaults
-    let cwd = env::current_dir()?;
-    let default_proj = format!(
-        "{}_proj",
-        cwd.file_name().unwrap_or_default().to_string_lossy()
-    );
-    let project = cli.project.unwrap_or(default_proj);
-    let root = cli.outdir.unwrap_or_else(|| cwd.join(&project));
-    let py_full = cli.py_full.unwrap_or_else(detect_system_python);
+    /// Repository URL for [project.urls] and CITATION.cff ({project}: the project name)
+    #[arg(long = "repo-url", value_name = "URL", global = true)]
+    repo_url: Option<String>,
 
-    // Derived versions used in templates
-    let mm = py_full.split('.').take(2).collect::<Vec<_>>().join(".");
-    let mm_nodec = mm.replace('.', "");
+    /// Homepage URL for [project.urls] and CITATION.cff ({project}: the project name)
+    #[arg(long = "homepage", value_name = "URL", global = true)]
+    homepage: Option<String>,
 
-    if cli.create_project {
-        println!("{} {}", ">>".cyan().bold(), "Create project".bold());
-        println!("  {} {}", "Project:".dimmed(), project.blue().bold());
-        println!(
-            "  {} {}",
-            "Root:   ".dimmed(),
-            root.display().to_string().blue()
-        );
-        println!("  {} {}", "Python: ".dimmed(), py_full.magenta());
+    /// Organization name for the README's repository badge
+    #[arg(long = "org", value_name = "NAME", global = true)]
+    org: Option<String>,
+
+    /// SPDX license expression for [project] license and CITATION.cff
+    #[arg(long = "license", value_name = "SPDX", global = true)]
+    license: Option<String>,
+
+    /// Maintainer as `Name` or `Name <email>` (repeatable; replaces the config's list)
+    #[arg(long = "maintainer", value_name = "WHO", action = ArgAction::Append, global = true)]
+    maintainers: Vec<pyproj::Maintainer>,
+
+    /// Also write a CITATION.cff
+    #[arg(long = "citation", action = ArgAction::SetTrue, global = true)]
+    citation: bool,
+
+    /// Console-script name for [project.scripts], backed by a generated cli.py
+    #[arg(long = "entry-point", value_name = "NAME", global = true)]
+    entry_point: Option<String>,
+
+    /// Put the package under a PEP 420 namespace: src/<NS>/<project>/, imported as NS.<project>
+    #[arg(long = "namespace", value_name = "NS", global = true)]
+    namespace: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspect or export the built-in templates
+    Templates {
+        #[command(subcommand)]
+        action: TemplatesCommand,
+    },
+    /// Tools for working on py-proj's templates
+    #[command(hide = true)]
+    Debug {
+        #[command(subcommand)]
+        action: DebugCommand,
+    },
+    /// Check uv, Python, the venv, and project config for common problems
+    Doctor {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Print the results as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Check that .vscode's interpreter, tools, launch targets, and extensions are there
+    #[command(name = "check-vscode")]
+    CheckVscode {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Print the results as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Show a project's name, version, Python, venv, components, and disk usage
+    Info {
+        /// Project root or a directory inside it (default: current directory)
+        path: Option<PathBuf>,
+        /// Print the overview as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Add modules (and placeholder tests) to an existing project
+    #[command(name = "add-module")]
+    AddModule {
+        /// Dotted module paths below the package, e.g. orders.billing
+        #[arg(required = true)]
+        modules: Vec<String>,
+        /// Project root (default: current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Add py-proj's config files to an existing project without touching its code
+    Adopt {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Replace existing config files that differ from the templates
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+        /// Add the config files only, not the venv or a uv-managed Python
+        #[arg(long = "skip-uv", action = ArgAction::SetTrue)]
+        skip_uv: bool,
+    },
+    /// Show how an existing project differs from the current templates
+    Diff {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+    },
+    /// Check generated files against the hashes recorded in the manifest
+    Verify {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Record the current contents as the new baseline
+        #[arg(long = "update-manifest", action = ArgAction::SetTrue)]
+        update_manifest: bool,
+    },
+    /// Find py-proj projects under a directory
+    List {
+        /// Directory to search (default: current directory)
+        dir: Option<PathBuf>,
+        /// How many directory levels to descend
+        #[arg(long = "max-depth", default_value_t = 4)]
+        max_depth: usize,
+        /// Only show projects not modified in this many days
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u64>,
+        /// Print the results as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Rename the project in pyproject.toml, pyrefly.toml, README.md, and the manifest
+    #[command(name = "rename-project")]
+    RenameProject {
+        /// Current project name
+        #[arg(long)]
+        from: String,
+        /// New project name
+        #[arg(long)]
+        to: String,
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Rename even if the git working tree has uncommitted changes
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+        /// Show the edits without writing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Carry out a plan written by --emit-plan
+    ApplyPlan {
+        /// The plan file
+        file: PathBuf,
+        /// Apply it here instead of the root it was planned for
+        #[arg(long, value_name = "DIR")]
+        root: Option<PathBuf>,
+    },
+    /// Refresh generated config files in an existing project
+    Upgrade {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Replace files you edited without asking
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+        /// Show the changes without writing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Remove what the last create or adopt wrote, leaving anything that was there before
+    Undo {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Show what would be removed without removing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+        /// Also remove generated files that were edited since
+        #[arg(long = "force-modified", action = ArgAction::SetTrue)]
+        force_modified: bool,
+    },
+    /// Recreate generated files, directories, and the venv that went missing, from the manifest
+    Repair {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Show what would be restored without writing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+        /// Restore files and directories only, not the venv
+        #[arg(long = "skip-uv", action = ArgAction::SetTrue)]
+        skip_uv: bool,
+    },
+    /// Pack the project into a .tar.gz or .zip without its venv, caches, build output, or .git
+    Archive {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Where to write the archive (default: <project>.tar.gz next to the project)
+        #[arg(long, short = 'o', value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// tar.gz or zip (default: from --output's extension, else tar.gz)
+        #[arg(long, value_enum)]
+        format: Option<archive::Format>,
+        /// Keep .git in the archive
+        #[arg(long = "with-git", action = ArgAction::SetTrue)]
+        with_git: bool,
+        /// Then delete the project, as --delete_project does (with its confirmation)
+        #[arg(long = "then-delete", action = ArgAction::SetTrue)]
+        then_delete: bool,
+        /// With --then-delete, move the project to the trash instead
+        #[arg(long, action = ArgAction::SetTrue, requires = "then_delete")]
+        trash: bool,
+        /// With --then-delete, delete without asking
+        #[arg(long, short = 'y', action = ArgAction::SetTrue, requires = "then_delete")]
+        yes: bool,
+    },
+    /// Raise the project version and move the Unreleased changelog entries into a release
+    Bump {
+        /// Which part of MAJOR.MINOR.PATCH to raise
+        #[arg(value_enum)]
+        part: bump::Part,
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Commit the release and tag it v<version> (the git tree must be clean)
+        #[arg(long = "git-tag", action = ArgAction::SetTrue)]
+        git_tag: bool,
+        /// Print the new version and the edits without writing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+    /// Write requirements.txt from uv.lock or pyproject.toml, for deploy targets that need one
+    #[command(name = "export-requirements")]
+    ExportRequirements {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// Also write <output>-<GROUP>.txt for this dependency group or extra, e.g. dev
+        #[arg(long, value_name = "GROUP")]
+        group: Option<String>,
+        /// The main file, relative to the project root (default: requirements.txt)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Write a conda environment.yml (Python pin, conda-forge packages, the rest via pip)
+    #[command(name = "export-conda")]
+    ExportConda {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// The file, relative to the project root (default: environment.yml)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Write a software bill of materials (CycloneDX or SPDX JSON) for the project's venv
+    Sbom {
+        /// Project root (default: current directory)
+        path: Option<PathBuf>,
+        /// cyclonedx (CycloneDX 1.5, the default) or spdx (SPDX 2.3)
+        #[arg(long, value_enum, default_value_t)]
+        format: sbom::Format,
+        /// The file, relative to the project root (default: sbom.json)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Print shell code that activates the project's venv: eval "$(py-proj env)"
+    Env {
+        /// Project root or a directory inside it (default: current directory)
+        path: Option<PathBuf>,
+        /// Shell to print for (default: the calling shell, from the parent process or $SHELL)
+        #[arg(long, value_enum)]
+        shell: Option<activate::Shell>,
+    },
+    /// Run a command in the project's environment (uv run in the project root): py-proj run -- pytest -x
+    Run {
+        /// Project root or a directory inside it (default: current directory)
+        #[arg(short = 'C', value_name = "PATH")]
+        dir: Option<PathBuf>,
+        /// Create a missing .venv without asking
+        #[arg(long = "yes", short = 'y', action = ArgAction::SetTrue)]
+        yes: bool,
+        /// The command and its arguments, after `--`
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// Scaffold every project listed in a TOML or YAML spec, with a result table at the end
+    Batch {
+        /// The spec: a [defaults] table and one [[project]] (name, outdir, options) per project
+        spec: PathBuf,
+        /// Scaffold up to N projects at once (Python installs still happen once, up front)
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        parallel: u16,
+        /// Carry on after a project fails (the default), even if the spec sets fail_fast
+        #[arg(long = "keep-going", action = ArgAction::SetTrue, conflicts_with = "fail_fast")]
+        keep_going: bool,
+        /// Start no further projects after the first failure
+        #[arg(long = "fail-fast", action = ArgAction::SetTrue)]
+        fail_fast: bool,
+        /// Validate the whole spec and print the plan without writing anything
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+        /// Write the project files only; skip uv (no Python install, no .venv)
+        #[arg(long = "skip-uv", action = ArgAction::SetTrue)]
+        skip_uv: bool,
+        /// Never touch the network (the Pythons must already be installed)
+        #[arg(long = "offline", action = ArgAction::SetTrue)]
+        offline: bool,
+    },
+    /// Open the project in an editor (VS Code by default)
+    Open {
+        /// Project root or a directory inside it (default: current directory)
+        path: Option<PathBuf>,
+    },
+    /// Show the log of past creates, cleans, deletes, and upgrades
+    History {
+        /// Only runs on this project, or on projects below this directory
+        path: Option<PathBuf>,
+        /// How many of the most recent runs to show
+        #[arg(long, short = 'n', default_value_t = 20)]
+        limit: usize,
+        /// Print the entries as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Summarize the history log: runs per command, flags, uv step times, failures, disk freed
+    Stats {
+        /// Only runs on this project, or on projects below this directory
+        path: Option<PathBuf>,
+        /// Only runs from this date (YYYY-MM-DD, UTC) or RFC 3339 time on
+        #[arg(long, value_name = "DATE", value_parser = stats::parse_since)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        /// Print the statistics as JSON
+        #[arg(long, action = ArgAction::SetTrue, conflicts_with = "clear")]
+        json: bool,
+        /// Delete the history log, and with it everything the statistics come from
+        #[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["path", "since"])]
+        clear: bool,
+    },
+    /// Show the settings from the flags, the environment, .pyprojrc.toml, and config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print each effective setting
+    Show {
+        /// Also name the layer each value came from: flag, env, repo rc, user config, or default
+        #[arg(long, action = ArgAction::SetTrue)]
+        origin: bool,
+        /// Print the settings as JSON
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TemplatesCommand {
+    /// List every file a scaffold with the current options would write
+    List,
+    /// Print one generated file, rendered with the current options
+    Show {
+        /// Relative path of the file, e.g. pyproject.toml or .vscode/settings.json
+        path: String,
+        /// Print the template with its placeholders instead of rendering it
+        #[arg(long, action = ArgAction::SetTrue)]
+        raw: bool,
+    },
+    /// Write every built-in template (placeholders intact) under DIR
+    Export {
+        dir: PathBuf,
+        /// Write into DIR even if it is not empty
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DebugCommand {
+    /// Render every template for a fixed set of option combinations, one subdirectory each
+    RenderAll {
+        outdir: PathBuf,
+        /// Write into OUTDIR even if it is not empty
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = cli_command()
+        .try_get_matches()
+        .and_then(|matches| Cli::from_arg_matches(&matches))
+        .unwrap_or_else(|e| exit_parse_error(e));
+    // Ctrl-C (or SIGTERM) during a uv/git step or while files are written stops the work
+    // at its next step, so the failure unwinds normally, rollback included; anywhere
+    // else, or on a second Ctrl-C, it just exits.
+    let _ = ctrlc::set_handler(|| {
+        if !util::interrupt() {
+            progress::restore_terminal();
+            pyproj::lock::release_all();
+            std::process::exit(130);
+        }
+    });
+    let json = matches!(
+        cli.command,
+        Some(
+            Command::Doctor { json: true, .. }
+                | Command::CheckVscode { json: true, .. }
+                | Command::Info { json: true, .. }
+                | Command::List { json: true, .. }
+                | Command::History { json: true, .. }
+                | Command::Stats { json: true, .. }
+                | Command::Config {
+                    action: ConfigCommand::Show { json: true, .. }
+                }
+        )
+    ) || cli.json;
+    let config = Config::load(cli.config.as_deref()).ok();
+    let keep_history = !cli.no_history && config.as_ref().and_then(|c| c.history) != Some(false);
+    let check_update = cli.check_update;
+    // `env` output is meant for eval; a notice there would be run as a command.
+    let evaluated = matches!(cli.command, Some(Command::Env { .. }));
+    let auto_update_check = !check_update
+        && !cli.offline
+        && !cli.quiet
+        && !json
+        && !evaluated
+        && config.is_some_and(|c| c.check_updates);
+    let result = run(cli);
+    if keep_history {
+        write_history(&result);
+    }
+    if check_update {
+        print_update_check();
+    } else if auto_update_check {
+        update_notice();
+    }
+    match result {
+        Ok(code) => code,
+        Err(e) => report_error(&e, json),
+    }
+}
 
-        create_project(&root, &project, &py_full, &mm, &mm_nodec)?;
-        println!("{} {}", "OK".green().bold(), "Project created.");
+/// `hint:` for a line on stderr: cyan in a terminal, plain when it is piped or
+/// redirected, like clap's own output.
+fn hint_label() -> String {
+    match io::stderr().is_terminal() {
+        true => "hint:".cyan().to_string(),
+        false => "hint:".to_string(),
     }
+}
 
-    if cli.clean_project {
-        println!("{} {}", ">>".cyan().bold(), "Clean project caches".bold());
-        clean_project(&root)?;
-        println!("{} {}", "OK".green().bold(), "Project cleaned.");
+/// [`Cli`] with `--help` on every subcommand: the top level prints its own help (see
+/// [`print_help`]), and `disable_help_flag` is a global setting, so clap would leave the
+/// subcommands without one.
+fn cli_command() -> clap::Command {
+    fn with_help(command: clap::Command) -> clap::Command {
+        let help = clap::Arg::new("help").short('h').long("help").action(ArgAction::Help).help("Print help");
+        command.arg(help).mut_subcommands(with_help)
     }
+    Cli::command().mut_subcommands(with_help)
+}
 
-    if cli.delete_project {
-        println!("{} {}", ">>".cyan().bold(), "Delete project (NUKE)".bold());
-        if !cli.yes {
-            bail!(
-                "{} Use {} to confirm deletion.",
-                "Refusing to delete without confirmation.".yellow(),
-                "--yes".bold()
-            );
+/// Print a clap parse error as clap would, with a `hint:` line for the usual slips in
+/// place of clap's own tip, then exit with clap's code.
+fn exit_parse_error(err: clap::Error) -> ! {
+    let Some(hint) = parse_hint(&err) else { err.exit() };
+    let rendered = match io::stderr().is_terminal() {
+        true => err.render().ansi().to_string(),
+        false => err.render().to_string(),
+    };
+    let hint = format!("  {} {hint}", hint_label());
+    let mut lines: Vec<String> = rendered.lines().map(str::to_string).collect();
+    match lines.iter().position(|l| l.trim_start().starts_with("tip:") || l.contains("tip:\x1b")) {
+        Some(i) => lines[i] = hint,
+        None => {
+            lines.splice(1..1, [String::new(), hint]);
         }
-        delete_project(&root)?;
-        println!("{} {}", "OK".green().bold(), "Project deleted.");
     }
+    eprintln!("{}", lines.join("\n"));
+    std::process::exit(err.exit_code());
+}
 
-    Ok(())
+/// What to say under a parse error: the nearest real flag for an unknown one
+/// (`--create-project` is `--create_project`), or what a `--python` version looks like.
+fn parse_hint(err: &clap::Error) -> Option<String> {
+    use clap::error::{ContextKind, ContextValue, ErrorKind};
+    let Some(ContextValue::String(arg)) = err.get(ContextKind::InvalidArg) else {
+        return None;
+    };
+    match err.kind() {
+        ErrorKind::UnknownArgument => {
+            let (flag, distance) = nearest_flag(arg)?;
+            Some(match distance {
+                0 if flag == arg.split('=').next().unwrap_or(arg) => format!(
+                    "`{flag}` is a top-level flag, not one of this subcommand's; `--help` after the subcommand lists those"
+                ),
+                0 => format!("did you mean `{flag}`? The mode flags are spelled with underscores: --create_project, --clean_project, --delete_project"),
+                _ => format!("did you mean `{flag}`? `py-proj --help` lists every flag"),
+            })
+        }
+        ErrorKind::ValueValidation if arg.starts_with("--python <") => Some(
+            "pass a version like `--python 3`, `--python 3.12`, `--python 3.12.4`, or `--python 3.13t` \
+             (free-threaded); `uv python list` shows what uv can install"
+                .to_string(),
+        ),
+        _ => None,
+    }
 }
-fn print_help() {
-    use owo_colors::OwoColorize as _;
 
-    // Keep the ASCII banner exactly as-is
-    print!("{BANNER}");
+/// The long flag of [`Cli`] closest to `arg`, and how far off it is, counting `-` and
+/// `_` as the same and ignoring case (so `--Create-Project` is 0 from `--create_project`).
+/// `None` when nothing is within a third of the flag's length.
+fn nearest_flag(arg: &str) -> Option<(String, usize)> {
+    use clap::CommandFactory;
+    let normalize = |s: &str| s.trim_start_matches('-').to_ascii_lowercase().replace('_', "-");
+    let wanted = normalize(arg.split('=').next().unwrap_or(arg));
+    if wanted.is_empty() {
+        return None;
+    }
+    let command = Cli::command();
+    let flags = command
+        .get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .flat_map(|a| a.get_long().into_iter().chain(a.get_all_aliases().unwrap_or_default()));
+    flags
+        .map(|flag| (util::edit_distance(&normalize(flag), &wanted), flag))
+        .filter(|(distance, _)| *distance <= (wanted.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(distance, flag)| (format!("--{flag}"), distance))
+}
 
-    let cmd = "py-proj";
+/// `--check-update`: ask GitHub now and say whether there is a newer release. Failing
+/// to find out is only a warning, whatever else this run did.
+#[allow(clippy::print_literal)]
+fn print_update_check() {
+    println!("{} {}", ">>".cyan().bold(), "Check for updates".bold());
+    match update::check(true) {
+        Ok(Some(status)) => {
+            println!("  {} {}", "Current:".dimmed(), status.current);
+            println!(
+                "  {} {} {}",
+                "Latest: ".dimmed(),
+                status.latest.blue().bold(),
+                format!("({})", status.release.url).dimmed()
+            );
+            match status.update_available() {
+                true => println!(
+                    "{} py-proj {} is available: {}",
+                    "OK".green().bold(),
+                    status.latest,
+                    status.release.url
+                ),
+                false => println!("{} {}", "OK".green().bold(), "py-proj is up to date."),
+            }
+        }
+        Ok(None) => {}
+        Err(e) => println!("{} could not check for updates: {e}", "WARN".yellow().bold()),
+    }
+}
 
-    // USAGE
-    println!("{}", "USAGE".bold().underline());
-    println!("  {}", format!("{cmd} [FLAGS] [OPTIONS]").italic());
-    println!();
+/// `check_updates = true`: at most once a week, mention a newer release on stderr. A
+/// failed check is a single dimmed line, and a known failure is not retried until then.
+fn update_notice() {
+    match update::check(false) {
+        Ok(Some(status)) if status.update_available() => eprintln!(
+            "  {} {}",
+            "note".cyan(),
+            format!(
+                "py-proj {} is available (you have {}): {}",
+                status.latest, status.current, status.release.url
+            )
+            .dimmed()
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("  {}", format!("update check failed: {e}").dimmed()),
+    }
+}
 
-    // EXAMPLE
-    println!("{}", "EXAMPLE".bold().underline());
-    println!(
-        "  {}",
-        format!("{cmd} --create_project --project myproj --python 3.13.1").cyan()
-    );
-    println!();
+/// Runs this invocation started, appended to the history log by `main` once `run`
+/// returns. An entry still without an outcome by then is the one that failed.
+static HISTORY: Mutex<Vec<history::Entry>> = Mutex::new(Vec::new());
 
-    // FLAGS
-    println!("{}", "FLAGS".bold());
-    println!(
-        "  {}  {}",
-        "🆕  --create_project".green().bold(),
-        "Create a new project in the target directory.".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "🧹  --clean_project".yellow().bold(),
-        "Remove caches: .venv, __pycache__, .pytest_cache, .ruff_cache, etc.".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "💣  --delete_project".red().bold(),
-        "Delete the entire project directory (requires --yes).".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "✅  -y, --yes".green().bold(),
-        "Auto-confirm dangerous actions (e.g., delete).".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "❓  -h, --help".bold(),
-        "Show this help.".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "🏷️  -V, --version".bold(),
-        "Show version.".dimmed()
-    );
-    println!();
+/// Start a history entry for `command` on `root`.
+fn record(command: &str, root: &Path) {
+    let args = env::args().skip(1).collect();
+    HISTORY.lock().unwrap().push(history::Entry::new(command, root, args));
+}
 
-    // OPTIONS
-    println!("{}", "OPTIONS".bold());
-    println!(
-        "  {}  {}",
-        "📦  -p, --project <NAME>".bold(),
-        "Project name (default: <cwd>_proj).".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "🐍  -P, --python <VER>".bold(),
-        "Python version for uv (default: auto-detected).".dimmed()
-    );
-    println!(
-        "  {}  {}",
-        "📁  --outdir <PATH>".bold(),
-        "Output directory (default: $PWD/<project>).".dimmed()
-    );
-    println!();
+/// Set the outcome of the latest history entry.
+fn recorded(outcome: &str) {
+    if let Some(entry) = HISTORY.lock().unwrap().last_mut() {
+        entry.outcome = outcome.to_string();
+    }
+}
 
-    // TIP
-    println!("{}", "💡  TIP".bold());
-    println!("  {}", "After creating the project, run".dimmed());
-    println!("    {}", "`uv pip install -e \".[dev]\"`".bold());
-    println!("  {}", "then".dimmed());
-    println!("    {}", "`uv run python -m src.main`".bold());
+/// Add the phase timings of a create to the latest history entry.
+fn recorded_phases(timings: &[(String, Duration)]) {
+    if let Some(entry) = HISTORY.lock().unwrap().last_mut() {
+        entry.set_phases(timings);
+    }
 }
-/// Create the project using the existing scaffolder plan (non-interactive).
-fn create_project(
-    root: &Path,
-    project: &str,
-    py_full: &str,
-    mm: &str,
-    mm_nodec: &str,
-) -> Result<()> {
-    // Ensure directories (same layout you had, plus app_logging)
-    for d in ["src", "tests", "Notebooks", ".vscode", "src/app_logging"] {
-        fs::create_dir_all(root.join(d))?;
-    }
-
-    let plan = ScaffoldPlan {
-        root: root.to_path_buf(),
-        project: project.to_string(),
-        py_full: py_full.to_string(),
-        mm: mm.to_string(),
-        mm_nodec: mm_nodec.to_string(),
-    };
 
-    plan.write_basic_src()?;
-    plan.write_vscode()?;
-    plan.write_envs()?;
-    plan.write_pyrefly()?;
-    plan.write_pyright()?;
-    plan.write_pyproject()?;
-    plan.write_gitignore()?;
-    plan.write_readme()?;
-    plan.write_app_logging()?; // include your logging package
-    plan.install_uv_toolchain()?; // uv python install + venv
-    plan.wirte_makefile()?; // wirte the makefile
+/// Add the bytes a clean removed to the latest history entry.
+fn recorded_freed(bytes: u64) {
+    if let Some(entry) = HISTORY.lock().unwrap().last_mut() {
+        entry.freed = Some(bytes);
+    }
+}
 
-    Ok(())
+/// Append this run's entries to the history log. The operation already happened, so
+/// failing to log it only warns (on stderr, keeping `--json` output intact).
+fn write_history(result: &Result<ExitCode>) {
+    let entries = std::mem::take(&mut *HISTORY.lock().unwrap());
+    let Some(log) = history::log_path().filter(|_| !entries.is_empty()) else {
+        return;
+    };
+    for mut entry in entries {
+        entry.finish();
+        if entry.outcome.is_empty() {
+            entry.outcome = match result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => format!("failed: {e:#}"),
+            };
+            if let Err(e) = result {
+                entry.error_kind = Some(pyproj::Error::find(e).map_or("Other", pyproj::Error::kind).to_string());
+            }
+        }
+        if let Err(e) = history::append(&log, &entry) {
+            eprintln!("{} history not recorded: {e:#}", "WARN".yellow().bold());
+            return;
+        }
+    }
 }
 
-/// Remove common build/test caches under the project.
-fn clean_project(root: &Path) -> Result<()> {
-    use std::fs::{remove_dir_all, remove_file};
-
-    let dirs = [
-        ".venv",
-        "__pycache__",
-        ".pytest_cache",
-        ".mypy_cache",
-        ".ruff_cache",
-        ".ipynb_checkpoints",
-        "build",
-        "dist",
-        "htmlcov",
-        ".coverage", // sometimes a file
-        ".cache",
-        "src/__pycache__",
-        "tests/__pycache__",
-        "Notebooks/.ipynb_checkpoints",
-    ];
-
-    for rel in dirs {
-        let p = root.join(rel);
-        if p.is_dir() {
-            println!(
-                "  {} {}",
-                "rm -rf".yellow(),
-                p.display().to_string().dimmed()
-            );
-            let _ = remove_dir_all(&p);
-        } else if p.is_file() && rel == ".coverage" {
-            println!("  {} {}", "rm".yellow(), p.display().to_string().dimmed());
-            let _ = remove_file(&p);
+/// Print `err` (as JSON for `--json` commands) and pick the exit code for its kind.
+fn report_error(err: &anyhow::Error, json: bool) -> ExitCode {
+    let typed = pyproj::Error::find(err);
+    let code = typed.map_or(1, pyproj::Error::exit_code);
+    if json {
+        let mut report = serde_json::json!({
+            "error": {
+                "kind": typed.map_or("Other", pyproj::Error::kind),
+                "message": format!("{err:#}"),
+                "exit_code": code,
+            }
+        });
+        if let Some(pyproj::Error::PreflightFailed { checks }) = typed {
+            report["preflight"] = serde_json::to_value(checks).unwrap_or_default();
+        }
+        if let Some(hint) = typed.and_then(pyproj::Error::hint) {
+            report["error"]["hint"] = hint.into();
+        }
+        println!("{report:#}");
+    } else {
+        eprintln!("Error: {err:?}");
+        if let Some(hint) = typed.and_then(pyproj::Error::hint) {
+            eprintln!("  {} {hint}", hint_label());
         }
     }
-    Ok(())
+    ExitCode::from(code)
 }
 
-/// Delete the entire project directory (dangerous).
 #[allow(clippy::print_literal)]
-fn delete_project(root: &Path) -> Result<()> {
-    if root.exists() {
-        println!("  {} {}", "rm -rf".red().bold(), root.display());
-        fs::remove_dir_all(root).with_context(|| format!("Failed to delete {}", root.display()))?;
-    } else {
-        println!("  {} {}", "SKIP".dimmed(), "Project root does not exist.");
+fn run(mut cli: Cli) -> Result<ExitCode> {
+
+    // A broken config file is reported by the commands that need it; here it only
+    // means falling back to the default timeouts.
+    let timeout = cli.command_timeout.or_else(|| {
+        Config::load(cli.config.as_deref()).ok()?.command_timeout
+    });
+    if let Some(secs) = timeout {
+        util::set_command_timeout((secs > 0).then(|| Duration::from_secs(secs)));
     }
-    Ok(())
+    let lock_timeout = cli.lock_timeout.or_else(|| {
+        Config::load(cli.config.as_deref()).ok()?.lock_timeout
+    });
+    if let Some(secs) = lock_timeout {
+        pyproj::lock::set_lock_timeout(Duration::from_secs(secs));
+    }
+
+    progress::set_quiet(cli.quiet);
+    pyproj::manifest::source_date_epoch()?;
+    pyproj::manifest::set_deterministic(cli.deterministic);
+
+    if let Some(path) = &cli.uv_bin {
+        util::set_uv_bin(&util::expand_tilde(Path::new(path)).to_string_lossy())?;
+    }
+
+    if cli.version {
+        println!("{} {}", "py-proj".bold(), env!("CARGO_PKG_VERSION").green());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(command) = cli.command.take() {
+        return match command {
+            Command::Templates {
+                action: TemplatesCommand::Export { dir, force },
+            } => {
+                println!("{} {}", ">>".cyan().bold(), "Export templates".bold());
+                templates::export(&dir, force)?;
+                println!("{} {}", "OK".green().bold(), "Templates exported.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Debug {
+                action: DebugCommand::RenderAll { outdir, force },
+            } => {
+                println!("{} {}", ">>".cyan().bold(), "Render all templates".bold());
+                templates::render_all(&outdir, force)?;
+                println!("{} Rendered into {}.", "OK".green().bold(), outdir.display());
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Templates {
+                action: TemplatesCommand::List,
+            } => {
+                templates::list(&preview_plan(&cli)?)?;
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Templates {
+                action: TemplatesCommand::Show { path, raw },
+            } => {
+                templates::show(&preview_plan(&cli)?, &path, raw)?;
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Config {
+                action: ConfigCommand::Show { origin, json },
+            } => {
+                let config = Config::load(cli.config.as_deref())?;
+                let editor = env::var("PY_PROJ_EDITOR").ok().filter(|e| !e.trim().is_empty());
+                let env = editor
+                    .into_iter()
+                    .map(|editor| ("editor", (toml::Value::String(editor), "PY_PROJ_EDITOR")))
+                    .collect();
+                let settings = config_show::settings(&config, &config_flags(&cli), &env);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&settings)?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                println!("{} {}", ">>".cyan().bold(), "Config".bold());
+                let cwd = env::current_dir()?;
+                let rc = pyproj::config::find_rc(&cwd);
+                let rc = rc.map_or_else(|| "none".dimmed().to_string(), |p| p.display().to_string().blue().to_string());
+                println!("  {} {rc}", "Repo rc:".dimmed());
+                for warning in &config.warnings {
+                    println!("  {} {}", "WARN".yellow().bold(), warning.yellow());
+                }
+                config_show::print_settings(&settings, origin);
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Open { path } => {
+                let start = match path {
+                    Some(p) => util::expand_tilde(&p),
+                    None => env::current_dir()?,
+                };
+                let config = Config::load(cli.config.as_deref())?;
+                open::open_project(&start, cli.editor.as_deref(), config.editor.as_deref())?;
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Info { path, json } => {
+                let start = match path {
+                    Some(p) => util::expand_tilde(&p),
+                    None => env::current_dir()?,
+                };
+                let info = info::inspect(&start)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&info)?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                println!("{} {}", ">>".cyan().bold(), "Project info".bold());
+                info::print_info(&info);
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Doctor { path, json } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                if !json {
+                    println!("{} {}", ">>".cyan().bold(), "Doctor".bold());
+                    println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                }
+                report_checks(&doctor::diagnose(&root)?, json)
+            }
+            Command::CheckVscode { path, json } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                if !json {
+                    println!("{} {}", ">>".cyan().bold(), "Check VS Code".bold());
+                    println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                }
+                report_checks(&check_vscode::diagnose(&root)?, json)
+            }
+            Command::AddModule { modules, path } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Add module".bold());
+                add_module::add_modules(&root, &modules)?;
+                println!("{} {}", "OK".green().bold(), "Modules added.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Adopt { path, force, skip_uv } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Adopt project".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = adopt::AdoptOptions {
+                    force,
+                    skip_uv,
+                    verbose: cli.verbose,
+                };
+                adopt::adopt_project(&root, &opts)?;
+                println!("{} {}", "OK".green().bold(), "Project adopted.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Diff { path } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Diff project".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                if diff::diff_project(&root, cli.verbose)? {
+                    return Ok(ExitCode::FAILURE);
+                }
+                println!("{} {}", "OK".green().bold(), "Project matches the templates.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Verify { path, update_manifest } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Verify project".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = verify::VerifyOptions { update_manifest };
+                if verify::verify_project(&root, &opts, cli.verbose)? {
+                    return Ok(ExitCode::FAILURE);
+                }
+                println!("{} {}", "OK".green().bold(), "Generated files match the manifest.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::List {
+                dir,
+                max_depth,
+                stale,
+                json,
+            } => {
+                let dir = match dir {
+                    Some(d) => d,
+                    None => env::current_dir()?,
+                };
+                let mut projects = list::find_projects(&dir, max_depth, &[])?;
+                if let Some(days) = stale {
+                    projects = list::filter_stale(projects, days);
+                }
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&projects)?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                println!("{} {}", ">>".cyan().bold(), "List projects".bold());
+                if projects.is_empty() {
+                    println!("  {} {}", "SKIP".dimmed(), "No projects found.");
+                } else {
+                    list::print_table(&projects);
+                }
+                println!("{} {} project(s).", "OK".green().bold(), projects.len());
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::RenameProject {
+                from,
+                to,
+                path,
+                force,
+                dry_run,
+            } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Rename project".bold());
+                println!("  {} {} → {}", "Name:".dimmed(), from.dimmed(), to.blue().bold());
+                let opts = rename::RenameOptions {
+                    from: &from,
+                    to: &to,
+                    force,
+                    dry_run,
+                };
+                rename::rename_project(&root, &opts)?;
+                if dry_run {
+                    println!("{} {}", "OK".green().bold(), "Dry run: nothing was written.");
+                } else {
+                    println!("{} {}", "OK".green().bold(), "Project renamed.");
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::ApplyPlan { file, root } => {
+                let planned = PlanFile::load(&file)?;
+                let root = root.unwrap_or_else(|| planned.root.clone());
+                record("apply-plan", &root);
+                println!("{} {}", ">>".cyan().bold(), "Apply plan".bold());
+                println!("  {} {}", "Plan:".dimmed(), file.display());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let summary = planned.apply(Some(&root), commands::printer(cli.verbose))?;
+                recorded("ok");
+                println!("{} Plan applied: {}.", "OK".green().bold(), summary.line());
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Upgrade {
+                path,
+                force,
+                dry_run,
+            } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                record("upgrade", &root);
+                println!("{} {}", ">>".cyan().bold(), "Upgrade project".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = upgrade::UpgradeOptions {
+                    force,
+                    dry_run,
+                    verbose: cli.verbose,
+                };
+                upgrade::upgrade_project(&root, &opts)?;
+                recorded(if dry_run { "dry run" } else { "ok" });
+                if dry_run {
+                    println!("{} {}", "OK".green().bold(), "Dry run: nothing was written.");
+                } else {
+                    println!("{} {}", "OK".green().bold(), "Project upgraded.");
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Archive {
+                path,
+                output,
+                format,
+                with_git,
+                then_delete,
+                trash,
+                yes,
+            } => {
+                let root = match path {
+                    Some(p) => resolve_target(&util::expand_tilde(&p))?,
+                    None => env::current_dir()?,
+                };
+                record("archive", &root);
+                println!("{} {}", ">>".cyan().bold(), "Archive project".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = archive::ArchiveOptions {
+                    output,
+                    format,
+                    with_git,
+                    deterministic: cli.deterministic,
+                    verbose: cli.verbose,
+                };
+                let archived = util::interruptible(|| archive::archive(&root, &opts))?;
+                for rel in &archived.excluded {
+                    println!("  {} {}", "skip".dimmed(), rel.display().to_string().dimmed());
+                }
+                recorded("archived");
+                println!(
+                    "{} Archived {} file(s) to {} {}",
+                    "OK".green().bold(),
+                    archived.files,
+                    archived.path.display().to_string().blue(),
+                    format!("({}, {})", archived.format.extension(), human_bytes(archived.bytes)).dimmed()
+                );
+                if then_delete {
+                    println!("{} {}", ">>".cyan().bold(), "Delete project (NUKE)".bold());
+                    let opts = DeleteOptions {
+                        trash,
+                        ..Default::default()
+                    };
+                    let deleted = confirm_and_delete(&root, &opts, yes)?;
+                    recorded(match deleted {
+                        "skipped" => "archived",
+                        _ => "archived and deleted",
+                    });
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Undo {
+                path,
+                dry_run,
+                force_modified,
+            } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                record("undo", &root);
+                println!("{} {}", ">>".cyan().bold(), "Undo scaffold".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = undo::UndoOptions {
+                    dry_run,
+                    force_modified,
+                    verbose: cli.verbose,
+                };
+                let undone = undo::undo(&root, &opts)?;
+                recorded(if dry_run { "dry run" } else { "ok" });
+                if dry_run {
+                    println!("  {} {}", "Would remove:".bold(), undone.removed);
+                    println!("  {} {}", "Would restore:".bold(), undone.restored);
+                    println!("{} {}", "OK".green().bold(), "Dry run: nothing was removed.");
+                } else if undone.modified > 0 {
+                    println!(
+                        "{} Removed {} and restored {} file(s); kept {} edited file(s) and {MANIFEST_FILE} (see --force-modified).",
+                        "OK".green().bold(),
+                        undone.removed,
+                        undone.restored,
+                        undone.modified
+                    );
+                } else {
+                    println!(
+                        "{} Scaffold undone: {} file(s) removed, {} restored.",
+                        "OK".green().bold(),
+                        undone.removed,
+                        undone.restored
+                    );
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Repair { path, dry_run, skip_uv } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                record("repair", &root);
+                println!("{} {}", ">>".cyan().bold(), "Repair scaffold".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = repair::RepairOptions {
+                    dry_run,
+                    skip_uv,
+                    verbose: cli.verbose,
+                };
+                let repaired = repair::repair(&root, &opts)?;
+                recorded(if dry_run { "dry run" } else { "ok" });
+                let venv = match repaired.venv {
+                    true => ", the venv",
+                    false => "",
+                };
+                if dry_run {
+                    println!(
+                        "{} Dry run: would restore {} file(s), {} dir(s){venv}.",
+                        "OK".green().bold(),
+                        repaired.files,
+                        repaired.dirs
+                    );
+                } else if repaired.files + repaired.dirs == 0 && !repaired.venv {
+                    println!("{} {}", "OK".green().bold(), "Nothing missing.");
+                } else {
+                    println!(
+                        "{} Restored {} file(s), {} dir(s){venv}.",
+                        "OK".green().bold(),
+                        repaired.files,
+                        repaired.dirs
+                    );
+                }
+                if repaired.modified > 0 {
+                    println!(
+                        "  {} {} edited file(s) left as they are (`diff` shows the changes).",
+                        "Note:".dimmed(),
+                        repaired.modified
+                    );
+                }
+                if repaired.unrestorable > 0 {
+                    println!(
+                        "  {} {} missing file(s) no template renders any more.",
+                        "Note:".dimmed(),
+                        repaired.unrestorable
+                    );
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Bump {
+                part,
+                path,
+                git_tag,
+                dry_run,
+            } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Bump version".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let opts = bump::BumpOptions {
+                    part,
+                    git_tag,
+                    dry_run,
+                };
+                bump::bump_project(&root, &opts)?;
+                if dry_run {
+                    println!("{} {}", "OK".green().bold(), "Dry run: nothing was written.");
+                } else {
+                    println!("{} {}", "OK".green().bold(), "Version bumped.");
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::ExportRequirements { path, group, output } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Export requirements".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                export::export_requirements(&root, &export::ExportOptions { group, output })?;
+                println!("{} {}", "OK".green().bold(), "Requirements exported.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::ExportConda { path, output } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Export conda environment".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                export::export_conda(&root, &export::CondaOptions { output })?;
+                println!("{} {}", "OK".green().bold(), "Conda environment exported.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Sbom { path, format, output } => {
+                let root = match path {
+                    Some(p) => p,
+                    None => env::current_dir()?,
+                };
+                println!("{} {}", ">>".cyan().bold(), "Software bill of materials".bold());
+                println!("  {} {}", "Root:".dimmed(), root.display().to_string().blue());
+                let written = sbom::write_sbom(&root, &sbom::SbomOptions { format, output })?;
+                print_sbom(&written);
+                println!("{} {}", "OK".green().bold(), "SBOM written.");
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Env { path, shell } => {
+                let start = match path {
+                    Some(p) => util::expand_tilde(&p),
+                    None => env::current_dir()?,
+                };
+                let shell = shell.unwrap_or_else(activate::Shell::detect);
+                if let Err(e) = activate::print_env(&start, shell) {
+                    // The comment on stdout already says why; keep eval's input clean.
+                    eprintln!("py-proj env: {e:#}");
+                    return Ok(ExitCode::FAILURE);
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Run { dir, yes, command } => {
+                let start = match dir {
+                    Some(p) => util::expand_tilde(&p),
+                    None => env::current_dir()?,
+                };
+                let code = commands::run::run_in_project(&start, &command, yes || cli.yes)?;
+                // Hand back the command's status as is; py-proj adds nothing after it.
+                Ok(ExitCode::from(u8::try_from(code).unwrap_or(1)))
+            }
+            Command::Batch {
+                spec,
+                parallel,
+                keep_going,
+                fail_fast,
+                dry_run,
+                skip_uv,
+                offline,
+            } => {
+                println!("{} {}", ">>".cyan().bold(), "Batch create".bold());
+                println!("  {} {}", "Spec:".dimmed(), spec.display().to_string().blue());
+                let config = Config::load(cli.config.as_deref())?;
+                let spec = batch::load(&spec, skip_uv, offline)?;
+                let opts = batch::BatchOptions {
+                    parallel: parallel.into(),
+                    fail_fast: fail_fast || (spec.fail_fast && !keep_going),
+                    skip_uv,
+                    offline,
+                    verbose: cli.verbose,
+                };
+                let entries = spec.entries;
+                let counts = batch::validate(&entries, &config, &opts)?;
+                if dry_run {
+                    batch::print_plan(&entries, &counts);
+                    println!(
+                        "{} Dry run: {} project(s) validated, nothing was written.",
+                        "OK".green().bold(),
+                        entries.len()
+                    );
+                    return Ok(ExitCode::SUCCESS);
+                }
+                batch::install_pythons(&entries, &opts)?;
+                let outcomes = util::interruptible(|| batch::run(&entries, &config, &opts));
+                for outcome in &outcomes {
+                    record("create", &outcome.root);
+                    recorded(&match &outcome.status {
+                        batch::Status::Created { .. } => "ok".to_string(),
+                        batch::Status::Failed { error } => format!("failed: {error}"),
+                        batch::Status::NotRun => "skipped".to_string(),
+                    });
+                }
+                println!("{} {}", ">>".cyan().bold(), "Batch results".bold());
+                batch::print_table(&outcomes);
+                let failed = outcomes
+                    .iter()
+                    .filter(|o| !matches!(o.status, batch::Status::Created { .. }))
+                    .count();
+                if failed > 0 {
+                    bail!("{failed} of {} project(s) were not created", outcomes.len());
+                }
+                println!("{} {} project(s) created.", "OK".green().bold(), outcomes.len());
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::History { path, limit, json } => {
+                let log = history::log_path()
+                    .context("no home directory (or $XDG_STATE_HOME) to keep the history in")?;
+                let entries = history::read(&log, path.as_deref(), limit)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                println!("{} {}", ">>".cyan().bold(), "History".bold());
+                println!("  {} {}", "Log:".dimmed(), log.display().to_string().blue());
+                if entries.is_empty() {
+                    println!("  {} {}", "SKIP".dimmed(), "No runs recorded.");
+                } else {
+                    commands::history::print_table(&entries, cli.verbose);
+                }
+                println!("{} {} run(s).", "OK".green().bold(), entries.len());
+                Ok(ExitCode::SUCCESS)
+            }
+            Command::Stats { path, since, json, clear } => {
+                let log = history::log_path()
+                    .context("no home directory (or $XDG_STATE_HOME) to keep the history in")?;
+                if clear {
+                    match history::clear(&log)? {
+                        true => println!("{} Removed {}.", "OK".green().bold(), log.display()),
+                        false => println!("{} {}", "OK".green().bold(), "No history to remove."),
+                    }
+                    return Ok(ExitCode::SUCCESS);
+                }
+                let entries = stats::since(history::read(&log, path.as_deref(), usize::MAX)?, since);
+                let summary = stats::aggregate(&entries);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&summary)?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                println!("{} {}", ">>".cyan().bold(), "Usage statistics".bold());
+                println!("  {} {}", "Log:".dimmed(), log.display().to_string().blue());
+                if entries.is_empty() {
+                    println!("  {} {}", "SKIP".dimmed(), "No runs recorded.");
+                } else {
+                    stats::print(&summary);
+                }
+                println!("{} {} run(s).", "OK".green().bold(), entries.len());
+                Ok(ExitCode::SUCCESS)
+            }
+        };
+    }
+
+    // If help is requested or no primary action was provided, show help and exit.
+    let no_action = !(cli.create_project || cli.clean_project || cli.delete_project);
+    if no_action && cli.check_update && !cli.help {
+        // `main` does the check once this returns.
+        return Ok(ExitCode::SUCCESS);
+    }
+    if cli.help || no_action {
+        print_help();
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Resolve defaults
+    let config = Config::load(cli.config.as_deref())?;
+    if !cli.json {
+        for warning in &config.warnings {
+            println!("  {} {}", "WARN".yellow().bold(), warning.yellow());
+        }
+    }
+    let python_origin = config.origins("python").last().map(|layer| layer.path().to_path_buf());
+    let templates_dir = cli.templates_dir.or(config.templates_dir);
+    let vars = template_vars(config.vars, cli.vars);
+    let mut post_hooks = config.post_hooks;
+    post_hooks.extend(cli.post_hooks);
+    let mut gitignore_extra = config.gitignore_extra;
+    gitignore_extra.extend(cli.gitignore_extra);
+    let mut clean_extra = config.clean.extra_patterns;
+    clean_extra.extend(cli.clean_extra);
+    if cli.clean_project && cli.clean_default_off && clean_extra.is_empty() {
+        bail!("--clean-default-off leaves nothing to clean; add --clean-extra <GLOB> or [clean] extra_patterns");
+    }
+    if let Some(dir) = &cli.all {
+        let dir = resolve_target(&util::expand_path(dir)?)?;
+        let opts = clean_all::CleanAllOptions {
+            clean: CleanOptions {
+                keep_venv: cli.keep_venv,
+                extra_patterns: clean_extra,
+                only_extra: cli.clean_default_off,
+            },
+            max_depth: cli.max_depth,
+            exclude: cli.exclude_dir,
+            dry_run: cli.dry_run,
+            yes: cli.yes,
+            json: cli.json,
+        };
+        record("clean", &dir);
+        if !cli.json {
+            println!("{} {}", ">>".cyan().bold(), "Clean every project".bold());
+            println!("  {} {}", "Under:".dimmed(), dir.display().to_string().blue());
+        }
+        let cleaned = clean_all::clean_all(&dir, &opts)?;
+        recorded(match (cli.dry_run, cleaned.confirmed, cleaned.errors.len()) {
+            (true, ..) => "dry run",
+            (false, false, _) if cleaned.reclaimable_bytes > 0 => "skipped",
+            (false, _, 0) => "ok",
+            (false, ..) => "failed: some projects could not be cleaned",
+        });
+        if cli.json {
+            println!("{}", serde_json::to_string_pretty(&cleaned)?);
+            return Ok(ExitCode::SUCCESS);
+        }
+        util::check_interrupt()?;
+        match (cli.dry_run, cleaned.confirmed) {
+            (true, _) => println!("{} {}", "OK".green().bold(), "Dry run: nothing was removed."),
+            (false, false) if cleaned.reclaimable_bytes > 0 => {
+                println!("  {} {}", "SKIP".dimmed(), "Nothing was removed.")
+            }
+            (false, false) => println!("{} {}", "OK".green().bold(), "Nothing to clean."),
+            (false, true) => {
+                let cleaned_count = cleaned.projects.iter().filter(|p| p.freed_bytes > 0).count();
+                println!(
+                    "{} Cleaned {cleaned_count} project(s), freed {}.",
+                    "OK".green().bold(),
+                    human_bytes(cleaned.freed_bytes)
+                );
+            }
+        }
+        if !cleaned.errors.is_empty() {
+            println!("  {}", "Errors:".yellow().bold());
+            for error in &cleaned.errors {
+                println!("    {}", error.yellow());
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    let cwd = env::current_dir()?;
+    // Without a path, clean/delete act on the project around the current directory.
+    let discover = !cli.create_project
+        && cli.path.is_none()
+        && cli.outdir.is_none()
+        && cli.project.is_none();
+    let mut project = cli.project.unwrap_or_else(|| match cli.here {
+        true => here_project(&cwd),
+        false => default_project(&cwd),
+    });
+    // Only a new project's name is cleaned up; clean/delete target what is on disk.
+    let mut renamed = None;
+    if cli.create_project {
+        let (clean, note) = util::sanitize_project_name(&project)?;
+        renamed = note.map(|note| (std::mem::replace(&mut project, clean), note));
+    }
+    let root = match (cli.path.or(cli.outdir), discover) {
+        (Some(dir), _) => util::expand_path(&dir)?,
+        (None, true) => util::find_project_root(&cwd).ok_or_else(|| {
+            let flag = match cli.delete_project {
+                true => "--delete_project",
+                false => "--clean_project",
+            };
+            pyproj::Error::project_not_found(&cwd, format!("py-proj {flag} path/to/proj"))
+        })?,
+        (None, false) if cli.here => cwd.clone(),
+        (None, false) => cwd.join(&project),
+    };
+    let root = match cli.create_project {
+        true => util::resolve_new_dir(&root, cli.create_parents)?,
+        false => resolve_target(&root)?,
+    };
+    let (mut py_full, py_source) = match (cli.py_full, config.python.as_ref().zip(python_origin.as_ref())) {
+        (Some(python), _) => (python, PythonSource::Flag),
+        (None, Some((python, path))) => {
+            let python = python
+                .parse()
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("invalid `python` in {}", path.display()))?;
+            (python, PythonSource::Config(path.clone()))
+        }
+        (None, None) => detect_python(&cwd),
+    };
+
+    if cli.create_project {
+        record("create", &root);
+        util::validate_project_name(&project)?;
+        if cli.here && !cli.force && !cli.backup && !cli.force_interactive {
+            check_here_empty(&root)?;
+        }
+        // `--quiet` prints just the project path at the end, `--json` just the report.
+        let chatty = !cli.quiet && !cli.json;
+        let resolved = match cli.skip_uv {
+            true => py_full.clone(),
+            false => py_full.resolve(cli.offline),
+        };
+        if chatty {
+            println!("{} {}", ">>".cyan().bold(), "Create project".bold());
+            println!("  {} {}", "Project:".dimmed(), project.blue().bold());
+            println!(
+                "  {} {}",
+                "Root:   ".dimmed(),
+                root.display().to_string().blue()
+            );
+            let shown = match resolved == py_full {
+                true => py_full.magenta().to_string(),
+                false => format!("{} → {} (resolved)", py_full, resolved).magenta().to_string(),
+            };
+            println!(
+                "  {} {} {}",
+                "Python: ".dimmed(),
+                shown,
+                format!("(from {py_source})").dimmed()
+            );
+            if let Some(toolchain) = cli.toolchain_file {
+                println!(
+                    "  {} {} {}",
+                    "Pin:    ".dimmed(),
+                    toolchain.file().magenta(),
+                    format!("(--toolchain-file {})", toolchain.name()).dimmed()
+                );
+            }
+            if cli.verbose && !cli.skip_uv {
+                let preference = match cli.uv_python_preference {
+                    Some(p) => format!("(--python-preference {})", p.name()),
+                    None => String::new(),
+                };
+                println!("  {} {} {}", "uv:     ".dimmed(), util::resolved_uv_bin(), preference.dimmed());
+            }
+        }
+        py_full = resolved;
+
+        let workspace_root = if cli.workspace_member {
+            let ws = match workspace::find_root(&root)? {
+                Some(ws) => Some(ws),
+                None if cli.init_workspace => {
+                    let ws = std::path::absolute(&cwd)?;
+                    if !cli.dry_run {
+                        let what = match workspace::init(&ws)? {
+                            true => "create",
+                            false => "update",
+                        };
+                        if chatty {
+                            println!("  {} {}", what.green(), "workspace pyproject.toml".dimmed());
+                        }
+                    }
+                    Some(ws)
+                }
+                None => None,
+            };
+            let ws = ws.context("no uv workspace root found; pass --init-workspace to create one")?;
+            if chatty {
+                println!("  {} {}", "Workspace:".dimmed(), ws.display().to_string().blue());
+            }
+            Some(ws)
+        } else {
+            None
+        };
+
+        let mut builder = ScaffoldPlan::builder(&project)
+            .root(&root)
+            .python(py_full.to_string())
+            .vars(vars)
+            .refresh_templates(cli.refresh_templates)
+            .skip_uv(cli.skip_uv)
+            .bootstrap_uv(cli.bootstrap_uv)
+            .offline(cli.offline)
+            .force_name(cli.force_name);
+        if let Some(preference) = cli.uv_python_preference {
+            builder = builder.python_preference(preference);
+        }
+        builder = with_metadata(builder.org_defaults(&config.org), &cli.metadata);
+        // Every event goes into the report; under --json nothing else goes onto stdout.
+        let reporter = Rc::new(report::Reporter::new(&cwd, !cli.no_manifest));
+        let printer = (!cli.json).then(|| commands::printer(cli.verbose));
+        builder = builder.on_event({
+            let reporter = Rc::clone(&reporter);
+            move |event| {
+                reporter.record(event);
+                if let Some(print) = &printer {
+                    print(event);
+                }
+            }
+        });
+        // Inside the project, the report is kept out of the sdist (and, if asked, git).
+        if let Some(rel) = cli.summary_file.as_deref().filter(|p| util::is_safe_relative(p)) {
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if cli.summary_file_gitignore {
+                builder = builder.gitignore_extra(format!("/{rel}"));
+            }
+            builder = builder.report_file(rel);
+        }
+        for hook in post_hooks {
+            builder = builder.post_hook(hook);
+        }
+        for pattern in gitignore_extra {
+            builder = builder.gitignore_extra(pattern);
+        }
+        for key in &cli.gen_secrets {
+            builder = builder.gen_secret(key);
+        }
+        for module in &cli.modules {
+            builder = builder.module(module.trim());
+        }
+        if let Some(addopts) = &cli.pytest_addopts {
+            builder = builder.pytest_addopts(addopts);
+        }
+        for marker in &cli.pytest_markers {
+            builder = builder.pytest_marker(marker);
+        }
+        for &extra in &cli.testing_extras {
+            builder = builder.testing_extra(extra);
+        }
+        builder = builder.async_main(cli.async_main).benchmarks(cli.benchmarks);
+        builder = builder.data_layout(cli.data_layout).dvc(cli.dvc);
+        for version in &cli.python_matrix {
+            builder = builder.matrix_python(version.to_string());
+        }
+        if let Some(runner) = cli.matrix_runner {
+            builder = builder.matrix_runner(runner);
+        }
+        if let Some(style) = cli.dep_style {
+            builder = builder.dep_style(style);
+        }
+        if let Some(bot) = cli.dep_updates {
+            builder = builder.dep_updates(bot);
+        }
+        if let Some(target) = cli.publish {
+            builder = builder.publish(target);
+        }
+        if let Some(hooks) = cli.git_hooks {
+            builder = builder.git_hooks(hooks);
+        }
+        if let Some(scanner) = cli.security {
+            builder = builder.security(scanner);
+        }
+        if let Some(status) = cli.status {
+            builder = builder.status(status);
+        }
+        if let Some(ide) = cli.ide.or(cli.no_vscode.then_some(Ide::None)) {
+            builder = builder.ide(ide);
+        }
+        if let Some(toolchain) = cli.toolchain_file {
+            builder = builder.toolchain(toolchain);
+        }
+        if let Some(endings) = cli.line_endings {
+            builder = builder.line_endings(endings);
+        }
+        if cli.tools_global {
+            builder = builder.tools_global(true);
+        }
+        if cli.emit_bootstrap {
+            builder = builder.emit_bootstrap(true);
+        }
+        if let Some(spdx) = &cli.license_header {
+            builder = builder.license_header(spdx);
+        }
+        if cli.jupytext {
+            builder = builder.jupytext(true).jupytext_ignore_ipynb(cli.jupytext_ignore_ipynb);
+        }
+        if cli.minimal || config.minimal {
+            builder = builder.minimal(true);
+            for component in config.with.iter().chain(&cli.with) {
+                builder = builder.with(component.trim());
+            }
+        }
+        if let Some(path) = &cli.code_workspace {
+            builder = builder.code_workspace(path.clone());
+        }
+        builder = builder.venv_seed(cli.venv_seed).recreate_venv(cli.recreate_venv);
+        if let Some(prompt) = &cli.venv_prompt {
+            builder = builder.venv_prompt(prompt);
+        }
+        if let Some(dir) = cli.venv_dir.as_ref().or(config.venv_dir.as_ref()) {
+            builder = builder.venv_dir(dir);
+        }
+        if let Some(dir) = cli.notebooks_dir.as_ref().or(config.notebooks_dir.as_ref()) {
+            builder = builder.notebooks_dir(dir);
+        }
+        for arg in &cli.uv_arg {
+            builder = builder.uv_arg(arg);
+        }
+        for pattern in config.exclude.iter().chain(&cli.exclude) {
+            builder = builder.exclude(pattern);
+        }
+        if let Some(mode) = cli.dir_mode {
+            builder = builder.dir_mode(mode);
+        }
+        if let Some(mode) = cli.file_mode {
+            builder = builder.file_mode(mode);
+        }
+        if let Some(lang) = &cli.lang {
+            builder = builder.lang(lang);
+        }
+        if let Some(profile) = cli.profile {
+            builder = builder.profile(profile);
+        }
+        if let Some(spec) = &cli.template_repo {
+            builder = builder.template_repo(spec);
+        } else if let Some(dir) = templates_dir {
+            builder = builder.templates_dir(dir);
+        }
+        if let Some(ws) = workspace_root {
+            builder = builder.workspace_member(ws);
+        }
+        let plan = builder.build()?;
+        let mut checks = pyproj::preflight::run(
+            &plan,
+            &pyproj::preflight::PreflightOptions { policy: write_policy(cli.force, cli.backup), renamed },
+            |key| env::var(key).ok(),
+        );
+        pyproj::preflight::ignore(&mut checks, &cli.preflight_ignore);
+        reporter.set_preflight(checks.clone());
+        if !cli.json {
+            preflight::print(&checks, cli.quiet);
+        }
+        // A dry run or a plan file writes nothing, so there is nothing to stop.
+        let writes = !cli.dry_run && cli.emit_plan.is_none();
+        if writes {
+            pyproj::preflight::gate(&checks)?;
+        }
+        if writes && !cli.json && checks.iter().any(pyproj::preflight::Check::warns) {
+            let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+            if cli.yes || cli.force || cli.force_interactive {
+                // The user already said to go ahead.
+            } else if interactive {
+                let go = dialoguer::Confirm::new()
+                    .with_prompt("Create the project anyway?")
+                    .default(false)
+                    .interact()?;
+                if !go {
+                    recorded("skipped");
+                    println!("  {} {}", "SKIP".dimmed(), "Nothing was created.");
+                    return Ok(ExitCode::SUCCESS);
+                }
+            } else {
+                println!(
+                    "  {} {}",
+                    "info".cyan(),
+                    "not a terminal, so continuing; pass --yes to acknowledge these warnings".dimmed()
+                );
+            }
+        }
+        if let Some(out) = &cli.emit_plan {
+            plan.check_venv()?;
+            let opts = CreateOptions {
+                manifest: !cli.no_manifest,
+                policy: write_policy(cli.force, cli.backup),
+                replace_pyproject: cli.pyproject_replace,
+                ..Default::default()
+            };
+            let actions = plan.plan(opts.manifest)?;
+            util::write(out, PlanFile::new(&plan, &actions, &opts)?.to_json()?)?;
+            recorded("plan emitted");
+            let files = actions.iter().filter(|a| matches!(a, PlannedAction::WriteFile { .. })).count();
+            let commands = actions.iter().filter(|a| matches!(a, PlannedAction::RunCommand { .. })).count();
+            println!(
+                "  {} {} {}",
+                "plan".green(),
+                out.display(),
+                format!("({files} files, {commands} commands)").dimmed()
+            );
+            println!(
+                "{} Plan written; nothing was created. Run it with `py-proj apply-plan {}`.",
+                "OK".green().bold(),
+                out.display()
+            );
+        } else if cli.dry_run && cli.json {
+            let report = reporter.report(&plan, None)?;
+            recorded("dry run");
+            println!("{}", report.to_json()?);
+        } else if cli.dry_run {
+            print_dry_run(&plan, !cli.no_manifest)?;
+            recorded("dry run");
+            println!("{} {}", "OK".green().bold(), "Dry run: nothing was written.");
+        } else {
+            let mut opts = CreateOptions {
+                manifest: !cli.no_manifest,
+                policy: write_policy(cli.force, cli.backup),
+                file_policies: Default::default(),
+                replace_pyproject: cli.pyproject_replace,
+                keep_on_failure: cli.keep_on_failure,
+            };
+            if cli.force || cli.force_interactive {
+                opts.file_policies = review_overwrites(&plan, &opts, cli.force_interactive, chatty)?;
+            }
+            let skipped = plan.offline_skips();
+            let started = Instant::now();
+            let created = pyproj::create(&plan, &opts).and_then(|summary| {
+                let sbom = match cli.sbom {
+                    Some(format) => Some(
+                        sbom::write_sbom(&plan.root, &sbom::SbomOptions { format, output: None })
+                            .context("the project was created, but its SBOM could not be written")?,
+                    ),
+                    None => None,
+                };
+                Ok((summary, sbom))
+            });
+            let total = started.elapsed();
+            let (summary, sbom) = match created {
+                Ok(created) => created,
+                Err(e) => {
+                    if let Some(path) = &cli.summary_file {
+                        write_failed_report(&reporter, &plan, &e, path, &cwd);
+                    }
+                    return Err(e);
+                }
+            };
+            recorded("ok");
+            recorded_phases(&summary.timings);
+            let mut report = reporter.report(&plan, Some(&summary))?;
+            if cli.timings {
+                report = report.with_timings(&summary, total);
+            }
+            if let Some(sbom) = &sbom {
+                report.sbom = Some(serde_json::to_value(sbom)?);
+            }
+            if let Some(path) = &cli.summary_file {
+                let path = plan.root.join(path);
+                report.write(&path).with_context(|| format!("failed to write the report {}", path.display()))?;
+            }
+            if cli.json {
+                println!("{}", report.to_json()?);
+            } else if cli.quiet {
+                println!("{}", std::path::absolute(&plan.root)?.display());
+            } else {
+                if !skipped.is_empty() {
+                    println!(
+                        "  {} {}",
+                        "offline".cyan(),
+                        format!("skipped: {}", skipped.join(", ")).dimmed()
+                    );
+                }
+                let venv = match cli.skip_uv {
+                    true => "; no .venv created (--skip-uv)",
+                    false => "",
+                };
+                println!(
+                    "{} {}: {}{venv}.",
+                    "OK".green().bold(),
+                    plan.strings.get("cli_project_created"),
+                    summary.line()
+                );
+                if let Some(sbom) = &sbom {
+                    print_sbom(sbom);
+                }
+                summary::print_tree(&plan.root, &summary::tree(&plan, !cli.no_manifest)?);
+                summary::print_next_steps(&summary::next_steps(&plan, &cwd), &plan.strings);
+            }
+            if cli.timings && !cli.json {
+                print_timings(&summary.timings, summary.overlapped, summary.parallel_saving, total);
+            }
+            // The project is there either way; an editor that won't start is not a failed create.
+            if cli.open && !cli.json {
+                if let Err(e) = open::open_root(&plan.root, cli.editor.as_deref(), config.editor.as_deref()) {
+                    eprintln!("{} could not open an editor: {e:#}", "WARN".yellow().bold());
+                }
+            }
+        }
+    }
+
+    if cli.clean_project {
+        let opts = CleanOptions {
+            keep_venv: cli.keep_venv,
+            extra_patterns: clean_extra,
+            only_extra: cli.clean_default_off,
+        };
+        record("clean", &root);
+        if cli.json {
+            let report = match cli.dry_run {
+                true => pyproj::plan_clean(&root, &opts)?,
+                false => util::interruptible(|| pyproj::clean(&root, &opts))?,
+            };
+            recorded(clean_outcome(&report, cli.dry_run));
+            if !cli.dry_run {
+                recorded_freed(report.freed());
+            }
+            println!("{:#}", clean_json(&root, &report, cli.dry_run));
+        } else {
+            println!("{} {}", ">>".cyan().bold(), "Clean project caches".bold());
+            print_root(&root, discover);
+            clean_preamble(&root)?;
+            if cli.dry_run {
+                print_clean_plan(&pyproj::plan_clean(&root, &opts)?);
+                recorded("dry run");
+                println!("{} {}", "OK".green().bold(), "Dry run: nothing was removed.");
+            } else {
+                let report = util::interruptible(|| pyproj::clean(&root, &opts))?;
+                recorded(clean_outcome(&report, false));
+                recorded_freed(report.freed());
+                print_clean_report(&report);
+                util::check_interrupt()?;
+                println!(
+                    "{} Removed {} path(s), freed {}.",
+                    "OK".green().bold(),
+                    report.removed().count(),
+                    human_bytes(report.freed())
+                );
+            }
+        }
+    }
+
+    if cli.delete_project {
+        record("delete", &root);
+        println!("{} {}", ">>".cyan().bold(), "Delete project (NUKE)".bold());
+        print_root(&root, discover);
+        let opts = DeleteOptions {
+            allow_unsafe: cli.i_know_what_im_doing,
+            force_unmanaged: cli.force_delete_unmanaged,
+            trash: cli.trash,
+        };
+        recorded(confirm_and_delete(&root, &opts, cli.yes)?);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print `doctor`-style checks (or their JSON); exit 1 when any failed.
+fn report_checks(checks: &[doctor::Check], json: bool) -> Result<ExitCode> {
+    let failed = checks.iter().filter(|c| c.status == doctor::Status::Fail).count();
+    if json {
+        println!("{}", serde_json::to_string_pretty(checks)?);
+    } else {
+        doctor::print_report(checks);
+    }
+    if failed > 0 {
+        if !json {
+            println!("{} {failed} check(s) failed.", "FAIL".red().bold());
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+    if !json {
+        println!("{} No problems found.", "OK".green().bold());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parse a `--var KEY=VALUE` argument.
+fn parse_var(s: &str) -> std::result::Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))?;
+    let key = key.trim();
+    let valid = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("invalid variable name `{key}` (use letters, digits, and _)"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn parse_clean_pattern(s: &str) -> std::result::Result<String, String> {
+    pyproj::cleanup::check_pattern(s).map(|()| s.to_string())
+}
+
+/// Merge config `[vars]` with `--var` flags (flags win).
+fn template_vars(
+    from_config: std::collections::BTreeMap<String, toml::Value>,
+    from_cli: Vec<(String, String)>,
+) -> pyproj::templates::Vars {
+    use minijinja::value::{Serde, Value};
+
+    let mut vars = pyproj::templates::Vars::new();
+    for (key, value) in from_config {
+        vars.insert(key, Value::from(Serde(value)));
+    }
+    for (key, value) in from_cli {
+        vars.insert(key, Value::from(value));
+    }
+    vars
+}
+
+fn print_help() {
+    use owo_colors::OwoColorize as _;
+
+    // Keep the ASCII banner exactly as-is
+    print!("{BANNER}");
+
+    let cmd = "py-proj";
+
+    // USAGE
+    println!("{}", "USAGE".bold().underline());
+    println!("  {}", format!("{cmd} [FLAGS] [OPTIONS]").italic());
+    println!();
+
+    // EXAMPLE
+    println!("{}", "EXAMPLE".bold().underline());
+    println!(
+        "  {}",
+        format!("{cmd} --create_project --project myproj --python 3.13.1").cyan()
+    );
+    println!();
+
+    // FLAGS
+    println!("{}", "FLAGS".bold());
+    println!(
+        "  {}  {}",
+        "🆕  --create_project".green().bold(),
+        "Create a new project in the target directory.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧹  --clean_project".yellow().bold(),
+        "Remove caches: .venv, __pycache__, .pytest_cache, .ruff_cache, etc.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🛟  --keep-venv".bold(),
+        "With --clean_project, leave .venv in place.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗑️  --clean-extra <GLOB>".bold(),
+        "With --clean_project, also remove matches (e.g. 'outputs/', '**/*.log'); repeatable.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🎯  --clean-default-off".bold(),
+        "Remove only the --clean-extra / [clean] extra_patterns matches.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗂️  --all <DIR>".bold(),
+        "With --clean_project, clean every project under DIR (--max-depth N, --exclude-dir GLOB).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧾  --json".bold(),
+        "With --create_project or --clean_project, print a JSON report instead.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "💣  --delete_project".red().bold(),
+        "Delete the entire project directory (type its name or pass --yes; refuses ~, /, non-py-proj dirs).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧩  --workspace-member".bold(),
+        "Scaffold a uv workspace member (add --init-workspace to create the root).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "💪  --force / --backup".bold(),
+        "Replace existing files that differ (--backup keeps <name>.bak).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔍  --force-interactive".bold(),
+        "Show each changed file's diff and ask: overwrite, skip, or back up.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📄  --pyproject-replace".bold(),
+        "With --force, overwrite pyproject.toml instead of merging py-proj's tables into it.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🩹  --keep-on-failure".bold(),
+        "Keep a half-created project instead of rolling it back.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🚫  --skip-uv".bold(),
+        "Write the files only; no Python install or .venv.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📥  --bootstrap-uv".bold(),
+        "Install uv with the official installer if it is missing.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "✈️  --offline".bold(),
+        "No network: needs an installed Python; uv runs with --offline.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🐍  --uv-python-preference <PREF>".bold(),
+        "only-managed, managed, system, or only-system, for every uv step.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔧  --uv-bin <PATH>".bold(),
+        "The uv to run (also $PY_PROJ_UV, $PY_PROJ_UV_BIN, $UV); checked with --version first.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📝  --no-manifest".bold(),
+        "Skip writing the .pyproj.toml manifest.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⏱️  --timings".bold(),
+        "After --create_project, show how long each phase took (slowest first).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🖊️  --open".bold(),
+        "After --create_project, open the project in an editor (VS Code by default).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🖊️  --editor <COMMAND>".bold(),
+        "Editor for --open and `open`; else $PY_PROJ_EDITOR, config `editor`, code, $VISUAL, $EDITOR.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧾  --sbom [cyclonedx|spdx]".bold(),
+        "After --create_project's install step, write sbom.json (see `sbom`). Not with --skip-uv.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "👀  --dry-run".bold(),
+        "Show what --create_project would write, or --clean_project remove (with sizes).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📝  --emit-plan <FILE>".bold(),
+        "With --create_project, write the planned actions as JSON instead; see apply-plan.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧾  --summary-file [PATH]".bold(),
+        "With --create_project, also write the --json report into the project (default: .pyproj-report.json), even on failure.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🙈  --summary-file-gitignore".bold(),
+        "Add the --summary-file report to .gitignore.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗑️  --trash".bold(),
+        "With --delete_project, move the project to the trash instead.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔓  --force-delete-unmanaged".bold(),
+        "With --delete_project, allow a project without py-proj's manifest or signature files.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "✅  -y, --yes".green().bold(),
+        "Auto-confirm dangerous actions (delete prompts for the name in a terminal).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🛫  --preflight-ignore <CHECK>".bold(),
+        "Create even though this preflight check failed (uv, python, network, disk, target, ...).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔎  -v, --verbose".bold(),
+        "Show where each file came from and stream uv output.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🤫  -q, --quiet".bold(),
+        "Hide progress output (uv steps, per-file lines).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "❓  -h, --help".bold(),
+        "Show this help.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏷️  -V, --version".bold(),
+        "Show version.".dimmed()
+    );
+    println!();
+
+    // COMMANDS
+    println!("{}", "COMMANDS".bold());
+    println!(
+        "  {}  {}",
+        "📤  templates export <DIR> [--force]".bold(),
+        "Dump the built-in templates for use with --templates-dir.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📄  templates list | show <PATH> [--raw]".bold(),
+        "List or print the files a scaffold with these options would write.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⬆️  upgrade [PATH] [--force] [--dry-run]".bold(),
+        "Refresh generated config files; edited files need confirmation.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📝  apply-plan <FILE> [--root DIR]".bold(),
+        "Carry out a plan from --emit-plan, refusing if its targets changed since.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧱  add-module <MOD>... [--path PATH]".bold(),
+        "Add src modules with a logger and a placeholder test.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧲  adopt [PATH] [--force]".bold(),
+        "Add config files to an existing project; merges pyproject.toml.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔍  diff [PATH]".bold(),
+        "Show drift from the current templates; exits 1 if anything differs.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔏  verify [PATH] [--update-manifest]".bold(),
+        "Check generated files against the manifest's hashes; exits 1 on drift.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📋  list [DIR] [--max-depth N] [--stale DAYS] [--json]".bold(),
+        "Find py-proj projects with their Python, .venv size, and age.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "ℹ️  info [PATH] [--json]".bold(),
+        "One project's name, version, Python pin, venv, components, and disk usage.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏷️  rename-project --from OLD --to NEW [PATH]".bold(),
+        "Rename everywhere py-proj wrote the name (--dry-run, --force).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⏪  undo [PATH] [--dry-run] [--force-modified]".bold(),
+        "Remove what the last create/adopt wrote; edited files and earlier content stay.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🩹  repair [PATH] [--dry-run] [--skip-uv]".bold(),
+        "Recreate missing generated files, dirs, and the venv; edited files stay.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗄️  archive [PATH] [-o FILE] [--format tar.gz|zip] [--with-git] [--then-delete]".bold(),
+        "Pack the project without its venv, caches, and .git; optionally delete it after.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔖  bump patch|minor|major [PATH] [--git-tag] [--dry-run]".bold(),
+        "Raise [project] version and date the Unreleased CHANGELOG.md entries.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📋  export-requirements [PATH] [--group dev] [-o FILE]".bold(),
+        "Write requirements.txt via uv export (uv.lock) or uv pip compile.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🐍  export-conda [PATH] [-o FILE]".bold(),
+        "Write a conda environment.yml: Python pin, conda-forge packages, the rest under pip.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧾  sbom [PATH] [--format cyclonedx|spdx] [-o FILE]".bold(),
+        "Write a CycloneDX or SPDX SBOM of the packages installed in the project's venv.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🐚  env [PATH] [--shell bash|zsh|fish|powershell]".bold(),
+        "Print venv activation (and the flat layout's PYTHONPATH) for eval \"$(py-proj env)\".".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "▶️  run [-C PATH] [-y] -- COMMAND...".bold(),
+        "Run COMMAND with uv run in the project root; exits with its status.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏫  batch SPEC [--parallel N] [--fail-fast] [--dry-run]".bold(),
+        "Scaffold every [[project]] in a TOML spec; ends with a per-project table.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📜  history [PATH] [-n N] [--json]".bold(),
+        "Show recent creates, cleans, deletes, and upgrades, and how they ended.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📊  stats [PATH] [--since DATE] [--json] [--clear]".bold(),
+        "Sum up the history: runs, flags, uv step times, failures, disk freed; all local.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🩺  doctor [PATH] [--json]".bold(),
+        "Check uv, Python, .venv, and project config; exits 1 on failure.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧭  check-vscode [PATH] [--json]".bold(),
+        "Check .vscode's interpreter, tools, launch targets, and extensions; exits 1 on failure.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⚙️  config show [--origin] [--json]".bold(),
+        "Print the effective settings; --origin names the layer each one came from.".dimmed()
+    );
+    println!();
+
+    // OPTIONS
+    println!("{}", "OPTIONS".bold());
+    println!(
+        "  {}  {}",
+        "📦  -p, --project <NAME>".bold(),
+        "Project name (default: <cwd>_proj).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📦  --force-name".bold(),
+        "Allow a project name that shadows a standard-library module (warned about instead).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🐍  -P, --python <VER>".bold(),
+        "Python for uv: 3.12.4, 3.12 (newest patch), or 3.13t (default: auto-detected).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📝  --description <TEXT>".bold(),
+        "One-line summary for pyproject.toml and README.md.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "👤  --author <NAME> / --email <ADDR>".bold(),
+        "Author for pyproject.toml and CITATION.cff.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔗  --repo-url <URL>".bold(),
+        "Repository URL for [project.urls] and CITATION.cff.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏢  --org / --license / --homepage / --maintainer".bold(),
+        "Organization metadata; defaults come from the config's [org] table.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📚  --citation".bold(),
+        "Also write a CITATION.cff (cff-version 1.2.0).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⌨️  --entry-point <NAME>".bold(),
+        "Add a console script NAME backed by a generated cli.py (typer with --template cli).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏢  --namespace <NS>".bold(),
+        "Package in src/<NS>/<project>/ (PEP 420), imported as NS.<project>, named NS-<project>.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📁  --outdir <PATH>".bold(),
+        "Output directory (default: $PWD/<project>); `~` and $VARS are expanded.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📁  --create-parents".bold(),
+        "Create the --outdir's missing parent directories instead of refusing.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏠  --here".bold(),
+        "Scaffold into the current directory (may already hold .git, README.md, LICENSE).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📍  [PATH]".bold(),
+        "Project to clean/delete (default: the one containing the current directory).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🎨  --template <PROFILE>".bold(),
+        "Kind of project: default, cli, datasci, or webapp.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🙈  --gitignore-extra <PATTERN>".bold(),
+        "Append a .gitignore pattern under `# py-proj: user entries` (repeatable).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔑  --gen-secret <KEY>".bold(),
+        "Write a random secret into .env as KEY (repeatable; a webapp gets SECRET_KEY).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧱  --modules <A,B,C>".bold(),
+        "Generate modules (dotted: subpackages) with a placeholder test each.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧪  --pytest-addopts <OPTS>".bold(),
+        "addopts for [tool.pytest.ini_options].".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏷️  --pytest-marker <NAME:DESC>".bold(),
+        "Register a pytest marker (repeatable).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🎲  --testing-extras hypothesis".bold(),
+        "Property tests: hypothesis dev dependency, CI/dev profiles, tests/test_properties.py.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔀  --async".bold(),
+        "async def main() run by asyncio.run; pytest-asyncio and an async test of main.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⏲️  --benchmarks".bold(),
+        "pytest-benchmark suite in benchmarks/, run by `make bench` but not `make test`.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗄️  --data-layout".bold(),
+        "data/raw, interim, processed and models/ (git-ignored), src/paths.py; on for datasci.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗄️  --dvc".bold(),
+        "Stub DVC pipeline: dvc.yaml and .dvcignore (with the data layout).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧮  --python-matrix <VERS>".bold(),
+        "Supported Pythons (e.g. 3.11,3.12,3.13): requires-python, classifiers, noxfile.py.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏃  --matrix-runner <RUNNER>".bold(),
+        "nox (default) or tox for the --python-matrix sessions.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📦  --dep-style extras|groups".bold(),
+        "Dev tools as a `dev` extra (default) or a PEP 735 dependency group.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🤖  --dep-updates <BOT>".bold(),
+        "dependabot (.github/dependabot.yml) or renovate (renovate.json).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🚀  --publish pypi".bold(),
+        "Release workflow on version tags: uv build, PyPI trusted publishing, GitHub release.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🪝  --git-hooks native".bold(),
+        ".githooks/ pre-commit (ruff) and pre-push (pytest), enabled via core.hooksPath.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🛡️  --security bandit|ruff-s".bold(),
+        "Security scanning: bandit (make security) or ruff's S rules in `ruff check`.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🏷️  --status alpha|beta|stable".bold(),
+        "Development Status classifier in pyproject.toml.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📝  --ide vscode|neovim|none".bold(),
+        "Editor files: .vscode/ (default), .nvim.lua for Neovim's 'exrc', or none (--no-vscode).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📌  --toolchain-file mise|asdf|python-version".bold(),
+        "Pin the Python in .mise.toml, .tool-versions, or .python-version (default: no pin file).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "↩️  --line-endings lf|crlf|native".bold(),
+        "Line endings of the generated files (default lf); .gitattributes keeps them.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧰  --tools-global".bold(),
+        "ruff and pyright as `uv tool install`ed tools, not dev dependencies.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📜  --emit-bootstrap".bold(),
+        "scripts/bootstrap.sh replaying the uv commands and hooks, for setups without py-proj.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "©️  --license-header <SPDX>".bold(),
+        "Start each generated .py file with an SPDX header and copyright line.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🪶  --minimal [--with <COMPONENT>]".bold(),
+        "Only pyproject.toml, src/, a smoke test, .gitignore, README; --with vscode keeps one.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📓  --jupytext [--jupytext-ignore-ipynb]".bold(),
+        "Pair notebooks with .py files (optionally commit only the .py side).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🗂️  --code-workspace [PATH]".bold(),
+        "Write <project>.code-workspace next to the project for multi-folder setups.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🌱  --venv-seed / --venv-prompt <NAME> / --uv-arg <ARG>".bold(),
+        "Shape `uv venv`: seed pip, name the prompt, pass extra arguments.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📁  --venv-dir <NAME>".bold(),
+        "Name the venv directory (default .venv); every generated file follows.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📓  --notebooks-dir <NAME>".bold(),
+        "Name the notebooks directory (default Notebooks); every generated file follows.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "♻️  --recreate-venv".bold(),
+        "Replace an existing .venv built with a different Python.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "✂️  --exclude <GLOB>".bold(),
+        "Leave out generated files, e.g. .envrc or \"src/app_logging/*\" (repeatable).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔐  --dir-mode / --file-mode <OCTAL>".bold(),
+        "Permissions for the root, its directories, and the files, e.g. 2775 and 664.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🌐  --lang <CODE>".bold(),
+        "Write docstrings, comments, and README prose in en (default) or ja.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧩  --templates-dir <PATH>".bold(),
+        "Override built-in templates with files from PATH.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🌐  --template-repo <URL[#REF]>".bold(),
+        "Use a git-hosted template pack (cached; --refresh-templates).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔣  --var <KEY=VALUE>".bold(),
+        "Extra template variable (repeatable).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🪝  --post-hook <COMMAND>".bold(),
+        "Run COMMAND in the new project (repeatable).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⏱️  --command-timeout <SECS>".bold(),
+        "Kill uv/git commands running longer than SECS (default: 600; 0: never).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔒  --lock-timeout <SECS>".bold(),
+        "Wait for another run on the same project (default: 30; 0: fail at once).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🧊  --deterministic".bold(),
+        "Stamp generated files with $SOURCE_DATE_EPOCH (default: 1970-01-01), not today.".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "⚙️  --config <PATH>".bold(),
+        "Config file (default: ~/.config/py-proj/config.toml).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "🔔  --check-update".bold(),
+        "Say whether a newer py-proj release is out (never installs anything).".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        "📜  --no-history".bold(),
+        "Do not record this run in ~/.local/state/py-proj/history.log.".dimmed()
+    );
+    println!();
+
+    // TIP
+    println!("{}", "💡  TIP".bold());
+    println!("  {}", "After creating the project, run".dimmed());
+    println!("    {}", "`uv pip install -e \".[dev]\"`".bold());
+    println!("  {}", "then".dimmed());
+    println!("    {}", "`uv run python -m src.main`".bold());
+}
+/// Print what a real run would write and execute, without touching the disk.
+/// What happens to existing files that differ: `--force` overwrites, `--backup` keeps a
+/// `.bak`, and otherwise they are skipped.
+fn write_policy(force: bool, backup: bool) -> WritePolicy {
+    match (force, backup) {
+        (true, _) => WritePolicy::Overwrite,
+        (_, true) => WritePolicy::Backup,
+        _ => WritePolicy::Skip,
+    }
+}
+
+fn print_dry_run(plan: &ScaffoldPlan, manifest: bool) -> Result<()> {
+    plan.check_venv()?;
+    let actions = plan.plan(manifest)?;
+    println!("  {}", "Would write:".bold());
+    for action in &actions {
+        match action {
+            PlannedAction::WriteFile { path, origin, .. } => {
+                println!("    {} {}", path.display(), format!("({})", origin.label()).dimmed())
+            }
+            PlannedAction::WriteCodeWorkspace { path, .. } => println!("    {}", path.display()),
+            _ => {}
+        }
+    }
+    println!("  {}", "Would run:".bold());
+    for action in &actions {
+        match action {
+            PlannedAction::RunCommand { program, args, cwd, .. } => {
+                let cmd = quote_argv(program, args);
+                match *cwd == plan.root {
+                    true => println!("    {cmd}"),
+                    false => println!("    {cmd} {}", format!("(in {})", cwd.display()).dimmed()),
+                }
+            }
+            PlannedAction::RemoveDir { path } => {
+                println!("    {} {}", "remove:".dimmed(), plan.root.join(path).display())
+            }
+            PlannedAction::RegisterMember { workspace, member } => println!(
+                "    {} {} {}",
+                "update:".dimmed(),
+                workspace.join("pyproject.toml").display(),
+                format!("(add member {member})").dimmed()
+            ),
+            _ => {}
+        }
+    }
+    for hook in &plan.post_hooks {
+        println!("    {} {}", "hook:".dimmed(), hook);
+    }
+    Ok(())
+}
+
+/// `<cwd_basename>_proj`.
+fn default_project(cwd: &Path) -> String {
+    format!("{}_proj", cwd.file_name().unwrap_or_default().to_string_lossy())
+}
+
+/// Show what `--force` is about to replace, as a diff per changed file. With
+/// `--force-interactive`, ask for each one whether to overwrite, skip, or back it up.
+fn review_overwrites(
+    plan: &ScaffoldPlan,
+    opts: &CreateOptions,
+    ask: bool,
+    show: bool,
+) -> Result<std::collections::BTreeMap<PathBuf, WritePolicy>> {
+    let mut choices = std::collections::BTreeMap::new();
+    let conflicts = plan.conflicts(&plan.plan(opts.manifest)?, opts.replace_pyproject)?;
+    if conflicts.is_empty() {
+        return Ok(choices);
+    }
+    if ask && !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        return Err(pyproj::Error::NeedsConfirmation {
+            action: format!("decide about {} changed file(s)", conflicts.len()),
+            flag: "--force or --backup",
+        }
+        .into());
+    }
+    if show || ask {
+        println!("  {} {}", "Replacing".yellow().bold(), format!("{} changed file(s):", conflicts.len()).dimmed());
+    }
+    for (path, old, new) in conflicts {
+        let rel = path.to_string_lossy().replace('\\', "/");
+        if show || ask {
+            commands::diff::print_change(&rel, &old, &new);
+        }
+        if ask {
+            let picked = dialoguer::Select::new()
+                .with_prompt(rel.as_str())
+                .items(["overwrite", "skip", "back up and overwrite"])
+                .default(1)
+                .interact()?;
+            let policy = match picked {
+                0 => WritePolicy::Overwrite,
+                2 => WritePolicy::Backup,
+                _ => WritePolicy::Skip,
+            };
+            choices.insert(path, policy);
+        }
+    }
+    Ok(choices)
+}
+
+/// `--here` names the project after the directory itself (minus a `_proj` suffix).
+fn here_project(cwd: &Path) -> String {
+    let name = cwd.file_name().unwrap_or_default().to_string_lossy();
+    name.strip_suffix("_proj").unwrap_or(&name).to_string()
+}
+
+/// Files a freshly created GitHub repository starts with; `--here` scaffolds around them.
+const HERE_ALLOWED: &[&str] = &[".git", "README.md", "LICENSE"];
+
+/// `--here` writes into a directory that already exists, so it must hold nothing but
+/// [`HERE_ALLOWED`] unless `--force` or `--backup` says otherwise.
+fn check_here_empty(dir: &Path) -> Result<()> {
+    let mut others: Vec<String> = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| !HERE_ALLOWED.contains(&name.as_str()))
+        .collect();
+    if others.is_empty() {
+        return Ok(());
+    }
+    others.sort();
+    let shown = match others.len() {
+        n if n > 3 => format!("{}, … ({n} entries)", others[..3].join(", ")),
+        _ => others.join(", "),
+    };
+    Err(pyproj::Error::TargetExists {
+        path: dir.to_path_buf(),
+        hint: format!(
+            "--here needs an empty directory (besides .git, README.md, LICENSE) but found {shown}; \
+             use --force or --backup to scaffold into it anyway"
+        ),
+    }
+    .into())
+}
+
+/// What the command line sets of the config's settings, for `config show`.
+fn config_flags(cli: &Cli) -> std::collections::BTreeMap<String, toml::Value> {
+    let strings = |list: &[String]| toml::Value::Array(list.iter().cloned().map(toml::Value::String).collect());
+    let mut flags = std::collections::BTreeMap::new();
+    let mut set = |key: &str, value: Option<toml::Value>| {
+        if let Some(value) = value {
+            flags.insert(key.to_string(), value);
+        }
+    };
+    let string = |value: &Option<String>| value.clone().map(toml::Value::String);
+    let path = |value: &Option<PathBuf>| value.as_ref().map(|p| toml::Value::String(p.display().to_string()));
+    let int = |value: Option<u64>| value.map(|n| toml::Value::Integer(n as i64));
+    set("python", cli.py_full.as_ref().map(|v| toml::Value::String(v.to_string())));
+    set("venv_dir", string(&cli.venv_dir));
+    set("notebooks_dir", string(&cli.notebooks_dir));
+    set("minimal", cli.minimal.then_some(toml::Value::Boolean(true)));
+    set("with", (!cli.with.is_empty()).then(|| strings(&cli.with)));
+    set("exclude", (!cli.exclude.is_empty()).then(|| strings(&cli.exclude)));
+    set("templates_dir", path(&cli.templates_dir));
+    set("gitignore_extra", (!cli.gitignore_extra.is_empty()).then(|| strings(&cli.gitignore_extra)));
+    set("post_hooks", (!cli.post_hooks.is_empty()).then(|| strings(&cli.post_hooks)));
+    set("command_timeout", int(cli.command_timeout));
+    set("lock_timeout", int(cli.lock_timeout));
+    set("clean.extra_patterns", (!cli.clean_extra.is_empty()).then(|| strings(&cli.clean_extra)));
+    set("history", cli.no_history.then_some(toml::Value::Boolean(false)));
+    set("editor", string(&cli.editor));
+    set("org.name", string(&cli.metadata.org));
+    set("org.license", string(&cli.metadata.license));
+    set("org.repo_url", string(&cli.metadata.repo_url));
+    set("org.homepage", string(&cli.metadata.homepage));
+    if !cli.metadata.maintainers.is_empty() {
+        set("org.maintainers", toml::Value::try_from(&cli.metadata.maintainers).ok());
+    }
+    for (key, value) in &cli.vars {
+        set(&format!("vars.{key}"), Some(toml::Value::String(value.clone())));
+    }
+    flags
+}
+
+/// The plan `--create_project` would build from these options, for `templates list/show`.
+fn preview_plan(cli: &Cli) -> Result<ScaffoldPlan> {
+    let config = Config::load(cli.config.as_deref())?;
+    let cwd = env::current_dir()?;
+    let project = cli.project.clone().unwrap_or_else(|| default_project(&cwd));
+    let (project, _) = util::sanitize_project_name(&project)?;
+    let mut builder = ScaffoldPlan::builder(&project)
+        .root(match &cli.outdir {
+            Some(dir) => util::resolve_new_dir(&util::expand_path(dir)?, true)?,
+            None => cwd.join(&project),
+        })
+        .vars(template_vars(config.vars, cli.vars.clone()))
+        .offline(cli.offline);
+    builder = with_metadata(builder.org_defaults(&config.org), &cli.metadata);
+    for pattern in config.gitignore_extra.iter().chain(&cli.gitignore_extra) {
+        builder = builder.gitignore_extra(pattern);
+    }
+    for key in &cli.gen_secrets {
+        builder = builder.gen_secret(key);
+    }
+    for module in &cli.modules {
+        builder = builder.module(module.trim());
+    }
+    if let Some(addopts) = &cli.pytest_addopts {
+        builder = builder.pytest_addopts(addopts);
+    }
+    for marker in &cli.pytest_markers {
+        builder = builder.pytest_marker(marker);
+    }
+    for &extra in &cli.testing_extras {
+        builder = builder.testing_extra(extra);
+    }
+    builder = builder.async_main(cli.async_main).benchmarks(cli.benchmarks);
+    builder = builder.data_layout(cli.data_layout).dvc(cli.dvc);
+    for version in &cli.python_matrix {
+        builder = builder.matrix_python(version.to_string());
+    }
+    if let Some(runner) = cli.matrix_runner {
+        builder = builder.matrix_runner(runner);
+    }
+    if let Some(style) = cli.dep_style {
+        builder = builder.dep_style(style);
+    }
+    if let Some(bot) = cli.dep_updates {
+        builder = builder.dep_updates(bot);
+    }
+    if let Some(target) = cli.publish {
+        builder = builder.publish(target);
+    }
+    if let Some(hooks) = cli.git_hooks {
+        builder = builder.git_hooks(hooks);
+    }
+    if let Some(scanner) = cli.security {
+        builder = builder.security(scanner);
+    }
+    if let Some(status) = cli.status {
+        builder = builder.status(status);
+    }
+    if let Some(ide) = cli.ide.or(cli.no_vscode.then_some(Ide::None)) {
+        builder = builder.ide(ide);
+    }
+    if let Some(toolchain) = cli.toolchain_file {
+        builder = builder.toolchain(toolchain);
+    }
+    if let Some(endings) = cli.line_endings {
+        builder = builder.line_endings(endings);
+    }
+    if cli.tools_global {
+        builder = builder.tools_global(true);
+    }
+    if cli.emit_bootstrap {
+        builder = builder.emit_bootstrap(true);
+    }
+    if let Some(spdx) = &cli.license_header {
+        builder = builder.license_header(spdx);
+    }
+    if cli.jupytext {
+        builder = builder.jupytext(true).jupytext_ignore_ipynb(cli.jupytext_ignore_ipynb);
+    }
+    if cli.minimal || config.minimal {
+        builder = builder.minimal(true);
+        for component in config.with.iter().chain(&cli.with) {
+            builder = builder.with(component.trim());
+        }
+    }
+    if let Some(path) = &cli.code_workspace {
+        builder = builder.code_workspace(path.clone());
+    }
+    builder = builder.venv_seed(cli.venv_seed).recreate_venv(cli.recreate_venv);
+    if let Some(prompt) = &cli.venv_prompt {
+        builder = builder.venv_prompt(prompt);
+    }
+    if let Some(dir) = cli.venv_dir.as_ref().or(config.venv_dir.as_ref()) {
+        builder = builder.venv_dir(dir);
+    }
+    if let Some(dir) = cli.notebooks_dir.as_ref().or(config.notebooks_dir.as_ref()) {
+        builder = builder.notebooks_dir(dir);
+    }
+    for arg in &cli.uv_arg {
+        builder = builder.uv_arg(arg);
+    }
+    for pattern in config.exclude.iter().chain(&cli.exclude) {
+        builder = builder.exclude(pattern);
+    }
+    if let Some(mode) = cli.dir_mode {
+        builder = builder.dir_mode(mode);
+    }
+    if let Some(mode) = cli.file_mode {
+        builder = builder.file_mode(mode);
+    }
+    if let Some(lang) = &cli.lang {
+        builder = builder.lang(lang);
+    }
+    if let Some(profile) = cli.profile {
+        builder = builder.profile(profile);
+    }
+    if let Some(python) = cli.py_full.as_ref().map(ToString::to_string).or(config.python.clone()) {
+        builder = builder.python(python);
+    }
+    if let Some(spec) = &cli.template_repo {
+        builder = builder.template_repo(spec);
+    } else if let Some(dir) = cli.templates_dir.clone().or(config.templates_dir) {
+        builder = builder.templates_dir(dir);
+    }
+    builder.build()
+}
+
+/// Project metadata flags shared by `--create_project` and `templates list/show`.
+fn with_metadata(
+    mut builder: pyproj::ScaffoldPlanBuilder,
+    metadata: &MetadataArgs,
+) -> pyproj::ScaffoldPlanBuilder {
+    if let Some(description) = &metadata.description {
+        builder = builder.description(description);
+    }
+    if let Some(author) = &metadata.author {
+        builder = builder.author(author);
+    }
+    if let Some(email) = &metadata.email {
+        builder = builder.email(email);
+    }
+    if let Some(url) = &metadata.repo_url {
+        builder = builder.repo_url(url);
+    }
+    if let Some(url) = &metadata.homepage {
+        builder = builder.homepage(url);
+    }
+    if let Some(name) = &metadata.org {
+        builder = builder.org_name(name);
+    }
+    if let Some(spdx) = &metadata.license {
+        builder = builder.license(spdx);
+    }
+    if !metadata.maintainers.is_empty() {
+        builder = builder.maintainers(metadata.maintainers.clone());
+    }
+    if let Some(name) = &metadata.entry_point {
+        builder = builder.entry_point(name);
+    }
+    if let Some(ns) = &metadata.namespace {
+        builder = builder.namespace(ns);
+    }
+    builder.citation(metadata.citation)
+}
+
+/// The per-path lines of a clean run: cache entries one by one, swept ones as a count,
+/// and anything that could not be removed.
+fn print_clean_report(report: &CleanReport) {
+    for warning in &report.warnings {
+        println!("  {} {}", "WARN".yellow().bold(), warning.yellow());
+    }
+    let mut swept = (0, 0);
+    for entry in report.removed() {
+        let path = entry.path.display().to_string();
+        match entry.kind {
+            CleanKind::CacheDir | CleanKind::ExtraDir => {
+                println!("  {} {}", "rm -rf".yellow(), path.dimmed())
+            }
+            CleanKind::CacheFile | CleanKind::ExtraFile => {
+                println!("  {} {}", "rm".yellow(), path.dimmed())
+            }
+            CleanKind::SweptDir => swept.0 += 1,
+            CleanKind::SweptFile => swept.1 += 1,
+        }
+    }
+    if swept != (0, 0) {
+        println!(
+            "  {} {}",
+            "rm -rf".yellow(),
+            format!(
+                "{} __pycache__/.ipynb_checkpoints dir(s), {} *.pyc/*.pyo file(s)",
+                swept.0, swept.1
+            )
+            .dimmed()
+        );
+    }
+    for entry in report.failed() {
+        println!(
+            "  {} {}",
+            "WARN".yellow().bold(),
+            format!(
+                "could not remove {}: {}",
+                entry.path.display(),
+                entry.error.as_deref().unwrap_or_default()
+            )
+            .yellow()
+        );
+    }
+}
+
+/// `--clean_project --dry-run`: what would be removed, biggest first, with a total.
+fn print_clean_plan(report: &CleanReport) {
+    let mut rows: Vec<&CleanEntry> = report.entries.iter().collect();
+    rows.sort_by(|a, b| b.size.bytes.cmp(&a.size.bytes).then_with(|| a.path.cmp(&b.path)));
+    let mut total = Size::default();
+    for entry in &rows {
+        total += entry.size;
+        let note = match entry.size.unreadable {
+            0 => String::new(),
+            n => format!("  ({n} unreadable, not counted)").yellow().to_string(),
+        };
+        println!(
+            "  {:>10}  {}{note}",
+            human_bytes(entry.size.bytes),
+            entry.path.display().to_string().dimmed()
+        );
+    }
+    println!(
+        "  {:>10}  {}",
+        human_bytes(total.bytes).bold(),
+        format!("total ({} path(s))", rows.len()).bold()
+    );
+}
+
+/// `--timings`: each phase of the scaffold, slowest first, what installing Python
+/// alongside the file writes saved, and the wall-clock total.
+/// The `write` line for an SBOM.
+fn print_sbom(written: &sbom::Written) {
+    println!(
+        "  {} {} {}",
+        "write".green(),
+        written.path.display(),
+        format!("({}, {} package(s))", written.format, written.packages).dimmed()
+    );
+}
+
+fn print_timings(timings: &[(String, Duration)], overlapped: Duration, parallel: Duration, total: Duration) {
+    let mut rows: Vec<&(String, Duration)> = timings.iter().collect();
+    rows.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+    println!("  {}", "Timings:".bold());
+    for (phase, elapsed) in rows {
+        println!("    {:>9}  {phase}", format_duration(*elapsed));
+    }
+    if !overlapped.is_zero() {
+        println!(
+            "    {:>9}  {}",
+            format!("-{}", format_duration(overlapped)).green(),
+            "saved: ran alongside the file writes".dimmed()
+        );
+    }
+    if !parallel.is_zero() {
+        println!(
+            "    {:>9}  {}",
+            format!("-{}", format_duration(parallel)).green(),
+            "saved: files written in parallel".dimmed()
+        );
+    }
+    println!("    {:>9}  {}", format_duration(total).bold(), "total".bold());
+}
+
+/// `1.23s`, or `45.6ms` below a second.
+fn format_duration(d: Duration) -> String {
+    match d.as_secs() {
+        0 => format!("{:.1}ms", d.as_secs_f64() * 1000.0),
+        _ => format!("{:.2}s", d.as_secs_f64()),
+    }
+}
+
+/// Write the `--summary-file` report of a create that failed with `err`. A rolled-back
+/// project has no root to write into, so the report then goes to the current directory.
+/// Failing to write it only warns; `err` is what the run reports.
+fn write_failed_report(reporter: &report::Reporter, plan: &ScaffoldPlan, err: &anyhow::Error, path: &Path, cwd: &Path) {
+    let path = match plan.root.is_dir() {
+        true => plan.root.join(path),
+        false => cwd.join(path.file_name().unwrap_or(report::DEFAULT_REPORT_FILE.as_ref())),
+    };
+    match reporter.failed(plan, err).and_then(|report| report.write(&path)) {
+        Ok(()) => eprintln!("  {} {}", "report".cyan(), path.display()),
+        Err(e) => eprintln!("{} could not write the report {}: {e:#}", "WARN".yellow().bold(), path.display()),
+    }
+}
+
+/// `--clean_project --json`: one object per path, for aggregating across projects.
+/// A clean's history outcome: paths that could not be removed make it a failure.
+fn clean_outcome(report: &CleanReport, dry_run: bool) -> &'static str {
+    match (dry_run, report.failed().count()) {
+        (true, _) => "dry run",
+        (false, 0) => "ok",
+        (false, _) => "failed: some paths could not be removed",
+    }
+}
+
+fn clean_json(root: &Path, report: &CleanReport, dry_run: bool) -> serde_json::Value {
+    let entries: Vec<serde_json::Value> = report
+        .entries
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "path": e.path,
+                "bytes": e.size.bytes,
+                "kind": e.kind,
+                "unreadable": e.size.unreadable,
+                "error": e.error,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "root": root,
+        "dry_run": dry_run,
+        "removed": report.removed().count(),
+        "failed": report.failed().count(),
+        "freed_bytes": report.freed(),
+        "entries": entries,
+        "warnings": report.warnings,
+    })
+}
+
+/// The `Root:` line of clean and delete, saying when it was found from the current directory.
+/// The (already expanded) clean/delete target made absolute, and symlinks resolved, so
+/// the safety checks and the echoed `Root:` are about the real directory. A missing
+/// path is an error rather than nothing to do.
+fn resolve_target(path: &Path) -> Result<PathBuf> {
+    let path = std::path::absolute(path)?;
+    let resolved = match std::fs::canonicalize(&path) {
+        Ok(resolved) => resolved,
+        Err(_) if path.symlink_metadata().is_ok() => {
+            bail!("{} is a dangling symlink; its target does not exist", path.display())
+        }
+        Err(_) => bail!("{} does not exist", path.display()),
+    };
+    if !resolved.is_dir() {
+        bail!("{} is not a directory", resolved.display());
+    }
+    Ok(resolved)
+}
+
+fn print_root(root: &Path, discovered: bool) {
+    let how = match discovered {
+        true => " (found from the current directory)".dimmed().to_string(),
+        false => String::new(),
+    };
+    println!("  {} {}{how}", "Root:".dimmed(), root.display().to_string().blue());
+}
+
+/// What every clean run prints first: the manifest, and where a member's venv lives.
+fn clean_preamble(root: &Path) -> Result<()> {
+    report_manifest(root)?;
+    if let Some(ws) = workspace::find_root(root)? {
+        println!(
+            "  {} {}",
+            "note".cyan(),
+            format!("workspace member; the shared .venv lives in {}", ws.display()).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Say whether `root` carries a py-proj manifest before touching it.
+fn report_manifest(root: &Path) -> Result<()> {
+    match Manifest::load(root)? {
+        Some(m) => println!(
+            "  {} {} {}",
+            "managed".green(),
+            format!("py-proj {} project `{}`", m.tool.version, m.options.project).dimmed(),
+            format!("(created {})", m.tool.created).dimmed()
+        ),
+        None => println!(
+            "  {} {}",
+            "WARN".yellow().bold(),
+            format!("no {MANIFEST_FILE} in {}; not created by py-proj?", root.display()).yellow()
+        ),
+    }
+    Ok(())
+}
+
+/// The `Check:` line of `--delete_project`: why the target does (or does not) look
+/// like a py-proj project.
+fn print_project_check(check: &ProjectCheck) {
+    let mut found: Vec<&str> = Vec::new();
+    if check.manifest {
+        found.push(MANIFEST_FILE);
+    }
+    if check.pyproject {
+        found.push("pyproject.toml");
+    }
+    found.extend(&check.signatures);
+    let found = match found.is_empty() {
+        true => "nothing py-proj writes".to_string(),
+        false => found.join(", "),
+    };
+    let verdict = match check.is_managed() {
+        true => "py-proj project".green().to_string(),
+        false => "not a py-proj project".yellow().bold().to_string(),
+    };
+    println!("  {} {verdict} {}", "Check:".dimmed(), format!("(found {found})").dimmed());
+}
+
+/// Check that `root` may be deleted, ask for its name (unless `yes`), and delete it;
+/// the history outcome: `ok`, `trashed`, or `skipped` when the name did not match.
+#[allow(clippy::print_literal)]
+fn confirm_and_delete(root: &Path, opts: &DeleteOptions, yes: bool) -> Result<&'static str> {
+    print_project_check(&pyproj::cleanup::check_project(root));
+    pyproj::cleanup::check_deletable(root, opts)?;
+    let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+    if !(yes || interactive) {
+        return Err(pyproj::Error::NeedsConfirmation {
+            action: format!("delete {}", root.display()),
+            flag: "--yes",
+        }
+        .into());
+    }
+    if !yes && !confirm_delete(root, !opts.trash)? {
+        println!("  {} {}", "SKIP".dimmed(), "Name did not match; nothing was deleted.");
+        return Ok("skipped");
+    }
+    match delete_project(root, opts, interactive)? {
+        Some(report) if report.trashed_to.is_some() => {
+            println!("{} {}", "OK".green().bold(), "Project moved to the trash.");
+            Ok("trashed")
+        }
+        Some(_) => {
+            println!("{} {}", "OK".green().bold(), "Project deleted.");
+            Ok("ok")
+        }
+        None => {
+            println!("  {} {}", "SKIP".dimmed(), "Name did not match; nothing was deleted.");
+            Ok("skipped")
+        }
+    }
+}
+
+/// Show what `--delete_project` would remove and make the user type the directory's
+/// name to go ahead. Ctrl-C exits through the interrupt handler before anything is removed.
+fn confirm_delete(root: &Path, permanently: bool) -> Result<bool> {
+    let canonical = util::canonicalize_lenient(root);
+    let name = canonical
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| canonical.display().to_string());
+    let size = util::measure(&canonical);
+    println!("  {} {}", "Path:".dimmed(), canonical.display().to_string().red().bold());
+    println!(
+        "  {} {} in {} file(s)",
+        "Size:".dimmed(),
+        human_bytes(size.bytes),
+        size.files
+    );
+    let what = match permanently {
+        true => "delete it permanently",
+        false => "move it to the trash",
+    };
+    let typed: String = dialoguer::Input::new()
+        .with_prompt(format!("Type `{name}` to {what}"))
+        .allow_empty(true)
+        .interact_text()?;
+    Ok(typed.trim() == name)
+}
+
+/// Delete the entire project directory (dangerous), or trash it. When the trash is not
+/// available, permanent deletion needs its own confirmation: the prompt in a terminal,
+/// otherwise a rerun without `--trash`. `None` when the user backed out.
+#[allow(clippy::print_literal)]
+fn delete_project(
+    root: &Path,
+    opts: &DeleteOptions,
+    interactive: bool,
+) -> Result<Option<pyproj::DeleteReport>> {
+    report_manifest(root)?;
+    let report = match util::interruptible(|| pyproj::delete(root, opts)) {
+        Err(e)
+            if matches!(
+                pyproj::Error::find(&e),
+                Some(pyproj::Error::TrashUnavailable { .. })
+            ) =>
+        {
+            println!("  {} {}", "WARN".yellow().bold(), format!("{e}").yellow());
+            if !interactive {
+                return Err(pyproj::Error::NeedsConfirmation {
+                    action: format!("permanently delete {}", root.display()),
+                    flag: "--yes without --trash",
+                }
+                .into());
+            }
+            if !confirm_delete(root, true)? {
+                return Ok(None);
+            }
+            let permanent = DeleteOptions {
+                trash: false,
+                ..opts.clone()
+            };
+            util::interruptible(|| pyproj::delete(root, &permanent))?
+        }
+        other => other?,
+    };
+    for warning in &report.warnings {
+        println!("  {} {}", "WARN".yellow().bold(), warning.yellow());
+    }
+    if !report.failed.is_empty() {
+        for (path, e) in &report.failed {
+            println!(
+                "  {} {}",
+                "WARN".yellow().bold(),
+                format!("could not remove {}: {e}", path.display()).yellow()
+            );
+        }
+        bail!(
+            "{} path(s) under {} could not be removed; everything else was deleted",
+            report.failed.len(),
+            root.display()
+        );
+    }
+    match &report.trashed_to {
+        Some(dest) => println!(
+            "  {} {} → {}",
+            "trash".yellow().bold(),
+            root.display(),
+            dest.display()
+        ),
+        None => println!("  {} {}", "rm -rf".red().bold(), root.display()),
+    }
+    if let Some(ws) = &report.unregistered_from {
+        println!(
+            "  {} {}",
+            "update".green(),
+            format!("workspace members ({})", ws.display()).dimmed()
+        );
+    }
+    Ok(Some(report))
 }
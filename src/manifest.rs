@@ -0,0 +1,398 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::profile::Profile;
+use crate::scaffold::{
+    DepStyle, DepUpdates, GitHooks, Ide, LineEndings, Maintainer, MatrixRunner, Publish, Security, Status, TestingExtra,
+    Toolchain,
+};
+use crate::util::{generated_mode, parse_mode, sha256_hex, write};
+
+/// File name of the manifest at the project root.
+pub const MANIFEST_FILE: &str = ".pyproj.toml";
+
+/// Record of how a project was scaffolded, written to `.pyproj.toml`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "py-proj")]
+    pub tool: ToolInfo,
+    pub options: Options,
+    /// What the create or adopt run found already there; `undo` leaves it.
+    #[serde(default, skip_serializing_if = "Preexisting::is_empty")]
+    pub preexisting: Preexisting,
+    #[serde(default)]
+    pub files: Vec<FileEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolInfo {
+    pub version: String,
+    /// RFC 3339 timestamp of the scaffold.
+    pub created: String,
+    /// RFC 3339 timestamp of the last `upgrade`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgraded: Option<String>,
+}
+
+/// Resolved options the project was created with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Options {
+    pub project: String,
+    pub python: String,
+    /// `--description`, if one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `--author`, `--email`, and `--repo-url`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+    /// `--homepage`, `--org`, `--license`, and `--maintainer` (or the config's `[org]`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintainers: Vec<Maintainer>,
+    /// `--template`; projects from before profiles existed are `default`.
+    #[serde(default)]
+    pub profile: Profile,
+    /// `--gitignore-extra` patterns and the config's `gitignore_extra`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gitignore_extra: Vec<String>,
+    /// `--entry-point`, if one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<String>,
+    /// `--namespace`: the package is `src/<namespace>/<import name>/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Keys of the secrets generated into `.env` (`--gen-secret`, and `SECRET_KEY` for
+    /// a webapp). Their values are never recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gen_secrets: Vec<String>,
+    /// `--modules`, generated along with the scaffold.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<String>,
+    /// `--pytest-addopts` and `--pytest-marker`, as rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pytest_addopts: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pytest_markers: Vec<String>,
+    /// `--testing-extras`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub testing_extras: Vec<TestingExtra>,
+    /// `--async`: `src/main.py` has an `async def main()`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub async_main: bool,
+    /// `--python-matrix` (oldest first) and `--matrix-runner`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub python_matrix: Vec<String>,
+    #[serde(default)]
+    pub matrix_runner: MatrixRunner,
+    /// `--dep-style`, when not `extras`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dep_style: Option<DepStyle>,
+    /// `--dep-updates`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dep_updates: Option<DepUpdates>,
+    /// `--publish`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<Publish>,
+    /// `--git-hooks`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_hooks: Option<GitHooks>,
+    /// `--security`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<Security>,
+    /// `--status`, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// `--ide`, when not `vscode`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ide: Option<Ide>,
+    /// `--toolchain-file`: the Python pin file written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    /// `--line-endings`, when not `lf`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_endings: Option<LineEndings>,
+    /// `--tools-global`: ruff and pyright are uv tools, not dev dependencies.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tools_global: bool,
+    /// `--emit-bootstrap`: the project has a script replaying the scaffold's commands.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub emit_bootstrap: bool,
+    /// `--license-header`: the SPDX identifier generated `.py` files start with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_header: Option<String>,
+    /// `--summary-file`: the report file, relative to the root, the sdist leaves out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_file: Option<String>,
+    /// `--jupytext` and `--jupytext-ignore-ipynb`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub jupytext: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub jupytext_ignore_ipynb: bool,
+    /// `--minimal`, and the `--with` components it keeps.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub minimal: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub with: Vec<String>,
+    /// `--venv-seed`, `--venv-prompt`, and `--uv-arg`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub venv_seed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venv_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uv_args: Vec<String>,
+    /// `--venv-dir`, when it is not `.venv`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venv_dir: Option<String>,
+    /// `--notebooks-dir`, when it is not `Notebooks`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notebooks_dir: Option<String>,
+    /// `--code-workspace`: where the `.code-workspace` file went, relative to the root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_workspace: Option<String>,
+    /// `--exclude` globs; upgrade and diff leave those files out too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// `--dir-mode` and `--file-mode`, as octal (`"2775"`, `"664"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_mode: Option<String>,
+    /// `--lang`, when it is not English.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    pub layout: String,
+    pub components: Vec<String>,
+    /// Directories the components make with no generated file in them (an empty `tests/`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirs: Vec<String>,
+    /// "builtin", "templates-dir", or "template-repo".
+    pub template_profile: String,
+    /// The override directory or repo spec for non-builtin profiles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_source: Option<String>,
+    /// User template variables, so re-renders (upgrade, diff) match the original.
+    #[serde(default)]
+    pub vars: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub sha256: String,
+    /// Octal permission bits (`"755"`) for files generated executable or with
+    /// `--file-mode`, so upgrade and diff can restore them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// The parts of a scaffold that were on disk before py-proj wrote it: empty for a
+/// create into a new directory, and for projects created before this was recorded.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preexisting {
+    /// The project root itself.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub root: bool,
+    /// Directories holding generated files, relative to the root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirs: Vec<String>,
+    /// Generated paths that were files already (merged into, overwritten, or skipped).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Those of `files` the run replaced after saving the original as `<path>.bak`, with
+    /// the hash of what it left, so `undo` can tell whether it may put the original back.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub backups: BTreeMap<String, String>,
+    /// The venv, or there was none for py-proj to make (`--skip-uv`, a workspace member).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub venv: bool,
+}
+
+impl Preexisting {
+    /// What of `files` and `dirs` (relative to `root`), the files' directories, and the
+    /// `venv` the run would make is on disk now; `venv` is `None` when it makes none. The root counts
+    /// unless `created_root`. Paths an `earlier` manifest records as generated by its
+    /// own run are not counted, so re-running a create keeps undoing the first one.
+    pub fn snapshot(
+        root: &Path,
+        files: &[String],
+        dirs: &[String],
+        venv: Option<&Path>,
+        created_root: bool,
+        earlier: Option<&Manifest>,
+    ) -> Preexisting {
+        let generated = |rel: &str| earlier.is_some_and(|m| m.hash_of(rel).is_some() && !m.preexisting.files.iter().any(|f| f == rel));
+        let made = |dir: &str| {
+            earlier.is_some_and(|m| {
+                !m.preexisting.dirs.iter().any(|d| d == dir)
+                    && (m.options.dirs.iter().any(|d| d == dir) || m.files.iter().any(|f| f.path.starts_with(&format!("{dir}/"))))
+            })
+        };
+        let dirs: BTreeSet<&str> = files
+            .iter()
+            .flat_map(|rel| Path::new(rel).ancestors().skip(1))
+            .filter_map(|dir| dir.to_str().filter(|d| !d.is_empty()))
+            .chain(dirs.iter().map(String::as_str))
+            .filter(|dir| root.join(dir).is_dir() && !made(dir))
+            .collect();
+        let files: Vec<String> = files
+            .iter()
+            .filter(|rel| root.join(rel).is_file() && !generated(rel))
+            .cloned()
+            .collect();
+        // A `.bak` already there is not the run's; the rest are settled afterwards.
+        let mut backups = earlier.map(|m| m.preexisting.backups.clone()).unwrap_or_default();
+        for rel in files.iter().filter(|rel| !root.join(format!("{rel}.bak")).exists()) {
+            backups.entry(rel.clone()).or_default();
+        }
+        Preexisting {
+            root: earlier.map_or(!created_root, |m| m.preexisting.root),
+            dirs: dirs.into_iter().map(str::to_string).collect(),
+            files,
+            backups,
+            venv: match earlier {
+                Some(m) => m.preexisting.venv,
+                None => venv.is_none_or(Path::is_dir),
+            },
+        }
+    }
+
+    /// After the run: keep the [`backups`](Preexisting::backups) it made, each with
+    /// the hash of the file it left.
+    pub fn settle(&mut self, root: &Path) {
+        self.backups.retain(|rel, hash| {
+            if hash.is_empty() && root.join(format!("{rel}.bak")).is_file() {
+                *hash = fs::read(root.join(rel)).map(sha256_hex).unwrap_or_default();
+            }
+            !hash.is_empty()
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Preexisting::default()
+    }
+}
+
+impl FileEntry {
+    /// The recorded permission bits, if any (and valid).
+    pub fn mode(&self) -> Option<u32> {
+        self.mode.as_deref().and_then(|m| u32::from_str_radix(m, 8).ok())
+    }
+}
+
+impl Manifest {
+    pub fn new(options: Options, files: Vec<FileEntry>) -> Manifest {
+        Manifest {
+            tool: ToolInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                created: build_time_rfc3339(),
+                upgraded: None,
+            },
+            options,
+            preexisting: Preexisting::default(),
+            files,
+        }
+    }
+
+    /// Read `<root>/.pyproj.toml`; `Ok(None)` when the project has no manifest.
+    pub fn load(root: &Path) -> Result<Option<Manifest>> {
+        let path = root.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let manifest =
+            toml::from_str(&text).with_context(|| format!("invalid manifest {}", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    /// The recorded hash for `path`, if the file was generated.
+    pub fn hash_of(&self, path: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|f| f.path == path)
+            .map(|f| f.sha256.as_str())
+    }
+
+    /// The permission bits recorded for `path`, else the ones py-proj generates it with.
+    pub fn mode_of(&self, path: &str) -> Option<u32> {
+        match self.files.iter().find(|f| f.path == path) {
+            Some(entry) if entry.mode.is_some() => entry.mode(),
+            _ => generated_mode(path, self.options.file_mode.as_deref().and_then(|m| parse_mode(m).ok())),
+        }
+    }
+
+    /// The file contents, header comment included.
+    pub fn to_toml(&self) -> Result<String> {
+        let body = toml::to_string(self).context("failed to serialize manifest")?;
+        Ok(format!("# Generated by py-proj. Commit this file; it is not part of the package.\n{body}"))
+    }
+
+    pub fn write(&self, root: &Path) -> Result<()> {
+        write(root.join(MANIFEST_FILE), self.to_toml()?)
+    }
+}
+
+pub fn now_rfc3339() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// The reproducible-builds variable: seconds since the Unix epoch to stamp output with.
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+/// Stamp generated output with `$SOURCE_DATE_EPOCH`, or the Unix epoch when it is not
+/// set, instead of the current time (`--deterministic`).
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, Ordering::Relaxed);
+}
+
+/// Whether `--deterministic` is on.
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+/// `$SOURCE_DATE_EPOCH`, if set; an error if it is set but not a number of seconds.
+pub fn source_date_epoch() -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    let Some(raw) = std::env::var_os(SOURCE_DATE_EPOCH) else {
+        return Ok(None);
+    };
+    let raw = raw.to_string_lossy();
+    let secs: i64 = raw
+        .trim()
+        .parse()
+        .with_context(|| format!("{SOURCE_DATE_EPOCH}={raw} is not a number of seconds since the Unix epoch"))?;
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(Some)
+        .with_context(|| format!("{SOURCE_DATE_EPOCH}={raw} is out of range"))
+}
+
+/// The time generated files are stamped with (the manifest's `created`, the year in a
+/// license header, ...): `$SOURCE_DATE_EPOCH` when set, else the Unix epoch under
+/// `--deterministic`, else now. Locks and history always use the real time.
+pub fn build_time() -> chrono::DateTime<chrono::Utc> {
+    match source_date_epoch() {
+        Ok(Some(time)) => time,
+        _ if is_deterministic() => chrono::DateTime::UNIX_EPOCH,
+        _ => chrono::Utc::now(),
+    }
+}
+
+/// [`build_time`] as RFC 3339, like [`now_rfc3339`].
+pub fn build_time_rfc3339() -> String {
+    build_time().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
@@ -0,0 +1,373 @@
+//! `--emit-plan` and `apply-plan`: a scaffold planned in one place and applied, exactly
+//! as planned, in another (a reviewed plan, a privileged runner). The plan file is JSON:
+//! the resolved options, and every [`PlannedAction`] with the full contents of the
+//! files it writes, plus what each target looked like when the plan was made.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::Error;
+use crate::events::Event;
+use crate::manifest::{build_time_rfc3339, Manifest};
+use crate::scaffold::{apply, CreateOptions, Origin, PlannedAction, ScaffoldPlan, WriteSummary};
+use crate::util::{base64_decode, base64_encode, is_safe_relative, sha256_hex, uv_bin, WritePolicy};
+
+/// The plan file format this py-proj writes and reads. Bumped whenever a field is
+/// added, removed, or changes meaning.
+pub const SCHEMA: u32 = 1;
+
+/// A planned scaffold, as written by `--emit-plan`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanFile {
+    /// The [`SCHEMA`] it was written with.
+    pub schema: u32,
+    /// The py-proj that made it; only a patch release may apply it.
+    pub py_proj_version: String,
+    /// When it was made (RFC 3339, UTC).
+    pub planned: String,
+    /// The project root it was made for.
+    pub root: PathBuf,
+    /// The resolved options, as the project's manifest records them (without the file
+    /// hashes, which the actions carry).
+    pub manifest: Manifest,
+    /// Whether the actions write `.pyproj.toml`.
+    pub write_manifest: bool,
+    /// What happens to existing files that differ: `skip`, `overwrite`, or `backup`.
+    pub policy: String,
+    pub replace_pyproject: bool,
+    pub recreate_venv: bool,
+    pub offline: bool,
+    /// Run in the root after the actions, as with `--post-hook`.
+    pub post_hooks: Vec<String>,
+    pub actions: Vec<Step>,
+}
+
+/// One [`PlannedAction`] in a plan file. Paths are relative to the project root; a
+/// command's `program` is a name, looked up where the plan is applied.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    CreateDir {
+        path: PathBuf,
+    },
+    WriteFile {
+        path: PathBuf,
+        origin: Origin,
+        contents: Contents,
+        /// SHA-256 of the file already at `path` when the plan was made, if any.
+        existing_sha256: Option<String>,
+    },
+    RunCommand {
+        label: String,
+        program: String,
+        args: Vec<String>,
+        /// `.` for the root; absolute when outside it (a workspace root).
+        cwd: PathBuf,
+        optional: bool,
+        background: bool,
+    },
+    RegisterMember {
+        workspace: PathBuf,
+        member: String,
+    },
+    RemoveDir {
+        path: PathBuf,
+    },
+    WriteCodeWorkspace {
+        path: PathBuf,
+        contents: Contents,
+        existing_sha256: Option<String>,
+    },
+}
+
+impl Step {
+    /// Refuse a path that a hand-edited plan could use to write or remove outside the
+    /// root. The code-workspace file is the one that may sit above it, so its path may
+    /// start with `..`, but must end in a `.code-workspace` file name.
+    fn check_path(&self) -> Result<()> {
+        let path = match self {
+            Step::CreateDir { path } | Step::WriteFile { path, .. } | Step::RemoveDir { path } => path,
+            Step::WriteCodeWorkspace { path, .. } => {
+                let below = path.components().skip_while(|c| *c == Component::ParentDir).collect::<PathBuf>();
+                if is_safe_relative(&below) && path.extension().is_some_and(|e| e == "code-workspace") {
+                    return Ok(());
+                }
+                path
+            }
+            Step::RunCommand { .. } | Step::RegisterMember { .. } => return Ok(()),
+        };
+        if !is_safe_relative(path) {
+            bail!("refusing plan path outside the project: {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// A file's bytes: inline when they are UTF-8, else base64.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Contents {
+    pub encoding: Encoding,
+    pub data: String,
+    /// Of the decoded bytes, so a damaged or hand-edited plan is caught.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encoding {
+    Utf8,
+    Base64,
+}
+
+impl Contents {
+    fn new(bytes: &[u8]) -> Contents {
+        let (encoding, data) = match std::str::from_utf8(bytes) {
+            Ok(text) => (Encoding::Utf8, text.to_string()),
+            Err(_) => (Encoding::Base64, base64_encode(bytes)),
+        };
+        Contents {
+            encoding,
+            data,
+            sha256: sha256_hex(bytes),
+        }
+    }
+
+    fn bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let bytes = match self.encoding {
+            Encoding::Utf8 => self.data.clone().into_bytes(),
+            Encoding::Base64 => base64_decode(&self.data).with_context(|| format!("contents of {}", path.display()))?,
+        };
+        if sha256_hex(&bytes) != self.sha256 {
+            bail!("the planned contents of {} do not match their sha256; the plan file was altered", path.display());
+        }
+        Ok(bytes)
+    }
+}
+
+impl PlanFile {
+    /// The plan file for running `actions`, planned from `plan`, with `opts`. Records the
+    /// current hash of every file they would replace.
+    pub fn new(plan: &ScaffoldPlan, actions: &[PlannedAction], opts: &CreateOptions) -> Result<PlanFile> {
+        if !opts.file_policies.is_empty() {
+            bail!("per-file overwrite choices cannot be put in a plan file");
+        }
+        let existing = |path: &Path| fs::read(plan.root.join(path)).ok().map(sha256_hex);
+        let steps = actions
+            .iter()
+            .map(|action| match action {
+                PlannedAction::CreateDir { path } => Step::CreateDir { path: path.clone() },
+                PlannedAction::WriteFile { path, contents, origin } => Step::WriteFile {
+                    path: path.clone(),
+                    origin: *origin,
+                    contents: Contents::new(contents),
+                    existing_sha256: existing(path),
+                },
+                PlannedAction::RunCommand {
+                    label,
+                    program,
+                    args,
+                    cwd,
+                    optional,
+                    background,
+                } => Step::RunCommand {
+                    label: label.clone(),
+                    program: program_name(program),
+                    args: args.clone(),
+                    cwd: match cwd.strip_prefix(&plan.root) {
+                        Ok(rel) if rel.as_os_str().is_empty() => PathBuf::from("."),
+                        Ok(rel) => rel.to_path_buf(),
+                        Err(_) => cwd.clone(),
+                    },
+                    optional: *optional,
+                    background: *background,
+                },
+                PlannedAction::RegisterMember { workspace, member } => Step::RegisterMember {
+                    workspace: workspace.clone(),
+                    member: member.clone(),
+                },
+                PlannedAction::RemoveDir { path } => Step::RemoveDir { path: path.clone() },
+                PlannedAction::WriteCodeWorkspace { path, contents } => Step::WriteCodeWorkspace {
+                    path: path.clone(),
+                    contents: Contents::new(contents),
+                    existing_sha256: existing(path),
+                },
+            })
+            .collect();
+        let mut manifest = plan.manifest()?;
+        manifest.files.clear();
+        Ok(PlanFile {
+            schema: SCHEMA,
+            py_proj_version: env!("CARGO_PKG_VERSION").to_string(),
+            planned: build_time_rfc3339(),
+            root: std::path::absolute(&plan.root)?,
+            manifest,
+            write_manifest: opts.manifest,
+            policy: policy_name(opts.policy).to_string(),
+            replace_pyproject: opts.replace_pyproject,
+            recreate_venv: plan.recreate_venv,
+            offline: plan.offline,
+            post_hooks: plan.post_hooks.clone(),
+            actions: steps,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// Read the plan file at `path`. Fails on another [`SCHEMA`], or when it was made
+    /// by a py-proj other than a patch release of this one.
+    pub fn load(path: &Path) -> Result<PlanFile> {
+        let text = fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&text).with_context(|| format!("{} is not valid JSON", path.display()))?;
+        match value.get("schema").and_then(serde_json::Value::as_u64) {
+            Some(schema) if schema == u64::from(SCHEMA) => {}
+            Some(schema) => bail!("{} uses plan schema {schema}; this py-proj reads schema {SCHEMA}", path.display()),
+            None => bail!("{} is not a py-proj plan file (no `schema`)", path.display()),
+        }
+        let plan: PlanFile =
+            serde_json::from_value(value).with_context(|| format!("{} is not a valid plan file", path.display()))?;
+        let ours = env!("CARGO_PKG_VERSION");
+        let minor = |v: &str| v.split('.').take(2).collect::<Vec<_>>().join(".");
+        if minor(&plan.py_proj_version) != minor(ours) {
+            bail!(
+                "{} was planned by py-proj {}; this is py-proj {ours}, and only a patch release may apply it \
+                 (plan again with this version)",
+                path.display(),
+                plan.py_proj_version
+            );
+        }
+        Ok(plan)
+    }
+
+    /// Carry out the plan in `root` (default: the root it was made for): refuse if any
+    /// path it touches is outside the root or any file it writes changed since, then
+    /// write, run, and roll back on failure as [`create`](crate::create) does.
+    pub fn apply(self, root: Option<&Path>, on_event: impl Fn(&Event<'_>) + 'static) -> Result<WriteSummary> {
+        for step in &self.actions {
+            step.check_path()?;
+        }
+        let root = root.map_or_else(|| self.root.clone(), Path::to_path_buf);
+        let moved = std::path::absolute(&root)? != self.root;
+        if moved && self.actions.iter().any(|s| matches!(s, Step::RegisterMember { .. })) {
+            bail!(
+                "this plan adds a member to the uv workspace at its root, {}; apply it there (without --root)",
+                self.root.display()
+            );
+        }
+        let mut actions = Vec::with_capacity(self.actions.len());
+        let mut changed = Vec::new();
+        for step in self.actions {
+            actions.push(match step {
+                Step::CreateDir { path } => PlannedAction::CreateDir { path },
+                Step::WriteFile {
+                    path,
+                    origin,
+                    contents,
+                    existing_sha256,
+                } => {
+                    if fs::read(root.join(&path)).ok().map(sha256_hex) != existing_sha256 {
+                        changed.push(path.clone());
+                    }
+                    PlannedAction::WriteFile {
+                        contents: contents.bytes(&path)?,
+                        path,
+                        origin,
+                    }
+                }
+                Step::RunCommand {
+                    label,
+                    program,
+                    args,
+                    cwd,
+                    optional,
+                    background,
+                } => PlannedAction::RunCommand {
+                    label,
+                    program: if program == "uv" { uv_bin() } else { program },
+                    args,
+                    cwd: match cwd == Path::new(".") {
+                        true => root.clone(),
+                        false => root.join(cwd),
+                    },
+                    optional,
+                    background,
+                },
+                Step::RegisterMember { workspace, member } => PlannedAction::RegisterMember { workspace, member },
+                Step::RemoveDir { path } => PlannedAction::RemoveDir { path },
+                Step::WriteCodeWorkspace {
+                    path,
+                    contents,
+                    existing_sha256,
+                } => {
+                    if fs::read(root.join(&path)).ok().map(sha256_hex) != existing_sha256 {
+                        changed.push(path.clone());
+                    }
+                    PlannedAction::WriteCodeWorkspace {
+                        contents: contents.bytes(&path)?,
+                        path,
+                    }
+                }
+            });
+        }
+        if let Some(first) = changed.first() {
+            let more = match changed.len() {
+                1 => String::new(),
+                n => format!(" (and {} more)", n - 1),
+            };
+            return Err(Error::TargetExists {
+                path: root.join(first),
+                hint: format!("it changed since the plan was made{more}; make the plan again"),
+            }
+            .into());
+        }
+
+        // The contents are in the plan, so its templates are not needed (or fetched) again.
+        let mut manifest = self.manifest;
+        let profile = std::mem::replace(&mut manifest.options.template_profile, "builtin".to_string());
+        let source = manifest.options.template_source.take();
+        let mut plan = ScaffoldPlan::from_manifest(&root, &manifest, on_event)?;
+        plan.template_profile = profile;
+        plan.template_source = source;
+        plan.post_hooks = self.post_hooks;
+        plan.recreate_venv = self.recreate_venv;
+        plan.offline = self.offline;
+        plan.skip_uv = !actions
+            .iter()
+            .any(|a| matches!(a, PlannedAction::RunCommand { program, .. } if *program == uv_bin()));
+        let opts = CreateOptions {
+            manifest: self.write_manifest,
+            policy: parse_policy(&self.policy)?,
+            replace_pyproject: self.replace_pyproject,
+            ..CreateOptions::default()
+        };
+        apply(&plan, actions, &opts)
+    }
+}
+
+/// `program` as the plan records it: its file name, so the one where the plan is
+/// applied is used.
+fn program_name(program: &str) -> String {
+    let name = Path::new(program).file_name().unwrap_or_default().to_string_lossy();
+    name.strip_suffix(".exe").unwrap_or(&name).to_string()
+}
+
+fn policy_name(policy: WritePolicy) -> &'static str {
+    match policy {
+        WritePolicy::Overwrite => "overwrite",
+        WritePolicy::Skip => "skip",
+        WritePolicy::Backup => "backup",
+    }
+}
+
+fn parse_policy(name: &str) -> Result<WritePolicy> {
+    match name {
+        "overwrite" => Ok(WritePolicy::Overwrite),
+        "skip" => Ok(WritePolicy::Skip),
+        "backup" => Ok(WritePolicy::Backup),
+        _ => bail!("unknown write policy `{name}` in the plan; expected skip, overwrite, or backup"),
+    }
+}
@@ -0,0 +1,56 @@
+//! Template profiles (`--template`): what kind of project is being scaffolded. The
+//! profile is visible to templates as `{{profile}}` and switches profile-specific
+//! content on and off.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// A general-purpose application.
+    #[default]
+    Default,
+    /// A command-line tool.
+    Cli,
+    /// Data science: notebooks, datasets, and pipelines.
+    Datasci,
+    /// A web application.
+    Webapp,
+}
+
+impl Profile {
+    pub const ALL: &[Profile] = &[Profile::Default, Profile::Cli, Profile::Datasci, Profile::Webapp];
+
+    /// The name used on the command line, in the manifest, and in templates.
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Default => "default",
+            Profile::Cli => "cli",
+            Profile::Datasci => "datasci",
+            Profile::Webapp => "webapp",
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Profile::ALL
+            .iter()
+            .copied()
+            .find(|p| p.name() == s.trim())
+            .ok_or_else(|| {
+                let names: Vec<&str> = Profile::ALL.iter().map(|p| p.name()).collect();
+                format!("unknown template profile `{s}`; expected one of: {}", names.join(", "))
+            })
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,87 @@
+//! Terminal progress display: a spinner for long uv steps, or plain lines when
+//! stdout is not a terminal. `--quiet` turns all of it off.
+
+use owo_colors::OwoColorize;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+/// Set by [`restore_terminal`]: every spinner stops drawing for good.
+static HALTED: AtomicBool = AtomicBool::new(false);
+
+/// Hide progress output for the rest of the run.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Stop every spinner and leave the terminal usable (line cleared, cursor shown, in
+/// case a prompt hid it), for a process that is about to exit on an interrupt.
+pub fn restore_terminal() {
+    HALTED.store(true, Ordering::Relaxed);
+    if io::stdout().is_terminal() {
+        print!("\r\x1b[2K\x1b[?25h");
+        let _ = io::stdout().flush();
+    }
+}
+
+/// A spinner with elapsed time on the current line, redrawn until [`Spinner::finish`].
+pub struct Spinner {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Start spinning next to `message`, or return None when stdout is not a terminal.
+    pub fn start(message: String) -> Option<Spinner> {
+        if !io::stdout().is_terminal() {
+            return None;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+            let started = Instant::now();
+            for frame in FRAMES.iter().cycle() {
+                if flag.load(Ordering::Relaxed) || HALTED.load(Ordering::Relaxed) {
+                    break;
+                }
+                let secs = started.elapsed().as_secs();
+                print!("\r\x1b[2K{} {message} {}", frame.cyan(), format!("{secs}s").dimmed());
+                let _ = io::stdout().flush();
+                thread::sleep(Duration::from_millis(100));
+            }
+            // Leave a clean line for whatever is printed next.
+            print!("\r\x1b[2K");
+            let _ = io::stdout().flush();
+        });
+        Some(Spinner {
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop and erase the spinner.
+    pub fn finish(mut self) {
+        self.stop_thread();
+    }
+
+    fn stop_thread(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
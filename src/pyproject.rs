@@ -0,0 +1,499 @@
+//! Merging a generated pyproject.toml into one that already exists. py-proj owns the
+//! `[tool.*]` tables it generates, the dev extras or dev dependency group, and
+//! `[build-system]` (only when absent); the rest of the user's file, comments included,
+//! is left as it is.
+
+use anyhow::{Context, Result};
+use toml_edit::{Array, DocumentMut, Item, Table, TableLike, Value};
+
+/// The result of [`merge`].
+#[derive(Debug, Default)]
+pub struct Merge {
+    /// The merged document.
+    pub text: String,
+    /// Tables, keys, and dev requirements that were not there before, e.g.
+    /// `[tool.ruff]`, `tool.ruff.fix`, `ruff>=0.6.0`.
+    pub added: Vec<String>,
+    /// Keys of owned tables whose value py-proj replaced.
+    pub replaced: Vec<Replaced>,
+}
+
+/// A key py-proj set to its own value, with the value it had before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replaced {
+    /// Dotted path, e.g. `tool.ruff.line-length`.
+    pub key: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl Merge {
+    pub fn is_noop(&self) -> bool {
+        self.added.is_empty() && self.replaced.is_empty()
+    }
+}
+
+/// Merge `generated` into `existing`: missing `[tool.*]` tables are added and the keys
+/// py-proj generates inside existing ones take its values; the dev extras (or the `dev`
+/// dependency group, whichever the generated file has) gain the missing requirements;
+/// `[project]`, `[project.scripts]`, and `[build-system]` are added only when absent,
+/// except for the script py-proj generates.
+pub fn merge(existing: &str, generated: &str) -> Result<Merge> {
+    let mut doc: DocumentMut = existing.parse().context("invalid TOML in pyproject.toml")?;
+    let gen: DocumentMut = generated.parse().context("generated pyproject.toml is invalid")?;
+    let mut next_pos = max_position(doc.as_table()) + 1;
+    let mut merge = Merge::default();
+    // A requirement the user lists in one style is not added again in the other.
+    let dev_names = |dev: Option<&Item>| -> Vec<String> {
+        let dev = dev.and_then(Item::as_array);
+        dev.into_iter().flatten().filter_map(|v| v.as_str()).map(requirement_name).collect()
+    };
+    let in_extras = dev_names(doc.get("project").and_then(|p| p.get("optional-dependencies")?.get("dev")));
+    let in_group = dev_names(doc.get("dependency-groups").and_then(|g| g.get("dev")));
+
+    match (doc.get_mut("project"), gen.get("project")) {
+        (None, Some(project)) => {
+            let mut project = project.clone();
+            reposition(&mut project, &mut next_pos);
+            doc.insert("project", project);
+            merge.added.push("[project]".to_string());
+        }
+        (Some(project), Some(gen_project)) => {
+            let project = project
+                .as_table_like_mut()
+                .context("`project` in pyproject.toml is not a table")?;
+            let gen_dev = gen_project
+                .get("optional-dependencies")
+                .and_then(|o| o.get("dev"))
+                .and_then(Item::as_array);
+            if let Some(gen_dev) = gen_dev {
+                let dev = DevList { table: "optional-dependencies", label: "dev extras", elsewhere: &in_group };
+                merge_dev(project, dev, gen_dev, &mut next_pos, &mut merge)?;
+            }
+            if let Some(scripts) = gen_project.get("scripts").and_then(Item::as_table) {
+                merge_owned(project, "project", "scripts", scripts, &mut next_pos, &mut merge)?;
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(gen_dev) = gen.get("dependency-groups").and_then(|g| g.get("dev")).and_then(Item::as_array) {
+        let dev = DevList { table: "dependency-groups", label: "dev dependency group", elsewhere: &in_extras };
+        merge_dev(doc.as_table_mut(), dev, gen_dev, &mut next_pos, &mut merge)?;
+    }
+
+    if let (None, Some(build)) = (doc.get("build-system"), gen.get("build-system")) {
+        let mut build = build.clone();
+        reposition(&mut build, &mut next_pos);
+        doc.insert("build-system", build);
+        merge.added.push("[build-system]".to_string());
+    }
+
+    if let Some(tools) = gen.get("tool").and_then(Item::as_table) {
+        if !doc.contains_key("tool") {
+            let mut tool = Table::new();
+            tool.set_implicit(true);
+            doc.insert("tool", Item::Table(tool));
+        }
+        let dest = doc["tool"]
+            .as_table_like_mut()
+            .context("`tool` in pyproject.toml is not a table")?;
+        for (name, item) in tools.iter() {
+            let Some(table) = item.as_table() else {
+                continue;
+            };
+            merge_owned(dest, "tool", name, table, &mut next_pos, &mut merge)?;
+        }
+    }
+
+    merge.text = doc.to_string();
+    Ok(merge)
+}
+
+/// Point `[project]` at other Pythons, `versions` oldest first: `requires-python`
+/// becomes `>=` the oldest and the `Programming Language :: Python :: X.Y` classifiers
+/// list exactly `versions`, where the old ones were. Everything else, the other
+/// classifiers included, is left as it is. A project without `[project]` is unchanged.
+pub fn set_python(existing: &str, versions: &[String]) -> Result<Merge> {
+    const PREFIX: &str = "Programming Language :: Python :: ";
+    let mut doc: DocumentMut = existing.parse().context("invalid TOML in pyproject.toml")?;
+    let mut merge = Merge::default();
+    let (Some(oldest), Some(project)) = (versions.first(), doc.get_mut("project").and_then(Item::as_table_like_mut))
+    else {
+        merge.text = doc.to_string();
+        return Ok(merge);
+    };
+
+    let requires = Value::from(format!(">={oldest}"));
+    match project.get_mut("requires-python").and_then(Item::as_value_mut) {
+        Some(old) if same(old, &requires) => {}
+        Some(old) => {
+            merge.replaced.push(Replaced { key: "project.requires-python".to_string(), old: bare(old), new: bare(&requires) });
+            let decor = old.decor().clone();
+            *old = requires;
+            *old.decor_mut() = decor;
+        }
+        None => {
+            project.insert("requires-python", Item::Value(requires));
+            merge.added.push("project.requires-python".to_string());
+        }
+    }
+
+    // `3` and `3 :: Only` stay; only the minor versions are py-proj's to rewrite.
+    let minor = |c: &str| c.strip_prefix(PREFIX).is_some_and(|v| v.contains('.') && !v.contains("::"));
+    if let Some(classifiers) = project.get_mut("classifiers").and_then(Item::as_array_mut) {
+        let before = classifiers.clone();
+        let at = classifiers.iter().position(|c| c.as_str().is_some_and(minor)).unwrap_or(classifiers.len());
+        classifiers.retain(|c| !c.as_str().is_some_and(minor));
+        for (i, version) in versions.iter().enumerate() {
+            classifiers.insert(at + i, format!("{PREFIX}{version}"));
+        }
+        // Inserted values take no decor; copy a neighbour's so multi-line arrays stay so.
+        if let Some(decor) = before.get(at).or_else(|| before.iter().last()).map(|v| v.decor().clone()) {
+            for value in classifiers.iter_mut().skip(at).take(versions.len()) {
+                *value.decor_mut() = decor.clone();
+            }
+        }
+        let new = Value::Array(classifiers.clone());
+        if !same(&Value::Array(before.clone()), &new) {
+            merge.replaced.push(Replaced { key: "project.classifiers".to_string(), old: bare(&Value::Array(before)), new: bare(&new) });
+        }
+    }
+
+    merge.text = doc.to_string();
+    Ok(merge)
+}
+
+/// Add `gen` as `dest[name]` if it is missing; otherwise set each key `gen` has,
+/// recursing into subtables, and keep the keys only the user has.
+fn merge_owned(
+    dest: &mut dyn TableLike,
+    prefix: &str,
+    name: &str,
+    gen: &Table,
+    next_pos: &mut isize,
+    merge: &mut Merge,
+) -> Result<()> {
+    let key = format!("{prefix}.{name}");
+    let Some(existing) = dest.get_mut(name) else {
+        let mut item = Item::Table(gen.clone());
+        reposition(&mut item, next_pos);
+        dest.insert(name, item);
+        merge.added.push(format!("[{key}]"));
+        return Ok(());
+    };
+    let table = existing
+        .as_table_like_mut()
+        .with_context(|| format!("`{key}` in pyproject.toml is not a table"))?;
+    for (child, item) in gen.iter() {
+        let path = format!("{key}.{child}");
+        match (table.get_mut(child), item) {
+            (None, _) => {
+                let mut item = item.clone();
+                reposition(&mut item, next_pos);
+                table.insert(child, item);
+                merge.added.push(path);
+            }
+            (Some(mine), Item::Table(sub)) if mine.is_table_like() => {
+                merge_owned(table, &key, child, sub, next_pos, merge)?;
+            }
+            (Some(mine), Item::Value(theirs)) => match mine.as_value_mut() {
+                Some(old) if same(old, theirs) => {}
+                Some(old) => {
+                    merge.replaced.push(Replaced {
+                        key: path,
+                        old: bare(old),
+                        new: bare(theirs),
+                    });
+                    let decor = old.decor().clone();
+                    *old = theirs.clone();
+                    *old.decor_mut() = decor;
+                }
+                None => {
+                    merge.replaced.push(Replaced {
+                        key: path,
+                        old: "(a table)".to_string(),
+                        new: bare(theirs),
+                    });
+                    *mine = item.clone();
+                }
+            },
+            (Some(mine), _) => {
+                merge.replaced.push(Replaced {
+                    key: path,
+                    old: mine.to_string().trim().to_string(),
+                    new: item.to_string().trim().to_string(),
+                });
+                let mut item = item.clone();
+                reposition(&mut item, next_pos);
+                *mine = item;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Where [`merge_dev`] puts the dev requirements: `dev` in `table` of its parent.
+struct DevList<'a> {
+    table: &'a str,
+    /// How [`Merge::added`] names the list when it is added whole.
+    label: &'a str,
+    /// Requirement names the other style already lists, left out here.
+    elsewhere: &'a [String],
+}
+
+/// Add the generated dev requirements to `parent`, keeping the requirements already
+/// there (including their version bounds).
+fn merge_dev(
+    parent: &mut dyn TableLike,
+    list: DevList<'_>,
+    gen_dev: &Array,
+    next_pos: &mut isize,
+    merge: &mut Merge,
+) -> Result<()> {
+    let mut gen_dev = gen_dev.clone();
+    gen_dev.retain(|v| !v.as_str().is_some_and(|req| list.elsewhere.contains(&requirement_name(req))));
+    if gen_dev.is_empty() {
+        return Ok(());
+    }
+    if parent.get(list.table).is_none() {
+        let mut table = Table::new();
+        table.set_position(Some(*next_pos));
+        *next_pos += 1;
+        parent.insert(list.table, Item::Table(table));
+    }
+    let table = parent
+        .get_mut(list.table)
+        .and_then(Item::as_table_like_mut)
+        .with_context(|| format!("`{}` in pyproject.toml is not a table", list.table))?;
+    if table.get("dev").is_none() {
+        table.insert("dev", toml_edit::value(gen_dev));
+        merge.added.push(list.label.to_string());
+    } else if let Some(dev) = table.get_mut("dev").and_then(Item::as_array_mut) {
+        let have: Vec<String> = dev.iter().filter_map(|v| v.as_str()).map(requirement_name).collect();
+        for req in gen_dev.iter().filter_map(|v| v.as_str()) {
+            if !have.contains(&requirement_name(req)) {
+                dev.push(req);
+                merge.added.push(req.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Equal as TOML values, whatever the formatting and comments.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| same(v, w)))
+        }
+        _ => false,
+    }
+}
+
+/// A value as written, without surrounding whitespace or comments.
+fn bare(value: &Value) -> String {
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    value.to_string()
+}
+
+/// Give copied tables fresh positions so they land at the end of the document.
+fn reposition(item: &mut Item, next: &mut isize) {
+    if let Some(table) = item.as_table_mut() {
+        table.set_position(Some(*next));
+        *next += 1;
+        for (_, child) in table.iter_mut() {
+            reposition(child, next);
+        }
+    }
+}
+
+fn max_position(table: &Table) -> isize {
+    table
+        .iter()
+        .filter_map(|(_, item)| item.as_table())
+        .map(|t| t.position().unwrap_or(0).max(max_position(t)))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The normalized package name of a requirement string (`Ruff>=0.6` -> `ruff`).
+fn requirement_name(req: &str) -> String {
+    req.split(|c: char| "<>=!~[; @".contains(c))
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+        .replace(['_', '.'], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hand-maintained pyproject.toml, as adopt finds them.
+    const EXISTING: &str = r#"# acme-tool: maintained by hand, please keep the comments.
+[project]
+name = "acme-tool"
+version = "1.4.2"
+description = "Does acme things"  # shown on PyPI
+requires-python = ">=3.10"
+dependencies = [
+    "requests>=2.31",
+    "click",  # the CLI
+]
+
+[project.optional-dependencies]
+dev = ["pytest>=8", "Ruff==0.5.0"]
+
+[project.scripts]
+acme = "acme.cli:main"
+
+[build-system]
+requires = ["hatchling"]
+build-backend = "hatchling.build"
+
+[tool.ruff]
+line-length = 100  # team standard
+extend-exclude = ["legacy"]
+
+[tool.black]
+skip-string-normalization = true
+"#;
+
+    const GENERATED: &str = r#"[project]
+name = "acme-tool"
+version = "0.1.0"
+requires-python = ">=3.12"
+dependencies = []
+
+[project.optional-dependencies]
+dev = ["pytest>=8.0", "ruff>=0.6.0", "pyright"]
+
+[project.scripts]
+acme-tool = "acme_tool.cli:main"
+
+[build-system]
+requires = ["setuptools>=68"]
+build-backend = "setuptools.build_meta"
+
+[tool.ruff]
+line-length = 88
+target-version = "py312"
+
+[tool.pytest.ini_options]
+testpaths = ["tests"]
+"#;
+
+    #[test]
+    fn merge_keeps_the_users_metadata_and_comments() {
+        let merge = merge(EXISTING, GENERATED).unwrap();
+        let text = &merge.text;
+        for kept in [
+            "# acme-tool: maintained by hand, please keep the comments.",
+            "version = \"1.4.2\"",
+            "description = \"Does acme things\"  # shown on PyPI",
+            "requires-python = \">=3.10\"",
+            "    \"click\",  # the CLI",
+            "acme = \"acme.cli:main\"",
+            "build-backend = \"hatchling.build\"",
+            "extend-exclude = [\"legacy\"]",
+            "[tool.black]\nskip-string-normalization = true",
+        ] {
+            assert!(text.contains(kept), "lost {kept:?}:\n{text}");
+        }
+        assert!(!text.contains("setuptools"), "{text}");
+        let doc: toml::Table = text.parse().unwrap();
+        assert_eq!(doc["project"]["scripts"]["acme-tool"].as_str(), Some("acme_tool.cli:main"));
+        assert_eq!(doc["tool"]["ruff"]["target-version"].as_str(), Some("py312"));
+        assert!(doc["tool"]["pytest"]["ini_options"].is_table());
+    }
+
+    #[test]
+    fn merge_replaces_owned_keys_and_reports_the_old_value() {
+        let merge = merge(EXISTING, GENERATED).unwrap();
+        assert_eq!(merge.replaced.len(), 1, "{:?}", merge.replaced);
+        let replaced = &merge.replaced[0];
+        assert_eq!(replaced.key, "tool.ruff.line-length");
+        assert_eq!((replaced.old.trim(), replaced.new.trim()), ("100", "88"));
+        let doc: toml::Table = merge.text.parse().unwrap();
+        assert_eq!(doc["tool"]["ruff"]["line-length"].as_integer(), Some(88));
+    }
+
+    #[test]
+    fn merge_adds_only_the_missing_dev_requirements() {
+        let merge = merge(EXISTING, GENERATED).unwrap();
+        let doc: toml::Table = merge.text.parse().unwrap();
+        let dev: Vec<&str> = doc["project"]["optional-dependencies"]["dev"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(dev, ["pytest>=8", "Ruff==0.5.0", "pyright"]);
+        assert!(merge.added.contains(&"pyright".to_string()), "{:?}", merge.added);
+    }
+
+    #[test]
+    fn merging_twice_changes_nothing() {
+        let once = merge(EXISTING, GENERATED).unwrap();
+        let twice = merge(&once.text, GENERATED).unwrap();
+        assert!(twice.is_noop(), "{:?} {:?}", twice.added, twice.replaced);
+        assert_eq!(twice.text, once.text);
+    }
+
+    #[test]
+    fn merge_into_an_empty_file_takes_the_generated_tables() {
+        let merge = merge("", GENERATED).unwrap();
+        let doc: toml::Table = merge.text.parse().unwrap();
+        let gen: toml::Table = GENERATED.parse().unwrap();
+        assert_eq!(doc, gen);
+    }
+
+    #[test]
+    fn set_python_rewrites_only_the_python_fields() {
+        let existing = r#"[project]
+name = "demo"
+requires-python = ">=3.12"  # the floor
+classifiers = [
+  "Development Status :: 4 - Beta",
+  "Programming Language :: Python :: 3",
+  "Programming Language :: Python :: 3 :: Only",
+  "Programming Language :: Python :: 3.12",
+  "Topic :: Utilities",
+]
+"#;
+        let versions = ["3.11".to_string(), "3.13".to_string()];
+        let merge = set_python(existing, &versions).unwrap();
+        assert_eq!(merge.text, r#"[project]
+name = "demo"
+requires-python = ">=3.11"  # the floor
+classifiers = [
+  "Development Status :: 4 - Beta",
+  "Programming Language :: Python :: 3",
+  "Programming Language :: Python :: 3 :: Only",
+  "Programming Language :: Python :: 3.11",
+  "Programming Language :: Python :: 3.13",
+  "Topic :: Utilities",
+]
+"#);
+        let keys: Vec<&str> = merge.replaced.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, ["project.requires-python", "project.classifiers"]);
+        assert!(set_python(&merge.text, &versions).unwrap().is_noop());
+
+        let bare = set_python("[project]\nname = \"demo\"\n", &versions[..1]).unwrap();
+        assert_eq!(bare.text, "[project]\nname = \"demo\"\nrequires-python = \">=3.11\"\n");
+        assert_eq!(bare.added, ["project.requires-python"]);
+        assert_eq!(set_python("[tool.ruff]\n", &versions).unwrap().text, "[tool.ruff]\n");
+    }
+}
@@ -0,0 +1,429 @@
+//! Python versions: parsing `--python`, finding a default, and asking uv which
+//! versions exist.
+
+use anyhow::Result;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::util::{path_python, run_output, uv_bin, FALLBACK_PYTHON};
+
+/// A Python version as uv understands it: `3`, `3.12`, or `3.12.4`, optionally
+/// with a suffix such as `t` for free-threaded builds (`3.13t`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PythonVersion {
+    pub major: u32,
+    pub minor: Option<u32>,
+    pub patch: Option<u32>,
+    /// Empty for regular builds.
+    pub suffix: String,
+}
+
+impl PythonVersion {
+    /// `3.12` (or just `3`), as used in `pythonVersion` and `python_version`.
+    pub fn mm(&self) -> String {
+        match self.minor {
+            Some(minor) => format!("{}.{minor}", self.major),
+            None => self.major.to_string(),
+        }
+    }
+
+    /// `312`, as used in Ruff's `target-version = "py312"`.
+    pub fn mm_nodec(&self) -> String {
+        self.mm().replace('.', "")
+    }
+
+    /// Whether all three components are given.
+    pub fn is_full(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    /// Whether the concrete `version` (e.g. from `uv python list`) satisfies this
+    /// request: `3.12` matches `3.12.4`, but not `3.12.4t` or `3.13.0`.
+    pub fn matches(&self, version: &PythonVersion) -> bool {
+        self.major == version.major
+            && self.minor.is_none_or(|m| version.minor == Some(m))
+            && self.patch.is_none_or(|p| version.patch == Some(p))
+            && self.suffix == version.suffix
+    }
+
+    /// A partial version -> the newest regular full version uv lists that matches it
+    /// (installed ones only when `installed_only`). Full or suffixed versions, and
+    /// anything uv cannot resolve, come back unchanged for uv to interpret.
+    pub fn resolve(&self, installed_only: bool) -> PythonVersion {
+        if self.is_full() || !self.suffix.is_empty() {
+            return self.clone();
+        }
+        let Ok(versions) = uv_python_versions(installed_only) else {
+            return self.clone();
+        };
+        self.newest_in(versions).unwrap_or_else(|| self.clone())
+    }
+
+    /// The newest regular full version in `versions` that this one matches.
+    fn newest_in(&self, versions: &[PythonVersion]) -> Option<PythonVersion> {
+        versions.iter().filter(|v| v.is_full() && self.matches(v)).max().cloned()
+    }
+
+    /// Up to `n` of `versions` numerically nearest to this one, for "did you mean".
+    pub fn closest(&self, versions: &[PythonVersion], n: usize) -> Vec<PythonVersion> {
+        let key = |v: &PythonVersion| {
+            [v.major, v.minor.unwrap_or(0), v.patch.unwrap_or(0)].map(i64::from)
+        };
+        let want = key(self);
+        let mut scored: Vec<(i64, &PythonVersion)> = versions
+            .iter()
+            .filter(|v| v.suffix == self.suffix)
+            .map(|v| {
+                let have = key(v);
+                let diff = |i: usize| (want[i] - have[i]).abs();
+                (diff(0) * 10_000 + diff(1) * 100 + diff(2), v)
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| *score);
+        scored.into_iter().take(n).map(|(_, v)| v.clone()).collect()
+    }
+}
+
+impl FromStr for PythonVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let usage = || {
+            format!("invalid Python version `{s}`; expected X, X.Y, or X.Y.Z, e.g. `3`, `3.12`, `3.12.4`, or `3.13t` (free-threaded)")
+        };
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+        let (numbers, suffix) = s.split_at(split);
+        if numbers.is_empty()
+            || numbers.ends_with('.')
+            || !suffix.chars().all(|c| c.is_ascii_alphanumeric() || c == '+')
+        {
+            return Err(usage());
+        }
+        let parts = numbers
+            .split('.')
+            .map(|p| p.parse::<u32>().map_err(|_| usage()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if parts.len() > 3 {
+            return Err(usage());
+        }
+        if parts[0] < 3 {
+            return Err(format!(
+                "Python {s} is not supported; py-proj scaffolds Python 3 projects (e.g. `--python 3.12`)"
+            ));
+        }
+        Ok(PythonVersion {
+            major: parts[0],
+            minor: parts.get(1).copied(),
+            patch: parts.get(2).copied(),
+            suffix: suffix.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        f.write_str(&self.suffix)
+    }
+}
+
+/// CPython versions uv can install (`--all-versions`), or only the installed ones,
+/// newest first as uv lists them. Each list is fetched once per process.
+pub fn uv_python_versions(installed_only: bool) -> Result<&'static [PythonVersion]> {
+    static ALL: OnceLock<Vec<PythonVersion>> = OnceLock::new();
+    static INSTALLED: OnceLock<Vec<PythonVersion>> = OnceLock::new();
+    let (cache, flag) = match installed_only {
+        true => (&INSTALLED, "--only-installed"),
+        false => (&ALL, "--all-versions"),
+    };
+    if let Some(versions) = cache.get() {
+        return Ok(versions);
+    }
+    let out = run_output(&uv_bin(), &["python", "list", flag], &env::temp_dir())?;
+    Ok(cache.get_or_init(|| parse_python_list(&out)))
+}
+
+/// `cpython-3.12.4-linux-x86_64-gnu  <download available>` lines -> `3.12.4`, deduplicated.
+/// uv spells free-threaded builds `3.13.1+freethreaded`; they become `3.13.1t`.
+fn parse_python_list(output: &str) -> Vec<PythonVersion> {
+    let mut versions: Vec<PythonVersion> = Vec::new();
+    for line in output.lines() {
+        let version = line
+            .split_whitespace()
+            .next()
+            .and_then(|key| key.strip_prefix("cpython-"))
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|v| v.replace("+freethreaded", "t").parse().ok());
+        if let Some(v) = version.filter(|v| !versions.contains(v)) {
+            versions.push(v);
+        }
+    }
+    versions
+}
+
+/// Where an auto-detected Python version came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PythonSource {
+    /// Given explicitly (`--python`).
+    Flag,
+    /// The `python` key of a config file: a repository's `.pyprojrc.toml` or the user's.
+    Config(PathBuf),
+    /// A `.python-version` file in the directory or one of its parents.
+    VersionFile(PathBuf),
+    /// The `python` entry of a tool manager's pin file in the directory or one of its
+    /// parents: asdf's (and mise's) `.tool-versions`, or mise's `.mise.toml`/`mise.toml`.
+    ToolFile(PathBuf),
+    /// `pyenv version-name`.
+    Pyenv,
+    /// The `python3`/`python` on `PATH`.
+    Path,
+    /// Nothing found; the hardcoded default.
+    Fallback,
+}
+
+impl fmt::Display for PythonSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PythonSource::Flag => f.write_str("--python"),
+            PythonSource::Config(path) => write!(f, "{}", path.display()),
+            PythonSource::VersionFile(path) | PythonSource::ToolFile(path) => write!(f, "{}", path.display()),
+            PythonSource::Pyenv => f.write_str("pyenv"),
+            PythonSource::Path => f.write_str("python on PATH"),
+            PythonSource::Fallback => f.write_str("default"),
+        }
+    }
+}
+
+/// The pin files of mise and asdf that name a Python, nearest-first within a directory.
+pub const TOOL_FILES: &[&str] = &[".tool-versions", ".mise.toml", "mise.toml"];
+
+/// The Python to scaffold for when `--python` is not given: the nearest
+/// `.python-version` from `dir` upwards, then the nearest mise/asdf pin (see
+/// [`TOOL_FILES`]), then pyenv, then `PATH`, then a default. Partial versions such as
+/// `3.12` are returned as-is.
+pub fn detect_python(dir: &Path) -> (PythonVersion, PythonSource) {
+    for d in dir.ancestors() {
+        let file = d.join(".python-version");
+        if let Some(version) = fs::read_to_string(&file).ok().as_deref().and_then(pinned_version) {
+            return (version, PythonSource::VersionFile(file));
+        }
+    }
+    if let Some((version, file)) = tool_file_python(dir) {
+        return (version, PythonSource::ToolFile(file));
+    }
+    if let Some(version) = pyenv_version() {
+        return (version, PythonSource::Pyenv);
+    }
+    match path_python().and_then(|v| v.parse().ok()) {
+        Some(version) => (version, PythonSource::Path),
+        None => (FALLBACK_PYTHON.parse().expect("valid fallback"), PythonSource::Fallback),
+    }
+}
+
+/// The first version in a `.python-version` file (comments, blanks, and `system` skipped).
+fn pinned_version(contents: &str) -> Option<PythonVersion> {
+    contents
+        .lines()
+        .map(str::trim)
+        .map(|l| l.strip_prefix("cpython-").unwrap_or(l))
+        .find_map(|l| l.parse().ok())
+}
+
+/// The Python of the nearest [`TOOL_FILES`] entry from `dir` upwards that has one, and
+/// the file it came from.
+pub fn tool_file_python(dir: &Path) -> Option<(PythonVersion, PathBuf)> {
+    dir.ancestors().flat_map(|d| TOOL_FILES.iter().map(move |name| d.join(name))).find_map(|file| {
+        let contents = fs::read_to_string(&file).ok()?;
+        let version = match file.extension() {
+            Some(ext) if ext == "toml" => mise_python(&contents),
+            _ => tool_versions_python(&contents),
+        }?;
+        Some((version, file))
+    })
+}
+
+/// The first version on the `python` line of a `.tool-versions` file, e.g.
+/// `python 3.12.4 3.11.9` (asdf takes the first as the default).
+fn tool_versions_python(contents: &str) -> Option<PythonVersion> {
+    let versions = contents.lines().find_map(|line| {
+        let mut words = line.split('#').next()?.split_whitespace();
+        (words.next()? == "python").then(|| words.collect::<Vec<_>>().join("\n"))
+    })?;
+    pinned_version(&versions)
+}
+
+/// The `[tools] python` of a `.mise.toml`: `"3.12"`, `["3.12", "3.11"]` (the first is
+/// the default), or `{ version = "3.12" }`.
+fn mise_python(contents: &str) -> Option<PythonVersion> {
+    let doc: toml::Table = contents.parse().ok()?;
+    let entry = doc.get("tools")?.get("python")?;
+    let entry = match entry {
+        toml::Value::Array(versions) => versions.first()?,
+        entry => entry,
+    };
+    let version = match entry {
+        toml::Value::Table(t) => t.get("version")?.as_str()?,
+        v => v.as_str()?,
+    };
+    pinned_version(version)
+}
+
+fn pyenv_version() -> Option<PythonVersion> {
+    let pyenv = which::which("pyenv").ok()?;
+    let out = Command::new(pyenv).arg("version-name").output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    // Several active versions come out as `3.12.4:3.11.9`.
+    let names = String::from_utf8_lossy(&out.stdout);
+    pinned_version(&names.trim().replace(':', "\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> PythonVersion {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn versions_parse_with_one_to_three_parts_and_a_suffix() {
+        assert_eq!(v("3").to_string(), "3");
+        assert_eq!(v("3.12").to_string(), "3.12");
+        assert_eq!(v("3.12.4").to_string(), "3.12.4");
+        assert_eq!(v("3.13t").suffix, "t");
+        for bad in ["", "three", "3.x", "3.12.4.1"] {
+            assert!(bad.parse::<PythonVersion>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn invalid_versions_are_refused_with_an_example() {
+        for bad in ["banana", "3.", ".12", "3..12", "3.12-dev", "3,12"] {
+            let err = bad.parse::<PythonVersion>().unwrap_err();
+            assert!(err.contains("expected X, X.Y, or X.Y.Z") && err.contains("`3.12`"), "{bad}: {err}");
+        }
+        let err = "2.7".parse::<PythonVersion>().unwrap_err();
+        assert!(err.contains("Python 2.7 is not supported"), "{err}");
+        assert_eq!(v(" 3.12 "), v("3.12"));
+        assert_eq!(
+            v("3.13.1t"),
+            PythonVersion { major: 3, minor: Some(13), patch: Some(1), suffix: "t".into() }
+        );
+    }
+
+    #[test]
+    fn partial_versions_resolve_to_the_newest_listed_patch() {
+        let listed = parse_python_list(
+            "cpython-3.13.1+freethreaded-linux-x86_64-gnu    <download available>\n\
+             cpython-3.13.1-linux-x86_64-gnu                 <download available>\n\
+             cpython-3.12.8-linux-x86_64-gnu                 /usr/bin/python3.12\n\
+             cpython-3.12.8-linux-x86_64-gnu                 <download available>\n\
+             cpython-3.12.10-linux-x86_64-gnu                <download available>\n\
+             pypy-3.10.14-linux-x86_64-gnu                   <download available>\n",
+        );
+        assert_eq!(listed, ["3.13.1t", "3.13.1", "3.12.8", "3.12.10"].map(v));
+        assert_eq!(v("3.12").newest_in(&listed), Some(v("3.12.10")));
+        assert_eq!(v("3").newest_in(&listed), Some(v("3.13.1")));
+        assert_eq!(v("3.13t").newest_in(&listed), Some(v("3.13.1t")));
+        assert_eq!(v("3.11").newest_in(&listed), None);
+        assert!(!v("3.12").matches(&v("3.13.0")));
+    }
+
+    #[test]
+    fn python_list_parses_across_uv_output_formats() {
+        // uv 0.4: installed ones with a path, symlinks with their target.
+        let linux = "cpython-3.12.8-linux-x86_64-gnu     /home/me/.local/share/uv/python/cpython-3.12.8-linux-x86_64-gnu/bin/python3.12\n\
+                     cpython-3.11.9-linux-x86_64-gnu     /usr/bin/python3.11 -> python3.11.9\n";
+        assert_eq!(parse_python_list(linux), [v("3.12.8"), v("3.11.9")]);
+        // macOS and Windows platform triples, and a pre-release.
+        let other = "cpython-3.14.0a3-macos-aarch64-none    <download available>\n\
+                     cpython-3.13.1-windows-x86_64-none     C:\\Users\\me\\python.exe\n";
+        assert_eq!(parse_python_list(other), [v("3.14.0a3"), v("3.13.1")]);
+        // Other implementations, blank lines, and warnings are not CPython versions.
+        let noise = "\nwarning: `uv python list` is experimental\n\
+                     pypy-3.10.14-linux-x86_64-gnu    <download available>\n\
+                     graalpy-3.11.0-linux-x86_64-gnu  <download available>\n";
+        assert!(parse_python_list(noise).is_empty());
+        assert!(parse_python_list("").is_empty());
+    }
+
+    #[test]
+    fn installed_patch_satisfies_a_partial_request() {
+        let installed = parse_python_list("cpython-3.12.4-linux-x86_64-gnu    /usr/bin/python3.12\n");
+        assert!(installed.iter().any(|i| v("3.12").matches(i)));
+        assert!(installed.iter().any(|i| v("3.12.4").matches(i)));
+        assert!(!installed.iter().any(|i| v("3.12.5").matches(i)));
+        assert!(!installed.iter().any(|i| v("3.12t").matches(i)));
+    }
+
+    #[test]
+    fn two_part_versions_give_well_formed_mm() {
+        let version = v("3.12");
+        assert!(!version.is_full());
+        assert_eq!((version.mm(), version.mm_nodec()), ("3.12".into(), "312".into()));
+        assert_eq!(v("3.9.18").mm_nodec(), "39");
+        assert_eq!(v("3.13.1t").mm(), "3.13");
+    }
+
+    #[test]
+    fn version_file_in_a_parent_is_found_and_kept_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        let child = dir.path().join("a/b");
+        fs::create_dir_all(&child).unwrap();
+        let file = dir.path().join(".python-version");
+        fs::write(&file, "# pinned\nsystem\n3.12\n").unwrap();
+        let (version, source) = detect_python(&child);
+        assert_eq!(version, v("3.12"));
+        assert_eq!(source, PythonSource::VersionFile(file));
+    }
+
+    #[test]
+    fn nearest_version_file_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let child = dir.path().join("child");
+        fs::create_dir(&child).unwrap();
+        fs::write(dir.path().join(".python-version"), "3.11\n").unwrap();
+        fs::write(child.join(".python-version"), "cpython-3.13.1\n").unwrap();
+        assert_eq!(detect_python(&child).0, v("3.13.1"));
+    }
+
+    #[test]
+    fn version_file_anywhere_above_beats_a_tool_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let child = dir.path().join("child");
+        fs::create_dir(&child).unwrap();
+        fs::write(dir.path().join(".python-version"), "3.11\n").unwrap();
+        fs::write(child.join(".tool-versions"), "python 3.12.4\n").unwrap();
+        assert_eq!(detect_python(&child).0, v("3.11"));
+    }
+
+    #[test]
+    fn tool_files_name_the_python() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(".tool-versions");
+        fs::write(&file, "nodejs 20.1.0\npython 3.12.4 3.11.9  # default first\n").unwrap();
+        assert_eq!(tool_file_python(dir.path()), Some((v("3.12.4"), file.clone())));
+        let (version, source) = detect_python(dir.path());
+        assert_eq!((version, source), (v("3.12.4"), PythonSource::ToolFile(file)));
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("mise.toml"), "[tools]\npython = [\"3.13\", \"3.12\"]\n").unwrap();
+        assert_eq!(tool_file_python(dir.path()).map(|(v, _)| v), Some(v("3.13")));
+        fs::write(dir.path().join(".mise.toml"), "[tools.python]\nversion = \"3.11\"\n").unwrap();
+        assert_eq!(tool_file_python(dir.path()).map(|(v, _)| v), Some(v("3.11")));
+    }
+}
@@ -1,115 +1,4342 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use minijinja::value::Serde;
+use minijinja::Value;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::bootstrap;
+use crate::config::OrgConfig;
+use crate::error::Error;
+use crate::events::{Event, EventHandler};
+use crate::lang::{Strings, CATALOG_DIR, DEFAULT_LANG};
+use crate::lock::ProjectLock;
+use crate::manifest::{FileEntry, Manifest, Options, Preexisting, MANIFEST_FILE};
+use crate::profile::Profile;
+use crate::pyproject;
+use crate::python::{detect_python, uv_python_versions, PythonVersion, TOOL_FILES};
+use crate::template_repo::TemplateRepo;
 use crate::templates::*;
-use crate::util::{run, write};
+use crate::util::{
+    blank_env_values, bootstrap_uv, canonicalize_lenient, check_interrupt, check_uv, dist_name, format_mode,
+    generated_mode, git_work_tree, glob_matches, import_name, interrupted, interruptible, is_safe_relative, list_files,
+    name_clash, normalize_line_endings, parse_mode, pyvenv_python, quote_argv, random_secret, relative_path,
+    remove_tree, run_cancellable, run_env, run_output, run_streaming, set_dir_mode, sha256_hex, uv_bin, uv_tools,
+    validate_module, validate_project_name, venv_python_matches, venv_python_version, write_with_mode, Captured,
+    NameClash, WriteOutcome, WritePolicy, DEFAULT_NOTEBOOKS_DIR, DEFAULT_VENV_DIR,
+};
+use crate::workspace;
 
+/// A planned file that already exists with other contents: (relative path, current
+/// contents, planned contents).
+pub type Conflict = (PathBuf, Vec<u8>, Vec<u8>);
+
+/// One step of a scaffold, computed up front by [`ScaffoldPlan::plan`] and applied by
+/// [`ScaffoldPlan::execute`]. Paths are relative to the project root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlannedAction {
+    CreateDir {
+        path: PathBuf,
+    },
+    WriteFile {
+        path: PathBuf,
+        contents: Vec<u8>,
+        origin: Origin,
+    },
+    RunCommand {
+        /// Progress line printed before the command runs.
+        label: String,
+        program: String,
+        args: Vec<String>,
+        cwd: PathBuf,
+        /// Warn and carry on when it fails instead of failing the scaffold.
+        optional: bool,
+        /// Needs nothing the other actions make but the root, so it starts on a
+        /// background thread with the first action; its turn in the order waits for it.
+        background: bool,
+    },
+    /// Add a member to a uv workspace root (pyproject.toml and VS Code settings).
+    RegisterMember {
+        workspace: PathBuf,
+        member: String,
+    },
+    /// Remove a directory under the root before the commands recreate it: a `.venv`
+    /// built with another Python (`--recreate-venv`). Rollback cannot bring it back.
+    RemoveDir {
+        path: PathBuf,
+    },
+    /// Write the `--code-workspace` file. It lives outside the project (`path` starts
+    /// with `..` unless it was pointed inside), so it is not in the manifest.
+    WriteCodeWorkspace {
+        path: PathBuf,
+        contents: Vec<u8>,
+    },
+}
+
+impl PlannedAction {
+    /// What [`WriteSummary::timings`] files this action's time under: one phase per
+    /// template group and per command, so new actions are timed without extra code.
+    pub fn phase(&self) -> String {
+        match self {
+            PlannedAction::CreateDir { .. } => "create directories".to_string(),
+            PlannedAction::WriteFile {
+                origin: Origin::Manifest,
+                ..
+            } => "write manifest".to_string(),
+            PlannedAction::WriteFile { path, .. } if path == Path::new(bootstrap::SCRIPT_PATH) => {
+                "write bootstrap script".to_string()
+            }
+            PlannedAction::WriteFile { path, .. } => {
+                match find(&path.to_string_lossy().replace('\\', "/")) {
+                    Some(t) => format!("write {}", t.group.name()),
+                    None => "write templates-dir extras".to_string(),
+                }
+            }
+            PlannedAction::RunCommand { program, args, .. } => {
+                let name = Path::new(program).file_stem().unwrap_or_default().to_string_lossy();
+                format!("{name} {}", args.join(" "))
+            }
+            PlannedAction::RegisterMember { .. } => "register workspace member".to_string(),
+            PlannedAction::WriteCodeWorkspace { .. } => "write code-workspace".to_string(),
+            PlannedAction::RemoveDir { path } => format!("remove {}", path.display()),
+        }
+    }
+}
+
+/// Where a planned file's contents come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Origin {
+    BuiltIn,
+    /// A `--templates-dir` file replacing a built-in template.
+    Override,
+    /// A `--templates-dir` file with no built-in counterpart.
+    Extra,
+    Manifest,
+}
+
+impl Origin {
+    pub fn label(self) -> &'static str {
+        match self {
+            Origin::BuiltIn => "built-in",
+            Origin::Override => "override",
+            Origin::Extra => "extra",
+            Origin::Manifest => "manifest",
+        }
+    }
+}
+
+/// Dev tools that `--tools-global` installs with `uv tool install` instead of listing
+/// them as dev dependencies. pytest is not one: it needs the project's environment.
+pub const GLOBAL_TOOLS: &[&str] = &["ruff", "pyright"];
+
+/// What runs the test and lint sessions across the `--python-matrix` versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatrixRunner {
+    /// `noxfile.py`, with uv as the venv backend.
+    #[default]
+    Nox,
+    /// `tox.ini`, with tox-uv.
+    Tox,
+}
+
+impl MatrixRunner {
+    pub fn name(self) -> &'static str {
+        match self {
+            MatrixRunner::Nox => "nox",
+            MatrixRunner::Tox => "tox",
+        }
+    }
+}
+
+impl FromStr for MatrixRunner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "nox" => Ok(MatrixRunner::Nox),
+            "tox" => Ok(MatrixRunner::Tox),
+            _ => Err(format!("unknown matrix runner `{s}`; expected nox or tox")),
+        }
+    }
+}
+
+/// Where pyproject.toml declares the dev tools (`--dep-style`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepStyle {
+    /// A `dev` extra under `[project.optional-dependencies]`: `uv pip install -e ".[dev]"`.
+    #[default]
+    Extras,
+    /// A PEP 735 `dev` group under `[dependency-groups]`, kept out of the published
+    /// metadata: `uv sync --group dev`, `uv pip install --group dev`.
+    Groups,
+}
+
+impl DepStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            DepStyle::Extras => "extras",
+            DepStyle::Groups => "groups",
+        }
+    }
+}
+
+impl FromStr for DepStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "extras" => Ok(DepStyle::Extras),
+            "groups" => Ok(DepStyle::Groups),
+            _ => Err(format!("unknown dependency style `{s}`; expected extras or groups")),
+        }
+    }
+}
+
+/// Which bot keeps dependencies up to date (`--dep-updates`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepUpdates {
+    /// `.github/dependabot.yml`.
+    Dependabot,
+    /// `renovate.json`.
+    Renovate,
+}
+
+impl DepUpdates {
+    pub fn name(self) -> &'static str {
+        match self {
+            DepUpdates::Dependabot => "dependabot",
+            DepUpdates::Renovate => "renovate",
+        }
+    }
+}
+
+impl FromStr for DepUpdates {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "dependabot" => Ok(DepUpdates::Dependabot),
+            "renovate" => Ok(DepUpdates::Renovate),
+            _ => Err(format!("unknown dependency bot `{s}`; expected dependabot or renovate")),
+        }
+    }
+}
+
+/// Where a release workflow publishes the package (`--publish`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Publish {
+    /// `.github/workflows/release.yml`: `uv build` and PyPI trusted publishing.
+    Pypi,
+}
+
+impl Publish {
+    pub fn name(self) -> &'static str {
+        match self {
+            Publish::Pypi => "pypi",
+        }
+    }
+}
+
+impl FromStr for Publish {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "pypi" => Ok(Publish::Pypi),
+            _ => Err(format!("unknown publish target `{s}`; expected pypi")),
+        }
+    }
+}
+
+/// Git hooks a project guards commits with (`--git-hooks`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHooks {
+    /// Plain shell scripts in `.githooks/`, enabled with `core.hooksPath`: no hook manager.
+    Native,
+}
+
+impl GitHooks {
+    pub fn name(self) -> &'static str {
+        match self {
+            GitHooks::Native => "native",
+        }
+    }
+}
+
+impl FromStr for GitHooks {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "native" => Ok(GitHooks::Native),
+            _ => Err(format!("unknown git hooks style `{s}`; expected native")),
+        }
+    }
+}
+
+/// The date a [deterministic](ScaffoldPlanBuilder::deterministic) plan stamps into its files.
+pub const DETERMINISTIC_DATE: &str = "YYYY-MM-DD";
+
+/// The secret a webapp gets in `.env` without `--gen-secret`.
+pub const DEFAULT_SECRET: &str = "SECRET_KEY";
+
+/// What a [deterministic](ScaffoldPlanBuilder::deterministic) plan (or `--deterministic`)
+/// writes instead of a generated secret.
+pub const SECRET_PLACEHOLDER: &str = "change-me";
+
+/// Keys the scaffold's own `.env` and the webapp settings already have.
+const RESERVED_ENV_KEYS: &[&str] = &["PYTHONPATH", "ENV", "DATA_ROOT", "DEBUG", "APP_NAME"];
+
+/// The project-local Neovim config `--ide neovim` writes, which Neovim's `exrc` loads.
+pub const NVIM_EXRC: &str = ".nvim.lua";
+
+/// Where `--git-hooks native` puts its hooks, relative to the project root.
+pub const GIT_HOOKS_DIR: &str = ".githooks";
+
+/// The static security scanner a project is set up for (`--security`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Security {
+    /// bandit as a dev dependency, `[tool.bandit]`, and a `make security` target.
+    Bandit,
+    /// ruff's `S` (flake8-bandit) rules, part of the usual `ruff check`.
+    RuffS,
+}
+
+impl Security {
+    pub fn name(self) -> &'static str {
+        match self {
+            Security::Bandit => "bandit",
+            Security::RuffS => "ruff-s",
+        }
+    }
+}
+
+impl FromStr for Security {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "bandit" => Ok(Security::Bandit),
+            "ruff-s" => Ok(Security::RuffS),
+            _ => Err(format!("unknown security scanner `{s}`; expected bandit or ruff-s")),
+        }
+    }
+}
+
+/// The project's maturity (`--status`), as a `Development Status` trove classifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Alpha,
+    Beta,
+    Stable,
+}
+
+impl Status {
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Alpha => "alpha",
+            Status::Beta => "beta",
+            Status::Stable => "stable",
+        }
+    }
+
+    /// The trove classifier, e.g. `Development Status :: 3 - Alpha`.
+    pub fn classifier(self) -> &'static str {
+        match self {
+            Status::Alpha => "Development Status :: 3 - Alpha",
+            Status::Beta => "Development Status :: 4 - Beta",
+            Status::Stable => "Development Status :: 5 - Production/Stable",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "alpha" => Ok(Status::Alpha),
+            "beta" => Ok(Status::Beta),
+            "stable" => Ok(Status::Stable),
+            _ => Err(format!("unknown development status `{s}`; expected alpha, beta, or stable")),
+        }
+    }
+}
+
+/// Which Pythons uv may use (`uv --python-preference`, `--uv-python-preference`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PythonPreference {
+    /// Only uv-managed Pythons, never the system one.
+    OnlyManaged,
+    Managed,
+    System,
+    OnlySystem,
+}
+
+impl PythonPreference {
+    pub fn name(self) -> &'static str {
+        match self {
+            PythonPreference::OnlyManaged => "only-managed",
+            PythonPreference::Managed => "managed",
+            PythonPreference::System => "system",
+            PythonPreference::OnlySystem => "only-system",
+        }
+    }
+}
+
+impl FromStr for PythonPreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "only-managed" => Ok(PythonPreference::OnlyManaged),
+            "managed" => Ok(PythonPreference::Managed),
+            "system" => Ok(PythonPreference::System),
+            "only-system" => Ok(PythonPreference::OnlySystem),
+            _ => Err(format!(
+                "unknown Python preference `{s}`; expected only-managed, managed, system, or only-system"
+            )),
+        }
+    }
+}
+
+/// The editor a project is set up for (`--ide`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ide {
+    /// `.vscode/`: interpreter, launch configurations, and tasks.
+    #[default]
+    Vscode,
+    /// `.nvim.lua`, loaded by Neovim's `exrc`: the venv on `PATH` and pyright's interpreter.
+    Neovim,
+    /// No editor files; `pyrightconfig.json` and `.editorconfig` still serve any editor.
+    None,
+}
+
+impl Ide {
+    pub fn name(self) -> &'static str {
+        match self {
+            Ide::Vscode => "vscode",
+            Ide::Neovim => "neovim",
+            Ide::None => "none",
+        }
+    }
+}
+
+impl FromStr for Ide {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "vscode" => Ok(Ide::Vscode),
+            "neovim" => Ok(Ide::Neovim),
+            "none" => Ok(Ide::None),
+            _ => Err(format!("unknown editor `{s}`; expected vscode, neovim, or none")),
+        }
+    }
+}
+
+/// The tool manager whose pin file the scaffold writes (`--toolchain-file`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Toolchain {
+    /// `.python-version`, which uv and pyenv read.
+    PythonVersion,
+    /// `.mise.toml`, which also has mise activate the venv (and `.envrc` says `use mise`).
+    Mise,
+    /// `.tool-versions`, which asdf (and mise) read.
+    Asdf,
+}
+
+impl Toolchain {
+    pub fn name(self) -> &'static str {
+        match self {
+            Toolchain::PythonVersion => "python-version",
+            Toolchain::Mise => "mise",
+            Toolchain::Asdf => "asdf",
+        }
+    }
+
+    /// The pin file, relative to the project root.
+    pub fn file(self) -> &'static str {
+        match self {
+            Toolchain::PythonVersion => ".python-version",
+            Toolchain::Mise => ".mise.toml",
+            Toolchain::Asdf => ".tool-versions",
+        }
+    }
+}
+
+impl FromStr for Toolchain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "python-version" => Ok(Toolchain::PythonVersion),
+            "mise" => Ok(Toolchain::Mise),
+            "asdf" => Ok(Toolchain::Asdf),
+            _ => Err(format!("unknown toolchain file `{s}`; expected mise, asdf, or python-version")),
+        }
+    }
+}
+
+/// Line terminators of the generated text files (`--line-endings`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    #[default]
+    Lf,
+    Crlf,
+    /// CRLF when py-proj runs on Windows, LF elsewhere.
+    Native,
+}
+
+impl LineEndings {
+    pub fn name(self) -> &'static str {
+        match self {
+            LineEndings::Lf => "lf",
+            LineEndings::Crlf => "crlf",
+            LineEndings::Native => "native",
+        }
+    }
+
+    /// Whether lines end in `\r\n` on this platform.
+    pub fn crlf(self) -> bool {
+        match self {
+            LineEndings::Lf => false,
+            LineEndings::Crlf => true,
+            LineEndings::Native => cfg!(windows),
+        }
+    }
+}
+
+impl FromStr for LineEndings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "lf" => Ok(LineEndings::Lf),
+            "crlf" => Ok(LineEndings::Crlf),
+            "native" => Ok(LineEndings::Native),
+            _ => Err(format!("unknown line endings `{s}`; expected lf, crlf, or native")),
+        }
+    }
+}
+
+/// An extra testing library wired into the scaffold (`--testing-extras`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestingExtra {
+    /// hypothesis as a dev dependency, its profiles in `tests/conftest.py`, and
+    /// property tests in `tests/test_properties.py`.
+    Hypothesis,
+}
+
+impl TestingExtra {
+    pub fn name(self) -> &'static str {
+        match self {
+            TestingExtra::Hypothesis => "hypothesis",
+        }
+    }
+}
+
+impl FromStr for TestingExtra {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "hypothesis" => Ok(TestingExtra::Hypothesis),
+            _ => Err(format!("unknown testing extra `{s}`; expected hypothesis")),
+        }
+    }
+}
+
+/// Template groups `--minimal` leaves out (the notebooks directory goes with
+/// [`Group::Notebook`]).
+pub const MINIMAL_SKIPS: &[Group] =
+    &[Group::Vscode, Group::Envs, Group::Pyrefly, Group::Makefile, Group::AppLogging, Group::Notebook];
+
+/// A `[project] maintainers` entry (`--maintainer`, or the config's `[org]` table).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Maintainer {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// `Jane Doe <jane@acme.dev>` or just `Jane Doe`.
+impl FromStr for Maintainer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, email) = match s.trim().strip_suffix('>').and_then(|rest| rest.rsplit_once('<')) {
+            Some((name, email)) => (name.trim(), Some(email.trim().to_string())),
+            None => (s.trim(), None),
+        };
+        if name.is_empty() {
+            return Err(format!("invalid maintainer `{s}`; expected `Name` or `Name <email>`"));
+        }
+        Ok(Maintainer { name: name.to_string(), email })
+    }
+}
+
+/// Most threads [`ScaffoldPlan::execute`] writes files on at once.
+const WRITE_THREADS: usize = 8;
+
+/// A file written by [`ScaffoldPlan::write_batch`], for the main thread to report.
+struct Written {
+    outcome: WriteOutcome,
+    merged: Option<pyproject::Merge>,
+    elapsed: Duration,
+}
+
+/// What a background command did, collected on its thread for the main one to report.
+struct Buffered {
+    result: Result<Captured>,
+    lines: Vec<String>,
+    elapsed: Duration,
+}
+
+/// The background command's outcome, waiting for it to finish if need be.
+fn join(handle: thread::ScopedJoinHandle<'_, Buffered>) -> Buffered {
+    handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Per-file results of [`ScaffoldPlan::execute`].
+#[derive(Debug, Default)]
+pub struct WriteSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: Vec<PathBuf>,
+    pub backed_up: Vec<PathBuf>,
+    /// Wall-clock time per phase (see [`PlannedAction::phase`]), in the order each
+    /// phase first ran; [`create`] adds its checks and the post-create hooks.
+    pub timings: Vec<(String, Duration)>,
+    /// How much of the background command's time passed alongside the other actions
+    /// instead of after them: the saving from running it concurrently.
+    pub overlapped: Duration,
+    /// How much sooner the files were written than one after another: their summed
+    /// write times (what the phases show) less the wall-clock time the batches took.
+    pub parallel_saving: Duration,
+    /// The installed Python that made `uv python install` unnecessary, when it was skipped.
+    pub python_already_installed: Option<PythonVersion>,
+}
+
+impl WriteSummary {
+    /// Add `elapsed` to `phase`, which is appended the first time it is seen.
+    fn time(&mut self, phase: String, elapsed: Duration) {
+        match self.timings.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.timings.push((phase, elapsed)),
+        }
+    }
+
+    fn record(&mut self, rel: &Path, outcome: &WriteOutcome) {
+        match outcome {
+            WriteOutcome::Created => self.created += 1,
+            WriteOutcome::Unchanged => self.unchanged += 1,
+            WriteOutcome::Updated => self.updated += 1,
+            WriteOutcome::Skipped => self.skipped.push(rel.to_path_buf()),
+            WriteOutcome::BackedUp(bak) => {
+                self.updated += 1;
+                self.backed_up.push(bak.clone());
+            }
+        }
+    }
+
+    /// "12 created, 3 unchanged, 1 skipped (use --force)".
+    pub fn line(&self) -> String {
+        let mut parts = Vec::new();
+        let counts = [
+            (self.created, "created"),
+            (self.updated, "updated"),
+            (self.unchanged, "unchanged"),
+        ];
+        for (n, what) in counts {
+            if n > 0 {
+                parts.push(format!("{n} {what}"));
+            }
+        }
+        if !self.skipped.is_empty() {
+            parts.push(format!("{} skipped (use --force)", self.skipped.len()));
+        }
+        if parts.is_empty() {
+            return "nothing to write".to_string();
+        }
+        parts.join(", ")
+    }
+}
+
+/// Everything needed to scaffold one project. Build one with [`ScaffoldPlan::builder`],
+/// or rebuild an existing project's with [`ScaffoldPlan::from_manifest`].
 pub struct ScaffoldPlan {
     pub root: PathBuf,
     pub project: String,
-    pub py_full: String,
-    pub mm: String,
-    pub mm_nodec: String,
+    pub python: PythonVersion,
+    /// One-line summary for pyproject.toml and README.md (`--description`).
+    pub description: Option<String>,
+    /// `--author`, `--email`, and `--repo-url`, for pyproject.toml and CITATION.cff.
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub repo_url: Option<String>,
+    /// `--homepage`, for `[project.urls]` and CITATION.cff.
+    pub homepage: Option<String>,
+    /// `--org`: the organization the README's repository badge names.
+    pub org: Option<String>,
+    /// `--license`: the SPDX expression for `[project] license` and CITATION.cff.
+    pub license: Option<String>,
+    /// `--maintainer`s, for `[project] maintainers`, CITATION.cff, and the README.
+    pub maintainers: Vec<Maintainer>,
+    /// Write CITATION.cff (`--citation`).
+    pub citation: bool,
+    /// YYYY-MM-DD stamped into generated files: today, or the manifest's creation date
+    /// when re-rendering, so upgrade and diff see the same contents.
+    pub date: String,
+    /// What kind of project this is (`--template`).
+    pub profile: Profile,
+    /// Patterns appended to .gitignore under `# py-proj: user entries`.
+    pub gitignore_extra: Vec<String>,
+    /// Console-script name for `[project.scripts]`, backed by a generated `cli.py`.
+    pub entry_point: Option<String>,
+    /// PEP 420 namespace the package goes under (`--namespace`): the `src/` files move to
+    /// `src/<namespace>/<import name>/`, imported as `<namespace>.<import name>`.
+    pub namespace: Option<String>,
+    /// Secrets generated into `.env`, as (key, value): `--gen-secret`, and [`DEFAULT_SECRET`]
+    /// for a webapp. Only the keys go in the manifest; a re-render reuses the values in `.env`.
+    pub secrets: Vec<(String, String)>,
+    /// Dotted module paths generated below the package, each with a placeholder test.
+    pub modules: Vec<String>,
+    /// `addopts` for `[tool.pytest.ini_options]`.
+    pub pytest_addopts: Option<String>,
+    /// Registered pytest markers, as `name: description`.
+    pub pytest_markers: Vec<String>,
+    /// Testing libraries set up on top of pytest (`--testing-extras`).
+    pub testing_extras: Vec<TestingExtra>,
+    /// `src/main.py` defines `async def main()`, run by `asyncio.run`, and the tests use
+    /// pytest-asyncio (`--async`). Flat layout only.
+    pub async_main: bool,
+    /// A pytest-benchmark suite in `benchmarks/`, kept out of the normal test run (`--benchmarks`).
+    pub benchmarks: bool,
+    /// `data/{raw,interim,processed}/` and `models/` with `src/paths.py` to find them
+    /// (`--data-layout`, or the datasci profile).
+    pub data_layout: bool,
+    /// A stub DVC pipeline, `dvc.yaml` and `.dvcignore` (`--dvc`).
+    pub dvc: bool,
+    /// Supported `MAJOR.MINOR` versions, oldest first; includes the primary `python`.
+    pub python_matrix: Vec<String>,
+    /// Writes noxfile.py or tox.ini when there is a `python_matrix`.
+    pub matrix_runner: MatrixRunner,
+    /// Whether the dev tools are an extra or a dependency group.
+    pub dep_style: DepStyle,
+    /// Dependency-update bot config to write, if any.
+    pub dep_updates: Option<DepUpdates>,
+    /// Release workflow to write, if any; also makes the project buildable.
+    pub publish: Option<Publish>,
+    /// Git hooks to write and enable, if any.
+    pub git_hooks: Option<GitHooks>,
+    /// Static security scanning the project is configured for.
+    pub security: Option<Security>,
+    /// `Development Status` classifier of the generated pyproject.toml, if any.
+    pub status: Option<Status>,
+    /// The editor whose project files are written.
+    pub ide: Ide,
+    /// The Python pin file written, if any.
+    pub toolchain: Option<Toolchain>,
+    /// Line terminators of the generated files (see `ScaffoldPlan::crlf_for`).
+    pub line_endings: LineEndings,
+    /// [`GLOBAL_TOOLS`] are uv tools rather than dev dependencies.
+    pub tools_global: bool,
+    /// Write [`bootstrap::SCRIPT_PATH`], replaying the commands of the scaffold.
+    pub emit_bootstrap: bool,
+    /// SPDX license identifier for the header of every generated `.py` file.
+    pub license_header: Option<String>,
+    /// The `--summary-file` report, relative to the root, left out of the sdist.
+    pub report_file: Option<String>,
+    /// Pair notebooks with `.py` files through jupytext, optionally git-ignoring the
+    /// `.ipynb` side.
+    pub jupytext: bool,
+    pub jupytext_ignore_ipynb: bool,
+    /// `--minimal`: leave out the [`MINIMAL_SKIPS`] groups not named in `with`.
+    pub minimal: bool,
+    pub with: Vec<String>,
+    /// Absolute path of the multi-root `.code-workspace` file to write, if any.
+    pub code_workspace: Option<PathBuf>,
+    /// Replace a `.venv` built with another Python instead of failing (`--recreate-venv`).
+    pub recreate_venv: bool,
+    /// `uv venv --seed`: pip, setuptools, and wheel in the venv.
+    pub venv_seed: bool,
+    /// `uv venv --prompt`; the project name when not given.
+    pub venv_prompt: Option<String>,
+    /// The venv's directory name under the root (`--venv-dir`); `.venv` when not given.
+    pub venv_dir: Option<String>,
+    /// The notebooks directory's name under the root (`--notebooks-dir`); `Notebooks`
+    /// when not given.
+    pub notebooks_dir: Option<String>,
+    /// Extra arguments appended to the `uv venv` command line (`--uv-arg`).
+    pub uv_args: Vec<String>,
+    /// Globs (relative to the root) of generated files to leave out (`--exclude`).
+    pub exclude: Vec<String>,
+    /// Permission bits for the root and every directory this run creates (`--dir-mode`).
+    pub dir_mode: Option<u32>,
+    /// Permission bits for every generated file (`--file-mode`); see [`generated_mode`].
+    pub file_mode: Option<u32>,
+    /// The language of docstrings, comments, and README prose (`--lang`).
+    pub strings: Strings,
+    /// Files here replace the built-in template with the same relative path.
+    pub templates_dir: Option<PathBuf>,
+    /// Where templates came from: "builtin", "templates-dir", or "template-repo".
+    pub template_profile: String,
+    /// The override dir or repo spec behind `template_profile`, if not built-in.
+    pub template_source: Option<String>,
+    /// User variables from `--var` and the config's `[vars]` table.
+    pub vars: Vars,
+    /// Shell commands run in the project root after a successful scaffold.
+    pub post_hooks: Vec<String>,
+    /// Set when scaffolding a uv workspace member: the workspace root it belongs to.
+    pub workspace_root: Option<PathBuf>,
+    /// Write the files only: no uv steps, so no `.venv`.
+    pub skip_uv: bool,
+    /// Install uv with the official installer if it is missing (never done implicitly).
+    pub bootstrap_uv: bool,
+    /// Stay off the network: no `uv python install`, uv runs with `--offline`.
+    pub offline: bool,
+    /// Passed to every uv step as `--python-preference`.
+    pub python_preference: Option<PythonPreference>,
+    /// Files and directories this run created, in order, for [`ScaffoldPlan::rollback`].
+    created: RefCell<Vec<PathBuf>>,
+    /// The installed Python matching `python`, once asked (see [`ScaffoldPlan::installed_python`]).
+    installed_python: OnceCell<Option<PythonVersion>>,
+    /// Whether this run added the member to the workspace `members` list.
+    registered: Cell<bool>,
+    on_event: EventHandler,
 }
 
 impl ScaffoldPlan {
-    pub fn write_basic_src(&self) -> Result<()> {
-        write(self.root.join("src/__init__.py"), "")?;
-        write(self.root.join("src/main.py"), main_py())?;
-        Ok(())
+    /// Rebuild the plan an existing project was created with, from its manifest.
+    pub fn from_manifest(
+        root: &Path,
+        manifest: &Manifest,
+        on_event: impl Fn(&Event<'_>) + 'static,
+    ) -> Result<ScaffoldPlan> {
+        let opts = &manifest.options;
+        let source = opts.template_source.as_deref();
+        let templates_dir = match (opts.template_profile.as_str(), source) {
+            ("builtin", _) => None,
+            ("templates-dir", Some(dir)) => {
+                let dir = PathBuf::from(dir);
+                if !dir.is_dir() {
+                    bail!(
+                        "templates dir recorded in the manifest no longer exists: {}",
+                        dir.display()
+                    );
+                }
+                Some(dir)
+            }
+            ("template-repo", Some(spec)) => Some(TemplateRepo::parse(spec)?.fetch(false, &on_event)?),
+            (profile, _) => bail!("unsupported template profile in manifest: `{profile}`"),
+        };
+        let vars = opts
+            .vars
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::from(Serde(v.clone()))))
+            .collect();
+        let workspace_root = match opts.layout.as_str() {
+            "member" => Some(workspace::find_root(root)?.with_context(|| {
+                format!("{} is a workspace member but no workspace root was found", root.display())
+            })?),
+            _ => None,
+        };
+        let strings = Strings::load(opts.lang.as_deref().unwrap_or(DEFAULT_LANG), templates_dir.as_deref())?;
+        Ok(ScaffoldPlan {
+            root: root.to_path_buf(),
+            project: opts.project.clone(),
+            python: opts.python.parse().map_err(anyhow::Error::msg)?,
+            description: opts.description.clone(),
+            author: opts.author.clone(),
+            email: opts.email.clone(),
+            repo_url: opts.repo_url.clone(),
+            homepage: opts.homepage.clone(),
+            org: opts.org.clone(),
+            license: opts.license.clone(),
+            maintainers: opts.maintainers.clone(),
+            citation: opts.components.iter().any(|c| c == Group::Citation.name()),
+            date: manifest.tool.created.chars().take(10).collect(),
+            profile: opts.profile,
+            gitignore_extra: opts.gitignore_extra.clone(),
+            entry_point: opts.entry_point.clone(),
+            namespace: opts.namespace.clone(),
+            secrets: recorded_secrets(root, &opts.gen_secrets)?,
+            modules: opts.modules.clone(),
+            pytest_addopts: opts.pytest_addopts.clone(),
+            pytest_markers: opts.pytest_markers.clone(),
+            testing_extras: opts.testing_extras.clone(),
+            async_main: opts.async_main,
+            benchmarks: opts.components.iter().any(|c| c == Group::Benchmarks.name()),
+            data_layout: opts.components.iter().any(|c| c == Group::DataLayout.name()),
+            dvc: opts.components.iter().any(|c| c == Group::Dvc.name()),
+            python_matrix: opts.python_matrix.clone(),
+            matrix_runner: opts.matrix_runner,
+            dep_style: opts.dep_style.unwrap_or_default(),
+            dep_updates: opts.dep_updates,
+            publish: opts.publish,
+            git_hooks: opts.git_hooks,
+            security: opts.security,
+            status: opts.status,
+            ide: opts.ide.unwrap_or_default(),
+            toolchain: opts.toolchain,
+            line_endings: opts.line_endings.unwrap_or_default(),
+            tools_global: opts.tools_global,
+            emit_bootstrap: opts.emit_bootstrap,
+            license_header: opts.license_header.clone(),
+            report_file: opts.report_file.clone(),
+            jupytext: opts.jupytext,
+            jupytext_ignore_ipynb: opts.jupytext_ignore_ipynb,
+            minimal: opts.minimal,
+            with: opts.with.clone(),
+            code_workspace: opts.code_workspace.as_ref().map(|rel| canonicalize_lenient(&root.join(rel))),
+            recreate_venv: false,
+            venv_seed: opts.venv_seed,
+            venv_prompt: opts.venv_prompt.clone(),
+            venv_dir: opts.venv_dir.clone(),
+            notebooks_dir: opts.notebooks_dir.clone(),
+            uv_args: opts.uv_args.clone(),
+            exclude: opts.exclude.clone(),
+            dir_mode: opts.dir_mode.as_deref().map(parse_mode).transpose().map_err(anyhow::Error::msg)?,
+            file_mode: opts.file_mode.as_deref().map(parse_mode).transpose().map_err(anyhow::Error::msg)?,
+            strings,
+            templates_dir,
+            template_profile: opts.template_profile.clone(),
+            template_source: opts.template_source.clone(),
+            vars,
+            post_hooks: Vec::new(),
+            workspace_root,
+            skip_uv: false,
+            bootstrap_uv: false,
+            offline: false,
+            python_preference: None,
+            created: RefCell::default(),
+            installed_python: OnceCell::new(),
+            registered: Cell::default(),
+            on_event: Box::new(on_event),
+        })
     }
 
-    pub fn write_vscode(&self) -> Result<()> {
-        write(self.root.join(".vscode/launch.json"), vscode_launch_json())?;
-        write(
-            self.root.join(".vscode/settings.json"),
-            vscode_settings_json(),
-        )?;
-        write(self.root.join(".vscode/tasks.json"), vscode_tasks_json())?;
-        Ok(())
+    /// Best guess at the plan for a project without a manifest: the `[project] name`
+    /// (or directory name) and description, and the venv's Python (or `.python-version`,
+    /// or the system's).
+    pub fn inferred(root: &Path, on_event: impl Fn(&Event<'_>) + 'static) -> Result<ScaffoldPlan> {
+        let pyproject = fs::read_to_string(root.join("pyproject.toml"))
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Table>(&text).ok());
+        let field = |key: &str| {
+            let value = pyproject.as_ref()?.get("project")?.get(key)?;
+            value.as_str().map(str::to_string)
+        };
+        let description = field("description");
+        let project = field("name")
+            .or_else(|| Some(root.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "project".to_string());
+        let python = venv_python_version(root)
+            .or_else(|| {
+                let pin = fs::read_to_string(root.join(".python-version")).ok()?;
+                Some(pin.trim().to_string()).filter(|p| !p.is_empty())
+            })
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| detect_python(root).0);
+        Ok(ScaffoldPlan {
+            root: root.to_path_buf(),
+            project,
+            python,
+            description,
+            author: None,
+            email: None,
+            repo_url: None,
+            homepage: None,
+            org: None,
+            license: None,
+            maintainers: Vec::new(),
+            citation: root.join("CITATION.cff").is_file(),
+            date: today(),
+            profile: Profile::Default,
+            gitignore_extra: Vec::new(),
+            entry_point: None,
+            namespace: None,
+            secrets: Vec::new(),
+            modules: Vec::new(),
+            pytest_addopts: None,
+            pytest_markers: Vec::new(),
+            testing_extras: match root.join("tests").join("test_properties.py").is_file() {
+                true => vec![TestingExtra::Hypothesis],
+                false => Vec::new(),
+            },
+            async_main: fs::read_to_string(root.join("src").join("main.py"))
+                .is_ok_and(|text| text.contains("async def main(")),
+            benchmarks: root.join("benchmarks").join("benchmark_example.py").is_file(),
+            data_layout: root.join("src").join("paths.py").is_file() && root.join("data").join("raw").is_dir(),
+            dvc: root.join("dvc.yaml").is_file(),
+            python_matrix: Vec::new(),
+            matrix_runner: MatrixRunner::Nox,
+            dep_style: match pyproject.as_ref().and_then(|t| t.get("dependency-groups")?.get("dev")) {
+                Some(_) => DepStyle::Groups,
+                None => DepStyle::Extras,
+            },
+            dep_updates: None,
+            publish: root.join(".github/workflows/release.yml").is_file().then_some(Publish::Pypi),
+            git_hooks: root.join(GIT_HOOKS_DIR).join("pre-commit").is_file().then_some(GitHooks::Native),
+            security: pyproject.as_ref().and_then(|t| {
+                let tool = t.get("tool")?;
+                match tool.get("bandit") {
+                    Some(_) => Some(Security::Bandit),
+                    None => {
+                        let select = tool.get("ruff")?.get("lint")?.get("extend-select")?.as_array()?;
+                        select.iter().any(|s| s.as_str() == Some("S")).then_some(Security::RuffS)
+                    }
+                }
+            }),
+            status: pyproject.as_ref().and_then(|t| {
+                let classifiers = t.get("project")?.get("classifiers")?.as_array()?;
+                let classifiers: Vec<&str> = classifiers.iter().filter_map(|c| c.as_str()).collect();
+                [Status::Alpha, Status::Beta, Status::Stable].into_iter().find(|s| classifiers.contains(&s.classifier()))
+            }),
+            ide: match (root.join(".vscode").is_dir(), root.join(NVIM_EXRC).is_file()) {
+                (false, true) => Ide::Neovim,
+                _ => Ide::Vscode,
+            },
+            toolchain: None,
+            line_endings: LineEndings::Lf,
+            tools_global: false,
+            emit_bootstrap: root.join(bootstrap::SCRIPT_PATH).is_file(),
+            license_header: None,
+            report_file: None,
+            jupytext: false,
+            jupytext_ignore_ipynb: false,
+            minimal: false,
+            with: Vec::new(),
+            code_workspace: None,
+            recreate_venv: false,
+            venv_seed: false,
+            venv_prompt: None,
+            venv_dir: None,
+            notebooks_dir: None,
+            uv_args: Vec::new(),
+            exclude: Vec::new(),
+            dir_mode: None,
+            file_mode: None,
+            strings: Strings::english(),
+            templates_dir: None,
+            template_profile: "builtin".to_string(),
+            template_source: None,
+            vars: Vars::new(),
+            post_hooks: Vec::new(),
+            workspace_root: None,
+            skip_uv: false,
+            bootstrap_uv: false,
+            offline: false,
+            python_preference: None,
+            created: RefCell::default(),
+            installed_python: OnceCell::new(),
+            registered: Cell::default(),
+            on_event: Box::new(on_event),
+        })
     }
 
-    pub fn write_envs(&self) -> Result<()> {
-        write(self.root.join(".env"), dotenv())?;
-        write(self.root.join(".envrc"), envrc())?;
-        Ok(())
+    /// Registry templates that apply to this plan (members get only their own package
+    /// files; CITATION.cff only with `citation`, cli.py only with `entry_point`, the
+    /// `matrix_runner`'s file only with a `python_matrix`, bot configs per `dep_updates`).
+    fn templates(&self) -> impl Iterator<Item = &'static Template> + '_ {
+        let skipped = self.skipped();
+        REGISTRY.iter().filter(move |t| !skipped.contains(&t.group)).filter(|t| match t.group {
+            Group::Citation => self.citation,
+            Group::Cli => self.entry_point.is_some() && self.workspace_root.is_none(),
+            Group::Nox | Group::Tox => {
+                let runner = match t.group {
+                    Group::Nox => MatrixRunner::Nox,
+                    _ => MatrixRunner::Tox,
+                };
+                !self.python_matrix.is_empty() && self.matrix_runner == runner && self.workspace_root.is_none()
+            }
+            Group::Dependabot => self.dep_updates == Some(DepUpdates::Dependabot) && self.workspace_root.is_none(),
+            Group::Renovate => self.dep_updates == Some(DepUpdates::Renovate) && self.workspace_root.is_none(),
+            Group::Release => self.publish == Some(Publish::Pypi) && self.workspace_root.is_none(),
+            Group::GitHooks => self.git_hooks == Some(GitHooks::Native) && self.workspace_root.is_none(),
+            Group::Hypothesis => self.testing_extras.contains(&TestingExtra::Hypothesis),
+            Group::Benchmarks => self.benchmarks,
+            Group::DataLayout => self.data_layout && self.workspace_root.is_none(),
+            Group::Dvc => self.dvc && self.workspace_root.is_none(),
+            Group::Webapp => self.profile == Profile::Webapp && self.workspace_root.is_none(),
+            Group::Tests => (self.minimal || self.async_main) && self.workspace_root.is_none(),
+            Group::Notebook => self.jupytext && self.workspace_root.is_none(),
+            Group::Vscode => self.ide == Ide::Vscode && self.workspace_root.is_none(),
+            Group::Neovim => self.ide == Ide::Neovim && self.workspace_root.is_none(),
+            Group::Toolchain => self.toolchain.is_some_and(|tc| tc.file() == t.path) && self.workspace_root.is_none(),
+            Group::Pyproject | Group::Readme | Group::Changelog => true,
+            _ => self.workspace_root.is_none(),
+        })
     }
 
-    pub fn write_pyrefly(&self) -> Result<()> {
-        write(
-            self.root.join("pyrefly.toml"),
-            pyrefly_toml(&self.project, &self.py_full),
-        )?;
-        Ok(())
+    /// Whether the scaffold writes the built-in template registered at `path` (`.envrc`),
+    /// i.e. neither its group nor an `--exclude` leaves it out.
+    pub fn writes(&self, path: &str) -> bool {
+        self.templates().any(|t| t.path == path) && !self.excluded(&self.relocated(path))
     }
 
-    pub fn write_pyright(&self) -> Result<()> {
-        write(
-            self.root.join("pyrightconfig.json"),
-            pyrightconfig_json(&self.mm),
-        )?;
-        Ok(())
+    /// The [`MINIMAL_SKIPS`] groups this plan leaves out: none without `minimal`, and
+    /// never one named in `with` (or the notebooks, with `jupytext`).
+    pub fn skipped(&self) -> Vec<Group> {
+        if !self.minimal {
+            return Vec::new();
+        }
+        MINIMAL_SKIPS
+            .iter()
+            .copied()
+            .filter(|g| !self.with.iter().any(|w| w == g.name()))
+            .filter(|g| !(*g == Group::Notebook && self.jupytext))
+            .collect()
     }
 
-    pub fn write_pyproject(&self) -> Result<()> {
-        write(
-            self.root.join("pyproject.toml"),
-            pyproject_toml(&self.project, &self.mm, &self.mm_nodec),
-        )?;
-        Ok(())
+    /// Names of the template groups this plan writes, in registry order.
+    fn components(&self) -> Vec<String> {
+        let mut components: Vec<String> = Vec::new();
+        for t in self.templates() {
+            if !components.iter().any(|c| c == t.group.name()) {
+                components.push(t.group.name().to_string());
+            }
+        }
+        components
     }
 
-    pub fn write_gitignore(&self) -> Result<()> {
-        write(self.root.join(".gitignore"), gitignore())?;
-        Ok(())
+    /// The src-layout package of a workspace member (the flat `src/` package is not
+    /// importable side by side with other members), with its cli.py under `entry_point`.
+    fn member_files(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        if self.workspace_root.is_none() {
+            return Ok(Vec::new());
+        }
+        let package = format!("src/{}", import_name(&self.project));
+        // The property tests and benchmarks need something to exercise; a member has no
+        // main.py to hold it.
+        let init = match self.testing_extras.contains(&TestingExtra::Hypothesis) || self.benchmarks {
+            true => render(&format!("{package}/__init__.py"), clamp_py(), &self.context())?.into_bytes(),
+            false => Vec::new(),
+        };
+        let mut files = vec![(format!("{package}/__init__.py"), init)];
+        if self.entry_point.is_some() {
+            let path = format!("{package}/cli.py");
+            files.push((path.clone(), render(&path, cli_py(), &self.context())?.into_bytes()));
+            let path = format!("{package}/__main__.py");
+            files.push((path.clone(), render(&path, dunder_main_py(), &self.context())?.into_bytes()));
+        }
+        Ok(files)
+    }
+
+    /// The `modules`, their parent packages, and a placeholder test for each, rendered
+    /// like `add-module` does. Only the flat layout has the app_logging package.
+    fn module_files(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        let (dir, package) = (self.package_dir(), self.package());
+        let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+        for module in &self.modules {
+            let parts = validate_module(module)?;
+            let (leaf, parents) = parts.split_last().expect("validated non-empty");
+            let mut pkg_dir = dir.clone();
+            for parent in parents {
+                pkg_dir = format!("{pkg_dir}/{parent}");
+                let init = format!("{pkg_dir}/__init__.py");
+                if !files.iter().any(|(p, _)| *p == init) {
+                    files.push((init, Vec::new()));
+                }
+            }
+            let mut parent_import = vec![package.as_str()];
+            parent_import.extend(parents.iter().copied());
+            let test_name = parts.join("_");
+            let mut ctx = Vars::new();
+            ctx.insert("module".into(), Value::from(module.as_str()));
+            ctx.insert("package".into(), Value::from(package.as_str()));
+            ctx.insert("parent_import".into(), Value::from(parent_import.join(".")));
+            ctx.insert("leaf".into(), Value::from(*leaf));
+            ctx.insert("test_name".into(), Value::from(test_name.as_str()));
+            let logger = self.workspace_root.is_none() && !self.skipped().contains(&Group::AppLogging);
+            ctx.insert("logger_import".into(), Value::from(logger));
+            let path = format!("{pkg_dir}/{leaf}.py");
+            files.push((path.clone(), render(&path, module_py(), &ctx)?.into_bytes()));
+            let path = format!("tests/test_{test_name}.py");
+            files.push((path.clone(), render(&path, module_test_py(), &ctx)?.into_bytes()));
+        }
+        Ok(files)
+    }
+
+    /// Directories every scaffold of this plan starts with.
+    pub fn dirs(&self) -> Vec<String> {
+        match self.workspace_root {
+            Some(_) => vec![format!("src/{}", import_name(&self.project)), "tests".to_string()],
+            None => {
+                let skipped = self.skipped();
+                [("src", None), ("tests", None), (DEFAULT_NOTEBOOKS_DIR, Some(Group::Notebook)), (".vscode", Some(Group::Vscode)), ("src/app_logging", Some(Group::AppLogging))]
+                    .into_iter()
+                    .filter(|(_, group)| group.is_none_or(|g| !skipped.contains(&g)))
+                    .filter(|(_, group)| *group != Some(Group::Vscode) || self.ide == Ide::Vscode)
+                    .map(|(dir, _)| self.relocated(dir))
+                    .collect()
+            }
+        }
+    }
+
+    /// The [`dirs`](ScaffoldPlan::dirs) a scaffold writing `files` creates: a directory
+    /// whose files were all excluded is left out with them.
+    fn created_dirs(&self, files: &[(String, Vec<u8>, Origin)]) -> Vec<String> {
+        let emptied = |dir: &String| {
+            let prefix = format!("{dir}/");
+            self.excluded(dir) || !self.exclude.is_empty()
+                && !files.iter().any(|(p, _, _)| p.starts_with(&prefix))
+                && self.all_files().is_ok_and(|all| all.iter().any(|(p, _, _)| p.starts_with(&prefix)))
+        };
+        self.dirs().into_iter().filter(|d| !emptied(d)).collect()
+    }
+
+    /// Every step of the scaffold, in order, computed without touching the disk.
+    /// Rendering happens here, so a broken template fails before anything is written.
+    /// With `manifest`, the last action writes `.pyproj.toml`. Post-create hooks are
+    /// not part of the plan; they run only after it was applied successfully.
+    pub fn plan(&self, manifest: bool) -> Result<Vec<PlannedAction>> {
+        let files = self.files()?;
+        let mut actions: Vec<PlannedAction> = self
+            .created_dirs(&files)
+            .into_iter()
+            .map(|d| PlannedAction::CreateDir { path: d.into() })
+            .collect();
+        let manifest = match manifest {
+            true => Some(self.manifest_file(&files)?),
+            false => None,
+        };
+        for (path, contents, origin) in files {
+            check_syntax(&path, &contents)?;
+            actions.push(PlannedAction::WriteFile {
+                path: path.into(),
+                contents,
+                origin,
+            });
+        }
+        if let Some(ws) = &self.workspace_root {
+            actions.push(PlannedAction::RegisterMember {
+                workspace: ws.clone(),
+                member: workspace::member_path(ws, &self.root)?,
+            });
+        }
+        if let Some(path) = &self.code_workspace {
+            let contents = self.code_workspace_file(path)?;
+            check_syntax(&path.to_string_lossy(), &contents)?;
+            actions.push(PlannedAction::WriteCodeWorkspace {
+                path: relative_path(&canonicalize_lenient(&self.root), path),
+                contents,
+            });
+        }
+        actions.extend(self.uv_actions());
+        if git_work_tree(&self.root).is_some() {
+            actions.extend(self.git_hooks_action());
+        }
+        if let Some(contents) = manifest {
+            actions.push(PlannedAction::WriteFile {
+                path: MANIFEST_FILE.into(),
+                contents,
+                origin: Origin::Manifest,
+            });
+        }
+        Ok(actions)
+    }
+
+    /// The files `actions` would write that already exist with other contents. The
+    /// manifest is left out; it is always rewritten. With `replace_pyproject` false, an
+    /// existing pyproject.toml is compared with what merging into it would give.
+    pub fn conflicts(&self, actions: &[PlannedAction], replace_pyproject: bool) -> Result<Vec<Conflict>> {
+        let mut conflicts = Vec::new();
+        for action in actions {
+            let (path, contents, origin) = match action {
+                PlannedAction::WriteFile { path, contents, origin } => (path, contents, origin),
+                PlannedAction::WriteCodeWorkspace { path, contents } => (path, contents, &Origin::BuiltIn),
+                _ => continue,
+            };
+            let Ok(current) = fs::read(self.root.join(path)) else {
+                continue;
+            };
+            let new = match replace_pyproject {
+                false => self.merged_pyproject(path, contents, *origin)?.map(|m| m.text.into_bytes()),
+                true => None,
+            };
+            let new = new.unwrap_or_else(|| contents.clone());
+            if *origin != Origin::Manifest && current != new {
+                conflicts.push((path.clone(), current, new));
+            }
+        }
+        Ok(conflicts)
+    }
+
+    /// The generated pyproject.toml merged into the one already at the root, when there
+    /// is one and it differs; `None` for any other file.
+    fn merged_pyproject(&self, path: &Path, contents: &[u8], origin: Origin) -> Result<Option<pyproject::Merge>> {
+        if path != Path::new("pyproject.toml") || origin == Origin::Manifest {
+            return Ok(None);
+        }
+        let Ok(current) = fs::read_to_string(self.root.join(path)) else {
+            return Ok(None);
+        };
+        if current.as_bytes() == contents {
+            return Ok(None);
+        }
+        pyproject::merge(&current, &String::from_utf8_lossy(contents))
+            .context("cannot merge into the existing pyproject.toml; fix it or pass --pyproject-replace")
+            .map(Some)
+    }
+
+    /// Apply `actions` in order, recording what they create for [`ScaffoldPlan::rollback`].
+    /// `policy` decides what happens to existing files whose content differs; the
+    /// manifest is always rewritten.
+    pub fn execute(&self, actions: &[PlannedAction], policy: WritePolicy) -> Result<WriteSummary> {
+        self.execute_with(actions, policy, &BTreeMap::new(), true)
+    }
+
+    /// [`ScaffoldPlan::execute`], with `policy` overridden for the paths in `file_policies`.
+    /// Unless `replace_pyproject`, an existing pyproject.toml that would be replaced is
+    /// merged into instead.
+    ///
+    /// A `background` command starts first, on its own thread with its output buffered,
+    /// and is waited for (its output replayed) when its turn comes. A failure on either
+    /// side stops the other: the command is cancelled, or the actions stop early.
+    fn execute_with(
+        &self,
+        actions: &[PlannedAction],
+        policy: WritePolicy,
+        file_policies: &BTreeMap<PathBuf, WritePolicy>,
+        replace_pyproject: bool,
+    ) -> Result<WriteSummary> {
+        let cancel = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let mut pending = None;
+            let background = actions
+                .iter()
+                .find(|a| matches!(a, PlannedAction::RunCommand { background: true, .. }));
+            if let Some(PlannedAction::RunCommand { program, args, cwd, .. }) = background {
+                self.create_dir(cwd)?;
+                let cancel = &cancel;
+                pending = Some(scope.spawn(move || {
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    let started = Instant::now();
+                    let mut lines = Vec::new();
+                    let result = run_cancellable(program, &args, cwd, |line| lines.push(line.to_string()), cancel);
+                    Buffered {
+                        result,
+                        lines,
+                        elapsed: started.elapsed(),
+                    }
+                }));
+            }
+            let result = self.apply(actions, policy, file_policies, replace_pyproject, &mut pending);
+            // Still running only when the actions failed before its turn.
+            if let Some(handle) = pending {
+                cancel.store(true, Ordering::SeqCst);
+                let _ = handle.join();
+            }
+            result
+        })
+    }
+
+    /// The body of [`ScaffoldPlan::execute_with`]: each action in order, taking the
+    /// background command's outcome from `pending` at its turn.
+    fn apply<'s>(
+        &self,
+        actions: &[PlannedAction],
+        policy: WritePolicy,
+        file_policies: &BTreeMap<PathBuf, WritePolicy>,
+        replace_pyproject: bool,
+        pending: &mut Option<thread::ScopedJoinHandle<'s, Buffered>>,
+    ) -> Result<WriteSummary> {
+        let mut summary = WriteSummary::default();
+        let steps = actions
+            .iter()
+            .filter(|a| matches!(a, PlannedAction::RunCommand { .. }))
+            .count();
+        let mut step = 0;
+        let mut finished = None;
+        let mut written = BTreeMap::new();
+        for (i, action) in actions.iter().enumerate() {
+            check_interrupt()?;
+            if let Some(handle) = pending.take_if(|h| h.is_finished()) {
+                let done = join(handle);
+                let failed = done.result.is_err();
+                finished = Some(done);
+                if failed {
+                    // Report it now, in its place among the steps, rather than write on;
+                    // a background command is never optional, so this returns the error.
+                    if let Some(at) = actions[i..].iter().position(|a| matches!(a, PlannedAction::RunCommand { background: true, .. })) {
+                        let skipped = actions[i..i + at].iter().filter(|a| matches!(a, PlannedAction::RunCommand { .. })).count();
+                        self.run_command(&actions[i + at], step + skipped + 1, steps, &mut finished, pending, &mut summary)?;
+                    }
+                }
+            }
+            let started = Instant::now();
+            let mut elapsed = None;
+            match action {
+                PlannedAction::CreateDir { path } => self.create_dir(&self.root.join(path))?,
+                PlannedAction::WriteFile { path, origin, .. } => {
+                    if !written.contains_key(&i) {
+                        let batch = Instant::now();
+                        written = self.write_batch(&actions[i..], i, policy, file_policies, replace_pyproject)?;
+                        let summed: Duration = written.values().map(|w: &Written| w.elapsed).sum();
+                        summary.parallel_saving += summed.saturating_sub(batch.elapsed());
+                    }
+                    let Written {
+                        outcome,
+                        merged,
+                        elapsed: took,
+                    } = written.remove(&i).expect("every file in the batch is written");
+                    elapsed = Some(took);
+                    let source = match origin {
+                        Origin::Override | Origin::Extra => {
+                            self.templates_dir.as_ref().map(|d| d.join(path))
+                        }
+                        _ => None,
+                    };
+                    self.emit(&Event::FileWritten {
+                        path,
+                        origin: *origin,
+                        source: source.as_deref(),
+                        outcome: &outcome,
+                    });
+                    if let Some(merge) = merged.filter(|m| !m.is_noop()) {
+                        self.emit(&Event::PyprojectMerged {
+                            added: &merge.added,
+                            replaced: &merge.replaced,
+                        });
+                    }
+                    summary.record(path, &outcome);
+                }
+                PlannedAction::RunCommand { .. } => {
+                    step += 1;
+                    elapsed = self.run_command(action, step, steps, &mut finished, pending, &mut summary)?;
+                }
+                PlannedAction::RegisterMember { workspace, member } => {
+                    self.registered.set(workspace::add_member(workspace, member)?);
+                    if self.registered.get() {
+                        self.emit(&Event::WorkspaceUpdated {
+                            what: "workspace members",
+                        });
+                    }
+                    if workspace::add_vscode_paths(workspace, member)? {
+                        self.emit(&Event::WorkspaceUpdated {
+                            what: "workspace .vscode/settings.json",
+                        });
+                    }
+                }
+                PlannedAction::RemoveDir { path } => {
+                    if let Some((path, e)) = remove_tree(&self.root.join(path)).into_iter().next() {
+                        return Err(e).with_context(|| format!("failed to remove {}", path.display()));
+                    }
+                }
+                PlannedAction::WriteCodeWorkspace { path, contents } => {
+                    let policy = file_policies.get(path).copied().unwrap_or(policy);
+                    let outcome = self.write_file(path, contents, policy)?;
+                    self.emit(&Event::FileWritten {
+                        path,
+                        origin: Origin::BuiltIn,
+                        source: None,
+                        outcome: &outcome,
+                    });
+                    summary.record(path, &outcome);
+                }
+            }
+            summary.time(action.phase(), elapsed.unwrap_or_else(|| started.elapsed()));
+        }
+        Ok(summary)
+    }
+
+    /// Run (or, if it is the background one, wait for) the command `action`, with its
+    /// progress events. Returns the background command's own run time, which is what
+    /// its phase is timed at.
+    fn run_command<'s>(
+        &self,
+        action: &PlannedAction,
+        step: usize,
+        steps: usize,
+        finished: &mut Option<Buffered>,
+        pending: &mut Option<thread::ScopedJoinHandle<'s, Buffered>>,
+        summary: &mut WriteSummary,
+    ) -> Result<Option<Duration>> {
+        let PlannedAction::RunCommand {
+            label,
+            program,
+            args,
+            cwd,
+            optional,
+            background,
+        } = action
+        else {
+            return Ok(None);
+        };
+        let command = quote_argv(program, args);
+        self.emit(&Event::CommandStarted {
+            label,
+            command: &command,
+            step,
+            steps,
+        });
+        if *cwd == self.root {
+            // uv creates the venv; it is ours to roll back if it was not there.
+            self.track(&self.venv_path());
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let started = Instant::now();
+        let (result, own) = match background {
+            true => {
+                let done = finished
+                    .take()
+                    .or_else(|| pending.take().map(join))
+                    .expect("the background command is waited for once");
+                for line in &done.lines {
+                    self.emit(&Event::CommandOutput { program, line });
+                }
+                summary.overlapped = done.elapsed.saturating_sub(started.elapsed());
+                (done.result, Some(done.elapsed))
+            }
+            false => {
+                let result = run_streaming(program, &args, cwd, |line| {
+                    self.emit(&Event::CommandOutput { program, line })
+                });
+                (result, None)
+            }
+        };
+        self.emit(&Event::CommandFinished {
+            success: result.is_ok(),
+            elapsed: own.unwrap_or_else(|| started.elapsed()),
+            exit_code: exit_code(&result),
+        });
+        match result {
+            Err(e) if *optional => {
+                let name = Path::new(program).file_stem().unwrap_or_default().to_string_lossy();
+                let reason = e.to_string().lines().next().unwrap_or_default().to_string();
+                self.warn(format!("`{name} {}` failed ({reason}); carrying on", args.join(" ")));
+            }
+            result => {
+                result?;
+            }
+        }
+        Ok(own)
+    }
+
+    /// Every file the scaffold writes, as (relative path, contents), in write order.
+    pub fn render_files(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        Ok(self.files()?.into_iter().map(|(path, bytes, _)| (path, bytes)).collect())
+    }
+
+    /// Every file the plan writes, minus the `exclude`d ones.
+    fn files(&self) -> Result<Vec<(String, Vec<u8>, Origin)>> {
+        let mut files = self.all_files()?;
+        files.retain(|(path, _, _)| !self.excluded(path));
+        Ok(files)
+    }
+
+    /// Whether the text file at `path` gets CRLF line endings: Makefiles, shell scripts,
+    /// and git hooks never do, batch files always do, and the rest follow `line_endings`.
+    /// `None` for notebooks, whose JSON is left as rendered.
+    fn crlf_for(&self, path: &str) -> Option<bool> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("ipynb") => None,
+            Some("bat" | "cmd") => Some(true),
+            Some("sh" | "envrc") => Some(false),
+            _ if name == "Makefile" || path.starts_with(".githooks/") => Some(false),
+            _ => Some(self.line_endings.crlf()),
+        }
+    }
+
+    fn excluded(&self, path: &str) -> bool {
+        self.exclude.iter().any(|pattern| glob_matches(pattern, path))
+    }
+
+    /// Warnings about `exclude`: patterns that match nothing (likely typos), and kept
+    /// files that mention an excluded one (they may break without it).
+    fn exclude_warnings(&self) -> Result<Vec<String>> {
+        if self.exclude.is_empty() {
+            return Ok(Vec::new());
+        }
+        let files = self.all_files()?;
+        let mut warnings = Vec::new();
+        for pattern in &self.exclude {
+            if !files.iter().any(|(path, _, _)| glob_matches(pattern, path)) {
+                warnings.push(format!("--exclude `{pattern}` matches no generated file"));
+            }
+        }
+        for (path, _, _) in files.iter().filter(|(path, _, _)| self.excluded(path)) {
+            let needles = reference_needles(path);
+            let dependents: Vec<&str> = files
+                .iter()
+                .filter(|(other, _, _)| !self.excluded(other) && other != ".gitignore")
+                .filter(|(_, bytes, _)| {
+                    let text = String::from_utf8_lossy(bytes);
+                    needles.iter().any(|needle| mentions(&text, needle))
+                })
+                .map(|(other, _, _)| other.as_str())
+                .collect();
+            if !dependents.is_empty() {
+                warnings.push(format!("excluding {path}, which {} still reference(s)", dependents.join(", ")));
+            }
+        }
+        Ok(warnings)
     }
 
-    pub fn write_readme(&self) -> Result<()> {
-        write(self.root.join("README.md"), readme_md(&self.project))?;
+    fn all_files(&self) -> Result<Vec<(String, Vec<u8>, Origin)>> {
+        let mut files = Vec::new();
+        for t in self.templates() {
+            let origin = match self.override_dir_for(t) {
+                Some(_) => Origin::Override,
+                None => Origin::BuiltIn,
+            };
+            files.push((self.relocated(t.path), self.render_template(t)?, origin));
+        }
+        for (path, bytes) in self.member_files()?.into_iter().chain(self.module_files()?) {
+            if files.iter().any(|(p, _, _)| *p == path) {
+                bail!("--modules would overwrite the generated {path}; pick another module name");
+            }
+            files.push((path, bytes, Origin::BuiltIn));
+        }
+        // Jupytext wants its front matter first in a paired notebook, and an empty
+        // `__init__.py` stays empty.
+        let notebooks = format!("{}/", self.notebooks_dir());
+        for (path, bytes, origin) in files.iter_mut() {
+            if *origin == Origin::BuiltIn && takes_provenance_stamp(path) && !path.starts_with(&notebooks) && !bytes.is_empty() {
+                *bytes = add_provenance_stamp(&String::from_utf8_lossy(bytes)).into_bytes();
+            }
+        }
+        if let Some(spdx) = &self.license_header {
+            let holder = self.license_holder();
+            for (path, bytes, origin) in files.iter_mut() {
+                // Jupytext wants its front matter first in a paired notebook.
+                if path.ends_with(".py") && !path.starts_with(&notebooks) && *origin == Origin::BuiltIn {
+                    let text = String::from_utf8_lossy(bytes);
+                    *bytes = add_license_header(&text, spdx, &self.date[..4], &holder)?.into_bytes();
+                }
+            }
+        }
+        if self.emit_bootstrap {
+            let script = bootstrap::script(&self.project, &self.bootstrap_steps());
+            files.push((bootstrap::SCRIPT_PATH.to_string(), script.into_bytes(), Origin::BuiltIn));
+        }
+        if let Some(dir) = &self.templates_dir {
+            for rel in self.extra_paths()? {
+                let bytes = self.read_override(&dir.join(&rel), &rel)?;
+                files.push((rel, bytes, Origin::Extra));
+            }
+        }
+        for (path, bytes, _) in files.iter_mut() {
+            if let Some(crlf) = self.crlf_for(path).filter(|_| std::str::from_utf8(bytes).is_ok()) {
+                *bytes = normalize_line_endings(bytes, crlf);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Who the `--license-header` copyright line names: the author, else the project's.
+    fn license_holder(&self) -> String {
+        license_holder(self.author.as_deref(), &self.project)
+    }
+
+    /// The uv steps: `uv sync` at the workspace root for members, else install + venv
+    /// (nothing when the project already has a `.venv`).
+    fn uv_actions(&self) -> Vec<PlannedAction> {
+        self.uv_steps(false)
+    }
+
+    /// [`ScaffoldPlan::uv_actions`], or with `replay` the same steps for a machine that
+    /// has none of this one's state: Python, venv, and uv tools are all installed anew.
+    fn uv_steps(&self, replay: bool) -> Vec<PlannedAction> {
+        if self.skip_uv {
+            return Vec::new();
+        }
+        let uv = uv_bin();
+        let py = self.python.to_string();
+        let step = |label: String, args: &[&str], cwd: &Path, optional: bool| {
+            let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            if self.offline {
+                args.insert(1, "--offline".to_string());
+            }
+            if let Some(preference) = self.python_preference {
+                args.splice(1..1, ["--python-preference".to_string(), preference.name().to_string()]);
+            }
+            PlannedAction::RunCommand {
+                label,
+                program: uv.clone(),
+                args,
+                cwd: cwd.to_path_buf(),
+                optional,
+                background: false,
+            }
+        };
+        let mut steps = Vec::new();
+        if let Some(ws) = &self.workspace_root {
+            // One venv for the whole workspace, at its root.
+            steps.push(step(
+                "🔗 Syncing uv workspace …".to_string(),
+                &["sync", "--python", &py],
+                ws,
+                false,
+            ));
+        } else if replay || !self.keeps_venv() {
+            if !replay && self.venv_path().is_dir() {
+                steps.push(PlannedAction::RemoveDir {
+                    path: PathBuf::from(self.venv_dir()),
+                });
+            }
+            if !self.offline && (replay || !self.python_installed()) {
+                let mut install = step(
+                    format!("⚙️  Installing Python {py} via uv …"),
+                    &["python", "install", &py],
+                    &self.root,
+                    false,
+                );
+                // The download dominates a cold scaffold; the file writes can go on meanwhile.
+                if let PlannedAction::RunCommand { background, .. } = &mut install {
+                    *background = true;
+                }
+                steps.push(install);
+            }
+            let args = self.venv_args();
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            steps.push(step("🧪 Creating uv venv …".to_string(), &args, &self.root, false));
+        }
+        if self.tools_global {
+            let installed = match replay {
+                true => Vec::new(),
+                false => uv_tools().unwrap_or_default(),
+            };
+            for tool in GLOBAL_TOOLS.iter().filter(|t| !installed.iter().any(|i| i == *t)) {
+                steps.push(step(
+                    format!("🧰 Installing {tool} as a uv tool …"),
+                    &["tool", "install", tool],
+                    &self.root,
+                    true,
+                ));
+            }
+        }
+        steps
+    }
+
+    /// `git config core.hooksPath` for `--git-hooks native`. Git takes the path relative to
+    /// the top of the work tree, which may be above the project.
+    fn git_hooks_action(&self) -> Option<PlannedAction> {
+        self.git_hooks?;
+        let root = canonicalize_lenient(&self.root);
+        let top = git_work_tree(&root).unwrap_or_else(|| root.clone());
+        let hooks = relative_path(&top, &root.join(GIT_HOOKS_DIR));
+        Some(PlannedAction::RunCommand {
+            label: "🪝 Enabling the git hooks …".to_string(),
+            program: "git".to_string(),
+            args: vec![
+                "config".to_string(),
+                "core.hooksPath".to_string(),
+                hooks.to_string_lossy().replace('\\', "/"),
+            ],
+            cwd: self.root.clone(),
+            optional: true,
+            background: false,
+        })
+    }
+
+    /// What `--emit-bootstrap` replays: the uv steps as a fresh machine needs them, then
+    /// the post-create hooks with the environment [`ScaffoldPlan::run_post_hooks`] gives them.
+    pub fn bootstrap_steps(&self) -> Vec<bootstrap::Step> {
+        let root = std::path::absolute(&self.root).unwrap_or_else(|_| self.root.clone());
+        let mut steps: Vec<bootstrap::Step> = self
+            .uv_steps(true)
+            .into_iter()
+            .chain(self.git_hooks_action())
+            .filter_map(|action| match action {
+                PlannedAction::RunCommand {
+                    program,
+                    args,
+                    cwd,
+                    optional,
+                    ..
+                } => Some(bootstrap::Step::Command {
+                    program: Path::new(&program).file_stem()?.to_string_lossy().into_owned(),
+                    args,
+                    cwd: relative_path(&root, &std::path::absolute(&cwd).unwrap_or(cwd)),
+                    optional,
+                }),
+                _ => None,
+            })
+            .collect();
+        let python = self.python.to_string();
+        let env = [
+            ("PY_PROJ_NAME", self.project.as_str()),
+            ("PY_PROJ_PYTHON", python.as_str()),
+        ];
+        steps.extend(self.post_hooks.iter().map(|hook| bootstrap::Step::Hook {
+            command: hook.clone(),
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }));
+        steps
+    }
+
+    /// The arguments of `uv venv` for a flat project: the Python, the prompt, `--seed`,
+    /// then the `--uv-arg`s as given.
+    pub fn venv_args(&self) -> Vec<String> {
+        let mut args = vec!["venv".to_string(), "--python".to_string(), self.python.to_string()];
+        args.extend(["--prompt".to_string(), self.prompt().to_string()]);
+        if self.venv_seed {
+            args.push("--seed".to_string());
+        }
+        args.push(self.venv_dir().to_string());
+        args.extend(self.uv_args.iter().cloned());
+        args
+    }
+
+    /// The venv's directory name under the root.
+    pub fn venv_dir(&self) -> &str {
+        self.venv_dir.as_deref().unwrap_or(DEFAULT_VENV_DIR)
+    }
+
+    /// The notebooks directory's name under the root.
+    pub fn notebooks_dir(&self) -> &str {
+        self.notebooks_dir.as_deref().unwrap_or(DEFAULT_NOTEBOOKS_DIR)
+    }
+
+    fn venv_path(&self) -> PathBuf {
+        self.root.join(self.venv_dir())
+    }
+
+    /// The venv's shell prompt name.
+    pub fn prompt(&self) -> &str {
+        self.venv_prompt.as_deref().unwrap_or(&self.project)
+    }
+
+    /// The distribution name: `<namespace>-<project>` under a namespace.
+    pub fn dist_name(&self) -> String {
+        match &self.namespace {
+            Some(ns) => dist_name(&format!("{ns}-{}", self.project)),
+            None => dist_name(&self.project),
+        }
+    }
+
+    /// The import path of the package: `src` in the flat layout, `<namespace>.<import name>`
+    /// with a namespace, the import name in a workspace member.
+    pub fn package(&self) -> String {
+        match (&self.workspace_root, &self.namespace) {
+            (Some(_), _) => import_name(&self.project),
+            (None, Some(ns)) => format!("{ns}.{}", import_name(&self.project)),
+            (None, None) => "src".to_string(),
+        }
+    }
+
+    /// What `python -m` runs: the package itself, through its `__main__.py`, except in the
+    /// flat layout, which keeps running `src.main`.
+    pub fn run_module(&self) -> String {
+        match (&self.workspace_root, &self.namespace) {
+            (None, None) => "src.main".to_string(),
+            _ => self.package(),
+        }
+    }
+
+    /// The directory of [`ScaffoldPlan::package`], relative to the root.
+    pub fn package_dir(&self) -> String {
+        match self.workspace_root {
+            Some(_) => format!("src/{}", import_name(&self.project)),
+            None => format!("src{}", self.namespace_prefix()),
+        }
+    }
+
+    /// `/<namespace>/<import name>` under a namespace, else nothing.
+    fn namespace_prefix(&self) -> String {
+        match &self.namespace {
+            Some(ns) => format!("/{ns}/{}", import_name(&self.project)),
+            None => String::new(),
+        }
+    }
+
+    /// Where the template at `path` is written: under a namespace, what the flat layout
+    /// puts in `src/` goes in the namespace package instead, and the notebooks go in
+    /// [`notebooks_dir`](Self::notebooks_dir).
+    fn relocated(&self, path: &str) -> String {
+        let below = |dir: &str| path.strip_prefix(dir).filter(|rest| rest.is_empty() || rest.starts_with('/'));
+        if let Some(rest) = below("src") {
+            return format!("src{}{rest}", self.namespace_prefix());
+        }
+        match below(DEFAULT_NOTEBOOKS_DIR) {
+            Some(rest) => format!("{}{rest}", self.notebooks_dir()),
+            None => path.to_string(),
+        }
+    }
+
+    /// Network steps that `offline` leaves out of this plan, for the summary.
+    pub fn offline_skips(&self) -> Vec<&'static str> {
+        let mut skips = Vec::new();
+        if !self.offline {
+            return skips;
+        }
+        if self.bootstrap_uv {
+            skips.push("uv bootstrap");
+        }
+        if self.installs_python() {
+            skips.push("uv python install");
+        }
+        if self.template_profile == "template-repo" {
+            skips.push("template repo fetch");
+        }
+        skips
+    }
+
+    /// Whether the uv steps would include `uv python install` for a Python uv lacks:
+    /// a flat project that builds its own venv, online.
+    fn installs_python(&self) -> bool {
+        !self.skip_uv && self.workspace_root.is_none() && !self.keeps_venv()
+    }
+
+    /// The installed Python uv would use for this one, newest first for a partial
+    /// version (`3.12` -> `3.12.8`); `None` when there is none or uv cannot tell, in
+    /// which case `uv python install` runs as before. The installed list has system
+    /// Pythons too, so under a preference uv is asked instead. Asked once per plan.
+    fn installed_python(&self) -> Option<&PythonVersion> {
+        self.installed_python
+            .get_or_init(|| match self.python_preference {
+                Some(preference) => {
+                    let py = self.python.to_string();
+                    let args = ["python", "find", "--python-preference", preference.name(), &py];
+                    run_output(&uv_bin(), &args, &std::env::temp_dir())
+                        .ok()
+                        .map(|_| self.python.clone())
+                }
+                None => uv_python_versions(true)
+                    .ok()?
+                    .iter()
+                    .find(|v| self.python.matches(v))
+                    .cloned(),
+            })
+            .as_ref()
+    }
+
+    fn python_installed(&self) -> bool {
+        self.installed_python().is_some()
+    }
+
+    /// Before the uv steps run: whether `uv python install` is left out because uv
+    /// already has the Python, saying so when it is.
+    fn skips_python_install(&self) -> Option<PythonVersion> {
+        if self.offline || !self.installs_python() {
+            return None;
+        }
+        let found = self.installed_python()?.clone();
+        self.emit(&Event::PythonInstallSkipped { version: &found });
+        Some(found)
+    }
+
+    /// Fail before anything is written when uv has no such Python. If the versions
+    /// cannot be listed, warn and let `uv python install` have its say.
+    fn check_python_available(&self) -> Result<()> {
+        let versions = match uv_python_versions(false) {
+            Ok(versions) => versions,
+            Err(e) => {
+                let reason = e.to_string().lines().next().unwrap_or_default().to_string();
+                self.warn(format!("could not list Python versions with uv ({reason}); trying {} anyway", self.python));
+                return Ok(());
+            }
+        };
+        if versions.iter().any(|v| self.python.matches(v)) {
+            return Ok(());
+        }
+        Err(Error::UnknownPython {
+            version: self.python.to_string(),
+            suggestions: self.python.closest(versions, 2).iter().map(ToString::to_string).collect(),
+        }
+        .into())
+    }
+
+    /// Offline there is no `uv python install`, so the Python must already be there.
+    fn ensure_python_installed(&self) -> Result<()> {
+        if !self.python_installed() {
+            bail!(
+                "Python {py} is not installed and --offline skips `uv python install`; \
+                 install it while online (`uv python install {py}`) or pick an installed version with --python",
+                py = self.python
+            );
+        }
         Ok(())
     }
-    pub fn wirte_makefile(&self) -> Result<()> {
-        write(
-            self.root.join("Makefile"),
-            app_make_file_creator(),
-        )?;
 
+    pub fn install_uv_toolchain(&self) -> Result<()> {
+        self.check_uv_toolchain()?;
+        self.skips_python_install();
+        self.execute(&self.uv_actions(), WritePolicy::Overwrite).map(|_| ())
+    }
+
+    /// What [`ScaffoldPlan::install_uv_toolchain`] checks before it runs anything: the
+    /// existing venv (see [`ScaffoldPlan::check_venv`]), and uv when there are uv steps.
+    /// Callers that write files first run it before the first write.
+    pub fn check_uv_toolchain(&self) -> Result<()> {
+        self.check_venv()?;
+        if !self.uv_actions().is_empty() {
+            check_uv()?;
+        }
         Ok(())
     }
 
-    /// NEW: create the `src/app_logging` package with all files you asked for.
-    pub fn write_app_logging(&self) -> Result<()> {
-        let base = self.root.join("src/app_logging");
-        write(base.join("__init__.py"), "")?;
-        write(
-            base.join("MyColoredFormatter.py"),
-            app_logging_my_colored_formatter_py(),
-        )?;
-        write(base.join("config07.json"), app_logging_config07_json())?;
-        write(base.join("constants.py"), app_logging_constants_py())?;
-        write(base.join("glogger.py"), app_logging_glogger_py())?;
-        write(
-            base.join("myCustomJsonClass01.py"),
-            app_logging_my_custom_json_class01_py(),
-        )?;
-        write(base.join("myFilters.py"), app_logging_my_filters_py())?;
+    /// Whether a flat project's existing `.venv` stays: its Python matches the plan's
+    /// (see [`venv_python_matches`]) or cannot be read, or it does not but nothing asked
+    /// to replace it (which [`ScaffoldPlan::check_venv`] refuses before any write).
+    fn keeps_venv(&self) -> bool {
+        if !self.venv_path().is_dir() {
+            return false;
+        }
+        match pyvenv_python(&self.venv_path()) {
+            Some(found) if !venv_python_matches(&found, &self.python) => !self.recreate_venv,
+            _ => true,
+        }
+    }
+
+    /// Check a flat project's existing `.venv` before anything is written: one built with
+    /// another Python fails, naming both versions, unless `recreate_venv`; one that is
+    /// kept is reported as such.
+    pub fn check_venv(&self) -> Result<()> {
+        if self.skip_uv || self.workspace_root.is_some() || !self.venv_path().is_dir() {
+            return Ok(());
+        }
+        let found = pyvenv_python(&self.venv_path());
+        match &found {
+            Some(found) if !venv_python_matches(found, &self.python) => {
+                if !self.recreate_venv {
+                    bail!(
+                        "{} was built with Python {found}, but Python {} was requested; \
+                         pass --recreate-venv to replace it (or --python {found} to keep it)",
+                        self.venv_path().display(),
+                        self.python
+                    );
+                }
+            }
+            _ => self.emit(&Event::VenvKept {
+                version: found.as_deref(),
+            }),
+        }
         Ok(())
     }
 
-    pub fn install_uv_toolchain(&self) -> Result<()> {
-        println!("⚙️  Installing Python {} via uv …", self.py_full);
-        run(
-            "uv",
-            &["python", "install", &self.py_full],
-            Path::new(&self.root),
-        )?;
-
-        println!("🧪 Creating uv venv …");
-        run(
-            "uv",
-            &["venv", "--python", &self.py_full, ".venv"],
-            Path::new(&self.root),
-        )?;
+    /// Run the official uv installer, reporting it like any other command.
+    fn install_uv(&self) -> Result<()> {
+        self.emit(&Event::CommandStarted {
+            label: "📥 Installing uv …",
+            command: "curl -LsSf https://astral.sh/uv/install.sh | sh",
+            step: 1,
+            steps: 1,
+        });
+        let started = Instant::now();
+        let result = bootstrap_uv(|line| {
+            self.emit(&Event::CommandOutput {
+                program: "uv-installer",
+                line,
+            })
+        });
+        self.emit(&Event::CommandFinished {
+            success: result.is_ok(),
+            elapsed: started.elapsed(),
+            exit_code: exit_code(&result),
+        });
+        self.emit(&Event::UvInstalled { path: &result? });
         Ok(())
     }
+
+    /// Record how this project was scaffolded in `.pyproj.toml`.
+    pub fn write_manifest(&self) -> Result<()> {
+        let contents = self.manifest_file(&self.files()?)?;
+        let action = PlannedAction::WriteFile {
+            path: MANIFEST_FILE.into(),
+            contents,
+            origin: Origin::Manifest,
+        };
+        self.execute(&[action], WritePolicy::Overwrite).map(|_| ())
+    }
+
+    /// What of the scaffold's files and directories, the venv, and the root is on disk
+    /// before it is written (see [`Preexisting::snapshot`]).
+    pub fn preexisting(&self, created_root: bool) -> Result<Preexisting> {
+        let files: Vec<String> = self.files()?.into_iter().map(|(path, _, _)| path).collect();
+        let venv = self.venv_path();
+        let venv = (!self.skip_uv && self.workspace_root.is_none()).then_some(venv.as_path());
+        let earlier = Manifest::load(&self.root)?;
+        Ok(Preexisting::snapshot(&self.root, &files, &self.dirs(), venv, created_root, earlier.as_ref()))
+    }
+
+    /// Add `preexisting`, settled (see [`Preexisting::settle`]), to the manifest the
+    /// scaffold wrote; nothing to do when it is empty.
+    pub fn record_preexisting(&self, mut preexisting: Preexisting) -> Result<()> {
+        preexisting.settle(&self.root);
+        if preexisting.is_empty() {
+            return Ok(());
+        }
+        let Some(mut manifest) = Manifest::load(&self.root)? else {
+            return Ok(());
+        };
+        manifest.preexisting = preexisting;
+        self.write_file(Path::new(MANIFEST_FILE), manifest.to_toml()?.as_bytes(), WritePolicy::Overwrite)
+            .map(|_| ())
+    }
+
+    /// The manifest a scaffold of this plan records: the resolved options and a hash
+    /// of every file it writes.
+    pub fn manifest(&self) -> Result<Manifest> {
+        self.manifest_for(&self.files()?)
+    }
+
+    /// The manifest contents for `files`.
+    fn manifest_file(&self, files: &[(String, Vec<u8>, Origin)]) -> Result<Vec<u8>> {
+        Ok(self.manifest_for(files)?.to_toml()?.into_bytes())
+    }
+
+    fn manifest_for(&self, files: &[(String, Vec<u8>, Origin)]) -> Result<Manifest> {
+        let dirs = self
+            .created_dirs(files)
+            .into_iter()
+            .filter(|dir| !files.iter().any(|(p, _, _)| p.starts_with(&format!("{dir}/"))))
+            .collect();
+        let mut files: Vec<FileEntry> = files
+            .iter()
+            .map(|(path, bytes, _)| FileEntry {
+                path: path.clone(),
+                sha256: sha256_hex(bytes),
+                mode: generated_mode(path, self.file_mode).map(format_mode),
+            })
+            .collect();
+        // By path, so the manifest does not change with the order files are planned in.
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut manifest = Manifest::new(
+            Options {
+                project: self.project.clone(),
+                python: self.python.to_string(),
+                description: self.description.clone(),
+                author: self.author.clone(),
+                email: self.email.clone(),
+                repo_url: self.repo_url.clone(),
+                homepage: self.homepage.clone(),
+                org: self.org.clone(),
+                license: self.license.clone(),
+                maintainers: self.maintainers.clone(),
+                profile: self.profile,
+                gitignore_extra: self.gitignore_extra.clone(),
+                entry_point: self.entry_point.clone(),
+                namespace: self.namespace.clone(),
+                gen_secrets: self.secrets.iter().map(|(key, _)| key.clone()).collect(),
+                modules: self.modules.clone(),
+                pytest_addopts: self.pytest_addopts.clone(),
+                pytest_markers: self.pytest_markers.clone(),
+                testing_extras: self.testing_extras.clone(),
+                async_main: self.async_main,
+                python_matrix: self.python_matrix.clone(),
+                matrix_runner: self.matrix_runner,
+                dep_style: Some(self.dep_style).filter(|s| *s != DepStyle::Extras),
+                dep_updates: self.dep_updates,
+                publish: self.publish,
+                git_hooks: self.git_hooks,
+                security: self.security,
+                status: self.status,
+                ide: Some(self.ide).filter(|i| *i != Ide::Vscode),
+                toolchain: self.toolchain,
+                line_endings: Some(self.line_endings).filter(|e| *e != LineEndings::Lf),
+                tools_global: self.tools_global,
+                emit_bootstrap: self.emit_bootstrap,
+                license_header: self.license_header.clone(),
+                report_file: self.report_file.clone(),
+                jupytext: self.jupytext,
+                jupytext_ignore_ipynb: self.jupytext_ignore_ipynb,
+                minimal: self.minimal,
+                with: self.with.clone(),
+                venv_seed: self.venv_seed,
+                venv_prompt: self.venv_prompt.clone(),
+                venv_dir: self.venv_dir.clone(),
+                notebooks_dir: self.notebooks_dir.clone(),
+                uv_args: self.uv_args.clone(),
+                code_workspace: self.code_workspace.as_ref().map(|path| {
+                    relative_path(&canonicalize_lenient(&self.root), path)
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                }),
+                exclude: self.exclude.clone(),
+                dir_mode: self.dir_mode.map(format_mode),
+                file_mode: self.file_mode.map(format_mode),
+                lang: Some(self.strings.lang().to_string()).filter(|lang| lang != DEFAULT_LANG),
+                layout: if self.workspace_root.is_some() { "member" } else { "flat" }.to_string(),
+                components: self.components(),
+                dirs,
+                template_profile: self.template_profile.clone(),
+                template_source: self.template_source.clone(),
+                vars: self.manifest_vars()?,
+            },
+            files,
+        );
+        // Re-running over an existing project keeps its original creation time.
+        if let Ok(Some(existing)) = Manifest::load(&self.root) {
+            manifest.tool.created = existing.tool.created;
+        }
+        Ok(manifest)
+    }
+
+    fn manifest_vars(&self) -> Result<std::collections::BTreeMap<String, toml::Value>> {
+        self.vars
+            .iter()
+            .map(|(k, v)| {
+                let value = toml::Value::try_from(v)
+                    .with_context(|| format!("variable `{k}` cannot be stored in the manifest"))?;
+                Ok((k.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Relative paths of override files that have no built-in counterpart.
+    fn extra_paths(&self) -> Result<Vec<String>> {
+        let Some(dir) = &self.templates_dir else {
+            return Ok(Vec::new());
+        };
+        let mut extras = Vec::new();
+        for rel in list_files(dir)? {
+            if !is_safe_relative(&rel) {
+                bail!("refusing template path outside the project: {}", rel.display());
+            }
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if find(&rel).is_none() && !rel.starts_with(&format!("{CATALOG_DIR}/")) {
+                extras.push(rel);
+            }
+        }
+        Ok(extras)
+    }
+
+    /// Write the files at the start of `actions`, up to the first action that is not a
+    /// file write, on up to [`WRITE_THREADS`] threads. The manifest is a batch of its
+    /// own, so it is still written last. Each parent directory is created once up front,
+    /// and every file is remembered for rollback before any is written. The outcomes are
+    /// keyed by index in the whole action list, which starts at `first` here; the first
+    /// failed write (by that index) is the error.
+    fn write_batch(
+        &self,
+        actions: &[PlannedAction],
+        first: usize,
+        policy: WritePolicy,
+        file_policies: &BTreeMap<PathBuf, WritePolicy>,
+        replace_pyproject: bool,
+    ) -> Result<BTreeMap<usize, Written>> {
+        let mut files = Vec::new();
+        for action in actions {
+            let PlannedAction::WriteFile { path, contents, origin } = action else {
+                break;
+            };
+            if !files.is_empty() && *origin == Origin::Manifest {
+                break;
+            }
+            let policy = match origin {
+                Origin::Manifest => WritePolicy::Overwrite,
+                _ => file_policies.get(path).copied().unwrap_or(policy),
+            };
+            let merged = match (policy, replace_pyproject) {
+                (WritePolicy::Skip, _) | (_, true) => None,
+                _ => self.merged_pyproject(path, contents, *origin)?,
+            };
+            files.push((path, contents, policy, merged));
+            if *origin == Origin::Manifest {
+                break;
+            }
+        }
+        let mut parents: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|(path, ..)| self.root.join(path).parent().map(Path::to_path_buf))
+            .collect();
+        parents.sort();
+        parents.dedup();
+        for dir in &parents {
+            self.create_dir(dir)?;
+        }
+        for (path, ..) in &files {
+            self.track(&self.root.join(path));
+        }
+
+        // Only plain data crosses to the workers: the plan itself is not `Sync`.
+        let (root, file_mode) = (self.root.as_path(), self.file_mode);
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let mut outcomes: Vec<(usize, Result<WriteOutcome>, Duration)> = thread::scope(|scope| {
+            // Writes wait on the disk (each file is synced), so they overlap even on one core.
+            let handles: Vec<_> = (0..WRITE_THREADS.min(files.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        while !failed.load(Ordering::Relaxed) && !interrupted() {
+                            let n = next.fetch_add(1, Ordering::Relaxed);
+                            let Some((path, contents, policy, merged)) = files.get(n) else {
+                                break;
+                            };
+                            let started = Instant::now();
+                            let bytes = merged.as_ref().map_or(contents.as_slice(), |m| m.text.as_bytes());
+                            let mode = generated_mode(&path.to_string_lossy(), file_mode);
+                            let outcome = write_with_mode(root.join(path), bytes, *policy, mode);
+                            if outcome.is_err() {
+                                failed.store(true, Ordering::Relaxed);
+                            }
+                            done.push((n, outcome, started.elapsed()));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+        outcomes.sort_by_key(|(n, ..)| *n);
+        let mut merges: Vec<_> = files.into_iter().map(|(.., merged)| merged).collect();
+        let mut written = BTreeMap::new();
+        for (n, outcome, elapsed) in outcomes {
+            let merged = merges[n].take();
+            written.insert(
+                first + n,
+                Written {
+                    outcome: outcome?,
+                    merged,
+                    elapsed,
+                },
+            );
+        }
+        // Interrupted with nothing failed: the workers stopped with files left unwritten.
+        check_interrupt()?;
+        Ok(written)
+    }
+
+    /// Write `rel` under the root, remembering it for rollback if it is new.
+    fn write_file(&self, rel: &Path, bytes: &[u8], policy: WritePolicy) -> Result<WriteOutcome> {
+        let path = self.root.join(rel);
+        if let Some(parent) = path.parent() {
+            self.create_dir(parent)?;
+        }
+        self.track(&path);
+        write_with_mode(&path, bytes, policy, generated_mode(&rel.to_string_lossy(), self.file_mode))
+    }
+
+    /// `create_dir_all`, remembering each directory it actually creates and giving it
+    /// the `dir_mode`.
+    pub fn create_dir(&self, dir: &Path) -> Result<()> {
+        let missing: Vec<&Path> = dir
+            .ancestors()
+            .take_while(|d| !d.as_os_str().is_empty() && !d.exists())
+            .collect();
+        for d in missing.into_iter().rev() {
+            self.track(d);
+            fs::create_dir(d).with_context(|| format!("failed to create {}", d.display()))?;
+            if let Some(mode) = self.dir_mode {
+                set_dir_mode(d, mode)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember `path` as created by this run, unless it already exists.
+    fn track(&self, path: &Path) {
+        if !path.exists() {
+            self.created.borrow_mut().push(path.to_path_buf());
+        }
+    }
+
+    /// Undo a failed scaffold: remove the root if this run created it, otherwise only
+    /// the files and directories this run created. Pre-existing content is never removed.
+    /// Returns how many of the files this run wrote were there to remove.
+    pub fn rollback(&self, created_root: bool) -> usize {
+        self.emit(&Event::RollbackStarted);
+        let files = self.created.borrow().iter().filter(|p| p.is_file()).count();
+        if created_root {
+            match fs::remove_dir_all(&self.root) {
+                Ok(()) => self.emit(&Event::RolledBack {
+                    path: &self.root,
+                    recursive: true,
+                }),
+                Err(e) => self.warn(format!("{}: {e}", self.root.display())),
+            }
+        } else {
+            let created = self.created.borrow();
+            // Removing a created directory takes everything below it along.
+            let tops = created
+                .iter()
+                .filter(|p| !created.iter().any(|d| *d != **p && p.starts_with(d)));
+            for path in tops.rev() {
+                let removed = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                match removed {
+                    Ok(()) => self.emit(&Event::RolledBack {
+                        path,
+                        recursive: false,
+                    }),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => self.warn(format!("{}: {e}", path.display())),
+                }
+            }
+        }
+        if let (Some(ws), true) = (&self.workspace_root, self.registered.get()) {
+            let unregistered = workspace::member_path(ws, &self.root)
+                .and_then(|member| workspace::remove_member(ws, &member));
+            if let Err(e) = unregistered {
+                self.warn(format!("{e:#}"));
+            }
+        }
+        files
+    }
+
+    fn emit(&self, event: &Event<'_>) {
+        (self.on_event)(event)
+    }
+
+    fn warn(&self, message: String) {
+        self.emit(&Event::Warning { message });
+    }
+
+    fn render_template(&self, t: &Template) -> Result<Vec<u8>> {
+        let bytes = match self.override_dir_for(t) {
+            Some(dir) => self.read_override(&dir.join(t.path), t.path)?,
+            None => t.render(&self.context())?.into_bytes(),
+        };
+        Ok(match (t.group, String::from_utf8(bytes)) {
+            (Group::Gitignore, Ok(text)) => dedup_patterns(&text).into_bytes(),
+            (Group::Envs, Ok(text)) if t.path == ".env.example" => {
+                let env = find(".env").context("no .env template")?;
+                let env = self.render_template(env)?;
+                (text + &blank_env_values(&String::from_utf8_lossy(&env))).into_bytes()
+            }
+            (Group::Renovate, Ok(text)) => {
+                // Renovate rejects the whole file on a syntax error; fail here instead.
+                let value: serde_json::Value = serde_json::from_str(&text)
+                    .with_context(|| format!("{} does not render to valid JSON", t.path))?;
+                (serde_json::to_string_pretty(&value)? + "\n").into_bytes()
+            }
+            (_, Ok(text)) => text.into_bytes(),
+            (_, Err(raw)) => raw.into_bytes(),
+        })
+    }
+
+    /// The override directory, if it has a replacement for `t`.
+    fn override_dir_for(&self, t: &Template) -> Option<&Path> {
+        self.templates_dir
+            .as_deref()
+            .filter(|dir| dir.join(t.path).is_file())
+    }
+
+    /// Read an override file, rendering it as a template when it is valid UTF-8.
+    fn read_override(&self, path: &Path, rel: &str) -> Result<Vec<u8>> {
+        let bytes =
+            fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(match String::from_utf8(bytes) {
+            Ok(text) => render(rel, &text, &self.context())?.into_bytes(),
+            Err(raw) => raw.into_bytes(),
+        })
+    }
+
+    /// Built-in variables merged over the user's `vars`.
+    fn context(&self) -> Vars {
+        let mut ctx = self.vars.clone();
+        for (name, value) in [
+            ("project", self.project.clone()),
+            ("dist_name", self.dist_name()),
+            ("import_name", import_name(&self.project)),
+            ("namespace", self.namespace.clone().unwrap_or_default()),
+            ("package", self.package()),
+            ("package_dir", self.package_dir()),
+            ("run_module", self.run_module()),
+            ("py_full", self.python.to_string()),
+            ("mm", self.python.mm()),
+            ("mm_nodec", self.python.mm_nodec()),
+        ] {
+            ctx.insert(name.to_string(), Value::from(value));
+        }
+        ctx.insert("workspace_member".to_string(), Value::from(self.workspace_root.is_some()));
+        for (name, value) in [
+            ("description", &self.description),
+            ("author", &self.author),
+            ("email", &self.email),
+            ("repo_url", &self.repo_url),
+            ("homepage", &self.homepage),
+            ("org", &self.org),
+            ("license", &self.license),
+        ] {
+            ctx.insert(name.to_string(), Value::from(value.clone().unwrap_or_default()));
+        }
+        // Every key present (an empty email included), as strict undefined requires.
+        let maintainers: Vec<BTreeMap<&str, &str>> = self
+            .maintainers
+            .iter()
+            .map(|m| BTreeMap::from([("name", m.name.as_str()), ("email", m.email.as_deref().unwrap_or_default())]))
+            .collect();
+        ctx.insert("maintainers".to_string(), Value::from(Serde(maintainers)));
+        let secrets: Vec<BTreeMap<&str, &str>> = self
+            .secrets
+            .iter()
+            .map(|(key, value)| BTreeMap::from([("key", key.as_str()), ("value", value.as_str())]))
+            .collect();
+        ctx.insert("secrets".to_string(), Value::from(Serde(secrets)));
+        ctx.insert("date".to_string(), Value::from(self.date.clone()));
+        ctx.insert("line_length".to_string(), Value::from(LINE_LENGTH));
+        ctx.insert("components".to_string(), Value::from(self.components()));
+        ctx.insert("profile".to_string(), Value::from(self.profile.name()));
+        ctx.insert("gitignore_extra".to_string(), Value::from(self.gitignore_extra.clone()));
+        ctx.insert("entry_point".to_string(), Value::from(self.entry_point.clone().unwrap_or_default()));
+        ctx.insert("modules".to_string(), Value::from(self.modules.clone()));
+        ctx.insert("pytest_addopts".to_string(), Value::from(self.pytest_addopts.clone().unwrap_or_default()));
+        ctx.insert("pytest_markers".to_string(), Value::from(self.pytest_markers.clone()));
+        let extras: Vec<&str> = self.testing_extras.iter().map(|e| e.name()).collect();
+        ctx.insert("testing_extras".to_string(), Value::from(extras));
+        ctx.insert("async_main".to_string(), Value::from(self.async_main && self.workspace_root.is_none()));
+        ctx.insert("benchmarks".to_string(), Value::from(self.benchmarks));
+        ctx.insert("data_layout".to_string(), Value::from(self.data_layout && self.workspace_root.is_none()));
+        ctx.insert("dvc".to_string(), Value::from(self.dvc && self.workspace_root.is_none()));
+        ctx.insert("python_matrix".to_string(), Value::from(self.python_matrix.clone()));
+        ctx.insert("matrix_runner".to_string(), Value::from(self.matrix_runner.name()));
+        ctx.insert("dep_style".to_string(), Value::from(self.dep_style.name()));
+        ctx.insert("publish".to_string(), Value::from(self.publish.map_or("", Publish::name)));
+        ctx.insert("git_hooks".to_string(), Value::from(self.git_hooks.map_or("", GitHooks::name)));
+        ctx.insert("security".to_string(), Value::from(self.security.map_or("", Security::name)));
+        ctx.insert("status".to_string(), Value::from(self.status.map_or("", Status::classifier)));
+        ctx.insert("ide".to_string(), Value::from(self.ide.name()));
+        ctx.insert("toolchain".to_string(), Value::from(self.toolchain.map_or("", Toolchain::name)));
+        ctx.insert("line_endings".to_string(), Value::from(self.line_endings.name()));
+        ctx.insert("tools_global".to_string(), Value::from(self.tools_global));
+        ctx.insert("license_header".to_string(), Value::from(self.license_header.clone().unwrap_or_default()));
+        ctx.insert("report_file".to_string(), Value::from(self.report_file.clone().unwrap_or_default()));
+        ctx.insert("jupytext".to_string(), Value::from(self.jupytext));
+        ctx.insert("jupytext_ignore_ipynb".to_string(), Value::from(self.jupytext_ignore_ipynb));
+        let skipped: Vec<&str> = self.skipped().into_iter().map(Group::name).collect();
+        ctx.insert("skipped".to_string(), Value::from(skipped));
+        let code_workspace = self.code_workspace.as_ref().map(|path| {
+            relative_path(&canonicalize_lenient(&self.root), path).to_string_lossy().replace('\\', "/")
+        });
+        ctx.insert("code_workspace".to_string(), Value::from(code_workspace.unwrap_or_default()));
+        ctx.insert("venv_seed".to_string(), Value::from(self.venv_seed));
+        ctx.insert("venv_prompt".to_string(), Value::from(self.prompt()));
+        ctx.insert("venv_dir".to_string(), Value::from(self.venv_dir()));
+        ctx.insert("notebooks_dir".to_string(), Value::from(self.notebooks_dir()));
+        ctx.insert("lang".to_string(), Value::from(self.strings.lang()));
+        ctx.insert("tr".to_string(), self.strings.to_value());
+        ctx
+    }
+
+    /// The multi-root workspace written to `path`: the project folder, the workspace's
+    /// other members, and the folder settings hoisted to the workspace level. A bare
+    /// `${workspaceFolder}` is ambiguous there, so each path names its folder.
+    fn code_workspace_file(&self, path: &Path) -> Result<Vec<u8>> {
+        let dir = path.parent().context("the .code-workspace path has no parent directory")?;
+        let root = canonicalize_lenient(&self.root);
+        let folder_path = |target: &Path| relative_path(dir, target).to_string_lossy().replace('\\', "/");
+        let mut folders = vec![serde_json::json!({ "name": self.project, "path": folder_path(&root) })];
+        let mut others = Vec::new();
+        if let Some(ws) = &self.workspace_root {
+            let ws = canonicalize_lenient(ws);
+            let member = workspace::member_path(&ws, &root)?;
+            for other in workspace::member_dirs(&ws) {
+                let name = other.file_name().unwrap_or_default().to_string_lossy().to_string();
+                if other == ws.join(&member) || name == self.project {
+                    continue;
+                }
+                folders.push(serde_json::json!({ "name": name, "path": folder_path(&other) }));
+                others.push(name);
+            }
+        }
+
+        let settings = find(".vscode/settings.json").context("no .vscode/settings.json template")?;
+        let text = String::from_utf8(self.render_template(settings)?)
+            .context(".vscode/settings.json is not UTF-8")?;
+        let mut settings: serde_json::Value = serde_json::from_str(&text)
+            .context(".vscode/settings.json does not render to valid JSON")?;
+        // A member's venv is the workspace root's, not one in its own folder.
+        let venv = match &self.workspace_root {
+            Some(ws) => relative_path(&root, &canonicalize_lenient(ws)).join(DEFAULT_VENV_DIR),
+            None => PathBuf::from(self.venv_dir()),
+        };
+        let folder = format!("${{workspaceFolder:{}}}", self.project);
+        let venv = format!("{folder}/{}", venv.to_string_lossy().replace('\\', "/"));
+        qualify_workspace_folder(&mut settings, &folder, &venv);
+        if let Some(extra) = settings.get_mut("python.analysis.extraPaths").and_then(|v| v.as_array_mut()) {
+            extra.extend(others.iter().map(|name| format!("${{workspaceFolder:{name}}}/src").into()));
+        }
+
+        let file = serde_json::json!({ "folders": folders, "settings": settings });
+        let text = serde_json::to_string_pretty(&file)? + "\n";
+        Ok(normalize_line_endings(text.as_bytes(), self.line_endings.crlf()))
+    }
+
+    /// Run each post-create hook through the shell; stop at the first failure.
+    /// The project is left in place so the user can fix things up by hand.
+    pub fn run_post_hooks(&self) -> Result<()> {
+        let root = self.root.to_string_lossy();
+        let python = self.python.to_string();
+        let envs = [
+            ("PY_PROJ_ROOT", root.as_ref()),
+            ("PY_PROJ_NAME", self.project.as_str()),
+            ("PY_PROJ_PYTHON", python.as_str()),
+        ];
+        for (i, hook) in self.post_hooks.iter().enumerate() {
+            self.emit(&Event::HookStarted {
+                index: i + 1,
+                total: self.post_hooks.len(),
+                command: hook,
+            });
+            let (shell, flag) = shell();
+            run_env(shell, &[flag, hook], &self.root, &envs).with_context(|| {
+                format!("post-create hook #{} failed: `{hook}` (project kept)", i + 1)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`ScaffoldPlan`]; see [`ScaffoldPlan::builder`].
+pub struct ScaffoldPlanBuilder {
+    project: String,
+    root: Option<PathBuf>,
+    python: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    email: Option<String>,
+    repo_url: Option<String>,
+    homepage: Option<String>,
+    org: Option<String>,
+    license: Option<String>,
+    maintainers: Vec<Maintainer>,
+    citation: bool,
+    profile: Profile,
+    gitignore_extra: Vec<String>,
+    entry_point: Option<String>,
+    namespace: Option<String>,
+    gen_secrets: Vec<String>,
+    modules: Vec<String>,
+    pytest_addopts: Option<String>,
+    pytest_markers: Vec<String>,
+    testing_extras: Vec<TestingExtra>,
+    async_main: bool,
+    benchmarks: bool,
+    data_layout: bool,
+    dvc: bool,
+    python_matrix: Vec<String>,
+    matrix_runner: MatrixRunner,
+    dep_style: DepStyle,
+    dep_updates: Option<DepUpdates>,
+    publish: Option<Publish>,
+    git_hooks: Option<GitHooks>,
+    security: Option<Security>,
+    status: Option<Status>,
+    ide: Ide,
+    toolchain: Option<Toolchain>,
+    line_endings: LineEndings,
+    tools_global: bool,
+    emit_bootstrap: bool,
+    license_header: Option<String>,
+    report_file: Option<String>,
+    jupytext: bool,
+    jupytext_ignore_ipynb: bool,
+    minimal: bool,
+    with: Vec<String>,
+    code_workspace: Option<Option<PathBuf>>,
+    recreate_venv: bool,
+    venv_seed: bool,
+    venv_prompt: Option<String>,
+    venv_dir: Option<String>,
+    notebooks_dir: Option<String>,
+    uv_args: Vec<String>,
+    exclude: Vec<String>,
+    dir_mode: Option<u32>,
+    file_mode: Option<u32>,
+    lang: Option<String>,
+    templates: TemplateSource,
+    refresh_templates: bool,
+    vars: Vars,
+    post_hooks: Vec<String>,
+    workspace_root: Option<PathBuf>,
+    skip_uv: bool,
+    bootstrap_uv: bool,
+    offline: bool,
+    python_preference: Option<PythonPreference>,
+    force_name: bool,
+    deterministic: bool,
+    on_event: EventHandler,
+}
+
+enum TemplateSource {
+    Builtin,
+    Dir(PathBuf),
+    Repo(String),
+}
+
+impl ScaffoldPlan {
+    /// Start a plan for a new project called `project`.
+    ///
+    /// ```no_run
+    /// use pyproj::ScaffoldPlan;
+    ///
+    /// let plan = ScaffoldPlan::builder("demo")
+    ///     .root("/tmp/demo")
+    ///     .python("3.12.4")
+    ///     .var("author", "Ada")
+    ///     .build()?;
+    /// let summary = pyproj::create(&plan, &Default::default())?;
+    /// println!("{}", summary.line());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn builder(project: impl Into<String>) -> ScaffoldPlanBuilder {
+        ScaffoldPlanBuilder {
+            project: project.into(),
+            root: None,
+            python: None,
+            description: None,
+            author: None,
+            email: None,
+            repo_url: None,
+            homepage: None,
+            org: None,
+            license: None,
+            maintainers: Vec::new(),
+            citation: false,
+            profile: Profile::Default,
+            gitignore_extra: Vec::new(),
+            entry_point: None,
+            namespace: None,
+            gen_secrets: Vec::new(),
+            modules: Vec::new(),
+            pytest_addopts: None,
+            pytest_markers: Vec::new(),
+            testing_extras: Vec::new(),
+            async_main: false,
+            benchmarks: false,
+            data_layout: false,
+            dvc: false,
+            python_matrix: Vec::new(),
+            matrix_runner: MatrixRunner::Nox,
+            dep_style: DepStyle::Extras,
+            dep_updates: None,
+            publish: None,
+            git_hooks: None,
+            security: None,
+            status: None,
+            ide: Ide::Vscode,
+            toolchain: None,
+            line_endings: LineEndings::Lf,
+            tools_global: false,
+            emit_bootstrap: false,
+            license_header: None,
+            report_file: None,
+            jupytext: false,
+            jupytext_ignore_ipynb: false,
+            minimal: false,
+            with: Vec::new(),
+            code_workspace: None,
+            recreate_venv: false,
+            venv_seed: false,
+            venv_prompt: None,
+            venv_dir: None,
+            notebooks_dir: None,
+            uv_args: Vec::new(),
+            exclude: Vec::new(),
+            dir_mode: None,
+            file_mode: None,
+            lang: None,
+            templates: TemplateSource::Builtin,
+            refresh_templates: false,
+            vars: Vars::new(),
+            post_hooks: Vec::new(),
+            workspace_root: None,
+            skip_uv: false,
+            bootstrap_uv: false,
+            offline: false,
+            python_preference: None,
+            force_name: false,
+            deterministic: false,
+            on_event: crate::events::silent(),
+        }
+    }
+}
+
+impl ScaffoldPlanBuilder {
+    /// Project directory (default: `<current dir>/<project>`).
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Python version for uv, e.g. `3.12.4`; a partial `3.12` is resolved to the newest
+    /// patch uv offers (default: see [`detect_python`]).
+    pub fn python(mut self, version: impl Into<String>) -> Self {
+        self.python = Some(version.into());
+        self
+    }
+
+    /// One-line project summary, written to pyproject.toml and README.md.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Author name for pyproject.toml and CITATION.cff (default: a placeholder).
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Author email for pyproject.toml and CITATION.cff.
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Repository URL for `[project.urls]` and CITATION.cff; `{project}` in it becomes
+    /// the project name.
+    pub fn repo_url(mut self, url: impl Into<String>) -> Self {
+        self.repo_url = Some(url.into());
+        self
+    }
+
+    /// Homepage URL for `[project.urls]` and CITATION.cff; `{project}` is substituted
+    /// as in [`repo_url`](Self::repo_url).
+    pub fn homepage(mut self, url: impl Into<String>) -> Self {
+        self.homepage = Some(url.into());
+        self
+    }
+
+    /// Organization name for the README's repository badge.
+    pub fn org_name(mut self, name: impl Into<String>) -> Self {
+        self.org = Some(name.into());
+        self
+    }
+
+    /// SPDX license expression for `[project] license` and CITATION.cff.
+    pub fn license(mut self, spdx: impl Into<String>) -> Self {
+        self.license = Some(spdx.into());
+        self
+    }
+
+    /// The project's maintainers, replacing any set before.
+    pub fn maintainers(mut self, maintainers: Vec<Maintainer>) -> Self {
+        self.maintainers = maintainers;
+        self
+    }
+
+    /// Defaults from the config's `[org]` table. Call it before the setters that
+    /// override it (the CLI flags); it never touches what the table leaves out.
+    pub fn org_defaults(mut self, org: &OrgConfig) -> Self {
+        self.org = org.name.clone().or(self.org);
+        self.license = org.license.clone().or(self.license);
+        self.repo_url = org.repo_url.clone().or(self.repo_url);
+        self.homepage = org.homepage.clone().or(self.homepage);
+        if !org.maintainers.is_empty() {
+            self.maintainers = org.maintainers.clone();
+        }
+        self
+    }
+
+    /// Also write a CITATION.cff.
+    pub fn citation(mut self, citation: bool) -> Self {
+        self.citation = citation;
+        self
+    }
+
+    /// The kind of project (default: [`Profile::Default`]).
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Add a .gitignore pattern (repeated ones are written once).
+    pub fn gitignore_extra(mut self, pattern: impl Into<String>) -> Self {
+        self.gitignore_extra.push(pattern.into());
+        self
+    }
+
+    /// Add a console script `name` that runs `main()` in a generated `cli.py`.
+    pub fn entry_point(mut self, name: impl Into<String>) -> Self {
+        self.entry_point = Some(name.into());
+        self
+    }
+
+    /// Put the package under the PEP 420 namespace `name`: `src/<name>/<import name>/`,
+    /// imported as `<name>.<import name>` and distributed as `<name>-<project>`.
+    pub fn namespace(mut self, name: impl Into<String>) -> Self {
+        self.namespace = Some(name.into());
+        self
+    }
+
+    /// Add a module (dotted for subpackages) with a placeholder test; repeats are ignored.
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        let module = module.into();
+        if !self.modules.contains(&module) {
+            self.modules.push(module);
+        }
+        self
+    }
+
+    /// pytest `addopts`, e.g. `-ra --strict-markers`.
+    pub fn pytest_addopts(mut self, addopts: impl Into<String>) -> Self {
+        self.pytest_addopts = Some(addopts.into());
+        self
+    }
+
+    /// Generate a random secret into `.env` as `key` (repeatable); a webapp gets
+    /// [`DEFAULT_SECRET`] without asking.
+    pub fn gen_secret(mut self, key: impl Into<String>) -> Self {
+        self.gen_secrets.push(key.into());
+        self
+    }
+
+    /// Register a pytest marker, given as `name` or `name:description`.
+    pub fn pytest_marker(mut self, marker: impl Into<String>) -> Self {
+        self.pytest_markers.push(marker.into());
+        self
+    }
+
+    /// Set up a testing library on top of pytest (repeatable).
+    pub fn testing_extra(mut self, extra: TestingExtra) -> Self {
+        if !self.testing_extras.contains(&extra) {
+            self.testing_extras.push(extra);
+        }
+        self
+    }
+
+    /// Make `src/main.py`'s `main` a coroutine, started by `asyncio.run` after logging is
+    /// set up, with pytest-asyncio for the tests (and an example test of `main`).
+    pub fn async_main(mut self, yes: bool) -> Self {
+        self.async_main = yes;
+        self
+    }
+
+    /// Add `benchmarks/` with a pytest-benchmark example, pytest-benchmark in the dev
+    /// dependencies, and `make bench`; `make test` does not run them.
+    pub fn benchmarks(mut self, yes: bool) -> Self {
+        self.benchmarks = yes;
+        self
+    }
+
+    /// Add `data/raw`, `data/interim`, `data/processed`, and `models/` (contents
+    /// git-ignored) and `src/paths.py` naming them; the datasci profile implies it.
+    pub fn data_layout(mut self, yes: bool) -> Self {
+        self.data_layout = yes;
+        self
+    }
+
+    /// Add a stub DVC pipeline (`dvc.yaml`, `.dvcignore`); needs the data layout.
+    pub fn dvc(mut self, yes: bool) -> Self {
+        self.dvc = yes;
+        self
+    }
+
+    /// Add a supported Python (`3.12`) to the test matrix; the primary `python` must
+    /// be one of them.
+    pub fn matrix_python(mut self, version: impl Into<String>) -> Self {
+        self.python_matrix.push(version.into());
+        self
+    }
+
+    /// What runs the matrix (default: [`MatrixRunner::Nox`]).
+    pub fn matrix_runner(mut self, runner: MatrixRunner) -> Self {
+        self.matrix_runner = runner;
+        self
+    }
+
+    /// Declare the dev tools as an extra or a dependency group (default: [`DepStyle::Extras`]).
+    pub fn dep_style(mut self, style: DepStyle) -> Self {
+        self.dep_style = style;
+        self
+    }
+
+    /// Write a config for a dependency-update bot.
+    pub fn dep_updates(mut self, bot: DepUpdates) -> Self {
+        self.dep_updates = Some(bot);
+        self
+    }
+
+    /// Write a release workflow that publishes the package on version tags, and make
+    /// the project buildable (`[build-system]`, PyPI classifiers and URLs).
+    pub fn publish(mut self, target: Publish) -> Self {
+        self.publish = Some(target);
+        self
+    }
+
+    /// Write git hooks that lint before commits and test before pushes, and point
+    /// `core.hooksPath` at them.
+    pub fn git_hooks(mut self, hooks: GitHooks) -> Self {
+        self.git_hooks = Some(hooks);
+        self
+    }
+
+    /// Set the project up for static security scanning with `scanner`.
+    pub fn security(mut self, scanner: Security) -> Self {
+        self.security = Some(scanner);
+        self
+    }
+
+    /// Mark the project alpha, beta, or stable in its trove classifiers.
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Write project files for `ide` instead of VS Code's (or none with [`Ide::None`]).
+    pub fn ide(mut self, ide: Ide) -> Self {
+        self.ide = ide;
+        self
+    }
+
+    /// Pin the project's Python in `toolchain`'s file; default: no pin file.
+    pub fn toolchain(mut self, toolchain: Toolchain) -> Self {
+        self.toolchain = Some(toolchain);
+        self
+    }
+
+    /// Line terminators of the generated text files; default: LF.
+    pub fn line_endings(mut self, endings: LineEndings) -> Self {
+        self.line_endings = endings;
+        self
+    }
+
+    /// Install ruff and pyright with `uv tool install` (unless `uv tool list` has them)
+    /// and call them by name, instead of making them dev dependencies.
+    pub fn tools_global(mut self, yes: bool) -> Self {
+        self.tools_global = yes;
+        self
+    }
+
+    /// Also write `scripts/bootstrap.sh` (`.ps1` on Windows): the uv commands and
+    /// post-create hooks of the scaffold, for setting the project up without py-proj.
+    pub fn emit_bootstrap(mut self, yes: bool) -> Self {
+        self.emit_bootstrap = yes;
+        self
+    }
+
+    /// Remove and recreate a `.venv` built with another Python instead of failing.
+    pub fn recreate_venv(mut self, yes: bool) -> Self {
+        self.recreate_venv = yes;
+        self
+    }
+
+    /// Seed the venv with pip, setuptools, and wheel (`uv venv --seed`).
+    pub fn venv_seed(mut self, yes: bool) -> Self {
+        self.venv_seed = yes;
+        self
+    }
+
+    /// The venv's prompt name (`uv venv --prompt`); default: the project name.
+    pub fn venv_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.venv_prompt = Some(prompt.into());
+        self
+    }
+
+    /// The venv's directory name under the project root; default: `.venv`.
+    pub fn venv_dir(mut self, name: impl Into<String>) -> Self {
+        self.venv_dir = Some(name.into());
+        self
+    }
+
+    /// The notebooks directory's name under the project root; default: `Notebooks`.
+    pub fn notebooks_dir(mut self, name: impl Into<String>) -> Self {
+        self.notebooks_dir = Some(name.into());
+        self
+    }
+
+    /// Append `arg` to the `uv venv` command line, after everything py-proj passes.
+    pub fn uv_arg(mut self, arg: impl Into<String>) -> Self {
+        self.uv_args.push(arg.into());
+        self
+    }
+
+    /// Start every generated `.py` file with an SPDX header for `spdx` (e.g. `Apache-2.0`)
+    /// and a copyright line naming the author.
+    pub fn license_header(mut self, spdx: impl Into<String>) -> Self {
+        self.license_header = Some(spdx.into());
+        self
+    }
+
+    /// Keep `path` (the `--summary-file` report, relative to the root) out of the sdist.
+    pub fn report_file(mut self, path: impl Into<String>) -> Self {
+        self.report_file = Some(path.into());
+        self
+    }
+
+    /// Pair notebooks with percent-format `.py` files: jupytext in the dev extras,
+    /// `[tool.jupytext]`, and a paired starter notebook.
+    pub fn jupytext(mut self, yes: bool) -> Self {
+        self.jupytext = yes;
+        self
+    }
+
+    /// With [`jupytext`](Self::jupytext), git-ignore the notebooks' `*.ipynb` so only the
+    /// `.py` side is committed.
+    pub fn jupytext_ignore_ipynb(mut self, yes: bool) -> Self {
+        self.jupytext_ignore_ipynb = yes;
+        self
+    }
+
+    /// Generate only the essentials: pyproject.toml, the src package, a smoke test,
+    /// .gitignore, README, and CHANGELOG, without the [`MINIMAL_SKIPS`] groups.
+    pub fn minimal(mut self, yes: bool) -> Self {
+        self.minimal = yes;
+        self
+    }
+
+    /// With [`minimal`](Self::minimal), keep this [`MINIMAL_SKIPS`] group after all, by
+    /// its manifest name (e.g. `vscode`).
+    pub fn with(mut self, component: impl Into<String>) -> Self {
+        self.with.push(component.into());
+        self
+    }
+
+    /// Also write a multi-root `<project>.code-workspace`: at `path` when it ends in
+    /// `.code-workspace`, in the directory `path` otherwise, and by default next to the
+    /// project (at the workspace root for a member).
+    pub fn code_workspace(mut self, path: Option<PathBuf>) -> Self {
+        self.code_workspace = Some(path);
+        self
+    }
+
+    /// Leave out the generated files matching `pattern`, a glob relative to the root
+    /// (`src/app_logging/*`); a directory leaves out everything below it.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Give the project root and every directory the scaffold creates the permission
+    /// bits `mode`, e.g. `0o2775` for a group-shared project; default: the umask's.
+    pub fn dir_mode(mut self, mode: u32) -> Self {
+        self.dir_mode = Some(mode);
+        self
+    }
+
+    /// Give every generated file the permission bits `mode` (execute added where it
+    /// grants read for the scripts and hooks), e.g. `0o664`; default: the umask's.
+    pub fn file_mode(mut self, mode: u32) -> Self {
+        self.file_mode = Some(mode);
+        self
+    }
+
+    /// Write docstrings, comments, README prose, and next steps in `lang` (`en`, `ja`, or
+    /// a language from the templates dir's `lang/` catalogs); default: English.
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Replace built-in templates with the files under `dir`.
+    pub fn templates_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.templates = TemplateSource::Dir(dir.into());
+        self
+    }
+
+    /// Use a git-hosted template pack (`URL` or `URL#REF`), fetched on [`build`](Self::build).
+    pub fn template_repo(mut self, spec: impl Into<String>) -> Self {
+        self.templates = TemplateSource::Repo(spec.into());
+        self
+    }
+
+    /// Re-fetch the template repo even if it is cached.
+    pub fn refresh_templates(mut self, refresh: bool) -> Self {
+        self.refresh_templates = refresh;
+        self
+    }
+
+    /// Set one template variable.
+    pub fn var(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.vars.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set several template variables at once.
+    pub fn vars(mut self, vars: Vars) -> Self {
+        self.vars.extend(vars);
+        self
+    }
+
+    /// Shell command run in the project root after a successful [`create`].
+    pub fn post_hook(mut self, command: impl Into<String>) -> Self {
+        self.post_hooks.push(command.into());
+        self
+    }
+
+    /// Scaffold a member of the uv workspace rooted at `workspace_root`.
+    pub fn workspace_member(mut self, workspace_root: impl Into<PathBuf>) -> Self {
+        self.workspace_root = Some(workspace_root.into());
+        self
+    }
+
+    /// Only write files; leave the venv (and uv) to the caller.
+    pub fn skip_uv(mut self, skip: bool) -> Self {
+        self.skip_uv = skip;
+        self
+    }
+
+    /// If uv is not installed, fetch it with the official installer before scaffolding.
+    pub fn bootstrap_uv(mut self, bootstrap: bool) -> Self {
+        self.bootstrap_uv = bootstrap;
+        self
+    }
+
+    /// Never touch the network: the Python must already be installed, uv runs with
+    /// `--offline`, and a template repo must already be cached.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Run every uv step with `--python-preference`, e.g. only-managed so uv never
+    /// picks up the distro Python.
+    pub fn python_preference(mut self, preference: PythonPreference) -> Self {
+        self.python_preference = Some(preference);
+        self
+    }
+
+    /// Allow a project name that shadows a standard-library module (it is only warned about).
+    pub fn force_name(mut self, force: bool) -> Self {
+        self.force_name = force;
+        self
+    }
+
+    /// Render the same bytes on every machine and every day: [`DETERMINISTIC_DATE`]
+    /// stands in for today's date (and the license header's year). For snapshots of
+    /// the output, as `py-proj debug render-all` takes.
+    pub fn deterministic(mut self, yes: bool) -> Self {
+        self.deterministic = yes;
+        self
+    }
+
+    /// Receive progress [`Event`]s (default: none).
+    pub fn on_event(mut self, handler: impl Fn(&Event<'_>) + 'static) -> Self {
+        self.on_event = Box::new(handler);
+        self
+    }
+
+    /// Validate the options, fetch the template repo if one was given, and fill in defaults.
+    pub fn build(mut self) -> Result<ScaffoldPlan> {
+        validate_project_name(&self.project)?;
+        let module = import_name(&self.project);
+        match name_clash(&self.project) {
+            Some(NameClash::Stdlib) if !self.force_name => {
+                return Err(crate::Error::NeedsConfirmation {
+                    action: format!(
+                        "name the project `{module}`, which shadows the standard-library module `{module}` \
+                         (stdlib; imports of it inside the project would break)"
+                    ),
+                    flag: "--force-name",
+                }
+                .into());
+            }
+            Some(clash) => {
+                let what = match clash {
+                    NameClash::Stdlib => "the standard-library module",
+                    NameClash::Pypi => "the popular PyPI package",
+                };
+                (self.on_event)(&Event::Warning {
+                    message: format!(
+                        "`{module}` shadows {what} `{module}` ({}); imports may pick the wrong one",
+                        clash.name()
+                    ),
+                });
+            }
+            None => {}
+        }
+        if self.workspace_root.is_some() && (self.venv_seed || self.venv_prompt.is_some() || !self.uv_args.is_empty()) {
+            bail!(
+                "--venv-seed, --venv-prompt, and --uv-arg shape `uv venv`, which a workspace member \
+                 does not run (it shares the workspace root's venv)"
+            );
+        }
+        if let Some(dir) = &self.venv_dir {
+            if self.workspace_root.is_some() && dir != DEFAULT_VENV_DIR {
+                bail!("--venv-dir names a project's own venv; a workspace member shares the workspace root's .venv");
+            }
+            if dir.is_empty() || dir == "." || dir == ".." || dir.contains(['/', '\\']) || dir.contains(char::is_whitespace) {
+                bail!("invalid --venv-dir {dir:?}: it must be a single directory name under the project root, like .venv312");
+            }
+        }
+        if let Some(dir) = &self.notebooks_dir {
+            if self.workspace_root.is_some() && dir != DEFAULT_NOTEBOOKS_DIR {
+                bail!("--notebooks-dir names a flat project's notebooks directory; a workspace member has none");
+            }
+            if dir.is_empty() || dir.starts_with('.') || dir.contains(['/', '\\']) || dir.contains(char::is_whitespace) {
+                bail!("invalid --notebooks-dir {dir:?}: it must be a single directory name under the project root, like notebooks, not starting with a dot");
+            }
+            if ["src", "tests"].contains(&dir.as_str()) || *dir == self.venv_dir.as_deref().unwrap_or(DEFAULT_VENV_DIR) {
+                bail!("invalid --notebooks-dir {dir:?}: the scaffold uses {dir}/ for something else");
+            }
+        }
+        if self.recreate_venv && (self.workspace_root.is_some() || self.skip_uv) {
+            bail!("--recreate-venv replaces a project's own .venv, which needs the uv steps of a flat project");
+        }
+        if let Some(prompt) = &self.venv_prompt {
+            if prompt.trim().is_empty() || prompt.contains('\n') {
+                bail!("invalid venv prompt {prompt:?}: it must be one non-empty line");
+            }
+        }
+        if self.uv_args.iter().any(|a| a.is_empty()) {
+            bail!("--uv-arg cannot be empty");
+        }
+        for component in &self.with {
+            if !MINIMAL_SKIPS.iter().any(|g| g.name() == component) {
+                let names: Vec<&str> = MINIMAL_SKIPS.iter().map(|g| g.name()).collect();
+                bail!(
+                    "--with `{component}` is not something --minimal leaves out; expected one of: {}",
+                    names.join(", ")
+                );
+            }
+        }
+        if self.jupytext_ignore_ipynb && !self.jupytext {
+            bail!("ignoring the .ipynb files only makes sense with jupytext pairing");
+        }
+        for spdx in self.license_header.iter().chain(&self.license) {
+            let valid = spdx.chars().all(|c| c.is_ascii_alphanumeric() || "-.+() ".contains(c));
+            if spdx.trim().is_empty() || !valid {
+                bail!("invalid SPDX license identifier `{spdx}`; use one like MIT or Apache-2.0");
+            }
+        }
+        for url in self.repo_url.iter_mut().chain(&mut self.homepage) {
+            *url = url.replace("{project}", &self.project);
+            if url.trim().is_empty() || url.chars().any(char::is_whitespace) {
+                bail!("invalid URL {url:?}: it must be non-empty, without spaces");
+            }
+        }
+        if let Some(name) = &self.org {
+            if name.trim().is_empty() || name.contains('\n') {
+                bail!("invalid organization name {name:?}: it must be one non-empty line");
+            }
+        }
+        for m in &self.maintainers {
+            if m.name.trim().is_empty() || m.name.contains('\n') {
+                bail!("invalid maintainer name {:?}: it must be one non-empty line", m.name);
+            }
+            if let Some(email) = &m.email {
+                if !email.contains('@') || email.chars().any(|c| c.is_whitespace() || "<>".contains(c)) {
+                    bail!("invalid email `{email}` for maintainer `{}`", m.name);
+                }
+            }
+        }
+        if let Some(bad) = self.gitignore_extra.iter().find(|p| p.trim().is_empty() || p.contains('\n')) {
+            bail!("invalid .gitignore pattern {bad:?}: it must be one non-empty line");
+        }
+        if let Some(name) = &self.entry_point {
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+            if !valid {
+                bail!(
+                    "invalid entry point `{name}`: use letters, digits, `-`, `_`, `.` \
+                     and start with a letter or digit (no spaces or slashes)"
+                );
+            }
+        }
+        for module in &self.modules {
+            validate_module(module)?;
+        }
+        if let Some(ns) = &self.namespace {
+            if self.workspace_root.is_some() {
+                bail!("--namespace lays out a standalone project's package; a workspace member has its own under src/");
+            }
+            if !validate_module(ns).is_ok_and(|parts| parts.len() == 1) {
+                bail!("invalid --namespace `{ns}`: it must be a valid Python identifier, like `acme`");
+            }
+        }
+        if !self.gen_secrets.is_empty() && self.workspace_root.is_some() {
+            bail!("--gen-secret writes to .env, which a workspace member does not have; the workspace root's is shared");
+        }
+        let mut secret_keys: Vec<String> = Vec::new();
+        if self.profile == Profile::Webapp && self.workspace_root.is_none() {
+            secret_keys.push(DEFAULT_SECRET.to_string());
+        }
+        for key in &self.gen_secrets {
+            let key = key.trim();
+            let ident = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !ident {
+                bail!("invalid --gen-secret {key:?}: use an environment variable name, like SECRET_KEY");
+            }
+            if RESERVED_ENV_KEYS.iter().any(|k| k.eq_ignore_ascii_case(key)) {
+                bail!("invalid --gen-secret {key}: the scaffold sets {} itself", key.to_ascii_uppercase());
+            }
+            // Case-insensitively: pydantic-settings reads `secret_key` from `SECRET_KEY`.
+            if !secret_keys.iter().any(|k| k.eq_ignore_ascii_case(key)) {
+                secret_keys.push(key.to_string());
+            }
+        }
+        let secrets = secret_keys
+            .into_iter()
+            .map(|key| Ok((key, new_secret(self.deterministic)?)))
+            .collect::<Result<Vec<_>>>()?;
+        for pattern in &self.exclude {
+            let escapes = Path::new(pattern).is_absolute() || pattern.split(['/', '\\']).any(|s| s == "..");
+            if pattern.trim().is_empty() || escapes {
+                bail!("invalid --exclude `{pattern}`: use a path or glob relative to the project root");
+            }
+        }
+        if let Some(mode) = self.dir_mode.filter(|m| m & 0o700 != 0o700 || *m > 0o7777) {
+            bail!("invalid --dir-mode {}: the owner needs rwx (7) to fill the directories", format_mode(mode));
+        }
+        if let Some(mode) = self.file_mode.filter(|m| m & 0o600 != 0o600 || *m > 0o777) {
+            bail!(
+                "invalid --file-mode {}: the owner needs rw (6), and setuid, setgid, and sticky bits do not apply",
+                format_mode(mode)
+            );
+        }
+        let mut pytest_markers: Vec<String> = Vec::new();
+        for marker in &self.pytest_markers {
+            let (name, description) = marker.split_once(':').unwrap_or((marker, ""));
+            let (name, description) = (name.trim(), description.trim());
+            let ident = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !ident || marker.contains('\n') {
+                bail!("invalid pytest marker {marker:?}: use `name` or `name:description` with an identifier name");
+            }
+            let marker = match description {
+                "" => name.to_string(),
+                _ => format!("{name}: {description}"),
+            };
+            if !pytest_markers.contains(&marker) {
+                pytest_markers.push(marker);
+            }
+        }
+        if let Some(key) = self.vars.keys().find(|k| BUILTIN_VARS.contains(&k.as_str())) {
+            bail!("`{key}` is a built-in template variable and cannot be overridden");
+        }
+        let root = match self.root {
+            Some(root) => root,
+            None => std::env::current_dir()?.join(&self.project),
+        };
+        let code_workspace = match self.code_workspace {
+            Some(path) => {
+                let default = match &self.workspace_root {
+                    Some(ws) => ws.clone(),
+                    None => std::path::absolute(&root)?.parent().map(Path::to_path_buf).unwrap_or_default(),
+                };
+                let path = path.unwrap_or(default);
+                let path = match path.extension().is_some_and(|ext| ext == "code-workspace") {
+                    true => path,
+                    false => path.join(format!("{}.code-workspace", self.project)),
+                };
+                Some(canonicalize_lenient(&path))
+            }
+            None => None,
+        };
+        if let Some(bot) = self.dep_updates {
+            let in_git = root.ancestors().any(|d| d.join(".git").exists());
+            if !in_git && self.post_hooks.iter().all(|h| !h.contains("git init")) {
+                (self.on_event)(&Event::Warning {
+                    message: format!(
+                        "--dep-updates {} only takes effect in a hosted git repository, and {} is not in one",
+                        bot.name(),
+                        root.display()
+                    ),
+                });
+            }
+        }
+        if let Some(target) = self.publish {
+            if self.workspace_root.is_some() {
+                bail!(
+                    "--publish {} writes a workflow for a standalone project; workspace members are released from the workspace root",
+                    target.name()
+                );
+            }
+            // The workflow is GitHub Actions; a repository hosted elsewhere could not run it.
+            if let Some(host) = self.repo_url.as_deref().and_then(url_host).filter(|h| *h != "github.com") {
+                bail!(
+                    "--publish {} writes a GitHub Actions workflow, but --repo-url is hosted on {host}; \
+                     only GitHub releases are supported for now",
+                    target.name()
+                );
+            }
+            if self.license.is_none() {
+                (self.on_event)(&Event::Warning {
+                    message: format!(
+                        "--publish {}: no license is set, so PyPI will list the project without one; \
+                         pass --license (or set `license` in the config's [org] table)",
+                        target.name()
+                    ),
+                });
+            }
+        }
+        let data_layout = self.data_layout || self.profile == Profile::Datasci;
+        if self.dvc && !data_layout {
+            bail!("--dvc tracks the data layout's directories; add --data-layout (or use --template datasci)");
+        }
+        if (self.data_layout || self.dvc) && self.workspace_root.is_some() {
+            bail!("--data-layout and --dvc lay out a standalone project; set them up at the workspace root");
+        }
+        if let Some(hooks) = self.git_hooks {
+            if self.workspace_root.is_some() {
+                bail!(
+                    "--git-hooks {} writes hooks for a standalone project; set them up at the workspace root",
+                    hooks.name()
+                );
+            }
+            if git_work_tree(&root).is_none() {
+                (self.on_event)(&Event::Warning {
+                    message: format!(
+                        "--git-hooks {}: {} is not in a git repository, so the hooks are written but not enabled; \
+                         run `git config core.hooksPath {GIT_HOOKS_DIR}` after `git init`",
+                        hooks.name(),
+                        root.display()
+                    ),
+                });
+            }
+        }
+        if let Some(toolchain) = self.toolchain {
+            if self.workspace_root.is_some() {
+                bail!(
+                    "--toolchain-file {} pins the Python of a standalone project; pin it at the workspace root",
+                    toolchain.name()
+                );
+            }
+            // A second pin in the project would only disagree with the one written.
+            let others: Vec<&str> = std::iter::once(&".python-version")
+                .chain(TOOL_FILES)
+                .copied()
+                .filter(|f| *f != toolchain.file() && root.join(f).is_file())
+                .collect();
+            if !others.is_empty() {
+                (self.on_event)(&Event::Warning {
+                    message: format!(
+                        "--toolchain-file {} writes {}, but {} in {} also pins Python; remove it so the two cannot disagree",
+                        toolchain.name(),
+                        toolchain.file(),
+                        others.join(" and "),
+                        root.display()
+                    ),
+                });
+            }
+        }
+        let requested = match self.python {
+            Some(python) => python.parse::<PythonVersion>().map_err(anyhow::Error::msg)?,
+            None => detect_python(root.parent().unwrap_or(&root)).0,
+        };
+        let python = match self.skip_uv {
+            true => requested,
+            false => requested.resolve(self.offline),
+        };
+        let mut matrix: Vec<PythonVersion> = Vec::new();
+        for version in &self.python_matrix {
+            let parsed = version.parse::<PythonVersion>().map_err(anyhow::Error::msg)?;
+            if parsed.minor.is_none() || !parsed.suffix.is_empty() {
+                bail!("invalid --python-matrix entry `{version}`: expected MAJOR.MINOR, e.g. 3.12");
+            }
+            if !matrix.iter().any(|v| v.mm() == parsed.mm()) {
+                matrix.push(parsed);
+            }
+        }
+        matrix.sort();
+        let python_matrix: Vec<String> = matrix.iter().map(PythonVersion::mm).collect();
+        if !python_matrix.is_empty() && !python_matrix.contains(&python.mm()) {
+            bail!(
+                "Python {python} is not in the matrix ({}); add {} or pick one of them with --python",
+                python_matrix.join(", "),
+                python.mm()
+            );
+        }
+        let (profile, source, templates_dir) = match self.templates {
+            TemplateSource::Builtin => ("builtin", None, None),
+            TemplateSource::Dir(dir) => {
+                let source = std::path::absolute(&dir)?.display().to_string();
+                ("templates-dir", Some(source), Some(dir))
+            }
+            TemplateSource::Repo(spec) => {
+                let repo = TemplateRepo::parse(&spec)?;
+                let dir = match self.offline {
+                    true => repo.cached()?,
+                    false => repo.fetch(self.refresh_templates, &*self.on_event)?,
+                };
+                ("template-repo", Some(spec), Some(dir))
+            }
+        };
+        let strings = Strings::load(self.lang.as_deref().unwrap_or(DEFAULT_LANG), templates_dir.as_deref())?;
+        let plan = ScaffoldPlan {
+            root,
+            project: self.project,
+            python,
+            description: self.description,
+            author: self.author,
+            email: self.email,
+            repo_url: self.repo_url,
+            homepage: self.homepage,
+            org: self.org,
+            license: self.license,
+            maintainers: self.maintainers,
+            citation: self.citation,
+            date: match self.deterministic {
+                true => DETERMINISTIC_DATE.to_string(),
+                false => today(),
+            },
+            profile: self.profile,
+            gitignore_extra: self.gitignore_extra.iter().map(|p| p.trim().to_string()).collect(),
+            entry_point: self.entry_point,
+            namespace: self.namespace,
+            secrets,
+            modules: self.modules,
+            pytest_addopts: self.pytest_addopts.filter(|a| !a.trim().is_empty()),
+            pytest_markers,
+            testing_extras: self.testing_extras,
+            async_main: self.async_main,
+            benchmarks: self.benchmarks,
+            data_layout,
+            dvc: self.dvc,
+            python_matrix,
+            matrix_runner: self.matrix_runner,
+            dep_style: self.dep_style,
+            dep_updates: self.dep_updates,
+            publish: self.publish,
+            git_hooks: self.git_hooks,
+            security: self.security,
+            status: self.status,
+            ide: self.ide,
+            toolchain: self.toolchain,
+            line_endings: self.line_endings,
+            tools_global: self.tools_global,
+            emit_bootstrap: self.emit_bootstrap,
+            license_header: self.license_header,
+            report_file: self.report_file,
+            jupytext: self.jupytext,
+            jupytext_ignore_ipynb: self.jupytext_ignore_ipynb,
+            minimal: self.minimal,
+            with: self.with,
+            code_workspace,
+            recreate_venv: self.recreate_venv,
+            venv_seed: self.venv_seed,
+            venv_prompt: self.venv_prompt,
+            venv_dir: self.venv_dir.filter(|d| d != DEFAULT_VENV_DIR),
+            notebooks_dir: self.notebooks_dir.filter(|d| d != DEFAULT_NOTEBOOKS_DIR),
+            uv_args: self.uv_args,
+            exclude: self.exclude,
+            dir_mode: self.dir_mode,
+            file_mode: self.file_mode,
+            strings,
+            templates_dir,
+            template_profile: profile.to_string(),
+            template_source: source,
+            vars: self.vars,
+            post_hooks: self.post_hooks,
+            workspace_root: self.workspace_root,
+            skip_uv: self.skip_uv,
+            bootstrap_uv: self.bootstrap_uv,
+            offline: self.offline,
+            python_preference: self.python_preference,
+            created: RefCell::default(),
+            installed_python: OnceCell::new(),
+            registered: Cell::default(),
+            on_event: self.on_event,
+        };
+        for warning in plan.exclude_warnings()? {
+            plan.warn(warning);
+        }
+        if !self.gen_secrets.is_empty() && !plan.writes(".env") {
+            plan.warn("--gen-secret has no .env to go in (left out by --minimal or --exclude)".to_string());
+        }
+        if cfg!(not(unix)) && (plan.dir_mode.is_some() || plan.file_mode.is_some()) {
+            plan.warn("--dir-mode and --file-mode are ignored on Windows, which has no permission bits".to_string());
+        }
+        Ok(plan)
+    }
+}
+
+/// How [`create`] treats existing files and failures.
+#[derive(Debug, Clone)]
+pub struct CreateOptions {
+    /// Write the `.pyproj.toml` manifest.
+    pub manifest: bool,
+    /// What happens to existing files whose content differs.
+    pub policy: WritePolicy,
+    /// Per-file exceptions to `policy`, by relative path (see [`ScaffoldPlan::conflicts`]).
+    pub file_policies: BTreeMap<PathBuf, WritePolicy>,
+    /// Overwrite an existing pyproject.toml that differs instead of merging the
+    /// generated tool tables and dev extras into it.
+    pub replace_pyproject: bool,
+    /// Leave a partially created project in place when something fails.
+    pub keep_on_failure: bool,
+}
+
+impl Default for CreateOptions {
+    fn default() -> Self {
+        CreateOptions {
+            manifest: true,
+            policy: WritePolicy::Skip,
+            file_policies: BTreeMap::new(),
+            replace_pyproject: false,
+            keep_on_failure: false,
+        }
+    }
+}
+
+/// Scaffold `plan`: write its files, run uv, then the post-create hooks.
+/// On failure everything this run created is rolled back unless `keep_on_failure`.
+pub fn create(plan: &ScaffoldPlan, opts: &CreateOptions) -> Result<WriteSummary> {
+    scaffold(plan, None, opts)
+}
+
+/// [`create`] with `actions` planned earlier (see [`crate::plan_file`]) instead of
+/// the ones `plan` would compute now; `plan` supplies the root, the venv, and the hooks.
+pub fn apply(plan: &ScaffoldPlan, actions: Vec<PlannedAction>, opts: &CreateOptions) -> Result<WriteSummary> {
+    scaffold(plan, Some(actions), opts)
+}
+
+fn scaffold(plan: &ScaffoldPlan, planned: Option<Vec<PlannedAction>>, opts: &CreateOptions) -> Result<WriteSummary> {
+    if let Some(dir) = &plan.templates_dir {
+        if !dir.is_dir() {
+            bail!("templates dir not found: {}", dir.display());
+        }
+    }
+
+    // Re-creating a project in place is fine; scaffolding inside another one is not.
+    let root = canonicalize_lenient(&plan.root);
+    let workspace = plan.workspace_root.as_deref().map(canonicalize_lenient);
+    let outer = root
+        .ancestors()
+        .skip(1)
+        .find(|d| d.join(MANIFEST_FILE).is_file() && workspace.as_deref() != Some(*d));
+    if let Some(outer) = outer {
+        bail!(
+            "{} is inside the py-proj project at {}; pick an --outdir outside it \
+             (or use --workspace-member for a uv workspace)",
+            root.display(),
+            outer.display()
+        );
+    }
+
+    // Held until the hooks are done; a second run against this root waits its turn.
+    let lock = ProjectLock::acquire(&plan.root, "create", true, &|message| plan.warn(message))?;
+    if let Some(mode) = plan.dir_mode {
+        set_dir_mode(&plan.root, mode)?;
+    }
+    plan.check_venv()?;
+    let mut checks = WriteSummary::default();
+    if plan.bootstrap_uv && !plan.skip_uv && which::which(uv_bin()).is_err() {
+        if plan.offline {
+            bail!("uv is not installed and --offline rules out --bootstrap-uv; install uv first");
+        }
+        // Before planning, so the uv steps run the freshly installed binary.
+        let started = Instant::now();
+        plan.install_uv()?;
+        checks.time("install uv".to_string(), started.elapsed());
+    }
+
+    // Asking uv for its installed Pythons is cheap next to an install it may save.
+    let started = Instant::now();
+    let already_installed = plan.skips_python_install();
+    if plan.installs_python() && !plan.offline {
+        let decision = match &already_installed {
+            Some(found) => format!("find installed Python: {found}, install skipped"),
+            None => "find installed Python: none, installing".to_string(),
+        };
+        checks.time(decision, started.elapsed());
+    }
+
+    // Render everything up front so a broken template never leaves a half-written project.
+    let actions = match planned {
+        Some(actions) => actions,
+        None => {
+            let started = Instant::now();
+            let actions = plan.plan(opts.manifest)?;
+            checks.time("render templates".to_string(), started.elapsed());
+            actions
+        }
+    };
+    // Likewise, a missing or outdated uv fails before the first file is written.
+    let uv = uv_bin();
+    if actions.iter().any(|a| matches!(a, PlannedAction::RunCommand { program, .. } if *program == uv)) {
+        let started = Instant::now();
+        check_uv()?;
+        match (plan.offline, plan.workspace_root.is_none()) {
+            (true, true) => plan.ensure_python_installed()?,
+            (false, true) => plan.check_python_available()?,
+            _ => {}
+        }
+        checks.time("check uv and Python".to_string(), started.elapsed());
+    }
+
+    let created_root = lock.created_root();
+    let preexisting = match opts.manifest {
+        true => Some(plan.preexisting(created_root)?),
+        false => None,
+    };
+    let executed = interruptible(|| {
+        plan.execute_with(&actions, opts.policy, &opts.file_policies, opts.replace_pyproject)
+    });
+    let mut summary = match executed {
+        Ok(summary) => summary,
+        Err(e) => {
+            if opts.keep_on_failure {
+                plan.warn(format!("keeping partial project at {}", plan.root.display()));
+                return Err(e);
+            }
+            let files = plan.rollback(created_root);
+            return match Error::find(&e) {
+                Some(Error::Interrupted { program, .. }) => Err(Error::Interrupted {
+                    program: program.clone(),
+                    rolled_back: Some(files),
+                }
+                .into()),
+                _ => Err(e),
+            };
+        }
+    };
+    summary.timings.splice(0..0, checks.timings);
+    summary.python_already_installed = already_installed;
+    if let Some(preexisting) = preexisting {
+        plan.record_preexisting(preexisting)?;
+    }
+    if !plan.post_hooks.is_empty() {
+        let started = Instant::now();
+        plan.run_post_hooks()?; // user hooks, only after everything else succeeded
+        summary.time("post-create hooks".to_string(), started.elapsed());
+    }
+
+    Ok(summary)
+}
+
+/// Fail when the rendered `contents` of `path` do not parse as what its extension says
+/// (JSON, TOML, YAML, or a notebook), naming the line and column, so a template edit or
+/// a value with a stray quote is caught before anything is written instead of by the
+/// tool that later reads the file. Other files are not checked.
+fn check_syntax(path: &str, contents: &[u8]) -> Result<()> {
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    let kind = match ext {
+        "json" | "code-workspace" => "JSON",
+        "ipynb" => "notebook JSON",
+        "toml" => "TOML",
+        "yml" | "yaml" | "cff" => "YAML",
+        _ => return Ok(()),
+    };
+    let text = String::from_utf8_lossy(contents);
+    let invalid = |line: usize, column: usize, message: &str| {
+        anyhow::anyhow!("{path} does not render to valid {kind} (line {line}, column {column}): {message}")
+    };
+    match ext {
+        "toml" => {
+            if let Err(e) = toml::from_str::<toml::Table>(&text) {
+                let at = e.span().map_or(0, |span| span.start);
+                let (line, column) = line_column(&text, at);
+                return Err(invalid(line, column, e.message()));
+            }
+        }
+        "yml" | "yaml" | "cff" => {
+            // A workflow or compose file may hold several `---` documents.
+            for document in serde_yaml::Deserializer::from_str(&text) {
+                if let Err(e) = serde_yaml::Value::deserialize(document) {
+                    let (line, column) = e.location().map_or((1, 1), |at| (at.line(), at.column()));
+                    let message = e.to_string();
+                    let message = message.split(" at line ").next().unwrap_or(&message);
+                    return Err(invalid(line, column, message));
+                }
+            }
+        }
+        _ => {
+            let value: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+                let at = format!(" at line {} column {}", e.line(), e.column());
+                invalid(e.line(), e.column(), e.to_string().trim_end_matches(&at))
+            })?;
+            if ext == "ipynb" {
+                let missing = [("cells", value["cells"].is_array()), ("metadata", value["metadata"].is_object())]
+                    .into_iter()
+                    .chain(["nbformat", "nbformat_minor"].map(|key| (key, value[key].is_u64())))
+                    .find(|(_, ok)| !ok);
+                if let Some((key, _)) = missing {
+                    return Err(invalid(1, 1, &format!("no `{key}` of the right type")));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The 1-based line and column of byte `at` in `text`.
+fn line_column(text: &str, at: usize) -> (usize, usize) {
+    let before = &text[..at.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// How other files refer to the generated file `path`: by path, by file name (unless it
+/// is a generic `__init__.py`), and for Python modules by dotted import path.
+fn reference_needles(path: &str) -> Vec<String> {
+    let mut needles = vec![path.to_string()];
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if name != "__init__.py" && name != path {
+        needles.push(name.to_string());
+    }
+    if let Some(module) = path.strip_suffix(".py") {
+        let module = module.trim_end_matches("/__init__").replace('/', ".");
+        if module.contains('.') {
+            needles.push(module);
+        }
+    }
+    needles
+}
+
+/// Whether `needle` occurs in `text` as a whole word: not glued to a longer name
+/// (`.env` is not mentioned by `.envrc`).
+fn mentions(text: &str, needle: &str) -> bool {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    text.match_indices(needle).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + needle.len()..].chars().next();
+        !before.is_some_and(is_name) && !after.is_some_and(is_name)
+    })
+}
+
+/// Point every `${workspaceFolder}` in `value` at `folder` (`${workspaceFolder:name}`),
+/// and paths into its `.venv` at `venv` (a `--venv-dir` venv is the folder's own).
+fn qualify_workspace_folder(value: &mut serde_json::Value, folder: &str, venv: &str) {
+    match value {
+        serde_json::Value::String(s) => {
+            *s = s
+                .replace("${workspaceFolder}/.venv/", &format!("{venv}/"))
+                .replace("${workspaceFolder}", folder);
+        }
+        serde_json::Value::Array(items) => {
+            items.iter_mut().for_each(|v| qualify_workspace_folder(v, folder, venv))
+        }
+        serde_json::Value::Object(map) => {
+            map.values_mut().for_each(|v| qualify_workspace_folder(v, folder, venv))
+        }
+        _ => {}
+    }
+}
+
+/// The `--license-header` copyright holder: `author`, else "the `<project>` authors".
+pub fn license_holder(author: Option<&str>, project: &str) -> String {
+    match author {
+        Some(author) => author.to_string(),
+        None => format!("the {project} authors"),
+    }
+}
+
+/// The host of a repository URL: `https://github.com/acme/x` and `git@github.com:acme/x`
+/// both give `github.com`.
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    host.split(':').next().filter(|h| !h.is_empty())
+}
+
+/// The exit code of a finished command: 0 on success, the failing status otherwise,
+/// `None` when it did not start, timed out, or was killed by a signal.
+fn exit_code<T>(result: &Result<T>) -> Option<i32> {
+    match result {
+        Ok(_) => Some(0),
+        Err(e) => match e.downcast_ref::<Error>() {
+            Some(Error::CommandFailed { status, .. }) => status.code(),
+            _ => None,
+        },
+    }
+}
+
+/// A value for a generated secret: random, or [`SECRET_PLACEHOLDER`] when the output has
+/// to be reproducible (a secret anyone can regenerate is no secret).
+fn new_secret(deterministic: bool) -> Result<String> {
+    match deterministic || crate::manifest::is_deterministic() {
+        true => Ok(SECRET_PLACEHOLDER.to_string()),
+        false => random_secret(),
+    }
+}
+
+/// The recorded secret `keys` with the values in the project's `.env`, so a re-render
+/// matches it; a key missing from `.env` (or a missing `.env`) gets a new value.
+fn recorded_secrets(root: &Path, keys: &[String]) -> Result<Vec<(String, String)>> {
+    let env = fs::read_to_string(root.join(".env")).unwrap_or_default();
+    keys.iter()
+        .map(|key| {
+            let current = env.lines().find_map(|line| line.strip_prefix(key.as_str())?.strip_prefix('='));
+            let value = match current {
+                Some(value) => value.to_string(),
+                None => new_secret(false)?,
+            };
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
+/// Today's date (UTC), YYYY-MM-DD; see [`crate::manifest::build_time`].
+fn today() -> String {
+    crate::manifest::build_time().format("%Y-%m-%d").to_string()
+}
+
+/// The platform shell and its "run this string" flag.
+fn shell() -> (&'static str, &'static str) {
+    if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::REGISTRY;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    /// Values a user may type that a template has to quote or escape.
+    const DESCRIPTION: &str = r#"A "quoted" tool: C:\temp, 'single', #hash, {{ braces }}, naïve café ☕"#;
+    const AUTHOR: &str = r#"Zoë "Z" O'Brien \ Jr."#;
+
+    /// Option sets that between them render every built-in template.
+    fn combinations() -> Vec<ScaffoldPlanBuilder> {
+        let tricky = |project: &str| {
+            ScaffoldPlan::builder(project)
+                .python("3.12")
+                .skip_uv(true)
+                .deterministic(true)
+                .description(DESCRIPTION)
+                .author(AUTHOR)
+                .email("zoe+py@example.com")
+        };
+        vec![
+            tricky("demo"),
+            tricky("demo").minimal(true),
+            tricky("demo")
+                .profile(Profile::Cli)
+                .entry_point("demo")
+                .publish(Publish::Pypi)
+                .license("MIT")
+                .license_header("MIT")
+                .citation(true)
+                .repo_url("https://github.com/example/demo")
+                .homepage("https://example.com/\"demo\"")
+                .status(Status::Beta)
+                .security(Security::Bandit)
+                .dep_updates(DepUpdates::Renovate)
+                .matrix_python("3.11")
+                .matrix_python("3.12")
+                .matrix_runner(MatrixRunner::Tox)
+                .testing_extra(TestingExtra::Hypothesis)
+                .benchmarks(true)
+                .git_hooks(GitHooks::Native)
+                .toolchain(Toolchain::Mise),
+            tricky("demo")
+                .profile(Profile::Datasci)
+                .data_layout(true)
+                .dvc(true)
+                .jupytext(true)
+                .dep_updates(DepUpdates::Dependabot)
+                .matrix_python("3.12")
+                .matrix_python("3.13")
+                .dep_style(DepStyle::Groups)
+                .toolchain(Toolchain::Asdf)
+                .ide(Ide::Neovim),
+            tricky("demo")
+                .profile(Profile::Webapp)
+                .async_main(true)
+                .toolchain(Toolchain::PythonVersion)
+                .line_endings(LineEndings::Crlf),
+            tricky("demo").namespace("acme").emit_bootstrap(true).security(Security::RuffS),
+        ]
+    }
+
+    #[test]
+    fn every_template_renders_to_valid_files_with_tricky_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rendered = BTreeSet::new();
+        for (i, builder) in combinations().into_iter().enumerate() {
+            let root = dir.path().join(i.to_string());
+            let plan = builder.root(&root).build().unwrap();
+            // `plan` checks every JSON, TOML, and YAML file it renders.
+            for action in plan.plan(false).unwrap_or_else(|e| panic!("combination {i}: {e:#}")) {
+                if let PlannedAction::WriteFile { path, contents, .. } = action {
+                    let path = path.to_string_lossy().replace('\\', "/");
+                    check_syntax(&path, &contents).unwrap();
+                    rendered.insert(path);
+                }
+            }
+        }
+        let missing: Vec<&str> = REGISTRY
+            .iter()
+            .map(|t| t.path)
+            .filter(|path| !rendered.iter().any(|r| r == path || r.ends_with(&format!("/{}", path.trim_start_matches("src/")))))
+            .collect();
+        assert!(missing.is_empty(), "never rendered: {missing:?}");
+    }
+
+    #[test]
+    fn check_syntax_names_the_line_and_column() {
+        let cases = [
+            ("pyrightconfig.json", "{\n  \"a\": 1,\n}\n", "line 3"),
+            ("pyproject.toml", "[project]\nname = \"a\"b\"\n", "line 2"),
+            ("CITATION.cff", "title: \"open\nauthors: []\n", "line 3"),
+            (".github/workflows/ci.yml", "jobs:\n\tbuild: {}\n", "line 2"),
+            ("docker-compose.yml", "a: [1, 2\n", "line 2"),
+            ("Notebooks/demo.ipynb", "{\"cells\": []}", "valid notebook JSON (line 1, column 1): no `metadata`"),
+        ];
+        for (path, text, expected) in cases {
+            let err = check_syntax(path, text.as_bytes()).expect_err(path).to_string();
+            assert!(err.starts_with(&format!("{path} does not render to valid")), "{err}");
+            assert!(err.contains(expected), "{path}: {err}");
+        }
+        check_syntax("ci.yml", b"a: 1\n---\nb: 2\n").unwrap();
+        check_syntax("notes.txt", b"{ not json").unwrap();
+    }
+
+    /// The directories and files `builder`'s plan creates, in order, as `D path` / `W path`.
+    fn planned(builder: ScaffoldPlanBuilder) -> Vec<String> {
+        let plan = builder.root("/nonexistent/demo").python("3.12").skip_uv(true).deterministic(true).build().unwrap();
+        plan.plan(false)
+            .unwrap()
+            .into_iter()
+            .filter_map(|action| match action {
+                PlannedAction::CreateDir { path } => Some(format!("D {}", path.display())),
+                PlannedAction::WriteFile { path, .. } => Some(format!("W {}", path.display())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const APP_LOGGING: [&str; 7] = [
+        "W src/app_logging/__init__.py",
+        "W src/app_logging/MyColoredFormatter.py",
+        "W src/app_logging/config07.json",
+        "W src/app_logging/constants.py",
+        "W src/app_logging/glogger.py",
+        "W src/app_logging/myCustomJsonClass01.py",
+        "W src/app_logging/myFilters.py",
+    ];
+
+    #[test]
+    fn default_plan_writes_exactly_these_paths() {
+        let mut expected = vec![
+            "D src", "D tests", "D Notebooks", "D .vscode", "D src/app_logging",
+            "W src/__init__.py", "W src/main.py", "W src/__main__.py",
+            "W .vscode/launch.json", "W .vscode/settings.json", "W .vscode/tasks.json",
+            "W .env", "W .env.example", "W .envrc", "W pyrefly.toml", "W pyrightconfig.json", "W pyproject.toml",
+            "W .gitignore", "W .gitattributes", "W .editorconfig", "W README.md", "W CHANGELOG.md", "W Makefile",
+        ];
+        expected.extend(APP_LOGGING);
+        assert_eq!(planned(ScaffoldPlan::builder("demo")), expected);
+    }
+
+    #[test]
+    fn minimal_plan_leaves_out_the_extras() {
+        let expected = [
+            "D src", "D tests",
+            "W src/__init__.py", "W src/main.py", "W src/__main__.py",
+            "W pyrightconfig.json", "W pyproject.toml", "W .gitignore", "W .gitattributes", "W .editorconfig",
+            "W README.md", "W CHANGELOG.md", "W tests/test_main.py",
+        ];
+        assert_eq!(planned(ScaffoldPlan::builder("demo").minimal(true)), expected);
+    }
+
+    #[test]
+    fn entry_point_and_citation_add_their_files() {
+        let default = planned(ScaffoldPlan::builder("demo"));
+        let with = planned(ScaffoldPlan::builder("demo").entry_point("demo").citation(true));
+        let added: Vec<&String> = with.iter().filter(|p| !default.contains(p)).collect();
+        assert_eq!(added, ["W src/cli.py", "W CITATION.cff"]);
+        assert!(default.iter().all(|p| with.contains(p)));
+    }
+
+    #[test]
+    fn env_example_has_every_key_of_env_and_no_values() {
+        let plan = ScaffoldPlan::builder("demo")
+            .data_layout(true)
+            .gen_secret("SECRET_KEY")
+            .root("/nonexistent/demo")
+            .python("3.12")
+            .skip_uv(true)
+            .build()
+            .unwrap();
+        let files: BTreeMap<String, String> = plan
+            .render_files()
+            .unwrap()
+            .into_iter()
+            .map(|(rel, bytes)| (rel, String::from_utf8(bytes).unwrap()))
+            .collect();
+        let settings = |text: &str| -> Vec<(String, String)> {
+            text.lines()
+                .filter(|l| !l.trim_start().starts_with('#'))
+                .filter_map(|l| l.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let env = settings(&files[".env"]);
+        let example = settings(&files[".env.example"]);
+        let keys = |s: &[(String, String)]| s.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        assert_eq!(keys(&example), keys(&env));
+        assert!(keys(&env).contains(&"SECRET_KEY".to_string()), "{env:?}");
+        assert!(env.iter().all(|(_, v)| !v.is_empty()), "{env:?}");
+        assert!(example.iter().all(|(_, v)| v.is_empty()), "{example:?}");
+
+        let gitignore: Vec<&str> = files[".gitignore"].lines().collect();
+        assert!(gitignore.contains(&".env") && gitignore.contains(&"!.env.example"), "{gitignore:?}");
+        assert!(files["README.md"].contains("cp -n .env.example .env"));
+    }
+
+    #[test]
+    fn a_thousand_files_are_written_in_parallel_and_rolled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("demo");
+        let plan = ScaffoldPlan::builder("demo").root(&root).python("3.12").skip_uv(true).build().unwrap();
+        let mut actions: Vec<PlannedAction> = (0..1000)
+            .map(|i| PlannedAction::WriteFile {
+                path: PathBuf::from(format!("pack/{}/{}/file{i}.txt", i % 10, i % 7)),
+                contents: format!("file {i}\n").into_bytes(),
+                origin: Origin::Extra,
+            })
+            .collect();
+        actions.push(PlannedAction::WriteFile {
+            path: PathBuf::from(".pyproj.toml"),
+            contents: b"written last\n".to_vec(),
+            origin: Origin::Manifest,
+        });
+
+        let summary = plan.execute(&actions, WritePolicy::Skip).unwrap();
+        assert_eq!(summary.created, 1001);
+        for i in (0..1000).step_by(37) {
+            let path = root.join(format!("pack/{}/{}/file{i}.txt", i % 10, i % 7));
+            assert_eq!(fs::read_to_string(path).unwrap(), format!("file {i}\n"));
+        }
+        let phases: Vec<&str> = summary.timings.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(phases, ["write templates-dir extras", "write manifest"]);
+
+        let again = plan.execute(&actions, WritePolicy::Skip).unwrap();
+        assert_eq!((again.created, again.unchanged), (0, 1001));
+        assert_eq!(plan.rollback(false), 1001);
+        assert!(!root.join("pack").exists());
+    }
+
+    /// `requires-python` and the classifiers of the pyproject.toml `builder` renders.
+    fn python_fields(builder: ScaffoldPlanBuilder) -> (String, Vec<String>) {
+        let plan = builder.root("/nonexistent/demo").skip_uv(true).deterministic(true).build().unwrap();
+        let (_, text) = plan.render_files().unwrap().into_iter().find(|(path, _)| path == "pyproject.toml").unwrap();
+        let doc: toml::Table = String::from_utf8(text).unwrap().parse().unwrap();
+        let project = &doc["project"];
+        let classifiers = project["classifiers"].as_array().unwrap().iter().map(|c| c.as_str().unwrap().to_string());
+        (project["requires-python"].as_str().unwrap().to_string(), classifiers.collect())
+    }
+
+    #[test]
+    fn requires_python_and_classifiers_follow_the_python_choice() {
+        const PY: &str = "Programming Language :: Python :: ";
+        let base = [format!("{PY}3"), format!("{PY}3 :: Only")];
+
+        // Two components, as a detected system Python may report, or a full patch.
+        for (python, mm) in [("3.12", "3.12"), ("3.13.1", "3.13"), ("3.11", "3.11")] {
+            let (requires, classifiers) = python_fields(ScaffoldPlan::builder("demo").python(python));
+            assert_eq!(requires, format!(">={mm}"), "{python}");
+            assert_eq!(classifiers, [base[0].clone(), base[1].clone(), format!("{PY}{mm}")], "{python}");
+        }
+
+        let (requires, classifiers) = python_fields(
+            ScaffoldPlan::builder("demo")
+                .python("3.12")
+                .matrix_python("3.13")
+                .matrix_python("3.11")
+                .matrix_python("3.12")
+                .matrix_python("3.11")
+                .status(Status::Beta),
+        );
+        // Sorted and deduplicated, so the oldest is the floor whatever the flag order.
+        assert_eq!(requires, ">=3.11");
+        let versions = ["3.11", "3.12", "3.13"].map(|v| format!("{PY}{v}"));
+        let expected: Vec<String> = [Status::Beta.classifier().to_string()].into_iter().chain(base).chain(versions).collect();
+        assert_eq!(classifiers, expected);
+
+        let plan = ScaffoldPlan::builder("demo").root("/nonexistent/demo").skip_uv(true).python("3.10").matrix_python("3.12").build();
+        let err = plan.err().expect("a Python outside the matrix is refused");
+        assert!(err.to_string().contains("Python 3.10 is not in the matrix (3.12)"), "{err}");
+    }
+
+    #[test]
+    fn the_toolchain_file_decides_the_one_pin_written() {
+        let pins = [".python-version", ".tool-versions", ".mise.toml"];
+        let cases = [
+            (None, None),
+            (Some(Toolchain::PythonVersion), Some("3.12.4\n")),
+            (Some(Toolchain::Asdf), Some("python 3.12.4\n")),
+            (Some(Toolchain::Mise), Some("[tools]\npython = \"3.12.4\"\n")),
+        ];
+        for (toolchain, expected) in cases {
+            let mut builder = ScaffoldPlan::builder("demo").root("/nonexistent/demo").python("3.12.4").skip_uv(true);
+            if let Some(toolchain) = toolchain {
+                builder = builder.toolchain(toolchain);
+            }
+            let files: BTreeMap<String, String> = builder
+                .build()
+                .unwrap()
+                .render_files()
+                .unwrap()
+                .into_iter()
+                .map(|(path, bytes)| (path, String::from_utf8(bytes).unwrap()))
+                .collect();
+            let written: Vec<&str> = pins.into_iter().filter(|p| files.contains_key(*p)).collect();
+            assert_eq!(written, toolchain.map(Toolchain::file).into_iter().collect::<Vec<_>>());
+            if let (Some(toolchain), Some(expected)) = (toolchain, expected) {
+                let pin = &files[toolchain.file()];
+                assert!(pin.contains(expected), "{}:\n{pin}", toolchain.file());
+            }
+            let use_mise = files[".envrc"].lines().any(|l| l == "use mise");
+            assert_eq!(use_mise, toolchain == Some(Toolchain::Mise), "{toolchain:?}");
+        }
+    }
+
+    #[test]
+    fn a_custom_notebooks_dir_replaces_every_reference_to_notebooks() {
+        let builder = || {
+            ScaffoldPlan::builder("demo")
+                .root("/nonexistent/demo")
+                .python("3.12")
+                .skip_uv(true)
+                .deterministic(true)
+                .profile(Profile::Datasci)
+                .jupytext(true)
+        };
+        let plan = builder().notebooks_dir("notebooks").build().unwrap();
+        let actions = plan.plan(false).unwrap();
+        let mut mentions = Vec::new();
+        for action in &actions {
+            match action {
+                PlannedAction::CreateDir { path } | PlannedAction::WriteFile { path, .. }
+                    if path.to_string_lossy().contains(DEFAULT_NOTEBOOKS_DIR) =>
+                {
+                    mentions.push(path.display().to_string());
+                }
+                PlannedAction::WriteFile { path, contents, .. } => {
+                    // The README's section heading is the word, not the directory.
+                    let text = String::from_utf8_lossy(contents);
+                    let lines = text.lines().filter(|l| l.contains(DEFAULT_NOTEBOOKS_DIR) && *l != "## Notebooks");
+                    mentions.extend(lines.map(|l| format!("{}: {l}", path.display())));
+                }
+                _ => {}
+            }
+        }
+        assert!(mentions.is_empty(), "still {DEFAULT_NOTEBOOKS_DIR}: {mentions:?}");
+        assert!(actions.iter().any(|a| matches!(a, PlannedAction::CreateDir { path } if path == Path::new("notebooks"))));
+
+        for bad in ["", ".notebooks", "nb/books", "nb\\books", "my notebooks", "src", "tests", ".venv"] {
+            let err = builder().notebooks_dir(bad).build().err().unwrap_or_else(|| panic!("{bad:?} was accepted"));
+            assert!(err.to_string().starts_with(&format!("invalid --notebooks-dir {bad:?}")), "{err}");
+        }
+    }
+
+    #[test]
+    fn gen_secret_refuses_the_keys_the_scaffold_sets() {
+        for key in RESERVED_ENV_KEYS.iter().copied().chain(["debug"]) {
+            let plan = ScaffoldPlan::builder("demo").root("/nonexistent/demo").python("3.12").skip_uv(true).gen_secret(key).build();
+            let err = plan.err().unwrap_or_else(|| panic!("{key} was accepted"));
+            assert!(err.to_string().contains("the scaffold sets"), "{key}: {err}");
+        }
+    }
+
+    #[test]
+    fn every_built_in_variable_is_reserved() {
+        let plan = ScaffoldPlan::builder("demo").root("/nonexistent/demo").python("3.12").build().unwrap();
+        let unreserved: Vec<String> = plan.context().into_keys().filter(|k| !BUILTIN_VARS.contains(&k.as_str())).collect();
+        assert!(unreserved.is_empty(), "--var could shadow {unreserved:?}");
+    }
 }
@@ -0,0 +1,103 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::events::Event;
+use crate::util::{cache_dir, run_output, sha256_hex};
+
+/// A template pack hosted in git: `URL` or `URL#REF` (branch or tag).
+pub struct TemplateRepo {
+    pub url: String,
+    pub git_ref: Option<String>,
+}
+
+impl TemplateRepo {
+    pub fn parse(spec: &str) -> Result<TemplateRepo> {
+        let (url, git_ref) = match spec.rsplit_once('#') {
+            Some((url, r)) if !r.is_empty() => (url, Some(r.to_string())),
+            Some((url, _)) => (url, None),
+            None => (spec, None),
+        };
+        if url.trim().is_empty() {
+            bail!("empty --template-repo URL");
+        }
+        Ok(TemplateRepo {
+            url: url.to_string(),
+            git_ref,
+        })
+    }
+
+    /// `$XDG_CACHE_HOME/py-proj/templates/<hash of url#ref>`.
+    pub fn cache_path(&self) -> Result<PathBuf> {
+        let key = format!("{}#{}", self.url, self.git_ref.as_deref().unwrap_or(""));
+        let base = cache_dir().context("cannot determine a cache directory (set XDG_CACHE_HOME)")?;
+        Ok(base.join("templates").join(&sha256_hex(key)[..16]))
+    }
+
+    /// Return a local checkout, cloning it on first use or when `refresh` is set.
+    /// A populated cache keeps working offline: a failed refresh falls back to it.
+    pub fn fetch(&self, refresh: bool, on_event: &dyn Fn(&Event<'_>)) -> Result<PathBuf> {
+        let dest = self.cache_path()?;
+        if dest.is_dir() && !refresh {
+            on_event(&Event::TemplatesCached {
+                url: &self.url,
+                path: &dest,
+            });
+            return Ok(dest);
+        }
+
+        on_event(&Event::TemplatesCloning { url: &self.url });
+        match self.clone_into(&dest) {
+            Ok(()) => Ok(dest),
+            Err(e) if dest.is_dir() => {
+                on_event(&Event::Warning {
+                    message: format!("could not refresh templates, using the cached copy: {e:#}"),
+                });
+                Ok(dest)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The cached checkout, without touching the network (for `--offline`).
+    pub fn cached(&self) -> Result<PathBuf> {
+        let dest = self.cache_path()?;
+        if !dest.is_dir() {
+            bail!(
+                "template repo {} is not cached and --offline forbids fetching it; run once online first",
+                self.url
+            );
+        }
+        Ok(dest)
+    }
+
+    fn clone_into(&self, dest: &Path) -> Result<()> {
+        let parent = dest.parent().expect("cache path has a parent");
+        fs::create_dir_all(parent)?;
+
+        // Clone next to the cache entry and swap it in, so a failed fetch never
+        // destroys a working cache.
+        let tmp = dest.with_extension("tmp");
+        if tmp.exists() {
+            fs::remove_dir_all(&tmp)?;
+        }
+        let tmp_str = tmp.to_string_lossy();
+        let mut args = vec!["clone", "--depth", "1", "--quiet"];
+        if let Some(r) = &self.git_ref {
+            args.extend(["--branch", r]);
+        }
+        args.extend([self.url.as_str(), &tmp_str]);
+        run_output("git", &args, parent).with_context(|| {
+            format!(
+                "failed to fetch template repo {} (check the URL, ref, and your network access)",
+                self.url
+            )
+        })?;
+
+        if dest.exists() {
+            fs::remove_dir_all(dest)?;
+        }
+        fs::rename(&tmp, dest)?;
+        Ok(())
+    }
+}
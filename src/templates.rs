@@ -1,23 +1,127 @@
-// Centralized string templates. Keep them simple and parametric where needed.
+//! Centralized string templates. Keep them simple and parametric where needed.
+//! Templates are rendered with minijinja by [`render`]; [`REGISTRY`] lists every
+//! template with its output path, and [`BUILTIN_VARS`] the variables they can use.
+
+use anyhow::{anyhow, Result};
+use minijinja::syntax::SyntaxConfig;
+use minijinja::{AutoEscape, Environment, ErrorKind, UndefinedBehavior, Value};
+use std::collections::BTreeMap;
 
 pub fn main_py() -> &'static str {
     r#"
-from src.app_logging.glogger import setup_logging
-import logging
+{% if "app_logging" not in skipped %}from {{package}}.app_logging.glogger import setup_logging
+{% endif %}{% if async_main %}import asyncio
+{% endif %}import logging
+{% if "hypothesis" in testing_extras or benchmarks %}
+
+def clamp(value: int, low: int, high: int) -> int:
+    """{{tr.clamp_doc}}{% if "hypothesis" in testing_extras %} {{tr.clamp_doc_see}}{% endif %}."""
+    return max(low, min(value, high))
+{% endif %}
+{%- if async_main %}
+async def main():
+    log = logging.getLogger("{{package}}.app")
+    log.info("Hello from isolated logging")
 
+
+def run():
+    """{{tr.main_run_doc}}"""
+    {% if "app_logging" not in skipped %}setup_logging(){% else %}logging.basicConfig(level=logging.INFO){% endif %}
+    asyncio.run(main())
+
+if __name__ == "__main__":
+    run()
+{%- else %}
 def main():
-    setup_logging()
-    log = logging.getLogger("src.app")
+    {% if "app_logging" not in skipped %}setup_logging(){% else %}logging.basicConfig(level=logging.INFO){% endif %}
+    log = logging.getLogger("{{package}}.app")
     log.info("Hello from isolated logging")
 
 if __name__ == "__main__":
     main()
+{%- endif %}
+
+
+
+"#
+}
 
+/// `python -m <package>`: `src.main` in the flat layout, the `--entry-point` command's
+/// `cli.main` in a workspace member's package.
+pub fn dunder_main_py() -> &'static str {
+    r#""""Run {{project}} with `python -m {{package}}`."""
 
+{% if workspace_member -%}
+from {{import_name}}.cli import main
+{%- elif async_main -%}
+from {{package}}.main import run
+{%- else -%}
+from {{package}}.main import main
+{%- endif %}
 
+{% if async_main %}run(){% else %}main(){% endif %}
 "#
 }
 
+/// The `--entry-point` command: typer under the cli profile, argparse otherwise. The
+/// version comes from the installed distribution, i.e. `[project] version`.
+pub fn cli_py() -> &'static str {
+    r#""""Command-line interface for {{project}}."""
+{% if profile != "cli" %}
+import argparse
+{%- endif %}
+from importlib.metadata import PackageNotFoundError, version
+{% if profile == "cli" %}
+import typer
+
+app = typer.Typer(help="Command-line interface for {{project}}.")
+{% endif %}
+
+def package_version() -> str:
+    try:
+        return version("{{dist_name}}")
+    except PackageNotFoundError:
+        return "unknown"
+
+{% if profile == "cli" %}
+def show_version(value: bool) -> None:
+    if value:
+        typer.echo(f"{{entry_point}} {package_version()}")
+        raise typer.Exit()
+
+
+@app.callback(invoke_without_command=True)
+def run(
+    version: bool = typer.Option(
+        False, "--version", callback=show_version, is_eager=True, help="Show the version and exit."
+    ),
+) -> None:
+    typer.echo("Hello from {{entry_point}}")
+
+
+def main() -> None:
+    app()
+
+
+if __name__ == "__main__":
+    main()
+{% else %}
+def main(argv: list[str] | None = None) -> int:
+    parser = argparse.ArgumentParser(
+        prog="{{entry_point}}",
+        description="Command-line interface for {{project}}.",
+    )
+    parser.add_argument("--version", action="version", version=f"%(prog)s {package_version()}")
+    parser.parse_args(argv)
+    print("Hello from {{entry_point}}")
+    return 0
+
+
+if __name__ == "__main__":
+    raise SystemExit(main())
+{% endif %}"#
+}
+
 pub fn vscode_launch_json() -> &'static str {
     r#"{
   "version": "0.2.0",
@@ -28,38 +132,71 @@ pub fn vscode_launch_json() -> &'static str {
       "request": "launch",
       "program": "${file}",
       "cwd": "${workspaceFolder}",
+{%- if venv_dir != ".venv" %}
+      "python": "${workspaceFolder}/{{venv_dir}}/bin/python",
+{%- endif %}
       "env": {
-        "PYTHONPATH": "${workspaceFolder}:${workspaceFolder}/src:${workspaceFolder}/Notebooks"
+        "PYTHONPATH": "${workspaceFolder}:${workspaceFolder}/src:${workspaceFolder}/{{notebooks_dir}}"
       },
       "console": "integratedTerminal",
       "justMyCode": true,
       "subProcess": true
     },
     {
-      "name": "Python: Module src.main",
+      "name": "Python: Module {{run_module}}",
       "type": "debugpy",
       "request": "launch",
-      "module": "src.main",
+      "module": "{{run_module}}",
       "cwd": "${workspaceFolder}",
+{%- if venv_dir != ".venv" %}
+      "python": "${workspaceFolder}/{{venv_dir}}/bin/python",
+{%- endif %}
       "env": {
-        "PYTHONPATH": "${workspaceFolder}:${workspaceFolder}/src:${workspaceFolder}/Notebooks"
+        "PYTHONPATH": "${workspaceFolder}:${workspaceFolder}/src:${workspaceFolder}/{{notebooks_dir}}"
       },
       "console": "integratedTerminal",
       "justMyCode": true,
       "subProcess": true
-    }
+    }{% if entry_point %},
+    {
+      "name": "Python: CLI {{entry_point}}",
+      "type": "debugpy",
+      "request": "launch",
+      "module": "{{package}}.cli",
+      "args": ["--version"],
+      "cwd": "${workspaceFolder}",
+{%- if venv_dir != ".venv" %}
+      "python": "${workspaceFolder}/{{venv_dir}}/bin/python",
+{%- endif %}
+      "console": "integratedTerminal",
+      "justMyCode": true
+    }{% endif %}{% if "webapp" in components %},
+    {
+      "name": "Python: uvicorn {{package}}.app",
+      "type": "debugpy",
+      "request": "launch",
+      "module": "uvicorn",
+      "args": ["{{package}}.app:create_app", "--factory", "--reload"],
+      "cwd": "${workspaceFolder}",
+{%- if venv_dir != ".venv" %}
+      "python": "${workspaceFolder}/{{venv_dir}}/bin/python",
+{%- endif %}
+      "console": "integratedTerminal",
+      "justMyCode": true,
+      "subProcess": true
+    }{% endif %}
   ]
 }"#
 }
 
 pub fn vscode_settings_json() -> &'static str {
     r#"{
-  "python.defaultInterpreterPath": "${workspaceFolder}/.venv/bin/python",
+  "python.defaultInterpreterPath": "${workspaceFolder}/{{venv_dir}}/bin/python",
   "python.terminal.activateEnvironment": true,
   "python.analysis.extraPaths": [
     "${workspaceFolder}",
     "${workspaceFolder}/src",
-    "${workspaceFolder}/Notebooks"
+    "${workspaceFolder}/{{notebooks_dir}}"
   ],
   "python.envFile": "${workspaceFolder}/.env",
   "jupyter.envFile": "${workspaceFolder}/.env",
@@ -68,33 +205,107 @@ pub fn vscode_settings_json() -> &'static str {
     "editor.formatOnSave": true
   },
   "black-formatter.importStrategy": "fromEnvironment",
-  "black-formatter.path": ["${workspaceFolder}/.venv/bin/black"],
-  "black-formatter.args": ["--line-length", "100"],
+  "black-formatter.path": ["${workspaceFolder}/{{venv_dir}}/bin/black"],
+  "black-formatter.args": ["--line-length", "{{line_length}}"],
   "notebook.defaultFormatter": "ms-python.black-formatter"
 }"#
 }
 
+/// `.nvim.lua`: Neovim sources it from the working directory when `'exrc'` is on (and
+/// the file is trusted), so it is written for a Neovim started in the project root.
+pub fn nvim_lua() -> &'static str {
+    r#"-- {{tr.nvim_header}}
+local root = vim.fn.getcwd()
+local venv = root .. "/{{venv_dir}}"
+
+-- {{tr.nvim_venv}}
+vim.env.VIRTUAL_ENV = venv
+vim.env.PATH = venv .. "/bin:" .. vim.env.PATH
+
+-- {{tr.nvim_pyright}}
+local pyright = {
+  settings = {
+    python = {
+      pythonPath = venv .. "/bin/python",
+      analysis = { extraPaths = { root, root .. "/src"{% if "notebook" not in skipped %}, root .. "/{{notebooks_dir}}"{% endif %} } },
+    },
+  },
+}
+if vim.lsp.config then
+  vim.lsp.config("pyright", pyright)
+else
+  local ok, lspconfig = pcall(require, "lspconfig")
+  if ok then
+    lspconfig.pyright.setup(pyright)
+  end
+end
+"#
+}
+
 pub fn vscode_tasks_json() -> &'static str {
     r#"{
   "version": "2.0.0",
   "tasks": [
     {
-      "label": "Run (uv): src.main",
+      "label": "Run (uv): {{run_module}}",
+      "type": "shell",
+      "command": "uv run python -m {{run_module}}",
+      "options": { "cwd": "${workspaceFolder}", "env": { "PYTHONPATH": "${workspaceFolder}{% if namespace %}/src{% endif %}"{% if venv_dir != ".venv" %}, "UV_PROJECT_ENVIRONMENT": "{{venv_dir}}"{% endif %} } },
+      "problemMatcher": []
+    }
+{%- if security == "bandit" %},
+    {
+      "label": "Security scan (bandit)",
+      "type": "shell",
+      "command": "uv run bandit -c pyproject.toml -r src",
+      "options": { "cwd": "${workspaceFolder}"{% if venv_dir != ".venv" %}, "env": { "UV_PROJECT_ENVIRONMENT": "{{venv_dir}}" }{% endif %} },
+      "problemMatcher": []
+    }
+{%- endif %}
+{%- if benchmarks %},
+    {
+      "label": "Benchmarks (pytest-benchmark)",
       "type": "shell",
-      "command": "uv run python -m src.main",
-      "options": { "cwd": "${workspaceFolder}", "env": { "PYTHONPATH": "${workspaceFolder}" } },
+      "command": "uv run pytest benchmarks --benchmark-autosave",
+      "options": { "cwd": "${workspaceFolder}"{% if venv_dir != ".venv" %}, "env": { "UV_PROJECT_ENVIRONMENT": "{{venv_dir}}" }{% endif %} },
       "problemMatcher": []
     }
+{%- endif %}
   ]
 }"#
 }
 
+/// With the data layout, `DATA_ROOT` is where `src/paths.py` finds `data/` and `models/`.
+/// The generated secrets come last, under a comment `.env.example` keeps.
 pub fn dotenv() -> &'static str {
-    "PYTHONPATH=.:./src:./Notebooks\nENV=dev\n"
+    "PYTHONPATH=.:./src:./{{notebooks_dir}}\nENV=dev\n{% if data_layout %}DATA_ROOT=.\n{% endif %}{% if \"webapp\" in components %}DEBUG=true\n{% endif %}{% if secrets %}# Secrets, random per project; make one with: python -c \"import secrets; print(secrets.token_urlsafe(32))\"\n{% endif %}{% for s in secrets %}{{s.key}}={{s.value}}\n{% endfor %}"
+}
+
+/// Followed by the keys of the rendered `.env` with their values blanked (see
+/// `ScaffoldPlan`), so the two never drift apart.
+pub fn dotenv_example() -> &'static str {
+    "# Copy to .env and fill in the values. .env is git-ignored; this file is committed.\n"
 }
 
+/// The `--license-header` comment that starts every generated `.py` file; applied by
+/// [`add_license_header`], never pasted into a template.
+pub fn license_header() -> &'static str {
+    "# SPDX-License-Identifier: {{spdx}}\n# Copyright (c) {{year}} {{holder}}\n"
+}
+
+/// `use mise` first when `{{toolchain}}` is `mise`, then `PYTHONPATH` (with
+/// `{{notebooks_dir}}`), the venv when `{{venv_dir}}` is not `.venv`, and `.env`.
 pub fn envrc() -> &'static str {
-    r#"export PYTHONPATH="${PYTHONPATH}:$PWD:$PWD/src:$PWD/Notebooks"
+    r#"{% if toolchain == "mise" -%}
+# The pinned Python and the venv, from .mise.toml. Needs mise's direnv library:
+#   mise direnv activate > ~/.config/direnv/lib/use_mise.sh
+use mise
+{% endif -%}
+export PYTHONPATH="${PYTHONPATH}:$PWD:$PWD/src:$PWD/{{notebooks_dir}}"
+{% if venv_dir != ".venv" -%}
+# uv run and uv sync use {{venv_dir}} instead of .venv.
+export UV_PROJECT_ENVIRONMENT="$PWD/{{venv_dir}}"
+{% endif -%}
 if [ -f ./.env ]; then
   set -a
   . ./.env
@@ -103,17 +314,40 @@ fi
 "#
 }
 
-pub fn pyrefly_toml(project: &str, py_full: &str) -> String {
-    format!(
-        r#"[project]
-name = "{project}"
-python = "{py_full}"
+/// `--toolchain-file python-version`: the pin uv and pyenv read.
+pub fn python_version() -> &'static str {
+    "{{py_full}}\n"
+}
+
+/// `--toolchain-file asdf`: the pin asdf (and mise) read.
+pub fn tool_versions() -> &'static str {
+    "python {{py_full}}\n"
+}
+
+/// `--toolchain-file mise`: the pinned Python, and the venv mise activates with it.
+pub fn mise_toml() -> &'static str {
+    r#"[tools]
+python = {{ py_full|toml }}
+
+[env]
+_.python.venv = { path = {{ venv_dir|toml }} }
+"#
+}
+
+pub fn pyrefly_toml() -> &'static str {
+    r#"[project]
+name = "{{project}}"
+python = "{{py_full}}"
 
 [paths]
 src = "src"
-notebooks = "Notebooks"
-venv = ".venv"
+{%- if "notebook" not in skipped %}
+notebooks = "{{notebooks_dir}}"
+{%- endif %}
+venv = "{{venv_dir}}"
+{%- if "envs" not in skipped %}
 env = ".env"
+{%- endif %}
 
 [imports]
 import_roots = ["src"]
@@ -126,119 +360,653 @@ format = ["black"]
 runner = "pytest"
 coverage = true
 "#
-    )
 }
 
-pub fn pyrightconfig_json(mm: &str) -> String {
-    format!(
-        r#"{{
-  "pythonVersion": "{mm}",
+pub fn pyrightconfig_json() -> &'static str {
+    r#"{
+  "pythonVersion": "{{mm}}",
   "pythonPlatform": "Darwin",
   "typeCheckingMode": "basic",
   "reportMissingImports": "warning",
   "useLibraryCodeForTypes": true,
   "include": [".", "src/"],
-  "exclude": ["**/__pycache__", ".venv"],
+  "exclude": ["**/__pycache__", "{{venv_dir}}"],
   "venvPath": ".",
-  "venv": ".venv",
+  "venv": "{{venv_dir}}",
   "executionEnvironments": [
-    {{
+    {
       "root": ".",
       "extraPaths": [
         "./src",
-        "./Notebooks/",
-        ".venv/lib/python{mm}/site-packages"
+{%- if "notebook" not in skipped %}
+        "./{{notebooks_dir}}/",
+{%- endif %}
+        "{{venv_dir}}/lib/python{{mm}}/site-packages"
       ]
-    }}
+    }
   ]
-}}"#
-    )
+}"#
 }
 
-pub fn pyproject_toml(project: &str, mm: &str, mm_nodec: &str) -> String {
-    format!(
-        r#"[project]
-name = "{project}"
+/// `{{status}}` is the whole `Development Status` classifier; `{{report_file}}` keeps
+/// the `--summary-file` out of the sdist. The dev tools go in a `dev` extra or group,
+/// per `{{dep_style}}`, never both.
+pub fn pyproject_toml() -> &'static str {
+    r#"[project]
+name = "{{dist_name}}"
 version = "0.1.0"
-description = "Minimal project template"
+description = {{ (description or "Minimal project template")|toml }}
 readme = "README.md"
-requires-python = ">={mm}"
-authors = [{{ name = "Your Name" }}]
-dependencies = []
-
+requires-python = ">={{ python_matrix[0] if python_matrix else mm }}"
+{%- if license %}
+license = {{ license|toml }}
+{%- endif %}
+classifiers = [
+{%- if status %}
+  {{ status|toml }},
+{%- endif %}
+  "Programming Language :: Python :: 3",
+  "Programming Language :: Python :: 3 :: Only",
+{%- for version in python_matrix or [mm] if "." in version %}
+  "Programming Language :: Python :: {{version}}",
+{%- endfor %}
+]
+authors = [{ name = {{ (author or "Your Name")|toml }}{% if email %}, email = {{ email|toml }}{% endif %} }]
+{%- if maintainers %}
+maintainers = [
+{%- for m in maintainers %}
+  { name = {{ m.name|toml }}{% if m.email %}, email = {{ m.email|toml }}{% endif %} },
+{%- endfor %}
+]
+{%- endif %}
+dependencies = [{% if entry_point and profile == "cli" %}"typer>=0.12"
+{%- elif "webapp" in components %}
+  "fastapi>=0.115.0",
+  "uvicorn[standard]>=0.30.0",
+  "pydantic-settings>=2.4.0"
+{% endif %}]
+{%- if homepage or repo_url %}
+
+[project.urls]
+{%- if homepage %}
+Homepage = {{ homepage|toml }}
+{%- endif %}
+{%- if repo_url %}
+Repository = {{ repo_url|toml }}
+{%- if publish %}
+Issues = {{ (repo_url ~ "/issues")|toml }}
+Changelog = {{ (repo_url ~ "/blob/main/CHANGELOG.md")|toml }}
+{%- endif %}
+{%- endif %}
+{%- endif %}
+{%- if entry_point %}
+
+[project.scripts]
+{{entry_point}} = "{{package}}.cli:main"
+{%- endif %}
+
+{% if workspace_member or entry_point or publish or namespace -%}
+[build-system]
+requires = ["hatchling"]
+build-backend = "hatchling.build"
+{%- if not workspace_member %}
+
+[tool.hatch.build.targets.wheel]
+packages = ["src{% if namespace %}/{{namespace}}{% endif %}"]
+{%- endif %}
+{%- if report_file %}
+
+[tool.hatch.build.targets.sdist]
+exclude = [{{ ("/" ~ report_file)|toml }}]
+{%- endif %}
+{%- else -%}
 [tool.uv]
+{%- endif %}
 
+{% if dep_style == "groups" -%}
+[dependency-groups]
+{%- else -%}
 [project.optional-dependencies]
+{%- endif %}
 dev = [
+{%- if not tools_global %}
   "ruff>=0.6.0",
+{%- endif %}
   "black>=24.0.0",
+{%- if not tools_global %}
   "pyright>=1.1.380",
+{%- endif %}
   "pytest>=8.0.0",
   "pytest-cov>=5.0.0",
   "ipykernel>=6.0.0",
   "rich>=13.0.0"
+{%- if "hypothesis" in testing_extras %},
+  "hypothesis>=6.100.0"
+{%- endif %}
+{%- if async_main %},
+  "pytest-asyncio>=0.24.0"
+{%- endif %}
+{%- if benchmarks %},
+  "pytest-benchmark>=4.0.0"
+{%- endif %}
+{%- if "webapp" in components %},
+  "httpx>=0.27.0"
+{%- endif %}
+{%- if jupytext %},
+  "jupytext>=1.16.0"
+{%- endif %}
+{%- if security == "bandit" %},
+  "bandit[toml]>=1.7.9"
+{%- endif %}
+{%- if python_matrix and matrix_runner == "nox" %},
+  "nox>=2024.4.15"
+{%- elif python_matrix %},
+  "tox>=4.0.0",
+  "tox-uv>=1.0.0"
+{%- endif %}
 ]
 
 [tool.ruff]
-line-length = 100
-target-version = "py{mm_nodec}"
-extend-exclude = [".venv"]
+line-length = {{line_length}}
+target-version = "py{{ python_matrix[0]|replace(".", "") if python_matrix else mm_nodec }}"
+extend-exclude = ["{{venv_dir}}"]
 fix = true
+
+{% if security == "ruff-s" -%}
+[tool.ruff.lint]
+# flake8-bandit security rules on top of ruff's defaults.
+extend-select = ["S"]
+
+[tool.ruff.lint.per-file-ignores]
+# pytest checks with plain asserts.
+"tests/*" = ["S101"]
+{%- if benchmarks %}
+"benchmarks/*" = ["S101"]
+{%- endif %}
+
+{% elif security == "bandit" -%}
+[tool.bandit]
+# `bandit -c pyproject.toml -r src` (`make security`); tests may use assert freely.
+exclude_dirs = ["tests", "{{venv_dir}}"]
+
+[tool.bandit.assert_used]
+skips = ["*/tests/*", "tests/*"]
+
+{% endif -%}
+{% if jupytext -%}
+[tool.jupytext]
+# Pair each notebook in {{notebooks_dir}}/ with a percent-format .py file beside it.
+formats = "ipynb,py:percent"
+
+{% endif -%}
+[tool.pytest.ini_options]
+testpaths = ["tests"]
+{%- if benchmarks %}
+# benchmarks/ sits outside testpaths, so only `pytest benchmarks` (make bench) runs it.
+python_files = ["test_*.py", "benchmark_*.py"]
+{%- endif %}
+pythonpath = ["{% if workspace_member or namespace %}src{% else %}.{% endif %}"]
+{%- if async_main %}
+# pytest-asyncio runs every `async def` test on an event loop, marked or not.
+asyncio_mode = "auto"
+asyncio_default_fixture_loop_scope = "function"
+{%- endif %}
+{%- if pytest_addopts %}
+addopts = {{ pytest_addopts|toml }}
+{%- endif %}
+{%- if pytest_markers %}
+markers = [
+{%- for marker in pytest_markers %}
+  {{ marker|toml }},
+{%- endfor %}
+]
+{%- endif %}
 "#
-    )
 }
 
+/// Mirrors [`ScaffoldPlan`](crate::scaffold::ScaffoldPlan)'s line-ending rules, so a
+/// checkout gets the same terminators the scaffold wrote.
+pub fn gitattributes() -> &'static str {
+    r#"# Text files are stored with LF and checked out {% if line_endings == "native" %}with the platform's line endings{% else %}with {{ line_endings|upper }}{% endif %}.
+* text=auto{% if line_endings != "native" %} eol={{line_endings}}{% endif %}
+
+# make and the shell need LF on every platform; cmd.exe needs CRLF.
+Makefile text eol=lf
+.envrc text eol=lf
+*.sh text eol=lf
+{% if git_hooks -%}
+.githooks/* text eol=lf
+{% endif -%}
+*.bat text eol=crlf
+*.cmd text eol=crlf
+
+# Jupyter writes notebooks with LF.
+*.ipynb text eol=lf
+"#
+}
+
+/// Only sections for files this plan writes; `indent_style = tab` in the Makefile is
+/// required by make, not a preference, and so is its LF.
+pub fn editorconfig() -> &'static str {
+    r#"# https://editorconfig.org
+root = true
+
+[*]
+{%- if line_endings != "native" %}
+end_of_line = {{line_endings}}
+{%- endif %}
+charset = utf-8
+trim_trailing_whitespace = true
+insert_final_newline = true
+
+[*.py]
+indent_style = space
+indent_size = 4
+max_line_length = {{line_length}}
+
+[*.{json,yaml,yml,toml}]
+indent_style = space
+indent_size = 2
+{% if "makefile" in components %}
+[Makefile]
+indent_style = tab
+{%- if line_endings != "lf" %}
+end_of_line = lf
+{%- endif %}
+{% endif %}
+[*.md]
+trim_trailing_whitespace = false
+"#
+}
+
+/// Citation File Format 1.2.0. "Family, Given" and "Given Family" names become a person;
+/// a single word is written as an entity name.
+pub fn citation_cff() -> &'static str {
+    r#"cff-version: 1.2.0
+message: "If you use this software, please cite it as below."
+title: {{ project|yaml }}
+{%- if description %}
+abstract: {{ description|yaml }}
+{%- endif %}
+version: "0.1.0"
+date-released: "{{date}}"
+{%- if repo_url %}
+repository-code: {{ repo_url|yaml }}
+{%- endif %}
+{%- if homepage %}
+url: {{ homepage|yaml }}
+{%- endif %}
+{%- if license %}
+license: {{ license|yaml }}
+{%- endif %}
+authors:
+{%- set name = (author or "Your Name")|trim %}
+{%- if "," in name %}
+{%- set parts = name|split(",", 1) %}
+  - family-names: {{ parts[0]|trim|yaml }}
+    given-names: {{ parts[1]|trim|yaml }}
+{%- elif " " in name %}
+{%- set parts = name|split %}
+  - given-names: {{ parts[:-1]|join(" ")|yaml }}
+    family-names: {{ parts[-1]|yaml }}
+{%- else %}
+  - name: {{ name|yaml }}
+{%- endif %}
+{%- if email %}
+    email: {{ email|yaml }}
+{%- endif %}
+{%- if maintainers %}
+contact:
+{%- for m in maintainers %}
+  - name: {{ m.name|yaml }}
+{%- if m.email %}
+    email: {{ m.email|yaml }}
+{%- endif %}
+{%- endfor %}
+{%- endif %}
+"#
+}
+
+/// Marked sections follow the base entries; [`dedup_patterns`] drops any pattern seen
+/// earlier in the file. uv.lock is deliberately not ignored.
+/// `{{gitignore_extra}}` is the user's patterns, from `gitignore_extra` in the config.
 pub fn gitignore() -> &'static str {
-    r#".venv/
-__pycache__/
-*.pyc
+    r#"# Environments (.env holds local settings and secrets; .env.example is committed)
+{{venv_dir}}/
 .env
+!.env.example
+
+# Python
+__pycache__/
+*.py[cod]
+*.egg-info/
+dist/
+build/
+
+# Tools
 .ipynb_checkpoints/
 .coverage
-.htmlcov/
+.coverage.*
+htmlcov/
 .mypy_cache/
 .pytest_cache/
-dist/
-build/
+.ruff_cache/
+.pyright/
+.nox/
+.tox/
+{%- if benchmarks %}
+.benchmarks/
+{%- endif %}
+.pyproj.lock
+
+# Logs (src/app_logging writes a rotating project_log_file.log)
+*.log
+*.log.*
+
+# OS and editors
+.DS_Store
+{%- if profile == "datasci" %}
+
+# py-proj: datasci profile
+{%- if not data_layout %}
+data/
+{%- endif %}
+*.parquet
+.dvc/
+{%- elif profile == "webapp" %}
+
+# py-proj: webapp profile
+.env.local
+staticfiles/
+{%- endif %}
+{%- if data_layout %}
+
+# py-proj: data layout (the contents stay out of git; the .gitkeep placeholders are kept)
+/data/**
+!/data/**/
+!/data/**/.gitkeep
+/models/**
+!/models/**/
+!/models/**/.gitkeep
+*.parquet
+{%- endif %}
+{%- if jupytext_ignore_ipynb %}
+
+# py-proj: jupytext (only the paired .py files are committed)
+{{notebooks_dir}}/*.ipynb
+{%- endif %}
+{%- if gitignore_extra %}
+
+# py-proj: user entries
+{%- for pattern in gitignore_extra %}
+{{ pattern }}
+{%- endfor %}
+{%- endif %}
 "#
 }
 
-pub fn readme_md(project: &str) -> String {
-    format!(
-        r#"# {project}
+pub fn readme_md() -> &'static str {
+    r#"# {{project}}
 
-Generated by PY-PROJ scaffolder.
+![Python {{mm}}](https://img.shields.io/badge/python-{{mm}}-blue.svg)
+[![Ruff](https://img.shields.io/endpoint?url=https://raw.githubusercontent.com/astral-sh/ruff/main/assets/badge/v2.json)](https://github.com/astral-sh/ruff)
+{%- if license %}
+![License: {{ license|md }}](https://img.shields.io/badge/license-{{ license|badge }}-green.svg)
+{%- endif %}
+{%- if org and repo_url %}
+[![{{ org|md }}](https://img.shields.io/badge/{{ org|badge }}-repository-black.svg)]({{ repo_url|md_url }})
+{%- endif %}
 
-## Setup
+{% if description %}{{description}}{% else %}{{tr.readme_generated}}{% endif %}
+
+## {{tr.readme_quickstart}}
 
 ```bash
-cd {project}
-direnv allow     # or: source .venv/bin/activate
-uv pip install -e ".[dev]"
-Running
-uv run python -m src.main
+cd {{project}}
+{% if workspace_member -%}
+uv sync --package {{dist_name}} {% if dep_style == "groups" %}--group{% else %}--extra{% endif %} dev
+{% if entry_point -%}
+uv run --package {{dist_name}} {{entry_point}} --version
+uv run --package {{dist_name}} python -m {{import_name}} --version   # {{tr.readme_same_via_main}}
+{%- else -%}
+uv run --package {{dist_name}} python -c "import {{import_name}}"
+{%- endif %}
+{%- else -%}
+{% if "envs" not in skipped -%}
+cp -n .env.example .env   # {{tr.readme_local_settings}}
+direnv allow     # {{ tr.readme_or_source|replace("{venv}", venv_dir)|replace("{prompt}", venv_prompt) }}
+{% else -%}
+source {{venv_dir}}/bin/activate   # {{ tr.readme_prompt|replace("{prompt}", venv_prompt) }}
+{% if venv_dir != ".venv" -%}
+export UV_PROJECT_ENVIRONMENT={{venv_dir}}   # {{tr.readme_uv_env}}
+{% endif -%}
+{% endif -%}
+{% if dep_style == "groups" %}uv sync --group dev{% else %}uv pip install -e ".[dev]"{% endif %}
+{% if entry_point -%}
+uv run {{entry_point}} --version
+{%- else -%}
+uv run python -m {{run_module}}
+{%- endif %}
+{%- endif %}
+```
 
-# Format code
-uvx black .
+## {{tr.readme_structure}}
 
-# Lint code
-uvx ruff check --fix
+```
+{{project}}/
+{% if workspace_member -%}
+├── src/
+│   └── {{import_name}}/       # {{tr.readme_tree_package}}{% if entry_point %} {{ tr.readme_tree_package_cli|replace("{name}", entry_point)|replace("{module}", import_name) }}{% endif %}
+{% for module in modules -%}
+│       {% if loop.last %}└{% else %}├{% endif %}── {{ module|replace(".", "/") }}.py
+{% endfor -%}
+├── tests/
+{% if benchmarks -%}
+├── benchmarks/            # {{tr.readme_tree_benchmarks}}
+{% endif -%}
+├── pyproject.toml
+└── README.md
+{%- else -%}
+├── {{package_dir}}/
+│   ├── main.py            # {{tr.readme_tree_entry}}{% if async_main %} {{tr.readme_tree_async}}{% endif %}
+│   ├── __main__.py        # python -m {{package}}
+{% if entry_point -%}
+│   ├── cli.py             # {{ tr.readme_tree_command|replace("{name}", entry_point) }}
+{% endif -%}
+{% if "webapp" in components -%}
+│   ├── app.py             # {{tr.readme_tree_app}}
+│   ├── settings.py        # {{tr.readme_tree_settings}}
+{% endif -%}
+{% for module in modules -%}
+│   ├── {{ module|replace(".", "/") }}.py
+{% endfor -%}
+{% if "app_logging" not in skipped -%}
+│   └── app_logging/       # {{tr.readme_tree_logging}}
+{% endif -%}
+├── tests/
+{% if benchmarks -%}
+├── benchmarks/            # {{tr.readme_tree_benchmarks}} (make bench)
+{% endif -%}
+{% if "notebook" not in skipped -%}
+├── {{notebooks_dir}}/{% if jupytext %}{{ " " * ([1, 21 - notebooks_dir|length]|max) }}# {{tr.readme_tree_jupytext}}{% endif %}
+{% endif -%}
+{% if data_layout -%}
+├── data/                  # {{tr.readme_tree_data}}
+├── models/                # {{tr.readme_tree_models}}
+{% endif -%}
+{% if dvc -%}
+├── dvc.yaml               # {{tr.readme_tree_dvc}}
+{% endif -%}
+{% if ide == "vscode" and "vscode" not in skipped -%}
+├── .vscode/               # {{tr.readme_tree_vscode}}
+{% elif ide == "neovim" -%}
+├── .nvim.lua              # {{tr.readme_tree_nvim}}
+{% endif -%}
+{% if git_hooks -%}
+├── .githooks/             # {{tr.readme_tree_githooks}}
+{% endif -%}
+{% if "envs" not in skipped -%}
+├── .env.example           # {{tr.readme_tree_env_example}}
+{% endif -%}
+├── pyproject.toml
+{% if "pyrefly" not in skipped -%}
+├── pyrefly.toml
+{% endif -%}
+├── pyrightconfig.json
+{% if "makefile" not in skipped -%}
+├── Makefile
+{% endif -%}
+{% if python_matrix -%}
+├── {% if matrix_runner == "nox" %}noxfile.py             # {{ tr.readme_tree_nox|replace("{versions}", python_matrix|join(", ")) }}{% else %}tox.ini                # {{ tr.readme_tree_tox|replace("{versions}", python_matrix|join(", ")) }}{% endif %}
+{% endif -%}
+└── README.md
+{%- endif %}
+```
+
+## {{tr.readme_development}}
 
-# Run tests
-uv run pytest
+{% if workspace_member -%}
+{{tr.readme_dev_workspace}}
 
-# Type checking
-uvx pyright
+```bash
+{% if tools_global -%}
+ruff check --fix   # {{tr.readme_lint}}
+{% else -%}
+uv run --package {{dist_name}} ruff check --fix   # {{tr.readme_lint}}
+{% endif -%}
+uv run --package {{dist_name}} black .            # {{tr.readme_format}}
+{% if tools_global -%}
+pyright            # {{tr.readme_type_check}}
+{% else -%}
+uv run --package {{dist_name}} pyright            # {{tr.readme_type_check}}
+{% endif -%}
+uv run --package {{dist_name}} py{{tr.readme_test}}             # {{tr.readme_test}}
+{%- if benchmarks %}
+uv run --package {{dist_name}} pytest benchmarks  # {{tr.readme_benchmark}}
+{%- endif %}
 ```
-Structure
-src/ - Main source code
-tests/ - Test files
-Notebooks/ - Jupyter notebooks
-.vscode/ - VS Code configuration
-pyproject.toml - Project configuration
-pyrefly.toml - Custom project metadata
+{%- if "hypothesis" in testing_extras %}
+
+{{tr.readme_hypothesis}}
+{%- endif %}
+{%- elif "makefile" in skipped -%}
+```bash
+uv run {% if entry_point %}{{entry_point}}{% else %}python -m {{run_module}}{% endif %}   # {{tr.readme_run}}
+uv run ruff check src   # {{tr.readme_lint}}
+uv run black src        # {{tr.readme_format}}
+uv run pyright src      # {{tr.readme_type_check}}
+uv run py{{tr.readme_test}}           # {{tr.readme_test}}
+{%- if benchmarks %}
+uv run pytest benchmarks # {{tr.readme_benchmark}}
+{%- endif %}
+```
+{%- else -%}
+```bash
+make install      # {{tr.readme_make_install}}
+make run          # {% if entry_point %}{{ tr.readme_make_run_command|replace("{name}", entry_point) }}{% else %}{{ tr.readme_make_run|replace("src.main", run_module) }}{% endif %}
+make lint         # {{tr.readme_make_lint}}
+make fmt          # {{tr.readme_make_fmt}}
+make typecheck    # pyright
+{%- if security == "bandit" %}
+make security     # {{tr.readme_make_security}}
+{%- endif %}
+make test         # {{tr.readme_make_test}}
+{%- if benchmarks %}
+make bench        # {{tr.readme_make_bench}}
+{%- endif %}
+make clean        # {{tr.readme_make_clean}}
+```
+{%- if tools_global %}
+
+{{tr.readme_tools_global}}
+{%- endif %}
+{%- if security == "ruff-s" %}
+
+{{tr.readme_ruff_s}}
+{%- endif %}
+{%- if "hypothesis" in testing_extras %}
+
+{{tr.readme_hypothesis}}
+{%- endif %}
+{%- if python_matrix %}
+
+{% if matrix_runner == "nox" %}{{ tr.readme_matrix_nox|replace("{versions}", python_matrix|join(", ")) }}{% else %}{{ tr.readme_matrix_tox|replace("{versions}", python_matrix|join(", ")) }}{% endif %}
+{%- endif %}
+{%- if git_hooks %}
+
+{{tr.readme_git_hooks}}
+{%- endif %}
+{%- if ide == "neovim" and not workspace_member %}
+
+### Neovim
+
+{{ tr.readme_neovim|replace("{venv}", venv_dir) }}
+
+```lua
+vim.o.exrc = true
+vim.lsp.enable({ "pyright", "ruff" })
+```
+
+{{tr.readme_neovim_lspconfig}}
+{%- endif %}
+{%- if data_layout %}
+
+## {{tr.readme_data_heading}}
+
+{{tr.readme_data}}
+{%- if dvc %}
+{{tr.readme_dvc}}
+{%- endif %}
+{%- endif %}
+{%- if "webapp" in components %}
+
+## {{tr.readme_webapp_heading}}
+
+{{tr.readme_webapp}}
+{%- endif %}
+{%- if "notebook" not in skipped %}
+
+## {{tr.readme_notebooks_heading}}
+
+{{ tr.readme_notebooks|replace("{venv}", venv_dir)|replace("{notebooks}", notebooks_dir) }}
+{%- if jupytext %}
+
+{{ tr.readme_jupytext|replace("{notebooks}", notebooks_dir) }}
+{%- if jupytext_ignore_ipynb %} {{tr.readme_jupytext_ignore}}{% endif %}
+{%- endif %}
+{%- endif %}
+{%- endif %}
+{%- if publish %}
+
+## {{tr.readme_releasing_heading}}
+
+{{tr.readme_releasing}}
+{%- endif %}
+{%- if maintainers %}
+
+## {{tr.readme_maintainers_heading}}
+{% for m in maintainers %}
+- {{ m.name|md }}{% if m.email %} <{{ m.email }}>{% endif %}
+{%- endfor %}
+{%- endif %}
+"#
+}
+
+pub fn changelog_md() -> &'static str {
+    r#"# Changelog
+
+All notable changes to this project are documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+`py-proj bump patch|minor|major` moves the Unreleased entries under a new release.
+
+## [Unreleased]
+
+### Added
+
+- Initial project scaffold.
 "#
-    )
 }
 
 // ------------------ app_logging templates ------------------
@@ -247,20 +1015,19 @@ pub fn app_logging_my_colored_formatter_py() -> &'static str {
 
 import logging
 import datetime as dt
-from src.app_logging.constants import *
+from {{package}}.app_logging.constants import *
 
 
 class MyColoredFormatter(logging.Formatter):
     """
-    Custom log formatter to add colors to log messages based on the log level
-    and other attributes such as timestamp, filename, function name, and line number.
+    {{tr.log_formatter_doc}}
     """
 
     def format(self, record):
-        # Generate timestamp with formatTime method including timezone
+        # {{tr.log_timestamp}}
         asctime = f"{TIME_COLOR}{self.formatTime(record, self.datefmt)}{RESET}"
 
-        # Add color to different fields
+        # {{tr.log_add_color}}
         log_color = COLORS.get(record.levelname, RESET)
         filename = f"{FILENAME_COLOR}{record.filename}{RESET}"
         funcName = f"{FUNCNAME_COLOR}{record.funcName}{RESET}"
@@ -268,7 +1035,7 @@ class MyColoredFormatter(logging.Formatter):
         levelname = f"{log_color}{record.levelname}{RESET}"
         message = f"{log_color}{record.getMessage()}{RESET}"
 
-        # Construct formatted log line
+        # {{tr.log_construct}}
         log_line = f"{asctime}: {filename}: {funcName}: L{lineno}: {levelname}: {message}"
         return log_line
 "#
@@ -286,12 +1053,12 @@ pub fn app_logging_config07_json() -> &'static str {
       "datefmt": "DATE: %Y-%m-%d TIME:%H:%M:%S [%z]"
     },
     "colored": {
-      "()": "src.app_logging.MyColoredFormatter.MyColoredFormatter",
+      "()": "{{package}}.app_logging.MyColoredFormatter.MyColoredFormatter",
       "format": "%(asctime)s: %(filename)s: %(funcName)s: L%(lineno)d: %(levelname)s: %(message)s",
       "datefmt": "DATE: %Y-%m-%d TIME:%H:%M:%S [%z]"
     },
     "json": {
-      "()": "src.app_logging.myCustomJsonClass01.MyJSONFormatter",
+      "()": "{{package}}.app_logging.myCustomJsonClass01.MyJSONFormatter",
       "fmt_keys": {
         "level": "levelname",
         "message": "message",
@@ -306,9 +1073,9 @@ pub fn app_logging_config07_json() -> &'static str {
   },
 
   "filters": {
-    "stdout_filter": { "()": "src.app_logging.myFilters.StdoutFilter" },
-    "stderr_filter": { "()": "src.app_logging.myFilters.StderrFilter" },
-    "only_src":      { "()": "logging.Filter", "name": "src" }
+    "stdout_filter": { "()": "{{package}}.app_logging.myFilters.StdoutFilter" },
+    "stderr_filter": { "()": "{{package}}.app_logging.myFilters.StderrFilter" },
+    "only_src":      { "()": "logging.Filter", "name": "{{package}}" }
   },
 
   "handlers": {
@@ -329,7 +1096,7 @@ pub fn app_logging_config07_json() -> &'static str {
       "class": "logging.handlers.RotatingFileHandler",
       "level": "DEBUG",
       "formatter": "json",
-      "filename": "src/app_logging/project_log_file.log",
+      "filename": "{{package_dir}}/app_logging/project_log_file.log",
       "maxBytes": 5242880,
       "backupCount": 3,
       "filters": ["only_src"]
@@ -342,12 +1109,12 @@ pub fn app_logging_config07_json() -> &'static str {
   },
 
   "loggers": {
-    "src": {
+    "{{package}}": {
       "level": "INFO",
       "handlers": ["queue_handler"],
       "propagate": false
     },
-    "src.sink": {
+    "{{package}}.sink": {
       "level": "DEBUG",
       "handlers": ["stdout", "stderr", "file_json"],
       "propagate": false
@@ -360,21 +1127,21 @@ pub fn app_logging_config07_json() -> &'static str {
 }
 pub fn app_logging_constants_py() -> &'static str {
     r#"
-# Define ANSI escape codes for colors
+# {{tr.log_ansi}}
 RESET = "\033[0m"
 COLORS = {
-    "DEBUG": "\033[36m",  # Cyan for DEBUG level
-    "INFO": "\033[95m",  # light magenta
-    "WARNING": "\033[33m",  # Yellow for WARNING level
-    "ERROR": "\033[31m",  # Red for ERROR level
-    "CRITICAL": "\033[1;31m",  # Bold Red for CRITICAL level
+    "DEBUG": "\033[36m",  # {{tr.log_debug_color}}
+    "INFO": "\033[95m",  # {{tr.log_info_color}}
+    "WARNING": "\033[33m",  # {{tr.log_warning_color}}
+    "ERROR": "\033[31m",  # {{tr.log_error_color}}
+    "CRITICAL": "\033[1;31m",  # {{tr.log_critical_color}}
 }
 
-# Additional colors for log fields
-FILENAME_COLOR = "\033[35m"  # Magenta for filename
-FUNCNAME_COLOR = "\033[34m"  # Blue for function name
-LINENO_COLOR = "\033[36m"  # Cyan for line number
-TIME_COLOR = "\033[90m"  # Light gray for timestamp
+# {{tr.log_field_colors}}
+FILENAME_COLOR = "\033[35m"  # {{tr.log_filename_color}}
+FUNCNAME_COLOR = "\033[34m"  # {{tr.log_funcname_color}}
+LINENO_COLOR = "\033[36m"  # {{tr.log_lineno_color}}
+TIME_COLOR = "\033[90m"  # {{tr.log_time_color}}
 
 if __name__ == "__main__":
     pass
@@ -393,8 +1160,8 @@ log_queue = Queue()
 HERE = Path(__file__).resolve().parent
 CONFIG_PATH = HERE / "config07.json"
 
-PROJECT_LOGGER = "src"      # your top-level package
-SINK_LOGGER = "src.sink"
+PROJECT_LOGGER = "{{package}}"      # {{tr.log_project_logger}}
+SINK_LOGGER = "{{package}}.sink"
 
 def setup_logging():
     with CONFIG_PATH.open() as f:
@@ -405,7 +1172,7 @@ def setup_logging():
 
     sink_handlers = logging.getLogger(SINK_LOGGER).handlers
     if not sink_handlers:
-        raise RuntimeError("No sink handlers found on src.sink")
+        raise RuntimeError("No sink handlers found on {{package}}.sink")
 
     listener = logging.handlers.QueueListener(log_queue, *sink_handlers)
     listener.start()
@@ -498,14 +1265,14 @@ pub fn app_logging_my_filters_py() -> &'static str {
 import logging
 
 class StdoutFilter(logging.Filter):
-    """Allow only DEBUG and INFO messages for stdout."""
+    """{{tr.log_stdout_filter}}"""
 
     def filter(self, record: logging.LogRecord) -> bool:
         return record.levelno <= logging.INFO
 
 
 class StderrFilter(logging.Filter):
-    """Allow only WARNING, ERROR, and CRITICAL messages for stderr."""
+    """{{tr.log_stderr_filter}}"""
 
     def filter(self, record: logging.LogRecord) -> bool:
         return record.levelno >= logging.WARNING
@@ -515,91 +1282,1079 @@ class StderrFilter(logging.Filter):
 
 pub fn app_make_file_creator() -> &'static str {
     r#"
-# === CONFIG ===
+# === {{tr.mk_config}} ===
 PYTHON_SRC=src
+{%- if namespace %}
+PYTHON_PKG={{package}}
+{%- endif %}
 
+# {{tr.mk_uv_run}}{% if tools_global %} {{tr.mk_tools_global}}{% endif %}
+{%- if venv_dir != ".venv" %}
+export UV_PROJECT_ENVIRONMENT := {{venv_dir}}
+{%- endif %}
+.DEFAULT_GOAL := help
 
 GREEN := \033[0;32m
 RED := \033[0;31m
 YELLOW := \033[0;33m
 BLUE := \033[0;34m
-NC := \033[0m # No Color
+# {{tr.mk_no_color}}
+NC := \033[0m
 ROCKET := 🚀
 CPU := 💻
 GPU := ⚡️
 BAR := 📊
-# === RUN APP ===
+# === {{tr.mk_run_app}} ===
+
+.PHONY: install
+install: ## {{tr.mk_install}}
+{%- if workspace_member %}
+	@uv sync --package {{dist_name}} {% if dep_style == "groups" %}--group{% else %}--extra{% endif %} dev
+{%- elif dep_style == "groups" %}
+	@uv sync --group dev
+{%- else %}
+	@uv pip install -e ".[dev]"
+{%- endif %}
 
 .PHONY: run
-run:
-	@uv run python -m $(PYTHON_SRC).main
+run: ## {% if entry_point %}{{ tr.mk_run_command|replace("{name}", entry_point) }}{% else %}{{tr.mk_run_main}}{% endif %}
+{%- if entry_point %}
+	@uv run {{entry_point}}
+{%- else %}
+	@uv run python -m {% if namespace %}$(PYTHON_PKG){% else %}$(PYTHON_SRC).main{% endif %}
+{%- endif %}
+{%- if "webapp" in components %}
 
-# === LINTING ===
+.PHONY: serve
+serve: ## {{tr.mk_serve}}
+	@uv run uvicorn $(PYTHON_{% if namespace %}PKG{% else %}SRC{% endif %}).app:create_app --factory --reload --host $(or $(HOST),127.0.0.1) --port $(or $(PORT),8000)
+{%- endif %}
+
+# === {{tr.mk_linting}} ===
 
 .PHONY: lint
-lint:
+lint: ## {{tr.mk_lint}}
 	@uv run ruff check $(PYTHON_SRC)
 
 .PHONY: lint-fix
-lint-fix:
+lint-fix: ## {{tr.mk_lint_fix}}
 	@uv run ruff check $(PYTHON_SRC) --fix
 
-# === FORMATTING ===
+# === {{tr.mk_formatting}} ===
 
 .PHONY: fmt
-fmt:
+fmt: ## {{tr.mk_fmt}}
 	@uv run black $(PYTHON_SRC)
 
 .PHONY: fmt-check
-fmt-check:
+fmt-check: ## {{tr.mk_fmt_check}}
 	@uv run black --check $(PYTHON_SRC)
 
-# === TYPE CHECKING ===
+# === {{tr.mk_type_checking}} ===
 
 .PHONY: typecheck
-typecheck:
+typecheck: ## {{tr.mk_typecheck}}
 	@uv run pyright $(PYTHON_SRC)
+{%- if security == "bandit" %}
+
+# === {{tr.mk_security}} ===
+
+.PHONY: security
+security: ## {{tr.mk_security_scan}}
+	@uv run bandit -c pyproject.toml -r $(PYTHON_SRC)
+{%- endif %}
 
-# === TESTING ===
+# === {{tr.mk_testing}} ===
 
 .PHONY: test
-test:
+test: ## {{tr.mk_test}}
 	@uv run pytest tests/
+{%- if benchmarks %}
+
+.PHONY: bench
+bench: ## {{tr.mk_bench}}
+	@uv run pytest benchmarks/ --benchmark-autosave
+{%- endif %}
 
 .PHONY: coverage
-coverage:
+coverage: ## {{tr.mk_coverage}}
 	@uv run pytest --cov=$(PYTHON_SRC) tests/
 
-# === CLEANING ===
+# === {{tr.mk_cleaning}} ===
 
 .PHONY: clean
-clean:
+clean: ## {{tr.mk_clean}}
 	@find . -type d -name '__pycache__' -exec rm -rf {} +
 	@find . -type d -name '.pytest_cache' -exec rm -rf {} +
 	@rm -rf .mypy_cache .ruff_cache .coverage dist build *.egg-info .pyright
 
-# === HELP ===
+# === {{tr.mk_help}} ===
+
+# {{tr.mk_help_comment}}
+.PHONY: help
+help: ## {{tr.mk_show_help}}
+	@echo "{{tr.mk_available}}"
+	@grep -E '^[a-zA-Z_-]+:.*## ' $(MAKEFILE_LIST) | awk 'BEGIN {FS = ":.*## "}; {printf "  $(BLUE)%-12s$(NC) %s\n", $$1, $$2}'
+"#
+}
 
+/// Test and lint sessions for every `--python-matrix` version. Dependencies come from
+/// pyproject.toml's dev extra (`uv pip install -r pyproject.toml`) or dev group (`uv pip
+/// install --group dev`), so the flat layout needs no build backend.
+pub fn noxfile_py() -> &'static str {
+    r#""""nox sessions for {{project}}: `uv run nox -l` lists them."""
 
+import nox
 
-.PHONY: help
-help:
-	@echo " "
-	@echo "$                                      "
-	@echo "$                                      "
-	@echo "$                                      "
-	@echo " "
-	@echo "Available make targets:"
-	@echo "  run          Run the main application"
-	@echo "  lint         Run Ruff linter"
-	@echo "  lint-fix     Run Ruff with auto-fix"
-	@echo "  fmt          Format code with Black"
-	@echo "  fmt-check    Check formatting with Black"
-	@echo "  typecheck    Static type check with Pyright"
-	@echo "  test         Run Pytest"
-	@echo "  coverage     Run tests with coverage report"
-	@echo "  clean        Remove build/test/cache artifacts"
-
-
-    "#
+nox.options.default_venv_backend = "uv"
+
+PYTHONS = [{% for version in python_matrix %}"{{version}}"{% if not loop.last %}, {% endif %}{% endfor %}]
+
+
+@nox.session(python=PYTHONS)
+def tests(session: nox.Session) -> None:
+{%- if dep_style == "groups" %}
+    session.install("--group", "dev")
+{%- else %}
+    session.install("-r", "pyproject.toml", "--extra", "dev")
+{%- endif %}
+    session.run("pytest", *session.posargs)
+
+
+@nox.session(python=PYTHONS)
+def lint(session: nox.Session) -> None:
+    session.install("ruff")
+    session.run("ruff", "check", "--no-fix", "src", "tests")
+{%- if security == "bandit" %}
+
+
+@nox.session(python=PYTHONS[0])
+def security(session: nox.Session) -> None:
+    session.install("bandit[toml]")
+    session.run("bandit", "-c", "pyproject.toml", "-r", "src")
+{%- endif %}
+{%- if benchmarks %}
+
+
+@nox.session(python=PYTHONS[0])
+def bench(session: nox.Session) -> None:
+    """Smoke-test the benchmarks: each runs once, untimed."""
+{%- if dep_style == "groups" %}
+    session.install("--group", "dev")
+{%- else %}
+    session.install("-r", "pyproject.toml", "--extra", "dev")
+{%- endif %}
+    session.run("pytest", "benchmarks", "--benchmark-disable", *session.posargs)
+{%- endif %}
+"#
+}
+
+pub fn tox_ini() -> &'static str {
+    r#"[tox]
+requires = tox-uv>=1
+env_list = {% for version in python_matrix %}py{{ version|replace(".", "") }}, {% endfor %}lint{% if security == "bandit" %}, security{% endif %}{% if benchmarks %}, bench{% endif %}
+
+[testenv]
+skip_install = true
+allowlist_externals = uv
+commands_pre = uv pip install --python {envpython} {% if dep_style == "groups" %}--group dev{% else %}-r pyproject.toml --extra dev{% endif %}
+commands = pytest {posargs}
+
+[testenv:lint]
+basepython = python{{ python_matrix[0] }}
+{%- if tools_global %}
+deps = ruff
+{%- endif %}
+commands = ruff check --no-fix src tests
+{%- if security == "bandit" %}
+
+[testenv:security]
+basepython = python{{ python_matrix[0] }}
+deps = bandit[toml]
+commands = bandit -c pyproject.toml -r src
+{%- endif %}
+{%- if benchmarks %}
+
+[testenv:bench]
+# Smoke-test the benchmarks: each runs once, untimed.
+basepython = python{{ python_matrix[0] }}
+commands = pytest benchmarks --benchmark-disable {posargs}
+{%- endif %}
+"#
+}
+
+/// The `--jupytext` starter notebook, percent format; `jupytext --sync` writes the
+/// paired `starter.ipynb`.
+pub fn starter_notebook_py() -> &'static str {
+    r#"# ---
+# jupyter:
+#   jupytext:
+#     formats: ipynb,py:percent
+#     text_representation:
+#       extension: .py
+#       format_name: percent
+#   kernelspec:
+#     display_name: Python 3
+#     language: python
+#     name: python3
+# ---
+
+# %% [markdown]
+# # {{project}}: starter notebook
+#
+# Paired with `starter.ipynb` by jupytext. Edit either side, then
+# `uv run jupytext --sync {{notebooks_dir}}/starter.py`.
+
+# %%
+from {{package}}.main import main
+{%- if data_layout %}
+from {{package}}.paths import PROCESSED_DATA_DIR, RAW_DATA_DIR
+
+print(f"raw data: {RAW_DATA_DIR}")
+print(f"processed data: {PROCESSED_DATA_DIR}")
+{%- endif %}
+{% if async_main %}
+# Jupyter runs cells inside an event loop, so the coroutine is awaited directly.
+await main()  # type: ignore  # noqa: F704
+{%- else %}
+main()
+{%- endif %}
+"#
+}
+
+/// `--data-layout`: the directories as absolute paths, so scripts and notebooks find
+/// them from any working directory.
+pub fn paths_py() -> &'static str {
+    r#""""Where {{project}} keeps its data and models.
+
+`DATA_ROOT` (set in .env) moves them: a path relative to the project root, or an absolute
+one. Unset or empty, they are in the project root.
+"""
+
+import os
+from pathlib import Path
+from typing import Final
+
+PROJECT_ROOT: Final[Path] = Path(__file__).resolve().parent.parent
+DATA_ROOT: Final[Path] = PROJECT_ROOT / os.environ.get("DATA_ROOT", "")
+
+DATA_DIR: Final[Path] = DATA_ROOT / "data"
+RAW_DATA_DIR: Final[Path] = DATA_DIR / "raw"
+INTERIM_DATA_DIR: Final[Path] = DATA_DIR / "interim"
+PROCESSED_DATA_DIR: Final[Path] = DATA_DIR / "processed"
+MODELS_DIR: Final[Path] = DATA_ROOT / "models"
+"#
+}
+
+/// `--dvc`: a pipeline with no stages yet, and an example of one in the comments.
+pub fn dvc_yaml() -> &'static str {
+    r#"# DVC pipeline for {{project}}: `dvc repro` runs the stages, `dvc dag` shows them.
+# Each stage names its command, what it reads (deps), and what it writes (outs), e.g.:
+#
+#   prepare:
+#     cmd: python -m {{package}}.prepare
+#     deps:
+#       - data/raw
+#     outs:
+#       - data/processed
+stages: {}
+"#
+}
+
+pub fn dvcignore() -> &'static str {
+    r#"# Paths DVC leaves out of its status and hashing (.gitignore syntax).
+{{venv_dir}}/
+__pycache__/
+.ipynb_checkpoints/
+"#
+}
+
+/// The webapp profile's application factory, served by `make serve` and the VS Code
+/// launch configuration as `uvicorn src.app:create_app --factory`.
+pub fn webapp_app_py() -> &'static str {
+    r#""""The {{project}} web application.
+
+`create_app()` builds it; uvicorn serves it (`make serve`):
+
+    uv run uvicorn {{package}}.app:create_app --factory --reload
+"""
+
+from fastapi import FastAPI
+
+from {{package}}.settings import Settings, get_settings
+
+
+def create_app(settings: Settings | None = None) -> FastAPI:
+    """A new application configured by `settings` (default: the environment and .env)."""
+    settings = settings or get_settings()
+    app = FastAPI(title=settings.app_name, debug=settings.debug)
+
+    @app.get("/health")
+    def health() -> dict[str, str]:
+        return {"status": "ok", "env": settings.env}
+
+    return app
+"#
+}
+
+/// The webapp profile's settings. Keys in `.env` that are not fields (PYTHONPATH) are
+/// ignored, and empty ones (a copied `.env.example`) fall back to the defaults. The
+/// generated secrets are `SecretStr`s, so they stay out of reprs and logs.
+pub fn webapp_settings_py() -> &'static str {
+    r#""""Settings for {{project}}, read from the environment and then from .env."""
+
+from functools import lru_cache
+from pathlib import Path
+
+{% if secrets %}from pydantic import SecretStr
+{% endif %}from pydantic_settings import BaseSettings, SettingsConfigDict
+
+PROJECT_ROOT = Path(__file__).resolve().parent.parent
+
+
+class Settings(BaseSettings):
+    """Each field is read from the variable of the same name in upper case (`ENV`)."""
+
+    model_config = SettingsConfigDict(
+        env_file=PROJECT_ROOT / ".env",
+        env_file_encoding="utf-8",
+        env_ignore_empty=True,
+        extra="ignore",
+    )
+
+    app_name: str = "{{project}}"
+    env: str = "dev"
+    debug: bool = False
+{%- for s in secrets %}
+    {{s.key|lower}}: SecretStr = SecretStr("")
+{%- endfor %}
+
+
+@lru_cache
+def get_settings() -> Settings:
+    """The settings, read once."""
+    return Settings()
+"#
+}
+
+/// Calls the app in-process through httpx's ASGI transport, on anyio's pytest plugin
+/// (anyio comes with FastAPI), so no server or pytest-asyncio is needed.
+pub fn webapp_test_app_py() -> &'static str {
+    r#"import httpx
+import pytest
+
+from {{package}}.app import create_app
+from {{package}}.settings import Settings
+
+
+@pytest.fixture
+def anyio_backend() -> str:
+    return "asyncio"
+
+
+@pytest.mark.anyio
+async def test_health():
+    app = create_app(Settings(env="test"))
+    transport = httpx.ASGITransport(app=app)
+    async with httpx.AsyncClient(transport=transport, base_url="http://test") as client:
+        response = await client.get("/health")
+    assert response.status_code == 200
+    assert response.json() == {"status": "ok", "env": "test"}
+"#
+}
+
+/// Weekly dependency PRs from Dependabot.
+pub fn dependabot_yml() -> &'static str {
+    r#"version: 2
+updates:
+  - package-ecosystem: "pip"
+    directory: "/"
+    schedule:
+      interval: "weekly"
+"#
+}
+
+/// Checked to be valid JSON after rendering (see `ScaffoldPlan`).
+pub fn renovate_json() -> &'static str {
+    r#"{
+  "$schema": "https://docs.renovatebot.com/renovate-schema.json",
+  "extends": ["config:recommended"],
+  "enabledManagers": ["pep621", "pip_requirements"],
+  "schedule": ["before 6am on monday"],
+  "lockFileMaintenance": { "enabled": true, "schedule": ["before 6am on monday"] }
+}
+"#
+}
+
+/// `--publish pypi`: build on a version tag, publish with PyPI trusted publishing, and
+/// attach the artifacts to the GitHub release.
+pub fn release_yml() -> &'static str {
+    r#"# Release {{dist_name}}: tag a version (`py-proj bump patch --git-tag`) and
+# `git push --follow-tags` to build the sdist and wheel, publish them to PyPI, and
+# attach them to a GitHub release.
+#
+# One-time setup: on PyPI, add a trusted publisher for this repository with workflow
+# `release.yml` and environment `pypi` (https://docs.pypi.org/trusted-publishers/).
+name: release
+
+on:
+  push:
+    tags: ["v*"]
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: astral-sh/setup-uv@v6
+      - name: Build the sdist and wheel
+        run: uv build
+      - uses: actions/upload-artifact@v4
+        with:
+          name: dist
+          path: dist/
+
+  pypi:
+    needs: build
+    runs-on: ubuntu-latest
+    environment:
+      name: pypi
+      url: https://pypi.org/p/{{dist_name}}
+    permissions:
+      id-token: write
+    steps:
+      - uses: actions/download-artifact@v4
+        with:
+          name: dist
+          path: dist/
+      - uses: pypa/gh-action-pypi-publish@release/v1
+
+  github-release:
+    needs: pypi
+    runs-on: ubuntu-latest
+    permissions:
+      contents: write
+    steps:
+      - uses: actions/download-artifact@v4
+        with:
+          name: dist
+          path: dist/
+      - name: Attach the artifacts to the GitHub release
+        env:
+          GH_TOKEN: {% raw %}${{ github.token }}{% endraw %}
+        run: gh release create "$GITHUB_REF_NAME" dist/* --repo "$GITHUB_REPOSITORY" --generate-notes
+"#
+}
+
+/// Root pyproject.toml written by `--init-workspace` (a virtual uv workspace).
+pub fn workspace_root_pyproject_toml() -> &'static str {
+    r#"# uv workspace root; members are added by `py-proj --create_project --workspace-member`.
+[tool.uv.workspace]
+members = []
+"#
+}
+
+/// `--minimal`'s one test, so `uv run pytest` has something to run.
+pub fn test_main_py() -> &'static str {
+    r#"{% if async_main %}import pytest
+
+from {{package}}.main import main
+
+
+@pytest.mark.asyncio
+async def test_main_runs():
+    await main()
+{% else %}from {{package}}.main import main
+
+
+def test_main_runs():
+    main()
+{% endif %}"#
+}
+
+/// A workspace member's `__init__.py` under `--testing-extras hypothesis` or
+/// `--benchmarks`: the function the property tests and benchmarks exercise (the flat
+/// layout has it in main.py).
+pub fn clamp_py() -> &'static str {
+    r#""""{{dist_name}}."""
+
+
+def clamp(value: int, low: int, high: int) -> int:
+    """{{tr.clamp_doc}}{% if "hypothesis" in testing_extras %} {{tr.clamp_doc_see}}{% endif %}."""
+    return max(low, min(value, high))
+"#
+}
+
+/// Hypothesis reads no config file, so its profiles are registered here.
+pub fn hypothesis_conftest_py() -> &'static str {
+    r#""""Hypothesis profiles: `ci` (more examples, no deadline) when $CI is set, as on
+GitHub Actions, and `dev` otherwise. HYPOTHESIS_PROFILE=ci|dev picks one explicitly."""
+
+import os
+
+from hypothesis import settings
+
+settings.register_profile("ci", max_examples=500, deadline=None, print_blob=True)
+settings.register_profile("dev", max_examples=50, deadline=500)
+settings.load_profile(os.environ.get("HYPOTHESIS_PROFILE", "ci" if os.environ.get("CI") else "dev"))
+"#
+}
+
+/// `--git-hooks native`: POSIX sh, which Git for Windows runs hooks with too. Each hook
+/// runs every check before failing, and lets the git operation through while there is
+/// no venv yet, so a fresh clone is not blocked.
+pub fn githooks_pre_commit() -> &'static str {
+    r#"#!/bin/sh
+# pre-commit hook for {{project}}: ruff lint and format checks.
+# Enabled by `git config core.hooksPath .githooks`; `git commit --no-verify` skips it once.
+# Git runs hooks with sh, also on Windows (Git for Windows ships one).
+cd "$(dirname "$0")/.." || exit 1
+{% if venv_dir != ".venv" -%}
+export UV_PROJECT_ENVIRONMENT="$PWD/{{venv_dir}}"
+{% endif -%}
+if [ ! -d "{{venv_dir}}" ]; then
+    echo "pre-commit: no {{venv_dir}} yet, skipping the checks (run \`uv sync\` to enable them)" >&2
+    exit 0
+fi
+
+status=0
+echo "pre-commit: uv run ruff check"
+uv run ruff check || status=1
+echo "pre-commit: uv run ruff format --check"
+uv run ruff format --check || status=1
+if [ "$status" -ne 0 ]; then
+    echo "pre-commit: checks failed; fix them (\`uv run ruff check --fix\`, \`uv run ruff format\`) or commit with --no-verify" >&2
+fi
+exit "$status"
+"#
+}
+
+pub fn githooks_pre_push() -> &'static str {
+    r#"#!/bin/sh
+# pre-push hook for {{project}}: the test suite.
+# Enabled by `git config core.hooksPath .githooks`; `git push --no-verify` skips it once.
+# Git runs hooks with sh, also on Windows (Git for Windows ships one).
+cd "$(dirname "$0")/.." || exit 1
+{% if venv_dir != ".venv" -%}
+export UV_PROJECT_ENVIRONMENT="$PWD/{{venv_dir}}"
+{% endif -%}
+if [ ! -d "{{venv_dir}}" ]; then
+    echo "pre-push: no {{venv_dir}} yet, skipping the tests (run \`uv sync\` to enable them)" >&2
+    exit 0
+fi
+
+echo "pre-push: uv run pytest -q"
+if ! uv run pytest -q; then
+    echo "pre-push: tests failed; fix them or push with --no-verify" >&2
+    exit 1
+fi
+"#
+}
+
+/// Outside tests/, so `make test` and a bare `pytest` skip it; `python_files` in
+/// pyproject.toml lets `pytest benchmarks` collect it.
+pub fn benchmark_example_py() -> &'static str {
+    r#""""Benchmarks of {{project}} with pytest-benchmark: {% if workspace_member or "makefile" in skipped %}`uv run pytest benchmarks`{% else %}`make bench`{% endif %}.
+The `benchmark` fixture calls the function many times and reports its timings."""
+
+from {% if workspace_member %}{{import_name}}{% else %}{{package}}.main{% endif %} import clamp
+
+
+def test_clamp_in_range(benchmark):
+    assert benchmark(clamp, 5, 0, 10) == 5
+
+
+def test_clamp_out_of_range(benchmark):
+    assert benchmark(clamp, -5, 0, 10) == 0
+"#
+}
+
+pub fn test_properties_py() -> &'static str {
+    r#""""Property-based tests: hypothesis generates the inputs, and each test states a rule
+that must hold for every one of them."""
+
+from hypothesis import given, strategies as st
+
+from {% if workspace_member %}{{import_name}}{% else %}{{package}}.main{% endif %} import clamp
+
+# (low, high) pairs with low <= high.
+ranges = st.tuples(st.integers(), st.integers()).map(sorted)
+
+
+@given(st.integers(), ranges)
+def test_clamp_stays_in_range(value, bounds):
+    low, high = bounds
+    assert low <= clamp(value, low, high) <= high
+
+
+@given(st.integers(), ranges)
+def test_clamp_is_idempotent(value, bounds):
+    low, high = bounds
+    once = clamp(value, low, high)
+    assert clamp(once, low, high) == once
+
+
+@given(ranges, st.data())
+def test_clamp_keeps_values_in_range(bounds, data):
+    low, high = bounds
+    value = data.draw(st.integers(low, high))
+    assert clamp(value, low, high) == value
+"#
+}
+
+// ------------------ add-module templates ------------------
+// Rendered by `add-module` with `module` (dotted path below the package), `package`
+// (`src` or the import package), `parent_import`, `leaf`, `test_name`, and
+// `logger_import` (whether the app_logging package is available).
+pub fn module_py() -> &'static str {
+    r#""""{{module}} module."""
+
+import logging
+{% if logger_import %}
+from {{package}}.app_logging.glogger import PROJECT_LOGGER
+
+log = logging.getLogger(f"{PROJECT_LOGGER}.{{module}}")
+{% else %}
+log = logging.getLogger(__name__)
+{% endif %}"#
+}
+
+pub fn module_test_py() -> &'static str {
+    r#"from {{parent_import}} import {{leaf}}
+
+
+def test_{{test_name}}_imports():
+    assert {{leaf}}.log is not None
+"#
+}
+
+// ------------------ template registry ------------------
+
+/// Which scaffold step writes a template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Group {
+    Src,
+    Vscode,
+    Neovim,
+    Toolchain,
+    Envs,
+    Pyrefly,
+    Pyright,
+    Pyproject,
+    Gitignore,
+    Gitattributes,
+    Editorconfig,
+    Readme,
+    Changelog,
+    Citation,
+    Makefile,
+    AppLogging,
+    Cli,
+    Nox,
+    Tox,
+    Dependabot,
+    Renovate,
+    Release,
+    GitHooks,
+    Hypothesis,
+    Benchmarks,
+    DataLayout,
+    Dvc,
+    Webapp,
+    Tests,
+    Notebook,
+}
+
+impl Group {
+    /// Stable name recorded in the manifest.
+    pub fn name(self) -> &'static str {
+        match self {
+            Group::Src => "src",
+            Group::Vscode => "vscode",
+            Group::Neovim => "neovim",
+            Group::Toolchain => "toolchain",
+            Group::Envs => "envs",
+            Group::Pyrefly => "pyrefly",
+            Group::Pyright => "pyright",
+            Group::Pyproject => "pyproject",
+            Group::Gitignore => "gitignore",
+            Group::Gitattributes => "gitattributes",
+            Group::Editorconfig => "editorconfig",
+            Group::Readme => "readme",
+            Group::Changelog => "changelog",
+            Group::Citation => "citation",
+            Group::Makefile => "makefile",
+            Group::AppLogging => "app_logging",
+            Group::Cli => "cli",
+            Group::Nox => "nox",
+            Group::Tox => "tox",
+            Group::Dependabot => "dependabot",
+            Group::Renovate => "renovate",
+            Group::Release => "release",
+            Group::GitHooks => "githooks",
+            Group::Hypothesis => "hypothesis",
+            Group::Benchmarks => "benchmarks",
+            Group::DataLayout => "data_layout",
+            Group::Dvc => "dvc",
+            Group::Webapp => "webapp",
+            Group::Tests => "tests",
+            Group::Notebook => "notebook",
+        }
+    }
+
+    /// Tool configuration that `upgrade` may refresh; everything else is user code or docs.
+    pub fn is_config(self) -> bool {
+        matches!(
+            self,
+            Group::Vscode
+                | Group::Neovim
+                | Group::Toolchain
+                | Group::Pyrefly
+                | Group::Pyright
+                | Group::Pyproject
+                | Group::Gitignore
+                | Group::Gitattributes
+                | Group::Editorconfig
+                | Group::Makefile
+                | Group::Nox
+                | Group::Tox
+                | Group::Dependabot
+                | Group::Renovate
+                | Group::Release
+                | Group::GitHooks
+        )
+    }
+}
+
+/// A built-in template and the path (relative to the project root) it is written to.
+pub struct Template {
+    /// Output path, relative to the project root, with forward slashes.
+    pub path: &'static str,
+    pub group: Group,
+    /// The unrendered template text.
+    pub source: fn() -> &'static str,
+}
+
+impl Template {
+    /// Render the template against `ctx`.
+    pub fn render(&self, ctx: &Vars) -> Result<String> {
+        render(self.path, (self.source)(), ctx)
+    }
+}
+
+fn empty() -> &'static str {
+    ""
+}
+
+/// Every built-in template, in the order the scaffold writes them.
+pub const REGISTRY: &[Template] = &[
+    Template { path: "src/__init__.py", group: Group::Src, source: empty },
+    Template { path: "src/main.py", group: Group::Src, source: main_py },
+    Template { path: "src/__main__.py", group: Group::Src, source: dunder_main_py },
+    Template { path: "src/cli.py", group: Group::Cli, source: cli_py },
+    Template { path: ".vscode/launch.json", group: Group::Vscode, source: vscode_launch_json },
+    Template { path: ".vscode/settings.json", group: Group::Vscode, source: vscode_settings_json },
+    Template { path: ".vscode/tasks.json", group: Group::Vscode, source: vscode_tasks_json },
+    Template { path: ".nvim.lua", group: Group::Neovim, source: nvim_lua },
+    Template { path: ".env", group: Group::Envs, source: dotenv },
+    Template { path: ".env.example", group: Group::Envs, source: dotenv_example },
+    Template { path: ".envrc", group: Group::Envs, source: envrc },
+    Template { path: ".python-version", group: Group::Toolchain, source: python_version },
+    Template { path: ".mise.toml", group: Group::Toolchain, source: mise_toml },
+    Template { path: ".tool-versions", group: Group::Toolchain, source: tool_versions },
+    Template { path: "pyrefly.toml", group: Group::Pyrefly, source: pyrefly_toml },
+    Template { path: "pyrightconfig.json", group: Group::Pyright, source: pyrightconfig_json },
+    Template { path: "pyproject.toml", group: Group::Pyproject, source: pyproject_toml },
+    Template { path: ".gitignore", group: Group::Gitignore, source: gitignore },
+    Template { path: ".gitattributes", group: Group::Gitattributes, source: gitattributes },
+    Template { path: ".editorconfig", group: Group::Editorconfig, source: editorconfig },
+    Template { path: "README.md", group: Group::Readme, source: readme_md },
+    Template { path: "CHANGELOG.md", group: Group::Changelog, source: changelog_md },
+    Template { path: "CITATION.cff", group: Group::Citation, source: citation_cff },
+    Template { path: "Makefile", group: Group::Makefile, source: app_make_file_creator },
+    Template { path: "noxfile.py", group: Group::Nox, source: noxfile_py },
+    Template { path: "tox.ini", group: Group::Tox, source: tox_ini },
+    Template { path: ".github/dependabot.yml", group: Group::Dependabot, source: dependabot_yml },
+    Template { path: "renovate.json", group: Group::Renovate, source: renovate_json },
+    Template { path: ".github/workflows/release.yml", group: Group::Release, source: release_yml },
+    Template { path: ".githooks/pre-commit", group: Group::GitHooks, source: githooks_pre_commit },
+    Template { path: ".githooks/pre-push", group: Group::GitHooks, source: githooks_pre_push },
+    Template { path: "tests/test_main.py", group: Group::Tests, source: test_main_py },
+    Template { path: "tests/conftest.py", group: Group::Hypothesis, source: hypothesis_conftest_py },
+    Template { path: "tests/test_properties.py", group: Group::Hypothesis, source: test_properties_py },
+    Template { path: "benchmarks/benchmark_example.py", group: Group::Benchmarks, source: benchmark_example_py },
+    Template { path: "Notebooks/starter.py", group: Group::Notebook, source: starter_notebook_py },
+    Template { path: "src/paths.py", group: Group::DataLayout, source: paths_py },
+    Template { path: "data/raw/.gitkeep", group: Group::DataLayout, source: empty },
+    Template { path: "data/interim/.gitkeep", group: Group::DataLayout, source: empty },
+    Template { path: "data/processed/.gitkeep", group: Group::DataLayout, source: empty },
+    Template { path: "models/.gitkeep", group: Group::DataLayout, source: empty },
+    Template { path: "dvc.yaml", group: Group::Dvc, source: dvc_yaml },
+    Template { path: ".dvcignore", group: Group::Dvc, source: dvcignore },
+    Template { path: "src/app.py", group: Group::Webapp, source: webapp_app_py },
+    Template { path: "src/settings.py", group: Group::Webapp, source: webapp_settings_py },
+    Template { path: "tests/test_app.py", group: Group::Webapp, source: webapp_test_app_py },
+    Template { path: "src/app_logging/__init__.py", group: Group::AppLogging, source: empty },
+    Template {
+        path: "src/app_logging/MyColoredFormatter.py",
+        group: Group::AppLogging,
+        source: app_logging_my_colored_formatter_py,
+    },
+    Template {
+        path: "src/app_logging/config07.json",
+        group: Group::AppLogging,
+        source: app_logging_config07_json,
+    },
+    Template {
+        path: "src/app_logging/constants.py",
+        group: Group::AppLogging,
+        source: app_logging_constants_py,
+    },
+    Template {
+        path: "src/app_logging/glogger.py",
+        group: Group::AppLogging,
+        source: app_logging_glogger_py,
+    },
+    Template {
+        path: "src/app_logging/myCustomJsonClass01.py",
+        group: Group::AppLogging,
+        source: app_logging_my_custom_json_class01_py,
+    },
+    Template {
+        path: "src/app_logging/myFilters.py",
+        group: Group::AppLogging,
+        source: app_logging_my_filters_py,
+    },
+];
+
+/// Drop every ignore pattern already listed earlier in `text`, and any `# py-proj:`
+/// section left empty by that. Comments and blank lines are kept.
+pub fn dedup_patterns(text: &str) -> String {
+    let is_pattern = |line: &str| !line.trim().is_empty() && !line.trim().starts_with('#');
+    let mut seen = std::collections::HashSet::new();
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !is_pattern(line) || seen.insert(line.trim()))
+        .collect();
+    let mut out: Vec<&str> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let empty_section = line.starts_with("# py-proj:")
+            && !lines.get(i + 1).is_some_and(|next| is_pattern(next));
+        if empty_section {
+            // The blank line that separated it from the previous section goes too.
+            if out.last().is_some_and(|l| l.trim().is_empty()) {
+                out.pop();
+            }
+            continue;
+        }
+        out.push(line);
+    }
+    let mut text = out.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Look up a built-in template by its relative output path.
+pub fn find(path: &str) -> Option<&'static Template> {
+    REGISTRY.iter().find(|t| t.path == path)
+}
+
+// ------------------ rendering ------------------
+
+/// Variables visible to templates.
+pub type Vars = BTreeMap<String, Value>;
+
+/// Variables py-proj always provides; user `--var`s are added on top but may not shadow
+/// them.
+pub const BUILTIN_VARS: &[&str] = &[
+    "project",
+    "dist_name",
+    "import_name",
+    "py_full",
+    "mm",
+    "mm_nodec",
+    "workspace_member",
+    "namespace", // empty without --namespace
+    "package", // import path: `src` flat, `<namespace>.<import_name>`, a member's import name
+    "package_dir", // the package's directory, relative to the root
+    "run_module", // what `python -m` runs: `src.main` flat, else the package
+    "description",
+    "author",
+    "email",
+    "repo_url",
+    "homepage",
+    "org",
+    "license", // these seven are empty when not given
+    "maintainers", // `name`/`email` maps
+    "secrets", // `key`/`value` maps of the secrets generated into .env
+    "date",
+    "line_length",
+    "components", // the [`Group`] names this plan writes
+    "profile", // see [`crate::profile::Profile`]
+    "gitignore_extra",
+    "entry_point", // the --entry-point command, empty when not given
+    "modules", // the --modules dotted paths
+    "pytest_addopts",
+    "pytest_markers", // as `name: description`
+    "testing_extras", // the --testing-extras names
+    "async_main",
+    "benchmarks",
+    "data_layout",
+    "dvc",
+    "python_matrix", // oldest first
+    "matrix_runner",
+    "dep_style", // `extras` or `groups`
+    "publish", // `pypi`, empty without --publish
+    "git_hooks", // `native`, empty without --git-hooks
+    "security", // `bandit` or `ruff-s`, empty without --security
+    "status", // empty without --status
+    "tools_global",
+    "license_header", // the SPDX identifier, empty without --license-header
+    "report_file", // the --summary-file path relative to the root, empty without it
+    "jupytext",
+    "jupytext_ignore_ipynb",
+    "skipped", // the [`Group`] names --minimal leaves out
+    "code_workspace", // the .code-workspace path relative to the root, empty without it
+    "venv_seed",
+    "venv_prompt", // the project name unless --venv-prompt was given
+    "venv_dir", // `.venv` unless --venv-dir was given
+    "notebooks_dir", // `Notebooks` unless --notebooks-dir was given
+    "ide", // `vscode`, `neovim`, or `none`
+    "toolchain", // `mise`, `asdf`, or `python-version`, empty without --toolchain-file
+    "line_endings", // `lf`, `crlf`, or `native`
+    "lang",
+    "tr",
+];
+
+/// Line length shared by ruff, black, and `.editorconfig` (the `line_length` variable).
+pub const LINE_LENGTH: u16 = 100;
+
+/// Strict about undefined variables. `|toml` and `|yaml` quote a string for TOML and
+/// YAML; `|md`, `|md_url`, and `|badge` escape one for Markdown text, a Markdown link
+/// target, and a shields.io badge path.
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.set_syntax(
+        SyntaxConfig::builder()
+            .keep_trailing_newline(true)
+            .build()
+            .expect("default delimiters are valid"),
+    );
+    env.add_filter("toml", |s: String| toml::Value::String(s).to_string());
+    // A JSON string is a valid YAML double-quoted scalar.
+    env.add_filter("yaml", |s: String| serde_json::Value::String(s).to_string());
+    env.add_filter("md", md_escape);
+    env.add_filter("md_url", |s: String| {
+        s.replace(' ', "%20").replace('(', "%28").replace(')', "%29").replace('<', "%3C").replace('>', "%3E")
+    });
+    env.add_filter("badge", badge_segment);
+    env
+}
+
+/// `s` as literal Markdown text: characters that would start emphasis, a link, HTML,
+/// or a table cell are backslash-escaped.
+fn md_escape(s: String) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\`*_[]<>|#".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `s` as one segment of a shields.io static badge path: `-` and `_` are doubled (they
+/// separate and stand for spaces there) and anything not URL-safe is percent-encoded.
+fn badge_segment(s: String) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '-' => out.push_str("--"),
+            '_' => out.push_str("__"),
+            ' ' => out.push('_'),
+            c if c.is_ascii_alphanumeric() || ".~".contains(c) => out.push(c),
+            c => {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    out.push_str(&format!("%{byte:02X}"));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `source` with the rendered [`license_header`] on top, after a shebang and an
+/// encoding line if there are any (Python needs both to stay first).
+pub fn add_license_header(source: &str, spdx: &str, year: &str, holder: &str) -> Result<String> {
+    let mut ctx = Vars::new();
+    ctx.insert("spdx".into(), Value::from(spdx));
+    ctx.insert("year".into(), Value::from(year));
+    ctx.insert("holder".into(), Value::from(holder));
+    let header = render("license header", license_header(), &ctx)?;
+    let keep = preamble_len(source);
+    Ok(format!("{}{header}{}", &source[..keep], &source[keep..]))
+}
+
+/// Whether the generated file at `path` gets the [`add_provenance_stamp`] comment: TOML,
+/// Makefiles, `.gitignore`, and Python take `#` comments; JSON takes none.
+pub fn takes_provenance_stamp(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name == "Makefile" || name == ".gitignore" || name.ends_with(".toml") || name.ends_with(".py")
+}
+
+/// `source` with a comment saying py-proj generated it, after any shebang or coding line,
+/// so `verify` and `upgrade` readers know edits should go through the manifest.
+pub fn add_provenance_stamp(source: &str) -> String {
+    let version: Vec<&str> = env!("CARGO_PKG_VERSION").split('.').take(2).collect();
+    let keep = preamble_len(source);
+    format!(
+        "{}# generated by py-proj v{} (do not edit below this line without updating the manifest)\n{}",
+        &source[..keep],
+        version.join("."),
+        &source[keep..]
+    )
+}
+
+/// The length of the lines that must stay first in a script: a shebang, a coding line.
+fn preamble_len(source: &str) -> usize {
+    let mut keep = 0;
+    for (i, line) in source.split_inclusive('\n').take(2).enumerate() {
+        let shebang = i == 0 && line.starts_with("#!");
+        let coding = line.trim_start().starts_with('#') && line.contains("coding");
+        if !(shebang || coding) {
+            break;
+        }
+        keep += line.len();
+    }
+    keep
+}
+
+/// Render `source` against `ctx`. `name` (usually the relative path) is used in errors.
+pub fn render(name: &str, source: &str, ctx: &Vars) -> Result<String> {
+    let env = environment();
+    let tmpl = env
+        .template_from_named_str(name, source)
+        .map_err(|e| anyhow!("template `{name}` is invalid: {e}"))?;
+
+    tmpl.render(ctx).map_err(|e| {
+        let mut missing: Vec<_> = tmpl
+            .undeclared_variables(false)
+            .into_iter()
+            .filter(|v| !ctx.contains_key(v))
+            .collect();
+        if e.kind() == ErrorKind::UndefinedError && !missing.is_empty() {
+            missing.sort();
+            anyhow!(
+                "template `{name}` references undefined variable(s): {} \
+                 (define with --var KEY=VALUE or [vars] in the config file)",
+                missing.join(", ")
+            )
+        } else {
+            anyhow!("failed to render template `{name}`: {e}")
+        }
+    })
 }
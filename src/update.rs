@@ -0,0 +1,207 @@
+//! Checking GitHub for a newer py-proj release. This only ever tells the user; nothing
+//! is downloaded or replaced.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+use crate::config::state_dir;
+use crate::util::run_output;
+
+const LATEST_RELEASE_API: &str =
+    "https://api.github.com/repos/Ghasak/pyproject_builder/releases/latest";
+
+/// Seconds the whole request may take; a check must never hold up the command it rides on.
+const TIMEOUT_SECS: &str = "5";
+
+/// How long an automatic check trusts the last answer before asking GitHub again.
+pub const CHECK_INTERVAL_DAYS: i64 = 7;
+
+const CACHE_FILE: &str = "update-check.json";
+
+/// A semantic version: `1.2.3`, optionally with a pre-release (`1.3.0-rc.1`). A leading
+/// `v` and build metadata (`+...`) are accepted and ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Dot-separated pre-release identifiers; empty for a release.
+    pub pre: Vec<String>,
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{s}` is not a semantic version (MAJOR.MINOR.PATCH)");
+        let core = s.trim().trim_start_matches('v');
+        let core = core.split_once('+').map_or(core, |(v, _)| v);
+        let (numbers, pre) = match core.split_once('-') {
+            Some((numbers, pre)) => (numbers, pre.split('.').map(str::to_string).collect()),
+            None => (core, Vec::new()),
+        };
+        let parts = numbers
+            .split('.')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return Err(invalid());
+        };
+        if pre.iter().any(String::is_empty) {
+            return Err(invalid());
+        }
+        Ok(Version { major, minor, patch, pre })
+    }
+}
+
+impl Ord for Version {
+    /// Semver precedence: a pre-release sorts before its release, and pre-release
+    /// identifiers compare numerically when both are numbers, else as text.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |v: &Version| (v.major, v.minor, v.patch);
+        let core = key(self).cmp(&key(other));
+        if core != Ordering::Equal {
+            return core;
+        }
+        match (self.pre.is_empty(), other.pre.is_empty()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        for (a, b) in self.pre.iter().zip(&other.pre) {
+            let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            };
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        self.pre.len().cmp(&other.pre.len())
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// The latest published release.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    /// The git tag, e.g. `v0.2.0`.
+    pub tag: String,
+    /// The release page.
+    pub url: String,
+}
+
+/// How the running py-proj compares to the latest release.
+#[derive(Debug, Clone)]
+pub struct UpdateStatus {
+    pub current: Version,
+    pub latest: Version,
+    pub release: Release,
+}
+
+impl UpdateStatus {
+    pub fn update_available(&self) -> bool {
+        self.latest > self.current
+    }
+}
+
+/// What the last check found, kept in the state directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cache {
+    checked_at: DateTime<Utc>,
+    /// `None` when that check failed.
+    release: Option<Release>,
+}
+
+/// Compare this py-proj with the latest release. Unless `fresh`, an answer from the
+/// last [`CHECK_INTERVAL_DAYS`] days is reused; if that check failed, this returns
+/// `None` rather than slowing an offline machine down on every run. GitHub is asked
+/// with `curl` under a short timeout, and the answer is cached for next time.
+pub fn check(fresh: bool) -> Result<Option<UpdateStatus>> {
+    let cache_path = state_dir().map(|d| d.join(CACHE_FILE));
+    let cached: Option<Cache> = cache_path
+        .as_ref()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|text| serde_json::from_str(&text).ok());
+    let release = match cached {
+        Some(c) if !fresh && Utc::now() - c.checked_at < Duration::days(CHECK_INTERVAL_DAYS) => {
+            match c.release {
+                Some(release) => release,
+                None => return Ok(None),
+            }
+        }
+        _ => {
+            let fetched = fetch_latest();
+            // Not being able to remember the answer only means asking again next time.
+            if let Some(path) = &cache_path {
+                let cache = Cache {
+                    checked_at: Utc::now(),
+                    release: fetched.as_ref().ok().cloned(),
+                };
+                if let Ok(text) = serde_json::to_string(&cache) {
+                    let _ = path.parent().map(fs::create_dir_all);
+                    let _ = fs::write(path, text);
+                }
+            }
+            fetched?
+        }
+    };
+    Ok(Some(UpdateStatus {
+        current: env!("CARGO_PKG_VERSION").parse().map_err(anyhow::Error::msg)?,
+        latest: release.tag.parse().map_err(anyhow::Error::msg)?,
+        release,
+    }))
+}
+
+/// Ask the GitHub releases API for the latest release.
+fn fetch_latest() -> Result<Release> {
+    #[derive(Deserialize)]
+    struct GitHubRelease {
+        tag_name: String,
+        html_url: String,
+    }
+
+    let args = [
+        "-fsSL",
+        "--max-time",
+        TIMEOUT_SECS,
+        "-H",
+        "Accept: application/vnd.github+json",
+        LATEST_RELEASE_API,
+    ];
+    let body = run_output("curl", &args, &env::temp_dir())
+        .with_context(|| format!("could not reach {LATEST_RELEASE_API}"))?;
+    let release: GitHubRelease = serde_json::from_str(&body)
+        .with_context(|| format!("unexpected response from {LATEST_RELEASE_API}"))?;
+    if release.tag_name.is_empty() {
+        bail!("the latest release on GitHub has no tag");
+    }
+    Ok(Release {
+        tag: release.tag_name,
+        url: release.html_url,
+    })
+}
@@ -1,34 +1,739 @@
 use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::env;
 use std::fs::{self, File};
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::error::Error;
+use crate::manifest::{Manifest, MANIFEST_FILE};
+use crate::python::PythonVersion;
+use crate::workspace;
+
+/// What to do when the destination of a write already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePolicy {
+    Overwrite,
+    /// Leave an existing file alone.
+    Skip,
+    /// Copy the existing file to `<name>.bak` before replacing it.
+    Backup,
+}
+
+/// What [`write_with`] did.
+#[derive(Debug, PartialEq, Eq)]
+pub enum WriteOutcome {
+    Created,
+    Updated,
+    /// The file already had exactly this content; it was not rewritten (mtime kept).
+    Unchanged,
+    /// The file differs but the policy said to leave it alone.
+    Skipped,
+    /// Replaced, with the previous content saved at the given path.
+    BackedUp(PathBuf),
+}
+
+/// Atomically write `content` to `path`, replacing any existing file.
 pub fn write<P: AsRef<Path>>(path: P, content: impl AsRef<[u8]>) -> Result<()> {
-    if let Some(parent) = path.as_ref().parent() {
-        fs::create_dir_all(parent)?;
+    write_with(path, content, WritePolicy::Overwrite).map(|_| ())
+}
+
+/// The permissions a generated file at `rel` (relative to the project root) gets:
+/// `0o755` for the ones meant to be run (`.envrc`, anything under `scripts/`, and git
+/// hooks under `.githooks/`), `None` for the default.
+pub fn file_mode(rel: &str) -> Option<u32> {
+    let rel = rel.replace('\\', "/");
+    let executable = rel == ".envrc" || rel.starts_with("scripts/") || rel.starts_with(".githooks/");
+    executable.then_some(0o755)
+}
+
+/// The permissions a generated file at `rel` gets when files are given `base` (`--file-mode`):
+/// `base` itself, with execute added wherever it grants read for the files [`file_mode`]
+/// makes executable (`664` -> `775`). Without `base`, [`file_mode`].
+pub fn generated_mode(rel: &str, base: Option<u32>) -> Option<u32> {
+    match base {
+        Some(mode) if file_mode(rel).is_some() => Some(mode | (mode & 0o444) >> 2),
+        Some(mode) => Some(mode),
+        None => file_mode(rel),
     }
-    let mut f = File::create(path)?;
-    f.write_all(content.as_ref())?;
-    Ok(())
 }
 
-/// Run a command for side effects, erroring on non-zero status.
-pub fn run(cmd: &str, args: &[&str], cwd: &Path) -> Result<()> {
-    let status = Command::new(cmd)
-        .args(args)
-        .current_dir(cwd)
-        .stdin(Stdio::null())
+/// Parse an octal mode as `--dir-mode` and `--file-mode` take it: `2775`, `0775`, or
+/// `0o775`, special bits (setuid, setgid, sticky) included.
+pub fn parse_mode(s: &str) -> std::result::Result<u32, String> {
+    let digits = s.strip_prefix("0o").unwrap_or(s);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if !digits.is_empty() && mode <= 0o7777 => Ok(mode),
+        _ => Err(format!("invalid mode {s:?}: expected octal permission bits, like 775 or 2775")),
+    }
+}
+
+/// `mode` as the manifest records it: `0o755` -> `"755"`.
+pub fn format_mode(mode: u32) -> String {
+    format!("{mode:o}")
+}
+
+/// The permission bits of the file at `path`; `None` where it is missing, and always on
+/// Windows, which has no such bits.
+pub fn current_mode(path: &Path) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).ok().map(|m| m.permissions().mode() & 0o777)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// Give the file at `path` the permission bits `mode`; true when they changed. Windows
+/// has no such bits; there it does nothing.
+pub fn set_mode(path: &Path, mode: u32) -> Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o7777 == mode) {
+            return Ok(false);
+        }
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+            .with_context(|| format!("failed to set the mode of {} to {}", path.display(), format_mode(mode)))?;
+        Ok(true)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (path, mode);
+        Ok(false)
+    }
+}
+
+/// [`set_mode`] for a directory, keeping a setgid bit it inherited from its parent so
+/// the group ownership set up above it carries on below.
+pub fn set_dir_mode(path: &Path, mode: u32) -> Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let inherited = fs::metadata(path).map_or(0, |m| m.permissions().mode() & 0o2000);
+        set_mode(path, mode | inherited)
+    }
+    #[cfg(not(unix))]
+    {
+        set_mode(path, mode)
+    }
+}
+
+/// `text` with every line break (`\n` or `\r\n`) written as `\r\n` when `crlf`, else as
+/// `\n`. A lone `\r` is not a line break and stays.
+pub fn normalize_line_endings(text: &[u8], crlf: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for (i, &b) in text.iter().enumerate() {
+        match b {
+            b'\r' if text.get(i + 1) == Some(&b'\n') => {}
+            b'\n' if crlf => out.extend_from_slice(b"\r\n"),
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+/// Write through a temp file in the same directory that is fsynced and renamed over
+/// `path`, so an interrupted run never leaves a truncated file. An existing file's
+/// permissions carry over to the new one.
+pub fn write_with<P: AsRef<Path>>(
+    path: P,
+    content: impl AsRef<[u8]>,
+    policy: WritePolicy,
+) -> Result<WriteOutcome> {
+    write_with_mode(path, content, policy, None)
+}
+
+/// [`write_with`], giving the file the permission bits `mode` (see [`file_mode`]) when
+/// set. The temp file gets them before the rename, so the file never exists without
+/// them; a file that already has this content only has its mode fixed.
+pub fn write_with_mode<P: AsRef<Path>>(
+    path: P,
+    content: impl AsRef<[u8]>,
+    policy: WritePolicy,
+    mode: Option<u32>,
+) -> Result<WriteOutcome> {
+    let path = path.as_ref();
+    let content = content.as_ref();
+    let existing = fs::metadata(path).ok().filter(|m| m.is_file());
+    if let Some(meta) = &existing {
+        if same_content(path, meta.len(), content) {
+            return match mode.map(|m| set_mode(path, m)).transpose()? {
+                Some(true) => Ok(WriteOutcome::Updated),
+                _ => Ok(WriteOutcome::Unchanged),
+            };
+        }
+        if policy == WritePolicy::Skip {
+            return Ok(WriteOutcome::Skipped);
+        }
+    }
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = dir.join(format!(".{name}.py-proj-{}.tmp", std::process::id()));
+    let written = (|| -> Result<()> {
+        let mut f = File::create(&tmp)?;
+        f.write_all(content)?;
+        f.sync_all()?;
+        if let Some(meta) = &existing {
+            fs::set_permissions(&tmp, meta.permissions())?;
+        }
+        if let Some(mode) = mode {
+            set_mode(&tmp, mode)?;
+        }
+        Ok(())
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.context(format!("failed to write {}", path.display())));
+    }
+
+    let mut outcome = match existing {
+        Some(_) => WriteOutcome::Updated,
+        None => WriteOutcome::Created,
+    };
+    if existing.is_some() && policy == WritePolicy::Backup {
+        let bak = dir.join(format!("{name}.bak"));
+        if let Err(e) = fs::copy(path, &bak) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("failed to back up {}", path.display()));
+        }
+        outcome = WriteOutcome::BackedUp(bak);
+    }
+    if let Err(e) = fs::rename(&tmp, path) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("failed to replace {}", path.display()));
+    }
+    Ok(outcome)
+}
+
+/// True when the file at `path` (of `len` bytes) holds exactly `content`. Files of
+/// a different size are never read.
+fn same_content(path: &Path, len: u64, content: &[u8]) -> bool {
+    len == content.len() as u64 && fs::read(path).is_ok_and(|old| old == content)
+}
+
+/// Run a command with inherited stdio (its output goes straight to the terminal),
+/// with extra environment variables for the child. Errors on non-zero status.
+pub fn run_env(cmd: &str, args: &[&str], cwd: &Path, envs: &[(&str, &str)]) -> Result<()> {
+    let status = command(cmd, args, cwd, envs)
         .status()
-        .with_context(|| format!("failed to run `{cmd} {}`", args.join(" ")))?;
+        .map_err(|e| spawn_error(cmd, args, e))?;
+    if !status.success() {
+        return Err(command_failed(cmd, status, ""));
+    }
+    Ok(())
+}
+
+/// What a command printed.
+#[derive(Debug, Default)]
+pub struct Captured {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Default for [`set_command_timeout`], generous enough for a slow toolchain download.
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Timeout in seconds for each captured command; 0 means none.
+static COMMAND_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_COMMAND_TIMEOUT.as_secs());
+/// Process (group) ids of the captured commands running right now.
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// How many [`interruptible`] sections are running right now.
+static WATCHING: AtomicUsize = AtomicUsize::new(0);
+
+/// How long an interrupted command gets to exit on its own before it is killed.
+pub const INTERRUPT_GRACE: Duration = Duration::from_secs(2);
+
+/// Kill each [`run_captured`]/[`run_streaming`] command that runs longer than `timeout`
+/// (`None`: wait forever).
+pub fn set_command_timeout(timeout: Option<Duration>) {
+    let secs = timeout.map_or(0, |t| t.as_secs().max(1));
+    COMMAND_TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
+
+fn command_timeout() -> Option<Duration> {
+    match COMMAND_TIMEOUT_SECS.load(Ordering::Relaxed) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Handle a Ctrl-C or SIGTERM: forward SIGINT to every running captured command's
+/// process group (killing what is left after [`INTERRUPT_GRACE`]) and flag the run, so
+/// those calls fail with [`Error::Interrupted`] and [`check_interrupt`] stops the
+/// [`interruptible`] work at its next step. Returns false when there is nobody to
+/// notice, or on a second interrupt: the caller should exit right away.
+pub fn interrupt() -> bool {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    let running = RUNNING.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    for &pid in &running {
+        signal_tree(pid);
+    }
+    let any_running = !running.is_empty();
+    if any_running {
+        thread::spawn(move || {
+            thread::sleep(INTERRUPT_GRACE);
+            let still = RUNNING.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            for pid in still.into_iter().filter(|pid| running.contains(pid)) {
+                kill_tree(pid);
+            }
+        });
+    }
+    any_running || WATCHING.load(Ordering::SeqCst) > 0
+}
+
+/// Whether the user has interrupted this run.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// [`Error::Interrupted`] once the user has interrupted this run.
+pub fn check_interrupt() -> Result<()> {
+    match interrupted() {
+        true => Err(Error::Interrupted {
+            program: None,
+            rolled_back: None,
+        }
+        .into()),
+        false => Ok(()),
+    }
+}
+
+/// Run `f`, which polls [`check_interrupt`] between its steps: meanwhile a Ctrl-C only
+/// flags the run instead of exiting, so `f` stops at a safe point (and can undo its work).
+pub fn interruptible<T>(f: impl FnOnce() -> T) -> T {
+    WATCHING.fetch_add(1, Ordering::SeqCst);
+    let _watching = defer(|| {
+        WATCHING.fetch_sub(1, Ordering::SeqCst);
+    });
+    f()
+}
+
+/// Pass the interrupt on to `pid` and its process group; a command that ignores it is
+/// killed by [`interrupt`] after the grace period.
+fn signal_tree(pid: u32) {
+    #[cfg(unix)]
+    unsafe {
+        // SAFETY: as in `kill_tree`.
+        libc::kill(-(pid as libc::pid_t), libc::SIGINT);
+    }
+    #[cfg(not(unix))]
+    kill_tree(pid);
+}
+
+/// Kill `pid` and its process group (each captured command gets its own on Unix).
+fn kill_tree(pid: u32) {
+    #[cfg(unix)]
+    unsafe {
+        // SAFETY: kill(2) takes plain integers; a stale pid at worst yields ESRCH.
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Run a command with stdout and stderr captured. On failure the error carries the
+/// end of stderr, so it makes sense without a terminal (CI, `--quiet`).
+pub fn run_captured(cmd: &str, args: &[&str], cwd: &Path) -> Result<Captured> {
+    run_streaming(cmd, args, cwd, |_| {})
+}
+
+/// Like [`run_captured`], but hand each output line (stdout or stderr, as it arrives)
+/// to `on_line` while the command runs. Partial and non-UTF-8 lines are passed on
+/// lossily; everything is still captured for the error on failure. A command that
+/// outlives the [command timeout](set_command_timeout) is killed.
+pub fn run_streaming(cmd: &str, args: &[&str], cwd: &Path, on_line: impl FnMut(&str)) -> Result<Captured> {
+    run_cancellable(cmd, args, cwd, on_line, &AtomicBool::new(false))
+}
+
+/// How often [`run_cancellable`] looks at its cancel flag.
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// [`run_streaming`] for a command on another thread: once `cancel` is set, the command
+/// and its children are killed and the call fails.
+pub fn run_cancellable(
+    cmd: &str,
+    args: &[&str],
+    cwd: &Path,
+    mut on_line: impl FnMut(&str),
+    cancel: &AtomicBool,
+) -> Result<Captured> {
+    let mut c = command(cmd, args, cwd, &[]);
+    c.stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        // Its own process group, so a timeout or Ctrl-C can take its children down too.
+        use std::os::unix::process::CommandExt;
+        c.process_group(0);
+    }
+    let mut child = c.spawn().map_err(|e| spawn_error(cmd, args, e))?;
+    let pid = child.id();
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner()).push(pid);
+    let _running = defer(move || {
+        RUNNING.lock().unwrap_or_else(|e| e.into_inner()).retain(|&p| p != pid);
+    });
+
+    // One reader thread per pipe, so neither can fill up and block the child.
+    let (tx, rx) = mpsc::channel();
+    if let Some(out) = child.stdout.take() {
+        pump(out, false, tx.clone());
+    }
+    if let Some(err) = child.stderr.take() {
+        pump(err, true, tx.clone());
+    }
+    drop(tx);
+
+    let timeout = command_timeout();
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let mut captured = Captured::default();
+    let mut tail = VecDeque::new();
+    let mut record = |is_stderr: bool, line: String| {
+        on_line(&line);
+        let buf = if is_stderr {
+            &mut captured.stderr
+        } else {
+            &mut captured.stdout
+        };
+        buf.push_str(&line);
+        buf.push('\n');
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    };
+
+    let mut timed_out = false;
+    let cancelled = || cancel.load(Ordering::SeqCst);
+    loop {
+        let wait = deadline.map_or(CANCEL_POLL, |d| d.saturating_duration_since(Instant::now()).min(CANCEL_POLL));
+        match rx.recv_timeout(wait) {
+            Ok((is_stderr, line)) => record(is_stderr, line),
+            Err(mpsc::RecvTimeoutError::Timeout) if cancelled() => break,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    timed_out = true;
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    // Both pipes are closed, but the command may still be running.
+    while !timed_out && !cancelled() && deadline.is_some_and(|d| Instant::now() < d) {
+        if child.try_wait()?.is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    if cancelled() && child.try_wait()?.is_none() {
+        kill_tree(pid);
+        let _ = child.kill();
+        let _ = child.wait();
+        anyhow::bail!("`{cmd}` was cancelled");
+    }
+    if deadline.is_some_and(|d| Instant::now() >= d) && child.try_wait()?.is_none() {
+        timed_out = true;
+    }
+
+    if timed_out {
+        kill_tree(pid);
+        let _ = child.kill();
+        let _ = child.wait();
+        // Pick up what was printed right before the kill.
+        while let Ok((is_stderr, line)) = rx.recv_timeout(Duration::from_millis(100)) {
+            record(is_stderr, line);
+        }
+        return Err(Error::Timeout {
+            program: cmd.to_string(),
+            secs: timeout.map_or(0, |t| t.as_secs()),
+            output: Vec::from(tail).join("\n"),
+        }
+        .into());
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("failed to wait for `{cmd}`"))?;
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        // Whatever it started and left behind in its process group goes too.
+        kill_tree(pid);
+        return Err(Error::Interrupted {
+            program: Some(cmd.to_string()),
+            rolled_back: None,
+        }
+        .into());
+    }
     if !status.success() {
-        anyhow::bail!("command `{cmd}` failed with status {status}");
+        return Err(command_failed(cmd, status, &captured.stderr));
+    }
+    Ok(captured)
+}
+
+/// Run `f` when the returned guard is dropped.
+fn defer(f: impl FnOnce()) -> impl Drop {
+    struct Guard<F: FnOnce()>(Option<F>);
+    impl<F: FnOnce()> Drop for Guard<F> {
+        fn drop(&mut self) {
+            if let Some(f) = self.0.take() {
+                f();
+            }
+        }
+    }
+    Guard(Some(f))
+}
+
+/// Forward lines read from `pipe` to `tx`, tagged with which stream they came from.
+fn pump(pipe: impl Read + Send + 'static, is_stderr: bool, tx: mpsc::Sender<(bool, String)>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if tx.send((is_stderr, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Like [`run_captured`], returning only stdout.
+pub fn run_output(cmd: &str, args: &[&str], cwd: &Path) -> Result<String> {
+    run_captured(cmd, args, cwd).map(|c| c.stdout)
+}
+
+/// A command that could not be started; a missing program becomes [`Error::MissingTool`].
+fn spawn_error(cmd: &str, args: &[&str], e: std::io::Error) -> anyhow::Error {
+    let err = match e.kind() {
+        std::io::ErrorKind::NotFound => Error::MissingTool {
+            tool: cmd.to_string(),
+        }
+        .into(),
+        _ => anyhow::Error::from(e),
+    };
+    err.context(format!("failed to run `{cmd} {}`", args.join(" ")))
+}
+
+/// Lines of output kept in a [`Error::CommandFailed`] or [`Error::Timeout`].
+const STDERR_TAIL_LINES: usize = 40;
+
+fn command_failed(cmd: &str, status: std::process::ExitStatus, stderr: &str) -> anyhow::Error {
+    let lines: Vec<&str> = stderr.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n");
+    Error::CommandFailed {
+        program: cmd.to_string(),
+        status,
+        stderr: tail,
+    }
+    .into()
+}
+
+fn command(cmd: &str, args: &[&str], cwd: &Path, envs: &[(&str, &str)]) -> Command {
+    let mut c = Command::new(cmd);
+    c.args(args)
+        .envs(envs.iter().copied())
+        .current_dir(cwd)
+        .stdin(Stdio::null());
+    c
+}
+
+/// Set by [`bootstrap_uv`] once it has installed uv somewhere off `PATH`.
+static BOOTSTRAPPED_UV: OnceLock<String> = OnceLock::new();
+
+/// Set by [`set_uv_bin`] (`--uv-bin`).
+static UV_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Environment variables naming the uv executable, in order of precedence.
+pub const UV_BIN_VARS: &[&str] = &["PY_PROJ_UV", "PY_PROJ_UV_BIN", "UV"];
+
+/// The uv executable: the one [`bootstrap_uv`] installed, then `--uv-bin`, then the
+/// first of [`UV_BIN_VARS`] that is set, else `uv` from `PATH`.
+pub fn uv_bin() -> String {
+    if let Some(uv) = BOOTSTRAPPED_UV.get().or(UV_OVERRIDE.get()) {
+        return uv.clone();
+    }
+    UV_BIN_VARS
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|s| !s.is_empty()))
+        .unwrap_or_else(|| "uv".to_string())
+}
+
+/// Point [`uv_bin`] at `path` for the rest of the process, once it is known to exist
+/// and to answer `--version` like uv does.
+pub fn set_uv_bin(path: &str) -> Result<()> {
+    if which::which(path).is_err() {
+        return Err(Error::MissingTool { tool: path.to_string() }.into());
+    }
+    let out = run_output(path, &["--version"], &env::temp_dir())
+        .with_context(|| format!("--uv-bin {path}: `--version` failed"))?;
+    if parse_uv_version(&out).is_none() {
+        let first = out.lines().next().unwrap_or_default().trim();
+        anyhow::bail!("--uv-bin {path} does not look like uv: `--version` printed {first:?}");
     }
+    let _ = UV_OVERRIDE.set(path.to_string());
     Ok(())
 }
 
-/// Find the system Python version or return a default.
+/// [`uv_bin`] as an absolute path when it can be found, for reports.
+pub fn resolved_uv_bin() -> String {
+    let uv = uv_bin();
+    which::which(&uv).map_or(uv, |path| path.display().to_string())
+}
+
+const UV_INSTALLER_URL: &str = "https://astral.sh/uv/install.sh";
+
+/// Download and run the official uv installer, then point [`uv_bin`] at the uv it
+/// installed (usually `~/.local/bin/uv`, which may not be on `PATH` yet). Installer
+/// output goes to `on_line`.
+pub fn bootstrap_uv(mut on_line: impl FnMut(&str)) -> Result<PathBuf> {
+    let tmp = env::temp_dir();
+    if cfg!(windows) {
+        let script = "irm https://astral.sh/uv/install.ps1 | iex";
+        run_streaming("powershell", &["-ExecutionPolicy", "ByPass", "-c", script], &tmp, &mut on_line)
+            .context("the uv installer failed")?;
+    } else {
+        // Download first, so a network error is reported as one instead of `sh` running
+        // an empty script.
+        let script = tmp.join(format!("py-proj-uv-install-{}.sh", std::process::id()));
+        let script_str = script.to_string_lossy();
+        let fetched = run_streaming(
+            "curl",
+            &["-LsSf", UV_INSTALLER_URL, "-o", &script_str],
+            &tmp,
+            &mut on_line,
+        )
+        .with_context(|| format!("failed to download {UV_INSTALLER_URL} (check your network access)"));
+        let installed = fetched.and_then(|_| {
+            run_streaming("sh", &[&script_str], &tmp, &mut on_line)
+                .context("the uv installer failed")
+        });
+        let _ = fs::remove_file(&script);
+        installed?;
+    }
+    let uv = find_installed_uv().context(
+        "the uv installer finished, but uv was not found on PATH, in ~/.local/bin, or in ~/.cargo/bin",
+    )?;
+    let _ = BOOTSTRAPPED_UV.set(uv.to_string_lossy().into_owned());
+    Ok(uv)
+}
+
+/// uv on `PATH`, or where the installer puts it.
+fn find_installed_uv() -> Option<PathBuf> {
+    if let Ok(uv) = which::which("uv") {
+        return Some(uv);
+    }
+    let exe = if cfg!(windows) { "uv.exe" } else { "uv" };
+    let mut dirs: Vec<PathBuf> = env::var_os("XDG_BIN_HOME").map(PathBuf::from).into_iter().collect();
+    if let Some(home) = home_dir() {
+        dirs.push(home.join(".local").join("bin"));
+        dirs.push(home.join(".cargo").join("bin"));
+    }
+    dirs.into_iter().map(|d| d.join(exe)).find(|p| p.is_file())
+}
+
+/// The official one-line uv installer for this OS.
+pub fn uv_install_hint() -> &'static str {
+    if cfg!(windows) {
+        r#"powershell -ExecutionPolicy ByPass -c "irm https://astral.sh/uv/install.ps1 | iex""#
+    } else if cfg!(target_os = "macos") {
+        "curl -LsSf https://astral.sh/uv/install.sh | sh   (or: brew install uv)"
+    } else {
+        "curl -LsSf https://astral.sh/uv/install.sh | sh"
+    }
+}
+
+/// Oldest uv with `uv python install`, which the scaffold relies on.
+pub const MIN_UV_VERSION: (u64, u64, u64) = (0, 4, 0);
+
+/// `.env` lines with every value removed (`KEY=secret` becomes `KEY=`); comments and
+/// blank lines stay as they are.
+pub fn blank_env_values(env: &str) -> String {
+    env.lines()
+        .map(|line| match line.split_once('=') {
+            Some((key, _)) if !line.trim_start().starts_with('#') => format!("{key}=\n"),
+            _ => format!("{line}\n"),
+        })
+        .collect()
+}
+
+/// Names of the tools installed with `uv tool install`.
+pub fn uv_tools() -> Result<Vec<String>> {
+    let out = run_output(&uv_bin(), &["tool", "list"], &env::temp_dir())?;
+    // Each tool is a `name vX.Y.Z` line, followed by `- executable` lines.
+    Ok(out
+        .lines()
+        .filter(|l| !l.starts_with('-'))
+        .filter_map(|l| l.split_whitespace().next())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Make sure uv is on `PATH` and at least [`MIN_UV_VERSION`], before anything is
+/// written. Returns the version it reported (None if it could not be parsed).
+pub fn check_uv() -> Result<Option<(u64, u64, u64)>> {
+    let uv = uv_bin();
+    if which::which(&uv).is_err() {
+        return Err(Error::MissingTool { tool: uv }.into());
+    }
+    let version = parse_uv_version(&run_output(&uv, &["--version"], &env::temp_dir())?);
+    if let Some(found) = version.filter(|v| *v < MIN_UV_VERSION) {
+        let fmt = |(a, b, c): (u64, u64, u64)| format!("{a}.{b}.{c}");
+        return Err(Error::OutdatedTool {
+            tool: uv,
+            found: fmt(found),
+            required: fmt(MIN_UV_VERSION),
+        }
+        .into());
+    }
+    Ok(version)
+}
+
+/// `uv 0.8.0 (0b2357294 2025-06-02)` -> `(0, 8, 0)`.
+fn parse_uv_version(output: &str) -> Option<(u64, u64, u64)> {
+    let version = output.split_whitespace().nth(1)?;
+    let mut parts = version.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+/// Used when no Python can be found at all.
+pub(crate) const FALLBACK_PYTHON: &str = "3.11.0";
+
+/// The version of the `python3`/`python` on `PATH`, or the hardcoded default.
 pub fn detect_system_python() -> String {
+    path_python().unwrap_or_else(|| FALLBACK_PYTHON.to_string())
+}
+
+/// The version the `python3`/`python` on `PATH` reports, if there is one.
+pub(crate) fn path_python() -> Option<String> {
     let candidate = which::which("python3")
         .or_else(|_| which::which("python"))
         .ok();
@@ -40,9 +745,955 @@ pub fn detect_system_python() -> String {
         {
             let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
             if !s.is_empty() {
-                return s;
+                return Some(s);
+            }
+        }
+    }
+    None
+}
+
+/// The current user's home directory, if it can be determined.
+pub fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `program` and `args` as one line a POSIX shell would run the same way, for logs:
+/// arguments with spaces or shell metacharacters are single-quoted.
+pub fn quote_argv(program: &str, args: &[String]) -> String {
+    let quote = |arg: &str| {
+        let plain = !arg.is_empty()
+            && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+        match plain {
+            true => arg.to_string(),
+            false => format!("'{}'", arg.replace('\'', r"'\''")),
+        }
+    };
+    std::iter::once(program).chain(args.iter().map(String::as_str)).map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// `path` with a leading `~` (alone or as `~/...`) replaced by the home directory;
+/// anything else, `~user` included, is returned as given.
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return path.to_path_buf();
+    };
+    match home_dir() {
+        Some(home) if rest.as_os_str().is_empty() => home,
+        Some(home) => home.join(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+/// A path typed by the user with the shell's expansions done: a leading `~` (also `~\`)
+/// as in [`expand_tilde`], and every `$VAR` or `${VAR}` replaced by the variable's value.
+/// An unset variable is an error, not an empty string that would quietly move the path.
+pub fn expand_path(path: &Path) -> Result<PathBuf> {
+    expand_path_with(&path.to_string_lossy(), home_dir(), |key| env::var(key).ok())
+}
+
+/// [`expand_path`] with the home directory and the variable lookup passed in.
+pub fn expand_path_with(path: &str, home: Option<PathBuf>, var: impl Fn(&str) -> Option<String>) -> Result<PathBuf> {
+    let mut out = String::new();
+    let mut rest = path;
+    if let (Some(home), Some(tail)) = (&home, path.strip_prefix('~')) {
+        if tail.is_empty() || tail.starts_with(['/', '\\']) {
+            out += &home.to_string_lossy();
+            rest = tail;
+        }
+    }
+    while let Some(i) = rest.find('$') {
+        out += &rest[..i];
+        let after = &rest[i + 1..];
+        let (name, tail) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => anyhow::bail!("unclosed `${{` in {path}"),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
             }
+        };
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            // A lone `$` (or `$1`) is not a variable reference; keep it.
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        match var(name) {
+            Some(value) => out += &value,
+            None => anyhow::bail!("{path} refers to ${name}, which is not set"),
         }
+        rest = tail;
+    }
+    Ok(PathBuf::from(out + rest))
+}
+
+/// Where a directory that may not exist yet really is: `path` made absolute against the
+/// current directory, with symlinks and `..` in its existing part resolved and `.`/`..`
+/// in the rest removed. A missing parent directory is an error unless `create_parents`.
+pub fn resolve_new_dir(path: &Path, create_parents: bool) -> Result<PathBuf> {
+    use std::path::Component;
+    let mut resolved = PathBuf::new();
+    // How many trailing components of `resolved` do not exist yet.
+    let mut missing = 0;
+    for component in std::path::absolute(path)?.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if missing > 0 => {
+                resolved.pop();
+                missing -= 1;
+            }
+            component => {
+                resolved.push(component);
+                if missing > 0 {
+                    missing += 1;
+                } else if let Ok(real) = fs::canonicalize(&resolved) {
+                    resolved = real;
+                } else {
+                    missing = 1;
+                }
+            }
+        }
+    }
+    let parent = resolved.parent().unwrap_or(&resolved);
+    if missing > 1 && !create_parents {
+        anyhow::bail!(
+            "the parent directory {} does not exist; create it first, or pass --create-parents",
+            parent.display()
+        );
+    }
+    if missing <= 1 && !parent.is_dir() {
+        anyhow::bail!("{} is not a directory", parent.display());
+    }
+    Ok(resolved)
+}
+
+/// Recursively list files under `dir` as paths relative to it (sorted, `.git` skipped).
+pub fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(base: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+            let entry = entry?;
+            let path = entry.path();
+            let ty = entry.file_type()?;
+            if ty.is_dir() {
+                if entry.file_name() != ".git" {
+                    walk(base, &path, out)?;
+                }
+            } else if ty.is_file() {
+                out.push(path.strip_prefix(base)?.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut out = Vec::new();
+    walk(dir, dir, &mut out)?;
+    out.sort();
+    Ok(out)
+}
+
+/// Hex-encoded SHA-256 of `data`.
+pub fn sha256_hex(data: impl AsRef<[u8]>) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data.as_ref())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard (padded) base64 of `data`.
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
+
+/// 32 bytes from the operating system's random source as URL-safe base64 (43 characters,
+/// no padding), like Python's `secrets.token_urlsafe(32)`.
+pub fn random_secret() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("no random source to generate a secret from: {e}"))?;
+    Ok(base64_encode(&bytes)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_"))
+}
+
+/// The bytes of standard base64 `text`; whitespace is ignored, padding optional.
+pub fn base64_decode(text: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut n, mut bits) = (0u32, 0);
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let Some(v) = BASE64.iter().position(|&b| b == c) else {
+            anyhow::bail!("invalid base64 character {:?}", c as char);
+        };
+        n = n << 6 | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((n >> bits) as u8);
+            n &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+/// True when `rel` is a plain relative path that cannot escape its base directory.
+pub fn is_safe_relative(rel: &Path) -> bool {
+    use std::path::Component;
+    !rel.as_os_str().is_empty() && rel.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// `path` made absolute with symlinks and `..` resolved, as far as it exists; the
+/// missing tail is appended as given.
+pub fn canonicalize_lenient(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    for base in path.ancestors() {
+        if let Ok(canonical) = fs::canonicalize(base) {
+            return match path.strip_prefix(base) {
+                Ok(rest) if !rest.as_os_str().is_empty() => canonical.join(rest),
+                _ => canonical,
+            };
+        }
+    }
+    path
+}
+
+/// The top of the git work tree `path` is in (the nearest ancestor with a `.git`), if any.
+pub fn git_work_tree(path: &Path) -> Option<PathBuf> {
+    let path = canonicalize_lenient(path);
+    path.ancestors().find(|d| d.join(".git").exists()).map(Path::to_path_buf)
+}
+
+/// `to` relative to the directory `from`, with `..` where they part; both absolute.
+pub fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut rel = PathBuf::new();
+    for _ in common..from.len() {
+        rel.push("..");
+    }
+    for part in &to[common..] {
+        rel.push(part);
+    }
+    if rel.as_os_str().is_empty() {
+        rel.push(".");
+    }
+    rel
+}
+
+/// Per-user cache directory: `$XDG_CACHE_HOME/py-proj`, falling back to `~/.cache/py-proj`.
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home_dir().map(|h| h.join(".cache")))?;
+    Some(base.join("py-proj"))
+}
+
+/// The venv directory name uv uses unless told otherwise.
+pub const DEFAULT_VENV_DIR: &str = ".venv";
+
+/// The name of a project's venv directory: the manifest's `venv_dir` (`--venv-dir`),
+/// else [`DEFAULT_VENV_DIR`].
+pub fn venv_dir_name(root: &Path) -> String {
+    Manifest::load(root)
+        .ok()
+        .flatten()
+        .and_then(|m| m.options.venv_dir)
+        .unwrap_or_else(|| DEFAULT_VENV_DIR.to_string())
+}
+
+/// The notebooks directory a flat project starts with unless told otherwise.
+pub const DEFAULT_NOTEBOOKS_DIR: &str = "Notebooks";
+
+/// The name of a project's notebooks directory: the manifest's `notebooks_dir`
+/// (`--notebooks-dir`), else [`DEFAULT_NOTEBOOKS_DIR`].
+pub fn notebooks_dir_name(root: &Path) -> String {
+    Manifest::load(root)
+        .ok()
+        .flatten()
+        .and_then(|m| m.options.notebooks_dir)
+        .unwrap_or_else(|| DEFAULT_NOTEBOOKS_DIR.to_string())
+}
+
+/// The Python version recorded in the pyvenv.cfg of the project venv under `root`
+/// (see [`venv_dir_name`]), if there is one.
+pub fn venv_python_version(root: &Path) -> Option<String> {
+    pyvenv_python(&root.join(venv_dir_name(root)))
+}
+
+/// The Python version recorded in the pyvenv.cfg of the venv at `venv`.
+pub fn pyvenv_python(venv: &Path) -> Option<String> {
+    parse_pyvenv_cfg(&fs::read_to_string(venv.join("pyvenv.cfg")).ok()?)
+}
+
+/// The `X.Y.Z` Python version in a pyvenv.cfg. uv writes `version_info = 3.12.4`, the
+/// stdlib `venv` `version = 3.12.4` (older ones `version_info` too), and virtualenv
+/// `version_info = 3.12.4.final.0`; keys are matched case-insensitively.
+pub fn parse_pyvenv_cfg(text: &str) -> Option<String> {
+    let value = |wanted: &str| {
+        text.lines().find_map(|l| {
+            let (key, value) = l.split_once('=')?;
+            key.trim().eq_ignore_ascii_case(wanted).then(|| value.trim())
+        })
+    };
+    let raw = value("version_info").or_else(|| value("version"))?;
+    let parts: Vec<&str> = raw
+        .split('.')
+        .take(3)
+        .take_while(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    (parts.len() >= 2).then(|| parts.join("."))
+}
+
+/// Whether a venv's Python `found` serves `wanted`: the same version, or the same
+/// minor one when either side has no patch level or they differ only in it.
+pub fn venv_python_matches(found: &str, wanted: &PythonVersion) -> bool {
+    found.parse::<PythonVersion>().is_ok_and(|found| found.mm() == wanted.mm())
+}
+
+/// The project `dir` belongs to: the nearest of `dir` and its parents with a py-proj
+/// manifest or a `pyproject.toml`.
+pub fn find_project_root(dir: &Path) -> Option<PathBuf> {
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .find(|d| d.join(MANIFEST_FILE).is_file() || d.join("pyproject.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// The venv a project runs in: its own (see [`venv_dir_name`]), or for a workspace
+/// member the workspace root's. `None` when there is none yet.
+pub fn project_venv(root: &Path) -> Result<Option<PathBuf>> {
+    let own = root.join(venv_dir_name(root));
+    if own.is_dir() {
+        return Ok(Some(own));
+    }
+    Ok(workspace::find_root(root)?.map(|ws| ws.join(venv_dir_name(&ws))).filter(|v| v.is_dir()))
+}
+
+/// `<dir>/pyproject.toml`, parsed; `None` when it is missing or not valid TOML.
+pub fn read_pyproject(dir: &Path) -> Option<toml::Table> {
+    toml::from_str(&fs::read_to_string(dir.join("pyproject.toml")).ok()?).ok()
+}
+
+/// Top-level standard-library modules (`sys.stdlib_module_names`, private ones left out),
+/// including those removed since 3.8 that older Pythons still ship.
+const STDLIB_MODULES: &[&str] = &[
+    "abc", "aifc", "antigravity", "argparse", "array", "ast", "asynchat", "asyncio", "asyncore",
+    "atexit", "audioop", "base64", "bdb", "binascii", "bisect", "builtins", "bz2", "cProfile",
+    "calendar", "cgi", "cgitb", "chunk", "cmath", "cmd", "code", "codecs", "codeop", "collections",
+    "colorsys", "compileall", "concurrent", "configparser", "contextlib", "contextvars", "copy",
+    "copyreg", "crypt", "csv", "ctypes", "curses", "dataclasses", "datetime", "dbm", "decimal",
+    "difflib", "dis", "distutils", "doctest", "email", "encodings", "ensurepip", "enum", "errno",
+    "faulthandler", "fcntl", "filecmp", "fileinput", "fnmatch", "fractions", "ftplib", "functools",
+    "gc", "genericpath", "getopt", "getpass", "gettext", "glob", "graphlib", "grp", "gzip",
+    "hashlib", "heapq", "hmac", "html", "http", "idlelib", "imaplib", "imghdr", "imp", "importlib",
+    "inspect", "io", "ipaddress", "itertools", "json", "keyword", "lib2to3", "linecache", "locale",
+    "logging", "lzma", "mailbox", "mailcap", "marshal", "math", "mimetypes", "mmap",
+    "modulefinder", "msilib", "msvcrt", "multiprocessing", "netrc", "nis", "nntplib", "nt",
+    "ntpath", "nturl2path", "numbers", "opcode", "operator", "optparse", "os", "ossaudiodev",
+    "pathlib", "pdb", "pickle", "pickletools", "pipes", "pkgutil", "platform", "plistlib",
+    "poplib", "posix", "posixpath", "pprint", "profile", "pstats", "pty", "pwd", "py_compile",
+    "pyclbr", "pydoc", "pydoc_data", "pyexpat", "queue", "quopri", "random", "re", "readline",
+    "reprlib", "resource", "rlcompleter", "runpy", "sched", "secrets", "select", "selectors",
+    "shelve", "shlex", "shutil", "signal", "site", "smtpd", "smtplib", "sndhdr", "socket",
+    "socketserver", "spwd", "sqlite3", "sre_compile", "sre_constants", "sre_parse", "ssl", "stat",
+    "statistics", "string", "stringprep", "struct", "subprocess", "sunau", "symtable", "sys",
+    "sysconfig", "syslog", "tabnanny", "tarfile", "telnetlib", "tempfile", "termios", "textwrap",
+    "this", "threading", "time", "timeit", "tkinter", "token", "tokenize", "tomllib", "trace",
+    "traceback", "tracemalloc", "tty", "turtle", "turtledemo", "types", "typing", "unicodedata",
+    "unittest", "urllib", "uu", "uuid", "venv", "warnings", "wave", "weakref", "webbrowser",
+    "winreg", "winsound", "wsgiref", "xdrlib", "xml", "xmlrpc", "zipapp", "zipfile", "zipimport",
+    "zlib", "zoneinfo",
+];
+
+/// Import names of widely used PyPI packages, lowercased as [`import_name`] spells them.
+const POPULAR_PACKAGES: &[&str] = &[
+    "aiohttp", "attr", "attrs", "black", "boto3", "botocore", "bs4", "celery", "certifi",
+    "click", "cryptography", "dateutil", "django", "fastapi", "flask", "httpx", "idna", "jax",
+    "jinja2", "keras", "lxml", "matplotlib", "mypy", "numba", "numpy", "openai", "packaging",
+    "pandas", "pil", "pip", "polars", "pyarrow", "pydantic", "pytest", "pytz", "redis",
+    "requests", "rich", "ruff", "scipy", "seaborn", "setuptools", "six", "sklearn",
+    "sqlalchemy", "streamlit", "tensorflow", "torch", "tqdm", "transformers", "typer",
+    "urllib3", "uvicorn", "wheel", "yaml",
+];
+
+/// Make a name usable as a project name: characters other than ASCII letters, digits,
+/// `-`, `_`, and `.` become `-`, and separators are trimmed from both ends. Returns the
+/// cleaned name and, when it differs, a note saying what was changed.
+pub fn sanitize_project_name(raw: &str) -> Result<(String, Option<String>)> {
+    let mut replaced: Vec<char> = Vec::new();
+    let mut name = String::new();
+    // A replacement next to a real separator folds into it: `Stuff!_proj` -> `Stuff_proj`.
+    let mut last_replaced = false;
+    for c in raw.chars() {
+        let separator = "-_.".contains(c);
+        if c.is_ascii_alphanumeric() || separator {
+            if separator && last_replaced {
+                name.pop();
+            }
+            name.push(c);
+            last_replaced = false;
+            continue;
+        }
+        if !replaced.contains(&c) {
+            replaced.push(c);
+        }
+        if !name.ends_with(['-', '_', '.']) {
+            name.push('-');
+            last_replaced = true;
+        }
+    }
+    let untrimmed_len = name.len();
+    let name = name.trim_matches(['-', '_', '.']).to_string();
+    if name.is_empty() {
+        return Err(Error::InvalidProjectName {
+            name: raw.to_string(),
+            reason: "nothing is left after dropping characters other than letters, digits, `-`, `_`, `.`",
+        }
+        .into());
+    }
+    if name == raw {
+        return Ok((name, None));
+    }
+    let mut changes = Vec::new();
+    if !replaced.is_empty() {
+        let chars: Vec<String> = replaced.iter().map(|c| format!("`{c}`")).collect();
+        changes.push(format!("replaced {} with `-`", chars.join(", ")));
+    }
+    if name.len() != untrimmed_len {
+        changes.push("trimmed separators at the ends".to_string());
+    }
+    Ok((name, Some(changes.join("; "))))
+}
+
+/// Reject project names that would break pyproject.toml, the import name, or the
+/// generated paths.
+pub fn validate_project_name(name: &str) -> Result<()> {
+    let invalid = |reason| Err(Error::InvalidProjectName { name: name.to_string(), reason }.into());
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && name.chars().last().is_some_and(|c| c.is_ascii_alphanumeric())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    if !valid {
+        return invalid("use letters, digits, `-`, `_`, `.`; start and end with a letter or digit");
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return invalid("it must start with a letter so the import name is valid Python");
+    }
+    // Import names are lowercased, so `False` is refused along with `false`.
+    let module = import_name(name);
+    if KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(&module)) {
+        return invalid("the import name would be a Python keyword");
+    }
+    Ok(())
+}
+
+/// Python keywords as spelled, which cannot be used as module names.
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+    "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try",
+    "while", "with", "yield",
+];
+
+/// Split a dotted module path, checking each part is a Python identifier.
+pub fn validate_module(module: &str) -> Result<Vec<&str>> {
+    let parts: Vec<&str> = module.split('.').collect();
+    for part in &parts {
+        let ident = part.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !ident || KEYWORDS.contains(part) {
+            anyhow::bail!("invalid module path `{module}`: `{part}` is not a valid Python identifier");
+        }
+    }
+    Ok(parts)
+}
+
+/// `*` matches any run of characters and `?` any one; everything else is literal.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    // Classic backtracking over the last `*`.
+    let (mut pi, mut ni, mut star, mut mark) = (0, 0, None, 0);
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some(pi);
+            mark = ni;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            ni = mark;
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Whether the relative path `rel` matches the glob `pattern`, segment by segment
+/// (`**` spans any number of directories). A pattern naming a directory matches
+/// everything below it.
+pub fn glob_matches(pattern: &str, rel: &str) -> bool {
+    fn go(p: &[&str], n: &[&str]) -> bool {
+        match (p.split_first(), n.split_first()) {
+            (None, _) => true,
+            (Some((&"**", rest)), _) => go(rest, n) || (!n.is_empty() && go(p, &n[1..])),
+            (Some((seg, rest)), Some((name, names))) => wildcard_match(seg, name) && go(rest, names),
+            (Some(_), None) => false,
+        }
+    }
+    let pattern: Vec<&str> = pattern.trim_end_matches('/').split('/').filter(|s| !s.is_empty() && *s != ".").collect();
+    let rel: Vec<&str> = rel.split('/').collect();
+    !pattern.is_empty() && go(&pattern, &rel)
+}
+
+/// What a project's import name collides with (see [`name_clash`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameClash {
+    /// A standard-library module: inside the project, `import <name>` may get either one.
+    Stdlib,
+    /// A popular PyPI package: installing it next to the project shadows one or the other.
+    Pypi,
+}
+
+impl NameClash {
+    /// `stdlib` or `PyPI`, as warnings name the kind of collision.
+    pub fn name(self) -> &'static str {
+        match self {
+            NameClash::Stdlib => "stdlib",
+            NameClash::Pypi => "PyPI",
+        }
+    }
+}
+
+/// Whether the project's import name is also a standard-library module or a popular PyPI
+/// package's. Only exact matches count: `dataclasses_proj` clashes with nothing.
+pub fn name_clash(project: &str) -> Option<NameClash> {
+    let name = import_name(project);
+    if STDLIB_MODULES.iter().any(|m| m.eq_ignore_ascii_case(&name)) {
+        Some(NameClash::Stdlib)
+    } else if POPULAR_PACKAGES.contains(&name.as_str()) {
+        Some(NameClash::Pypi)
+    } else {
+        None
+    }
+}
+
+/// The PEP 503 normalized distribution name: `Acme_ML.tools` -> `acme-ml-tools`.
+pub fn dist_name(project: &str) -> String {
+    normalized(project, '-')
+}
+
+/// The Python import name for a project name: `Acme-ML.tools` -> `acme_ml_tools`.
+pub fn import_name(project: &str) -> String {
+    normalized(project, '_')
+}
+
+/// Lowercase, with each run of `-`, `_`, `.` replaced by `sep`.
+fn normalized(project: &str, sep: char) -> String {
+    let mut out = String::new();
+    for c in project.chars() {
+        if "-_.".contains(c) {
+            if !out.ends_with(sep) {
+                out.push(sep);
+            }
+        } else {
+            out.push(c.to_ascii_lowercase());
+        }
+    }
+    out
+}
+
+/// Remove `path` and everything below it, continuing past failures, and return each
+/// path that could not be removed. Symlinks are unlinked, never followed, so a link to
+/// an external data directory leaves that directory alone. On Windows read-only
+/// entries are made writable first. A missing `path` is not a failure.
+pub fn remove_tree(path: &Path) -> Vec<(PathBuf, std::io::Error)> {
+    let mut failed = Vec::new();
+    remove_tree_into(path, &mut failed);
+    failed
+}
+
+fn remove_tree_into(path: &Path, failed: &mut Vec<(PathBuf, std::io::Error)>) {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => return failed.push((path.to_path_buf(), e)),
+    };
+    #[cfg(windows)]
+    if meta.permissions().readonly() {
+        let mut perms = meta.permissions();
+        perms.set_readonly(false);
+        let _ = fs::set_permissions(path, perms);
+    }
+    let removed = if meta.is_dir() {
+        let before = failed.len();
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries {
+                    match entry {
+                        Ok(entry) => remove_tree_into(&entry.path(), failed),
+                        Err(e) => failed.push((path.to_path_buf(), e)),
+                    }
+                }
+            }
+            Err(e) => return failed.push((path.to_path_buf(), e)),
+        }
+        // A child that stayed behind already explains why this is not empty.
+        if failed.len() > before {
+            return;
+        }
+        fs::remove_dir(path)
+    } else {
+        remove_link_or_file(path, &meta)
+    };
+    if let Err(e) = removed {
+        failed.push((path.to_path_buf(), e));
+    }
+}
+
+/// `symlink_metadata` never reports a link as a directory, but Windows removes
+/// directory symlinks and junctions with `remove_dir`.
+fn remove_link_or_file(path: &Path, meta: &fs::Metadata) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::FileTypeExt;
+        if meta.file_type().is_symlink_dir() {
+            return fs::remove_dir(path);
+        }
+    }
+    let _ = meta;
+    fs::remove_file(path)
+}
+
+/// Bytes available to this user on the filesystem that holds `path`, or would once it
+/// is created (its nearest existing ancestor). `None` where that cannot be read, or
+/// off Unix.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let existing = canonicalize_lenient(path).ancestors().find(|p| p.exists())?.to_path_buf();
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: a NUL-terminated path and a statvfs the call fills in.
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        #[allow(clippy::unnecessary_cast, clippy::useless_conversion)]
+        Some(u64::from(stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = existing;
+        None
+    }
+}
+
+/// Total size of the files under `path`, without following symlinks (0 if missing).
+pub fn dir_size(path: &Path) -> u64 {
+    measure(path).bytes
+}
+
+/// The size of a file or directory tree, and how much of it could not be read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Size {
+    pub bytes: u64,
+    /// Files (and symlinks) counted in `bytes`.
+    pub files: u64,
+    /// Entries whose metadata or listing failed (e.g. permission denied); their
+    /// contents are not counted.
+    pub unreadable: usize,
+}
+
+impl std::ops::AddAssign for Size {
+    fn add_assign(&mut self, other: Size) {
+        self.bytes += other.bytes;
+        self.files += other.files;
+        self.unreadable += other.unreadable;
+    }
+}
+
+/// Like [`dir_size`], but counting what could not be read instead of ignoring it.
+/// A missing `path` is 0 bytes, not an error.
+pub fn measure(path: &Path) -> Size {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Size::default(),
+        Err(_) => return Size { unreadable: 1, ..Size::default() },
+    };
+    if !meta.is_dir() {
+        return Size { bytes: meta.len(), files: 1, unreadable: 0 };
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return Size { unreadable: 1, ..Size::default() };
+    };
+    let mut size = Size::default();
+    for entry in entries {
+        match entry {
+            Ok(entry) => size += measure(&entry.path()),
+            Err(_) => size.unreadable += 1,
+        }
+    }
+    size
+}
+
+/// The Levenshtein distance between `a` and `b`, in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// `1536` -> `1.5 KiB`.
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The names in `dir`, sorted.
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> =
+            fs::read_dir(dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn write_with_creates_updates_and_leaves_same_content_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/a.txt");
+        assert_eq!(write_with(&path, "one", WritePolicy::Overwrite).unwrap(), WriteOutcome::Created);
+        assert_eq!(write_with(&path, "one", WritePolicy::Overwrite).unwrap(), WriteOutcome::Unchanged);
+        assert_eq!(write_with(&path, "two", WritePolicy::Overwrite).unwrap(), WriteOutcome::Updated);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+        assert_eq!(names(path.parent().unwrap()), ["a.txt"]);
+    }
+
+    #[test]
+    fn skip_policy_keeps_a_differing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "mine").unwrap();
+        assert_eq!(write_with(&path, "theirs", WritePolicy::Skip).unwrap(), WriteOutcome::Skipped);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "mine");
+        let new = dir.path().join("b.txt");
+        assert_eq!(write_with(&new, "theirs", WritePolicy::Skip).unwrap(), WriteOutcome::Created);
+    }
+
+    #[test]
+    fn backup_policy_saves_the_previous_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "mine").unwrap();
+        let bak = dir.path().join("a.txt.bak");
+        assert_eq!(write_with(&path, "theirs", WritePolicy::Backup).unwrap(), WriteOutcome::BackedUp(bak.clone()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "theirs");
+        assert_eq!(fs::read_to_string(&bak).unwrap(), "mine");
+        // Nothing to back up when the content is already there.
+        assert_eq!(write_with(&path, "theirs", WritePolicy::Backup).unwrap(), WriteOutcome::Unchanged);
+        assert_eq!(fs::read_to_string(&bak).unwrap(), "mine");
+    }
+
+    #[test]
+    fn failed_write_leaves_no_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // The rename over a non-empty directory fails after the temp file is written.
+        let path = dir.path().join("taken");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("inside"), "x").unwrap();
+        let err = write(&path, "contents").unwrap_err();
+        assert!(format!("{err:#}").contains("taken"), "{err:#}");
+        assert_eq!(names(dir.path()), ["taken"]);
+        assert_eq!(names(&path), ["inside"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaced_file_keeps_its_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+        write(&path, "new").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o750);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_command_error_carries_its_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = "echo kept on stdout; echo first problem >&2; echo second problem >&2; exit 3";
+        let err = run_captured("sh", &["-c", script], dir.path()).unwrap_err();
+        let Some(Error::CommandFailed { program, status, stderr }) = Error::find(&err) else {
+            panic!("not CommandFailed: {err:#}");
+        };
+        assert_eq!(program, "sh");
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(stderr, "first problem\nsecond problem");
+        assert!(format!("{err:#}").contains("second problem"), "{err:#}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn captured_output_is_returned_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let captured = run_captured("sh", &["-c", "echo out; echo err >&2"], dir.path()).unwrap();
+        assert_eq!(captured.stdout, "out\n");
+        assert_eq!(captured.stderr, "err\n");
+        // More than a pipe buffer on both streams at once must not deadlock.
+        let big = "i=0; while [ $i -lt 20000 ]; do echo xxxxxxxxxxxxxxxxxxxxxxxx; echo yyyyyyyyyyyyyyyyyyyyyyyy >&2; i=$((i+1)); done";
+        let captured = run_captured("sh", &["-c", big], dir.path()).unwrap();
+        assert_eq!(captured.stdout.lines().count(), 20000);
+        assert_eq!(captured.stderr.lines().count(), 20000);
+    }
+
+    fn expand(path: &str, home: &str) -> Result<PathBuf> {
+        let vars = |key: &str| match key {
+            "PROJECTS" => Some("/srv/projects".to_string()),
+            "USERPROFILE" => Some(r"C:\Users\me".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        expand_path_with(path, Some(PathBuf::from(home)), vars)
+    }
+
+    #[test]
+    fn expands_a_leading_tilde_and_variables_in_unix_paths() {
+        let ok = |path: &str| expand(path, "/home/me").unwrap();
+        assert_eq!(ok("~"), Path::new("/home/me"));
+        assert_eq!(ok("~/work/new"), Path::new("/home/me/work/new"));
+        assert_eq!(ok("$PROJECTS/foo"), Path::new("/srv/projects/foo"));
+        assert_eq!(ok("${PROJECTS}_old/foo"), Path::new("/srv/projects_old/foo"));
+        assert_eq!(ok("a$EMPTY/b"), Path::new("a/b"));
+        // Only a leading `~` (or `~/`) is the home directory; `$` without a name stays.
+        assert_eq!(ok("~other/x"), Path::new("~other/x"));
+        assert_eq!(ok("a/~/b"), Path::new("a/~/b"));
+        assert_eq!(ok("cost$/$1"), Path::new("cost$/$1"));
+        assert_eq!(ok("relative/dir"), Path::new("relative/dir"));
+    }
+
+    #[test]
+    fn expands_windows_style_paths() {
+        let ok = |path: &str| expand(path, r"C:\Users\me").unwrap().to_string_lossy().into_owned();
+        assert_eq!(ok(r"~\work\new"), r"C:\Users\me\work\new");
+        assert_eq!(ok(r"${USERPROFILE}\work"), r"C:\Users\me\work");
+        assert_eq!(ok(r"$USERPROFILE\work"), r"C:\Users\me\work");
+        assert_eq!(ok(r"D:\data\~\x"), r"D:\data\~\x");
+    }
+
+    #[test]
+    fn unset_or_unclosed_variables_are_errors() {
+        let err = |path: &str| expand(path, "/home/me").unwrap_err().to_string();
+        assert_eq!(err("$NOPE/foo"), "$NOPE/foo refers to $NOPE, which is not set");
+        assert_eq!(err("${PROJECTS/foo"), "unclosed `${` in ${PROJECTS/foo");
+    }
+
+    #[test]
+    fn new_dir_resolves_against_existing_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(base.join("a")).unwrap();
+        let resolved = resolve_new_dir(&base.join("a/./../a/new"), false).unwrap();
+        assert_eq!(resolved, base.join("a/new"));
+        let err = resolve_new_dir(&base.join("missing/new"), false).unwrap_err().to_string();
+        assert!(err.contains("pass --create-parents"), "{err}");
+        assert_eq!(resolve_new_dir(&base.join("missing/new"), true).unwrap(), base.join("missing/new"));
+        assert!(!base.join("missing").exists());
+    }
+
+    #[test]
+    fn name_clash_matches_only_exact_import_names() {
+        assert_eq!(name_clash("json"), Some(NameClash::Stdlib));
+        assert_eq!(name_clash("Dataclasses"), Some(NameClash::Stdlib));
+        assert_eq!(name_clash("requests"), Some(NameClash::Pypi));
+        assert_eq!(name_clash("NumPy"), Some(NameClash::Pypi));
+        // Near misses: a suffix, a prefix, or a private stdlib module.
+        for name in ["dataclasses_proj", "json-tools", "my-requests", "numpy2", "_thread", "demo"] {
+            assert_eq!(name_clash(name), None, "{name}");
+        }
+        assert_eq!((NameClash::Stdlib.name(), NameClash::Pypi.name()), ("stdlib", "PyPI"));
+    }
+
+    #[test]
+    fn only_scripts_get_an_executable_mode() {
+        for rel in [".envrc", "scripts/bootstrap.sh", r"scripts\setup.ps1", ".githooks/pre-commit"] {
+            assert_eq!(file_mode(rel), Some(0o755), "{rel}");
+        }
+        for rel in ["pyproject.toml", "src/scripts.py", "docs/scripts/x.md", ".envrc.bak"] {
+            assert_eq!(file_mode(rel), None, "{rel}");
+        }
+        assert_eq!(generated_mode(".envrc", Some(0o664)), Some(0o775));
+        assert_eq!(generated_mode(".envrc", Some(0o600)), Some(0o700));
+        assert_eq!(generated_mode("README.md", Some(0o664)), Some(0o664));
+        assert_eq!(generated_mode("README.md", None), None);
+    }
+
+    #[test]
+    fn modes_parse_as_octal() {
+        assert_eq!(parse_mode("775"), Ok(0o775));
+        assert_eq!(parse_mode("0o2775"), Ok(0o2775));
+        assert_eq!(parse_mode("0755"), Ok(0o755));
+        for bad in ["", "0o", "789", "17777", "rwx"] {
+            assert!(parse_mode(bad).is_err(), "{bad}");
+        }
+        assert_eq!(format_mode(0o755), "755");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_with_mode_sets_the_bits_through_the_rename() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        write_with_mode(&path, "#!/bin/sh\n", WritePolicy::Overwrite, Some(0o755)).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o755);
+        assert_eq!(names(dir.path()), ["run.sh"]);
     }
-    "3.11.0".to_string()
 }
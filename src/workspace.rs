@@ -0,0 +1,191 @@
+//! uv workspace support: find or create the workspace root and keep its
+//! `[tool.uv.workspace] members` list and VS Code settings in step with members.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Table};
+
+use crate::templates::{vscode_settings_json, workspace_root_pyproject_toml};
+use crate::util::{wildcard_match, write, DEFAULT_VENV_DIR};
+
+/// The nearest ancestor of `member` (not `member` itself) that is a uv workspace root.
+pub fn find_root(member: &Path) -> Result<Option<PathBuf>> {
+    let member = std::path::absolute(member)?;
+    for dir in member.ancestors().skip(1) {
+        if is_root(dir) {
+            return Ok(Some(dir.to_path_buf()));
+        }
+    }
+    Ok(None)
+}
+
+/// True when `dir/pyproject.toml` declares `[tool.uv.workspace]`.
+pub fn is_root(dir: &Path) -> bool {
+    read(dir).is_some_and(|doc| workspace_table(&doc).is_some())
+}
+
+/// Members listed by the workspace root at `dir` (empty if it is not one).
+pub fn members(dir: &Path) -> Vec<String> {
+    read(dir)
+        .and_then(|doc| {
+            let members = workspace_table(&doc)?.get("members")?.as_array()?;
+            Some(members.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        })
+        .unwrap_or_default()
+}
+
+/// The directories of the members listed by the workspace root at `dir`, with a
+/// wildcard in the last component (`packages/*`) expanded to the matching projects.
+pub fn member_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for member in members(dir) {
+        let member = member.trim_end_matches('/');
+        let (parent, last) = member.rsplit_once('/').unwrap_or(("", member));
+        if !last.contains(['*', '?']) {
+            dirs.push(dir.join(member));
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(dir.join(parent)) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join("pyproject.toml").is_file())
+            .filter(|p| wildcard_match(last, &p.file_name().unwrap_or_default().to_string_lossy()))
+            .collect();
+        found.sort();
+        dirs.extend(found);
+    }
+    dirs.dedup();
+    dirs
+}
+
+/// `member` relative to `root`, with forward slashes, as written in `members`.
+pub fn member_path(root: &Path, member: &Path) -> Result<String> {
+    let member = std::path::absolute(member)?;
+    let rel = member.strip_prefix(root).with_context(|| {
+        format!("{} is not inside the workspace {}", member.display(), root.display())
+    })?;
+    Ok(rel.to_string_lossy().replace('\\', "/"))
+}
+
+/// Add `[tool.uv.workspace]` to `dir/pyproject.toml`, creating the file if needed.
+/// Returns true when the file was created rather than edited.
+pub fn init(dir: &Path) -> Result<bool> {
+    let path = dir.join("pyproject.toml");
+    if !path.exists() {
+        write(&path, workspace_root_pyproject_toml())?;
+        return Ok(true);
+    }
+    let mut doc = parse(&path)?;
+    let tool = doc
+        .entry("tool")
+        .or_insert_with(|| Item::Table(implicit_table()))
+        .as_table_mut()
+        .context("`tool` in pyproject.toml is not a table")?;
+    let uv = tool
+        .entry("uv")
+        .or_insert_with(|| Item::Table(implicit_table()))
+        .as_table_mut()
+        .context("`tool.uv` in pyproject.toml is not a table")?;
+    let mut ws = Table::new();
+    ws.insert("members", toml_edit::value(Array::new()));
+    uv.insert("workspace", Item::Table(ws));
+    write(&path, doc.to_string())?;
+    Ok(false)
+}
+
+/// Add `member` to the root's `members` array; false if it was already listed.
+pub fn add_member(root: &Path, member: &str) -> Result<bool> {
+    edit_members(root, |members| {
+        if members.iter().any(|v| v.as_str() == Some(member)) {
+            return false;
+        }
+        members.push(member);
+        true
+    })
+}
+
+/// Drop `member` from the root's `members` array; false if it was not listed.
+pub fn remove_member(root: &Path, member: &str) -> Result<bool> {
+    edit_members(root, |members| {
+        let before = members.len();
+        members.retain(|v| v.as_str() != Some(member));
+        members.len() != before
+    })
+}
+
+fn edit_members(root: &Path, edit: impl FnOnce(&mut Array) -> bool) -> Result<bool> {
+    let path = root.join("pyproject.toml");
+    let mut doc = parse(&path)?;
+    let ws = doc
+        .get_mut("tool")
+        .and_then(|t| t.get_mut("uv"))
+        .and_then(|u| u.get_mut("workspace"))
+        .and_then(Item::as_table_like_mut)
+        .with_context(|| format!("{} has no [tool.uv.workspace]", path.display()))?;
+    if ws.get("members").is_none() {
+        ws.insert("members", toml_edit::value(Array::new()));
+    }
+    let members = ws
+        .get_mut("members")
+        .and_then(Item::as_array_mut)
+        .context("`tool.uv.workspace.members` is not an array")?;
+    if !edit(members) {
+        return Ok(false);
+    }
+    write(&path, doc.to_string())?;
+    Ok(true)
+}
+
+/// Point the root VS Code settings at `member/src` so imports resolve in one window.
+/// Returns false when the path was already there.
+pub fn add_vscode_paths(root: &Path, member: &str) -> Result<bool> {
+    let path = root.join(".vscode").join("settings.json");
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        // The workspace's venv is uv's default one at the root.
+        Err(_) => vscode_settings_json().replace("{{venv_dir}}", DEFAULT_VENV_DIR),
+    };
+    let mut settings: serde_json::Value = serde_json::from_str(&text)
+        .with_context(|| format!("invalid JSON in {}", path.display()))?;
+    let Some(obj) = settings.as_object_mut() else {
+        bail!("{} is not a JSON object", path.display());
+    };
+    let extra = obj
+        .entry("python.analysis.extraPaths")
+        .or_insert_with(|| serde_json::json!([]));
+    let Some(extra) = extra.as_array_mut() else {
+        bail!("python.analysis.extraPaths in {} is not an array", path.display());
+    };
+    let entry = format!("${{workspaceFolder}}/{member}/src");
+    if extra.iter().any(|v| v.as_str() == Some(entry.as_str())) {
+        return Ok(false);
+    }
+    extra.push(entry.into());
+    write(&path, serde_json::to_string_pretty(&settings)?)?;
+    Ok(true)
+}
+
+fn read(dir: &Path) -> Option<DocumentMut> {
+    fs::read_to_string(dir.join("pyproject.toml")).ok()?.parse().ok()
+}
+
+fn parse(path: &Path) -> Result<DocumentMut> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    text.parse()
+        .with_context(|| format!("invalid TOML in {}", path.display()))
+}
+
+fn workspace_table(doc: &DocumentMut) -> Option<&dyn toml_edit::TableLike> {
+    doc.get("tool")?.get("uv")?.get("workspace")?.as_table_like()
+}
+
+fn implicit_table() -> Table {
+    let mut t = Table::new();
+    t.set_implicit(true);
+    t
+}
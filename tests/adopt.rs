@@ -0,0 +1,23 @@
+//! `py-proj adopt` adds the tooling to an existing project without touching what the
+//! project already has.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+
+#[test]
+fn adopt_keeps_an_existing_env_and_describes_it_in_the_example() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.join("legacy");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("pyproject.toml"), "[project]\nname = \"legacy\"\nversion = \"0.1.0\"\n").unwrap();
+    let env = "API_TOKEN=hunter2\n# the database\nDB_URL=postgres://localhost/legacy\n";
+    fs::write(root.join(".env"), env).unwrap();
+
+    sandbox.cmd().args(["adopt", "legacy", "--skip-uv"]).assert().success();
+    assert_eq!(fs::read_to_string(root.join(".env")).unwrap(), env);
+    let example = fs::read_to_string(root.join(".env.example")).unwrap();
+    assert!(example.ends_with("API_TOKEN=\n# the database\nDB_URL=\n"), "{example}");
+    assert!(!example.contains("hunter2"));
+}
@@ -0,0 +1,151 @@
+//! `--emit-plan` and `apply-plan`: an applied plan gives the tree a direct create
+//! would, and a plan is refused when it reaches outside the project root, comes from
+//! another py-proj release, or its target changed since it was made.
+
+mod common;
+
+use common::{tree, Sandbox};
+use serde_json::{json, Value};
+use std::fs;
+
+/// Emit a deterministic plan for `demo` to plan.json and change it with `edit`.
+fn emit(sandbox: &Sandbox, edit: impl FnOnce(&mut Value)) {
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo"])
+        .args(["--emit-plan", "plan.json", "--deterministic"])
+        .assert()
+        .success();
+    let path = sandbox.join("plan.json");
+    let mut plan: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    edit(&mut plan);
+    fs::write(&path, serde_json::to_string(&plan).unwrap()).unwrap();
+}
+
+/// Emit a plan for `demo`, change its steps with `edit`, and apply it.
+fn apply_edited(sandbox: &Sandbox, edit: impl FnOnce(&mut Vec<Value>)) -> assert_cmd::assert::Assert {
+    emit(sandbox, |plan| edit(plan["actions"].as_array_mut().unwrap()));
+    sandbox.cmd().args(["apply-plan", "plan.json", "--deterministic"]).assert()
+}
+
+#[test]
+fn traversal_path_is_refused() {
+    let sandbox = Sandbox::new();
+    apply_edited(&sandbox, |actions| {
+        let write = actions.iter_mut().find(|a| a["action"] == "write_file").unwrap();
+        write["path"] = json!("../ESCAPED.txt");
+    })
+    .failure()
+    .stderr(predicates::str::contains("refusing plan path outside the project: ../ESCAPED.txt"));
+    assert!(!sandbox.join("ESCAPED.txt").exists());
+    assert!(!sandbox.join("demo").exists(), "nothing is written before the check");
+}
+
+#[test]
+fn absolute_remove_dir_is_refused() {
+    let sandbox = Sandbox::new();
+    let victim = sandbox.join("victim");
+    fs::create_dir(&victim).unwrap();
+    fs::write(victim.join("keep.txt"), "keep").unwrap();
+    apply_edited(&sandbox, |actions| {
+        actions.insert(0, json!({ "action": "remove_dir", "path": victim }));
+    })
+    .failure()
+    .stderr(predicates::str::contains("refusing plan path outside the project"));
+    assert!(victim.join("keep.txt").exists());
+}
+
+/// A `write_code_workspace` step with an empty file at `path`.
+fn code_workspace(path: &str) -> Value {
+    json!({
+        "action": "write_code_workspace",
+        "path": path,
+        "contents": { "encoding": "utf8", "data": "", "sha256": EMPTY_SHA256 },
+        "existing_sha256": null,
+    })
+}
+
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+#[test]
+fn code_workspace_may_sit_above_the_root() {
+    let sandbox = Sandbox::new();
+    apply_edited(&sandbox, |actions| actions.push(code_workspace("../demo.code-workspace"))).success();
+    assert!(sandbox.join("demo.code-workspace").is_file());
+}
+
+#[test]
+fn code_workspace_above_the_root_must_be_one() {
+    let sandbox = Sandbox::new();
+    apply_edited(&sandbox, |actions| actions.push(code_workspace("../../escaped.txt")))
+        .failure()
+        .stderr(predicates::str::contains("refusing plan path outside the project: ../../escaped.txt"));
+}
+
+#[test]
+fn unedited_plan_applies() {
+    let sandbox = Sandbox::new();
+    apply_edited(&sandbox, |_| {}).success();
+    assert!(sandbox.join("demo/pyproject.toml").is_file());
+}
+
+#[test]
+fn an_applied_plan_matches_a_direct_create() {
+    let planned = Sandbox::new();
+    emit(&planned, |_| {});
+    assert!(!planned.join("demo").exists(), "emitting a plan writes nothing");
+    planned.stdout(&["apply-plan", "plan.json", "--deterministic"]);
+
+    let direct = Sandbox::new();
+    direct.create("demo", &["--deterministic"]);
+    let (applied, created) = (tree(&planned.join("demo")), tree(&direct.join("demo")));
+    assert_eq!(applied.keys().collect::<Vec<_>>(), created.keys().collect::<Vec<_>>());
+    for (rel, contents) in &applied {
+        assert!(created[rel] == *contents, "{rel} differs from a direct create");
+    }
+}
+
+#[test]
+fn a_plan_applies_under_another_root() {
+    let sandbox = Sandbox::new();
+    emit(&sandbox, |_| {});
+    sandbox.stdout(&["apply-plan", "plan.json", "--root", "elsewhere"]);
+    assert!(sandbox.join("elsewhere/pyproject.toml").is_file());
+    assert!(!sandbox.join("demo").exists());
+}
+
+#[test]
+fn a_plan_from_another_release_or_schema_is_refused() {
+    let sandbox = Sandbox::new();
+    emit(&sandbox, |plan| plan["py_proj_version"] = json!("0.2.0"));
+    sandbox
+        .cmd()
+        .args(["apply-plan", "plan.json"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("was planned by py-proj 0.2.0"));
+
+    emit(&sandbox, |plan| plan["schema"] = json!(99));
+    sandbox
+        .cmd()
+        .args(["apply-plan", "plan.json"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("uses plan schema 99"));
+    assert!(!sandbox.join("demo").exists());
+}
+
+#[test]
+fn a_target_changed_since_planning_is_refused() {
+    let sandbox = Sandbox::new();
+    emit(&sandbox, |_| {});
+    fs::create_dir(sandbox.join("demo")).unwrap();
+    fs::write(sandbox.join("demo/Makefile"), "all:\n").unwrap();
+    sandbox
+        .cmd()
+        .args(["apply-plan", "plan.json"])
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains("it changed since the plan was made"));
+    assert_eq!(fs::read_to_string(sandbox.join("demo/Makefile")).unwrap(), "all:\n");
+}
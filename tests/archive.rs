@@ -0,0 +1,140 @@
+//! `py-proj archive`: the project packed without the venv, caches, and `.git`, with
+//! symlinks stored as links and, under `--deterministic`, the same bytes every time.
+
+mod common;
+
+use common::{tree, Sandbox};
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A scaffolded `demo` with a venv, caches, and a `.git` to leave out.
+fn project(sandbox: &Sandbox) -> PathBuf {
+    let root = sandbox.create("demo", &[]);
+    for dir in [".venv/bin", "src/__pycache__", ".pytest_cache", ".git/objects"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(root.join(".venv/bin/python"), "").unwrap();
+    fs::write(root.join("src/__pycache__/main.cpython-312.pyc"), "").unwrap();
+    fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    fs::write(root.join("notes.txt"), "kept\n").unwrap();
+    root
+}
+
+/// The files (not directories) of the archive at `path`, under their `demo/` name,
+/// with their contents; a symlink's is `-> target`.
+fn entries(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut files = BTreeMap::new();
+    if path.extension().is_some_and(|e| e == "zip") {
+        let mut zip = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).unwrap();
+            if !entry.is_dir() {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes).unwrap();
+                if entry.is_symlink() {
+                    bytes = [b"-> ".as_slice(), &bytes].concat();
+                }
+                files.insert(entry.name().unwrap().into_owned(), bytes);
+            }
+        }
+        return files;
+    }
+    let mut tar = tar::Archive::new(GzDecoder::new(File::open(path).unwrap()));
+    for entry in tar.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let name = entry.path().unwrap().to_string_lossy().into_owned();
+        let mut bytes = Vec::new();
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => continue,
+            tar::EntryType::Symlink => {
+                bytes = format!("-> {}", entry.link_name().unwrap().unwrap().display()).into_bytes();
+            }
+            _ => {
+                entry.read_to_end(&mut bytes).unwrap();
+            }
+        }
+        files.insert(name, bytes);
+    }
+    files
+}
+
+/// What `root` should archive to: its files but the excluded ones, under `demo/`.
+fn expected(root: &Path, with_git: bool) -> BTreeMap<String, Vec<u8>> {
+    tree(root)
+        .into_iter()
+        .filter(|(rel, _)| {
+            ![".venv/", "src/__pycache__/", ".pytest_cache/"].iter().any(|skip| rel.starts_with(skip))
+                && (with_git || !rel.starts_with(".git/"))
+        })
+        .map(|(rel, bytes)| (format!("demo/{rel}"), bytes))
+        .collect()
+}
+
+#[test]
+fn a_tarball_holds_the_project_without_the_venv_caches_or_git() {
+    let sandbox = Sandbox::new();
+    let root = project(&sandbox);
+    let out = sandbox.stdout(&["archive", "demo"]);
+    let tarball = sandbox.join("demo.tar.gz");
+    assert!(out.contains(&format!("to {} (tar.gz, ", tarball.display())), "{out}");
+    for skipped in ["skip .venv", "skip .git", "skip .pytest_cache"] {
+        assert!(out.contains(skipped), "{out}");
+    }
+    assert_eq!(entries(&tarball), expected(&root, false));
+}
+
+#[test]
+fn a_zip_with_git_keeps_the_repository() {
+    let sandbox = Sandbox::new();
+    let root = project(&sandbox);
+    sandbox.stdout(&["archive", "demo", "-o", "backup.zip", "--with-git"]);
+    let files = entries(&sandbox.join("backup.zip"));
+    assert!(files.contains_key("demo/.git/HEAD"));
+    assert_eq!(files, expected(&root, true));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_are_stored_as_links() {
+    let sandbox = Sandbox::new();
+    let root = project(&sandbox);
+    fs::create_dir(sandbox.join("data")).unwrap();
+    fs::write(sandbox.join("data/big.csv"), "a,b\n").unwrap();
+    std::os::unix::fs::symlink("../data", root.join("data")).unwrap();
+    for out in ["demo.tar.gz", "demo.zip"] {
+        sandbox.stdout(&["archive", "demo", "-o", out]);
+        let files = entries(&sandbox.join(out));
+        assert_eq!(files.get("demo/data").map(Vec::as_slice), Some(b"-> ../data".as_slice()), "{out}");
+        assert!(!files.contains_key("demo/data/big.csv"), "{out}");
+    }
+}
+
+#[test]
+fn deterministic_archives_of_the_same_tree_are_identical() {
+    let sandbox = Sandbox::new();
+    let root = project(&sandbox);
+    for format in ["tar.gz", "zip"] {
+        let first = format!("first.{format}");
+        sandbox.stdout(&["archive", "demo", "-o", &first, "--deterministic"]);
+        // Another mtime does not show under --deterministic.
+        let notes = File::options().write(true).open(root.join("notes.txt")).unwrap();
+        notes.set_modified(SystemTime::now() - Duration::from_secs(86_400)).unwrap();
+        let second = format!("second.{format}");
+        sandbox.stdout(&["archive", "demo", "-o", &second, "--deterministic"]);
+        assert!(fs::read(sandbox.join(first)).unwrap() == fs::read(sandbox.join(second)).unwrap(), "{format}");
+    }
+}
+
+#[test]
+fn then_delete_removes_the_project_after_archiving() {
+    let sandbox = Sandbox::new();
+    project(&sandbox);
+    sandbox.stdout(&["archive", "demo", "--then-delete", "--yes"]);
+    assert!(sandbox.join("demo.tar.gz").is_file());
+    assert!(!sandbox.join("demo").exists());
+    sandbox.cmd().args(["archive", "demo"]).assert().failure();
+}
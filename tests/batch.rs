@@ -0,0 +1,77 @@
+//! `batch`: YAML specs, and `--keep-going` against a spec that asks to fail fast.
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+use std::fs;
+
+const BATCH: [&str; 3] = ["batch", "--skip-uv", "--offline"];
+
+#[test]
+fn yaml_spec_creates_each_project() {
+    let sandbox = Sandbox::new();
+    fs::write(
+        sandbox.join("spec.yaml"),
+        "defaults:\n  python: \"3.12\"\nproject:\n  - name: student01\n  - name: student02\n    outdir: room-b/student02\n",
+    )
+    .unwrap();
+    sandbox.cmd().args(BATCH).arg("spec.yaml").assert().success();
+    assert!(sandbox.join("student01/pyproject.toml").is_file());
+    assert!(sandbox.join("room-b/student02/pyproject.toml").is_file());
+}
+
+#[test]
+fn yaml_spec_errors_name_the_field() {
+    let sandbox = Sandbox::new();
+    fs::write(sandbox.join("spec.yml"), "project:\n  - name: a\n    citation: maybe\n").unwrap();
+    sandbox
+        .cmd()
+        .args(BATCH)
+        .arg("spec.yml")
+        .assert()
+        .failure()
+        .stderr(contains("project #1 (`a`): field `citation`"));
+}
+
+/// A spec whose first project fails after validation (its post hook exits 1).
+fn failing_spec(sandbox: &Sandbox, fail_fast: bool) {
+    fs::write(
+        sandbox.join("spec.toml"),
+        format!(
+            "fail_fast = {fail_fast}\n[defaults]\npython = \"3.12\"\n\n\
+             [[project]]\nname = \"first\"\npost_hooks = [\"false\"]\n\n[[project]]\nname = \"second\"\n"
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn spec_fail_fast_stops_after_the_first_failure() {
+    let sandbox = Sandbox::new();
+    failing_spec(&sandbox, true);
+    sandbox.cmd().args(BATCH).arg("spec.toml").assert().failure();
+    assert!(!sandbox.join("second").exists());
+}
+
+#[test]
+fn keep_going_overrides_the_spec() {
+    let sandbox = Sandbox::new();
+    failing_spec(&sandbox, true);
+    sandbox
+        .cmd()
+        .args(BATCH)
+        .args(["spec.toml", "--keep-going"])
+        .assert()
+        .failure()
+        .stderr(contains("1 of 2 project(s) were not created"));
+    assert!(sandbox.join("second/pyproject.toml").is_file());
+}
+
+#[test]
+fn keep_going_is_the_default() {
+    let sandbox = Sandbox::new();
+    failing_spec(&sandbox, false);
+    sandbox.cmd().args(BATCH).arg("spec.toml").assert().failure();
+    assert!(sandbox.join("second/pyproject.toml").is_file());
+}
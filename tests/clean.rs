@@ -0,0 +1,152 @@
+//! `--clean_project` removes tool caches, build leftovers, and bytecode, and leaves the
+//! project's own files alone.
+
+mod common;
+
+use common::{plain, Sandbox};
+use predicates::str::contains;
+use std::fs;
+
+#[test]
+fn clean_removes_caches_egg_info_and_coverage_data() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    let root = sandbox.join("demo");
+    for dir in [".tox/py312", ".nox/tests", ".eggs", ".hypothesis/examples", "pip-wheel-metadata"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for dir in ["demo.egg-info", "src/demo.egg-info", "src/__pycache__", ".venv/bin"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in [".coverage", ".coverage.host.123.456", "src/__pycache__/main.cpython-312.pyc"] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    sandbox.cmd().args(["--clean_project", "demo"]).assert().success();
+    for gone in [
+        ".tox",
+        ".nox",
+        ".eggs",
+        ".hypothesis",
+        "pip-wheel-metadata",
+        "demo.egg-info",
+        "src/demo.egg-info",
+        "src/__pycache__",
+        ".coverage",
+        ".coverage.host.123.456",
+        ".venv",
+    ] {
+        assert!(!root.join(gone).exists(), "{gone} is still there");
+    }
+    assert!(root.join("pyproject.toml").is_file());
+    assert!(root.join("src/main.py").is_file());
+}
+
+#[test]
+fn keep_venv_leaves_the_venv() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    let root = sandbox.join("demo");
+    fs::create_dir_all(root.join(".venv/bin")).unwrap();
+    fs::create_dir_all(root.join(".tox")).unwrap();
+    sandbox.cmd().args(["--clean_project", "demo", "--keep-venv"]).assert().success();
+    assert!(root.join(".venv/bin").is_dir());
+    assert!(!root.join(".tox").exists());
+}
+
+#[test]
+fn clean_from_a_subdirectory_finds_the_project() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    let root = sandbox.join("demo");
+    fs::create_dir_all(root.join("src/__pycache__")).unwrap();
+    let out = sandbox
+        .cmd()
+        .current_dir(root.join("src"))
+        .arg("--clean_project")
+        .assert()
+        .success();
+    let stdout = plain(&out.get_output().stdout);
+    assert!(stdout.contains("(found from the current directory)"), "{stdout}");
+    assert!(!root.join("src/__pycache__").exists());
+    assert!(!root.join("src/src_proj").exists());
+}
+
+#[test]
+fn clean_outside_a_project_fails_with_a_hint() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("empty")).unwrap();
+    sandbox
+        .cmd()
+        .current_dir(sandbox.join("empty"))
+        .arg("--clean_project")
+        .assert()
+        .code(2)
+        .stderr(contains("no project found in"))
+        .stderr(contains("py-proj --clean_project path/to/proj"));
+}
+
+#[test]
+fn clean_resolves_a_relative_path_and_echoes_it() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::create_dir_all(sandbox.join("elsewhere")).unwrap();
+    fs::create_dir_all(root.join(".tox")).unwrap();
+    let out = sandbox
+        .cmd()
+        .current_dir(sandbox.join("elsewhere"))
+        .args(["--clean_project", "../demo"])
+        .assert()
+        .success();
+    let stdout = plain(&out.get_output().stdout);
+    let absolute = fs::canonicalize(&root).unwrap();
+    assert!(stdout.contains(&format!("Root: {}\n", absolute.display())), "{stdout}");
+    assert!(!root.join(".tox").exists());
+}
+
+#[test]
+fn clean_expands_a_tilde() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::create_dir_all(root.join(".tox")).unwrap();
+    fs::create_dir_all(sandbox.join("elsewhere")).unwrap();
+    sandbox
+        .cmd()
+        .current_dir(sandbox.join("elsewhere"))
+        .args(["--clean_project", "~/demo"])
+        .assert()
+        .success();
+    assert!(!root.join(".tox").exists());
+}
+
+#[test]
+fn clean_refuses_a_missing_or_dangling_path() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(["--clean_project", "nope"])
+        .assert()
+        .failure()
+        .stderr(contains("nope does not exist"));
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(sandbox.join("gone"), sandbox.join("dangling")).unwrap();
+        sandbox
+            .cmd()
+            .args(["--clean_project", "dangling"])
+            .assert()
+            .failure()
+            .stderr(contains("is a dangling symlink"));
+    }
+}
+
+#[test]
+fn clean_path_conflicts_with_outdir() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    sandbox
+        .cmd()
+        .args(["--clean_project", "demo", "--outdir", "demo"])
+        .assert()
+        .code(2)
+        .stderr(contains("cannot be used with '--outdir <OUTDIR>'"));
+}
@@ -0,0 +1,23 @@
+//! `--help` works on the top level (py-proj's own page) and on every subcommand.
+
+mod common;
+
+use common::{plain, Sandbox};
+
+#[test]
+fn top_level_help_is_py_projs_own() {
+    let out = Sandbox::new().stdout(&["--help"]);
+    assert!(out.contains("PY-PROJ"), "{out}");
+}
+
+#[test]
+fn every_subcommand_has_help() {
+    let sandbox = Sandbox::new();
+    for args in [&["templates", "--help"][..], &["templates", "export", "-h"], &["repair", "--help"], &["doctor", "-h"]] {
+        let out = sandbox.cmd().args(args).assert().success();
+        let stdout = plain(&out.get_output().stdout);
+        let usage = format!("Usage: pyproj {}", args[..args.len() - 1].join(" "));
+        assert!(stdout.contains(&usage), "{args:?}:\n{stdout}");
+        assert!(stdout.contains("-h, --help"), "{args:?}:\n{stdout}");
+    }
+}
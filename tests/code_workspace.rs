@@ -0,0 +1,59 @@
+//! `--code-workspace` writes a valid multi-root workspace whose settings agree with the
+//! project's own `.vscode/settings.json`.
+
+mod common;
+
+use common::Sandbox;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+fn json(path: &Path) -> Value {
+    let text = fs::read_to_string(path).unwrap();
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{} is not valid JSON: {e}", path.display()))
+}
+
+#[test]
+fn workspace_settings_match_the_folder_settings() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &["--code-workspace"]);
+    let workspace = json(&sandbox.join("demo.code-workspace"));
+    assert_eq!(workspace["folders"], serde_json::json!([{ "name": "demo", "path": "demo" }]));
+
+    let folder = json(&sandbox.join("demo/.vscode/settings.json"));
+    let hoisted = workspace["settings"].as_object().unwrap();
+    let mut shared = 0;
+    for (key, value) in folder.as_object().unwrap() {
+        let Some(hoisted) = hoisted.get(key) else {
+            continue;
+        };
+        let hoisted = hoisted.to_string().replace("${workspaceFolder:demo}", "${workspaceFolder}");
+        assert_eq!(hoisted, value.to_string(), "{key}");
+        shared += 1;
+    }
+    assert!(hoisted.contains_key("python.defaultInterpreterPath"));
+    assert!(shared >= 5, "only {shared} settings in common");
+}
+
+#[test]
+fn workspace_member_lists_the_other_members() {
+    let sandbox = Sandbox::new();
+    let ws = sandbox.join("ws");
+    fs::create_dir_all(ws.join("packages/other")).unwrap();
+    fs::write(
+        ws.join("pyproject.toml"),
+        "[project]\nname = \"ws\"\nversion = \"0.1.0\"\n\n[tool.uv.workspace]\nmembers = [\"packages/*\"]\n",
+    )
+    .unwrap();
+    fs::write(ws.join("packages/other/pyproject.toml"), "[project]\nname = \"other\"\nversion = \"0.1.0\"\n").unwrap();
+    sandbox
+        .cmd()
+        .current_dir(&ws)
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12"])
+        .args(["--outdir", "packages/demo", "--project", "demo", "--workspace-member", "--code-workspace"])
+        .assert()
+        .success();
+    let path = ws.join("demo.code-workspace");
+    json(&path);
+    common::assert_snapshot("code_workspace/member.code-workspace", &fs::read(&path).unwrap());
+}
@@ -0,0 +1,45 @@
+//! External commands are killed after the command timeout, or when cancelled, with
+//! what they printed so far. The timeout is process-wide, so these run in their own
+//! test binary.
+
+#![cfg(unix)]
+
+use pyproj::util::{run_cancellable, run_captured, set_command_timeout};
+use pyproj::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn command_past_the_timeout_is_killed() {
+    let dir = tempfile::tempdir().unwrap();
+    set_command_timeout(Some(Duration::from_secs(1)));
+    let started = Instant::now();
+    let err = run_captured("sh", &["-c", "echo started; sleep 30"], dir.path()).unwrap_err();
+    set_command_timeout(None);
+    assert!(started.elapsed() < Duration::from_secs(10), "took {:?}", started.elapsed());
+    match Error::find(&err) {
+        Some(e @ Error::Timeout { program, secs, output }) => {
+            assert_eq!((program.as_str(), *secs, output.as_str()), ("sh", 1, "started"));
+            assert_eq!(e.exit_code(), 6);
+        }
+        _ => panic!("not a timeout: {err:#}"),
+    }
+}
+
+#[test]
+fn cancelled_command_is_killed() {
+    let dir = tempfile::tempdir().unwrap();
+    let cancel = AtomicBool::new(false);
+    let started = Instant::now();
+    let result = thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(300));
+            cancel.store(true, Ordering::SeqCst);
+        });
+        run_cancellable("sleep", &["30"], dir.path(), |_| {}, &cancel)
+    });
+    let err = result.unwrap_err();
+    assert!(format!("{err}").contains("was cancelled"), "{err:#}");
+    assert!(started.elapsed() < Duration::from_secs(10), "took {:?}", started.elapsed());
+}
@@ -0,0 +1,175 @@
+//! Shared setup for the CLI tests: py-proj run from a scratch directory that is also
+//! its home, with an empty user config and no history.
+
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use pyproj::{PlannedAction, ScaffoldPlan, ScaffoldPlanBuilder};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+pub struct Sandbox {
+    dir: TempDir,
+}
+
+impl Default for Sandbox {
+    fn default() -> Sandbox {
+        Sandbox::new()
+    }
+}
+
+impl Sandbox {
+    pub fn new() -> Sandbox {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.toml"), "history = false\n").unwrap();
+        Sandbox { dir }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// `rel` under the sandbox.
+    pub fn join(&self, rel: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(rel)
+    }
+
+    /// `py-proj` run in the sandbox, without colors.
+    pub fn cmd(&self) -> Command {
+        let mut cmd = Command::cargo_bin("pyproj").unwrap();
+        cmd.current_dir(self.path())
+            .env("HOME", self.path())
+            .env("XDG_STATE_HOME", self.join(".state"))
+            .env("XDG_CACHE_HOME", self.join(".cache"))
+            .env("PY_PROJ_CONFIG", self.join("config.toml"))
+            .env("NO_COLOR", "1")
+            .env_remove("RUST_BACKTRACE");
+        cmd
+    }
+
+    /// Run `args` and return its stdout, without colors; panics unless it succeeds.
+    pub fn stdout(&self, args: &[&str]) -> String {
+        plain(&self.cmd().args(args).assert().success().get_output().stdout)
+    }
+
+    /// Put a stand-in uv at `<sandbox>/bin/uv` and return it: it reports uv 0.5.0 with
+    /// CPython 3.12.4 installed and 3.13.1 available, makes a `pyvenv.cfg` for `uv venv`,
+    /// succeeds at everything else, and appends each command line to `<sandbox>/uv.log`.
+    #[cfg(unix)]
+    pub fn fake_uv(&self) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = self.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let uv = bin.join("uv");
+        let script = format!(
+            r#"#!/bin/sh
+echo "$*" >> '{log}'
+case "$1" in
+  --version) echo "uv 0.5.0";;
+  python) [ "$2" = list ] && [ "$3" != --only-installed ] && echo "cpython-3.13.1-linux-x86_64-gnu    <download available>"
+          [ "$2" = list ] && echo "cpython-3.12.4-linux-x86_64-gnu    /usr/bin/python3";;
+  venv) for a; do d=$a; done; mkdir -p "$d"; printf 'home = /usr/bin
+version_info = 3.12.4
+' > "$d/pyvenv.cfg";;
+esac
+exit 0
+"#,
+            log = self.join("uv.log").display()
+        );
+        fs::write(&uv, script).unwrap();
+        fs::set_permissions(&uv, fs::Permissions::from_mode(0o755)).unwrap();
+        uv
+    }
+
+    /// The command lines the [`Sandbox::fake_uv`] was run with.
+    pub fn uv_log(&self) -> Vec<String> {
+        fs::read_to_string(self.join("uv.log")).unwrap_or_default().lines().map(str::to_string).collect()
+    }
+
+    /// Create the project `name` in the sandbox without uv or the network.
+    pub fn create(&self, name: &str, extra: &[&str]) -> PathBuf {
+        self.cmd()
+            .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", name])
+            .args(extra)
+            .assert()
+            .success();
+        self.join(name)
+    }
+}
+
+/// `output` as text with the ANSI color codes taken out.
+pub fn plain(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Every file under `root` with its contents, by relative path (with `/`).
+pub fn tree(root: &Path) -> BTreeMap<String, Vec<u8>> {
+    fn walk(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                walk(root, &path, files);
+            } else {
+                let rel = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+                files.insert(rel, fs::read(&path).unwrap());
+            }
+        }
+    }
+    let mut files = BTreeMap::new();
+    walk(root, root, &mut files);
+    files
+}
+
+/// The file `rel` as a deterministic plan for the project `demo` with `options` would
+/// write it; nothing touches the disk.
+pub fn render(options: fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder, rel: &str) -> Vec<u8> {
+    let builder = ScaffoldPlan::builder("demo").root("/nonexistent/demo").python("3.12").skip_uv(true).deterministic(true);
+    let plan = options(builder).build().unwrap();
+    plan.plan(false)
+        .unwrap()
+        .into_iter()
+        .find_map(|a| match a {
+            PlannedAction::WriteFile { path, contents, .. } if path == Path::new(rel) => Some(contents),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("{rel} is not planned"))
+}
+
+/// Compare `actual` with the golden file `tests/snapshots/<rel>`. With
+/// `UPDATE_SNAPSHOTS=1` the golden file is (re)written instead.
+pub fn assert_snapshot(rel: &str, actual: &[u8]) {
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(rel);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        fs::write(&golden, actual).unwrap();
+        return;
+    }
+    let Ok(expected) = fs::read(&golden) else {
+        panic!("no snapshot {}; run with UPDATE_SNAPSHOTS=1 to write it", golden.display());
+    };
+    if expected != actual {
+        let (expected, actual) = (String::from_utf8_lossy(&expected), String::from_utf8_lossy(actual));
+        let line = expected.lines().zip(actual.lines()).position(|(e, a)| e != a);
+        let line = line.unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{rel} differs from its snapshot at line {}:\n  expected: {:?}\n  actual:   {:?}\n\
+             run with UPDATE_SNAPSHOTS=1 if the change is intended",
+            line + 1,
+            expected.lines().nth(line).unwrap_or("<end of file>"),
+            actual.lines().nth(line).unwrap_or("<end of file>"),
+        );
+    }
+}
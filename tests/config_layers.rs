@@ -0,0 +1,70 @@
+//! Settings layered from the user config, the nearest `.pyprojrc.toml` above the
+//! current directory, the environment, and the flags, as `config show --origin` and a
+//! create inside the repository see them.
+
+mod common;
+
+use common::{plain, Sandbox};
+use std::fs;
+
+/// A sandbox whose user config sets the Python and the org, under a repository
+/// `mono/` whose rc overrides the Python and `mono/team/` whose rc renames the org.
+fn monorepo() -> Sandbox {
+    let sandbox = Sandbox::new();
+    fs::write(
+        sandbox.join("config.toml"),
+        "history = false\npython = \"3.10\"\nvenv_dir = \".venv-user\"\n\n[org]\nname = \"user\"\nlicense = \"MIT\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(sandbox.join("mono/team/work")).unwrap();
+    fs::write(sandbox.join("mono/.pyprojrc.toml"), "python = \"3.11\"\n\n[org]\nname = \"mono\"\n").unwrap();
+    fs::write(sandbox.join("mono/team/.pyprojrc.toml"), "[org]\nname = \"team\"\n").unwrap();
+    sandbox
+}
+
+/// The `key = value  # origin` line of `config show --origin` for `key`.
+fn line<'a>(out: &'a str, key: &str) -> &'a str {
+    out.lines()
+        .find(|l| l.trim_start().starts_with(&format!("{key} ")))
+        .unwrap_or_else(|| panic!("no {key} in:\n{out}"))
+}
+
+#[test]
+fn config_show_names_the_layer_of_each_value() {
+    let sandbox = monorepo();
+    let run = |dir: &str, args: &[&str]| {
+        let mut cmd = sandbox.cmd();
+        cmd.current_dir(sandbox.join(dir)).env("PY_PROJ_EDITOR", "nano").args(["config", "show", "--origin"]).args(args);
+        plain(&cmd.assert().success().get_output().stdout)
+    };
+
+    let out = run("mono/team/work", &[]);
+    let team = sandbox.join("mono/team/.pyprojrc.toml");
+    assert!(out.contains(&format!("Repo rc: {}", team.display())), "{out}");
+    // Only the nearest rc is read, so mono/'s Python does not apply here.
+    assert!(line(&out, "python").contains(r#""3.10"  # user config"#), "{out}");
+    assert!(line(&out, "org.name").contains(&format!(r#""team"  # repo rc ({})"#, team.display())), "{out}");
+    assert!(line(&out, "org.license").contains("# user config"), "{out}");
+    assert!(line(&out, "editor").contains(r#""nano"  # env ($PY_PROJ_EDITOR)"#), "{out}");
+    assert!(line(&out, "notebooks_dir").contains("(not set)  # default"), "{out}");
+
+    let out = run("mono", &["--python", "3.13"]);
+    assert!(line(&out, "python").contains(r#""3.13"  # flag (--python)"#), "{out}");
+    assert!(line(&out, "org.name").contains(r#""mono"  # repo rc"#), "{out}");
+}
+
+#[test]
+fn a_create_inside_the_repository_uses_its_rc() {
+    let sandbox = monorepo();
+    fs::remove_file(sandbox.join("mono/team/.pyprojrc.toml")).unwrap();
+    sandbox
+        .cmd()
+        .current_dir(sandbox.join("mono/team/work"))
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo"])
+        .assert()
+        .success();
+    let root = sandbox.join("mono/team/work/demo");
+    let pyproject = fs::read_to_string(root.join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains(r#"requires-python = ">=3.11""#), "{pyproject}");
+    assert!(fs::read_to_string(root.join(".vscode/settings.json")).unwrap().contains(".venv-user"));
+}
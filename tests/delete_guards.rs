@@ -0,0 +1,92 @@
+//! Delete refuses the home directory and strangers' directories; create refuses to
+//! scaffold inside another project.
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+use std::fs;
+
+#[test]
+fn delete_refuses_the_home_directory() {
+    let sandbox = Sandbox::new();
+    fs::write(sandbox.join("pyproject.toml"), "[project]\nname = \"home\"\n").unwrap();
+    fs::write(sandbox.join(".pyproj.toml"), "").unwrap();
+    sandbox
+        .cmd()
+        .arg("--delete_project")
+        .arg(sandbox.path())
+        .arg("--yes")
+        .assert()
+        .code(2)
+        .stderr(contains("it is your home directory"))
+        .stderr(contains("--i-know-what-im-doing"));
+    assert!(sandbox.join("pyproject.toml").is_file());
+}
+
+#[test]
+fn delete_refuses_a_directory_that_is_not_a_project() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("photos")).unwrap();
+    fs::write(sandbox.join("photos/cat.jpg"), "meow").unwrap();
+    sandbox
+        .cmd()
+        .args(["--delete_project", "photos", "--yes"])
+        .assert()
+        .code(2)
+        .stderr(contains("does not look like a py-proj project"));
+    assert!(sandbox.join("photos/cat.jpg").is_file());
+}
+
+#[test]
+fn create_refuses_to_nest_inside_a_project() {
+    let sandbox = Sandbox::new();
+    sandbox.create("outer", &[]);
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12"])
+        .args(["--outdir", "outer/src", "--project", "inner"])
+        .assert()
+        .failure()
+        .stderr(contains("is inside the py-proj project at"));
+    assert!(!sandbox.join("outer/src/inner").exists());
+}
+
+#[test]
+fn delete_refuses_a_pyproject_without_scaffold_files() {
+    let sandbox = Sandbox::new();
+    let other = sandbox.join("other");
+    fs::create_dir_all(other.join("lib")).unwrap();
+    fs::write(other.join("pyproject.toml"), "[project]\nname = \"other\"\n").unwrap();
+    fs::write(other.join("lib/core.py"), "x = 1\n").unwrap();
+    let out = sandbox
+        .cmd()
+        .args(["--delete_project", "other", "--yes"])
+        .assert()
+        .code(2)
+        .stderr(contains("--force-delete-unmanaged"));
+    let stdout = common::plain(&out.get_output().stdout);
+    assert!(stdout.contains("not a py-proj project (found pyproject.toml)"), "{stdout}");
+    assert!(other.join("lib/core.py").is_file());
+
+    sandbox
+        .cmd()
+        .args(["--delete_project", "other", "--yes", "--force-delete-unmanaged"])
+        .assert()
+        .success();
+    assert!(!other.exists());
+}
+
+#[test]
+fn delete_recognises_a_scaffold_without_its_manifest() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    fs::remove_file(sandbox.join("demo/.pyproj.toml")).unwrap();
+    let out = sandbox.cmd().args(["--delete_project", "demo", "--yes"]).assert().success();
+    let stdout = common::plain(&out.get_output().stdout);
+    assert!(
+        stdout.contains("py-proj project (found pyproject.toml, pyrefly.toml, .vscode/settings.json, Makefile)"),
+        "{stdout}"
+    );
+    assert!(!sandbox.join("demo").exists());
+}
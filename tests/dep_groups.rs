@@ -0,0 +1,89 @@
+//! `--dep-style groups`: the dev tools live in a PEP 735 `dev` group, and everything that
+//! installs them (`make install`, the README quickstart, the next steps) uses `uv sync`.
+
+mod common;
+
+use common::{render, Sandbox};
+use pyproj::scaffold::DepStyle;
+use pyproj::ScaffoldPlanBuilder;
+use serde_json::Value;
+use std::process::Command;
+
+fn groups(b: ScaffoldPlanBuilder) -> ScaffoldPlanBuilder {
+    b.dep_style(DepStyle::Groups)
+}
+
+fn text(options: fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder, rel: &str) -> String {
+    String::from_utf8(render(options, rel)).unwrap()
+}
+
+#[test]
+fn groups_mode_lists_the_dev_tools_in_a_dependency_group_only() {
+    let pyproject: toml::Table = text(groups, "pyproject.toml").parse().unwrap();
+    let dev = pyproject["dependency-groups"]["dev"].as_array().unwrap();
+    let names: Vec<&str> = dev.iter().map(|d| d.as_str().unwrap()).collect();
+    assert!(names.iter().any(|d| d.starts_with("pytest>=")), "{names:?}");
+    assert!(names.iter().any(|d| d.starts_with("ruff>=")), "{names:?}");
+    let extras = pyproject["project"].get("optional-dependencies");
+    assert!(extras.and_then(|e| e.get("dev")).is_none(), "a dev extra as well: {extras:?}");
+
+    let extras: toml::Table = text(|b| b, "pyproject.toml").parse().unwrap();
+    assert!(extras["project"]["optional-dependencies"].get("dev").is_some());
+    assert!(extras.get("dependency-groups").is_none());
+}
+
+#[test]
+fn groups_mode_installs_with_uv_sync() {
+    let makefile = text(groups, "Makefile");
+    assert!(makefile.contains(".PHONY: install\ninstall: ## Install the project and its dev tools\n\t@uv sync --group dev\n"), "{makefile}");
+    assert!(!makefile.contains("pip install"), "{makefile}");
+
+    let readme = text(groups, "README.md");
+    assert!(readme.contains("\nuv sync --group dev\n"), "{readme}");
+    assert!(!readme.contains("pip install -e"), "{readme}");
+
+    let out = Sandbox::new()
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo"])
+        .args(["--dep-style", "groups", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&out).unwrap();
+    let steps: Vec<&str> = report["next_steps"].as_array().unwrap().iter().map(|s| s["command"].as_str().unwrap()).collect();
+    assert!(steps.contains(&"uv sync --group dev"), "{steps:?}");
+    assert!(!steps.iter().any(|s| s.contains("pip install")), "{steps:?}");
+}
+
+#[test]
+fn extras_mode_keeps_installing_the_dev_extra() {
+    let makefile = text(|b| b, "Makefile");
+    assert!(makefile.contains("install: ## Install the project and its dev tools\n\t@uv pip install -e \".[dev]\"\n"), "{makefile}");
+    let readme = text(|b| b, "README.md");
+    assert!(readme.contains("\nuv pip install -e \".[dev]\"\n"), "{readme}");
+}
+
+/// Needs uv and the network; skipped without uv.
+#[test]
+fn uv_sync_installs_the_dev_group_into_the_venv() {
+    if which::which("uv").is_err() {
+        eprintln!("uv is not installed; skipping");
+        return;
+    }
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--python", "3.12", "--project", "demo", "--dep-style", "groups"])
+        .assert()
+        .success();
+    let root = sandbox.join("demo");
+    let uv = |args: &[&str]| {
+        let out = Command::new("uv").args(args).current_dir(&root).output().unwrap();
+        let output = format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+        assert!(out.status.success(), "uv {}: {output}", args.join(" "));
+    };
+    uv(&["sync", "--group", "dev"]);
+    uv(&["run", "--no-sync", "python", "-c", "import pytest, ruff"]);
+}
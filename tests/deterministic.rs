@@ -0,0 +1,63 @@
+//! `--deterministic` and `$SOURCE_DATE_EPOCH`: the same options give byte-identical
+//! trees, whenever and wherever they run.
+
+mod common;
+
+use common::{tree, Sandbox};
+use std::fs;
+
+const OPTIONS: [&str; 6] = ["--license", "MIT", "--license-header", "MIT", "--citation", "--template"];
+
+#[test]
+fn two_deterministic_runs_give_identical_trees() {
+    let scaffold = || {
+        let sandbox = Sandbox::new();
+        let root = sandbox.create("demo", &[&OPTIONS[..], &["datasci", "--deterministic"]].concat());
+        (tree(&root), sandbox)
+    };
+    let (first, _a) = scaffold();
+    let (second, _b) = scaffold();
+    assert_eq!(first.keys().collect::<Vec<_>>(), second.keys().collect::<Vec<_>>());
+    for (rel, contents) in &first {
+        assert!(second[rel] == *contents, "{rel} differs between the runs");
+    }
+    let manifest = String::from_utf8_lossy(&first[".pyproj.toml"]);
+    assert!(manifest.contains("created = \"1970-01-01T00:00:00Z\""), "{manifest}");
+    assert!(String::from_utf8_lossy(&first["src/main.py"]).contains("Copyright (c) 1970 the demo authors"));
+}
+
+#[test]
+fn source_date_epoch_stamps_the_dates_and_years() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo"])
+        .args(&OPTIONS[..5])
+        .assert()
+        .success();
+    let root = sandbox.join("demo");
+    let read = |rel: &str| fs::read_to_string(root.join(rel)).unwrap();
+    assert!(read(".pyproj.toml").contains("created = \"2023-11-14T22:13:20Z\""));
+    assert!(read("CITATION.cff").contains("date-released: \"2023-11-14\""));
+    assert!(read("src/main.py").contains("Copyright (c) 2023 the demo authors"));
+
+    let manifest: toml::Table = read(".pyproj.toml").parse().unwrap();
+    let paths: Vec<&str> = manifest["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted, "the manifest lists its files in order");
+}
+
+#[test]
+fn a_malformed_source_date_epoch_is_an_error() {
+    let sandbox = Sandbox::new();
+    let out = sandbox
+        .cmd()
+        .env("SOURCE_DATE_EPOCH", "yesterday")
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo", "--deterministic"])
+        .assert()
+        .failure();
+    let stderr = String::from_utf8_lossy(&out.get_output().stderr);
+    assert!(stderr.contains("SOURCE_DATE_EPOCH=yesterday is not a number of seconds"), "{stderr}");
+}
@@ -0,0 +1,58 @@
+//! Typed errors keep their documented exit codes, and `--json` names the variant.
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+
+#[test]
+fn delete_without_confirmation_exits_2() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    sandbox
+        .cmd()
+        .args(["--delete_project", "demo"])
+        .assert()
+        .code(2)
+        .stderr(contains("without confirmation; pass --yes"));
+    assert!(sandbox.join("demo/pyproject.toml").is_file());
+}
+
+#[test]
+fn missing_uv_exits_3_before_writing() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .env("PATH", sandbox.join("no-bin"))
+        .args(["--create_project", "--yes", "--offline", "--python", "3.12", "--project", "demo"])
+        .args(["--preflight-ignore", "uv"])
+        .assert()
+        .code(3)
+        .stderr(contains("`uv` was not found on PATH"));
+    assert!(!sandbox.join("demo").exists());
+}
+
+#[test]
+fn json_error_names_the_variant() {
+    let sandbox = Sandbox::new();
+    let out = sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "import", "--json"])
+        .assert()
+        .code(2);
+    let report: serde_json::Value = serde_json::from_slice(&out.get_output().stdout).unwrap();
+    assert_eq!(report["error"]["kind"], "InvalidProjectName");
+    assert_eq!(report["error"]["exit_code"], 2);
+}
+
+#[test]
+fn bad_python_is_a_usage_error() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "banana", "--project", "demo"])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid Python version `banana`"));
+    assert!(!sandbox.join("demo").exists());
+}
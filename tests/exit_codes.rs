@@ -0,0 +1,45 @@
+//! Commands that report findings through their exit status return it from `run`, so
+//! `main` still gets to write the history and print notices before exiting.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+
+#[test]
+fn verify_exits_1_when_a_generated_file_changed() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    sandbox.cmd().args(["verify", "demo"]).assert().success();
+    fs::write(root.join("Makefile"), "changed\n").unwrap();
+    sandbox.cmd().args(["verify", "demo"]).assert().code(1);
+}
+
+#[test]
+fn diff_exits_1_when_the_project_differs() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::write(root.join("Makefile"), "changed\n").unwrap();
+    sandbox.cmd().args(["diff", "demo"]).assert().code(1);
+}
+
+#[cfg(unix)]
+#[test]
+fn run_hands_back_the_command_status() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::create_dir(root.join(".venv")).unwrap();
+    // Stands in for `uv --version` and `uv run -- <argv>`.
+    let uv = sandbox.join("uv");
+    fs::write(&uv, "#!/bin/sh\n[ \"$1\" = --version ] && exec echo uv 0.5.0\nshift 2\nexec \"$@\"\n").unwrap();
+    fs::set_permissions(&uv, fs::Permissions::from_mode(0o755)).unwrap();
+    sandbox
+        .cmd()
+        .arg("--uv-bin")
+        .arg(&uv)
+        .args(["run", "-C", "demo", "--", "sh", "-c", "exit 3"])
+        .assert()
+        .code(3);
+}
@@ -0,0 +1,31 @@
+//! `py-proj export-conda`: the project's requirements as a conda environment.yml,
+//! conda-forge packages as match specs and the rest under `pip:`.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+
+#[test]
+fn environment_yml_splits_conda_and_pip_requirements() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    let pyproject = fs::read_to_string(root.join("pyproject.toml")).unwrap();
+    let pyproject = pyproject.replacen(
+        "dependencies = []",
+        r#"dependencies = ["numpy>=2.0", "requests[socks]>=2.31; python_version >= '3.8'", "*star: value"]"#,
+        1,
+    );
+    fs::write(root.join("pyproject.toml"), pyproject).unwrap();
+    sandbox.cmd().current_dir(&root).arg("export-conda").assert().success();
+
+    let text = fs::read_to_string(root.join("environment.yml")).unwrap();
+    let env: serde_yaml::Value = serde_yaml::from_str(&text).unwrap();
+    assert_eq!(env["name"].as_str(), Some("demo"));
+    assert_eq!(env["channels"][0].as_str(), Some("conda-forge"));
+    let deps = env["dependencies"].as_sequence().unwrap();
+    let specs: Vec<&str> = deps.iter().filter_map(serde_yaml::Value::as_str).collect();
+    assert_eq!(specs, ["python=3.12", "numpy>=2.0", "pip"]);
+    let pip: Vec<&str> = deps.last().unwrap()["pip"].as_sequence().unwrap().iter().filter_map(|v| v.as_str()).collect();
+    assert_eq!(pip, ["requests[socks]>=2.31; python_version >= '3.8'", "*star: value"]);
+}
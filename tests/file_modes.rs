@@ -0,0 +1,76 @@
+//! Scripts are generated executable, everything else is not, and the manifest keeps
+//! the mode so `diff` notices and `upgrade` restores a lost executable bit;
+//! `--dir-mode`/`--file-mode` set the bits for the root and every generated path.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{plain, Sandbox};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+fn mode(path: &Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn scripts_are_executable_and_templates_are_not() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--emit-bootstrap", "--git-hooks", "native"]);
+    for rel in [".envrc", "scripts/bootstrap.sh", ".githooks/pre-commit"] {
+        assert_eq!(mode(&root.join(rel)) & 0o111, 0o111, "{rel} is not executable");
+    }
+    for rel in ["pyproject.toml", "Makefile", "src/main.py", "README.md", ".env"] {
+        assert_eq!(mode(&root.join(rel)) & 0o111, 0, "{rel} is executable");
+    }
+    let manifest: toml::Table = fs::read_to_string(root.join(".pyproj.toml")).unwrap().parse().unwrap();
+    let envrc = manifest["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"].as_str() == Some(".envrc"))
+        .unwrap();
+    assert_eq!(envrc["mode"].as_str(), Some("755"));
+}
+
+#[test]
+fn upgrade_restores_a_lost_executable_bit() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::set_permissions(root.join(".envrc"), fs::Permissions::from_mode(0o644)).unwrap();
+
+    let out = sandbox.cmd().args(["diff", "demo"]).assert().code(1);
+    assert!(plain(&out.get_output().stdout).contains("mode .envrc (644 -> 755)"));
+    let out = sandbox.cmd().args(["upgrade", "demo"]).assert().success();
+    assert!(plain(&out.get_output().stdout).contains("chmod .envrc (755)"));
+    assert_eq!(mode(&root.join(".envrc")), 0o755);
+}
+
+#[test]
+fn dir_and_file_modes_apply_to_the_root_and_everything_generated() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--dir-mode", "2775", "--file-mode", "664"]);
+    for rel in ["", "src", "tests", ".vscode"] {
+        let mode = fs::metadata(root.join(rel)).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode, 0o2775, "{rel:?} is {mode:o}");
+    }
+    for rel in ["pyproject.toml", "Makefile", "src/main.py", ".vscode/settings.json"] {
+        assert_eq!(mode(&root.join(rel)), 0o664, "{rel}");
+    }
+    // Scripts keep their execute bit wherever the mode grants read.
+    assert_eq!(mode(&root.join(".envrc")), 0o775);
+}
+
+#[test]
+fn a_mode_the_owner_cannot_use_is_refused() {
+    let sandbox = Sandbox::new();
+    let out = sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo", "--dir-mode", "555"])
+        .assert()
+        .failure();
+    assert!(plain(&out.get_output().stderr).contains("invalid --dir-mode 555"));
+    assert!(!sandbox.join("demo").exists());
+}
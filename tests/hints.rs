@@ -0,0 +1,48 @@
+//! The usual slips get a `hint:` line that says what to type instead.
+
+mod common;
+
+use common::{plain, Sandbox};
+use std::fs;
+
+fn stderr(sandbox: &Sandbox, dir: &str, args: &[&str], code: i32) -> String {
+    let out = sandbox.cmd().current_dir(sandbox.join(dir)).args(args).assert().code(code);
+    plain(&out.get_output().stderr)
+}
+
+#[test]
+fn dash_instead_of_underscore_suggests_the_real_flag() {
+    let sandbox = Sandbox::new();
+    let err = stderr(&sandbox, "", &["--create-project"], 2);
+    assert!(err.contains("hint: did you mean `--create_project`?"), "{err}");
+    assert!(err.contains("spelled with underscores"), "{err}");
+
+    let err = stderr(&sandbox, "", &["--clean-projet"], 2);
+    assert!(err.contains("hint: did you mean `--clean_project`?"), "{err}");
+}
+
+#[test]
+fn top_level_flag_after_a_subcommand_is_not_suggested_back() {
+    let sandbox = Sandbox::new();
+    let err = stderr(&sandbox, "", &["templates", "list", "--yes"], 2);
+    assert!(err.contains("hint: `--yes` is a top-level flag, not one of this subcommand's"), "{err}");
+    assert!(!err.contains("did you mean"), "{err}");
+}
+
+#[test]
+fn bad_version_shows_examples() {
+    let sandbox = Sandbox::new();
+    let err = stderr(&sandbox, "", &["--create_project", "--python", "three"], 2);
+    assert!(err.contains("hint: pass a version like `--python 3`, `--python 3.12`"), "{err}");
+}
+
+#[test]
+fn delete_outside_a_project_names_the_searched_dirs_and_the_flag() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.join("empty/deeper")).unwrap();
+    let err = stderr(&sandbox, "empty/deeper", &["--delete_project", "--yes"], 2);
+    let deeper = fs::canonicalize(sandbox.join("empty/deeper")).unwrap();
+    assert!(err.contains(&format!("no project found in {}", deeper.display())), "{err}");
+    assert!(err.contains(&format!("    {}\n", deeper.parent().unwrap().display())), "{err}");
+    assert!(err.contains("hint: run this inside a project, or pass the project's path: `py-proj --delete_project path/to/proj`"), "{err}");
+}
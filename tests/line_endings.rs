@@ -0,0 +1,78 @@
+//! `--line-endings`: generated text files get the chosen terminators, except the ones
+//! whose tools insist on LF (or CRLF), and notebooks are written as rendered.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Every file under `root`, relative to it.
+fn files(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            match entry.file_type().unwrap().is_dir() {
+                true => dirs.push(entry.path()),
+                false => found.push(entry.path().strip_prefix(root).unwrap().to_path_buf()),
+            }
+        }
+    }
+    found
+}
+
+fn has_cr(bytes: &[u8]) -> bool {
+    bytes.contains(&b'\r')
+}
+
+/// Every `\n` is preceded by `\r`.
+fn all_crlf(bytes: &[u8]) -> bool {
+    bytes.iter().enumerate().all(|(i, b)| *b != b'\n' || (i > 0 && bytes[i - 1] == b'\r'))
+}
+
+#[test]
+fn lf_is_the_default() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    for rel in files(&root) {
+        assert!(!has_cr(&fs::read(root.join(&rel)).unwrap()), "{} has CR", rel.display());
+    }
+    let gitattributes = fs::read_to_string(root.join(".gitattributes")).unwrap();
+    assert!(gitattributes.contains("* text=auto eol=lf"), "{gitattributes}");
+}
+
+#[test]
+fn crlf_applies_to_text_files_but_not_to_makefiles_or_scripts() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--line-endings", "crlf"]);
+    for rel in ["pyproject.toml", "README.md", "src/main.py", ".vscode/settings.json", ".gitignore"] {
+        let bytes = fs::read(root.join(rel)).unwrap();
+        assert!(has_cr(&bytes) && all_crlf(&bytes), "{rel} is not all CRLF");
+    }
+    for rel in ["Makefile", ".envrc"] {
+        assert!(!has_cr(&fs::read(root.join(rel)).unwrap()), "{rel} has CR");
+    }
+    let gitattributes = fs::read_to_string(root.join(".gitattributes")).unwrap();
+    assert!(gitattributes.contains("Makefile text eol=lf"), "{gitattributes}");
+}
+
+#[test]
+fn native_follows_the_platform() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--line-endings", "native"]);
+    let bytes = fs::read(root.join("pyproject.toml")).unwrap();
+    assert_eq!(has_cr(&bytes), cfg!(windows));
+}
+
+#[test]
+fn notebooks_are_left_as_rendered() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.join("over/Notebooks")).unwrap();
+    let notebook = b"{\r\n \"cells\": [],\n \"metadata\": {\"note\": \"a\\r\\nb\"},\r\n \"nbformat\": 4,\n \"nbformat_minor\": 5\r\n}\n";
+    fs::write(sandbox.join("over/Notebooks/explore.ipynb"), notebook).unwrap();
+    for mode in ["lf", "crlf"] {
+        let root = sandbox.create(&format!("demo-{mode}"), &["--templates-dir", "over", "--line-endings", mode]);
+        assert_eq!(fs::read(root.join("Notebooks/explore.ipynb")).unwrap(), notebook, "{mode}");
+    }
+}
@@ -0,0 +1,83 @@
+//! The generated Makefile: every target is annotated for `make help`, declared
+//! `.PHONY`, and runs Python tools through `uv run`. Snapshots live in
+//! tests/snapshots/makefile/.
+
+mod common;
+
+use common::Sandbox;
+use pyproj::scaffold::Security;
+use pyproj::ScaffoldPlanBuilder;
+use std::fs;
+use std::process::Command;
+
+const TOOLS: &[&str] = &["python", "ruff", "black", "pyright", "pytest", "bandit", "nox", "tox"];
+
+fn makefile(options: fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder) -> String {
+    String::from_utf8(common::render(options, "Makefile")).unwrap()
+}
+
+/// `(target, recipe lines)` for each rule in `text`.
+fn rules(text: &str) -> Vec<(&str, Vec<&str>)> {
+    let mut rules: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in text.lines() {
+        if let Some(recipe) = line.strip_prefix('\t') {
+            rules.last_mut().unwrap().1.push(recipe);
+        } else if let Some((target, _)) = line.split_once(':').filter(|(t, rest)| {
+            !t.starts_with(['.', '#']) && !t.contains([' ', '=']) && !rest.starts_with('=')
+        }) {
+            rules.push((target, Vec::new()));
+        }
+    }
+    rules
+}
+
+fn check(text: &str) {
+    for (target, recipe) in rules(text) {
+        assert!(text.contains(&format!(".PHONY: {target}\n{target}:")), "{target} is not .PHONY");
+        let rule = text.lines().find(|l| l.starts_with(&format!("{target}:"))).unwrap();
+        assert!(rule.contains(" ## "), "{target} has no ## description");
+        for line in recipe {
+            let words: Vec<&str> = line.trim_start_matches('@').split_whitespace().collect();
+            if let Some(tool) = words.first().filter(|w| TOOLS.contains(w)) {
+                panic!("{target} runs {tool} outside `uv run`: {line}");
+            }
+        }
+    }
+}
+
+#[test]
+fn makefile_default() {
+    let text = makefile(|b| b);
+    check(&text);
+    assert!(text.contains(".DEFAULT_GOAL := help"));
+    common::assert_snapshot("makefile/default.mk", text.as_bytes());
+}
+
+#[test]
+fn makefile_with_every_target() {
+    let text = makefile(|b| {
+        b.entry_point("demo").benchmarks(true).security(Security::Bandit).matrix_python("3.11").matrix_python("3.12")
+    });
+    check(&text);
+    common::assert_snapshot("makefile/every_target.mk", text.as_bytes());
+}
+
+#[test]
+fn make_help_lists_the_targets_even_with_a_file_named_test() {
+    if Command::new("make").arg("--version").output().is_err() {
+        eprintln!("make is not installed; skipping");
+        return;
+    }
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::write(root.join("test"), "").unwrap();
+    let help = Command::new("make").current_dir(&root).output().unwrap();
+    assert!(help.status.success());
+    let help = common::plain(&help.stdout);
+    let makefile = fs::read_to_string(root.join("Makefile")).unwrap();
+    for (target, _) in rules(&makefile) {
+        assert!(help.lines().any(|l| l.split_whitespace().next() == Some(target)), "{target}:\n{help}");
+    }
+    let dry_run = Command::new("make").args(["-n", "test"]).current_dir(&root).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&dry_run.stdout), "uv run pytest tests/\n");
+}
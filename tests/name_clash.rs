@@ -0,0 +1,47 @@
+//! A project named after a stdlib module needs `--force-name`; one named after a popular
+//! PyPI package gets a warning. Both for `--project` and the name taken from the directory.
+
+mod common;
+
+use common::{plain, Sandbox};
+use predicates::str::contains;
+use std::fs;
+
+const CREATE: [&str; 6] = ["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12"];
+
+#[test]
+fn stdlib_name_needs_force_name() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(CREATE)
+        .args(["--project", "json"])
+        .assert()
+        .code(2)
+        .stderr(contains("shadows the standard-library module `json`"))
+        .stderr(contains("--force-name"));
+    assert!(!sandbox.join("json").exists());
+
+    let out = sandbox.cmd().args(CREATE).args(["--project", "json", "--force-name"]).assert().success();
+    let output = plain(&out.get_output().stderr) + &plain(&out.get_output().stdout);
+    assert!(output.contains("`json` shadows the standard-library module `json` (stdlib)"), "{output}");
+}
+
+#[test]
+fn pypi_name_from_the_directory_is_a_json_warning() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("requests")).unwrap();
+    let out = sandbox
+        .cmd()
+        .current_dir(sandbox.join("requests"))
+        .args(CREATE)
+        .args(["--here", "--json"])
+        .assert()
+        .success();
+    let report: serde_json::Value = serde_json::from_slice(&out.get_output().stdout).unwrap();
+    let warnings = report["warnings"].as_array().unwrap();
+    assert!(
+        warnings.iter().any(|w| w.as_str().unwrap().contains("popular PyPI package `requests` (PyPI)")),
+        "{warnings:?}"
+    );
+}
@@ -0,0 +1,46 @@
+//! `--notebooks-dir`: the notebooks directory under another name, remembered in the
+//! manifest so later commands use it too.
+
+mod common;
+
+use common::{tree, Sandbox};
+use std::fs;
+
+#[test]
+fn a_custom_name_is_used_everywhere_and_kept_by_later_commands() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--template", "datasci", "--jupytext", "--notebooks-dir", "notebooks"]);
+    assert!(root.join("notebooks/starter.py").is_file());
+    assert!(!root.join("Notebooks").exists());
+    for (rel, contents) in tree(&root) {
+        let text = String::from_utf8_lossy(&contents);
+        let stale: Vec<&str> = text.lines().filter(|l| l.contains("Notebooks") && *l != "## Notebooks").collect();
+        assert!(stale.is_empty(), "{rel}: {stale:?}");
+    }
+    let manifest: toml::Table = fs::read_to_string(root.join(".pyproj.toml")).unwrap().parse().unwrap();
+    assert_eq!(manifest["options"]["notebooks_dir"].as_str(), Some("notebooks"));
+
+    sandbox.cmd().args(["diff", "demo"]).assert().success();
+    sandbox.stdout(&["upgrade", "demo"]);
+    assert!(!root.join("Notebooks").exists());
+
+    fs::create_dir(root.join("notebooks/.ipynb_checkpoints")).unwrap();
+    sandbox.stdout(&["--clean_project", "demo"]);
+    assert!(!root.join("notebooks/.ipynb_checkpoints").exists());
+    assert!(root.join("notebooks/starter.py").is_file());
+}
+
+#[test]
+fn a_name_with_a_separator_or_leading_dot_is_refused() {
+    let sandbox = Sandbox::new();
+    for bad in ["nb/books", ".notebooks"] {
+        let out = sandbox
+            .cmd()
+            .args(["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo", "--notebooks-dir", bad])
+            .assert()
+            .failure();
+        let stderr = String::from_utf8_lossy(&out.get_output().stderr);
+        assert!(stderr.contains(&format!("invalid --notebooks-dir {bad:?}")), "{stderr}");
+        assert!(!sandbox.join("demo").exists());
+    }
+}
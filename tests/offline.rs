@@ -0,0 +1,29 @@
+//! `--offline` never runs a uv command that would reach the network.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{plain, Sandbox};
+
+#[test]
+fn offline_create_runs_no_network_uv_commands() {
+    let sandbox = Sandbox::new();
+    let uv = sandbox.fake_uv();
+    let out = sandbox
+        .cmd()
+        .arg("--uv-bin")
+        .arg(&uv)
+        .args(["--create_project", "--yes", "--offline", "--python", "3.12", "--project", "demo"])
+        .assert()
+        .success();
+    assert!(plain(&out.get_output().stdout).contains("offline skipped: uv python install"));
+    let log = sandbox.uv_log();
+    assert!(log.iter().any(|l| l.starts_with("venv")), "{log:#?}");
+    for line in &log {
+        assert!(!line.starts_with("python install"), "downloads a Python: {line}");
+        assert!(!line.starts_with("self update"), "updates uv: {line}");
+        let installs = line.starts_with("pip install") || line.starts_with("sync") || line.starts_with("lock") || line.starts_with("venv");
+        assert!(!installs || line.split(' ').any(|a| a == "--offline"), "not --offline: {line}");
+    }
+}
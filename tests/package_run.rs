@@ -0,0 +1,92 @@
+//! `python -m <package>`: with a namespace the package's `__main__.py` is what
+//! launch.json, tasks.json, the Makefile, the README, and the next steps run; the flat
+//! layout gets `src/__main__.py` too but keeps running `src.main`.
+
+mod common;
+
+use common::{render, Sandbox};
+use pyproj::ScaffoldPlanBuilder;
+use serde_json::Value;
+use std::process::Command;
+
+fn namespaced(b: ScaffoldPlanBuilder) -> ScaffoldPlanBuilder {
+    b.namespace("acme")
+}
+
+fn text(options: fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder, rel: &str) -> String {
+    String::from_utf8(render(options, rel)).unwrap()
+}
+
+/// The `command`s of the `--json` report's next steps.
+fn next_steps(sandbox: &Sandbox, extra: &[&str]) -> Vec<String> {
+    let out = sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo", "--json"])
+        .args(extra)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&out).unwrap();
+    report["next_steps"].as_array().unwrap().iter().map(|s| s["command"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn the_package_layout_runs_the_package_through_its_dunder_main() {
+    let main = text(namespaced, "src/acme/demo/__main__.py");
+    assert!(main.contains("from acme.demo.main import main\n"), "{main}");
+    assert!(main.contains("`python -m acme.demo`"), "{main}");
+
+    let launch: Value = serde_json::from_str(&text(namespaced, ".vscode/launch.json")).unwrap();
+    let modules: Vec<&str> = launch["configurations"].as_array().unwrap().iter().filter_map(|c| c["module"].as_str()).collect();
+    assert_eq!(modules, ["acme.demo"]);
+
+    let tasks = text(namespaced, ".vscode/tasks.json");
+    assert!(tasks.contains("\"command\": \"uv run python -m acme.demo\""), "{tasks}");
+
+    let makefile = text(namespaced, "Makefile");
+    assert!(makefile.contains("PYTHON_PKG=acme.demo\n"), "{makefile}");
+    assert!(makefile.contains("run: ## Run the main application\n\t@uv run python -m $(PYTHON_PKG)\n"), "{makefile}");
+
+    let readme = text(namespaced, "README.md");
+    assert!(readme.contains("\nuv run python -m acme.demo\n"), "{readme}");
+    assert!(readme.contains("make run          # run acme.demo\n"), "{readme}");
+    assert!(!readme.contains("acme.demo.main"), "{readme}");
+
+    let steps = next_steps(&Sandbox::new(), &["--namespace", "acme"]);
+    assert!(steps.contains(&"uv run python -m acme.demo".to_string()), "{steps:?}");
+}
+
+#[test]
+fn the_flat_layout_gets_a_dunder_main_but_keeps_running_src_main() {
+    let main = text(|b| b, "src/__main__.py");
+    assert!(main.contains("from src.main import main\n"), "{main}");
+
+    let launch = text(|b| b, ".vscode/launch.json");
+    assert!(launch.contains("\"module\": \"src.main\""), "{launch}");
+    let makefile = text(|b| b, "Makefile");
+    assert!(makefile.contains("\t@uv run python -m $(PYTHON_SRC).main\n"), "{makefile}");
+
+    let steps = next_steps(&Sandbox::new(), &[]);
+    assert!(steps.contains(&"uv run python -m src.main".to_string()), "{steps:?}");
+}
+
+/// Needs uv and the network; skipped without uv.
+#[test]
+fn uv_run_python_dash_m_runs_the_generated_package() {
+    if which::which("uv").is_err() {
+        eprintln!("uv is not installed; skipping");
+        return;
+    }
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--python", "3.12", "--project", "demo", "--namespace", "acme"])
+        .assert()
+        .success();
+    let out = Command::new("uv").args(["run", "python", "-m", "acme.demo"]).current_dir(sandbox.join("demo")).output().unwrap();
+    let output = format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+    assert!(out.status.success(), "{output}");
+    assert!(output.contains("Hello from isolated logging"), "{output}");
+}
@@ -0,0 +1,53 @@
+//! The generated `[tool.pytest.ini_options]` finds the tests and makes the project's
+//! code importable in both layouts, and carries `--pytest-addopts`/`--pytest-marker`.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+use std::path::Path;
+
+fn pytest_options(root: &Path) -> toml::Table {
+    let pyproject: toml::Table = fs::read_to_string(root.join("pyproject.toml")).unwrap().parse().unwrap();
+    pyproject["tool"]["pytest"]["ini_options"].as_table().unwrap().clone()
+}
+
+fn strings(value: &toml::Value) -> Vec<&str> {
+    value.as_array().unwrap().iter().map(|v| v.as_str().unwrap()).collect()
+}
+
+#[test]
+fn flat_layout_imports_src_from_the_root() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    let options = pytest_options(&root);
+    assert_eq!(strings(&options["testpaths"]), ["tests"]);
+    assert_eq!(strings(&options["pythonpath"]), ["."]);
+    assert!(root.join("tests").is_dir());
+    // `import src.main` from a test resolves against the pythonpath entry.
+    assert!(root.join("src/__init__.py").is_file());
+    assert!(root.join("src/main.py").is_file());
+}
+
+#[test]
+fn namespace_layout_imports_from_src() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--namespace", "acme"]);
+    let options = pytest_options(&root);
+    assert_eq!(strings(&options["testpaths"]), ["tests"]);
+    assert_eq!(strings(&options["pythonpath"]), ["src"]);
+    // `import acme.demo.main` from a test resolves against the pythonpath entry.
+    assert!(root.join("src/acme/demo/main.py").is_file());
+}
+
+#[test]
+fn addopts_and_markers_survive_quotes() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create(
+        "demo",
+        &["--pytest-addopts", r#"-q -k "not slow""#, "--pytest-marker", r#"slow:takes a "long" time"#],
+    );
+    let options = pytest_options(&root);
+    assert_eq!(options["addopts"].as_str(), Some(r#"-q -k "not slow""#));
+    assert_eq!(strings(&options["markers"]), [r#"slow: takes a "long" time"#]);
+}
@@ -0,0 +1,59 @@
+//! `uv python install` is left out when uv already has the requested Python.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{plain, Sandbox};
+
+#[test]
+fn installed_python_skips_the_install() {
+    let sandbox = Sandbox::new();
+    let uv = sandbox.fake_uv();
+    let out = sandbox
+        .cmd()
+        .arg("--uv-bin")
+        .arg(&uv)
+        .args(["--create_project", "--yes", "--python", "3.12", "--project", "demo"])
+        .args(["--preflight-ignore", "network"])
+        .assert()
+        .success();
+    let stdout = plain(&out.get_output().stdout);
+    assert!(stdout.contains("Python 3.12.4 already installed (skipped)"), "{stdout}");
+    let log = sandbox.uv_log();
+    assert!(log.iter().any(|l| l == "python list --only-installed"), "{log:#?}");
+    assert!(!log.iter().any(|l| l.starts_with("python install")), "{log:#?}");
+    assert!(log.iter().any(|l| l.starts_with("venv --python 3.12.4 ")), "{log:#?}");
+}
+
+#[test]
+fn json_report_names_the_installed_python() {
+    let sandbox = Sandbox::new();
+    let uv = sandbox.fake_uv();
+    let out = sandbox
+        .cmd()
+        .arg("--uv-bin")
+        .arg(&uv)
+        .args(["--create_project", "--yes", "--python", "3.12", "--project", "demo", "--json"])
+        .args(["--preflight-ignore", "network"])
+        .assert()
+        .success();
+    let report: serde_json::Value = serde_json::from_slice(&out.get_output().stdout).unwrap();
+    assert_eq!(report["uv"]["python_already_installed"], "3.12.4");
+}
+
+#[test]
+fn missing_python_is_installed() {
+    let sandbox = Sandbox::new();
+    let uv = sandbox.fake_uv();
+    sandbox
+        .cmd()
+        .arg("--uv-bin")
+        .arg(&uv)
+        .args(["--create_project", "--yes", "--python", "3.13", "--project", "demo"])
+        .args(["--preflight-ignore", "network"])
+        .assert()
+        .success();
+    let log = sandbox.uv_log();
+    assert!(log.iter().any(|l| l.starts_with("python install 3.13")), "{log:#?}");
+}
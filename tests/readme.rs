@@ -0,0 +1,39 @@
+//! The generated README follows the options: badges, quickstart, tree, and the
+//! development section. Snapshots live in tests/snapshots/readme/.
+
+mod common;
+
+use pyproj::ScaffoldPlanBuilder;
+
+fn readme(options: fn(ScaffoldPlanBuilder) -> ScaffoldPlanBuilder) -> Vec<u8> {
+    common::render(options, "README.md")
+}
+
+#[test]
+fn readme_default() {
+    common::assert_snapshot("readme/default.md", &readme(|b| b.description("A small demo.")));
+}
+
+#[test]
+fn readme_minimal() {
+    let text = readme(|b| b.minimal(true));
+    common::assert_snapshot("readme/minimal.md", &text);
+    assert!(!String::from_utf8(text).unwrap().contains("## Notebooks"));
+}
+
+#[test]
+fn readme_package() {
+    common::assert_snapshot(
+        "readme/package.md",
+        &readme(|b| {
+            b.description("A command-line demo.")
+                .entry_point("demo")
+                .license("MIT")
+                .org_name("Acme")
+                .repo_url("https://github.com/acme/demo")
+                .matrix_python("3.11")
+                .matrix_python("3.12")
+                .benchmarks(true)
+        }),
+    );
+}
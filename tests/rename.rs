@@ -0,0 +1,37 @@
+//! `rename-project` rewrites every place the scaffold put the name.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+
+#[test]
+fn rename_rewrites_dunder_main_and_the_readme_tree() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    sandbox
+        .cmd()
+        .args(["rename-project", "--from", "demo", "--to", "renamed", "demo"])
+        .assert()
+        .success();
+    let main = fs::read_to_string(root.join("src/__main__.py")).unwrap();
+    assert!(main.contains("\"\"\"Run renamed with `python -m src`.\"\"\""), "{main}");
+    let readme = fs::read_to_string(root.join("README.md")).unwrap();
+    assert!(readme.lines().any(|l| l == "renamed/"), "{readme}");
+    assert!(!readme.lines().any(|l| l == "demo/" || l == "# demo" || l == "cd demo"), "{readme}");
+    sandbox.cmd().args(["verify", "demo"]).assert().success();
+}
+
+#[test]
+fn rename_without_a_manifest_still_finds_dunder_main() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::remove_file(root.join(".pyproj.toml")).unwrap();
+    sandbox
+        .cmd()
+        .args(["rename-project", "--from", "demo", "--to", "renamed", "demo"])
+        .assert()
+        .success();
+    let main = fs::read_to_string(root.join("src/__main__.py")).unwrap();
+    assert!(main.contains("Run renamed with `python -m src`"), "{main}");
+}
@@ -0,0 +1,45 @@
+//! Golden files for `py-proj debug render-all`: every template under each of its
+//! option combinations, byte for byte, in tests/snapshots/render_all/. Each is stored
+//! as `<path>.snap`, so the generated .gitignore and .gitattributes stay inert.
+//! `UPDATE_SNAPSHOTS=1 cargo test --test render_all` rewrites them after an intended change.
+
+mod common;
+
+use common::{assert_snapshot, tree, Sandbox};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+const COMBINATIONS: [&str; 5] = ["default", "minimal", "package", "windows", "datasci"];
+
+#[test]
+fn every_rendered_file_matches_its_snapshot() {
+    let sandbox = Sandbox::new();
+    sandbox.stdout(&["debug", "render-all", "out"]);
+    let rendered = tree(&sandbox.join("out"));
+    let combinations: BTreeSet<&str> = rendered.keys().map(|rel| rel.split('/').next().unwrap()).collect();
+    assert_eq!(combinations, BTreeSet::from(COMBINATIONS));
+
+    let sandbox_path = sandbox.path().to_string_lossy();
+    for (rel, contents) in &rendered {
+        assert!(!String::from_utf8_lossy(contents).contains(&*sandbox_path), "{rel} names the output directory");
+        assert_snapshot(&format!("render_all/{rel}.snap"), contents);
+    }
+
+    // A template that stopped rendering leaves its golden file behind.
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_none() {
+        let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/render_all");
+        let stale: Vec<String> = tree(&golden)
+            .into_keys()
+            .filter(|rel| !rendered.contains_key(rel.trim_end_matches(".snap")))
+            .collect();
+        assert!(stale.is_empty(), "no longer rendered: {stale:?}");
+    }
+}
+
+#[test]
+fn render_all_refuses_a_non_empty_directory_without_force() {
+    let sandbox = Sandbox::new();
+    sandbox.stdout(&["debug", "render-all", "out"]);
+    sandbox.cmd().args(["debug", "render-all", "out"]).assert().code(4);
+    sandbox.stdout(&["debug", "render-all", "out", "--force"]);
+}
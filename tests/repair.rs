@@ -0,0 +1,70 @@
+//! `py-proj repair` puts back the generated files, directories, and venv that went
+//! missing, byte for byte, and leaves the ones that are there alone.
+
+mod common;
+
+use common::{tree, Sandbox};
+use std::fs;
+
+#[test]
+fn missing_files_come_back_identical_and_edited_ones_stay() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    let mut original = tree(&root);
+    fs::remove_file(root.join("Makefile")).unwrap();
+    fs::remove_file(root.join("src/main.py")).unwrap();
+    fs::remove_dir_all(root.join(".vscode")).unwrap();
+    fs::remove_dir_all(root.join("tests")).unwrap();
+    fs::write(root.join("README.md"), "# demo\n\nOur own words.\n").unwrap();
+
+    let out = sandbox.stdout(&["repair", "demo"]);
+    for line in [
+        "mkdir tests/",
+        "restore Makefile",
+        "restore src/main.py",
+        "restore .vscode/settings.json",
+        "modified README.md (left as is)",
+    ] {
+        assert!(out.contains(line), "no {line:?} in:\n{out}");
+    }
+    original.insert("README.md".to_string(), b"# demo\n\nOur own words.\n".to_vec());
+    assert_eq!(tree(&root), original);
+    assert!(root.join("tests").is_dir());
+
+    // Nothing is left to do, and the restored files match the manifest again.
+    let out = sandbox.stdout(&["repair", "demo"]);
+    assert!(!out.contains("restore"), "{out}");
+}
+
+#[test]
+fn a_dry_run_lists_what_would_be_restored() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::remove_file(root.join("pyproject.toml")).unwrap();
+    let before = tree(&root);
+    let out = sandbox.stdout(&["repair", "demo", "--dry-run"]);
+    assert!(out.contains("restore pyproject.toml"), "{out}");
+    assert_eq!(tree(&root), before);
+}
+
+#[cfg(unix)]
+#[test]
+fn a_missing_venv_is_recreated() {
+    let sandbox = Sandbox::new();
+    let uv = sandbox.fake_uv();
+    let uv = uv.to_str().unwrap();
+    sandbox
+        .cmd()
+        .args(["--uv-bin", uv, "--create_project", "--yes", "--python", "3.12", "--project", "demo"])
+        .args(["--preflight-ignore", "network"])
+        .assert()
+        .success();
+    fs::remove_dir_all(sandbox.join("demo/.venv")).unwrap();
+    fs::remove_file(sandbox.join("uv.log")).unwrap();
+
+    let out = sandbox.stdout(&["--uv-bin", uv, "repair", "demo"]);
+    assert!(out.contains("venv .venv"), "{out}");
+    assert!(sandbox.join("demo/.venv/pyvenv.cfg").is_file());
+    let log = sandbox.uv_log();
+    assert!(log.iter().any(|l| l.starts_with("venv ")), "{log:#?}");
+}
@@ -0,0 +1,46 @@
+//! `--gen-secret KEY`: a random value for KEY in `.env`, listed blank in `.env.example`.
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+
+/// The value of `key` in the `.env`-style `text`.
+fn value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+}
+
+#[test]
+fn the_secret_is_in_env_and_blank_in_the_example() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &["--gen-secret", "SECRET_KEY"]);
+    let env = fs::read_to_string(root.join(".env")).unwrap();
+    let secret = value(&env, "SECRET_KEY").unwrap_or_else(|| panic!("no SECRET_KEY in:\n{env}"));
+    assert!(secret.len() >= 32, "{secret:?}");
+    let example = fs::read_to_string(root.join(".env.example")).unwrap();
+    assert_eq!(value(&example, "SECRET_KEY"), Some(""), "{example}");
+    assert!(!example.contains(secret));
+}
+
+#[test]
+fn two_runs_generate_different_secrets() {
+    let sandbox = Sandbox::new();
+    let secret = |name: &str| {
+        let root = sandbox.create(name, &["--gen-secret", "API_TOKEN"]);
+        let env = fs::read_to_string(root.join(".env")).unwrap();
+        value(&env, "API_TOKEN").unwrap().to_string()
+    };
+    assert_ne!(secret("one"), secret("two"));
+}
+
+#[test]
+fn a_key_the_scaffold_sets_itself_is_refused() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .cmd()
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo", "--gen-secret", "PYTHONPATH"])
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("invalid --gen-secret PYTHONPATH: the scaffold sets PYTHONPATH itself"));
+    assert!(!sandbox.join("demo").exists());
+}
@@ -0,0 +1,80 @@
+# generated by py-proj v0.1 (do not edit below this line without updating the manifest)
+
+# === CONFIG ===
+PYTHON_SRC=src
+
+# Every Python tool runs through `uv run`, so targets behave the same whether or not
+# the venv is activated.
+.DEFAULT_GOAL := help
+
+GREEN := \033[0;32m
+RED := \033[0;31m
+YELLOW := \033[0;33m
+BLUE := \033[0;34m
+# No Color
+NC := \033[0m
+ROCKET := 🚀
+CPU := 💻
+GPU := ⚡️
+BAR := 📊
+# === RUN APP ===
+
+.PHONY: install
+install: ## Install the project and its dev tools
+	@uv pip install -e ".[dev]"
+
+.PHONY: run
+run: ## Run the main application
+	@uv run python -m $(PYTHON_SRC).main
+
+# === LINTING ===
+
+.PHONY: lint
+lint: ## Run Ruff linter
+	@uv run ruff check $(PYTHON_SRC)
+
+.PHONY: lint-fix
+lint-fix: ## Run Ruff with auto-fix
+	@uv run ruff check $(PYTHON_SRC) --fix
+
+# === FORMATTING ===
+
+.PHONY: fmt
+fmt: ## Format code with Black
+	@uv run black $(PYTHON_SRC)
+
+.PHONY: fmt-check
+fmt-check: ## Check formatting with Black
+	@uv run black --check $(PYTHON_SRC)
+
+# === TYPE CHECKING ===
+
+.PHONY: typecheck
+typecheck: ## Static type check with Pyright
+	@uv run pyright $(PYTHON_SRC)
+
+# === TESTING ===
+
+.PHONY: test
+test: ## Run Pytest
+	@uv run pytest tests/
+
+.PHONY: coverage
+coverage: ## Run tests with coverage report
+	@uv run pytest --cov=$(PYTHON_SRC) tests/
+
+# === CLEANING ===
+
+.PHONY: clean
+clean: ## Remove build/test/cache artifacts
+	@find . -type d -name '__pycache__' -exec rm -rf {} +
+	@find . -type d -name '.pytest_cache' -exec rm -rf {} +
+	@rm -rf .mypy_cache .ruff_cache .coverage dist build *.egg-info .pyright
+
+# === HELP ===
+
+# Lists every target annotated with `## description` on its rule line.
+.PHONY: help
+help: ## Show this help
+	@echo "Available make targets:"
+	@grep -E '^[a-zA-Z_-]+:.*## ' $(MAKEFILE_LIST) | awk 'BEGIN {FS = ":.*## "}; {printf "  $(BLUE)%-12s$(NC) %s\n", $$1, $$2}'
@@ -0,0 +1,40 @@
+//! Delete and clean remove symlinks themselves, never what they point at.
+#![cfg(unix)]
+
+mod common;
+
+use common::Sandbox;
+use std::fs;
+use std::os::unix::fs::symlink;
+
+#[test]
+fn delete_leaves_a_symlinked_directory_alone() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    let external = sandbox.join("datasets");
+    fs::create_dir_all(external.join("raw")).unwrap();
+    fs::write(external.join("raw/big.csv"), "a,b\n1,2\n").unwrap();
+    symlink(&external, sandbox.join("demo/datasets")).unwrap();
+    symlink(external.join("raw"), sandbox.join("demo/src/raw")).unwrap();
+
+    sandbox.cmd().args(["--delete_project", "demo", "--yes"]).assert().success();
+    assert!(!sandbox.join("demo").exists());
+    assert!(external.join("raw/big.csv").is_file());
+}
+
+#[test]
+fn clean_removes_a_symlinked_cache_but_not_its_target() {
+    let sandbox = Sandbox::new();
+    sandbox.create("demo", &[]);
+    let shared = sandbox.join("shared-tox");
+    fs::create_dir_all(shared.join("py312")).unwrap();
+    fs::write(shared.join("py312/marker"), "keep").unwrap();
+    symlink(&shared, sandbox.join("demo/.tox")).unwrap();
+    symlink(&shared, sandbox.join("demo/src/linked")).unwrap();
+    fs::create_dir_all(shared.join("__pycache__")).unwrap();
+
+    sandbox.cmd().args(["--clean_project", "demo"]).assert().success();
+    assert!(fs::symlink_metadata(sandbox.join("demo/.tox")).is_err());
+    assert!(shared.join("py312/marker").is_file());
+    assert!(shared.join("__pycache__").is_dir());
+}
@@ -0,0 +1,38 @@
+//! `--templates-dir`: files there replace the built-in templates of the same path, are
+//! still rendered, and are added when there is no built-in one.
+
+mod common;
+
+use common::{plain, Sandbox};
+use std::fs;
+
+const CREATE: [&str; 9] = ["--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo", "--verbose"];
+
+#[test]
+fn partial_override_replaces_only_the_files_it_has() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("over")).unwrap();
+    fs::write(sandbox.join("over/Makefile"), "run:\n\techo {{project}}\n").unwrap();
+    fs::write(sandbox.join("over/EXTRA.md"), "extra for {{project}}\n").unwrap();
+    let out = sandbox.stdout(&[&CREATE[..], &["--templates-dir", "over"]].concat());
+
+    assert_eq!(fs::read_to_string(sandbox.join("demo/Makefile")).unwrap(), "run:\n\techo demo\n");
+    assert_eq!(fs::read_to_string(sandbox.join("demo/EXTRA.md")).unwrap(), "extra for demo\n");
+    assert!(out.contains("override Makefile"), "{out}");
+    assert!(out.contains("extra EXTRA.md"), "{out}");
+    assert!(out.contains("built-in pyproject.toml"), "{out}");
+
+    // Everything not overridden is what a plain create writes.
+    let plain_sandbox = Sandbox::new();
+    let default = plain_sandbox.create("demo", &[]);
+    let pyproject = |root: &std::path::Path| fs::read_to_string(root.join("pyproject.toml")).unwrap();
+    assert_eq!(pyproject(&sandbox.join("demo")), pyproject(&default));
+}
+
+#[test]
+fn missing_directory_is_an_error() {
+    let sandbox = Sandbox::new();
+    let out = sandbox.cmd().args(&CREATE[..8]).args(["--templates-dir", "nowhere"]).assert().failure();
+    assert!(plain(&out.get_output().stderr).contains("nowhere"));
+    assert!(!sandbox.join("demo").exists());
+}
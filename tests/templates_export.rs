@@ -0,0 +1,61 @@
+//! `templates export`: the built-in templates, placeholders intact, which scaffold the
+//! same project when fed back through `--templates-dir`.
+
+mod common;
+
+use common::Sandbox;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Every file under `root` but the manifest, which records where the templates came
+/// from, and without the provenance stamp, which only built-in templates get.
+fn tree(root: &Path) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                let rel = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+                if rel != ".pyproj.toml" {
+                    let text = String::from_utf8(fs::read(&path).unwrap()).unwrap();
+                    let text = text.lines().filter(|l| !l.starts_with("# generated by py-proj v")).collect::<Vec<_>>().join("\n");
+                    files.insert(rel, text);
+                }
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn export_round_trips_through_templates_dir() {
+    let sandbox = Sandbox::new();
+    sandbox.cmd().args(["templates", "export", "exported"]).assert().success();
+    let pyproject = fs::read_to_string(sandbox.join("exported/pyproject.toml")).unwrap();
+    assert!(pyproject.contains("{{"), "placeholders are left in:\n{pyproject}");
+
+    let exported = sandbox.create("demo", &["--templates-dir", "exported"]);
+    let builtin = Sandbox::new();
+    let default = builtin.create("demo", &[]);
+    let (exported, default) = (tree(&exported), tree(&default));
+    assert_eq!(exported.keys().collect::<Vec<_>>(), default.keys().collect::<Vec<_>>());
+    for (path, contents) in &default {
+        assert_eq!(exported[path], *contents, "{path} differs");
+    }
+}
+
+#[test]
+fn export_refuses_a_non_empty_directory_without_force() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("out")).unwrap();
+    fs::write(sandbox.join("out/keep.txt"), "mine").unwrap();
+    sandbox.cmd().args(["templates", "export", "out"]).assert().code(4);
+    assert!(!sandbox.join("out/pyproject.toml").exists());
+    sandbox.cmd().args(["templates", "export", "out", "--force"]).assert().success();
+    assert!(sandbox.join("out/pyproject.toml").is_file());
+    assert_eq!(fs::read_to_string(sandbox.join("out/keep.txt")).unwrap(), "mine");
+}
@@ -0,0 +1,19 @@
+//! `--timings`: the time each phase of a create took, slowest first, then the total.
+
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn timings_list_the_write_phases_and_the_total() {
+    let sandbox = Sandbox::new();
+    let out = sandbox.stdout(&[
+        "--create_project", "--yes", "--skip-uv", "--offline", "--python", "3.12", "--project", "demo", "--timings",
+    ]);
+    let timings = &out[out.find("Timings:").expect(&out)..];
+    for phase in ["write pyproject", "write manifest", "total"] {
+        assert!(timings.contains(phase), "no {phase:?} in:\n{timings}");
+    }
+    let total = timings.lines().position(|l| l.ends_with("  total")).unwrap();
+    assert!(timings.lines().take(total).skip(1).all(|l| l.starts_with("    ")), "{timings}");
+}
@@ -0,0 +1,41 @@
+//! `--toolchain-file`: the Python comes from the nearest pin, and the scaffold writes
+//! the chosen tool manager's pin; the summary says which was read and which written.
+
+mod common;
+
+use common::{plain, Sandbox};
+use std::fs;
+
+#[test]
+fn the_summary_names_the_pin_honored_and_the_pin_written() {
+    let sandbox = Sandbox::new();
+    fs::write(sandbox.join(".tool-versions"), "nodejs 20.1.0\npython 3.11.9\n").unwrap();
+    let out = sandbox.stdout(&["--create_project", "--yes", "--skip-uv", "--offline", "--project", "demo", "--toolchain-file", "mise"]);
+    let tool_versions = sandbox.join(".tool-versions");
+    assert!(out.contains(&format!("Python:  3.11.9 (from {})", tool_versions.display())), "{out}");
+    assert!(out.contains("Pin:     .mise.toml (--toolchain-file mise)"), "{out}");
+
+    let root = sandbox.join("demo");
+    assert!(fs::read_to_string(root.join(".mise.toml")).unwrap().contains("python = \"3.11.9\""));
+    assert!(fs::read_to_string(root.join(".envrc")).unwrap().lines().any(|l| l == "use mise"));
+    assert!(!root.join(".python-version").exists() && !root.join(".tool-versions").exists());
+}
+
+#[test]
+fn a_competing_pin_in_the_project_is_warned_about() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.join("legacy")).unwrap();
+    fs::write(sandbox.join("legacy/.python-version"), "3.12\n").unwrap();
+    let out = sandbox
+        .cmd()
+        .current_dir(sandbox.join("legacy"))
+        .args(["--create_project", "--yes", "--skip-uv", "--offline", "--here", "--force", "--toolchain-file", "asdf"])
+        .assert()
+        .success();
+    let out = plain(&out.get_output().stdout);
+    assert!(
+        out.contains("--toolchain-file asdf writes .tool-versions, but .python-version in"),
+        "{out}"
+    );
+    assert_eq!(fs::read_to_string(sandbox.join("legacy/.tool-versions")).unwrap().lines().last(), Some("python 3.12"));
+}
@@ -0,0 +1,69 @@
+//! `--delete_project --trash` on Linux: the project lands in the freedesktop.org trash
+//! under `$XDG_DATA_HOME/Trash`, with a `.trashinfo` file file managers can restore it
+//! from, and a second project of the same name does not overwrite the first.
+
+#![cfg(all(unix, not(target_os = "macos")))]
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+use std::fs;
+use std::path::PathBuf;
+
+fn trash(sandbox: &Sandbox) -> PathBuf {
+    sandbox.join(".local/share/Trash")
+}
+
+fn trash_project(sandbox: &Sandbox, name: &str) {
+    sandbox
+        .cmd()
+        .env("XDG_DATA_HOME", sandbox.join(".local/share"))
+        .args(["--delete_project", name, "--trash", "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Project moved to the trash."));
+    assert!(!sandbox.join(name).exists());
+}
+
+#[test]
+fn trashing_writes_a_trashinfo_with_the_encoded_path_and_the_date() {
+    let sandbox = Sandbox::new();
+    // Project names cannot hold a space; the directory can.
+    let root = sandbox.join("my proj");
+    fs::rename(sandbox.create("demo", &[]), &root).unwrap();
+    let original = fs::canonicalize(&root).unwrap().to_string_lossy().replace(' ', "%20");
+    trash_project(&sandbox, "my proj");
+
+    assert!(trash(&sandbox).join("files/my proj/pyproject.toml").is_file());
+    let info = fs::read_to_string(trash(&sandbox).join("info/my proj.trashinfo")).unwrap();
+    let lines: Vec<&str> = info.lines().collect();
+    assert_eq!(lines[..2], ["[Trash Info]", &format!("Path={original}")]);
+    let date = lines[2].strip_prefix("DeletionDate=").unwrap_or_else(|| panic!("{info}"));
+    assert!(chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").is_ok(), "{date}");
+    assert_eq!(lines.len(), 3, "{info}");
+}
+
+#[test]
+fn a_second_project_of_the_same_name_gets_the_next_slot() {
+    let sandbox = Sandbox::new();
+    for _ in 0..2 {
+        sandbox.create("demo", &[]);
+        trash_project(&sandbox, "demo");
+    }
+    // A .trashinfo left without its files still claims its slot.
+    fs::write(trash(&sandbox).join("info/demo.3.trashinfo"), "[Trash Info]\n").unwrap();
+    sandbox.create("demo", &[]);
+    trash_project(&sandbox, "demo");
+
+    let mut files: Vec<String> = fs::read_dir(trash(&sandbox).join("files"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["demo", "demo.2", "demo.4"]);
+    for name in ["demo.2", "demo.4"] {
+        let info = fs::read_to_string(trash(&sandbox).join(format!("info/{name}.trashinfo"))).unwrap();
+        assert!(info.lines().any(|l| l.starts_with("Path=") && l.ends_with("/demo")), "{info}");
+    }
+}
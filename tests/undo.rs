@@ -0,0 +1,68 @@
+//! `py-proj undo` reverses the last create or adopt from its manifest, leaving what
+//! was there before (and what was edited since) alone.
+
+mod common;
+
+use common::{plain, tree, Sandbox};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// `legacy/`, an existing project with its own pyproject.toml, .gitignore, and code.
+fn legacy(sandbox: &Sandbox) -> BTreeMap<String, Vec<u8>> {
+    let root = sandbox.join("legacy");
+    fs::create_dir_all(root.join("legacy")).unwrap();
+    fs::write(root.join("pyproject.toml"), "[project]\nname = \"legacy\"\nversion = \"0.1.0\"\n").unwrap();
+    fs::write(root.join(".gitignore"), "*.sqlite\n").unwrap();
+    fs::write(root.join("legacy/__init__.py"), "VERSION = 1\n").unwrap();
+    fs::write(root.join("notes.txt"), "keep me\n").unwrap();
+    tree(&root)
+}
+
+#[test]
+fn undo_after_adopt_leaves_only_what_was_there_before() {
+    let sandbox = Sandbox::new();
+    let before = legacy(&sandbox);
+    sandbox.cmd().args(["adopt", "legacy", "--skip-uv"]).assert().success();
+    let adopted = tree(&sandbox.join("legacy"));
+    assert!(adopted.contains_key(".pyproj.toml") && adopted.contains_key("Makefile"));
+    assert_ne!(adopted["pyproject.toml"], before["pyproject.toml"]);
+
+    let out = sandbox.stdout(&["undo", "legacy"]);
+    assert!(out.contains("restore pyproject.toml (from pyproject.toml.bak)"), "{out}");
+    assert_eq!(tree(&sandbox.join("legacy")), before);
+}
+
+#[test]
+fn a_dry_run_changes_nothing() {
+    let sandbox = Sandbox::new();
+    legacy(&sandbox);
+    sandbox.cmd().args(["adopt", "legacy", "--skip-uv"]).assert().success();
+    let adopted = tree(&sandbox.join("legacy"));
+    let out = sandbox.stdout(&["undo", "legacy", "--dry-run"]);
+    assert!(out.contains("rm Makefile"), "{out}");
+    assert!(out.contains("Would remove:"), "{out}");
+    assert_eq!(tree(&sandbox.join("legacy")), adopted);
+}
+
+#[test]
+fn edited_files_are_kept_until_force_modified() {
+    let sandbox = Sandbox::new();
+    let root = sandbox.create("demo", &[]);
+    fs::write(root.join("Makefile"), "all:\n\techo mine\n").unwrap();
+
+    let out = sandbox.stdout(&["undo", "demo"]);
+    assert!(out.contains("keep Makefile (edited since it was generated; --force-modified removes it)"), "{out}");
+    assert_eq!(tree(&root).into_keys().collect::<Vec<_>>(), [".pyproj.toml", "Makefile"]);
+
+    sandbox.stdout(&["undo", "demo", "--force-modified"]);
+    assert!(!root.exists(), "a root the create made goes once it is empty");
+}
+
+#[test]
+fn undo_refuses_a_directory_without_a_manifest() {
+    let sandbox = Sandbox::new();
+    let before = legacy(&sandbox);
+    let out = sandbox.cmd().args(["undo", "legacy"]).assert().failure();
+    assert!(plain(&out.get_output().stderr).contains("no .pyproj.toml in"));
+    assert_eq!(tree(&sandbox.join("legacy")), before);
+}
@@ -0,0 +1,48 @@
+//! uv is checked before anything is written; `--skip-uv` writes the files without it.
+
+mod common;
+
+use common::{plain, Sandbox};
+use predicates::str::contains;
+use std::fs;
+
+const CREATE: [&str; 7] = ["--create_project", "--yes", "--offline", "--python", "3.12", "--project", "demo"];
+
+#[test]
+fn missing_uv_fails_before_writing_with_the_install_command() {
+    let sandbox = Sandbox::new();
+    let out = sandbox.cmd().env("PATH", sandbox.join("no-bin")).args(CREATE).assert().code(7);
+    let stdout = plain(&out.get_output().stdout);
+    assert!(stdout.lines().any(|l| l.contains("uv") && l.contains("not found on PATH")), "{stdout}");
+    assert!(stdout.contains("https://astral.sh/uv/install.sh"), "{stdout}");
+    assert!(!sandbox.join("demo").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn outdated_uv_is_refused() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new();
+    let bin = sandbox.join("bin");
+    fs::create_dir(&bin).unwrap();
+    fs::write(bin.join("uv"), "#!/bin/sh\necho uv 0.1.0\n").unwrap();
+    fs::set_permissions(bin.join("uv"), fs::Permissions::from_mode(0o755)).unwrap();
+    sandbox
+        .cmd()
+        .env("PATH", format!("{}:/usr/bin:/bin", bin.display()))
+        .args(CREATE)
+        .assert()
+        .code(7)
+        .stderr(contains("uv 0.1.0 is older than"));
+    assert!(!sandbox.join("demo").exists());
+}
+
+#[test]
+fn skip_uv_writes_the_files_without_uv() {
+    let sandbox = Sandbox::new();
+    let out = sandbox.cmd().env("PATH", sandbox.join("no-bin")).args(CREATE).arg("--skip-uv").assert().success();
+    assert!(plain(&out.get_output().stdout).contains("no .venv created (--skip-uv)"));
+    assert!(sandbox.join("demo/pyproject.toml").is_file());
+    assert!(!sandbox.join("demo/.venv").exists());
+}